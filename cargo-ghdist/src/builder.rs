@@ -1,15 +1,57 @@
 use anyhow::{Context, Result};
+use cargo_ghinstall::utils;
 use cargo_manifest::Manifest;
 use git2::Repository;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-
-use crate::cli::Args;
-use crate::config::Config;
+use std::time::Instant;
+
+use crate::cache::{CacheStats, CompileCache};
+use crate::changelog;
+use crate::cli::{ArchiveFormat, Args, PackageFormat, Provider};
+use crate::config::{Config, GitLabAssets, PagesPublisherConfig, ReleaseNotesMode};
+use crate::container;
+use crate::contributors;
+use crate::deb;
+use crate::docker;
 use crate::error::{GhDistError, Result as GhResult};
-use crate::github::{get_content_type, GitHubClient};
-use crate::packager;
+use crate::fanout;
+use crate::gates;
+use crate::github::{self, get_content_type, GitHubClient, ReleaseOptions, ReleaseProvider};
+use crate::gitlab::GitLabClient;
+use crate::glibc;
+use crate::hooks::{self, HookContext, HookStage};
+use crate::incremental::{self, ExistingRelease};
+use crate::library::{self, NativeLibrary};
+use crate::manifest::{ReleaseManifest, MANIFEST_FILE_NAME};
+use crate::mirror;
+use crate::notifications::{self, Event, Notification};
+use crate::npm::{self, NpmRelease};
+use crate::packager::{self, PackageMetadata};
+use crate::pages::{self, PageRelease};
+use crate::plan::{self, BuildSource, Plan, TargetPlan};
+use crate::preflight::{self, PreflightOverrides};
+use crate::publish::{self, PublishOptions};
+use crate::pull_requests;
+use crate::remote_build::{self, ArtifactSource};
+use crate::resume::{ResumeState, UploadAction};
+use crate::retention::{self, ReleaseEntry};
+use crate::rpm;
+use crate::signing::{SignatureStatus, Signer};
+use crate::size_report::{SizeReport, SIZE_REPORT_FILE_NAME};
+use crate::smoke_test;
+use crate::summary::{AssetSummary, Summary};
+use crate::tagging;
+use crate::toolchain;
+use crate::ui::{self, Ui, UiMode};
+use crate::wasm;
+use crate::windows_installer;
+use crate::workspace::{self, WorkspacePackage};
+
+/// Release body written next to the assets by `--no-upload`
+pub const RELEASE_NOTES_FILE_NAME: &str = "RELEASE_NOTES.md";
 
 /// Find workspace manifest by looking up parent directories
 fn find_workspace_manifest() -> Result<Manifest> {
@@ -33,6 +75,82 @@ fn find_workspace_manifest() -> Result<Manifest> {
     anyhow::bail!("No workspace manifest found")
 }
 
+/// Add prebuilt assets (remote builds or --from-artifacts) to the manifest, matched by target name
+fn record_prebuilt(
+    manifest: &mut ReleaseManifest,
+    target_archives: &mut Vec<(String, PathBuf)>,
+    targets: &[String],
+    assets: &[PathBuf],
+) -> Result<()> {
+    for target in targets {
+        let target_assets = remote_build::assets_for_target(assets, target);
+        let Some(archive) = target_assets.first() else {
+            tracing::warn!("No prebuilt assets found for {}", target);
+            continue;
+        };
+        target_archives.push((target.clone(), archive.clone()));
+        manifest.add_target(target, &[], &target_assets, SignatureStatus::NotApplicable)?;
+    }
+    Ok(())
+}
+
+/// A release that has just been published, with what its publishers need
+struct PublishedRelease<'a> {
+    owner: &'a str,
+    repo: &'a str,
+    tag: &'a str,
+    version: &'a str,
+    target_commitish: Option<&'a str>,
+    url: &'a str,
+    manifest: &'a ReleaseManifest,
+    /// Every asset of the release, on disk
+    assets: &'a [PathBuf],
+    /// The archive of each target, for the npm package
+    target_archives: &'a [(String, PathBuf)],
+    binary_names: &'a [String],
+    package_metadata: Option<&'a PackageMetadata>,
+    /// The Linux binaries of each target, for the container image
+    linux_binaries: &'a [(String, Vec<PathBuf>)],
+    output_dir: &'a Path,
+}
+
+/// What a run produced, before the release (if any) exists
+fn summarize(
+    tag: &str,
+    manifest: &ReleaseManifest,
+    failed_targets: Vec<String>,
+    assets: &[PathBuf],
+    output_dir: &Path,
+    cache: Option<CacheStats>,
+) -> Summary {
+    Summary {
+        tag: tag.to_string(),
+        targets: manifest.targets.iter().map(|t| t.target.clone()).collect(),
+        failed_targets,
+        assets: assets
+            .iter()
+            .map(|path| AssetSummary::from_path(path))
+            .collect(),
+        output_dir: Some(output_dir.to_path_buf()),
+        uploaded_assets: 0,
+        release_url: None,
+        cache,
+    }
+}
+
+fn log_upload(provider: Provider, path: &Path) {
+    let asset = AssetSummary::from_path(path);
+    tracing::info!(
+        event = "asset_uploaded",
+        provider = %provider,
+        asset = %asset.name,
+        size = asset.size,
+        "Uploaded {} to {}",
+        asset.name,
+        provider
+    );
+}
+
 fn sanitize_path_component(value: &str) -> String {
     value
         .chars()
@@ -43,6 +161,16 @@ fn sanitize_path_component(value: &str) -> String {
         .collect()
 }
 
+/// Directory cargo writes a profile's output to; the built-in dev and test
+/// profiles share `debug`, and bench shares `release`
+fn profile_dir(profile: &str) -> &str {
+    match profile {
+        "dev" | "test" => "debug",
+        "bench" => "release",
+        other => other,
+    }
+}
+
 fn select_previous_tag(mut tags: Vec<(i64, String)>, current_tag: &str) -> String {
     tags.sort_by(|(left_time, left_name), (right_time, right_name)| {
         left_time
@@ -65,274 +193,1496 @@ fn select_previous_tag(mut tags: Vec<(i64, String)>, current_tag: &str) -> Strin
 
 pub struct DistBuilder {
     args: Args,
-    #[allow(dead_code)]
     config: Config,
     github_client: GitHubClient,
+    /// Set when `--provider gitlab` is selected
+    gitlab_client: Option<GitLabClient>,
+    /// The only workspace member selected with --package, released with its own tags
+    package: Option<WorkspacePackage>,
+    /// Members selected with --package or --workspace; empty builds cargo's default members
+    selected: Vec<WorkspacePackage>,
+    /// Cargo target directory the binaries are built in
+    target_dir: PathBuf,
+    /// Directory holding one `<tag>` directory of assets per release
+    output_dir: PathBuf,
+    ui: Ui,
 }
 
 impl DistBuilder {
     pub fn new(mut args: Args) -> Result<Self> {
-        // Load configuration
-        let config_path = args
-            .config
-            .clone()
-            .unwrap_or_else(|| PathBuf::from(".config/ghdist.toml"));
+        let config = Config::load_for(&mut args)?;
 
-        let config = Config::load(&config_path).context("Failed to load configuration")?;
-
-        // Merge configuration with args
-        config.merge_with_args(&mut args)?;
+        let github_client = GitHubClient::from_args(&args)?.with_progress(!args.no_progress);
+        let providers = args.providers();
+        let gitlab_client = if providers.contains(&Provider::Gitlab) {
+            if config.gitlab.assets()? == GitLabAssets::Links
+                && !providers.contains(&Provider::Github)
+            {
+                anyhow::bail!(
+                    "[gitlab] assets = \"links\" points at the GitHub release, so it needs --provider github,gitlab"
+                );
+            }
+            Some(GitLabClient::new(&config.gitlab, !args.no_progress)?)
+        } else {
+            None
+        };
 
-        let github_client = GitHubClient::new(args.github_token.clone())?;
+        let selected = if args.package.is_empty() && !args.workspace {
+            Vec::new()
+        } else {
+            workspace::select_packages(
+                &workspace::workspace_packages()?,
+                &args.package,
+                args.workspace,
+                &args.exclude,
+            )?
+        };
+        let package = args
+            .release_package()
+            .and_then(|name| selected.iter().find(|p| p.name == name))
+            .cloned();
+
+        let target_dir = match &args.target_dir {
+            Some(dir) => dir.clone(),
+            None => workspace::target_directory().unwrap_or_else(|e| {
+                tracing::debug!("Using ./target: {e:#}");
+                PathBuf::from("target")
+            }),
+        };
+        let output_dir = args
+            .output_dir
+            .clone()
+            .unwrap_or_else(|| target_dir.join("dist"));
 
         Ok(Self {
+            ui: Ui::new(UiMode::from_args(&args)),
             args,
             config,
             github_client,
+            gitlab_client,
+            package,
+            selected,
+            target_dir,
+            output_dir,
         })
     }
 
-    pub async fn run(&self) -> Result<()> {
+    /// Directory the assets of `tag` are written to
+    fn dist_dir(&self, tag: &str) -> PathBuf {
+        self.output_dir.join(sanitize_path_component(tag))
+    }
+
+    /// Prefix of release tags, specific to the selected package if any
+    fn tag_prefix(&self) -> String {
+        self.config
+            .tag
+            .prefix_for(self.package.as_ref().map(|p| p.name.as_str()))
+    }
+
+    /// Directory of the selected package relative to the repository root
+    ///
+    /// `None` releases the whole repository (or a package at the root).
+    fn package_path(&self, repo: &Repository) -> Option<PathBuf> {
+        let package = self.package.as_ref()?;
+        let workdir = repo.workdir()?.canonicalize().ok()?;
+        let dir = package.dir().canonicalize().ok()?;
+        dir.strip_prefix(&workdir)
+            .ok()
+            .filter(|path| !path.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+    }
+
+    pub async fn run(&self) -> Result<Summary> {
         // Get or detect tag
         let tag = self.get_tag()?;
-        tracing::info!("Building distribution for tag: {}", tag);
 
-        // Parse repository info
+        let result = self.release(tag.clone(), false).await;
+        if let Err(e) = &result {
+            if !self.config.notifications.webhooks.is_empty() && !self.args.no_upload {
+                let repository = self
+                    .args
+                    .parse_repository()
+                    .map(|(owner, repo)| format!("{owner}/{repo}"))
+                    .unwrap_or_default();
+                let error = format!("{e:#}");
+                notifications::send(
+                    &self.config.notifications,
+                    &Notification {
+                        repository: &repository,
+                        tag: &tag,
+                        event: Event::Failed { error: &error },
+                    },
+                )
+                .await;
+            }
+        }
+        result
+    }
+
+    /// Build this runner's targets and add them to a shared draft release
+    ///
+    /// For CI matrix jobs: each job uploads its assets plus checksum and manifest
+    /// fragments, and [`DistBuilder::finalize`] assembles the release from them.
+    pub async fn upload(&self) -> Result<Summary> {
+        let tag = self.get_tag()?;
+        self.release(tag, true).await
+    }
+
+    /// Merge the fragments uploaded by matrix jobs, write the notes and publish the release
+    pub async fn finalize(&self) -> Result<Summary> {
+        let tag = self.get_tag()?;
         let (owner, repo) = self.args.parse_repository()?;
-        tracing::info!("Repository: {}/{}", owner, repo);
+        self.ui.step(format!("Collecting the uploads for {tag}"));
+        let release = self
+            .github_client
+            .find_release(&owner, &repo, &tag)
+            .await?
+            .with_context(|| {
+                format!("No release found for tag {tag}. Run `cargo ghdist upload` first")
+            })?;
 
-        // Create output directory
-        let dist_tag = sanitize_path_component(&tag);
-        let output_dir = PathBuf::from("target").join("dist").join(&dist_tag);
+        let output_dir = self.dist_dir(&tag);
         fs::create_dir_all(&output_dir)?;
 
-        // Build for each target
-        let mut all_archives = Vec::new();
-        for target in self.args.targets() {
-            tracing::info!("Building for target: {}", target);
-
-            match self.build_for_target(&target).await {
-                Ok(binaries) => {
-                    // Create archive for this target
-                    let archive_name = format!("{repo}-{target}-{dist_tag}");
-                    let archive_path = packager::create_archive(
-                        &binaries,
-                        &output_dir,
-                        &archive_name,
-                        self.args.archive_format(),
-                    )?;
-                    all_archives.push(archive_path);
-                }
-                Err(e) => {
-                    tracing::error!("Failed to build for {}: {}", target, e);
-                    if !self.should_continue_on_error() {
-                        return Err(e.into());
-                    }
-                }
+        let mut checksum_fragments = Vec::new();
+        let mut manifest_fragments = Vec::new();
+        let mut fragment_ids = Vec::new();
+        for asset in &release.assets {
+            let is_checksum = fanout::is_checksum_fragment(&asset.name);
+            if !is_checksum && !fanout::is_manifest_fragment(&asset.name) {
+                continue;
             }
-        }
-
-        if all_archives.is_empty() {
-            return Err(GhDistError::BuildFailed {
-                target: "all targets".to_string(),
+            let bytes = self
+                .github_client
+                .download_asset(&owner, &repo, asset.id.0)
+                .await?;
+            if is_checksum {
+                checksum_fragments.push(String::from_utf8(bytes)?);
+            } else {
+                manifest_fragments.push(ReleaseManifest::from_json(&bytes)?);
             }
-            .into());
+            fragment_ids.push(asset.id.0);
         }
-
-        // Generate checksums if requested
-        if !self.args.no_checksum {
-            let checksum_file = packager::generate_checksums(&all_archives, &output_dir)?;
-            all_archives.push(checksum_file);
+        if manifest_fragments.is_empty() {
+            anyhow::bail!("Release {tag} has no uploads from `cargo ghdist upload`");
         }
 
-        // Get current commit SHA if using --hash option
-        let target_commitish = if self.args.hash {
-            let sha = Repository::open(".").ok().and_then(|repo| {
-                repo.head()
-                    .ok()
-                    .and_then(|head| head.target())
-                    .map(|oid| oid.to_string())
-            });
-            tracing::info!("Using commit SHA for release: {:?}", sha);
-            sha
-        } else {
-            tracing::info!("Not using commit SHA (--hash not specified)");
-            None
-        };
-
-        // Generate release notes
-        let mut release_notes = self.generate_release_notes(&tag, &owner, &repo, self.args.hash)?;
-
-        // For tagged releases, append GitHub's auto-generated release notes
-        if !self.args.hash {
-            tracing::info!(
-                "Fetching GitHub's auto-generated release notes for tag {}",
-                tag
-            );
-
-            // Get the previous tag for comparison
-            let previous_tag = self.find_previous_tag(&tag).ok();
+        self.ui.step("Merging checksums and manifests");
+        let manifest = fanout::merge_manifests(&tag, manifest_fragments);
+        let mut assets = vec![manifest.write(&output_dir)?];
+        let previous_manifest = self.previous_manifest(&tag, &owner, &repo).await;
+        let size_report = SizeReport::compare(&manifest, previous_manifest.as_ref());
+        assets.push(size_report.write(&output_dir)?);
+
+        if !checksum_fragments.is_empty() {
+            // Hash the merged manifest and size report alongside the runners' assets
+            let sidecars = self.sidecar_checksums(&assets)?;
+            let checksum_file = packager::generate_checksums(&assets, &output_dir)?;
+            checksum_fragments.push(fs::read_to_string(&checksum_file)?);
+            fs::write(&checksum_file, fanout::merge_checksums(&checksum_fragments))?;
+            assets.extend(sidecars);
+            assets.push(checksum_file);
+        }
 
-            // Fetch auto-generated release notes from GitHub
-            match self
+        let existing = self
+            .github_client
+            .list_release_assets(&owner, &repo, release.id.0)
+            .await?;
+        let mut uploaded_assets = 0;
+        for asset_path in &assets {
+            if self
                 .github_client
-                .generate_release_notes(
-                    &owner,
-                    &repo,
-                    &tag,
-                    target_commitish.as_deref(),
-                    previous_tag.as_deref(),
-                )
-                .await
+                .replace_asset(&owner, &repo, release.id.0, asset_path, &existing)
+                .await?
             {
-                Ok(auto_notes) => {
-                    tracing::debug!("Got auto-generated notes: {} chars", auto_notes.len());
-                    // Append the auto-generated notes to our custom notes
-                    release_notes.push_str("\n\n---\n");
-                    release_notes.push_str("\n## 📋 Auto-generated Release Notes\n\n");
-                    release_notes.push_str(&auto_notes);
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to get auto-generated release notes: {}", e);
-                    // Continue without auto-generated notes
-                }
+                log_upload(Provider::Github, asset_path);
+                uploaded_assets += 1;
             }
         }
+        for asset_id in fragment_ids {
+            self.github_client
+                .delete_asset(&owner, &repo, asset_id)
+                .await?;
+        }
 
-        tracing::debug!("Final release notes: {} chars", release_notes.len());
+        let changelog_notes = self.read_changelog_section(&tag)?;
+        let target_commitish = self.target_commitish();
+        let mut release_notes = self.base_release_notes(
+            &tag,
+            &owner,
+            &repo,
+            changelog_notes,
+            &manifest,
+            &size_report,
+        )?;
+        release_notes.push_str(&self.container_notes(&owner, &repo, &tag));
+        self.append_history_notes(
+            &mut release_notes,
+            &tag,
+            &owner,
+            &repo,
+            target_commitish.as_deref(),
+        )
+        .await?;
+
+        if self.args.create_tag && !self.args.hash {
+            let token = self.github_client.optional_token(&owner, &repo).await?;
+            self.ensure_tag(&tag, token.as_deref())?;
+        }
 
-        // Create or update GitHub release
+        self.ui.step(format!("Publishing release {tag}"));
         let release = self
             .github_client
             .create_release(
                 &owner,
                 &repo,
                 &tag,
-                self.args.draft,
-                target_commitish.as_deref(),
-                Some(&release_notes),
+                &ReleaseOptions {
+                    draft: self.args.draft,
+                    prerelease: self.args.prerelease,
+                    target_commitish: target_commitish.as_deref(),
+                    body: Some(&release_notes),
+                    make_latest: self.args.latest(),
+                    discussion_category: self.args.discussion_category.as_deref(),
+                },
             )
             .await?;
 
-        // Upload all assets
-        for asset_path in &all_archives {
-            let asset_name = asset_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown");
-
-            // Check if asset already exists and delete it
-            if let Some(asset_id) = self
-                .github_client
-                .asset_exists(&owner, &repo, release.id.0, asset_name)
-                .await?
-            {
-                tracing::info!("Deleting existing asset: {}", asset_name);
-                self.github_client
-                    .delete_asset(&owner, &repo, asset_id)
-                    .await?;
-            }
+        let version = self
+            .get_package_version()
+            .unwrap_or_else(|_| tag.strip_prefix('v').unwrap_or(&tag).to_string());
+        self.finalize_publishers(
+            &release,
+            &manifest,
+            &assets,
+            &version,
+            target_commitish.as_deref(),
+            &mut release_notes,
+        )
+        .await?;
 
-            // Upload new asset
-            self.github_client
-                .upload_asset(
-                    &owner,
-                    &repo,
-                    release.id.0,
-                    asset_path,
-                    get_content_type(asset_path),
-                )
-                .await?;
+        if !self.args.skip_publish {
+            self.run_cargo_publish().await?;
         }
 
-        // Run cargo publish if requested
-        if !self.args.skip_publish {
-            self.run_cargo_publish()?;
+        let hook_context = HookContext {
+            version,
+            tag: tag.clone(),
+            targets: manifest.targets.iter().map(|t| t.target.clone()).collect(),
+            assets: release
+                .assets
+                .iter()
+                .map(|asset| PathBuf::from(&asset.name))
+                .collect(),
+            release_url: Some(release.html_url.to_string()),
+        };
+        self.announce(&owner, &repo, &hook_context).await;
+
+        tracing::info!(
+            event = "release_published",
+            provider = %Provider::Github,
+            tag = %tag,
+            url = %release.html_url,
+            "Finalized release {}: {}",
+            tag,
+            release.html_url
+        );
+        self.ui.finish();
+        Ok(Summary {
+            tag,
+            targets: hook_context.targets,
+            failed_targets: Vec::new(),
+            assets: release
+                .assets
+                .iter()
+                .map(|asset| AssetSummary {
+                    name: asset.name.clone(),
+                    size: u64::try_from(asset.size).unwrap_or(0),
+                })
+                .collect(),
+            output_dir: Some(output_dir),
+            uploaded_assets,
+            release_url: Some(release.html_url.to_string()),
+            cache: None,
+        })
+    }
+
+    /// Run the publishers on a finalized release, downloading the runners'
+    /// assets they need and unpacking the Linux binaries for the image
+    async fn finalize_publishers(
+        &self,
+        release: &octocrab::models::repos::Release,
+        manifest: &ReleaseManifest,
+        local_assets: &[PathBuf],
+        version: &str,
+        target_commitish: Option<&str>,
+        release_notes: &mut String,
+    ) -> Result<()> {
+        let (owner, repo) = self.args.parse_repository()?;
+        let publishers = &self.config.publishers;
+        let needs_assets = publishers.docker.is_some()
+            || publishers.s3.is_some()
+            || publishers.npm.is_some()
+            || publishers.pages.is_some();
+        let output_dir = self.dist_dir(&release.tag_name);
+
+        let mut assets = local_assets.to_vec();
+        if needs_assets && !self.is_github_draft() {
+            self.ui
+                .step("Downloading the runners' assets for the publishers");
+            for asset in &release.assets {
+                let path = output_dir.join(&asset.name);
+                if assets.contains(&path) {
+                    continue;
+                }
+                let bytes = self
+                    .github_client
+                    .download_asset(&owner, &repo, asset.id.0)
+                    .await?;
+                fs::write(&path, bytes)?;
+                assets.push(path);
+            }
         }
 
-        tracing::info!("Distribution completed successfully!");
-        tracing::info!("Release URL: {}", release.html_url);
+        let target_archives = fanout::target_archives(manifest, &assets);
+        let linux_binaries = if publishers.docker.is_some() && !self.is_github_draft() {
+            fanout::unpack_linux_binaries(manifest, &target_archives, &output_dir.join("bin"))?
+        } else {
+            Vec::new()
+        };
+        let binary_names = manifest
+            .targets
+            .first()
+            .map(|target| {
+                target
+                    .binaries
+                    .iter()
+                    .map(|b| b.name.trim_end_matches(".exe").to_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let package_metadata = match publishers.npm {
+            Some(_) => Some(self.get_package_metadata(&repo)?),
+            None => None,
+        };
 
-        Ok(())
+        self.run_publishers(
+            &PublishedRelease {
+                owner: &owner,
+                repo: &repo,
+                tag: &release.tag_name,
+                version,
+                target_commitish,
+                url: release.html_url.as_str(),
+                manifest,
+                assets: &assets,
+                target_archives: &target_archives,
+                binary_names: &binary_names,
+                package_metadata: package_metadata.as_ref(),
+                linux_binaries: &linux_binaries,
+                output_dir: &output_dir,
+            },
+            release_notes,
+        )
+        .await
     }
 
-    /// Get package version from Cargo.toml
-    fn get_package_version(&self) -> Result<String> {
-        let manifest = Manifest::from_path("Cargo.toml").context("Failed to parse Cargo.toml")?;
+    /// Resolve what a release of the current tag would do, without running any of it
+    pub fn plan(&self) -> Result<Plan> {
+        let tag = self.get_tag()?;
+        let (owner, repo) = self.args.parse_repository()?;
+        let version = self
+            .get_package_version()
+            .unwrap_or_else(|_| tag.strip_prefix('v').unwrap_or(&tag).to_string());
+        let dist_tag = sanitize_path_component(&tag);
 
-        // Check if this is a workspace manifest with workspace.package.version
-        if let Some(workspace) = &manifest.workspace {
-            if let Some(ws_package) = &workspace.package {
-                if let Some(version) = &ws_package.version {
-                    return Ok(version.clone());
-                }
-            }
+        let binaries = self
+            .get_binary_info()?
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| {
+                self.args
+                    .bins
+                    .as_ref()
+                    .is_none_or(|bins| bins.contains(name))
+            })
+            .filter(|name| !self.is_excluded_bin(name))
+            .chain(self.config.default.examples.iter().cloned())
+            .collect::<Vec<_>>();
+
+        let packages = self.args.packages();
+        let package_metadata = if packages.is_empty() {
+            None
+        } else {
+            Some(self.get_package_metadata(&repo)?)
+        };
+        let extension = match self.args.archive_format() {
+            ArchiveFormat::Tgz => "tar.gz",
+            ArchiveFormat::Zip => "zip",
+        };
+
+        let has_libraries = !self.native_libraries().is_empty();
+
+        let remote_targets = if self.args.from_artifacts.is_some() || self.args.no_upload {
+            Vec::new()
+        } else {
+            remote_build::remote_targets(&self.config.remote_build, &self.args.targets())
+        };
+        let mut targets = Vec::new();
+        for target in self.args.targets() {
+            let image = self.config.container_image_for(&target).map(String::from);
+            let source = if self.args.from_artifacts.is_some() {
+                BuildSource::Prebuilt
+            } else if remote_targets.contains(&target) {
+                BuildSource::Remote
+            } else if image.is_some() {
+                BuildSource::Container
+            } else {
+                BuildSource::Local
+            };
+            let built_here = matches!(source, BuildSource::Local | BuildSource::Container);
+            let glibc = glibc::links_glibc(&target).then_some(plan::PLANNED_GLIBC);
+            let archive_name = plan::archive_name(&self.config, &repo, &target, &dist_tag, glibc);
+            targets.push(TargetPlan {
+                toolchain: self
+                    .config
+                    .toolchain_for(&target)
+                    .filter(|_| built_here)
+                    .map(String::from),
+                image: image.filter(|_| built_here),
+                archive: (source != BuildSource::Prebuilt && !binaries.is_empty())
+                    .then(|| format!("{archive_name}.{extension}")),
+                library_archive: (source != BuildSource::Prebuilt && has_libraries)
+                    .then(|| format!("{}.{extension}", plan::library_archive_name(&archive_name))),
+                packages: package_metadata
+                    .iter()
+                    .filter(|_| built_here)
+                    .flat_map(|metadata| {
+                        packages.iter().filter_map(|format| {
+                            plan::package_file_name(*format, metadata, &target, &self.config)
+                        })
+                    })
+                    .collect(),
+                target,
+                source,
+            });
         }
 
-        // Get version from package
-        if let Some(package) = manifest.package {
-            match package.version {
-                Some(cargo_manifest::MaybeInherited::Local(version)) => {
-                    return Ok(version);
-                }
-                Some(cargo_manifest::MaybeInherited::Inherited { .. }) => {
-                    // Version is inherited from workspace, need to read workspace manifest
-                    if let Ok(workspace_manifest) = find_workspace_manifest() {
-                        if let Some(ws_package) =
-                            workspace_manifest.workspace.and_then(|ws| ws.package)
-                        {
-                            if let Some(version) = ws_package.version {
-                                return Ok(version);
-                            }
-                        }
-                    }
-                }
-                None => {
-                    // No version in package, try workspace
-                    if let Ok(workspace_manifest) = find_workspace_manifest() {
-                        if let Some(ws_package) =
-                            workspace_manifest.workspace.and_then(|ws| ws.package)
-                        {
-                            if let Some(version) = ws_package.version {
-                                return Ok(version);
-                            }
-                        }
-                    }
-                }
+        let mut assets = targets
+            .iter()
+            .flat_map(|t| {
+                t.archive
+                    .iter()
+                    .chain(&t.library_archive)
+                    .chain(&t.packages)
+                    .cloned()
+            })
+            .collect::<Vec<_>>();
+        assets.push(MANIFEST_FILE_NAME.to_string());
+        assets.push(SIZE_REPORT_FILE_NAME.to_string());
+        if !self.args.no_checksum {
+            if self.config.default.checksum_sidecars {
+                let sidecars = assets
+                    .iter()
+                    .map(|name| format!("{name}.sha256"))
+                    .collect::<Vec<_>>();
+                assets.extend(sidecars);
             }
+            assets.push("SHA256SUMS".to_string());
         }
 
-        anyhow::bail!("No version field found in Cargo.toml")
+        Ok(Plan {
+            steps: self.plan_steps(&tag, &version, &owner, &repo, &targets, assets.len()),
+            tag,
+            repository: format!("{owner}/{repo}"),
+            version,
+            binaries,
+            targets,
+            assets,
+        })
     }
 
-    /// Get binary names and descriptions from the project
-    fn get_binary_info(&self) -> Result<Vec<(String, Option<String>)>> {
-        let mut binaries = Vec::new();
+    /// The steps `release` takes for these settings, in order
+    fn plan_steps(
+        &self,
+        tag: &str,
+        version: &str,
+        owner: &str,
+        repo: &str,
+        targets: &[TargetPlan],
+        asset_count: usize,
+    ) -> Vec<String> {
+        let mut steps = vec!["Check the working tree and branch".to_string()];
+        if targets.iter().any(|t| t.source == BuildSource::Local) {
+            steps.push("Check the installed toolchains and targets".to_string());
+        }
+        if !self.args.hash && !self.args.no_verify_version {
+            steps.push(format!("Check that {tag} matches the Cargo.toml version"));
+        }
+        if self.config.gates.semver_checks && !self.args.hash {
+            steps.push("Run cargo-semver-checks".to_string());
+        }
+        if self.args.run_tests {
+            steps.push("Run the test suite".to_string());
+        }
+        if self.config.gates.publish_dry_run {
+            steps.push("Dry-run cargo publish".to_string());
+        }
+        if !self.config.hooks.pre_build.is_empty() {
+            steps.push("Run pre_build hooks".to_string());
+        }
 
-        // Try to read workspace members
-        if let Ok(manifest) = Manifest::from_path("Cargo.toml") {
-            if let Some(workspace) = manifest.workspace {
-                // For workspace projects, check each member
-                for member in &workspace.members {
-                    let member_path = PathBuf::from(&member).join("Cargo.toml");
-                    if let Ok(member_manifest) = Manifest::from_path(&member_path) {
-                        if let Some(package) = member_manifest.package {
-                            // Check if this package produces a binary
-                            // By default, packages with src/main.rs produce a binary with the package name
-                            let has_main =
-                                PathBuf::from(&member).join("src").join("main.rs").exists();
+        let remote = targets
+            .iter()
+            .filter(|t| t.source == BuildSource::Remote)
+            .map(|t| t.target.as_str())
+            .collect::<Vec<_>>();
+        if !remote.is_empty() {
+            steps.push(format!(
+                "Dispatch {} for {}",
+                self.config
+                    .remote_build
+                    .workflow
+                    .as_deref()
+                    .unwrap_or("the remote workflow"),
+                remote.join(", ")
+            ));
+        }
+        let built = targets
+            .iter()
+            .filter(|t| matches!(t.source, BuildSource::Local | BuildSource::Container))
+            .count();
+        if built > 0 {
+            steps.push(format!("Build and archive {built} target(s)"));
+        }
+        if let Some(source) = &self.args.from_artifacts {
+            steps.push(format!("Collect prebuilt archives from {source}"));
+        }
 
-                            // Extract description as String if available
-                            let description = package.description.and_then(|d| match d {
-                                cargo_manifest::MaybeInherited::Local(s) => Some(s),
-                                cargo_manifest::MaybeInherited::Inherited { .. } => None,
-                            });
+        steps.push(format!(
+            "Write {MANIFEST_FILE_NAME} and {SIZE_REPORT_FILE_NAME}"
+        ));
+        if !self.config.hooks.pre_upload.is_empty() {
+            steps.push("Run pre_upload hooks".to_string());
+        }
+        if !self.args.no_checksum {
+            steps.push(if self.config.default.checksum_sidecars {
+                "Generate SHA256SUMS and .sha256 sidecars".to_string()
+            } else {
+                "Generate SHA256SUMS".to_string()
+            });
+        }
 
-                            // Check for explicit bin targets
-                            if !member_manifest.bin.is_empty() {
-                                for bin in &member_manifest.bin {
-                                    let bin_name =
-                                        bin.name.clone().unwrap_or_else(|| package.name.clone());
+        if self.args.no_upload {
+            steps.push(format!(
+                "Write the release notes to {}",
+                self.dist_dir(tag).join(RELEASE_NOTES_FILE_NAME).display()
+            ));
+            return steps;
+        }
+
+        if self.args.create_tag && !self.args.hash {
+            steps.push(format!("Create and push tag {tag} if it is missing"));
+        }
+        let providers = self.args.providers();
+        for provider in &providers {
+            let destination = match provider {
+                Provider::Github => format!("{owner}/{repo}"),
+                Provider::Gitlab => format!(
+                    "GitLab project {}",
+                    self.config
+                        .gitlab
+                        .project
+                        .clone()
+                        .unwrap_or_else(|| format!("{owner}/{repo}"))
+                ),
+            };
+            steps.push(format!(
+                "Create {}release {tag} on {destination} and upload {asset_count} assets",
+                if self.args.draft && *provider == Provider::Github {
+                    "draft "
+                } else {
+                    ""
+                }
+            ));
+        }
+        let publishes_to_github = providers.contains(&Provider::Github);
+        if let (Some(docker_config), false) = (
+            &self.config.publishers.docker,
+            self.args.draft && publishes_to_github,
+        ) {
+            steps.push(format!(
+                "Push container image {}",
+                docker::image_name(docker_config, owner, repo)
+            ));
+        }
+        if let (Some(s3_config), false) = (
+            &self.config.publishers.s3,
+            self.args.draft && publishes_to_github,
+        ) {
+            steps.push(format!(
+                "Mirror the assets to s3://{}/{} and list them in the release notes",
+                s3_config.bucket,
+                s3_config.key_prefix(repo, tag, version)
+            ));
+        }
+        if self.config.publishers.npm.is_some() && publishes_to_github && !self.args.draft {
+            steps.push("Publish the npm package".to_string());
+        }
+        if let (Some(pages_config), true, false) = (
+            &self.config.publishers.pages,
+            publishes_to_github,
+            self.args.draft,
+        ) {
+            steps.push(format!(
+                "Update the download page on {}",
+                pages_config.branch()
+            ));
+        }
+        if self.args.hash
+            && !self.args.no_prune
+            && publishes_to_github
+            && self.config.retention.is_enabled()
+        {
+            steps.push("Prune old continuous releases".to_string());
+        }
+        if !self.args.skip_publish {
+            steps.push("Publish to crates.io".to_string());
+        }
+        if !self.config.hooks.post_release.is_empty() {
+            steps.push("Run post_release hooks".to_string());
+        }
+        if !self.config.notifications.webhooks.is_empty() {
+            steps.push("Send notifications".to_string());
+        }
+        steps
+    }
+
+    /// Build and release `tag`; `upload_only` stops after adding this runner's
+    /// assets and fragments to the shared draft (see [`DistBuilder::upload`])
+    async fn release(&self, tag: String, upload_only: bool) -> Result<Summary> {
+        if upload_only && self.args.no_upload {
+            anyhow::bail!("--no-upload can't be used with `cargo ghdist upload`");
+        }
+        tracing::info!("Building distribution for tag: {}", tag);
+        self.ui.step(format!("Checking the release of {tag}"));
+
+        // Make sure the working tree matches what is being released
+        let repository = Repository::open(".").context("Failed to open git repository")?;
+        preflight::check(
+            &repository,
+            &self.config.preflight.allowed_branches,
+            PreflightOverrides {
+                allow_dirty: self.args.allow_dirty,
+                allow_branch: self.args.allow_branch,
+            },
+        )?;
+
+        // Prebuilt targets don't need a local toolchain
+        if self.args.from_artifacts.is_none() {
+            toolchain::ensure_installed(
+                &toolchain::requirements(&self.config, self.args.targets()),
+                self.config.preflight.install_toolchains,
+            )?;
+        }
+
+        // --hash tags are derived from the version, so only explicit tags can drift
+        if !self.args.hash && !self.args.no_verify_version {
+            match self.get_package_version() {
+                Ok(version) => tagging::verify_tag_version(&tag, &self.tag_prefix(), &version)?,
+                Err(e) => tracing::warn!("Skipping tag version check: {}", e),
+            }
+        }
+
+        if self.config.gates.semver_checks && !self.args.hash {
+            self.semver_gate(&tag)?;
+        }
+
+        if self.args.run_tests {
+            gates::run_tests(&self.config.gates, &self.args.targets())?;
+        }
+
+        if self.config.gates.publish_dry_run {
+            publish::dry_run_workspace(&self.selected_names(), &self.publish_options()).await?;
+        }
+
+        let mut hook_context = HookContext {
+            version: self
+                .get_package_version()
+                .unwrap_or_else(|_| tag.strip_prefix('v').unwrap_or(&tag).to_string()),
+            tag: tag.clone(),
+            targets: self.args.targets(),
+            ..Default::default()
+        };
+        hooks::run(&self.config.hooks, HookStage::PreBuild, &hook_context)?;
+
+        // Read the changelog up front so --require-changelog fails before building
+        let changelog_notes = self.read_changelog_section(&tag)?;
+
+        // Parse repository info
+        let (owner, repo) = self.args.parse_repository()?;
+        tracing::info!("Repository: {}/{}", owner, repo);
+
+        // Create output directory
+        let dist_tag = sanitize_path_component(&tag);
+        let output_dir = self.dist_dir(&tag);
+        fs::create_dir_all(&output_dir)?;
+
+        let packages = self.args.packages();
+        let package_metadata = if packages.is_empty() && self.config.publishers.npm.is_none() {
+            None
+        } else {
+            Some(self.get_package_metadata(&repo)?)
+        };
+
+        let signer = Signer::from_config(&self.config.windows_signing)?;
+        let mut manifest = ReleaseManifest::new(&tag);
+
+        // --from-artifacts replaces every build, local or remote
+        let build_targets = if self.args.from_artifacts.is_some() {
+            Vec::new()
+        } else {
+            self.args.targets()
+        };
+
+        // Start the remote workflow first so it builds alongside the local targets
+        let remote_targets =
+            remote_build::remote_targets(&self.config.remote_build, &build_targets);
+        let remote = if remote_targets.is_empty() {
+            None
+        } else if self.args.no_upload {
+            tracing::warn!(
+                "Skipping remote targets with --no-upload: {}",
+                remote_targets.join(", ")
+            );
+            None
+        } else {
+            Some(
+                remote_build::dispatch(
+                    &self.github_client,
+                    &self.config.remote_build,
+                    &owner,
+                    &repo,
+                    &tag,
+                    &remote_targets,
+                )
+                .await?,
+            )
+        };
+
+        // Targets finished by an earlier run of this release don't need rebuilding
+        let existing = if self.args.force || self.args.no_upload || build_targets.is_empty() {
+            None
+        } else {
+            ExistingRelease::fetch(&self.github_client, &owner, &repo, &tag)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to check the existing release: {}", e);
+                    None
+                })
+        };
+
+        // Build for each target
+        let mut all_archives = Vec::new();
+        let mut linux_binaries = Vec::new();
+        let mut target_archives = Vec::new();
+        let mut binary_names = Vec::new();
+        let mut failed_targets = Vec::new();
+        let mut host = None;
+        let cache = CompileCache::detect(&self.config.cache);
+        if let Some(cache) = &cache {
+            cache.zero_stats();
+        }
+        for target in build_targets {
+            if remote_targets.contains(&target) {
+                continue;
+            }
+
+            // The container image is built from the binaries, so those targets always build
+            let needs_binaries =
+                self.config.publishers.docker.is_some() && target.contains("linux");
+            if let Some(existing) = existing.as_ref().filter(|_| !needs_binaries) {
+                match existing
+                    .reuse_target(&self.github_client, &owner, &repo, &target, &output_dir)
+                    .await
+                {
+                    Ok(Some((entry, assets))) => {
+                        tracing::info!(
+                            "Assets for {} are already on the release, skipping the build (pass --force to rebuild)",
+                            target
+                        );
+                        if binary_names.is_empty() {
+                            binary_names = entry
+                                .binaries
+                                .iter()
+                                .map(|b| b.name.trim_end_matches(".exe").to_string())
+                                .collect();
+                        }
+                        target_archives.push((target.clone(), assets[0].clone()));
+                        manifest.targets.push(entry);
+                        all_archives.extend(assets);
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Failed to reuse assets for {}: {}", target, e),
+                }
+            }
+
+            tracing::info!(
+                event = "build_started",
+                target = %target,
+                "Building for target: {}",
+                target
+            );
+            self.ui.step(format!("Building {target}"));
+            let started = Instant::now();
+
+            match self.build_for_target(&target, cache.as_ref()).await {
+                Ok((binaries, libraries)) => {
+                    tracing::info!(
+                        event = "build_finished",
+                        target = %target,
+                        binaries = binaries.len(),
+                        duration_ms = started.elapsed().as_millis() as u64,
+                        "Built {} binaries for {}",
+                        binaries.len(),
+                        target
+                    );
+                    if self.config.smoke_test.enabled {
+                        if host.is_none() {
+                            host = Some(gates::host_triple()?);
+                        }
+                        smoke_test::run(
+                            &self.config.smoke_test,
+                            &target,
+                            host.as_deref().unwrap_or_default(),
+                            &binaries,
+                        )?;
+                    }
+
+                    if wasm::is_wasm_target(&target) {
+                        wasm::optimize(&self.config.wasm, &binaries)?;
+                        wasm::optimize(&self.config.wasm, &libraries)?;
+                    }
+
+                    // The glibc the binaries need can go into the archive name
+                    let min_glibc = if glibc::links_glibc(&target) {
+                        let shared = libraries
+                            .iter()
+                            .filter(|path| path.extension().is_some_and(|ext| ext == "so"));
+                        let linked = binaries.iter().chain(shared).cloned().collect::<Vec<_>>();
+                        match glibc::min_glibc(&linked) {
+                            Ok(version) => version,
+                            Err(e) => {
+                                tracing::warn!(
+                                    "Failed to inspect glibc versions for {}: {}",
+                                    target,
+                                    e
+                                );
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+                    if let Some(version) = &min_glibc {
+                        tracing::info!("{} needs glibc {} or newer", target, version);
+                    }
+
+                    // Sign Windows executables before they are archived
+                    let signature = if !target.contains("windows") {
+                        SignatureStatus::NotApplicable
+                    } else if let Some(signer) = &signer {
+                        signer.sign_all(&binaries)?
+                    } else {
+                        SignatureStatus::Unsigned
+                    };
+
+                    // Create archive for this target
+                    let archive_name = plan::archive_name(
+                        &self.config,
+                        &repo,
+                        &target,
+                        &dist_tag,
+                        min_glibc.as_deref(),
+                    );
+                    let mut target_assets = Vec::new();
+                    if !binaries.is_empty() {
+                        let archive = packager::create_archive(
+                            &binaries,
+                            &output_dir,
+                            &archive_name,
+                            self.args.archive_format(),
+                        )?;
+                        target_archives.push((target.clone(), archive.clone()));
+                        target_assets.push(archive);
+                    }
+                    if !libraries.is_empty() {
+                        let entries = library::archive_entries(
+                            &libraries,
+                            self.config.library.headers.as_deref(),
+                        )?;
+                        target_assets.push(packager::create_archive_with_entries(
+                            &entries,
+                            &output_dir,
+                            &plan::library_archive_name(&archive_name),
+                            self.args.archive_format(),
+                        )?);
+                    }
+
+                    if binary_names.is_empty() {
+                        binary_names = binaries
+                            .iter()
+                            .filter_map(|b| b.file_stem().and_then(|s| s.to_str()))
+                            .map(String::from)
+                            .collect();
+                    }
+
+                    if let Some(metadata) =
+                        package_metadata.as_ref().filter(|_| !binaries.is_empty())
+                    {
+                        target_assets.extend(self.create_packages(
+                            &packages,
+                            &binaries,
+                            &output_dir,
+                            metadata,
+                            &target,
+                        )?);
+                    }
+
+                    if target.contains("linux") && !binaries.is_empty() {
+                        linux_binaries.push((target.clone(), binaries.clone()));
+                    }
+
+                    for asset in &target_assets {
+                        let asset = AssetSummary::from_path(asset);
+                        tracing::info!(
+                            event = "archive_created",
+                            target = %target,
+                            asset = %asset.name,
+                            size = asset.size,
+                            "Created {}",
+                            asset.name
+                        );
+                    }
+                    manifest.add_target(&target, &binaries, &target_assets, signature)?;
+                    if let Some(version) = min_glibc {
+                        manifest.set_min_glibc(&target, version);
+                    }
+                    all_archives.extend(target_assets);
+                }
+                Err(e) => {
+                    tracing::error!(
+                        event = "build_failed",
+                        target = %target,
+                        error = %e,
+                        "Failed to build for {}: {}",
+                        target,
+                        e
+                    );
+                    if !self.should_continue_on_error() {
+                        return Err(e.into());
+                    }
+                    self.ui.fail();
+                    failed_targets.push(target.clone());
+                }
+            }
+        }
+
+        let cache_stats = cache.as_ref().and_then(CompileCache::stats);
+        if let Some(stats) = &cache_stats {
+            tracing::info!("Compiler cache: {}", stats);
+        }
+
+        if let Some(remote) = remote {
+            self.ui.step(format!(
+                "Waiting for remote builds of {}",
+                remote_targets.join(", ")
+            ));
+            let assets = remote
+                .finish(
+                    &self.github_client,
+                    &self.config.remote_build,
+                    &owner,
+                    &repo,
+                    &output_dir,
+                )
+                .await?;
+            record_prebuilt(
+                &mut manifest,
+                &mut target_archives,
+                &remote_targets,
+                &assets,
+            )?;
+            all_archives.extend(assets);
+        }
+
+        if let Some(source) = &self.args.from_artifacts {
+            let assets = match ArtifactSource::parse(source)? {
+                ArtifactSource::Run(_) if self.args.no_upload => {
+                    anyhow::bail!("--no-upload needs a local directory for --from-artifacts")
+                }
+                ArtifactSource::Run(run_id) => {
+                    tracing::info!("Using artifacts from workflow run {}", run_id);
+                    remote_build::download_run_artifacts(
+                        &self.github_client,
+                        &owner,
+                        &repo,
+                        run_id,
+                        &output_dir,
+                    )
+                    .await?
+                }
+                ArtifactSource::Dir(dir) => {
+                    tracing::info!("Using prebuilt artifacts from {}", dir.display());
+                    remote_build::local_artifacts(&dir)?
+                }
+            };
+            record_prebuilt(
+                &mut manifest,
+                &mut target_archives,
+                &self.args.targets(),
+                &assets,
+            )?;
+            all_archives.extend(assets);
+        }
+
+        if all_archives.is_empty() {
+            return Err(GhDistError::BuildFailed {
+                target: "all targets".to_string(),
+            }
+            .into());
+        }
+
+        // Matrix jobs only contribute their assets; `finalize` assembles the release
+        if upload_only {
+            hook_context.assets = all_archives.clone();
+            hooks::run(&self.config.hooks, HookStage::PreUpload, &hook_context)?;
+            let mut summary = summarize(
+                &tag,
+                &manifest,
+                failed_targets,
+                &all_archives,
+                &output_dir,
+                cache_stats,
+            );
+            let (url, uploaded) = self
+                .upload_fragment(&tag, &owner, &repo, all_archives, &manifest, &output_dir)
+                .await?;
+            summary.release_url = Some(url);
+            summary.uploaded_assets = uploaded;
+            self.ui.finish();
+            return Ok(summary);
+        }
+
+        all_archives.push(manifest.write(&output_dir)?);
+
+        // Compare binary sizes against the previous release's manifest
+        let previous_manifest = if self.args.no_upload {
+            None
+        } else {
+            self.previous_manifest(&tag, &owner, &repo).await
+        };
+        let size_report = SizeReport::compare(&manifest, previous_manifest.as_ref());
+        all_archives.push(size_report.write(&output_dir)?);
+
+        // Hooks may still modify assets, so checksums are computed afterwards
+        hook_context.assets = all_archives.clone();
+        hooks::run(&self.config.hooks, HookStage::PreUpload, &hook_context)?;
+
+        // What an earlier run already uploaded, so identical assets are not uploaded again
+        let publishes_to_github = self.args.providers().contains(&Provider::Github);
+        let uploaded_state = if !self.args.no_upload && publishes_to_github {
+            self.uploaded_state(&owner, &repo, &tag).await?
+        } else {
+            None
+        };
+
+        // Generate checksums if requested
+        if !self.args.no_checksum {
+            let sidecars = self.sidecar_checksums(&all_archives)?;
+            let checksum_file = packager::generate_checksums(&all_archives, &output_dir)?;
+            if let Some(state) = uploaded_state.as_ref().filter(|_| self.args.resume) {
+                // Cover assets left on the release by earlier runs too
+                let local_names = all_archives
+                    .iter()
+                    .filter_map(|path| path.file_name().and_then(|n| n.to_str()))
+                    .collect::<Vec<_>>();
+                let mut file = fs::OpenOptions::new().append(true).open(&checksum_file)?;
+                for (name, sha256) in state.remote_only_checksums(&local_names) {
+                    writeln!(file, "{sha256}  {name}")?;
+                }
+            }
+            all_archives.extend(sidecars);
+            all_archives.push(checksum_file);
+        }
+
+        let target_commitish = self.target_commitish();
+        let mut release_notes = self.base_release_notes(
+            &tag,
+            &owner,
+            &repo,
+            changelog_notes,
+            &manifest,
+            &size_report,
+        )?;
+
+        // Offline packaging ends here, leaving the would-be release in the output directory
+        if self.args.no_upload {
+            let notes_file = output_dir.join(RELEASE_NOTES_FILE_NAME);
+            fs::write(&notes_file, &release_notes)?;
+            tracing::info!(
+                event = "packaged",
+                assets = all_archives.len(),
+                output_dir = %output_dir.display(),
+                "Packaged {} assets into {} without uploading",
+                all_archives.len(),
+                output_dir.display()
+            );
+            tracing::info!("Release notes written to {}", notes_file.display());
+            self.ui.finish();
+            return Ok(summarize(
+                &tag,
+                &manifest,
+                failed_targets,
+                &all_archives,
+                &output_dir,
+                cache_stats,
+            ));
+        }
+
+        release_notes.push_str(&self.container_notes(&owner, &repo, &tag));
+        self.append_history_notes(
+            &mut release_notes,
+            &tag,
+            &owner,
+            &repo,
+            target_commitish.as_deref(),
+        )
+        .await?;
+
+        tracing::debug!("Final release notes: {} chars", release_notes.len());
+
+        // Catch oversized assets before the release exists rather than halfway through uploading
+        for asset_path in &all_archives {
+            let name = asset_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            github::check_asset_size(name, fs::metadata(asset_path)?.len())?;
+        }
+
+        // Create and push the tag first so the release points at the commit we built
+        if self.args.create_tag && !self.args.hash {
+            let token = self.github_client.optional_token(&owner, &repo).await?;
+            self.ensure_tag(&tag, token.as_deref())?;
+        }
+
+        // Create or update the release on each forge, GitHub first so GitLab links resolve
+        let options = self.release_options(target_commitish.as_deref(), &release_notes);
+        let mut release_url = None;
+        let mut uploaded_assets = 0;
+        for provider in self.args.providers() {
+            let client = self.release_provider(provider)?;
+            let release = client
+                .create_or_update_release(&owner, &repo, &tag, &options)
+                .await?;
+
+            // Upload all assets, replacing any left over from an earlier run
+            uploaded_assets = 0;
+            self.ui.step(format!(
+                "Uploading {} assets to {}",
+                all_archives.len(),
+                client.provider()
+            ));
+            for asset_path in &all_archives {
+                let uploaded = match &uploaded_state {
+                    Some(state) if client.provider() == Provider::Github => {
+                        self.upload_if_changed(&owner, &repo, release.id, asset_path, state)
+                            .await?
+                    }
+                    _ => {
+                        client
+                            .attach_asset(&owner, &repo, &tag, &release, asset_path)
+                            .await?
+                    }
+                };
+                if uploaded {
+                    log_upload(client.provider(), asset_path);
+                    uploaded_assets += 1;
+                }
+            }
+            tracing::info!(
+                event = "release_published",
+                provider = %client.provider(),
+                tag = %tag,
+                url = %release.url,
+                "Published {} release: {}",
+                client.provider(),
+                release.url
+            );
+            release_url.get_or_insert(release.url);
+        }
+        let release_url = release_url.unwrap_or_default();
+
+        self.run_publishers(
+            &PublishedRelease {
+                owner: &owner,
+                repo: &repo,
+                tag: &tag,
+                version: &hook_context.version,
+                target_commitish: target_commitish.as_deref(),
+                url: &release_url,
+                manifest: &manifest,
+                assets: &all_archives,
+                target_archives: &target_archives,
+                binary_names: &binary_names,
+                package_metadata: package_metadata.as_ref(),
+                linux_binaries: &linux_binaries,
+                output_dir: &output_dir,
+            },
+            &mut release_notes,
+        )
+        .await?;
+
+        // Run cargo publish if requested
+        if !self.args.skip_publish {
+            self.run_cargo_publish().await?;
+        }
+
+        let mut summary = summarize(
+            &tag,
+            &manifest,
+            failed_targets,
+            &all_archives,
+            &output_dir,
+            cache_stats,
+        );
+        summary.release_url = Some(release_url.clone());
+        summary.uploaded_assets = uploaded_assets;
+        hook_context.assets = all_archives;
+        hook_context.release_url = Some(release_url.clone());
+        self.announce(&owner, &repo, &hook_context).await;
+
+        self.ui.finish();
+        tracing::info!("Distribution completed successfully!");
+        tracing::info!("Release URL: {}", release_url);
+
+        Ok(summary)
+    }
+
+    /// Get package version from Cargo.toml
+    fn get_package_version(&self) -> Result<String> {
+        if let Some(package) = &self.package {
+            return Ok(package.version.clone());
+        }
+
+        let manifest = Manifest::from_path("Cargo.toml").context("Failed to parse Cargo.toml")?;
+
+        // Check if this is a workspace manifest with workspace.package.version
+        if let Some(workspace) = &manifest.workspace {
+            if let Some(ws_package) = &workspace.package {
+                if let Some(version) = &ws_package.version {
+                    return Ok(version.clone());
+                }
+            }
+        }
+
+        // Get version from package
+        if let Some(package) = manifest.package {
+            match package.version {
+                Some(cargo_manifest::MaybeInherited::Local(version)) => {
+                    return Ok(version);
+                }
+                Some(cargo_manifest::MaybeInherited::Inherited { .. }) => {
+                    // Version is inherited from workspace, need to read workspace manifest
+                    if let Ok(workspace_manifest) = find_workspace_manifest() {
+                        if let Some(ws_package) =
+                            workspace_manifest.workspace.and_then(|ws| ws.package)
+                        {
+                            if let Some(version) = ws_package.version {
+                                return Ok(version);
+                            }
+                        }
+                    }
+                }
+                None => {
+                    // No version in package, try workspace
+                    if let Ok(workspace_manifest) = find_workspace_manifest() {
+                        if let Some(ws_package) =
+                            workspace_manifest.workspace.and_then(|ws| ws.package)
+                        {
+                            if let Some(version) = ws_package.version {
+                                return Ok(version);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        anyhow::bail!("No version field found in Cargo.toml")
+    }
+
+    /// Collect package metadata (name, version, description, ...) from Cargo.toml
+    fn get_package_metadata(&self, repo_name: &str) -> Result<PackageMetadata> {
+        let manifest = Manifest::from_path("Cargo.toml").context("Failed to parse Cargo.toml")?;
+        let workspace_package = find_workspace_manifest()
+            .ok()
+            .and_then(|ws| ws.workspace)
+            .and_then(|ws| ws.package);
+
+        let mut metadata = PackageMetadata {
+            name: repo_name.to_string(),
+            version: self.get_package_version()?,
+            ..Default::default()
+        };
+
+        if let Some(ws_package) = &workspace_package {
+            metadata.description = ws_package.description.clone();
+            metadata.authors = ws_package.authors.clone().unwrap_or_default();
+            metadata.homepage = ws_package
+                .homepage
+                .clone()
+                .or_else(|| ws_package.repository.clone());
+            metadata.license = ws_package.license.clone();
+        }
+
+        if let Some(package) = manifest.package {
+            metadata.name = package.name;
+            if let Some(cargo_manifest::MaybeInherited::Local(description)) = package.description {
+                metadata.description = Some(description);
+            }
+            if let Some(cargo_manifest::MaybeInherited::Local(authors)) = package.authors {
+                metadata.authors = authors;
+            }
+            let homepage = match (package.homepage, package.repository) {
+                (Some(cargo_manifest::MaybeInherited::Local(url)), _) => Some(url),
+                (_, Some(cargo_manifest::MaybeInherited::Local(url))) => Some(url),
+                _ => None,
+            };
+            if homepage.is_some() {
+                metadata.homepage = homepage;
+            }
+            if let Some(cargo_manifest::MaybeInherited::Local(license)) = package.license {
+                metadata.license = Some(license);
+            }
+        }
+
+        if let Some(package) = &self.package {
+            metadata.name = package.name.clone();
+            if package.description.is_some() {
+                metadata.description = package.description.clone();
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    /// Build the requested system packages for a target
+    fn create_packages(
+        &self,
+        packages: &[PackageFormat],
+        binaries: &[PathBuf],
+        output_dir: &Path,
+        metadata: &PackageMetadata,
+        target: &str,
+    ) -> Result<Vec<PathBuf>> {
+        let mut created = Vec::new();
+
+        for package in packages {
+            match package {
+                PackageFormat::Deb => {
+                    if deb::debian_arch(target).is_none() {
+                        tracing::info!("Skipping .deb for non-Linux target {}", target);
+                        continue;
+                    }
+                    created.push(deb::create_deb(
+                        binaries,
+                        output_dir,
+                        metadata,
+                        target,
+                        &self.config.deb,
+                    )?);
+                }
+                PackageFormat::Rpm => {
+                    if rpm::rpm_arch(target).is_none() {
+                        tracing::info!("Skipping .rpm for non-Linux target {}", target);
+                        continue;
+                    }
+                    created.push(rpm::create_rpm(
+                        binaries,
+                        output_dir,
+                        metadata,
+                        target,
+                        &self.config.rpm,
+                    )?);
+                }
+                PackageFormat::Msi | PackageFormat::Nsis => {
+                    if windows_installer::wix_arch(target).is_none() {
+                        tracing::info!("Skipping {} for non-Windows target {}", package, target);
+                        continue;
+                    }
+                    let create = if *package == PackageFormat::Msi {
+                        windows_installer::create_msi
+                    } else {
+                        windows_installer::create_nsis
+                    };
+                    created.push(create(
+                        binaries,
+                        output_dir,
+                        metadata,
+                        target,
+                        &self.config.windows_installer,
+                    )?);
+                }
+            }
+        }
+
+        Ok(created)
+    }
+
+    /// Get binary names and descriptions from the project
+    fn get_binary_info(&self) -> Result<Vec<(String, Option<String>)>> {
+        if !self.selected.is_empty() {
+            return Ok(self
+                .selected
+                .iter()
+                .flat_map(|package| {
+                    package
+                        .bin_names()
+                        .into_iter()
+                        .map(|name| (name, package.description.clone()))
+                })
+                .collect());
+        }
+
+        let mut binaries = Vec::new();
+
+        // Try to read workspace members
+        if let Ok(manifest) = Manifest::from_path("Cargo.toml") {
+            if let Some(workspace) = manifest.workspace {
+                // For workspace projects, check each member
+                for member in &workspace.members {
+                    let member_path = PathBuf::from(&member).join("Cargo.toml");
+                    if let Ok(member_manifest) = Manifest::from_path(&member_path) {
+                        if let Some(package) = member_manifest.package {
+                            // Check if this package produces a binary
+                            // By default, packages with src/main.rs produce a binary with the package name
+                            let has_main =
+                                PathBuf::from(&member).join("src").join("main.rs").exists();
+
+                            // Extract description as String if available
+                            let description = package.description.and_then(|d| match d {
+                                cargo_manifest::MaybeInherited::Local(s) => Some(s),
+                                cargo_manifest::MaybeInherited::Inherited { .. } => None,
+                            });
+
+                            // Check for explicit bin targets
+                            if !member_manifest.bin.is_empty() {
+                                for bin in &member_manifest.bin {
+                                    let bin_name =
+                                        bin.name.clone().unwrap_or_else(|| package.name.clone());
                                     binaries.push((bin_name, description.clone()));
                                 }
                             } else if has_main {
@@ -414,97 +1764,817 @@ impl DistBuilder {
                 } else {
                     binary_install_commands.push_str(&format!("\n# {binary_name}\n"));
                 }
-                binary_install_commands.push_str(&format!(
-                    "cargo ghinstall {owner}/{repo_name}@{tag} --bin {binary_name}\n"
-                ));
+                binary_install_commands.push_str(&format!(
+                    "cargo ghinstall {owner}/{repo_name}@{tag} --bin {binary_name}\n"
+                ));
+            }
+        }
+
+        // Group conventional commits since the previous tag
+        let previous_tag = self
+            .find_previous_tag(tag)
+            .ok()
+            .filter(|t| repo.find_reference(&format!("refs/tags/{t}")).is_ok());
+        let changelog = if is_continuous {
+            String::new()
+        } else {
+            changelog::collect_commits(
+                &repo,
+                previous_tag.as_deref(),
+                self.package_path(&repo).as_deref(),
+            )
+            .map(|commits| changelog::render(&commits, owner, repo_name))
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to generate changelog: {}", e);
+                None
+            })
+            .map(|c| format!("{c}\n"))
+            .unwrap_or_default()
+        };
+
+        // Build the release notes
+        let notes = if is_continuous {
+            format!(
+                r#"## 🚀 Continuous Release
+
+**Commit:** `{}`
+**Author:** {}
+**Branch:** {}
+
+### 📝 Commit Message
+{}
+
+### ⚠️ Note
+This is an automated development build. Use for testing purposes only.
+For stable releases, please use tagged versions.
+
+### 📦 Installation
+```bash
+# Install all binaries
+cargo ghinstall {}/{}@{}
+{}```
+
+### 🔗 Links
+- [Commit](https://github.com/{}/{}/commit/{})
+"#,
+                sha,
+                author_name,
+                branch,
+                message.trim(),
+                owner,
+                repo_name,
+                tag,
+                binary_install_commands,
+                owner,
+                repo_name,
+                sha
+            )
+        } else {
+            format!(
+                r#"## 🎉 Release {}
+
+**Commit:** `{}`
+**Author:** {}
+
+{}### 📦 Installation
+```bash
+# Install all binaries
+cargo ghinstall {}/{}@{}
+{}
+# Or download directly from the release assets
+```
+
+### 🔗 Links
+- [Commit](https://github.com/{}/{}/commit/{})
+- [Compare](https://github.com/{}/{}/compare/{}...{})
+"#,
+                tag,
+                sha,
+                author_name,
+                changelog,
+                owner,
+                repo_name,
+                tag,
+                binary_install_commands,
+                owner,
+                repo_name,
+                sha,
+                owner,
+                repo_name,
+                previous_tag.as_deref().unwrap_or("main"),
+                tag
+            )
+        };
+
+        Ok(notes)
+    }
+
+    /// Render the contributors since the previous tag, resolving GitHub logins
+    async fn contributors_section(
+        &self,
+        tag: &str,
+        owner: &str,
+        repo_name: &str,
+    ) -> Result<Option<String>> {
+        let mut contributors = {
+            let repo = Repository::open(".")?;
+            let previous_tag = self
+                .find_previous_tag(tag)
+                .ok()
+                .filter(|t| repo.find_reference(&format!("refs/tags/{t}")).is_ok());
+            contributors::collect_contributors(
+                &repo,
+                previous_tag.as_deref(),
+                self.package_path(&repo).as_deref(),
+            )?
+        };
+
+        for contributor in &mut contributors {
+            match self
+                .github_client
+                .get_commit_author_login(owner, repo_name, &contributor.sha)
+                .await
+            {
+                Ok(login) => contributor.login = login,
+                Err(e) => tracing::debug!("Could not resolve login for {}: {}", contributor.sha, e),
+            }
+        }
+
+        Ok(contributors::render(&contributors))
+    }
+
+    /// Render merged pull requests since the previous tag grouped by label
+    async fn pull_request_notes(
+        &self,
+        tag: &str,
+        owner: &str,
+        repo_name: &str,
+    ) -> Result<Option<String>> {
+        let since = {
+            let repo = Repository::open(".")?;
+            self.find_previous_tag(tag)
+                .ok()
+                .and_then(|t| repo.revparse_single(&format!("refs/tags/{t}")).ok())
+                .and_then(|obj| obj.peel_to_commit().ok())
+                .and_then(|commit| {
+                    chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+                        .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+                })
+        };
+
+        let pull_requests = self
+            .github_client
+            .list_merged_pull_requests(owner, repo_name, since.as_deref())
+            .await?;
+
+        let notes_config = &self.config.release_notes;
+        let categories = if notes_config.categories.is_empty() {
+            pull_requests::default_categories()
+        } else {
+            notes_config.categories.clone()
+        };
+
+        Ok(pull_requests::render(
+            &pull_requests,
+            &categories,
+            &notes_config.exclude_labels,
+        ))
+    }
+
+    /// Read the CHANGELOG.md section for this tag, enforcing --require-changelog
+    fn read_changelog_section(&self, tag: &str) -> Result<Option<String>> {
+        let Some(path) = self.args.changelog_file() else {
+            return Ok(None);
+        };
+
+        let section = match fs::read_to_string(&path) {
+            Ok(content) => changelog::extract_section(&content, tag),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e).context(format!("Failed to read {}", path.display())),
+        };
+
+        match section {
+            Some(section) => {
+                tracing::info!("Using release notes from {}", path.display());
+                Ok(Some(section))
+            }
+            None if self.args.require_changelog => Err(GhDistError::Config(format!(
+                "No entry for {tag} found in {}",
+                path.display()
+            ))
+            .into()),
+            None => {
+                tracing::warn!(
+                    "No entry for {} in {}; using generated release notes",
+                    tag,
+                    path.display()
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Block breaking library changes unless the version is a major bump
+    fn semver_gate(&self, tag: &str) -> Result<()> {
+        let previous_tag = self.find_previous_tag(tag)?;
+        if previous_tag == "main" || previous_tag == tag {
+            tracing::info!("No previous release tag, skipping semver checks");
+            return Ok(());
+        }
+
+        let prefix = self.tag_prefix();
+        let parse = |t: &str| semver::Version::parse(t.strip_prefix(&prefix).unwrap_or(t)).ok();
+        if let (Some(previous), Some(current)) = (parse(&previous_tag), parse(tag)) {
+            if gates::is_major_bump(&previous, &current) {
+                tracing::info!(
+                    "{} is a major bump from {}, skipping semver checks",
+                    tag,
+                    previous_tag
+                );
+                return Ok(());
+            }
+        }
+
+        let libraries = gates::library_packages(Path::new("."))?;
+        if libraries.is_empty() {
+            tracing::info!("No library crates, skipping semver checks");
+            return Ok(());
+        }
+
+        tracing::info!("Running cargo semver-checks against {}", previous_tag);
+        gates::run_semver_checks(&libraries, &previous_tag)
+    }
+
+    /// Add this runner's assets plus checksum and manifest fragments to the shared draft
+    async fn upload_fragment(
+        &self,
+        tag: &str,
+        owner: &str,
+        repo: &str,
+        mut assets: Vec<PathBuf>,
+        manifest: &ReleaseManifest,
+        output_dir: &Path,
+    ) -> Result<(String, usize)> {
+        let key = fanout::fragment_key(manifest);
+        if !self.args.no_checksum {
+            let sidecars = self.sidecar_checksums(&assets)?;
+            let checksums = fanout::write_checksum_fragment(&assets, &key, output_dir)?;
+            assets.extend(sidecars);
+            assets.push(checksums);
+        }
+        assets.push(fanout::write_manifest_fragment(manifest, &key, output_dir)?);
+
+        let target_commitish = self.target_commitish();
+        self.ui.step(format!(
+            "Uploading {} assets to the draft release",
+            assets.len()
+        ));
+        let release = fanout::draft_release(
+            &self.github_client,
+            owner,
+            repo,
+            tag,
+            target_commitish.as_deref(),
+        )
+        .await?;
+        let existing = self
+            .github_client
+            .list_release_assets(owner, repo, release.id.0)
+            .await?;
+        let mut uploaded_assets = 0;
+        for asset_path in &assets {
+            if self
+                .github_client
+                .replace_asset(owner, repo, release.id.0, asset_path, &existing)
+                .await?
+            {
+                log_upload(Provider::Github, asset_path);
+                uploaded_assets += 1;
+            }
+        }
+
+        tracing::info!(
+            "Uploaded {} assets to the draft release {}; run `cargo ghdist finalize` once all jobs are done",
+            uploaded_assets,
+            tag
+        );
+        Ok((release.html_url.to_string(), uploaded_assets))
+    }
+
+    /// What the release for `tag` already has, listed once for all uploads;
+    /// empty if there is no release yet
+    async fn uploaded_state(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> Result<Option<ResumeState>> {
+        let Some(release) = self.github_client.find_release(owner, repo, tag).await? else {
+            tracing::info!("No release for {} yet, uploading every asset", tag);
+            return Ok(Some(ResumeState::default()));
+        };
+
+        let uploaded = self
+            .github_client
+            .list_release_assets(owner, repo, release.id.0)
+            .await?;
+        let previous_checksums = match uploaded
+            .iter()
+            .find(|a| a.name == "SHA256SUMS" && a.state == "uploaded")
+        {
+            Some(asset) => {
+                let content = self
+                    .github_client
+                    .download_asset(owner, repo, asset.id)
+                    .await?;
+                incremental::parse_checksums(&String::from_utf8_lossy(&content))
+            }
+            None => Default::default(),
+        };
+
+        Ok(Some(ResumeState {
+            uploaded,
+            previous_checksums,
+        }))
+    }
+
+    /// Upload one asset unless an identical copy is already on the release,
+    /// returning whether it was uploaded
+    async fn upload_if_changed(
+        &self,
+        owner: &str,
+        repo: &str,
+        release_id: u64,
+        asset_path: &Path,
+        state: &ResumeState,
+    ) -> Result<bool> {
+        let name = asset_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+        let sha256 = packager::sha256_file(asset_path)?;
+
+        match state.plan(name, &sha256) {
+            UploadAction::Skip => {
+                tracing::info!("{} is already uploaded, skipping", name);
+                return Ok(false);
+            }
+            UploadAction::Replace(asset_id) => {
+                tracing::info!("Replacing incomplete or changed asset: {}", name);
+                self.github_client
+                    .delete_asset(owner, repo, asset_id)
+                    .await?;
+            }
+            UploadAction::Upload => {}
+        }
+
+        self.github_client
+            .upload_asset(
+                owner,
+                repo,
+                release_id,
+                asset_path,
+                get_content_type(asset_path),
+            )
+            .await?;
+        Ok(true)
+    }
+
+    /// The release notes section with the `docker pull` line, when the image is pushed
+    ///
+    /// The image is only pushed once the release exists, but the notes name it up front.
+    fn container_notes(&self, owner: &str, repo: &str, tag: &str) -> String {
+        match &self.config.publishers.docker {
+            Some(docker_config) if !self.is_github_draft() => format!(
+                "\n### 🐳 Container Image\n\n```bash\ndocker pull {}\n```\n",
+                docker::image_reference(&docker::image_name(docker_config, owner, repo), tag)
+            ),
+            _ => String::new(),
+        }
+    }
+
+    /// Whether the release stays a draft on GitHub, where publishers must not
+    /// run ahead of it
+    fn is_github_draft(&self) -> bool {
+        self.args.draft && self.args.providers().contains(&Provider::Github)
+    }
+
+    /// Push the image, mirror the assets, publish the npm package and the
+    /// download page, and prune old continuous releases, once the release is
+    /// published; a failed release leaves none of them behind
+    async fn run_publishers(
+        &self,
+        release: &PublishedRelease<'_>,
+        release_notes: &mut String,
+    ) -> Result<()> {
+        let PublishedRelease {
+            owner, repo, tag, ..
+        } = *release;
+        let publishes_to_github = self.args.providers().contains(&Provider::Github);
+        let draft = self.is_github_draft();
+
+        // A draft's `:latest` would run ahead of the release
+        if self.config.publishers.docker.is_some() && draft {
+            tracing::warn!("Not pushing the container image: the release is a draft");
+        } else if let Some(docker_config) = &self.config.publishers.docker {
+            docker::publish(
+                docker_config,
+                release.linux_binaries,
+                release.output_dir,
+                &docker::image_name(docker_config, owner, repo),
+                tag,
+                &self.github_client.token(owner, repo).await?,
+            )?;
+        }
+
+        // Add the mirror URLs to the notes of the release that now exists
+        if self.config.publishers.s3.is_some() && draft {
+            tracing::warn!("Not mirroring the assets: the release is a draft");
+        } else if let Some(s3_config) = &self.config.publishers.s3 {
+            let prefix = s3_config.key_prefix(repo, tag, release.version);
+            let mirrored =
+                mirror::publish(s3_config, release.assets, &prefix, !self.args.no_progress).await?;
+            release_notes.push_str(&mirror::render_notes(&mirrored));
+            let options = self.release_options(release.target_commitish, release_notes);
+            for provider in self.args.providers() {
+                self.release_provider(provider)?
+                    .create_or_update_release(owner, repo, tag, &options)
+                    .await?;
+            }
+        }
+
+        // The npm package downloads the uploaded archives, which a draft keeps private
+        if self.config.publishers.npm.is_some() && draft {
+            tracing::warn!(
+                "Not publishing the npm package: the archives it downloads are in a draft release"
+            );
+        } else if let (Some(npm_config), Some(metadata), true) = (
+            &self.config.publishers.npm,
+            release.package_metadata,
+            publishes_to_github,
+        ) {
+            let package_dir = npm::generate_package(
+                npm_config,
+                metadata,
+                &NpmRelease {
+                    owner,
+                    repo,
+                    tag,
+                    archives: release.target_archives,
+                    binaries: release.binary_names,
+                },
+                release.output_dir,
+            )?;
+            npm::publish(npm_config, &package_dir)?;
+        }
+
+        // The download page links to the GitHub release assets, which a draft keeps private
+        if self.config.publishers.pages.is_some() && draft {
+            tracing::warn!("Not updating the download page: the release is a draft");
+        } else if let (Some(pages_config), true) =
+            (&self.config.publishers.pages, publishes_to_github)
+        {
+            self.publish_pages(
+                pages_config,
+                owner,
+                repo,
+                release.manifest,
+                release.assets,
+                release.url,
+            )
+            .await?;
+        }
+
+        // Prune old continuous releases only once this one is fully uploaded
+        if self.args.hash
+            && !self.args.no_prune
+            && publishes_to_github
+            && self.config.retention.is_enabled()
+        {
+            if let Err(e) = self.prune_continuous_releases(owner, repo, tag).await {
+                tracing::warn!("Failed to prune old continuous releases: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Post-release hooks and notifications; the release is already public, so failures only warn
+    async fn announce(&self, owner: &str, repo: &str, hook_context: &HookContext) {
+        if let Err(e) = hooks::run(&self.config.hooks, HookStage::PostRelease, hook_context) {
+            tracing::warn!("{}", e);
+        }
+
+        if !self.config.notifications.webhooks.is_empty() {
+            let asset_names = hook_context
+                .assets
+                .iter()
+                .filter_map(|path| path.file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            notifications::send(
+                &self.config.notifications,
+                &Notification {
+                    repository: &format!("{owner}/{repo}"),
+                    tag: &hook_context.tag,
+                    event: Event::Released {
+                        url: hook_context.release_url.as_deref().unwrap_or_default(),
+                        assets: &asset_names,
+                    },
+                },
+            )
+            .await;
+        }
+    }
+
+    /// Commit to point a `--hash` release at
+    fn target_commitish(&self) -> Option<String> {
+        if self.args.hash {
+            let sha = Repository::open(".").ok().and_then(|repo| {
+                repo.head()
+                    .ok()
+                    .and_then(|head| head.target())
+                    .map(|oid| oid.to_string())
+            });
+            tracing::info!("Using commit SHA for release: {:?}", sha);
+            sha
+        } else {
+            tracing::info!("Not using commit SHA (--hash not specified)");
+            None
+        }
+    }
+
+    /// Changelog section (or generated notes) followed by the binary size table
+    fn base_release_notes(
+        &self,
+        tag: &str,
+        owner: &str,
+        repo: &str,
+        changelog_notes: Option<String>,
+        manifest: &ReleaseManifest,
+        size_report: &SizeReport,
+    ) -> Result<String> {
+        let mut release_notes = match changelog_notes {
+            Some(notes) => notes,
+            None => self.generate_release_notes(tag, owner, repo, self.args.hash)?,
+        };
+        if self.args.hash {
+            release_notes.push_str(&format!("\n{}\n", retention::CONTINUOUS_MARKER));
+        }
+
+        if let Some(size_table) = size_report.render() {
+            release_notes.push('\n');
+            release_notes.push_str(&size_table);
+        }
+
+        if let Some(glibc_table) = glibc::render_notes(manifest) {
+            release_notes.push('\n');
+            release_notes.push_str(&glibc_table);
+        }
+
+        Ok(release_notes)
+    }
+
+    /// Contributors plus merged pull requests or GitHub's notes, for tagged releases
+    async fn append_history_notes(
+        &self,
+        release_notes: &mut String,
+        tag: &str,
+        owner: &str,
+        repo: &str,
+        target_commitish: Option<&str>,
+    ) -> Result<()> {
+        // For tagged releases, thank the people who contributed since the previous tag
+        if !self.args.hash {
+            match self.contributors_section(tag, owner, repo).await {
+                Ok(Some(section)) => {
+                    release_notes.push('\n');
+                    release_notes.push_str(&section);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to list contributors: {}", e),
+            }
+        }
+
+        // For tagged releases, append merged pull requests or GitHub's auto-generated notes
+        if !self.args.hash {
+            match self.config.release_notes.mode()? {
+                ReleaseNotesMode::PullRequests => {
+                    match self.pull_request_notes(tag, owner, repo).await {
+                        Ok(Some(pr_notes)) => {
+                            release_notes.push_str("\n\n---\n\n");
+                            release_notes.push_str(&pr_notes);
+                        }
+                        Ok(None) => tracing::info!("No merged pull requests since previous tag"),
+                        Err(e) => tracing::warn!("Failed to list merged pull requests: {}", e),
+                    }
+                }
+                ReleaseNotesMode::Github => {
+                    tracing::info!(
+                        "Fetching GitHub's auto-generated release notes for tag {}",
+                        tag
+                    );
+
+                    // Get the previous tag for comparison
+                    let previous_tag = self.find_previous_tag(tag).ok();
+
+                    // Fetch auto-generated release notes from GitHub
+                    match self
+                        .github_client
+                        .generate_release_notes(
+                            owner,
+                            repo,
+                            tag,
+                            target_commitish,
+                            previous_tag.as_deref(),
+                        )
+                        .await
+                    {
+                        Ok(auto_notes) => {
+                            tracing::debug!("Got auto-generated notes: {} chars", auto_notes.len());
+                            // Append the auto-generated notes to our custom notes
+                            release_notes.push_str("\n\n---\n");
+                            release_notes.push_str("\n## 📋 Auto-generated Release Notes\n\n");
+                            release_notes.push_str(&auto_notes);
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to get auto-generated release notes: {}", e);
+                            // Continue without auto-generated notes
+                        }
+                    }
+                }
             }
         }
 
-        // Build the release notes
-        let notes = if is_continuous {
-            format!(
-                r#"## 🚀 Continuous Release
+        Ok(())
+    }
 
-**Commit:** `{}`
-**Author:** {}
-**Branch:** {}
+    /// Create and push `tag` at HEAD unless it already exists locally
+    fn ensure_tag(&self, tag: &str, token: Option<&str>) -> Result<()> {
+        let repo = Repository::open(".").context("Failed to open git repository")?;
+        if tagging::tag_exists(&repo, tag) {
+            tracing::info!("Tag {} already exists, not creating it", tag);
+            return Ok(());
+        }
 
-### 📝 Commit Message
-{}
+        let template = self
+            .config
+            .tag
+            .message
+            .as_deref()
+            .unwrap_or(tagging::DEFAULT_TAG_MESSAGE);
+        let message = tagging::render_message(template, tag);
+        tagging::create_tag(&repo, tag, &message, self.args.sign_tag)?;
+        tracing::info!("Created tag {}", tag);
+
+        let remote = self.config.tag.remote.as_deref().unwrap_or("origin");
+        tagging::push_tag(&repo, remote, tag, token)?;
+        tracing::info!("Pushed tag {} to {}", tag, remote);
 
-### ⚠️ Note
-This is an automated development build. Use for testing purposes only.
-For stable releases, please use tagged versions.
+        Ok(())
+    }
 
-### 📦 Installation
-```bash
-# Install all binaries
-cargo ghinstall {}/{}@{}
-{}```
+    /// Add this release to the download page on the pages branch
+    async fn publish_pages(
+        &self,
+        pages_config: &PagesPublisherConfig,
+        owner: &str,
+        repo: &str,
+        manifest: &ReleaseManifest,
+        assets: &[PathBuf],
+        release_url: &str,
+    ) -> Result<()> {
+        let asset_names = assets
+            .iter()
+            .filter_map(|path| path.file_name().and_then(|n| n.to_str()))
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let checksums = match assets
+            .iter()
+            .find(|path| path.file_name().is_some_and(|n| n == "SHA256SUMS"))
+        {
+            Some(path) => incremental::parse_checksums(&fs::read_to_string(path)?),
+            None => Default::default(),
+        };
+        let date = chrono::DateTime::<chrono::Utc>::from(std::time::SystemTime::now())
+            .format("%Y-%m-%d")
+            .to_string();
+        let release = PageRelease::new(
+            manifest,
+            &asset_names,
+            &checksums,
+            &format!(
+                "https://github.com/{owner}/{repo}/releases/download/{}",
+                manifest.tag
+            ),
+            release_url,
+            &date,
+        );
 
-### 🔗 Links
-- [Commit](https://github.com/{}/{}/commit/{})
-"#,
-                sha,
-                author_name,
-                branch,
-                message.trim(),
-                owner,
-                repo_name,
-                tag,
-                binary_install_commands,
-                owner,
-                repo_name,
-                sha
-            )
-        } else {
-            format!(
-                r#"## 🎉 Release {}
+        let remote = self.config.tag.remote.as_deref().unwrap_or("origin");
+        let title = pages_config.title.as_deref().unwrap_or(repo);
+        pages::publish(
+            pages_config,
+            remote,
+            title,
+            release,
+            self.github_client
+                .optional_token(owner, repo)
+                .await?
+                .as_deref(),
+        )?;
+        tracing::info!(
+            "Published the download page to the {} branch",
+            pages_config.branch()
+        );
+        Ok(())
+    }
 
-**Commit:** `{}`
-**Author:** {}
+    /// Delete continuous releases and their tags that fall outside `[retention]`
+    async fn prune_continuous_releases(&self, owner: &str, repo: &str, tag: &str) -> Result<()> {
+        let releases = self
+            .github_client
+            .list_releases(owner, repo)
+            .await?
+            .into_iter()
+            .map(|release| ReleaseEntry {
+                id: release.id.0,
+                tag: release.tag_name,
+                created_at: release.created_at,
+                marked: release
+                    .body
+                    .is_some_and(|body| body.contains(retention::CONTINUOUS_MARKER)),
+            })
+            .collect::<Vec<_>>();
 
-### 📦 Installation
-```bash
-# Install all binaries
-cargo ghinstall {}/{}@{}
-{}
-# Or download directly from the release assets
-```
+        let now = chrono::DateTime::from(std::time::SystemTime::now());
+        let prefix = self.package.is_some().then(|| self.tag_prefix());
+        let pruned = retention::select_for_pruning(
+            &releases,
+            tag,
+            prefix.as_deref(),
+            &self.config.retention,
+            now,
+        );
+        if pruned.is_empty() {
+            tracing::info!("No continuous releases to prune");
+        }
 
-### 🔗 Links
-- [Commit](https://github.com/{}/{}/commit/{})
-- [Compare](https://github.com/{}/{}/compare/{}...{})
-"#,
-                tag,
-                sha,
-                author_name,
-                owner,
-                repo_name,
-                tag,
-                binary_install_commands,
-                owner,
-                repo_name,
-                sha,
-                owner,
-                repo_name,
-                self.find_previous_tag(tag)
-                    .unwrap_or_else(|_| "main".to_string()),
-                tag
-            )
-        };
+        for release in pruned {
+            tracing::info!("Pruning continuous release: {}", release.tag);
+            self.github_client
+                .delete_release(owner, repo, release.id)
+                .await?;
+            self.github_client
+                .delete_tag(owner, repo, &release.tag)
+                .await?;
+        }
 
-        Ok(notes)
+        Ok(())
+    }
+
+    /// Fetch `dist-manifest.json` from the previous release, if there is one
+    async fn previous_manifest(
+        &self,
+        tag: &str,
+        owner: &str,
+        repo: &str,
+    ) -> Option<ReleaseManifest> {
+        let previous_tag = self.find_previous_tag(tag).ok()?;
+        if previous_tag == "main" || previous_tag == tag {
+            return None;
+        }
+
+        match self
+            .github_client
+            .download_release_asset(owner, repo, &previous_tag, MANIFEST_FILE_NAME)
+            .await
+            .and_then(|bytes| bytes.map(|b| ReleaseManifest::from_json(&b)).transpose())
+        {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                tracing::warn!("Failed to fetch manifest of {}: {}", previous_tag, e);
+                None
+            }
+        }
     }
 
     /// Find the previous tag for comparison
+    ///
+    /// Package releases only consider tags with the package's prefix.
     fn find_previous_tag(&self, current_tag: &str) -> Result<String> {
         let repo = Repository::open(".")?;
         let mut tags = Vec::new();
+        let package_prefix = self.package.is_some().then(|| self.tag_prefix());
 
         repo.tag_foreach(|_oid, name| {
             if let Some(tag_name) = name.strip_prefix(b"refs/tags/") {
                 if let Ok(tag_str) = std::str::from_utf8(tag_name) {
+                    if package_prefix
+                        .as_deref()
+                        .is_some_and(|prefix| !tag_str.starts_with(prefix))
+                    {
+                        return true;
+                    }
                     if let Ok(tag_obj) = repo.revparse_single(tag_str) {
                         if let Ok(commit) = tag_obj.peel_to_commit() {
                             tags.push((commit.time().seconds(), tag_str.to_string()));
@@ -536,8 +2606,13 @@ cargo ghinstall {}/{}@{}
 
         // Look for tags pointing to HEAD
         let tags = repo.tag_names(None).context("Failed to get git tags")?;
+        let package_prefix = self.package.is_some().then(|| self.tag_prefix());
 
-        for tag in tags.iter().flatten() {
+        for tag in tags.iter().flatten().filter(|tag| {
+            package_prefix
+                .as_deref()
+                .is_none_or(|prefix| tag.starts_with(prefix))
+        }) {
             if let Ok(tag_obj) = repo.revparse_single(tag) {
                 if tag_obj.id() == oid {
                     return Ok(tag.to_string());
@@ -551,7 +2626,10 @@ cargo ghinstall {}/{}@{}
 
             // Read version from Cargo.toml
             let version = self.get_package_version()?;
-            let tag = format!("{version}-{short_sha}");
+            let tag = match &package_prefix {
+                Some(prefix) => format!("{prefix}{version}-{short_sha}"),
+                None => format!("{version}-{short_sha}"),
+            };
 
             tracing::info!(
                 "No tag found on HEAD. Using version-sha format as tag: {}",
@@ -567,14 +2645,64 @@ cargo ghinstall {}/{}@{}
         }
     }
 
-    /// Build binaries for a specific target
-    async fn build_for_target(&self, target: &str) -> GhResult<Vec<PathBuf>> {
+    /// Build `target`, returning its binaries and its cdylib/staticlib outputs
+    async fn build_for_target(
+        &self,
+        target: &str,
+        cache: Option<&CompileCache>,
+    ) -> GhResult<(Vec<PathBuf>, Vec<PathBuf>)> {
+        let target_config = self.config.target.get(target);
+
         let mut cmd = Command::new("cargo");
+        if let Some(toolchain) = self.config.toolchain_for(target) {
+            cmd.arg(format!("+{toolchain}"));
+        }
+        let container_image = self.config.container_image_for(target);
+        let project = std::env::current_dir()?;
+        // Inside a container only the project is mounted, so the target directory must be in it
+        let build_target_dir = match container_image {
+            Some(_) => self
+                .target_dir
+                .strip_prefix(&project)
+                .map(Path::to_path_buf)
+                .or_else(|_| {
+                    if self.target_dir.is_relative() {
+                        Ok(self.target_dir.clone())
+                    } else {
+                        Err(GhDistError::Config(format!(
+                            "Container builds need the target directory inside the project, not {}",
+                            self.target_dir.display()
+                        )))
+                    }
+                })?,
+            None => self.target_dir.clone(),
+        };
         cmd.arg("build")
             .arg("--target")
             .arg(target)
             .arg("--target-dir")
-            .arg("target");
+            .arg(&build_target_dir);
+        if self.args.quiet {
+            cmd.arg("--quiet");
+        }
+
+        if self.args.workspace {
+            cmd.arg("--workspace");
+            for excluded in &self.args.exclude {
+                cmd.arg("--exclude").arg(excluded);
+            }
+        } else {
+            for package in &self.args.package {
+                cmd.arg("--package").arg(package);
+            }
+        }
+
+        cmd.args(self.feature_args(target));
+        if let Some(target_config) = target_config {
+            cmd.args(target_config.build_std_args());
+            let rustflags = std::env::var("RUSTFLAGS").ok();
+            cmd.envs(target_config.build_env(target, rustflags.as_deref()));
+        }
 
         // Add profile
         let profile = self.args.profile();
@@ -591,9 +2719,45 @@ cargo ghinstall {}/{}@{}
             }
         }
 
-        let status = cmd.status().map_err(|_| GhDistError::BuildFailed {
-            target: target.to_string(),
-        })?;
+        // Selecting an example would otherwise build only the examples
+        let examples = &self.config.default.examples;
+        if !examples.is_empty() {
+            if self.args.bins.is_none() {
+                cmd.arg("--bins");
+            }
+            for example in examples {
+                cmd.arg("--example").arg(example);
+            }
+        }
+
+        // Selecting binaries or examples would leave out the native libraries
+        let native_libraries = self.native_libraries();
+        if !native_libraries.is_empty() && (self.args.bins.is_some() || !examples.is_empty()) {
+            cmd.arg("--lib");
+        }
+
+        // The wrapper is installed on this machine, not in the build container
+        if let (Some(cache), None) = (cache, container_image) {
+            cmd.envs(cache.envs());
+        }
+
+        if let Some(image) = container_image {
+            tracing::info!("Building {} in container image {}", target, image);
+            cmd = container::wrap(
+                &self.config.container,
+                image,
+                &project,
+                container::project_owner(&project),
+                &cmd,
+            );
+        }
+
+        let status =
+            cmd.stdout(ui::child_stdout())
+                .status()
+                .map_err(|_| GhDistError::BuildFailed {
+                    target: target.to_string(),
+                })?;
 
         if !status.success() {
             return Err(GhDistError::BuildFailed {
@@ -603,30 +2767,104 @@ cargo ghinstall {}/{}@{}
 
         // Find built binaries
         let target_dir = self.get_target_dir(target);
-        let binaries = self
-            .find_binaries(&target_dir)
-            .map_err(|_| GhDistError::BuildFailed {
-                target: target.to_string(),
-            })?;
-
-        if binaries.is_empty() {
+        let mut binaries =
+            self.find_binaries(&target_dir)
+                .map_err(|_| GhDistError::BuildFailed {
+                    target: target.to_string(),
+                })?;
+        // Shared libraries are executable on Unix too
+        let libraries = library::find(&target_dir, &native_libraries, target);
+        binaries.retain(|binary| !libraries.contains(binary));
+
+        if binaries.is_empty() && libraries.is_empty() {
             return Err(GhDistError::BuildFailed {
                 target: format!("{target} (no binaries found)"),
             });
         }
 
-        Ok(binaries)
+        Ok((binaries, libraries))
+    }
+
+    /// `<asset>.sha256` files for the assets, when `checksum_sidecars` is set
+    fn sidecar_checksums(&self, assets: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        if !self.config.default.checksum_sidecars {
+            return Ok(Vec::new());
+        }
+        packager::generate_sidecar_checksums(assets)
+    }
+
+    /// Settings for the release of this run, with `body` as its notes
+    fn release_options<'a>(
+        &'a self,
+        target_commitish: Option<&'a str>,
+        body: &'a str,
+    ) -> ReleaseOptions<'a> {
+        ReleaseOptions {
+            draft: self.args.draft,
+            prerelease: self.args.prerelease,
+            target_commitish,
+            body: Some(body),
+            make_latest: self.args.latest(),
+            discussion_category: self.args.discussion_category.as_deref(),
+        }
+    }
+
+    /// The client that publishes releases to `provider`
+    fn release_provider(&self, provider: Provider) -> Result<&dyn ReleaseProvider> {
+        Ok(match provider {
+            Provider::Github => &self.github_client,
+            Provider::Gitlab => self
+                .gitlab_client
+                .as_ref()
+                .context("GitLab client is not configured")?,
+        })
+    }
+
+    /// cdylib and staticlib targets of the packages being built
+    fn native_libraries(&self) -> Vec<NativeLibrary> {
+        if !self.selected.is_empty() {
+            return library::detect(&self.selected);
+        }
+        workspace::workspace_packages()
+            .map(|packages| library::detect(&packages))
+            .unwrap_or_default()
+    }
+
+    /// Cargo feature flags for `target`, applying its `[target."<triple>"]` overrides
+    fn feature_args(&self, target: &str) -> Vec<String> {
+        let target_config = self.config.target.get(target);
+
+        let mut features = self.args.features.clone().unwrap_or_default();
+        for feature in target_config.into_iter().flat_map(|t| &t.features) {
+            if !features.contains(feature) {
+                features.push(feature.clone());
+            }
+        }
+        let all_features = target_config
+            .and_then(|t| t.all_features)
+            .unwrap_or(self.args.all_features);
+        let no_default_features = target_config
+            .and_then(|t| t.no_default_features)
+            .unwrap_or(self.args.no_default_features);
+
+        let mut args = Vec::new();
+        if all_features {
+            args.push("--all-features".to_string());
+        } else if !features.is_empty() {
+            args.push("--features".to_string());
+            args.push(features.join(","));
+        }
+        if no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+        args
     }
 
     /// Get the target directory for built binaries
     fn get_target_dir(&self, target: &str) -> PathBuf {
-        let profile = if self.args.profile() == "release" {
-            "release"
-        } else {
-            self.args.profile()
-        };
-
-        PathBuf::from("target").join(target).join(profile)
+        self.target_dir
+            .join(target)
+            .join(profile_dir(self.args.profile()))
     }
 
     /// Find binary files in a directory
@@ -662,15 +2900,48 @@ cargo ghinstall {}/{}@{}
                         continue;
                     }
                 }
+                if self.is_excluded_bin(file_name) {
+                    continue;
+                }
                 binaries.push(path);
             }
         }
 
+        // Examples land in examples/, next to copies with a hash suffix
+        let examples = &self.config.default.examples;
+        let examples_dir = dir.join("examples");
+        if !examples.is_empty() && examples_dir.is_dir() {
+            for entry in fs::read_dir(&examples_dir)? {
+                let path = entry?.path();
+                let file_name = path.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+                if examples.iter().any(|e| e == file_name)
+                    && path.is_file()
+                    && self.is_binary(&path)?
+                {
+                    binaries.push(path);
+                }
+            }
+        }
+
         Ok(binaries)
     }
 
+    /// Whether `exclude_bins` keeps a binary out of the release
+    fn is_excluded_bin(&self, name: &str) -> bool {
+        self.config
+            .default
+            .exclude_bins
+            .iter()
+            .any(|pattern| utils::glob_match(pattern, name))
+    }
+
     /// Check if a file is a binary executable
     fn is_binary(&self, path: &Path) -> Result<bool> {
+        // WebAssembly modules carry no executable bit or .exe extension
+        if wasm::is_wasm_file(path) {
+            return Ok(true);
+        }
+
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
@@ -690,17 +2961,26 @@ cargo ghinstall {}/{}@{}
         }
     }
 
-    /// Run cargo publish
-    fn run_cargo_publish(&self) -> Result<()> {
+    /// Run cargo publish for the selected (or all publishable) workspace members
+    async fn run_cargo_publish(&self) -> Result<()> {
         tracing::info!("Running cargo publish");
+        self.ui.step("Publishing to crates.io");
 
-        let status = Command::new("cargo").arg("publish").status()?;
+        publish::publish_workspace(&self.selected_names(), &self.publish_options())
+            .await
+            .context("cargo publish failed")
+    }
 
-        if !status.success() {
-            tracing::warn!("cargo publish failed");
-        }
+    fn selected_names(&self) -> Vec<String> {
+        self.selected.iter().map(|p| p.name.clone()).collect()
+    }
 
-        Ok(())
+    fn publish_options(&self) -> PublishOptions {
+        PublishOptions {
+            token: self.args.registry_token.clone(),
+            no_verify: self.args.no_verify,
+            allow_dirty: self.args.allow_dirty,
+        }
     }
 
     /// Check if we should continue on build errors
@@ -713,6 +2993,7 @@ cargo ghinstall {}/{}@{}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::TargetConfig;
     use std::sync::{Mutex, MutexGuard};
     use tempfile::tempdir;
 
@@ -757,6 +3038,42 @@ mod tests {
             github_token: None,
             bins: None,
             profile: None,
+            packages: None,
+            changelog_file: None,
+            require_changelog: false,
+            latest: None,
+            discussion_category: None,
+            no_prune: false,
+            create_tag: false,
+            sign_tag: false,
+            allow_dirty: false,
+            allow_branch: false,
+            no_verify_version: false,
+            run_tests: false,
+            package: Vec::new(),
+            workspace: false,
+            exclude: Vec::new(),
+            registry_token: None,
+            no_verify: false,
+            features: None,
+            no_default_features: false,
+            all_features: false,
+            from_artifacts: None,
+            force: false,
+            resume: false,
+            no_upload: false,
+            no_progress: false,
+            provider: None,
+            app_id: None,
+            app_private_key: None,
+            app_installation_id: None,
+            profile_name: None,
+            prerelease: false,
+            target_dir: None,
+            output_dir: None,
+            switches: Default::default(),
+            quiet: false,
+            log_format: crate::cli::LogFormat::Text,
         }
     }
 
@@ -768,6 +3085,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_profile_dir() {
+        assert_eq!(profile_dir("dev"), "debug");
+        assert_eq!(profile_dir("test"), "debug");
+        assert_eq!(profile_dir("release"), "release");
+        assert_eq!(profile_dir("bench"), "release");
+        assert_eq!(profile_dir("dist"), "dist");
+    }
+
     #[test]
     fn test_select_previous_tag_uses_tag_time() {
         let tags = vec![
@@ -1112,6 +3438,12 @@ edition = "2021"
             args: default_test_args(),
             config: Config::default(),
             github_client: GitHubClient::new(None).unwrap(),
+            gitlab_client: None,
+            package: None,
+            selected: Vec::new(),
+            target_dir: PathBuf::from("target"),
+            output_dir: PathBuf::from("target/dist"),
+            ui: Ui::new(UiMode::Quiet),
         };
 
         let binaries = builder.find_binaries(&target_dir).unwrap();
@@ -1123,6 +3455,97 @@ edition = "2021"
         assert_eq!(binary_names, vec!["current"]);
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_find_binaries_excludes_and_adds_examples() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let _cwd_lock = lock_current_dir();
+        let temp_dir = tempdir().unwrap();
+        let _cwd_guard = CurrentDirGuard::change_to(temp_dir.path());
+
+        fs::create_dir_all("src/bin").unwrap();
+        fs::write("src/main.rs", "fn main() {}").unwrap();
+        fs::write("src/bin/xtask.rs", "fn main() {}").unwrap();
+        fs::write(
+            "Cargo.toml",
+            "[package]\nname = \"tool\"\nversion = \"1.0.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+
+        let target_dir = temp_dir.path().join("target-bin");
+        fs::create_dir_all(target_dir.join("examples")).unwrap();
+        for name in [
+            "tool",
+            "xtask",
+            "examples/demo",
+            "examples/demo-0123abcd",
+            "examples/other",
+        ] {
+            let path = target_dir.join(name);
+            fs::write(&path, name).unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        fs::write(target_dir.join("examples/demo.d"), "deps").unwrap();
+
+        let mut config = Config::default();
+        config.default.exclude_bins = vec!["x*".to_string()];
+        config.default.examples = vec!["demo".to_string()];
+        let builder = DistBuilder {
+            args: default_test_args(),
+            config,
+            github_client: GitHubClient::new(None).unwrap(),
+            gitlab_client: None,
+            package: None,
+            selected: Vec::new(),
+            target_dir: PathBuf::from("target"),
+            output_dir: PathBuf::from("target/dist"),
+            ui: Ui::new(UiMode::Quiet),
+        };
+
+        let binaries = builder.find_binaries(&target_dir).unwrap();
+        assert_eq!(
+            binaries,
+            vec![target_dir.join("tool"), target_dir.join("examples/demo")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_binaries_finds_wasm_modules() {
+        let _cwd_lock = lock_current_dir();
+        let temp_dir = tempdir().unwrap();
+        let _cwd_guard = CurrentDirGuard::change_to(temp_dir.path());
+
+        fs::create_dir("src").unwrap();
+        fs::write("src/main.rs", "fn main() {}").unwrap();
+        fs::write(
+            "Cargo.toml",
+            "[package]\nname = \"tool\"\nversion = \"1.0.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+
+        // cargo leaves .wasm outputs without the executable bit
+        let target_dir = temp_dir.path().join("wasm32-wasip1");
+        fs::create_dir(&target_dir).unwrap();
+        fs::write(target_dir.join("tool.wasm"), b"\0asm").unwrap();
+        fs::write(target_dir.join("tool.d"), b"deps").unwrap();
+
+        let builder = DistBuilder {
+            args: default_test_args(),
+            config: Config::default(),
+            github_client: GitHubClient::new(None).unwrap(),
+            gitlab_client: None,
+            package: None,
+            selected: Vec::new(),
+            target_dir: PathBuf::from("target"),
+            output_dir: PathBuf::from("target/dist"),
+            ui: Ui::new(UiMode::Quiet),
+        };
+
+        let binaries = builder.find_binaries(&target_dir).unwrap();
+        assert_eq!(binaries, vec![target_dir.join("tool.wasm")]);
+    }
+
     #[test]
     fn test_get_tag_without_hash_option_fails() {
         let _cwd_lock = lock_current_dir();
@@ -1181,4 +3604,55 @@ version = "1.0.0""#,
         // Should not find any tags
         assert!(found_tag.is_none());
     }
+
+    #[tokio::test]
+    async fn test_feature_args() {
+        let mut config = Config::default();
+        config.target.insert(
+            "x86_64-unknown-linux-musl".to_string(),
+            TargetConfig {
+                features: vec!["vendored-openssl".to_string(), "cli".to_string()],
+                no_default_features: Some(true),
+                ..Default::default()
+            },
+        );
+        config.target.insert(
+            "aarch64-apple-darwin".to_string(),
+            TargetConfig {
+                all_features: Some(true),
+                ..Default::default()
+            },
+        );
+
+        let mut args = default_test_args();
+        args.features = Some(vec!["cli".to_string()]);
+        let builder = DistBuilder {
+            args,
+            config,
+            github_client: GitHubClient::new(None).unwrap(),
+            gitlab_client: None,
+            package: None,
+            selected: Vec::new(),
+            target_dir: PathBuf::from("target"),
+            output_dir: PathBuf::from("target/dist"),
+            ui: Ui::new(UiMode::Quiet),
+        };
+
+        assert_eq!(
+            builder.feature_args("x86_64-unknown-linux-gnu"),
+            vec!["--features", "cli"]
+        );
+        assert_eq!(
+            builder.feature_args("x86_64-unknown-linux-musl"),
+            vec![
+                "--features",
+                "cli,vendored-openssl",
+                "--no-default-features"
+            ]
+        );
+        assert_eq!(
+            builder.feature_args("aarch64-apple-darwin"),
+            vec!["--all-features"]
+        );
+    }
 }
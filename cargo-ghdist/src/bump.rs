@@ -0,0 +1,516 @@
+use anyhow::{Context, Result};
+use git2::Repository;
+use semver::{Prerelease, Version};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Value};
+
+use crate::changelog;
+use crate::cli::{Args, BumpLevel};
+use crate::config::Config;
+use crate::tagging;
+
+/// Compute the next version for `level`
+///
+/// Bumping a pre-release to the level it is a pre-release of drops the
+/// pre-release part (e.g. `2.0.0-rc.1` → `2.0.0` for major). `Pre` increments
+/// an existing `<pre_id>.N` suffix or starts `<pre_id>.1` on the next patch.
+pub fn next_version(current: &Version, level: BumpLevel, pre_id: &str) -> Result<Version> {
+    let mut next = current.clone();
+    let is_pre = !current.pre.is_empty();
+    next.build = semver::BuildMetadata::EMPTY;
+    next.pre = Prerelease::EMPTY;
+
+    match level {
+        BumpLevel::Major if is_pre && current.minor == 0 && current.patch == 0 => {}
+        BumpLevel::Major => {
+            next.major += 1;
+            next.minor = 0;
+            next.patch = 0;
+        }
+        BumpLevel::Minor if is_pre && current.patch == 0 => {}
+        BumpLevel::Minor => {
+            next.minor += 1;
+            next.patch = 0;
+        }
+        BumpLevel::Patch if is_pre => {}
+        BumpLevel::Patch => next.patch += 1,
+        BumpLevel::Pre => {
+            let number = current
+                .pre
+                .as_str()
+                .strip_prefix(pre_id)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .and_then(|n| n.parse::<u64>().ok());
+            let pre = match number {
+                Some(n) => format!("{pre_id}.{}", n + 1),
+                None => {
+                    if !is_pre {
+                        next.patch += 1;
+                    }
+                    format!("{pre_id}.1")
+                }
+            };
+            next.pre = Prerelease::new(&pre)?;
+        }
+    }
+
+    Ok(next)
+}
+
+/// Replace a string value while keeping its surrounding formatting
+fn set_string(item: &mut Item, value: &str) {
+    if let Some(existing) = item.as_value_mut() {
+        let decor = existing.decor().clone();
+        *existing = Value::from(value);
+        *existing.decor_mut() = decor;
+    }
+}
+
+/// Update version requirements on workspace members in a dependency table
+fn update_dependency_table(table: &mut Item, members: &HashSet<String>, old: &str, new: &str) {
+    let Some(table) = table.as_table_like_mut() else {
+        return;
+    };
+
+    for (name, dependency) in table.iter_mut() {
+        if !members.contains(name.get()) {
+            continue;
+        }
+        let Some(dependency) = dependency.as_table_like_mut() else {
+            continue;
+        };
+        if let Some(version) = dependency.get_mut("version") {
+            if let Some(requirement) = version.as_str().map(|r| r.replace(old, new)) {
+                set_string(version, &requirement);
+            }
+        }
+    }
+}
+
+/// Rewrite one Cargo.toml: package/workspace versions and member dependency requirements
+pub fn update_manifest(
+    content: &str,
+    members: &HashSet<String>,
+    old: &str,
+    new: &str,
+) -> Result<String> {
+    let mut doc = content.parse::<DocumentMut>()?;
+
+    if let Some(version) = doc
+        .get_mut("package")
+        .and_then(|package| package.get_mut("version"))
+        .filter(|version| version.is_str())
+    {
+        set_string(version, new);
+    }
+
+    if let Some(workspace) = doc.get_mut("workspace") {
+        if let Some(version) = workspace
+            .get_mut("package")
+            .and_then(|package| package.get_mut("version"))
+        {
+            set_string(version, new);
+        }
+        if let Some(dependencies) = workspace.get_mut("dependencies") {
+            update_dependency_table(dependencies, members, old, new);
+        }
+    }
+
+    for key in ["dependencies", "dev-dependencies", "build-dependencies"] {
+        if let Some(dependencies) = doc.get_mut(key) {
+            update_dependency_table(dependencies, members, old, new);
+        }
+    }
+
+    Ok(doc.to_string())
+}
+
+/// Set the version of workspace packages (entries without a `source`) in Cargo.lock
+pub fn update_lockfile(content: &str, members: &HashSet<String>, new: &str) -> Result<String> {
+    let mut doc = content.parse::<DocumentMut>()?;
+
+    if let Some(packages) = doc
+        .get_mut("package")
+        .and_then(|p| p.as_array_of_tables_mut())
+    {
+        for package in packages.iter_mut() {
+            let is_member = package
+                .get("name")
+                .and_then(|n| n.as_str())
+                .is_some_and(|name| members.contains(name));
+            if is_member && !package.contains_key("source") {
+                if let Some(version) = package.get_mut("version") {
+                    set_string(version, new);
+                }
+            }
+        }
+    }
+
+    Ok(doc.to_string())
+}
+
+/// Member directories listed in `[workspace] members`, expanding trailing `/*` globs
+pub fn member_dirs(root: &Path, doc: &DocumentMut) -> Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    let members = doc
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array());
+
+    for member in members.into_iter().flatten().filter_map(|m| m.as_str()) {
+        match member.strip_suffix("/*") {
+            Some(parent) => {
+                for entry in fs::read_dir(root.join(parent))? {
+                    let path = entry?.path();
+                    if path.join("Cargo.toml").exists() {
+                        dirs.push(path);
+                    }
+                }
+            }
+            None => dirs.push(root.join(member)),
+        }
+    }
+
+    dirs.sort();
+    Ok(dirs)
+}
+
+fn manifest_version(doc: &DocumentMut) -> Option<&str> {
+    doc.get("workspace")
+        .and_then(|w| w.get("package"))
+        .and_then(|p| p.get("version"))
+        .or_else(|| doc.get("package").and_then(|p| p.get("version")))
+        .and_then(|v| v.as_str())
+}
+
+/// Result of bumping the versions in a workspace
+#[derive(Debug)]
+pub struct BumpOutcome {
+    pub previous: Version,
+    pub version: Version,
+    pub files: Vec<PathBuf>,
+}
+
+/// Bump the root package or workspace version and everything that refers to it
+pub fn bump_workspace(root: &Path, level: BumpLevel, pre_id: &str) -> Result<BumpOutcome> {
+    let root_manifest = root.join("Cargo.toml");
+    let root_content = fs::read_to_string(&root_manifest).context("Failed to read Cargo.toml")?;
+    let root_doc = root_content.parse::<DocumentMut>()?;
+
+    let previous = manifest_version(&root_doc)
+        .context("No version found in [package] or [workspace.package] of Cargo.toml")?;
+    let previous = Version::parse(previous)
+        .with_context(|| format!("Version {previous} is not valid SemVer"))?;
+    let version = next_version(&previous, level, pre_id)?;
+    let (old, new) = (previous.to_string(), version.to_string());
+
+    let mut manifests = vec![root_manifest];
+    manifests.extend(
+        member_dirs(root, &root_doc)?
+            .into_iter()
+            .map(|dir| dir.join("Cargo.toml"))
+            .filter(|path| path.exists() && *path != root.join("Cargo.toml")),
+    );
+
+    let mut members = HashSet::new();
+    for manifest in &manifests {
+        let doc = fs::read_to_string(manifest)?.parse::<DocumentMut>()?;
+        if let Some(name) = doc
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+        {
+            members.insert(name.to_string());
+        }
+    }
+
+    let mut files = Vec::new();
+    for manifest in manifests {
+        let content = fs::read_to_string(&manifest)?;
+        let updated = update_manifest(&content, &members, &old, &new)?;
+        if updated != content {
+            fs::write(&manifest, updated)?;
+            files.push(manifest);
+        }
+    }
+
+    let lockfile = root.join("Cargo.lock");
+    if lockfile.exists() {
+        let content = fs::read_to_string(&lockfile)?;
+        let updated = update_lockfile(&content, &members, &new)?;
+        if updated != content {
+            fs::write(&lockfile, updated)?;
+            files.push(lockfile);
+        }
+    }
+
+    Ok(BumpOutcome {
+        previous,
+        version,
+        files,
+    })
+}
+
+/// Commit the changed files (skipping git-ignored ones such as an ignored Cargo.lock)
+pub fn commit_files(repo: &Repository, files: &[PathBuf], message: &str) -> Result<git2::Oid> {
+    let workdir = repo
+        .workdir()
+        .context("Cannot commit in a bare repository")?
+        .canonicalize()?;
+
+    let mut index = repo.index()?;
+    for file in files {
+        let relative = file.canonicalize()?;
+        let relative = relative.strip_prefix(&workdir)?;
+        if !repo.is_path_ignored(relative)? {
+            index.add_path(relative)?;
+        }
+    }
+    index.write()?;
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = repo
+        .signature()
+        .context("Set user.name and user.email to commit")?;
+    let parent = repo.head()?.peel_to_commit()?;
+
+    Ok(repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &[&parent],
+    )?)
+}
+
+/// `cargo ghdist bump`: bump versions, commit, and optionally tag
+pub struct Bump {
+    args: Args,
+    config: Config,
+    level: BumpLevel,
+    pre_id: String,
+    no_commit: bool,
+    changelog: bool,
+}
+
+impl Bump {
+    pub fn new(
+        mut args: Args,
+        level: BumpLevel,
+        pre_id: String,
+        no_commit: bool,
+        changelog: bool,
+    ) -> Result<Self> {
+        let config = Config::load_for(&mut args)?;
+
+        Ok(Self {
+            args,
+            config,
+            level,
+            pre_id,
+            no_commit,
+            changelog,
+        })
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let mut outcome = bump_workspace(Path::new("."), self.level, &self.pre_id)?;
+        if self.changelog {
+            outcome.files.push(self.write_changelog(&outcome)?);
+        }
+        println!(
+            "📦 Bumped version {} → {}",
+            outcome.previous, outcome.version
+        );
+        for file in &outcome.files {
+            println!("   updated {}", file.display());
+        }
+
+        if self.no_commit {
+            return Ok(());
+        }
+
+        let repo = Repository::open(".").context("Failed to open git repository")?;
+        let tag = tagging::tag_for_version(self.config.tag.prefix(), &outcome.version);
+        commit_files(&repo, &outcome.files, &format!("chore: release {tag}"))?;
+        println!("📝 Committed release {tag}");
+
+        if self.args.create_tag {
+            let template = self
+                .config
+                .tag
+                .message
+                .as_deref()
+                .unwrap_or(tagging::DEFAULT_TAG_MESSAGE);
+            tagging::create_tag(
+                &repo,
+                &tag,
+                &tagging::render_message(template, &tag),
+                self.args.sign_tag,
+            )?;
+            println!("🏷️  Created tag {tag}");
+        }
+
+        Ok(())
+    }
+
+    /// Prepend the new version's section to the changelog, returning its path
+    fn write_changelog(&self, outcome: &BumpOutcome) -> Result<PathBuf> {
+        let path = self
+            .args
+            .changelog_file()
+            .unwrap_or_else(|| PathBuf::from("CHANGELOG.md"));
+        let repo = Repository::open(".").context("Failed to open git repository")?;
+        let previous_tag = tagging::tag_for_version(self.config.tag.prefix(), &outcome.previous);
+        let previous_tag = tagging::tag_exists(&repo, &previous_tag).then_some(previous_tag);
+
+        let commits = changelog::collect_commits(&repo, previous_tag.as_deref(), None)?;
+        let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let entry = changelog::render_entry(&commits, &outcome.version.to_string(), &date);
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        fs::write(&path, changelog::insert_entry(&content, &entry))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn bump(version: &str, level: BumpLevel) -> String {
+        next_version(&Version::parse(version).unwrap(), level, "rc")
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_next_version() {
+        assert_eq!(bump("1.2.3", BumpLevel::Major), "2.0.0");
+        assert_eq!(bump("1.2.3", BumpLevel::Minor), "1.3.0");
+        assert_eq!(bump("1.2.3", BumpLevel::Patch), "1.2.4");
+        assert_eq!(bump("1.2.3", BumpLevel::Pre), "1.2.4-rc.1");
+        assert_eq!(bump("1.2.4-rc.1", BumpLevel::Pre), "1.2.4-rc.2");
+        assert_eq!(bump("1.2.4-beta", BumpLevel::Pre), "1.2.4-rc.1");
+        assert_eq!(bump("1.2.4-rc.2", BumpLevel::Patch), "1.2.4");
+        assert_eq!(bump("2.0.0-rc.1", BumpLevel::Major), "2.0.0");
+        assert_eq!(bump("1.3.0-rc.1", BumpLevel::Minor), "1.3.0");
+        assert_eq!(bump("1.3.1-rc.1", BumpLevel::Minor), "1.4.0");
+        assert_eq!(bump("1.2.3+build.5", BumpLevel::Patch), "1.2.4");
+    }
+
+    #[test]
+    fn test_update_manifest_preserves_formatting() {
+        let members = HashSet::from(["core".to_string()]);
+        let content = r#"[package]
+name = "tool"
+version = "0.1.0" # keep this comment
+
+[dependencies]
+core = { path = "../core", version = "=0.1.0" }
+serde = "0.1.0"
+"#;
+
+        let updated = update_manifest(content, &members, "0.1.0", "0.2.0").unwrap();
+        assert!(updated.contains("version = \"0.2.0\" # keep this comment"));
+        assert!(updated.contains("core = { path = \"../core\", version = \"=0.2.0\" }"));
+        assert!(updated.contains("serde = \"0.1.0\""));
+
+        // Inherited versions are left alone
+        let inherited = "[package]\nname = \"tool\"\nversion.workspace = true\n";
+        assert_eq!(
+            update_manifest(inherited, &members, "0.1.0", "0.2.0").unwrap(),
+            inherited
+        );
+    }
+
+    #[test]
+    fn test_bump_workspace() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            r#"[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+version = "0.3.0"
+
+[workspace.dependencies]
+lib-a = { path = "crates/lib-a", version = "0.3.0" }
+"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("crates/lib-a")).unwrap();
+        fs::write(
+            root.join("crates/lib-a/Cargo.toml"),
+            "[package]\nname = \"lib-a\"\nversion.workspace = true\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("Cargo.lock"),
+            r#"version = 3
+
+[[package]]
+name = "lib-a"
+version = "0.3.0"
+
+[[package]]
+name = "serde"
+version = "0.3.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .unwrap();
+
+        let outcome = bump_workspace(root, BumpLevel::Minor, "rc").unwrap();
+        assert_eq!(outcome.previous.to_string(), "0.3.0");
+        assert_eq!(outcome.version.to_string(), "0.4.0");
+        assert_eq!(outcome.files.len(), 2);
+
+        let manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("[workspace.package]\nversion = \"0.4.0\""));
+        assert!(manifest.contains("lib-a = { path = \"crates/lib-a\", version = \"0.4.0\" }"));
+
+        let lockfile = fs::read_to_string(root.join("Cargo.lock")).unwrap();
+        assert!(lockfile.contains("name = \"lib-a\"\nversion = \"0.4.0\""));
+        assert!(lockfile.contains("name = \"serde\"\nversion = \"0.3.0\""));
+    }
+
+    #[test]
+    fn test_commit_files() {
+        let temp_dir = tempdir().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Test User").unwrap();
+            config.set_str("user.email", "test@example.com").unwrap();
+        }
+        fs::write(temp_dir.path().join(".gitignore"), "Cargo.lock\n").unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+        fs::write(temp_dir.path().join("Cargo.lock"), "version = 3\n").unwrap();
+
+        let sig = repo.signature().unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+
+        let files = vec![
+            temp_dir.path().join("Cargo.toml"),
+            temp_dir.path().join("Cargo.lock"),
+        ];
+        commit_files(&repo, &files, "chore: release v0.2.0").unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("chore: release v0.2.0"));
+        let tree = head.tree().unwrap();
+        assert!(tree.get_name("Cargo.toml").is_some());
+        assert!(tree.get_name("Cargo.lock").is_none());
+    }
+}
@@ -0,0 +1,180 @@
+use crate::config::CacheConfig;
+use serde::Serialize;
+use std::fmt;
+use std::process::Command;
+
+const SCCACHE: &str = "sccache";
+
+/// Compiler cache wired into the release builds through RUSTC_WRAPPER
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileCache {
+    wrapper: String,
+    env: Vec<(String, String)>,
+}
+
+/// Cache hits and misses of one run, as sccache counts them
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CompileCache {
+    /// The configured `rustc_wrapper`, or sccache when it is installed and no
+    /// RUSTC_WRAPPER is set already; `None` when `enabled = false`
+    pub fn detect(config: &CacheConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let wrapper = match &config.rustc_wrapper {
+            Some(wrapper) => wrapper.clone(),
+            None if std::env::var_os("RUSTC_WRAPPER").is_some() => return None,
+            None if sccache_installed() => SCCACHE.to_string(),
+            None => return None,
+        };
+        let mut env = config
+            .env
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<Vec<_>>();
+        env.sort();
+        tracing::info!("Caching compilation with {}", wrapper);
+        Some(Self { wrapper, env })
+    }
+
+    fn is_sccache(&self) -> bool {
+        std::path::Path::new(&self.wrapper)
+            .file_stem()
+            .is_some_and(|stem| stem == SCCACHE)
+    }
+
+    /// RUSTC_WRAPPER and the cache's environment for a cargo build
+    pub fn envs(&self) -> Vec<(String, String)> {
+        let mut envs = vec![("RUSTC_WRAPPER".to_string(), self.wrapper.clone())];
+        if self.is_sccache() {
+            // sccache can't cache incremental compilation
+            envs.push(("CARGO_INCREMENTAL".to_string(), "0".to_string()));
+        }
+        envs.extend(self.env.iter().cloned());
+        envs
+    }
+
+    /// Reset sccache's counters so `stats` covers this run only
+    pub fn zero_stats(&self) {
+        if !self.is_sccache() {
+            return;
+        }
+        let zeroed = Command::new(&self.wrapper)
+            .arg("--zero-stats")
+            .envs(self.env.clone())
+            .output();
+        if let Err(e) = zeroed {
+            tracing::warn!("Failed to reset sccache statistics: {}", e);
+        }
+    }
+
+    /// Hits and misses since `zero_stats`; `None` for other wrappers or when
+    /// sccache can't report them
+    pub fn stats(&self) -> Option<CacheStats> {
+        if !self.is_sccache() {
+            return None;
+        }
+        let output = Command::new(&self.wrapper)
+            .args(["--show-stats", "--stats-format", "json"])
+            .envs(self.env.clone())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+        parse_stats(&output.stdout)
+    }
+}
+
+fn sccache_installed() -> bool {
+    Command::new(SCCACHE).arg("--version").output().is_ok()
+}
+
+/// Sum the per-language counts of `sccache --show-stats --stats-format json`
+fn parse_stats(json: &[u8]) -> Option<CacheStats> {
+    let value: serde_json::Value = serde_json::from_slice(json).ok()?;
+    let stats = value.get("stats")?;
+    let total = |key: &str| -> u64 {
+        stats[key]["counts"]
+            .as_object()
+            .map(|counts| counts.values().filter_map(|n| n.as_u64()).sum())
+            .unwrap_or(0)
+    };
+    Some(CacheStats {
+        hits: total("cache_hits"),
+        misses: total("cache_misses"),
+    })
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.hits + self.misses;
+        write!(f, "{} hits, {} misses", self.hits, self.misses)?;
+        if total > 0 {
+            write!(f, " ({}% hit rate)", self.hits * 100 / total)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_configured_wrapper() {
+        let config = CacheConfig {
+            enabled: true,
+            rustc_wrapper: Some("/usr/local/bin/sccache".to_string()),
+            env: HashMap::from([("SCCACHE_DIR".to_string(), "/cache".to_string())]),
+        };
+        let cache = CompileCache::detect(&config).unwrap();
+        assert_eq!(
+            cache.envs(),
+            vec![
+                (
+                    "RUSTC_WRAPPER".to_string(),
+                    "/usr/local/bin/sccache".to_string()
+                ),
+                ("CARGO_INCREMENTAL".to_string(), "0".to_string()),
+                ("SCCACHE_DIR".to_string(), "/cache".to_string()),
+            ]
+        );
+
+        let other = CacheConfig {
+            rustc_wrapper: Some("cachepot".to_string()),
+            ..config.clone()
+        };
+        let cache = CompileCache::detect(&other).unwrap();
+        assert_eq!(cache.envs()[0].1, "cachepot");
+        assert_eq!(cache.stats(), None);
+
+        let disabled = CacheConfig {
+            enabled: false,
+            ..config
+        };
+        assert_eq!(CompileCache::detect(&disabled), None);
+    }
+
+    #[test]
+    fn test_parse_stats() {
+        let json = br#"{"stats":{"compile_requests":30,
+            "cache_hits":{"counts":{"Rust":24,"C/C++":2},"adv_counts":{}},
+            "cache_misses":{"counts":{"Rust":4},"adv_counts":{}}}}"#;
+        let stats = parse_stats(json).unwrap();
+        assert_eq!(
+            stats,
+            CacheStats {
+                hits: 26,
+                misses: 4
+            }
+        );
+        assert_eq!(stats.to_string(), "26 hits, 4 misses (86% hit rate)");
+        assert_eq!(CacheStats::default().to_string(), "0 hits, 0 misses");
+        assert_eq!(parse_stats(b"not json"), None);
+    }
+}
@@ -0,0 +1,405 @@
+use anyhow::Result;
+use git2::{Commit, DiffOptions, Repository, Sort};
+use std::path::Path;
+
+/// Release-note section a conventional commit belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Breaking,
+    Feature,
+    Fix,
+    Performance,
+}
+
+impl ChangeKind {
+    fn heading(&self) -> &'static str {
+        match self {
+            ChangeKind::Breaking => "#### ⚠️ Breaking Changes",
+            ChangeKind::Feature => "#### ✨ Features",
+            ChangeKind::Fix => "#### 🐛 Bug Fixes",
+            ChangeKind::Performance => "#### ⚡ Performance",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    pub kind: ChangeKind,
+    pub scope: Option<String>,
+    pub description: String,
+    pub sha: String,
+}
+
+/// Parse a commit message such as `feat(cli)!: add --foo`; unrelated types return `None`
+pub fn parse_commit(message: &str, sha: &str) -> Option<ConventionalCommit> {
+    let subject = message.lines().next()?.trim();
+    let (prefix, description) = subject.split_once(':')?;
+    let description = description.trim();
+    if description.is_empty() {
+        return None;
+    }
+
+    let (prefix, bang) = match prefix.strip_suffix('!') {
+        Some(prefix) => (prefix, true),
+        None => (prefix, false),
+    };
+    let (commit_type, scope) = match prefix.split_once('(') {
+        Some((commit_type, scope)) => (commit_type, Some(scope.strip_suffix(')')?.to_string())),
+        None => (prefix, None),
+    };
+
+    let breaking = bang
+        || message.lines().any(|line| {
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        });
+
+    let kind = match commit_type.to_ascii_lowercase().as_str() {
+        _ if breaking => ChangeKind::Breaking,
+        "feat" => ChangeKind::Feature,
+        "fix" => ChangeKind::Fix,
+        "perf" => ChangeKind::Performance,
+        _ => return None,
+    };
+
+    Some(ConventionalCommit {
+        kind,
+        scope: scope.filter(|s| !s.is_empty()),
+        description: description.to_string(),
+        sha: sha.to_string(),
+    })
+}
+
+/// Whether `commit` changes anything under `path` (relative to the repository root)
+pub fn touches_path(repo: &Repository, commit: &Commit, path: &Path) -> Result<bool> {
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+
+    let mut options = DiffOptions::new();
+    options.pathspec(path);
+    let diff = repo.diff_tree_to_tree(
+        parent_tree.as_ref(),
+        Some(&commit.tree()?),
+        Some(&mut options),
+    )?;
+    Ok(diff.deltas().len() > 0)
+}
+
+/// Collect conventional commits reachable from HEAD but not from `previous_tag`
+///
+/// With `path`, only commits touching that directory are included.
+pub fn collect_commits(
+    repo: &Repository,
+    previous_tag: Option<&str>,
+    path: Option<&Path>,
+) -> Result<Vec<ConventionalCommit>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push_head()?;
+
+    if let Some(previous_tag) = previous_tag {
+        match repo
+            .revparse_single(previous_tag)
+            .and_then(|obj| obj.peel_to_commit())
+        {
+            Ok(commit) => revwalk.hide(commit.id())?,
+            Err(e) => tracing::warn!("Could not resolve previous tag {}: {}", previous_tag, e),
+        }
+    }
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if let Some(path) = path {
+            if !touches_path(repo, &commit, path)? {
+                continue;
+            }
+        }
+        if let Some(parsed) = parse_commit(commit.message().unwrap_or(""), &commit.id().to_string())
+        {
+            commits.push(parsed);
+        }
+    }
+
+    Ok(commits)
+}
+
+/// Render grouped changelog sections; `None` when there is nothing to report
+pub fn render(commits: &[ConventionalCommit], owner: &str, repo: &str) -> Option<String> {
+    if commits.is_empty() {
+        return None;
+    }
+
+    let mut kinds = commits.iter().map(|c| c.kind).collect::<Vec<_>>();
+    kinds.sort();
+    kinds.dedup();
+
+    let mut changelog = String::from("### 📝 Changelog\n");
+    for kind in kinds {
+        changelog.push_str(&format!("\n{}\n", kind.heading()));
+        for commit in commits.iter().filter(|c| c.kind == kind) {
+            let short_sha = &commit.sha[..commit.sha.len().min(7)];
+            let scope = commit
+                .scope
+                .as_ref()
+                .map(|s| format!("**{s}:** "))
+                .unwrap_or_default();
+            changelog.push_str(&format!(
+                "- {scope}{} ([{short_sha}](https://github.com/{owner}/{repo}/commit/{}))\n",
+                commit.description, commit.sha
+            ));
+        }
+    }
+
+    Some(changelog)
+}
+
+/// A keep-a-changelog section for `version`, e.g. `## [1.2.3] - 2024-01-01`
+pub fn render_entry(commits: &[ConventionalCommit], version: &str, date: &str) -> String {
+    let mut entry = format!("## [{version}] - {date}\n");
+    let mut kinds = commits.iter().map(|c| c.kind).collect::<Vec<_>>();
+    kinds.sort();
+    kinds.dedup();
+    if kinds.is_empty() {
+        entry.push_str("\nNo user-facing changes.\n");
+    }
+    for kind in kinds {
+        entry.push_str(&format!("\n{}\n\n", &kind.heading()[1..]));
+        for commit in commits.iter().filter(|c| c.kind == kind) {
+            let scope = commit
+                .scope
+                .as_ref()
+                .map(|s| format!("**{s}:** "))
+                .unwrap_or_default();
+            entry.push_str(&format!(
+                "- {scope}{} ({})\n",
+                commit.description,
+                &commit.sha[..commit.sha.len().min(7)]
+            ));
+        }
+    }
+    entry
+}
+
+/// Add `entry` above the newest release in a changelog, below any `[Unreleased]` section
+pub fn insert_entry(content: &str, entry: &str) -> String {
+    if content.trim().is_empty() {
+        return format!("# Changelog\n\n{entry}");
+    }
+
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if line.starts_with("## ") && !line[3..].trim_start_matches('[').starts_with("Unreleased") {
+            return format!("{}{entry}\n{}", &content[..offset], &content[offset..]);
+        }
+        offset += line.len();
+    }
+    let separator = if content.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    format!("{content}{separator}{entry}")
+}
+
+/// Extract the keep-a-changelog section for `tag` (e.g. `## [1.2.3] - 2024-01-01`)
+pub fn extract_section(content: &str, tag: &str) -> Option<String> {
+    let version = tag.strip_prefix('v').unwrap_or(tag);
+    let mut section: Option<Vec<&str>> = None;
+
+    for line in content.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            if section.is_some() {
+                break;
+            }
+            let name = heading
+                .trim_start_matches('[')
+                .split([']', ' '])
+                .next()
+                .unwrap_or("");
+            if name == version || name == tag {
+                section = Some(Vec::new());
+            }
+        } else if let Some(lines) = section.as_mut() {
+            // Skip link reference definitions such as `[1.2.3]: https://...`
+            if !(line.starts_with('[') && line.contains("]: ")) {
+                lines.push(line);
+            }
+        }
+    }
+
+    let body = section?.join("\n").trim().to_string();
+    (!body.is_empty()).then_some(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_render_entry() {
+        let commits = vec![
+            parse_commit("fix(github): retry uploads", "1111111111").unwrap(),
+            parse_commit("feat: add deb packages", "2222222222").unwrap(),
+        ];
+        assert_eq!(
+            render_entry(&commits, "1.3.0", "2024-05-01"),
+            "## [1.3.0] - 2024-05-01\n\n### ✨ Features\n\n- add deb packages (2222222)\n\n### 🐛 Bug Fixes\n\n- **github:** retry uploads (1111111)\n"
+        );
+        let entry = render_entry(&[], "1.3.1", "2024-05-02");
+        assert!(entry.ends_with("No user-facing changes.\n"));
+        assert_eq!(
+            extract_section(&insert_entry("", &entry), "v1.3.1").as_deref(),
+            Some("No user-facing changes.")
+        );
+    }
+
+    #[test]
+    fn test_insert_entry() {
+        let entry = "## [1.1.0] - 2024-05-01\n\n- new\n";
+        let content =
+            "# Changelog\n\n## [Unreleased]\n\n- wip\n\n## [1.0.0] - 2024-01-01\n\n- old\n";
+        assert_eq!(
+            insert_entry(content, entry),
+            "# Changelog\n\n## [Unreleased]\n\n- wip\n\n## [1.1.0] - 2024-05-01\n\n- new\n\n## [1.0.0] - 2024-01-01\n\n- old\n"
+        );
+        assert_eq!(
+            insert_entry("# Changelog\n", entry),
+            "# Changelog\n\n## [1.1.0] - 2024-05-01\n\n- new\n"
+        );
+        assert_eq!(
+            insert_entry("", entry),
+            "# Changelog\n\n## [1.1.0] - 2024-05-01\n\n- new\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_commit() {
+        let commit = parse_commit("feat(cli): add --packages flag", "abc").unwrap();
+        assert_eq!(commit.kind, ChangeKind::Feature);
+        assert_eq!(commit.scope.as_deref(), Some("cli"));
+        assert_eq!(commit.description, "add --packages flag");
+
+        assert_eq!(
+            parse_commit("fix: handle empty tags", "abc").unwrap().kind,
+            ChangeKind::Fix
+        );
+        assert_eq!(
+            parse_commit("perf: faster hashing", "abc").unwrap().kind,
+            ChangeKind::Performance
+        );
+        assert_eq!(
+            parse_commit("feat!: drop old config", "abc").unwrap().kind,
+            ChangeKind::Breaking
+        );
+        assert_eq!(
+            parse_commit("refactor: x\n\nBREAKING CHANGE: removed y", "abc")
+                .unwrap()
+                .kind,
+            ChangeKind::Breaking
+        );
+
+        assert!(parse_commit("chore: bump deps", "abc").is_none());
+        assert!(parse_commit("Merge branch 'main'", "abc").is_none());
+        assert!(parse_commit("feat:", "abc").is_none());
+    }
+
+    #[test]
+    fn test_render() {
+        let commits = vec![
+            parse_commit("fix(github): retry uploads", "1111111111").unwrap(),
+            parse_commit("feat: add deb packages", "2222222222").unwrap(),
+            parse_commit("feat!: rename config", "3333333333").unwrap(),
+        ];
+
+        let changelog = render(&commits, "owner", "repo").unwrap();
+        let breaking = changelog.find("Breaking Changes").unwrap();
+        let features = changelog.find("Features").unwrap();
+        let fixes = changelog.find("Bug Fixes").unwrap();
+        assert!(breaking < features && features < fixes);
+        assert!(changelog.contains(
+            "- **github:** retry uploads ([1111111](https://github.com/owner/repo/commit/1111111111))"
+        ));
+        assert!(!changelog.contains("Performance"));
+
+        assert!(render(&[], "owner", "repo").is_none());
+    }
+
+    #[test]
+    fn test_extract_section() {
+        let content = r#"# Changelog
+
+## [Unreleased]
+- Work in progress
+
+## [1.2.0] - 2024-03-01
+### Added
+- Debian packages
+
+### Fixed
+- Upload retries
+
+## [1.1.0] - 2024-01-01
+- Older change
+
+[1.2.0]: https://github.com/owner/repo/compare/v1.1.0...v1.2.0
+"#;
+
+        assert_eq!(
+            extract_section(content, "v1.2.0").unwrap(),
+            "### Added\n- Debian packages\n\n### Fixed\n- Upload retries"
+        );
+        assert_eq!(extract_section(content, "1.1.0").unwrap(), "- Older change");
+        assert!(extract_section(content, "v1.0.0").is_none());
+        assert!(extract_section("## 2.0.0\n\n## 1.0.0\n- x\n", "2.0.0").is_none());
+    }
+
+    #[test]
+    fn test_collect_commits_since_tag() {
+        let temp_dir = tempdir().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+
+        let commit_file = |file: &str, message: &str| {
+            let mut index = repo.index().unwrap();
+            let path = temp_dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, message).unwrap();
+            index.add_path(Path::new(file)).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents = repo
+                .head()
+                .ok()
+                .and_then(|h| h.peel_to_commit().ok())
+                .into_iter()
+                .collect::<Vec<_>>();
+            let parents = parents.iter().collect::<Vec<_>>();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap()
+        };
+        let commit = |message: &str| commit_file("file.txt", message);
+
+        let first = commit("feat: initial feature");
+        repo.tag_lightweight("v0.1.0", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+        commit("fix: after release");
+        commit("docs: readme");
+
+        let commits = collect_commits(&repo, Some("v0.1.0"), None).unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].description, "after release");
+
+        let all = collect_commits(&repo, None, None).unwrap();
+        assert_eq!(all.len(), 2);
+
+        // Package releases only see commits under the package directory
+        commit_file("crates/cli/src/main.rs", "feat(cli): new flag");
+        commit_file("crates/core/src/lib.rs", "fix(core): edge case");
+        let cli = collect_commits(&repo, Some("v0.1.0"), Some(Path::new("crates/cli"))).unwrap();
+        assert_eq!(cli.len(), 1);
+        assert_eq!(cli[0].description, "new flag");
+    }
+}
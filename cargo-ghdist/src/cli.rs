@@ -1,6 +1,10 @@
+use anyhow::Context;
+use clap::builder::BoolishValueParser;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+use crate::github::GitHubApp;
+
 #[derive(Parser, Debug)]
 #[clap(
     name = "cargo-ghdist",
@@ -32,36 +36,75 @@ pub struct GhdistCli {
 
     /// Build targets (comma-separated Rust triple format)
     /// Example: x86_64-unknown-linux-gnu,aarch64-unknown-linux-gnu
-    #[clap(short = 'T', long, value_delimiter = ',', global = true)]
+    #[clap(
+        short = 'T',
+        long,
+        value_delimiter = ',',
+        env = "GHDIST_TARGETS",
+        global = true
+    )]
     pub targets: Option<Vec<String>>,
 
     /// Archive format (tgz or zip)
-    #[clap(short, long, global = true)]
+    #[clap(short, long, env = "GHDIST_FORMAT", global = true)]
     pub format: Option<ArchiveFormat>,
 
     /// Create as draft release
-    #[clap(long, global = true)]
-    pub draft: bool,
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_DRAFT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub draft: Option<bool>,
 
     /// Skip cargo publish step
-    #[clap(long, global = true)]
-    pub skip_publish: bool,
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_SKIP_PUBLISH",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub skip_publish: Option<bool>,
 
     /// Don't generate checksum files (SHA256SUMS)
-    #[clap(long, global = true)]
-    pub no_checksum: bool,
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_NO_CHECKSUM",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub no_checksum: Option<bool>,
 
     /// Configuration file path
-    #[clap(long, default_value = ".config/ghdist.toml", global = true)]
+    #[clap(
+        long,
+        default_value = ".config/ghdist.toml",
+        env = "GHDIST_CONFIG",
+        global = true
+    )]
     pub config: PathBuf,
 
-    /// Enable verbose output
-    #[clap(long, global = true)]
+    /// Enable verbose output: log lines of every step instead of step headers
+    #[clap(long, global = true, conflicts_with = "quiet")]
     pub verbose: bool,
 
+    /// Only print warnings, errors and the results asked for with --output json
+    #[clap(short, long, global = true)]
+    pub quiet: bool,
+
     /// GitHub repository (owner/repo)
     /// If not specified, uses repository from Cargo.toml
-    #[clap(long, global = true)]
+    #[clap(long, env = "GHDIST_REPOSITORY", global = true)]
     pub repository: Option<String>,
 
     /// GitHub token (can also be set via GITHUB_TOKEN env var)
@@ -69,12 +112,234 @@ pub struct GhdistCli {
     pub github_token: Option<String>,
 
     /// Binary names to include (if not specified, includes all)
-    #[clap(long, value_delimiter = ',', global = true)]
+    #[clap(long, value_delimiter = ',', env = "GHDIST_BINS", global = true)]
     pub bins: Option<Vec<String>>,
 
     /// Cargo build profile (release, debug, etc.)
-    #[clap(long, global = true)]
+    #[clap(long, env = "GHDIST_PROFILE", global = true)]
     pub profile: Option<String>,
+
+    /// Additional package formats to build (comma-separated: deb, rpm, msi, nsis)
+    #[clap(long, value_delimiter = ',', env = "GHDIST_PACKAGES", global = true)]
+    pub packages: Option<Vec<PackageFormat>>,
+
+    /// Use the matching section of this changelog (keep-a-changelog format) as the release body
+    #[clap(long, env = "GHDIST_CHANGELOG_FILE", global = true)]
+    pub changelog_file: Option<PathBuf>,
+
+    /// Fail the release if the changelog has no section for the current version
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_REQUIRE_CHANGELOG",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub require_changelog: Option<bool>,
+
+    /// Whether the release becomes the repository's "Latest" release (true, false or legacy)
+    /// Defaults to false for --hash releases
+    #[clap(long, env = "GHDIST_LATEST", global = true)]
+    pub latest: Option<LatestMode>,
+
+    /// Open a discussion for the release in this existing Discussions category
+    #[clap(long, env = "GHDIST_DISCUSSION_CATEGORY", global = true)]
+    pub discussion_category: Option<String>,
+
+    /// Don't delete old continuous releases after a --hash release
+    #[clap(long, global = true)]
+    pub no_prune: bool,
+
+    /// Create an annotated tag at HEAD and push it if the tag does not exist yet
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_CREATE_TAG",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub create_tag: Option<bool>,
+
+    /// GPG-sign the tag created by --create-tag
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_SIGN_TAG",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub sign_tag: Option<bool>,
+
+    /// Release even with uncommitted changes or unpushed commits
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_ALLOW_DIRTY",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub allow_dirty: Option<bool>,
+
+    /// Release even when HEAD is not on an allowed branch
+    #[clap(long, global = true)]
+    pub allow_branch: bool,
+
+    /// Don't check that a version-like tag matches the Cargo.toml version
+    #[clap(long, global = true)]
+    pub no_verify_version: bool,
+
+    /// Run the test suite before building and abort the release if it fails
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_RUN_TESTS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub run_tests: Option<bool>,
+
+    /// Workspace members to build and package (repeatable or comma-separated)
+    /// A single package is released with its own tag prefix (e.g. cli-v1.2.0)
+    #[clap(short, long, value_delimiter = ',', global = true)]
+    pub package: Vec<String>,
+
+    /// Build and package every workspace member
+    #[clap(long, global = true, conflicts_with = "package")]
+    pub workspace: bool,
+
+    /// crates.io token for cargo publish (can also be set via CARGO_REGISTRY_TOKEN env var)
+    #[clap(long, env = "CARGO_REGISTRY_TOKEN", global = true)]
+    pub registry_token: Option<String>,
+
+    /// Pass --no-verify to cargo publish
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_NO_VERIFY",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub no_verify: Option<bool>,
+
+    /// Workspace members to leave out (requires --workspace)
+    #[clap(long, value_delimiter = ',', global = true, requires = "workspace")]
+    pub exclude: Vec<String>,
+
+    /// Cargo features to enable for release builds (comma-separated)
+    #[clap(
+        short = 'F',
+        long,
+        value_delimiter = ',',
+        env = "GHDIST_FEATURES",
+        global = true
+    )]
+    pub features: Option<Vec<String>>,
+
+    /// Do not enable the default feature
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_NO_DEFAULT_FEATURES",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub no_default_features: Option<bool>,
+
+    /// Enable all available features
+    #[clap(
+        long,
+        global = true,
+        env = "GHDIST_ALL_FEATURES",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true",
+        value_parser = BoolishValueParser::new()
+    )]
+    pub all_features: Option<bool>,
+
+    /// Skip building and release prebuilt archives from a GitHub Actions run ID or a local directory
+    #[clap(long, value_name = "RUN_ID|DIR", global = true)]
+    pub from_artifacts: Option<String>,
+
+    /// Rebuild targets whose assets are already on the release
+    #[clap(long, global = true)]
+    pub force: bool,
+
+    /// Only upload assets that are missing or differ from what is already on the release
+    #[clap(long, global = true)]
+    pub resume: bool,
+
+    /// Build and package into <output-dir>/<tag> without any GitHub calls
+    #[clap(long, global = true)]
+    pub no_upload: bool,
+
+    /// Don't show upload progress bars or progress log lines
+    #[clap(long, global = true)]
+    pub no_progress: bool,
+
+    /// Forges to publish the release to (comma-separated: github, gitlab)
+    #[clap(long, value_delimiter = ',', global = true)]
+    pub provider: Option<Vec<Provider>>,
+
+    /// Authenticate as this GitHub App instead of with a token
+    #[clap(long, env = "GITHUB_APP_ID", global = true)]
+    pub app_id: Option<u64>,
+
+    /// PEM private key of the GitHub App (or set GITHUB_APP_PRIVATE_KEY to the key itself)
+    #[clap(long, env = "GITHUB_APP_PRIVATE_KEY_PATH", global = true)]
+    pub app_private_key: Option<PathBuf>,
+
+    /// Installation of the GitHub App to use (default: looked up from the repository)
+    #[clap(long, env = "GITHUB_APP_INSTALLATION_ID", global = true)]
+    pub app_installation_id: Option<u64>,
+
+    /// Apply the settings of [profile.<NAME>] in the config file
+    #[clap(long, env = "GHDIST_PROFILE_NAME", global = true)]
+    pub profile_name: Option<String>,
+
+    /// Cargo target directory (default: from `cargo metadata`, honouring CARGO_TARGET_DIR)
+    #[clap(long, global = true)]
+    pub target_dir: Option<PathBuf>,
+
+    /// Directory the archives and packages are written to (default: <target-dir>/dist)
+    #[clap(long, global = true)]
+    pub output_dir: Option<PathBuf>,
+
+    /// Log lines as text, or as JSON objects with an `event` field for builds,
+    /// archives, uploads and releases
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = LogFormat::Text,
+        env = "GHDIST_LOG_FORMAT",
+        global = true
+    )]
+    pub log_format: LogFormat,
+
+    /// Print the result (the plan for `plan`) as text or as a JSON document on stdout;
+    /// with json, logs go to stderr
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        env = "GHDIST_OUTPUT",
+        global = true
+    )]
+    pub output: OutputFormat,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -85,13 +350,104 @@ pub enum Command {
         #[clap(short = 'y', long)]
         yes: bool,
 
-        /// CI provider (github, gitlab, etc.)
-        #[clap(long, default_value = "github")]
-        ci: String,
+        /// CI provider to generate the release pipeline for
+        #[clap(long, value_enum, default_value_t = CiProvider::Github)]
+        ci: CiProvider,
 
         /// Skip generating CI workflow
         #[clap(long)]
         skip_ci: bool,
+
+        /// Also generate a workflow opening a version bump + changelog pull request,
+        /// and release when it is merged (GitHub Actions only)
+        #[clap(long)]
+        release_pr: bool,
+    },
+
+    /// Bump the version in Cargo.toml (and the workspace), commit, and optionally tag
+    Bump {
+        /// Which part of the version to bump
+        #[clap(value_enum)]
+        level: BumpLevel,
+
+        /// Pre-release identifier used by `pre`
+        #[clap(long, default_value = "rc")]
+        pre_id: String,
+
+        /// Update files without committing
+        #[clap(long)]
+        no_commit: bool,
+
+        /// Add a section for the new version to the changelog (--changelog-file, default
+        /// CHANGELOG.md) listing the conventional commits since the previous version's tag
+        #[clap(long)]
+        changelog: bool,
+    },
+
+    /// Delete a release (and its tag) from GitHub
+    Rollback {
+        /// Tag of the release to delete
+        #[clap(value_name = "TAG")]
+        release_tag: String,
+
+        /// Delete only the release and leave the git tag in place
+        #[clap(long)]
+        keep_tag: bool,
+
+        /// Skip the confirmation prompt
+        #[clap(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// Publish an existing draft release (set --latest to control the "Latest" badge)
+    PublishDraft {
+        /// Tag of the draft release
+        #[clap(value_name = "TAG")]
+        release_tag: String,
+
+        /// Mark the release as a pre-release
+        #[clap(long)]
+        prerelease: bool,
+    },
+
+    /// Build this runner's targets and upload them to a shared draft release (CI matrix jobs)
+    Upload,
+
+    /// Merge the uploads of all matrix jobs into one release and publish it
+    Finalize,
+
+    /// Check the token, git state, toolchains and configuration a release needs
+    Doctor,
+
+    /// Show the tag, targets, assets and steps of a release without running it
+    Plan,
+
+    /// Store, remove or show the GitHub token used when GITHUB_TOKEN isn't set
+    Auth {
+        #[clap(subcommand)]
+        action: AuthAction,
+    },
+
+    /// Download a published release and check its checksums, archives, binaries and signatures
+    Verify {
+        /// Tag of the release to verify
+        #[clap(value_name = "TAG")]
+        release_tag: String,
+    },
+
+    /// Update cargo-ghdist from its own GitHub releases
+    #[clap(name = "self")]
+    SelfManagement {
+        #[clap(subcommand)]
+        action: SelfAction,
+    },
+
+    /// Print a shell completion script for cargo-ghdist; run it in a project to also
+    /// complete that project's profiles and targets
+    Completions {
+        /// Shell to generate the script for
+        #[clap(value_enum)]
+        shell: clap_complete::Shell,
     },
 }
 
@@ -106,12 +462,66 @@ pub struct Args {
     pub skip_publish: bool,
     pub no_checksum: bool,
     pub config: Option<PathBuf>,
-    #[allow(dead_code)]
     pub verbose: bool,
     pub repository: Option<String>,
     pub github_token: Option<String>,
     pub bins: Option<Vec<String>>,
     pub profile: Option<String>,
+    pub packages: Option<Vec<PackageFormat>>,
+    pub changelog_file: Option<PathBuf>,
+    pub require_changelog: bool,
+    pub latest: Option<LatestMode>,
+    pub discussion_category: Option<String>,
+    pub no_prune: bool,
+    pub create_tag: bool,
+    pub sign_tag: bool,
+    pub allow_dirty: bool,
+    pub allow_branch: bool,
+    pub no_verify_version: bool,
+    pub run_tests: bool,
+    pub package: Vec<String>,
+    pub workspace: bool,
+    pub exclude: Vec<String>,
+    pub registry_token: Option<String>,
+    pub no_verify: bool,
+    pub features: Option<Vec<String>>,
+    pub no_default_features: bool,
+    pub all_features: bool,
+    pub from_artifacts: Option<String>,
+    pub force: bool,
+    pub resume: bool,
+    pub no_upload: bool,
+    pub no_progress: bool,
+    pub provider: Option<Vec<Provider>>,
+    pub app_id: Option<u64>,
+    pub app_private_key: Option<PathBuf>,
+    pub app_installation_id: Option<u64>,
+    pub profile_name: Option<String>,
+    pub prerelease: bool,
+    pub target_dir: Option<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+    pub switches: Switches,
+    pub quiet: bool,
+    pub log_format: LogFormat,
+}
+
+/// On/off settings given explicitly as `--flag[=true|false]` or a GHDIST_* variable
+///
+/// `None` leaves the setting to the config file, and `Some(false)` turns off
+/// something the config file turns on.
+#[derive(Debug, Clone, Default)]
+pub struct Switches {
+    pub draft: Option<bool>,
+    pub skip_publish: Option<bool>,
+    pub no_checksum: Option<bool>,
+    pub require_changelog: Option<bool>,
+    pub create_tag: Option<bool>,
+    pub sign_tag: Option<bool>,
+    pub allow_dirty: Option<bool>,
+    pub run_tests: Option<bool>,
+    pub no_verify: Option<bool>,
+    pub no_default_features: Option<bool>,
+    pub all_features: Option<bool>,
 }
 
 impl From<GhdistCli> for Args {
@@ -121,15 +531,64 @@ impl From<GhdistCli> for Args {
             hash: cli.hash,
             targets: cli.targets,
             format: cli.format,
-            draft: cli.draft,
-            skip_publish: cli.skip_publish,
-            no_checksum: cli.no_checksum,
+            draft: cli.draft.unwrap_or(false),
+            skip_publish: cli.skip_publish.unwrap_or(false),
+            no_checksum: cli.no_checksum.unwrap_or(false),
             config: Some(cli.config),
             verbose: cli.verbose,
             repository: cli.repository,
             github_token: cli.github_token,
             bins: cli.bins,
             profile: cli.profile,
+            packages: cli.packages,
+            changelog_file: cli.changelog_file,
+            require_changelog: cli.require_changelog.unwrap_or(false),
+            latest: cli.latest,
+            discussion_category: cli.discussion_category,
+            no_prune: cli.no_prune,
+            create_tag: cli.create_tag.unwrap_or(false),
+            sign_tag: cli.sign_tag.unwrap_or(false),
+            allow_dirty: cli.allow_dirty.unwrap_or(false),
+            allow_branch: cli.allow_branch,
+            no_verify_version: cli.no_verify_version,
+            run_tests: cli.run_tests.unwrap_or(false),
+            package: cli.package,
+            workspace: cli.workspace,
+            exclude: cli.exclude,
+            registry_token: cli.registry_token,
+            no_verify: cli.no_verify.unwrap_or(false),
+            features: cli.features,
+            no_default_features: cli.no_default_features.unwrap_or(false),
+            all_features: cli.all_features.unwrap_or(false),
+            from_artifacts: cli.from_artifacts,
+            force: cli.force,
+            resume: cli.resume,
+            no_upload: cli.no_upload,
+            // Progress bars would break up JSON lines
+            no_progress: cli.no_progress || cli.quiet || cli.log_format == LogFormat::Json,
+            provider: cli.provider,
+            app_id: cli.app_id,
+            app_private_key: cli.app_private_key,
+            app_installation_id: cli.app_installation_id,
+            profile_name: cli.profile_name,
+            prerelease: false,
+            target_dir: cli.target_dir,
+            output_dir: cli.output_dir,
+            quiet: cli.quiet,
+            log_format: cli.log_format,
+            switches: Switches {
+                draft: cli.draft,
+                skip_publish: cli.skip_publish,
+                no_checksum: cli.no_checksum,
+                require_changelog: cli.require_changelog,
+                create_tag: cli.create_tag,
+                sign_tag: cli.sign_tag,
+                allow_dirty: cli.allow_dirty,
+                run_tests: cli.run_tests,
+                no_verify: cli.no_verify,
+                no_default_features: cli.no_default_features,
+                all_features: cli.all_features,
+            },
         }
     }
 }
@@ -149,6 +608,136 @@ impl std::fmt::Display for ArchiveFormat {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PackageFormat {
+    Deb,
+    Rpm,
+    Msi,
+    Nsis,
+}
+
+impl std::fmt::Display for PackageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PackageFormat::Deb => write!(f, "deb"),
+            PackageFormat::Rpm => write!(f, "rpm"),
+            PackageFormat::Msi => write!(f, "msi"),
+            PackageFormat::Nsis => write!(f, "nsis"),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SelfAction {
+    /// Replace this executable with the latest release, verifying its checksum
+    Update {
+        /// Install this release tag instead, even if it is older
+        #[clap(long)]
+        tag: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone, Copy)]
+pub enum AuthAction {
+    /// Store a token in the OS keyring
+    Login {
+        /// Read the token from stdin instead of prompting
+        #[clap(long)]
+        with_token: bool,
+    },
+    /// Remove the token from the OS keyring
+    Logout,
+    /// Show where the token comes from
+    Status,
+}
+
+/// Forge a release is published to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Provider {
+    Github,
+    Gitlab,
+}
+
+impl std::fmt::Display for Provider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Provider::Github => write!(f, "GitHub"),
+            Provider::Gitlab => write!(f, "GitLab"),
+        }
+    }
+}
+
+/// CI service `cargo ghdist init` writes a release pipeline for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CiProvider {
+    Github,
+    Gitlab,
+    Circleci,
+}
+
+impl std::fmt::Display for CiProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CiProvider::Github => write!(f, "github"),
+            CiProvider::Gitlab => write!(f, "gitlab"),
+            CiProvider::Circleci => write!(f, "circleci"),
+        }
+    }
+}
+
+/// Version component bumped by `cargo ghdist bump`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BumpLevel {
+    Major,
+    Minor,
+    Patch,
+    /// Start or increment a pre-release such as `-rc.1`
+    Pre,
+}
+
+/// How results are printed on stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// GitHub's `make_latest` setting for a release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LatestMode {
+    True,
+    False,
+    /// Let GitHub pick by creation date and semantic version
+    Legacy,
+}
+
+impl std::fmt::Display for LatestMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LatestMode::True => write!(f, "true"),
+            LatestMode::False => write!(f, "false"),
+            LatestMode::Legacy => write!(f, "legacy"),
+        }
+    }
+}
+
 impl Args {
     /// Get the list of targets, using defaults if not specified
     pub fn targets(&self) -> Vec<String> {
@@ -160,6 +749,58 @@ impl Args {
         })
     }
 
+    /// The package released on its own, when exactly one is selected with `-p`
+    pub fn release_package(&self) -> Option<&str> {
+        match self.package.as_slice() {
+            [package] => Some(package),
+            _ => None,
+        }
+    }
+
+    /// Get the `make_latest` setting; continuous releases never become "Latest" unless asked
+    pub fn latest(&self) -> Option<LatestMode> {
+        self.latest
+            .or_else(|| self.hash.then_some(LatestMode::False))
+    }
+
+    /// Forges to publish to, GitHub unless `--provider` says otherwise
+    /// Forges to release to, GitHub first whatever the order given, since
+    /// GitLab's `assets = "links"` point at the GitHub release's assets
+    pub fn providers(&self) -> Vec<Provider> {
+        let mut providers = self
+            .provider
+            .clone()
+            .unwrap_or_else(|| vec![Provider::Github]);
+        providers.sort_by_key(|provider| *provider != Provider::Github);
+        providers.dedup();
+        providers
+    }
+
+    /// GitHub App credentials, if an app ID is configured
+    ///
+    /// The key comes from GITHUB_APP_PRIVATE_KEY when set, so CI secrets needn't be written to disk.
+    pub fn github_app(&self) -> anyhow::Result<Option<GitHubApp>> {
+        let Some(app_id) = self.app_id else {
+            return Ok(None);
+        };
+        let private_key = match std::env::var("GITHUB_APP_PRIVATE_KEY") {
+            Ok(key) if !key.trim().is_empty() => key,
+            _ => {
+                let path = self.app_private_key.as_ref().with_context(|| {
+                    format!("GitHub App {app_id} needs --app-private-key or GITHUB_APP_PRIVATE_KEY")
+                })?;
+                std::fs::read_to_string(path).with_context(|| {
+                    format!("Failed to read the GitHub App key {}", path.display())
+                })?
+            }
+        };
+        Ok(Some(GitHubApp {
+            app_id,
+            private_key,
+            installation_id: self.app_installation_id,
+        }))
+    }
+
     /// Get the archive format, using the default if not specified
     pub fn archive_format(&self) -> ArchiveFormat {
         self.format.unwrap_or(ArchiveFormat::Tgz)
@@ -170,6 +811,19 @@ impl Args {
         self.profile.as_deref().unwrap_or("release")
     }
 
+    /// Get the additional package formats to build
+    pub fn packages(&self) -> Vec<PackageFormat> {
+        self.packages.clone().unwrap_or_default()
+    }
+
+    /// Changelog to read release notes from; `--require-changelog` implies CHANGELOG.md
+    pub fn changelog_file(&self) -> Option<PathBuf> {
+        self.changelog_file.clone().or_else(|| {
+            self.require_changelog
+                .then(|| PathBuf::from("CHANGELOG.md"))
+        })
+    }
+
     /// Parse repository from argument or Cargo.toml
     pub fn parse_repository(&self) -> anyhow::Result<(String, String)> {
         if let Some(repo) = &self.repository {
@@ -0,0 +1,195 @@
+use clap::builder::PossibleValuesParser;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use crate::cli::GhdistCli;
+use crate::config::Config;
+
+/// Name the scripts complete; cargo's own completion doesn't reach subcommands
+const BIN_NAME: &str = "cargo-ghdist";
+
+/// Values offered for arguments that depend on the project
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Candidates {
+    /// `[profile.<name>]` sections of the ghdist config
+    pub profile_names: Vec<String>,
+    /// Cargo profiles: the built-in ones and `[profile.*]` in Cargo.toml
+    pub cargo_profiles: Vec<String>,
+    /// Configured targets and the ones rustup has installed
+    pub targets: Vec<String>,
+}
+
+impl Candidates {
+    /// Read what the project at the current directory configures; anything
+    /// missing or unreadable just offers nothing
+    pub fn detect(config_path: &Path) -> Self {
+        let config = Config::load(config_path, None).ok();
+        let mut profile_names = config
+            .as_ref()
+            .map(|config| config.profile.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        profile_names.sort();
+
+        let mut targets = Vec::new();
+        if let Some(config) = &config {
+            targets.extend(config.default.targets.iter().cloned());
+            let mut sections = config.target.keys().cloned().collect::<Vec<_>>();
+            sections.sort();
+            targets.extend(sections);
+        }
+        targets.extend(installed_targets());
+
+        Self {
+            profile_names,
+            cargo_profiles: cargo_profiles(Path::new("Cargo.toml")),
+            targets: dedup(targets),
+        }
+    }
+}
+
+/// `dev`, `release` and the custom profiles of a manifest
+fn cargo_profiles(manifest: &Path) -> Vec<String> {
+    let mut profiles = vec!["dev".to_string(), "release".to_string()];
+    let custom = std::fs::read_to_string(manifest)
+        .ok()
+        .and_then(|content| content.parse::<toml::Table>().ok())
+        .and_then(|manifest| manifest.get("profile")?.as_table().cloned())
+        .unwrap_or_default();
+    profiles.extend(custom.keys().cloned());
+    dedup(profiles)
+}
+
+/// Targets from `rustup target list --installed`, empty without rustup
+fn installed_targets() -> Vec<String> {
+    Command::new("rustup")
+        .args(["target", "list", "--installed"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Drop repeats, keeping the first occurrence
+fn dedup(values: Vec<String>) -> Vec<String> {
+    let mut unique = Vec::new();
+    for value in values {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    unique
+}
+
+/// The CLI with `candidates` as the possible values of the project-specific
+/// arguments; only used to generate completions, never to parse
+fn command(candidates: &Candidates) -> clap::Command {
+    let mut command = GhdistCli::command().name(BIN_NAME);
+    for (arg, values) in [
+        ("profile_name", &candidates.profile_names),
+        ("profile", &candidates.cargo_profiles),
+        ("targets", &candidates.targets),
+    ] {
+        if !values.is_empty() {
+            let values = values.clone();
+            command = command.mut_arg(arg, |arg| {
+                arg.value_parser(PossibleValuesParser::new(values))
+            });
+        }
+    }
+    command
+}
+
+/// Write the completion script for `shell` to `out`
+pub fn generate(shell: Shell, candidates: &Candidates, out: &mut dyn Write) {
+    let mut command = command(candidates);
+    clap_complete::generate(shell, &mut command, BIN_NAME, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(shell: Shell, candidates: &Candidates) -> String {
+        let mut out = Vec::new();
+        generate(shell, candidates, &mut out);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_generate_every_shell() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let script = script(shell, &Candidates::default());
+            assert!(script.contains("cargo-ghdist"), "{shell}");
+            assert!(script.contains("finalize"), "{shell}");
+        }
+    }
+
+    #[test]
+    fn test_generate_offers_candidates() {
+        let candidates = Candidates {
+            profile_names: vec!["nightly".to_string()],
+            cargo_profiles: vec!["dev".to_string(), "dist".to_string()],
+            targets: vec!["x86_64-unknown-linux-musl".to_string()],
+        };
+        let fish = script(Shell::Fish, &candidates);
+        assert!(fish.contains("-l profile-name") && fish.contains("nightly"));
+        assert!(fish.contains("dist"));
+        assert!(fish.contains("x86_64-unknown-linux-musl"));
+
+        let bash = script(Shell::Bash, &candidates);
+        assert!(bash.contains("x86_64-unknown-linux-musl"));
+    }
+
+    #[test]
+    fn test_cargo_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = dir.path().join("Cargo.toml");
+        std::fs::write(
+            &manifest,
+            "[package]\nname = \"demo\"\n\n[profile.dist]\ninherits = \"release\"\n\n[profile.release]\nlto = true\n",
+        )
+        .unwrap();
+        assert_eq!(cargo_profiles(&manifest), vec!["dev", "release", "dist"]);
+        assert_eq!(
+            cargo_profiles(&dir.path().join("missing.toml")),
+            vec!["dev", "release"]
+        );
+    }
+
+    #[test]
+    fn test_detect_reads_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("ghdist.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+[default]
+targets = ["aarch64-apple-darwin"]
+
+[target."x86_64-unknown-linux-musl"]
+linker = "musl-gcc"
+
+[profile.nightly]
+draft = true
+"#,
+        )
+        .unwrap();
+        let candidates = Candidates::detect(&config_path);
+        assert_eq!(candidates.profile_names, vec!["nightly"]);
+        assert_eq!(
+            candidates.targets[..2],
+            ["aarch64-apple-darwin", "x86_64-unknown-linux-musl"]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::ContainerConfig;
+
+/// Where the project is mounted inside the build container
+pub const PROJECT_DIR: &str = "/project";
+
+/// Cargo's home inside the build container when Docker runs as the project's
+/// owner, who can't write to the image's own (usually root-owned) cargo home
+pub const CARGO_HOME: &str = "/project/target/.cargo-home";
+
+/// Owner of the project directory, so files written by the container stay editable
+#[cfg(unix)]
+pub fn project_owner(project: &Path) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(project)
+        .ok()
+        .map(|meta| (meta.uid(), meta.gid()))
+}
+
+#[cfg(not(unix))]
+pub fn project_owner(_project: &Path) -> Option<(u32, u32)> {
+    None
+}
+
+/// Run a prepared cargo command inside `image` instead of on the host
+///
+/// The project directory is mounted at [`PROJECT_DIR`] and used as the working
+/// directory, so the relative `--target-dir` ends up in the host's target
+/// directory. Environment variables set on `cmd` are forwarded with `-e`.
+/// Docker runs as `user` (uid, gid) to avoid root-owned build output, with
+/// `CARGO_HOME` and `HOME` at [`CARGO_HOME`] so cargo can write its registry
+/// cache; rootless Podman already maps the container's root to the invoking
+/// user.
+pub fn wrap(
+    config: &ContainerConfig,
+    image: &str,
+    project: &Path,
+    user: Option<(u32, u32)>,
+    cmd: &Command,
+) -> Command {
+    let engine = config.engine();
+    let mut container = Command::new(engine);
+    container
+        .arg("run")
+        .arg("--rm")
+        .arg("--volume")
+        .arg(format!("{}:{PROJECT_DIR}", project.display()))
+        .arg("--workdir")
+        .arg(PROJECT_DIR);
+
+    if let Some((uid, gid)) = user.filter(|_| engine == "docker") {
+        container.arg("--user").arg(format!("{uid}:{gid}"));
+        for key in ["CARGO_HOME", "HOME"] {
+            if !cmd.get_envs().any(|(set, _)| set == key) {
+                container.arg("--env").arg(format!("{key}={CARGO_HOME}"));
+            }
+        }
+    }
+    for volume in &config.volumes {
+        container.arg("--volume").arg(volume);
+    }
+    for (key, value) in cmd.get_envs() {
+        if let Some(value) = value {
+            container.arg("--env").arg(format!(
+                "{}={}",
+                key.to_string_lossy(),
+                value.to_string_lossy()
+            ));
+        }
+    }
+
+    container
+        .arg(image)
+        .arg(cmd.get_program())
+        .args(cmd.get_args());
+    container
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap() {
+        let mut cargo = Command::new("cargo");
+        cargo
+            .args(["build", "--target", "x86_64-unknown-linux-gnu"])
+            .env("CC", "gcc");
+
+        let config = ContainerConfig {
+            volumes: vec!["/cache/registry:/project/target/.cargo-home/registry".to_string()],
+            ..Default::default()
+        };
+        let cmd = wrap(
+            &config,
+            "ghcr.io/org/builder:centos7",
+            Path::new("/work/tool"),
+            Some((1000, 100)),
+            &cargo,
+        );
+
+        assert_eq!(cmd.get_program(), "docker");
+        assert_eq!(
+            args(&cmd),
+            vec![
+                "run",
+                "--rm",
+                "--volume",
+                "/work/tool:/project",
+                "--workdir",
+                "/project",
+                "--user",
+                "1000:100",
+                "--env",
+                "CARGO_HOME=/project/target/.cargo-home",
+                "--env",
+                "HOME=/project/target/.cargo-home",
+                "--volume",
+                "/cache/registry:/project/target/.cargo-home/registry",
+                "--env",
+                "CC=gcc",
+                "ghcr.io/org/builder:centos7",
+                "cargo",
+                "build",
+                "--target",
+                "x86_64-unknown-linux-gnu",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrap_podman() {
+        let config = ContainerConfig {
+            engine: Some("podman".to_string()),
+            ..Default::default()
+        };
+        let cmd = wrap(
+            &config,
+            "builder",
+            Path::new("/work"),
+            Some((1000, 100)),
+            &Command::new("cargo"),
+        );
+
+        assert_eq!(cmd.get_program(), "podman");
+        assert!(!args(&cmd).contains(&"--user".to_string()));
+        assert!(!args(&cmd).contains(&"CARGO_HOME=/project/target/.cargo-home".to_string()));
+    }
+
+    #[test]
+    fn test_wrap_keeps_cargo_home() {
+        let mut cargo = Command::new("cargo");
+        cargo.env("CARGO_HOME", "/opt/cargo");
+        let cmd = wrap(
+            &ContainerConfig::default(),
+            "builder",
+            Path::new("/work"),
+            Some((1000, 100)),
+            &cargo,
+        );
+
+        let args = args(&cmd);
+        assert!(args.contains(&"CARGO_HOME=/opt/cargo".to_string()));
+        assert!(!args.contains(&"CARGO_HOME=/project/target/.cargo-home".to_string()));
+        assert!(args.contains(&"HOME=/project/target/.cargo-home".to_string()));
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::Result;
+use git2::{Oid, Repository};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::changelog;
+
+/// A commit author in the release range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contributor {
+    pub name: String,
+    pub email: String,
+    /// One of the contributor's commits, used to resolve the GitHub login
+    pub sha: String,
+    /// No commits reachable from the previous tag
+    pub first_time: bool,
+    pub login: Option<String>,
+}
+
+fn is_bot(name: &str) -> bool {
+    name.ends_with("[bot]")
+}
+
+fn author_emails(repo: &Repository, from: Oid) -> Result<HashSet<String>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(from)?;
+
+    let mut emails = HashSet::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let author = commit.author();
+        if let Some(email) = author.email() {
+            emails.insert(email.to_lowercase());
+        }
+    }
+
+    Ok(emails)
+}
+
+/// Collect authors of commits reachable from HEAD but not from `previous_tag`
+///
+/// With `path`, only authors of commits touching that directory are listed.
+pub fn collect_contributors(
+    repo: &Repository,
+    previous_tag: Option<&str>,
+    path: Option<&Path>,
+) -> Result<Vec<Contributor>> {
+    let previous = previous_tag.and_then(|tag| {
+        repo.revparse_single(tag)
+            .and_then(|obj| obj.peel_to_commit())
+            .map(|commit| commit.id())
+            .ok()
+    });
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+    revwalk.push_head()?;
+    let earlier_authors = match previous {
+        Some(oid) => {
+            revwalk.hide(oid)?;
+            Some(author_emails(repo, oid)?)
+        }
+        None => None,
+    };
+
+    let mut contributors: Vec<Contributor> = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let author = commit.author();
+        let name = author.name().unwrap_or("unknown").to_string();
+        let email = author.email().unwrap_or("").to_lowercase();
+
+        if is_bot(&name) || contributors.iter().any(|c| c.email == email) {
+            continue;
+        }
+        if let Some(path) = path {
+            if !changelog::touches_path(repo, &commit, path)? {
+                continue;
+            }
+        }
+
+        contributors.push(Contributor {
+            first_time: earlier_authors
+                .as_ref()
+                .is_some_and(|earlier| !earlier.contains(&email)),
+            name,
+            email,
+            sha: commit.id().to_string(),
+            login: None,
+        });
+    }
+
+    Ok(contributors)
+}
+
+/// Render the "Thanks to" section; `None` when there are no contributors
+pub fn render(contributors: &[Contributor]) -> Option<String> {
+    let contributors = contributors
+        .iter()
+        .filter(|c| !c.login.as_deref().is_some_and(is_bot))
+        .collect::<Vec<_>>();
+    if contributors.is_empty() {
+        return None;
+    }
+
+    let mut section = String::from("### 🙏 Thanks to\n\n");
+    for contributor in contributors {
+        let who = match &contributor.login {
+            Some(login) => format!("@{login}"),
+            None => contributor.name.clone(),
+        };
+        if contributor.first_time {
+            section.push_str(&format!("- {who} 🎉 (first contribution)\n"));
+        } else {
+            section.push_str(&format!("- {who}\n"));
+        }
+    }
+
+    Some(section)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_render() {
+        let contributors = vec![
+            Contributor {
+                name: "Jane Doe".to_string(),
+                email: "jane@example.com".to_string(),
+                sha: "abc".to_string(),
+                first_time: false,
+                login: Some("jane".to_string()),
+            },
+            Contributor {
+                name: "New Person".to_string(),
+                email: "new@example.com".to_string(),
+                sha: "def".to_string(),
+                first_time: true,
+                login: None,
+            },
+            Contributor {
+                name: "Renovate".to_string(),
+                email: "bot@example.com".to_string(),
+                sha: "123".to_string(),
+                first_time: false,
+                login: Some("renovate[bot]".to_string()),
+            },
+        ];
+
+        let section = render(&contributors).unwrap();
+        assert!(section.contains("- @jane\n"));
+        assert!(section.contains("- New Person 🎉 (first contribution)\n"));
+        assert!(!section.contains("renovate"));
+        assert!(render(&[]).is_none());
+    }
+
+    #[test]
+    fn test_collect_contributors() {
+        let temp_dir = tempdir().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+
+        let commit = |name: &str, email: &str, message: &str| {
+            let sig = git2::Signature::now(name, email).unwrap();
+            let mut index = repo.index().unwrap();
+            std::fs::write(temp_dir.path().join("file.txt"), message).unwrap();
+            index.add_path(Path::new("file.txt")).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents = repo
+                .head()
+                .ok()
+                .and_then(|h| h.peel_to_commit().ok())
+                .into_iter()
+                .collect::<Vec<_>>();
+            let parents = parents.iter().collect::<Vec<_>>();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap()
+        };
+
+        let first = commit("Jane", "jane@example.com", "initial");
+        repo.tag_lightweight("v0.1.0", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+        commit("Jane", "JANE@example.com", "second");
+        commit("Newcomer", "new@example.com", "third");
+        commit("dependabot[bot]", "bot@example.com", "deps");
+        commit("Newcomer", "new@example.com", "fourth");
+
+        let contributors = collect_contributors(&repo, Some("v0.1.0"), None).unwrap();
+        assert_eq!(contributors.len(), 2);
+        assert_eq!(contributors[0].name, "Jane");
+        assert!(!contributors[0].first_time);
+        assert_eq!(contributors[1].name, "Newcomer");
+        assert!(contributors[1].first_time);
+
+        // Without a previous tag nobody is marked as a first-time contributor
+        let all = collect_contributors(&repo, None, None).unwrap();
+        assert!(all.iter().all(|c| !c.first_time));
+
+        // Every commit above touched file.txt only
+        let scoped = collect_contributors(&repo, None, Some(Path::new("crates/cli"))).unwrap();
+        assert!(scoped.is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+use crate::config::DebConfig;
+use crate::error::GhDistError;
+use crate::packager::{self, PackageFile, PackageMetadata};
+use anyhow::Result;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Map a Rust target triple to a Debian architecture name
+pub fn debian_arch(target: &str) -> Option<&'static str> {
+    if !target.contains("linux") {
+        return None;
+    }
+
+    let arch = target.split('-').next().unwrap_or("");
+    match arch {
+        "x86_64" => Some("amd64"),
+        "aarch64" => Some("arm64"),
+        "i686" | "i586" => Some("i386"),
+        "armv7" => Some("armhf"),
+        "arm" => Some(if target.ends_with("hf") {
+            "armhf"
+        } else {
+            "armel"
+        }),
+        "riscv64gc" => Some("riscv64"),
+        "powerpc64le" => Some("ppc64el"),
+        "s390x" => Some("s390x"),
+        "loongarch64" => Some("loong64"),
+        _ => None,
+    }
+}
+
+/// Convert a Cargo version into a Debian-compatible version string
+pub fn debian_version(version: &str) -> String {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    // Pre-release identifiers must sort before the release in dpkg
+    version.replacen('-', "~", 1)
+}
+
+/// Build a .deb package containing the given binaries
+pub fn create_deb(
+    binaries: &[PathBuf],
+    output_dir: &Path,
+    metadata: &PackageMetadata,
+    target: &str,
+    config: &DebConfig,
+) -> Result<PathBuf> {
+    let arch = debian_arch(target).ok_or_else(|| {
+        GhDistError::Package(format!("No Debian architecture known for target {target}"))
+    })?;
+    let version = debian_version(&metadata.version);
+
+    let files = packager::collect_package_files(
+        binaries,
+        &metadata.name,
+        &config.completions,
+        &config.man_pages,
+        "usr/share/zsh/vendor-completions",
+    )?;
+    let installed_size = files
+        .iter()
+        .map(|f| f.content.len() as u64)
+        .sum::<u64>()
+        .div_ceil(1024);
+    let control = control_file(metadata, &version, arch, installed_size, config);
+    let mtime = packager::source_date_epoch();
+
+    let control_tar = build_tar_gz(
+        &[PackageFile {
+            path: "control".to_string(),
+            content: control.into_bytes(),
+            mode: 0o644,
+        }],
+        mtime,
+    )?;
+    let data_tar = build_tar_gz(&files, mtime)?;
+
+    let deb_path = output_dir.join(format!("{}_{version}_{arch}.deb", metadata.name));
+    let mut builder = ar::Builder::new(File::create(&deb_path)?);
+    for (name, content) in [
+        ("debian-binary", b"2.0\n".to_vec()),
+        ("control.tar.gz", control_tar),
+        ("data.tar.gz", data_tar),
+    ] {
+        let mut header = ar::Header::new(name.as_bytes().to_vec(), content.len() as u64);
+        header.set_mode(0o100644);
+        header.set_mtime(mtime);
+        builder.append(&header, content.as_slice())?;
+    }
+
+    tracing::info!("Created Debian package: {}", deb_path.display());
+    Ok(deb_path)
+}
+
+/// Render the DEBIAN/control file
+fn control_file(
+    metadata: &PackageMetadata,
+    version: &str,
+    arch: &str,
+    installed_size: u64,
+    config: &DebConfig,
+) -> String {
+    let maintainer = config
+        .maintainer
+        .clone()
+        .or_else(|| metadata.authors.first().cloned())
+        .unwrap_or_else(|| format!("{} maintainers", metadata.name));
+
+    let mut control = format!(
+        "Package: {}\nVersion: {version}\nArchitecture: {arch}\nMaintainer: {maintainer}\n\
+         Installed-Size: {installed_size}\nSection: {}\nPriority: {}\n",
+        metadata.name,
+        config.section.as_deref().unwrap_or("utils"),
+        config.priority.as_deref().unwrap_or("optional"),
+    );
+
+    if let Some(depends) = &config.depends {
+        control.push_str(&format!("Depends: {depends}\n"));
+    }
+    if let Some(homepage) = &metadata.homepage {
+        control.push_str(&format!("Homepage: {homepage}\n"));
+    }
+
+    let description = metadata
+        .description
+        .as_deref()
+        .unwrap_or(metadata.name.as_str());
+    let mut lines = description.trim().lines();
+    control.push_str(&format!(
+        "Description: {}\n",
+        lines.next().unwrap_or(&metadata.name)
+    ));
+    for line in lines {
+        let line = line.trim();
+        // Empty lines in extended descriptions are written as " ."
+        if line.is_empty() {
+            control.push_str(" .\n");
+        } else {
+            control.push_str(&format!(" {line}\n"));
+        }
+    }
+
+    control
+}
+
+/// Build a gzip-compressed tarball with root-owned entries and parent directories
+fn build_tar_gz(files: &[PackageFile], mtime: u64) -> Result<Vec<u8>> {
+    let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::best()));
+
+    let mut dirs = BTreeSet::new();
+    for file in files {
+        let mut parent = Path::new(&file.path).parent();
+        while let Some(dir) = parent {
+            if dir.as_os_str().is_empty() {
+                break;
+            }
+            dirs.insert(dir.to_path_buf());
+            parent = dir.parent();
+        }
+    }
+
+    for dir in &dirs {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_mode(0o755);
+        header.set_size(0);
+        header.set_mtime(mtime);
+        header.set_uid(0);
+        header.set_gid(0);
+        tar.append_data(&mut header, format!("{}/", dir.display()), std::io::empty())?;
+    }
+
+    for file in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_mode(file.mode);
+        header.set_size(file.content.len() as u64);
+        header.set_mtime(mtime);
+        header.set_uid(0);
+        header.set_gid(0);
+        tar.append_data(&mut header, &file.path, file.content.as_slice())?;
+    }
+
+    Ok(tar.into_inner()?.finish()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    fn test_metadata() -> PackageMetadata {
+        PackageMetadata {
+            name: "mytool".to_string(),
+            version: "1.2.3-beta.1".to_string(),
+            description: Some("A useful tool".to_string()),
+            authors: vec!["Jane Doe <jane@example.com>".to_string()],
+            homepage: Some("https://github.com/owner/mytool".to_string()),
+            license: Some("MIT".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_debian_arch() {
+        assert_eq!(debian_arch("x86_64-unknown-linux-gnu"), Some("amd64"));
+        assert_eq!(debian_arch("aarch64-unknown-linux-musl"), Some("arm64"));
+        assert_eq!(debian_arch("armv7-unknown-linux-gnueabihf"), Some("armhf"));
+        assert_eq!(debian_arch("arm-unknown-linux-gnueabi"), Some("armel"));
+        assert_eq!(debian_arch("x86_64-apple-darwin"), None);
+    }
+
+    #[test]
+    fn test_debian_version() {
+        assert_eq!(debian_version("1.2.3"), "1.2.3");
+        assert_eq!(debian_version("v1.2.3-rc.1"), "1.2.3~rc.1");
+    }
+
+    #[test]
+    fn test_control_file() {
+        let control = control_file(
+            &test_metadata(),
+            "1.2.3",
+            "amd64",
+            42,
+            &DebConfig::default(),
+        );
+
+        assert!(control.contains("Package: mytool\n"));
+        assert!(control.contains("Architecture: amd64\n"));
+        assert!(control.contains("Maintainer: Jane Doe <jane@example.com>\n"));
+        assert!(control.contains("Installed-Size: 42\n"));
+        assert!(control.contains("Homepage: https://github.com/owner/mytool\n"));
+        assert!(control.ends_with("Description: A useful tool\n"));
+    }
+
+    #[test]
+    fn test_create_deb() {
+        let temp_dir = tempdir().unwrap();
+        let binary = temp_dir.path().join("mytool");
+        fs::write(&binary, b"binary").unwrap();
+        let man = temp_dir.path().join("mytool.1");
+        fs::write(&man, b".TH MYTOOL 1").unwrap();
+
+        let config = DebConfig {
+            man_pages: vec![man],
+            ..Default::default()
+        };
+        let deb_path = create_deb(
+            &[binary],
+            temp_dir.path(),
+            &test_metadata(),
+            "x86_64-unknown-linux-gnu",
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(
+            deb_path.file_name().unwrap(),
+            "mytool_1.2.3~beta.1_amd64.deb"
+        );
+
+        let mut archive = ar::Archive::new(File::open(&deb_path).unwrap());
+        let mut names = Vec::new();
+        let mut data_paths = Vec::new();
+        while let Some(entry) = archive.next_entry() {
+            let mut entry = entry.unwrap();
+            let name = String::from_utf8(entry.header().identifier().to_vec()).unwrap();
+            if name == "data.tar.gz" {
+                let mut content = Vec::new();
+                entry.read_to_end(&mut content).unwrap();
+                let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(content.as_slice()));
+                for file in tar.entries().unwrap() {
+                    let file = file.unwrap();
+                    data_paths.push(file.path().unwrap().display().to_string());
+                }
+            }
+            names.push(name);
+        }
+
+        assert_eq!(
+            names,
+            vec!["debian-binary", "control.tar.gz", "data.tar.gz"]
+        );
+        assert!(data_paths.contains(&"usr/bin/mytool".to_string()));
+        assert!(data_paths.contains(&"usr/share/man/man1/mytool.1.gz".to_string()));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::config::DockerPublisherConfig;
+use crate::error::GhDistError;
+use crate::ui;
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const REGISTRY: &str = "ghcr.io";
+
+/// Map a Rust Linux target to the Docker platform architecture
+pub fn docker_arch(target: &str) -> Option<&'static str> {
+    if !target.contains("linux") {
+        return None;
+    }
+
+    match target.split('-').next().unwrap_or("") {
+        "x86_64" => Some("amd64"),
+        "aarch64" => Some("arm64"),
+        _ => None,
+    }
+}
+
+/// Full image name, defaulting to ghcr.io/{owner}/{repo}
+pub fn image_name(config: &DockerPublisherConfig, owner: &str, repo: &str) -> String {
+    config
+        .image
+        .clone()
+        .unwrap_or_else(|| format!("{REGISTRY}/{owner}/{repo}"))
+        .to_lowercase()
+}
+
+/// Convert a release tag into a valid image tag (`v1.2.3` becomes `1.2.3`)
+pub fn image_tag(tag: &str) -> String {
+    let tag = match tag.strip_prefix('v') {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+        _ => tag,
+    };
+
+    tag.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// The `image:tag` reference a release of `tag` is pushed as
+pub fn image_reference(image: &str, tag: &str) -> String {
+    format!("{image}:{}", image_tag(tag))
+}
+
+/// Base image for the generated Dockerfile: scratch for static musl builds, distroless otherwise
+fn default_base_image(targets: &[String]) -> &'static str {
+    if targets.iter().all(|t| t.contains("musl")) {
+        "scratch"
+    } else {
+        "gcr.io/distroless/cc-debian12"
+    }
+}
+
+/// Render the built-in Dockerfile; binaries are staged per architecture under the context
+fn render_dockerfile(base: &str, entrypoint: &str) -> String {
+    format!(
+        "FROM {base}\n\
+         ARG TARGETARCH\n\
+         COPY ${{TARGETARCH}}/ /usr/local/bin/\n\
+         ENTRYPOINT [\"/usr/local/bin/{entrypoint}\"]\n"
+    )
+}
+
+/// Build a multi-architecture image from the Linux binaries and push it to the registry
+pub fn publish(
+    config: &DockerPublisherConfig,
+    linux_binaries: &[(String, Vec<PathBuf>)],
+    output_dir: &Path,
+    image: &str,
+    tag: &str,
+    token: &str,
+) -> Result<String> {
+    let builds = linux_binaries
+        .iter()
+        .filter_map(|(target, binaries)| docker_arch(target).map(|arch| (target, arch, binaries)))
+        .collect::<Vec<_>>();
+
+    if builds.is_empty() {
+        return Err(GhDistError::Package(
+            "No x86_64/aarch64 Linux binaries available for the container image".to_string(),
+        )
+        .into());
+    }
+
+    // Stage binaries as {context}/{arch}/{binary}
+    let context = output_dir.join("docker");
+    if context.exists() {
+        fs::remove_dir_all(&context)?;
+    }
+    let mut platforms = Vec::new();
+    for (_, arch, binaries) in &builds {
+        let arch_dir = context.join(arch);
+        fs::create_dir_all(&arch_dir)?;
+        for binary in binaries.iter() {
+            if let Some(name) = binary.file_name() {
+                fs::copy(binary, arch_dir.join(name))?;
+            }
+        }
+        platforms.push(format!("linux/{arch}"));
+    }
+
+    let dockerfile = match &config.dockerfile {
+        Some(path) => path.clone(),
+        None => {
+            let entrypoint = config
+                .binary
+                .clone()
+                .or_else(|| {
+                    builds[0]
+                        .2
+                        .first()
+                        .and_then(|b| b.file_name())
+                        .map(|n| n.to_string_lossy().into_owned())
+                })
+                .unwrap_or_default();
+            let targets = builds
+                .iter()
+                .map(|(t, _, _)| t.to_string())
+                .collect::<Vec<_>>();
+            let base = config
+                .base_image
+                .as_deref()
+                .unwrap_or_else(|| default_base_image(&targets));
+            let path = context.join("Dockerfile");
+            fs::write(&path, render_dockerfile(base, &entrypoint))?;
+            path
+        }
+    };
+
+    login(image, token)?;
+
+    let reference = image_reference(image, tag);
+    let mut command = Command::new("docker");
+    command
+        .arg("buildx")
+        .arg("build")
+        .arg("--platform")
+        .arg(platforms.join(","))
+        .arg("--file")
+        .arg(&dockerfile)
+        .arg("--tag")
+        .arg(&reference);
+    if config.tag_latest {
+        command.arg("--tag").arg(format!("{image}:latest"));
+    }
+    command.arg("--push").arg(&context);
+
+    tracing::info!("Building and pushing container image {}", reference);
+    let status = command
+        .stdout(ui::child_stdout())
+        .status()
+        .context("Failed to run `docker buildx`. Is Docker with buildx installed?")?;
+
+    if !status.success() {
+        return Err(
+            GhDistError::Package(format!("docker buildx build failed for {reference}")).into(),
+        );
+    }
+
+    Ok(reference)
+}
+
+/// Log in to the image registry with the GitHub token
+fn login(image: &str, token: &str) -> Result<()> {
+    let registry = image.split('/').next().unwrap_or(REGISTRY);
+    let username = std::env::var("GITHUB_ACTOR").unwrap_or_else(|_| "cargo-ghdist".to_string());
+
+    let mut child = Command::new("docker")
+        .arg("login")
+        .arg(registry)
+        .arg("--username")
+        .arg(&username)
+        .arg("--password-stdin")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .context("Failed to run `docker login`")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(token.as_bytes())?;
+    }
+
+    if !child.wait()?.success() {
+        return Err(GhDistError::Package(format!("docker login to {registry} failed")).into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docker_arch() {
+        assert_eq!(docker_arch("x86_64-unknown-linux-musl"), Some("amd64"));
+        assert_eq!(docker_arch("aarch64-unknown-linux-gnu"), Some("arm64"));
+        assert_eq!(docker_arch("x86_64-apple-darwin"), None);
+        assert_eq!(docker_arch("armv7-unknown-linux-gnueabihf"), None);
+    }
+
+    #[test]
+    fn test_image_name() {
+        let config = DockerPublisherConfig::default();
+        assert_eq!(
+            image_name(&config, "Owner", "MyTool"),
+            "ghcr.io/owner/mytool"
+        );
+
+        let config = DockerPublisherConfig {
+            image: Some("ghcr.io/org/custom".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(image_name(&config, "owner", "tool"), "ghcr.io/org/custom");
+    }
+
+    #[test]
+    fn test_image_tag() {
+        assert_eq!(image_tag("v1.2.3"), "1.2.3");
+        assert_eq!(image_tag("1.2.3+build.5"), "1.2.3-build.5");
+        assert_eq!(image_tag("feature/new-ui"), "feature-new-ui");
+        assert_eq!(image_tag("vabcdef0"), "vabcdef0");
+    }
+
+    #[test]
+    fn test_render_dockerfile() {
+        let base = default_base_image(&["x86_64-unknown-linux-musl".to_string()]);
+        assert_eq!(base, "scratch");
+        assert_eq!(
+            default_base_image(&["x86_64-unknown-linux-gnu".to_string()]),
+            "gcr.io/distroless/cc-debian12"
+        );
+
+        let dockerfile = render_dockerfile(base, "mytool");
+        assert!(dockerfile.starts_with("FROM scratch\n"));
+        assert!(dockerfile.contains("COPY ${TARGETARCH}/ /usr/local/bin/\n"));
+        assert!(dockerfile.contains("ENTRYPOINT [\"/usr/local/bin/mytool\"]"));
+    }
+}
@@ -0,0 +1,423 @@
+use anyhow::Result;
+use git2::Repository;
+use std::process::Command;
+
+use cargo_ghinstall::auth::{self, TokenSource};
+
+use crate::cli::Args;
+use crate::config::Config;
+use crate::github::{GitHubApp, GitHubClient};
+use crate::preflight::{self, PreflightOverrides};
+use crate::tagging;
+use crate::toolchain;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    /// Not ready, but a release can still go ahead (e.g. with an extra flag)
+    Warn,
+    Fail,
+}
+
+/// Outcome of one doctor check
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+    /// How to fix a warning or failure
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let icon = match self.status {
+            Status::Pass => "✅",
+            Status::Warn => "⚠️ ",
+            Status::Fail => "❌",
+        };
+        let mut line = format!("{icon} {}: {}", self.name, self.detail);
+        if let Some(hint) = &self.hint {
+            line.push_str(&format!("\n   → {hint}"));
+        }
+        line
+    }
+}
+
+/// Whether a classic token's scopes allow creating releases and uploading assets
+pub fn scopes_allow_release(scopes: &[String]) -> bool {
+    scopes
+        .iter()
+        .any(|scope| scope == "repo" || scope == "public_repo")
+}
+
+/// Checks the environment a release needs, reporting every problem at once
+pub struct Doctor {
+    args: Args,
+}
+
+impl Doctor {
+    pub fn new(args: Args) -> Self {
+        Self { args }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        let mut args = self.args.clone();
+        let mut checks = Vec::new();
+
+        let config = self.check_config(&mut args, &mut checks);
+
+        let repository = match args.parse_repository() {
+            Ok((owner, repo)) => {
+                checks.push(Check::pass("Repository", format!("{owner}/{repo}")));
+                Some((owner, repo))
+            }
+            Err(e) => {
+                checks.push(Check::fail(
+                    "Repository",
+                    format!("{e:#}"),
+                    "Set `repository = \"https://github.com/OWNER/REPO\"` in Cargo.toml or pass --repository OWNER/REPO",
+                ));
+                None
+            }
+        };
+
+        checks.extend(self.check_git(&args, &config));
+        checks.push(Self::check_token(&args, repository.as_ref()).await);
+        checks.extend(Self::check_toolchains(&args, &config));
+        if let Some(check) = Self::check_container_engine(&args, &config) {
+            checks.push(check);
+        }
+
+        for check in &checks {
+            println!("{}", check.render());
+        }
+
+        let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+        if failed > 0 {
+            anyhow::bail!("{failed} check(s) failed");
+        }
+        println!("\n🩺 Ready to release");
+        Ok(())
+    }
+
+    /// Load and merge the configuration, falling back to the defaults if it is invalid
+    fn check_config(&self, args: &mut Args, checks: &mut Vec<Check>) -> Config {
+        let path = Config::path(args);
+        if !path.exists() {
+            checks.push(Check::pass(
+                "Configuration",
+                format!("{} not found, using defaults", path.display()),
+            ));
+            return Config::default();
+        }
+
+        let config = match Config::load(&path, args.profile_name.as_deref()) {
+            Ok(config) => config,
+            Err(e) => {
+                checks.push(Check::fail(
+                    "Configuration",
+                    format!("{}: {e:#}", path.display()),
+                    "Fix the file, or regenerate it with `cargo ghdist init`",
+                ));
+                return Config::default();
+            }
+        };
+        match config.merge_with_args(args) {
+            Ok(()) => checks.push(Check::pass(
+                "Configuration",
+                format!("{} is valid", path.display()),
+            )),
+            Err(e) => checks.push(Check::fail(
+                "Configuration",
+                format!("{}: {e:#}", path.display()),
+                "Fix the [default] section",
+            )),
+        }
+        config
+    }
+
+    fn check_git(&self, args: &Args, config: &Config) -> Vec<Check> {
+        let repo = match Repository::open(".") {
+            Ok(repo) => repo,
+            Err(e) => {
+                return vec![Check::fail(
+                    "Git",
+                    format!("Not a git repository: {}", e.message()),
+                    "Run cargo ghdist from the repository root",
+                )]
+            }
+        };
+
+        let mut checks = Vec::new();
+        match preflight::check(
+            &repo,
+            &config.preflight.allowed_branches,
+            PreflightOverrides {
+                allow_dirty: args.allow_dirty,
+                allow_branch: args.allow_branch,
+            },
+        ) {
+            Ok(()) => checks.push(Check::pass("Git", "Working tree and branch are ready")),
+            Err(e) => checks.push(Check::warn(
+                "Git",
+                e.to_string(),
+                "Releases stop at this check until it is fixed",
+            )),
+        }
+
+        let tag_check = if let Some(tag) = &args.tag {
+            if tagging::tag_exists(&repo, tag) {
+                Check::pass("Tag", format!("{tag} exists"))
+            } else {
+                Check::warn(
+                    "Tag",
+                    format!("{tag} does not exist locally"),
+                    "Create it with `git tag`, or pass --create-tag to create it during the release",
+                )
+            }
+        } else if let Some(tag) = head_tag(&repo) {
+            Check::pass("Tag", format!("HEAD is tagged {tag}"))
+        } else if args.hash {
+            Check::pass("Tag", "A version-sha tag will be generated (--hash)")
+        } else {
+            Check::fail(
+                "Tag",
+                "No tag on HEAD",
+                "Tag HEAD, pass --tag, or use --hash for a continuous release",
+            )
+        };
+        checks.push(tag_check);
+        checks
+    }
+
+    async fn check_token(args: &Args, repository: Option<&(String, String)>) -> Check {
+        const NAME: &str = "GitHub token";
+        let missing_hint =
+            "Export GITHUB_TOKEN, pass --github-token, or run `cargo ghdist auth login`";
+
+        match args.github_app() {
+            Ok(Some(app)) => return Self::check_app(&app, repository).await,
+            Ok(None) => {}
+            Err(e) => {
+                return Check::fail(
+                    NAME,
+                    format!("{e:#}"),
+                    "Point --app-private-key at the app's PEM key",
+                )
+            }
+        }
+
+        let token = match args.github_token.clone().filter(|token| !token.is_empty()) {
+            Some(token) => Some((token, TokenSource::Environment)),
+            None => auth::find_token(),
+        };
+        let Some((token, source)) = token else {
+            return Check::fail(NAME, "No token found", missing_hint);
+        };
+        let Some((owner, repo)) = repository else {
+            return Check::warn(
+                NAME,
+                "Set, but not checked without a repository",
+                "Fix the repository check above",
+            );
+        };
+
+        let access = match GitHubClient::new(Some(token)) {
+            Ok(client) => client.token_access(owner, repo).await,
+            Err(e) => Err(e),
+        };
+        match access {
+            Err(e) => Check::fail(
+                NAME,
+                format!("{e:#}"),
+                "Create a token with the `repo` scope, or Contents: read and write for fine-grained tokens",
+            ),
+            Ok(access) => match access.scopes {
+                Some(scopes) if !scopes_allow_release(&scopes) => Check::fail(
+                    NAME,
+                    format!("Token scopes are [{}]", scopes.join(", ")),
+                    "Add the `repo` scope (or `public_repo` for public repositories)",
+                ),
+                _ if !access.can_push => Check::fail(
+                    NAME,
+                    format!("Token can't push to {owner}/{repo}"),
+                    "Grant the token Contents: read and write on the repository",
+                ),
+                Some(scopes) => Check::pass(
+                    NAME,
+                    format!(
+                        "Token from {source} can release to {owner}/{repo} (scopes: {})",
+                        scopes.join(", ")
+                    ),
+                ),
+                None => Check::pass(
+                    NAME,
+                    format!("Token from {source} can release to {owner}/{repo}"),
+                ),
+            },
+        }
+    }
+
+    /// Mint an installation token, which proves the key is valid and the app is installed
+    async fn check_app(app: &GitHubApp, repository: Option<&(String, String)>) -> Check {
+        const NAME: &str = "GitHub App";
+        let Some((owner, repo)) = repository else {
+            return Check::warn(
+                NAME,
+                format!(
+                    "App {} set, but not checked without a repository",
+                    app.app_id
+                ),
+                "Fix the repository check above",
+            );
+        };
+
+        let token = match GitHubClient::from_app(app) {
+            Ok(client) => client.token(owner, repo).await,
+            Err(e) => Err(e),
+        };
+        match token {
+            Ok(_) => Check::pass(
+                NAME,
+                format!("App {} is installed on {owner}/{repo}", app.app_id),
+            ),
+            Err(e) => Check::fail(
+                NAME,
+                format!("{e:#}"),
+                "Install the app on the repository with Contents: read and write",
+            ),
+        }
+    }
+
+    fn check_toolchains(args: &Args, config: &Config) -> Vec<Check> {
+        if args.from_artifacts.is_some() {
+            return Vec::new();
+        }
+        let requirements = toolchain::requirements(config, args.targets());
+        if requirements.is_empty() {
+            return Vec::new();
+        }
+        if !toolchain::rustup_available() {
+            return vec![Check::warn(
+                "Toolchains",
+                "rustup not found, so installed targets can't be checked",
+                "Install rustup from https://rustup.rs",
+            )];
+        }
+
+        requirements
+            .iter()
+            .map(|requirement| {
+                let name = format!("Target {}", requirement.target);
+                match toolchain::ensure_installed(std::slice::from_ref(requirement), false) {
+                    Ok(()) => Check::pass(
+                        name,
+                        match &requirement.toolchain {
+                            Some(toolchain) => format!("Installed for {toolchain}"),
+                            None => "Installed".to_string(),
+                        },
+                    ),
+                    Err(e) => Check::fail(
+                        name,
+                        "Missing toolchain, target or component",
+                        e.to_string(),
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// `None` unless some target builds in a container
+    fn check_container_engine(args: &Args, config: &Config) -> Option<Check> {
+        if args.from_artifacts.is_some()
+            || !args
+                .targets()
+                .iter()
+                .any(|target| config.container_image_for(target).is_some())
+        {
+            return None;
+        }
+
+        let engine = config.container.engine();
+        let available = Command::new(engine)
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success());
+        Some(if available {
+            Check::pass("Container engine", format!("{engine} is available"))
+        } else {
+            Check::fail(
+                "Container engine",
+                format!("{engine} not found"),
+                format!("Install {engine}, or set engine under [container]"),
+            )
+        })
+    }
+}
+
+/// A tag pointing at HEAD, if any
+fn head_tag(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?.target()?;
+    let tags = repo.tag_names(None).ok()?;
+    let tag = tags.iter().flatten().find(|tag| {
+        repo.revparse_single(tag)
+            .ok()
+            .and_then(|object| object.peel_to_commit().ok())
+            .is_some_and(|commit| commit.id() == head)
+    });
+    tag.map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_allow_release() {
+        let scopes = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(scopes_allow_release(&scopes(&["repo", "workflow"])));
+        assert!(scopes_allow_release(&scopes(&["public_repo"])));
+        assert!(!scopes_allow_release(&scopes(&["read:org", "gist"])));
+        assert!(!scopes_allow_release(&[]));
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            Check::pass("Repository", "owner/tool").render(),
+            "✅ Repository: owner/tool"
+        );
+        assert_eq!(
+            Check::fail("Tag", "No tag on HEAD", "Pass --tag").render(),
+            "❌ Tag: No tag on HEAD\n   → Pass --tag"
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use anyhow::Result;
+use octocrab::models::repos::Release;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::github::{GitHubClient, ReleaseOptions};
+use crate::manifest::{ReleaseManifest, MANIFEST_FILE_NAME};
+use crate::packager;
+use crate::verify;
+
+const CHECKSUM_FRAGMENT_PREFIX: &str = "SHA256SUMS.";
+const MANIFEST_FRAGMENT_PREFIX: &str = "dist-manifest.";
+
+/// Name shared by one runner's fragments, derived from the targets it built
+pub fn fragment_key(manifest: &ReleaseManifest) -> String {
+    let mut targets = manifest
+        .targets
+        .iter()
+        .map(|t| t.target.as_str())
+        .collect::<Vec<_>>();
+    targets.sort();
+    if targets.is_empty() {
+        "assets".to_string()
+    } else {
+        targets.join(".")
+    }
+}
+
+pub fn is_checksum_fragment(name: &str) -> bool {
+    name.starts_with(CHECKSUM_FRAGMENT_PREFIX)
+}
+
+pub fn is_manifest_fragment(name: &str) -> bool {
+    name != MANIFEST_FILE_NAME
+        && name.starts_with(MANIFEST_FRAGMENT_PREFIX)
+        && name.ends_with(".json")
+}
+
+/// Write `SHA256SUMS.<key>` covering this runner's assets
+pub fn write_checksum_fragment(files: &[PathBuf], key: &str, output_dir: &Path) -> Result<PathBuf> {
+    let checksums = packager::generate_checksums(files, output_dir)?;
+    let path = output_dir.join(format!("{CHECKSUM_FRAGMENT_PREFIX}{key}"));
+    fs::rename(checksums, &path)?;
+    Ok(path)
+}
+
+/// Write `dist-manifest.<key>.json` with this runner's targets
+pub fn write_manifest_fragment(
+    manifest: &ReleaseManifest,
+    key: &str,
+    output_dir: &Path,
+) -> Result<PathBuf> {
+    let path = output_dir.join(format!("{MANIFEST_FRAGMENT_PREFIX}{key}.json"));
+    fs::write(&path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(path)
+}
+
+/// Combine `SHA256SUMS` fragments into one file sorted by asset name
+///
+/// An asset listed twice (e.g. after a job was re-run) keeps its last checksum.
+pub fn merge_checksums(fragments: &[String]) -> String {
+    let mut lines = BTreeMap::new();
+    for line in fragments.iter().flat_map(|f| f.lines()) {
+        if let Some((_, name)) = line.split_once("  ") {
+            lines.insert(name.to_string(), line.to_string());
+        }
+    }
+    lines.into_values().map(|line| line + "\n").collect()
+}
+
+/// Combine manifest fragments, keeping the last entry per target, sorted by target
+pub fn merge_manifests(tag: &str, fragments: Vec<ReleaseManifest>) -> ReleaseManifest {
+    let targets = fragments
+        .into_iter()
+        .flat_map(|fragment| fragment.targets)
+        .map(|target| (target.target.clone(), target))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut manifest = ReleaseManifest::new(tag);
+    manifest.targets = targets.into_values().collect();
+    manifest
+}
+
+/// The binary archive of each target, among the release `assets` on disk
+pub fn target_archives(manifest: &ReleaseManifest, assets: &[PathBuf]) -> Vec<(String, PathBuf)> {
+    manifest
+        .targets
+        .iter()
+        .filter_map(|target| {
+            let archive = target.assets.iter().find(|name| {
+                let stem = name
+                    .strip_suffix(".tar.gz")
+                    .or_else(|| name.strip_suffix(".zip"));
+                stem.is_some_and(|stem| !stem.ends_with("-lib"))
+            })?;
+            let path = assets
+                .iter()
+                .find(|path| path.file_name().is_some_and(|n| n == archive.as_str()))?;
+            Some((target.target.clone(), path.clone()))
+        })
+        .collect()
+}
+
+/// Unpack the binaries of the Linux targets from their archives into
+/// `{dir}/<target>/`, for the container image the runners didn't build
+pub fn unpack_linux_binaries(
+    manifest: &ReleaseManifest,
+    target_archives: &[(String, PathBuf)],
+    dir: &Path,
+) -> Result<Vec<(String, Vec<PathBuf>)>> {
+    let mut unpacked = Vec::new();
+    for (target, archive) in target_archives.iter().filter(|(t, _)| t.contains("linux")) {
+        let names = manifest
+            .targets
+            .iter()
+            .filter(|t| &t.target == target)
+            .flat_map(|t| t.binaries.iter().map(|b| b.name.as_str()))
+            .collect::<Vec<_>>();
+        let file_name = archive.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let entries = verify::read_archive(file_name, &fs::read(archive)?)?.unwrap_or_default();
+
+        let target_dir = dir.join(target);
+        fs::create_dir_all(&target_dir)?;
+        let mut binaries = Vec::new();
+        for (path, content) in entries {
+            let Some(name) = Path::new(&path).file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !names.contains(&name) {
+                continue;
+            }
+            let binary = target_dir.join(name);
+            fs::write(&binary, content)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&binary, fs::Permissions::from_mode(0o755))?;
+            }
+            binaries.push(binary);
+        }
+        unpacked.push((target.clone(), binaries));
+    }
+    Ok(unpacked)
+}
+
+/// Get the draft release matrix jobs upload to, creating it if needed
+///
+/// Jobs that start together may each create a draft, since drafts are not tied
+/// to the tag yet. Every job then settles on the oldest draft for the tag and
+/// deletes its own duplicate, so all uploads end up in one release.
+pub async fn draft_release(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    tag: &str,
+    target_commitish: Option<&str>,
+) -> Result<Release> {
+    // find_release picks the oldest draft, the one every job settles on
+    if let Some(release) = client.find_release(owner, repo, tag).await? {
+        if !release.draft {
+            anyhow::bail!("Release {tag} is already published, so it can't take more uploads");
+        }
+        return Ok(release);
+    }
+
+    let created = client
+        .create_release(
+            owner,
+            repo,
+            tag,
+            &ReleaseOptions {
+                draft: true,
+                target_commitish,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    let oldest = client
+        .list_releases(owner, repo)
+        .await?
+        .into_iter()
+        .filter(|release| release.draft && release.tag_name == tag)
+        .min_by_key(|release| release.id.0);
+    match oldest {
+        Some(oldest) if oldest.id != created.id => {
+            tracing::info!("Another job created the draft for {} first, using it", tag);
+            client.delete_release(owner, repo, created.id.0).await?;
+            Ok(oldest)
+        }
+        _ => Ok(created),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::ArchiveFormat;
+    use crate::manifest::{BinarySize, TargetManifest};
+    use crate::signing::SignatureStatus;
+    use tempfile::tempdir;
+
+    fn target(name: &str, asset: &str) -> TargetManifest {
+        TargetManifest {
+            target: name.to_string(),
+            assets: vec![asset.to_string()],
+            binaries: Vec::new(),
+            signature: SignatureStatus::NotApplicable,
+            min_glibc: None,
+        }
+    }
+
+    #[test]
+    fn test_fragment_names() {
+        let mut manifest = ReleaseManifest::new("v1.0.0");
+        assert_eq!(fragment_key(&manifest), "assets");
+
+        manifest.targets = vec![
+            target("x86_64-unknown-linux-gnu", "a.tar.gz"),
+            target("aarch64-unknown-linux-gnu", "b.tar.gz"),
+        ];
+        let key = fragment_key(&manifest);
+        assert_eq!(key, "aarch64-unknown-linux-gnu.x86_64-unknown-linux-gnu");
+
+        let dir = tempdir().unwrap();
+        let path = write_manifest_fragment(&manifest, &key, dir.path()).unwrap();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(is_manifest_fragment(name));
+        assert!(!is_manifest_fragment(MANIFEST_FILE_NAME));
+        assert!(!is_checksum_fragment(name));
+
+        let asset = dir.path().join("a.tar.gz");
+        fs::write(&asset, "archive").unwrap();
+        let path = write_checksum_fragment(&[asset], &key, dir.path()).unwrap();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(is_checksum_fragment(name));
+        assert!(!is_checksum_fragment("SHA256SUMS"));
+        assert!(!dir.path().join("SHA256SUMS").exists());
+        assert!(fs::read_to_string(path).unwrap().ends_with("  a.tar.gz\n"));
+    }
+
+    #[test]
+    fn test_unpack_linux_binaries() {
+        let dir = tempdir().unwrap();
+        let binary = dir.path().join("tool");
+        fs::write(&binary, b"binary").unwrap();
+        let name = "tool-x86_64-unknown-linux-gnu-v1.0.0";
+        let archive =
+            packager::create_archive(&[binary], dir.path(), name, ArchiveFormat::Tgz).unwrap();
+        let library = dir.path().join(format!("{name}-lib.tar.gz"));
+        fs::write(&library, b"library").unwrap();
+
+        let mut manifest = ReleaseManifest::new("v1.0.0");
+        let mut linux = target("x86_64-unknown-linux-gnu", &format!("{name}-lib.tar.gz"));
+        linux.assets.insert(0, format!("{name}.tar.gz"));
+        linux.binaries = vec![BinarySize {
+            name: "tool".to_string(),
+            size: 6,
+        }];
+        manifest.targets = vec![linux, target("aarch64-apple-darwin", "missing.zip")];
+
+        let archives = target_archives(&manifest, &[library, archive.clone()]);
+        assert_eq!(
+            archives,
+            vec![("x86_64-unknown-linux-gnu".to_string(), archive)]
+        );
+
+        let out = dir.path().join("binaries");
+        let unpacked = unpack_linux_binaries(&manifest, &archives, &out).unwrap();
+        let tool = out.join("x86_64-unknown-linux-gnu").join("tool");
+        assert_eq!(
+            unpacked,
+            vec![("x86_64-unknown-linux-gnu".to_string(), vec![tool.clone()])]
+        );
+        assert_eq!(fs::read(tool).unwrap(), b"binary");
+    }
+
+    #[test]
+    fn test_merge_checksums() {
+        let merged = merge_checksums(&[
+            "bbb  tool-linux.tar.gz\n".to_string(),
+            "aaa  tool-macos.tar.gz\nold  dist-manifest.json\n".to_string(),
+            "new  dist-manifest.json\n".to_string(),
+        ]);
+        assert_eq!(
+            merged,
+            "new  dist-manifest.json\nbbb  tool-linux.tar.gz\naaa  tool-macos.tar.gz\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_manifests() {
+        let mut linux = ReleaseManifest::new("v1.0.0");
+        linux.targets = vec![target("x86_64-unknown-linux-gnu", "old.tar.gz")];
+        let mut macos = ReleaseManifest::new("v1.0.0");
+        macos.targets = vec![target("aarch64-apple-darwin", "mac.tar.gz")];
+        let mut rerun = ReleaseManifest::new("v1.0.0");
+        rerun.targets = vec![target("x86_64-unknown-linux-gnu", "new.tar.gz")];
+
+        let merged = merge_manifests("v1.0.0", vec![linux, macos, rerun]);
+        assert_eq!(merged.tag, "v1.0.0");
+        assert_eq!(
+            merged
+                .targets
+                .iter()
+                .map(|t| (t.target.as_str(), t.assets[0].as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("aarch64-apple-darwin", "mac.tar.gz"),
+                ("x86_64-unknown-linux-gnu", "new.tar.gz")
+            ]
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use semver::Version;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use toml_edit::DocumentMut;
+
+use crate::bump;
+use crate::config::GatesConfig;
+use crate::hooks;
+use crate::ui;
+
+/// Whether going from `previous` to `current` is a major bump under Cargo's rules
+///
+/// For `0.x` versions the minor component acts as the major one.
+pub fn is_major_bump(previous: &Version, current: &Version) -> bool {
+    if previous.major == 0 {
+        current.major > 0 || current.minor > previous.minor
+    } else {
+        current.major > previous.major
+    }
+}
+
+/// Names of workspace packages that have a library target
+pub fn library_packages(root: &Path) -> Result<Vec<String>> {
+    let root_doc = fs::read_to_string(root.join("Cargo.toml"))
+        .context("Failed to read Cargo.toml")?
+        .parse::<DocumentMut>()?;
+
+    let mut dirs = vec![root.to_path_buf()];
+    dirs.extend(bump::member_dirs(root, &root_doc)?);
+
+    let mut libraries = Vec::new();
+    for dir in dirs {
+        let Ok(content) = fs::read_to_string(dir.join("Cargo.toml")) else {
+            continue;
+        };
+        let doc = content.parse::<DocumentMut>()?;
+        let Some(name) = doc
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+        else {
+            continue;
+        };
+        if doc.contains_key("lib") || dir.join("src/lib.rs").exists() {
+            libraries.push(name.to_string());
+        }
+    }
+
+    Ok(libraries)
+}
+
+/// Run `cargo semver-checks` for `packages` against `baseline_tag`
+pub fn run_semver_checks(packages: &[String], baseline_tag: &str) -> Result<()> {
+    let mut cmd = Command::new("cargo");
+    cmd.arg("semver-checks")
+        .arg("check-release")
+        .arg("--baseline-rev")
+        .arg(baseline_tag);
+    for package in packages {
+        cmd.arg("--package").arg(package);
+    }
+
+    let status = cmd.stdout(ui::child_stdout()).status().context(
+        "Failed to run cargo semver-checks. Install it with `cargo install cargo-semver-checks`",
+    )?;
+    if !status.success() {
+        anyhow::bail!(
+            "cargo semver-checks found breaking changes since {baseline_tag}. \
+             Bump the major version or disable [gates] semver_checks"
+        );
+    }
+
+    Ok(())
+}
+
+/// Host target triple as reported by `rustc -vV`
+pub fn host_triple() -> Result<String> {
+    let output = Command::new("rustc")
+        .arg("-vV")
+        .output()
+        .context("Failed to run rustc")?;
+    String::from_utf8(output.stdout)?
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(|host| host.trim().to_string())
+        .context("rustc -vV did not report a host triple")
+}
+
+/// Arguments for `cargo test`, optionally for a specific target
+pub fn cargo_test_args(config: &GatesConfig, target: Option<&str>) -> Vec<String> {
+    let mut args = vec!["test".to_string()];
+    if let Some(profile) = &config.test_profile {
+        args.push("--profile".to_string());
+        args.push(profile.clone());
+    }
+    if !config.test_features.is_empty() {
+        args.push("--features".to_string());
+        args.push(config.test_features.join(","));
+    }
+    if let Some(target) = target {
+        args.push("--target".to_string());
+        args.push(target.to_string());
+    }
+    args
+}
+
+/// Release targets whose test binaries can run on `host`
+pub fn runnable_targets<'a>(targets: &'a [String], host: &str) -> Vec<&'a str> {
+    targets
+        .iter()
+        .map(String::as_str)
+        .filter(|target| *target == host)
+        .collect()
+}
+
+fn run_checked(cmd: &mut Command, description: &str) -> Result<()> {
+    tracing::info!("Running {}", description);
+    let status = cmd
+        .stdout(ui::child_stdout())
+        .status()
+        .with_context(|| format!("Failed to run {description}"))?;
+    if !status.success() {
+        anyhow::bail!("{description} failed, aborting the release");
+    }
+    Ok(())
+}
+
+/// Run the configured test command, or `cargo test` (per runnable target if enabled)
+pub fn run_tests(config: &GatesConfig, targets: &[String]) -> Result<()> {
+    if let Some(command) = &config.test_command {
+        return run_checked(&mut hooks::shell_command(command), command);
+    }
+
+    let mut runs = vec![None];
+    if config.test_per_target {
+        let host = host_triple()?;
+        let runnable = runnable_targets(targets, &host);
+        for target in targets.iter().filter(|t| !runnable.contains(&t.as_str())) {
+            tracing::info!("Skipping tests for {}: cannot run on {}", target, host);
+        }
+        runs.extend(runnable.into_iter().map(Some));
+    }
+
+    for target in runs {
+        let args = cargo_test_args(config, target);
+        run_checked(
+            Command::new("cargo").args(&args),
+            &format!("cargo {}", args.join(" ")),
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_major_bump() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert!(is_major_bump(&v("1.4.2"), &v("2.0.0")));
+        assert!(!is_major_bump(&v("1.4.2"), &v("1.5.0")));
+        assert!(is_major_bump(&v("0.4.2"), &v("0.5.0")));
+        assert!(!is_major_bump(&v("0.4.2"), &v("0.4.3")));
+        assert!(is_major_bump(&v("0.4.2"), &v("1.0.0")));
+    }
+
+    #[test]
+    fn test_cargo_test_args() {
+        assert_eq!(cargo_test_args(&GatesConfig::default(), None), vec!["test"]);
+
+        let config = GatesConfig {
+            test_profile: Some("release".to_string()),
+            test_features: vec!["a".to_string(), "b".to_string()],
+            ..GatesConfig::default()
+        };
+        assert_eq!(
+            cargo_test_args(&config, Some("x86_64-unknown-linux-gnu")).join(" "),
+            "test --profile release --features a,b --target x86_64-unknown-linux-gnu"
+        );
+    }
+
+    #[test]
+    fn test_runnable_targets() {
+        let targets = vec![
+            "x86_64-unknown-linux-gnu".to_string(),
+            "aarch64-unknown-linux-gnu".to_string(),
+        ];
+        assert_eq!(
+            runnable_targets(&targets, "x86_64-unknown-linux-gnu"),
+            vec!["x86_64-unknown-linux-gnu"]
+        );
+        assert!(runnable_targets(&targets, "aarch64-apple-darwin").is_empty());
+        assert!(host_triple().unwrap().contains('-'));
+    }
+
+    #[test]
+    fn test_run_custom_test_command() {
+        let passing = GatesConfig {
+            test_command: Some("exit 0".to_string()),
+            ..GatesConfig::default()
+        };
+        assert!(run_tests(&passing, &[]).is_ok());
+
+        let failing = GatesConfig {
+            test_command: Some("exit 3".to_string()),
+            ..GatesConfig::default()
+        };
+        assert!(run_tests(&failing, &[]).is_err());
+    }
+
+    #[test]
+    fn test_library_packages() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"core\", \"cli\", \"explicit\"]\n",
+        )
+        .unwrap();
+        for (dir, manifest, file) in [
+            ("core", "[package]\nname = \"core\"\n", "src/lib.rs"),
+            ("cli", "[package]\nname = \"cli\"\n", "src/main.rs"),
+            (
+                "explicit",
+                "[package]\nname = \"explicit\"\n\n[lib]\npath = \"lib.rs\"\n",
+                "lib.rs",
+            ),
+        ] {
+            fs::create_dir_all(root.join(dir).join("src")).unwrap();
+            fs::write(root.join(dir).join("Cargo.toml"), manifest).unwrap();
+            fs::write(root.join(dir).join(file), "").unwrap();
+        }
+
+        assert_eq!(library_packages(root).unwrap(), vec!["core", "explicit"]);
+    }
+}
@@ -1,45 +1,253 @@
+use crate::cli::{LatestMode, Provider};
 use crate::error::{GhDistError, Result as GhResult};
+use crate::packager;
+use crate::pull_requests::PullRequest;
+use crate::remote_build::{RunArtifact, WorkflowRun};
+use crate::resume::UploadedAsset;
 use anyhow::Result;
-use octocrab::{models::repos::Release, Octocrab};
+use async_trait::async_trait;
+use cargo_ghinstall::progress::Progress;
+use futures_util::TryStreamExt;
+use octocrab::{
+    models::{repos::Release, AppId, InstallationId},
+    params::repos::Reference,
+    repos::releases::MakeLatest,
+    Octocrab,
+};
 use reqwest::Client;
+use secrecy::ExposeSecret;
 use std::path::Path;
+use tokio::sync::OnceCell;
+use tokio_util::io::ReaderStream;
+
+/// GitHub rejects release assets of 2 GiB or more
+pub const MAX_ASSET_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Settings applied when creating or updating a release
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseOptions<'a> {
+    pub draft: bool,
+    pub prerelease: bool,
+    pub target_commitish: Option<&'a str>,
+    pub body: Option<&'a str>,
+    pub make_latest: Option<LatestMode>,
+    /// Existing Discussions category to open a thread in for the release
+    pub discussion_category: Option<&'a str>,
+}
+
+/// Fail before uploading an asset GitHub would reject for its size
+pub fn check_asset_size(name: &str, size: u64) -> GhResult<()> {
+    if size >= MAX_ASSET_SIZE {
+        return Err(GhDistError::AssetUpload(format!(
+            "{name} is {:.2} GiB, over GitHub's 2 GiB limit for release assets",
+            size as f64 / (1024.0 * 1024.0 * 1024.0)
+        )));
+    }
+    Ok(())
+}
+
+fn make_latest(mode: LatestMode) -> MakeLatest {
+    match mode {
+        LatestMode::True => MakeLatest::True,
+        LatestMode::False => MakeLatest::False,
+        LatestMode::Legacy => MakeLatest::Legacy,
+    }
+}
+
+/// What the configured token may do in a repository
+#[derive(Debug, Clone)]
+pub struct TokenAccess {
+    /// Scopes of a classic token; fine-grained and app tokens don't report any
+    pub scopes: Option<Vec<String>>,
+    /// Whether the token can push to the repository, which creating releases needs
+    pub can_push: bool,
+}
+
+/// Release created or updated through a [`ReleaseProvider`]
+#[derive(Debug, Clone)]
+pub struct ProviderRelease {
+    /// Numeric release ID, 0 on GitLab which keys releases by tag
+    pub id: u64,
+    /// Web page of the release
+    pub url: String,
+}
+
+/// A forge that releases are published to
+#[async_trait]
+pub trait ReleaseProvider: Send + Sync {
+    fn provider(&self) -> Provider;
+
+    /// Create the release for `tag`, or update it if it already exists
+    async fn create_or_update_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        options: &ReleaseOptions<'_>,
+    ) -> Result<ProviderRelease>;
+
+    /// Attach a file to the release, replacing an earlier asset with the same name;
+    /// returns `false` when an identical asset was already attached
+    async fn attach_asset(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        release: &ProviderRelease,
+        path: &Path,
+    ) -> Result<bool>;
+}
+
+/// GitHub App credentials, exchanged for installation tokens
+#[derive(Debug, Clone)]
+pub struct GitHubApp {
+    pub app_id: u64,
+    /// PEM-encoded private key
+    pub private_key: String,
+    /// Installation to act as; looked up from the repository when unset
+    pub installation_id: Option<u64>,
+}
+
+/// Client authenticated as a GitHub App, and the installation client it resolves to
+struct AppAuth {
+    app: Octocrab,
+    installation_id: Option<u64>,
+    installation: OnceCell<Octocrab>,
+}
+
+/// Installation tokens are refreshed once they have less than this left
+const TOKEN_REFRESH_MINUTES: i64 = 5;
 
 pub struct GitHubClient {
     octocrab: Octocrab,
     http_client: Client,
     token: Option<String>,
+    app: Option<AppAuth>,
+    /// Show upload progress (a bar on a terminal, log lines otherwise)
+    progress: bool,
 }
 
 impl GitHubClient {
     pub fn new(token: Option<String>) -> Result<Self> {
-        let token = token.or_else(|| std::env::var("GITHUB_TOKEN").ok());
+        let token = token.or_else(|| cargo_ghinstall::auth::find_token().map(|(token, _)| token));
         let octocrab = if let Some(token) = token.clone() {
             Octocrab::builder().personal_token(token).build()?
         } else {
             Octocrab::builder().build()?
         };
 
-        let http_client = Client::builder()
-            .user_agent("cargo-ghdist")
-            .timeout(std::time::Duration::from_secs(300))
-            .build()?;
-
         Ok(Self {
             octocrab,
-            http_client,
+            http_client: Self::http_client()?,
             token,
+            app: None,
+            progress: true,
+        })
+    }
+
+    /// Client that authenticates as a GitHub App installation
+    ///
+    /// The installation is resolved on the first request, from the repository it targets.
+    pub fn from_app(app: &GitHubApp) -> Result<Self> {
+        let key = jsonwebtoken::EncodingKey::from_rsa_pem(app.private_key.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Invalid GitHub App private key: {e}"))?;
+        let app_client = Octocrab::builder().app(AppId(app.app_id), key).build()?;
+
+        Ok(Self {
+            octocrab: app_client.clone(),
+            http_client: Self::http_client()?,
+            token: None,
+            app: Some(AppAuth {
+                app: app_client,
+                installation_id: app.installation_id,
+                installation: OnceCell::new(),
+            }),
+            progress: true,
+        })
+    }
+
+    /// Client for the GitHub App in `args` if one is configured, the token otherwise
+    pub fn from_args(args: &crate::cli::Args) -> Result<Self> {
+        match args.github_app()? {
+            Some(app) => Self::from_app(&app),
+            None => Self::new(args.github_token.clone()),
+        }
+    }
+
+    fn http_client() -> Result<Client> {
+        Ok(Client::builder()
+            .user_agent("cargo-ghdist")
+            .timeout(std::time::Duration::from_secs(300))
+            .build()?)
+    }
+
+    /// API client for requests against `owner/repo`
+    async fn api(&self, owner: &str, repo: &str) -> Result<Octocrab> {
+        match &self.app {
+            Some(app) => Ok(Self::installation(app, owner, repo).await?.clone()),
+            None => Ok(self.octocrab.clone()),
+        }
+    }
+
+    async fn installation<'a>(app: &'a AppAuth, owner: &str, repo: &str) -> Result<&'a Octocrab> {
+        app.installation
+            .get_or_try_init(|| async {
+                let id = match app.installation_id {
+                    Some(id) => InstallationId(id),
+                    None => {
+                        app.app
+                            .apps()
+                            .get_repository_installation(owner, repo)
+                            .await
+                            .map_err(|e| {
+                                anyhow::anyhow!(
+                                    "GitHub App is not installed on {owner}/{repo}: {e}"
+                                )
+                            })?
+                            .id
+                    }
+                };
+                Ok(app.app.installation(id)?)
+            })
+            .await
+    }
+
+    /// Token for raw requests against `owner/repo`, if any
+    ///
+    /// A GitHub App gets a fresh installation token whenever the cached one is about to expire.
+    pub async fn optional_token(&self, owner: &str, repo: &str) -> Result<Option<String>> {
+        let Some(app) = &self.app else {
+            return Ok(self.token.clone());
+        };
+        let token = Self::installation(app, owner, repo)
+            .await?
+            .installation_token_with_buffer(chrono::Duration::minutes(TOKEN_REFRESH_MINUTES))
+            .await?;
+        Ok(Some(token.expose_secret().to_string()))
+    }
+
+    /// Token for raw requests against `owner/repo`, for uploads and git pushes
+    pub async fn token(&self, owner: &str, repo: &str) -> Result<String> {
+        self.optional_token(owner, repo).await?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "GitHub token not found. Set GITHUB_TOKEN or run `cargo ghdist auth login`"
+            )
         })
     }
 
+    /// Turn upload progress reporting on or off
+    pub fn with_progress(mut self, enabled: bool) -> Self {
+        self.progress = enabled;
+        self
+    }
+
     /// Create a new release or update existing one
     pub async fn create_release(
         &self,
         owner: &str,
         repo: &str,
         tag: &str,
-        draft: bool,
-        target_commitish: Option<&str>,
-        body: Option<&str>,
+        options: &ReleaseOptions<'_>,
     ) -> GhResult<Release> {
         // Check if release already exists
         tracing::debug!(
@@ -48,67 +256,33 @@ impl GitHubClient {
             repo,
             tag
         );
-        match self
-            .octocrab
-            .repos(owner, repo)
-            .releases()
-            .get_by_tag(tag)
+        let octocrab = self
+            .api(owner, repo)
             .await
-        {
-            Ok(release) => {
+            .map_err(|e| GhDistError::ReleaseCreation(format!("{e:#}")))?;
+        let repos = octocrab.repos(owner, repo);
+        let releases = repos.releases();
+        // find_release also sees drafts, so re-running against a draft updates it
+        let release = match self.find_release(owner, repo, tag).await {
+            Ok(Some(release)) => {
                 tracing::info!("Release {} already exists, will update it", tag);
-                let result = match (target_commitish, body) {
-                    (Some(target), Some(body_text)) => {
-                        self.octocrab
-                            .repos(owner, repo)
-                            .releases()
-                            .update(release.id.0)
-                            .tag_name(tag)
-                            .draft(draft)
-                            .name(tag)
-                            .target_commitish(target)
-                            .body(body_text)
-                            .send()
-                            .await
-                    }
-                    (Some(target), None) => {
-                        self.octocrab
-                            .repos(owner, repo)
-                            .releases()
-                            .update(release.id.0)
-                            .tag_name(tag)
-                            .draft(draft)
-                            .name(tag)
-                            .target_commitish(target)
-                            .send()
-                            .await
-                    }
-                    (None, Some(body_text)) => {
-                        self.octocrab
-                            .repos(owner, repo)
-                            .releases()
-                            .update(release.id.0)
-                            .tag_name(tag)
-                            .draft(draft)
-                            .name(tag)
-                            .body(body_text)
-                            .send()
-                            .await
-                    }
-                    (None, None) => {
-                        self.octocrab
-                            .repos(owner, repo)
-                            .releases()
-                            .update(release.id.0)
-                            .tag_name(tag)
-                            .draft(draft)
-                            .name(tag)
-                            .send()
-                            .await
-                    }
-                };
+                let mut builder = releases
+                    .update(release.id.0)
+                    .tag_name(tag)
+                    .draft(options.draft)
+                    .prerelease(options.prerelease)
+                    .name(tag);
+                if let Some(target) = options.target_commitish {
+                    builder = builder.target_commitish(target);
+                }
+                if let Some(body_text) = options.body {
+                    builder = builder.body(body_text);
+                }
+                if let Some(latest) = options.make_latest {
+                    builder = builder.make_latest(make_latest(latest));
+                }
 
-                match result {
+                match builder.send().await {
                     Ok(release) => {
                         tracing::info!("Successfully updated release: {}", tag);
                         Ok(release)
@@ -121,64 +295,36 @@ impl GitHubClient {
                     }
                 }
             }
-            Err(e) => {
+            lookup => {
                 // Create new release
-                tracing::info!("Release {} doesn't exist, creating new one: {:?}", tag, e);
+                tracing::info!(
+                    "Release {} doesn't exist, creating new one: {:?}",
+                    tag,
+                    lookup.err()
+                );
                 tracing::debug!(
-                    "Creating release with: draft={}, target_commitish={:?}",
-                    draft,
-                    target_commitish
+                    "Creating release with: draft={}, target_commitish={:?}, make_latest={:?}",
+                    options.draft,
+                    options.target_commitish,
+                    options.make_latest
                 );
 
-                // Build and send the release in one expression to avoid borrowing issues
-                let result = match (target_commitish, body) {
-                    (Some(target), Some(body_text)) => {
-                        self.octocrab
-                            .repos(owner, repo)
-                            .releases()
-                            .create(tag)
-                            .draft(draft)
-                            .name(tag)
-                            .target_commitish(target)
-                            .body(body_text)
-                            .send()
-                            .await
-                    }
-                    (Some(target), None) => {
-                        self.octocrab
-                            .repos(owner, repo)
-                            .releases()
-                            .create(tag)
-                            .draft(draft)
-                            .name(tag)
-                            .target_commitish(target)
-                            .send()
-                            .await
-                    }
-                    (None, Some(body_text)) => {
-                        self.octocrab
-                            .repos(owner, repo)
-                            .releases()
-                            .create(tag)
-                            .draft(draft)
-                            .name(tag)
-                            .body(body_text)
-                            .send()
-                            .await
-                    }
-                    (None, None) => {
-                        self.octocrab
-                            .repos(owner, repo)
-                            .releases()
-                            .create(tag)
-                            .draft(draft)
-                            .name(tag)
-                            .send()
-                            .await
-                    }
-                };
+                let mut builder = releases
+                    .create(tag)
+                    .draft(options.draft)
+                    .prerelease(options.prerelease)
+                    .name(tag);
+                if let Some(target) = options.target_commitish {
+                    builder = builder.target_commitish(target);
+                }
+                if let Some(body_text) = options.body {
+                    builder = builder.body(body_text);
+                }
+                if let Some(latest) = options.make_latest {
+                    builder = builder.make_latest(make_latest(latest));
+                }
 
-                match result {
+                match builder.send().await {
                     Ok(release) => {
                         tracing::info!("Successfully created release: {}", tag);
                         Ok(release)
@@ -191,9 +337,56 @@ impl GitHubClient {
                     }
                 }
             }
+        }?;
+
+        match options.discussion_category {
+            Some(category) => self
+                .set_discussion_category(owner, repo, release.id.0, category)
+                .await
+                .map_err(|e| {
+                    GhDistError::ReleaseUpdate(format!(
+                        "Failed to link release {tag} to discussion category {category}: {e}"
+                    ))
+                }),
+            None => Ok(release),
         }
     }
 
+    /// Link a release to a Discussions category (octocrab's builders lack this field)
+    async fn set_discussion_category(
+        &self,
+        owner: &str,
+        repo: &str,
+        release_id: u64,
+        category: &str,
+    ) -> Result<Release> {
+        let url = format!("https://api.github.com/repos/{owner}/{repo}/releases/{release_id}");
+
+        let response = self
+            .http_client
+            .patch(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.token(owner, repo).await?),
+            )
+            .header("Accept", "application/vnd.github+json")
+            .json(&serde_json::json!({ "discussion_category_name": category }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            anyhow::bail!("{status} - {error_text}");
+        }
+
+        tracing::info!("Opened a discussion in category: {}", category);
+        Ok(response.json().await?)
+    }
+
     /// Upload an asset to a release
     pub async fn upload_asset(
         &self,
@@ -208,10 +401,15 @@ impl GitHubClient {
             .and_then(|n| n.to_str())
             .ok_or_else(|| GhDistError::AssetUpload("Invalid asset path".to_string()))?;
 
-        tracing::info!("Uploading asset: {}", asset_name);
+        // Stream the file rather than reading archives of hundreds of MB into memory
+        let file = tokio::fs::File::open(asset_path).await?;
+        let size = file.metadata().await?.len();
+        check_asset_size(asset_name, size)?;
 
-        // Read file content
-        let file_content = tokio::fs::read(asset_path).await?;
+        tracing::info!("Uploading asset: {} ({} bytes)", asset_name, size);
+        let progress = Progress::new(asset_name, Some(size), self.progress);
+        let counter = progress.clone();
+        let body = ReaderStream::new(file).inspect_ok(move |chunk| counter.inc(chunk.len() as u64));
 
         // Upload using GitHub API
         let url = format!(
@@ -222,17 +420,21 @@ impl GitHubClient {
             .http_client
             .post(&url)
             .header("Content-Type", content_type)
+            .header("Content-Length", size)
             .header(
                 "Authorization",
                 format!(
                     "Bearer {}",
-                    self.get_token()
-                        .map_err(|_| GhDistError::AssetUpload("No GitHub token".to_string()))?
+                    self.token(owner, repo)
+                        .await
+                        .map_err(|e| GhDistError::AssetUpload(format!("{e:#}")))?
                 ),
             )
-            .body(file_content)
+            .body(reqwest::Body::wrap_stream(body))
             .send()
-            .await?;
+            .await;
+        progress.finish();
+        let response = response?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -249,6 +451,44 @@ impl GitHubClient {
         Ok(())
     }
 
+    /// Upload an asset, deleting any asset with the same name among `existing`
+    /// (the release's assets, listed once by the caller) first
+    ///
+    /// Returns `false` without uploading when the existing asset is complete
+    /// and GitHub's digest matches the local file.
+    pub async fn replace_asset(
+        &self,
+        owner: &str,
+        repo: &str,
+        release_id: u64,
+        asset_path: &Path,
+        existing: &[UploadedAsset],
+    ) -> Result<bool> {
+        let asset_name = asset_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+
+        if let Some(existing) = existing.iter().find(|asset| asset.name == asset_name) {
+            if existing.is_identical(&packager::sha256_file(asset_path)?) {
+                tracing::info!("{} is already uploaded, skipping", asset_name);
+                return Ok(false);
+            }
+            tracing::info!("Deleting existing asset: {}", asset_name);
+            self.delete_asset(owner, repo, existing.id).await?;
+        }
+
+        self.upload_asset(
+            owner,
+            repo,
+            release_id,
+            asset_path,
+            get_content_type(asset_path),
+        )
+        .await?;
+        Ok(true)
+    }
+
     /// Delete an existing asset from a release
     pub async fn delete_asset(&self, owner: &str, repo: &str, asset_id: u64) -> Result<()> {
         let url = format!("https://api.github.com/repos/{owner}/{repo}/releases/assets/{asset_id}");
@@ -256,7 +496,10 @@ impl GitHubClient {
         let response = self
             .http_client
             .delete(&url)
-            .header("Authorization", format!("Bearer {}", self.get_token()?))
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.token(owner, repo).await?),
+            )
             .send()
             .await?;
 
@@ -276,9 +519,10 @@ impl GitHubClient {
         target_commitish: Option<&str>,
         previous_tag: Option<&str>,
     ) -> Result<String> {
+        let octocrab = self.api(owner, repo).await?;
         let release_notes = match (target_commitish, previous_tag) {
             (Some(target), Some(prev_tag)) => {
-                self.octocrab
+                octocrab
                     .repos(owner, repo)
                     .releases()
                     .generate_release_notes(tag)
@@ -288,7 +532,7 @@ impl GitHubClient {
                     .await?
             }
             (Some(target), None) => {
-                self.octocrab
+                octocrab
                     .repos(owner, repo)
                     .releases()
                     .generate_release_notes(tag)
@@ -297,7 +541,7 @@ impl GitHubClient {
                     .await?
             }
             (None, Some(prev_tag)) => {
-                self.octocrab
+                octocrab
                     .repos(owner, repo)
                     .releases()
                     .generate_release_notes(tag)
@@ -306,7 +550,7 @@ impl GitHubClient {
                     .await?
             }
             (None, None) => {
-                self.octocrab
+                octocrab
                     .repos(owner, repo)
                     .releases()
                     .generate_release_notes(tag)
@@ -318,24 +562,165 @@ impl GitHubClient {
         Ok(release_notes.body)
     }
 
-    /// Get the GitHub token from the client
-    fn get_token(&self) -> Result<&str> {
-        self.token.as_deref().ok_or_else(|| {
-            anyhow::anyhow!("GitHub token not found. Set GITHUB_TOKEN environment variable")
-        })
+    /// Resolve the GitHub login of a commit's author, if the email is linked to an account
+    pub async fn get_commit_author_login(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> Result<Option<String>> {
+        let commit = self
+            .api(owner, repo)
+            .await?
+            .commits(owner, repo)
+            .get(sha)
+            .await?;
+        Ok(commit.author.map(|author| author.login))
     }
 
-    /// Check if an asset already exists in a release
-    pub async fn asset_exists(
+    /// List pull requests merged after `since` (RFC 3339), oldest first
+    pub async fn list_merged_pull_requests(
         &self,
         owner: &str,
         repo: &str,
-        release_id: u64,
+        since: Option<&str>,
+    ) -> Result<Vec<PullRequest>> {
+        let mut query = format!("repo:{owner}/{repo} is:pr is:merged");
+        if let Some(since) = since {
+            query.push_str(&format!(" merged:>{since}"));
+        }
+
+        let octocrab = self.api(owner, repo).await?;
+        let page = octocrab
+            .search()
+            .issues_and_pull_requests(&query)
+            .sort("created")
+            .order("asc")
+            .per_page(100)
+            .send()
+            .await?;
+
+        Ok(octocrab
+            .all_pages(page)
+            .await?
+            .into_iter()
+            .map(|issue| PullRequest {
+                number: issue.number,
+                title: issue.title,
+                url: issue.html_url.to_string(),
+                author: issue.user.login,
+                labels: issue.labels.into_iter().map(|label| label.name).collect(),
+            })
+            .collect())
+    }
+
+    /// Download an asset by name from the release for `tag`; `None` if either does not exist
+    pub async fn download_release_asset(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
         asset_name: &str,
-    ) -> Result<Option<u64>> {
-        // Get all releases and find the one with matching ID.
-        let page = self
+    ) -> Result<Option<Vec<u8>>> {
+        let Ok(release) = self
             .octocrab
+            .repos(owner, repo)
+            .releases()
+            .get_by_tag(tag)
+            .await
+        else {
+            return Ok(None);
+        };
+        let Some(asset) = release.assets.iter().find(|a| a.name == asset_name) else {
+            return Ok(None);
+        };
+
+        Ok(Some(self.download_asset(owner, repo, asset.id.0).await?))
+    }
+
+    /// Download a release asset by ID, which also works for draft releases
+    pub async fn download_asset(&self, owner: &str, repo: &str, asset_id: u64) -> Result<Vec<u8>> {
+        let url = format!("https://api.github.com/repos/{owner}/{repo}/releases/assets/{asset_id}");
+        let mut request = self
+            .http_client
+            .get(&url)
+            .header("Accept", "application/octet-stream");
+        if let Some(token) = self.optional_token(owner, repo).await? {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to download asset {}: {}",
+                asset_id,
+                response.status()
+            );
+        }
+
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Look up the release for `tag`, including drafts; `None` if there is none
+    pub async fn find_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> Result<Option<Release>> {
+        match self
+            .api(owner, repo)
+            .await?
+            .repos(owner, repo)
+            .releases()
+            .get_by_tag(tag)
+            .await
+        {
+            Ok(release) => Ok(Some(release)),
+            // Draft releases are not returned by the tag lookup, only by the listing.
+            // Matrix jobs racing to create the draft settle on the oldest and delete
+            // the others, so pick that one rather than a duplicate about to go away.
+            Err(octocrab::Error::GitHub { source, .. })
+                if source.status_code == reqwest::StatusCode::NOT_FOUND =>
+            {
+                Ok(self
+                    .list_releases(owner, repo)
+                    .await?
+                    .into_iter()
+                    .filter(|release| release.tag_name == tag)
+                    .min_by_key(|release| release.id.0))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Publish a draft release
+    pub async fn publish_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        release_id: u64,
+        prerelease: bool,
+        latest: Option<LatestMode>,
+    ) -> Result<Release> {
+        let octocrab = self.api(owner, repo).await?;
+        let repos = octocrab.repos(owner, repo);
+        let releases = repos.releases();
+        let mut builder = releases
+            .update(release_id)
+            .draft(false)
+            .prerelease(prerelease);
+        if let Some(latest) = latest {
+            builder = builder.make_latest(make_latest(latest));
+        }
+
+        Ok(builder.send().await?)
+    }
+
+    /// List all releases of the repository
+    pub async fn list_releases(&self, owner: &str, repo: &str) -> Result<Vec<Release>> {
+        let octocrab = self.api(owner, repo).await?;
+        let page = octocrab
             .repos(owner, repo)
             .releases()
             .list()
@@ -343,18 +728,274 @@ impl GitHubClient {
             .send()
             .await?;
 
-        for release in self.octocrab.all_pages(page).await? {
-            if release.id.0 == release_id {
-                for asset in &release.assets {
-                    if asset.name == asset_name {
-                        return Ok(Some(asset.id.0));
-                    }
-                }
+        Ok(octocrab.all_pages(page).await?)
+    }
+
+    /// Delete a release; the tag is left in place
+    pub async fn delete_release(&self, owner: &str, repo: &str, release_id: u64) -> Result<()> {
+        self.api(owner, repo)
+            .await?
+            .repos(owner, repo)
+            .releases()
+            .delete(release_id)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete a tag from the remote repository
+    pub async fn delete_tag(&self, owner: &str, repo: &str, tag: &str) -> Result<()> {
+        self.api(owner, repo)
+            .await?
+            .repos(owner, repo)
+            .delete_ref(&Reference::Tag(tag.to_string()))
+            .await?;
+        Ok(())
+    }
+
+    /// Authenticated GET against the REST API, decoding the JSON response
+    async fn api_get<T: serde::de::DeserializeOwned>(
+        &self,
+        owner: &str,
+        repo: &str,
+        url: &str,
+    ) -> Result<T> {
+        let response = self
+            .http_client
+            .get(url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.token(owner, repo).await?),
+            )
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            anyhow::bail!("{status} - {error_text}");
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Check the token against the repository, reporting its scopes and push access
+    pub async fn token_access(&self, owner: &str, repo: &str) -> Result<TokenAccess> {
+        let response = self
+            .http_client
+            .get(format!("https://api.github.com/repos/{owner}/{repo}"))
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.token(owner, repo).await?),
+            )
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await?;
+
+        match response.status().as_u16() {
+            401 => anyhow::bail!("GitHub rejected the token (expired or revoked?)"),
+            404 => anyhow::bail!("{owner}/{repo} not found, or the token can't see it"),
+            _ if !response.status().is_success() => {
+                anyhow::bail!("{} checking {owner}/{repo}", response.status())
+            }
+            _ => {}
+        }
+
+        let scopes = response
+            .headers()
+            .get("x-oauth-scopes")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|scope| scope.trim().to_string())
+                    .filter(|scope| !scope.is_empty())
+                    .collect()
+            });
+        let body: serde_json::Value = response.json().await?;
+        let can_push = body["permissions"]["push"].as_bool().unwrap_or(false);
+
+        Ok(TokenAccess { scopes, can_push })
+    }
+
+    /// Every asset of a release with its upload state and digest
+    pub async fn list_release_assets(
+        &self,
+        owner: &str,
+        repo: &str,
+        release_id: u64,
+    ) -> Result<Vec<UploadedAsset>> {
+        let mut assets = Vec::new();
+        for page in 1.. {
+            let url = format!(
+                "https://api.github.com/repos/{owner}/{repo}/releases/{release_id}/assets?per_page=100&page={page}"
+            );
+            let batch = self
+                .api_get::<Vec<UploadedAsset>>(owner, repo, &url)
+                .await?;
+            let done = batch.len() < 100;
+            assets.extend(batch);
+            if done {
                 break;
             }
         }
+        Ok(assets)
+    }
+
+    /// Trigger a workflow through its workflow_dispatch event
+    pub async fn dispatch_workflow(
+        &self,
+        owner: &str,
+        repo: &str,
+        workflow: &str,
+        git_ref: &str,
+        inputs: &serde_json::Value,
+    ) -> Result<()> {
+        let url = format!(
+            "https://api.github.com/repos/{owner}/{repo}/actions/workflows/{workflow}/dispatches"
+        );
+
+        let response = self
+            .http_client
+            .post(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.token(owner, repo).await?),
+            )
+            .header("Accept", "application/vnd.github+json")
+            .json(&serde_json::json!({ "ref": git_ref, "inputs": inputs }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            anyhow::bail!("Failed to dispatch {workflow}: {status} - {error_text}");
+        }
+
+        Ok(())
+    }
+
+    /// Recent workflow_dispatch runs of a workflow, newest first
+    pub async fn list_workflow_runs(
+        &self,
+        owner: &str,
+        repo: &str,
+        workflow: &str,
+    ) -> Result<Vec<WorkflowRun>> {
+        #[derive(serde::Deserialize)]
+        struct Runs {
+            workflow_runs: Vec<WorkflowRun>,
+        }
+
+        let url = format!(
+            "https://api.github.com/repos/{owner}/{repo}/actions/workflows/{workflow}/runs?event=workflow_dispatch&per_page=20"
+        );
+        Ok(self.api_get::<Runs>(owner, repo, &url).await?.workflow_runs)
+    }
+
+    pub async fn get_workflow_run(
+        &self,
+        owner: &str,
+        repo: &str,
+        run_id: u64,
+    ) -> Result<WorkflowRun> {
+        self.api_get(
+            owner,
+            repo,
+            &format!("https://api.github.com/repos/{owner}/{repo}/actions/runs/{run_id}"),
+        )
+        .await
+    }
+
+    /// Artifacts uploaded by a workflow run
+    pub async fn list_run_artifacts(
+        &self,
+        owner: &str,
+        repo: &str,
+        run_id: u64,
+    ) -> Result<Vec<RunArtifact>> {
+        #[derive(serde::Deserialize)]
+        struct Artifacts {
+            artifacts: Vec<RunArtifact>,
+        }
+
+        let url = format!(
+            "https://api.github.com/repos/{owner}/{repo}/actions/runs/{run_id}/artifacts?per_page=100"
+        );
+        Ok(self
+            .api_get::<Artifacts>(owner, repo, &url)
+            .await?
+            .artifacts)
+    }
+
+    /// Download an artifact as the zip archive GitHub serves it in
+    pub async fn download_artifact(
+        &self,
+        owner: &str,
+        repo: &str,
+        artifact: &RunArtifact,
+    ) -> Result<Vec<u8>> {
+        // The API redirects to blob storage; reqwest drops the token on the cross-host hop
+        let response = self
+            .http_client
+            .get(&artifact.archive_download_url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.token(owner, repo).await?),
+            )
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to download artifact {}: {}",
+                artifact.name,
+                response.status()
+            );
+        }
 
-        Ok(None)
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+#[async_trait]
+impl ReleaseProvider for GitHubClient {
+    fn provider(&self) -> Provider {
+        Provider::Github
+    }
+
+    async fn create_or_update_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        options: &ReleaseOptions<'_>,
+    ) -> Result<ProviderRelease> {
+        let release = self.create_release(owner, repo, tag, options).await?;
+        Ok(ProviderRelease {
+            id: release.id.0,
+            url: release.html_url.to_string(),
+        })
+    }
+
+    async fn attach_asset(
+        &self,
+        owner: &str,
+        repo: &str,
+        _tag: &str,
+        release: &ProviderRelease,
+        path: &Path,
+    ) -> Result<bool> {
+        let existing = self.list_release_assets(owner, repo, release.id).await?;
+        self.replace_asset(owner, repo, release.id, path, &existing)
+            .await
     }
 }
 
@@ -367,6 +1008,11 @@ pub fn get_content_type(path: &Path) -> &'static str {
         "zip" => "application/zip",
         "xz" => "application/x-xz",
         "bz2" => "application/x-bzip2",
+        "deb" => "application/vnd.debian.binary-package",
+        "rpm" => "application/x-rpm",
+        "msi" => "application/x-msi",
+        "exe" => "application/vnd.microsoft.portable-executable",
+        "json" => "application/json",
         "txt" => "text/plain",
         _ => "application/octet-stream",
     }
@@ -380,6 +1026,29 @@ mod tests {
     async fn explicit_token_is_available_for_asset_requests() {
         let client = GitHubClient::new(Some("cli-token".to_string())).unwrap();
 
-        assert_eq!(client.get_token().unwrap(), "cli-token");
+        assert_eq!(client.token("owner", "repo").await.unwrap(), "cli-token");
+    }
+
+    #[tokio::test]
+    async fn invalid_app_key_is_rejected() {
+        let app = GitHubApp {
+            app_id: 12345,
+            private_key: "not a key".to_string(),
+            installation_id: None,
+        };
+
+        let error = GitHubClient::from_app(&app).err().unwrap();
+        assert!(error.to_string().contains("Invalid GitHub App private key"));
+    }
+
+    #[test]
+    fn test_check_asset_size() {
+        assert!(check_asset_size("tool.tar.gz", 1024).is_ok());
+        assert!(check_asset_size("tool.tar.gz", MAX_ASSET_SIZE - 1).is_ok());
+
+        let error = check_asset_size("tool.tar.gz", MAX_ASSET_SIZE + 1024 * 1024 * 1024)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("tool.tar.gz is 3.00 GiB"), "{error}");
     }
 }
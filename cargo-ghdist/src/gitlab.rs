@@ -0,0 +1,356 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use cargo_ghinstall::progress::Progress;
+use futures_util::TryStreamExt;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::Deserialize;
+use std::path::Path;
+use tokio_util::io::ReaderStream;
+
+use crate::cli::Provider;
+use crate::config::{GitLabAssets, GitLabConfig};
+use crate::github::{get_content_type, ProviderRelease, ReleaseOptions, ReleaseProvider};
+
+/// Percent-encode one URL path segment, e.g. a project path or tag
+pub fn encode_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Generic package versions can't contain slashes, which branch-like tags may have
+pub fn package_version(tag: &str) -> String {
+    tag.replace('/', "-")
+}
+
+/// Where a link on the GitLab release points for an asset
+pub fn asset_url(
+    config: &GitLabConfig,
+    mode: GitLabAssets,
+    project: &str,
+    owner: &str,
+    repo: &str,
+    tag: &str,
+    file_name: &str,
+) -> String {
+    match mode {
+        GitLabAssets::Package => format!(
+            "{}/api/v4/projects/{}/packages/generic/{}/{}/{}",
+            config.url(),
+            encode_segment(project),
+            encode_segment(config.package.as_deref().unwrap_or(repo)),
+            encode_segment(&package_version(tag)),
+            encode_segment(file_name)
+        ),
+        GitLabAssets::Links => {
+            format!("https://github.com/{owner}/{repo}/releases/download/{tag}/{file_name}")
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabRelease {
+    #[serde(rename = "_links", default)]
+    links: ReleaseLinks,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ReleaseLinks {
+    #[serde(rename = "self")]
+    web: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetLink {
+    id: u64,
+    name: String,
+}
+
+/// GitLab Releases client, authenticated with GITLAB_TOKEN
+pub struct GitLabClient {
+    http_client: Client,
+    config: GitLabConfig,
+    assets: GitLabAssets,
+    /// Checked when the first request is sent, so planning works without it
+    token: Option<String>,
+    progress: bool,
+}
+
+impl GitLabClient {
+    pub fn new(config: &GitLabConfig, progress: bool) -> Result<Self> {
+        let token = std::env::var("GITLAB_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+        let http_client = Client::builder()
+            .user_agent("cargo-ghdist")
+            .timeout(std::time::Duration::from_secs(300))
+            .build()?;
+
+        Ok(Self {
+            http_client,
+            assets: config.assets()?,
+            config: config.clone(),
+            token,
+            progress,
+        })
+    }
+
+    /// Project path such as `group/project`, from `[gitlab] project` or the
+    /// GitHub owner/repo; [`GitLabClient::api`] encodes it
+    fn project(&self, owner: &str, repo: &str) -> String {
+        self.config
+            .project
+            .clone()
+            .unwrap_or_else(|| format!("{owner}/{repo}"))
+    }
+
+    fn token(&self) -> Result<&str> {
+        self.token
+            .as_deref()
+            .context("--provider gitlab needs a GITLAB_TOKEN with the api scope")
+    }
+
+    fn api(&self, project: &str, path: &str) -> String {
+        format!(
+            "{}/api/v4/projects/{}{path}",
+            self.config.url(),
+            encode_segment(project)
+        )
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request
+            .header("PRIVATE-TOKEN", self.token()?)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            anyhow::bail!("GitLab API error: {status} - {error_text}");
+        }
+        Ok(response)
+    }
+
+    async fn find_release(&self, project: &str, tag: &str) -> Result<Option<GitLabRelease>> {
+        let response = self
+            .http_client
+            .get(self.api(project, &format!("/releases/{}", encode_segment(tag))))
+            .header("PRIVATE-TOKEN", self.token()?)
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("GitLab API error: {}", response.status());
+        }
+        Ok(Some(response.json().await?))
+    }
+
+    /// PUT a file into the generic package registry, streaming it from disk
+    async fn upload_package_file(
+        &self,
+        project: &str,
+        package: &str,
+        tag: &str,
+        path: &Path,
+    ) -> Result<()> {
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .context("Invalid asset path")?;
+        let file = tokio::fs::File::open(path).await?;
+        let size = file.metadata().await?.len();
+
+        tracing::info!("Uploading {} to the GitLab package registry", file_name);
+        let progress = Progress::new(file_name, Some(size), self.progress);
+        let counter = progress.clone();
+        let body = ReaderStream::new(file).inspect_ok(move |chunk| counter.inc(chunk.len() as u64));
+
+        let url = self.api(
+            project,
+            &format!(
+                "/packages/generic/{}/{}/{}",
+                encode_segment(package),
+                encode_segment(&package_version(tag)),
+                encode_segment(file_name)
+            ),
+        );
+        let result = self
+            .send(
+                self.http_client
+                    .put(url)
+                    .header("Content-Type", get_content_type(path))
+                    .header("Content-Length", size)
+                    .body(reqwest::Body::wrap_stream(body)),
+            )
+            .await;
+        progress.finish();
+        result.map(|_| ())
+    }
+}
+
+#[async_trait]
+impl ReleaseProvider for GitLabClient {
+    fn provider(&self) -> Provider {
+        Provider::Gitlab
+    }
+
+    async fn create_or_update_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        options: &ReleaseOptions<'_>,
+    ) -> Result<ProviderRelease> {
+        let project = self.project(owner, repo);
+        if options.draft {
+            tracing::warn!("GitLab has no draft releases, publishing {} directly", tag);
+        }
+
+        let mut body = serde_json::json!({
+            "name": tag,
+            "description": options.body.unwrap_or_default(),
+        });
+        let release: GitLabRelease = if self.find_release(&project, tag).await?.is_some() {
+            tracing::info!("GitLab release {} already exists, will update it", tag);
+            self.send(
+                self.http_client
+                    .put(self.api(&project, &format!("/releases/{}", encode_segment(tag))))
+                    .json(&body),
+            )
+            .await?
+            .json()
+            .await?
+        } else {
+            body["tag_name"] = tag.into();
+            // Only needed when the tag hasn't been mirrored to GitLab yet
+            if let Some(target) = options.target_commitish {
+                body["ref"] = target.into();
+            }
+            self.send(
+                self.http_client
+                    .post(self.api(&project, "/releases"))
+                    .json(&body),
+            )
+            .await?
+            .json()
+            .await?
+        };
+
+        Ok(ProviderRelease {
+            id: 0,
+            url: release
+                .links
+                .web
+                .unwrap_or_else(|| format!("{}/{project}/-/releases/{tag}", self.config.url())),
+        })
+    }
+
+    async fn attach_asset(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        _release: &ProviderRelease,
+        path: &Path,
+    ) -> Result<bool> {
+        let project = self.project(owner, repo);
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .context("Invalid asset path")?;
+
+        if self.assets == GitLabAssets::Package {
+            let package = self.config.package.as_deref().unwrap_or(repo);
+            self.upload_package_file(&project, package, tag, path)
+                .await?;
+        }
+
+        let links_url = self.api(
+            &project,
+            &format!("/releases/{}/assets/links", encode_segment(tag)),
+        );
+        let links: Vec<AssetLink> = self
+            .send(self.http_client.get(&links_url))
+            .await?
+            .json()
+            .await?;
+        if let Some(link) = links.iter().find(|link| link.name == file_name) {
+            self.send(self.http_client.delete(format!("{links_url}/{}", link.id)))
+                .await?;
+        }
+
+        let url = asset_url(
+            &self.config,
+            self.assets,
+            &project,
+            owner,
+            repo,
+            tag,
+            file_name,
+        );
+        self.send(self.http_client.post(&links_url).json(&serde_json::json!({
+            "name": file_name,
+            "url": url,
+            "link_type": if self.assets == GitLabAssets::Package { "package" } else { "other" },
+        })))
+        .await?;
+        tracing::info!("Linked {} on the GitLab release", file_name);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_segment() {
+        assert_eq!(encode_segment("group/sub/tool"), "group%2Fsub%2Ftool");
+        assert_eq!(encode_segment("v1.2.3+build"), "v1.2.3%2Bbuild");
+        assert_eq!(package_version("release/2025-01"), "release-2025-01");
+    }
+
+    #[test]
+    fn test_asset_url() {
+        let config = GitLabConfig {
+            url: Some("https://gitlab.example.com".to_string()),
+            package: Some("tool-bin".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            asset_url(
+                &config,
+                GitLabAssets::Package,
+                "group/tool",
+                "owner",
+                "tool",
+                "v1.2.3",
+                "tool.tar.gz"
+            ),
+            "https://gitlab.example.com/api/v4/projects/group%2Ftool/packages/generic/tool-bin/v1.2.3/tool.tar.gz"
+        );
+        assert_eq!(
+            asset_url(
+                &config,
+                GitLabAssets::Links,
+                "group/tool",
+                "owner",
+                "tool",
+                "v1.2.3",
+                "tool.tar.gz"
+            ),
+            "https://github.com/owner/tool/releases/download/v1.2.3/tool.tar.gz"
+        );
+    }
+}
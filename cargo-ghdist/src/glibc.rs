@@ -0,0 +1,161 @@
+use crate::manifest::ReleaseManifest;
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Whether binaries built for `target` link against glibc
+pub fn links_glibc(target: &str) -> bool {
+    target.contains("-linux-gnu")
+}
+
+/// Highest `GLIBC_x.y` symbol version the binaries need, e.g. "2.28";
+/// `None` when none of them links glibc dynamically
+pub fn min_glibc(binaries: &[PathBuf]) -> Result<Option<String>> {
+    let mut highest: Option<Vec<u32>> = None;
+    for binary in binaries {
+        let output = version_info(binary)?;
+        if let Some(version) = required_version(&output) {
+            if highest.as_ref().is_none_or(|h| version > *h) {
+                highest = Some(version);
+            }
+        }
+    }
+    Ok(highest.map(|version| format_version(&version)))
+}
+
+/// Version sections from `readelf`, or dynamic symbols from `objdump` when
+/// binutils only ships that
+fn version_info(binary: &PathBuf) -> Result<String> {
+    let output = match Command::new("readelf")
+        .args(["--version-info", "--wide"])
+        .arg(binary)
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => Command::new("objdump")
+            .arg("-T")
+            .arg(binary)
+            .output()
+            .context("Neither readelf nor objdump is available to inspect glibc versions")?,
+    };
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to read symbol versions of {}: {}",
+            binary.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Highest `GLIBC_` version in readelf or objdump output; `GLIBC_PRIVATE`
+/// and other non-numeric versions are ignored
+fn required_version(output: &str) -> Option<Vec<u32>> {
+    output
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+        .filter_map(|token| token.strip_prefix("GLIBC_"))
+        .filter_map(|version| {
+            version
+                .split('.')
+                .map(|part| part.parse::<u32>().ok())
+                .collect::<Option<Vec<_>>>()
+        })
+        .max()
+}
+
+fn format_version(version: &[u32]) -> String {
+    version
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Markdown table of the glibc each Linux target needs; `None` when no
+/// target recorded one
+pub fn render_notes(manifest: &ReleaseManifest) -> Option<String> {
+    let rows = manifest
+        .targets
+        .iter()
+        .filter_map(|t| Some((&t.target, t.min_glibc.as_ref()?)))
+        .map(|(target, glibc)| format!("| {target} | {glibc} |\n"))
+        .collect::<String>();
+    if rows.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "### 🐧 Linux Compatibility\n\n\
+         | Target | Minimum glibc |\n|--------|---------------|\n{rows}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::TargetManifest;
+    use crate::signing::SignatureStatus;
+
+    const READELF: &str = "\
+Version symbols section '.gnu.version' contains 120 entries:
+ Addr: 0x0000000000001c2e  Offset: 0x001c2e  Link: 6 (.dynsym)
+  000:   0 (*local*)       2 (GLIBC_2.2.5)   3 (GLIBC_2.3)     4 (GLIBC_2.28)
+
+Version needs section '.gnu.version_r' contains 2 entries:
+ Addr: 0x0000000000001d20  Offset: 0x001d20  Link: 7 (.dynstr)
+  000000: Version: 1  File: libgcc_s.so.1  Cnt: 1
+  0x0010:   Name: GCC_3.0  Flags: none  Version: 5
+  0x0020: Version: 1  File: libc.so.6  Cnt: 4
+  0x0030:   Name: GLIBC_2.28  Flags: none  Version: 4
+  0x0040:   Name: GLIBC_2.3  Flags: none  Version: 3
+  0x0050:   Name: GLIBC_PRIVATE  Flags: none  Version: 6
+  0x0060:   Name: GLIBC_2.2.5  Flags: none  Version: 2
+";
+
+    const OBJDUMP: &str = "\
+DYNAMIC SYMBOL TABLE:
+0000000000000000      DF *UND*	0000000000000000 (GLIBC_2.2.5) write
+0000000000000000      DF *UND*	0000000000000000 (GLIBC_2.17) clock_gettime
+0000000000000000  w   DF *UND*	0000000000000000 (GLIBC_2.9)  pipe2
+";
+
+    #[test]
+    fn test_required_version() {
+        assert_eq!(required_version(READELF), Some(vec![2, 28]));
+        // 2.17 is newer than 2.9 even though it sorts lower as text
+        assert_eq!(required_version(OBJDUMP), Some(vec![2, 17]));
+        assert_eq!(required_version("Name: GLIBC_PRIVATE"), None);
+        assert_eq!(required_version("No version information found"), None);
+        assert_eq!(format_version(&[2, 2, 5]), "2.2.5");
+    }
+
+    #[test]
+    fn test_links_glibc() {
+        assert!(links_glibc("x86_64-unknown-linux-gnu"));
+        assert!(links_glibc("armv7-unknown-linux-gnueabihf"));
+        assert!(!links_glibc("x86_64-unknown-linux-musl"));
+        assert!(!links_glibc("x86_64-pc-windows-gnu"));
+    }
+
+    #[test]
+    fn test_render_notes() {
+        let target = |name: &str, glibc: Option<&str>| TargetManifest {
+            target: name.to_string(),
+            assets: Vec::new(),
+            binaries: Vec::new(),
+            signature: SignatureStatus::NotApplicable,
+            min_glibc: glibc.map(String::from),
+        };
+        let mut manifest = ReleaseManifest::new("v1.0.0");
+        manifest
+            .targets
+            .push(target("x86_64-unknown-linux-musl", None));
+        assert_eq!(render_notes(&manifest), None);
+
+        manifest
+            .targets
+            .push(target("x86_64-unknown-linux-gnu", Some("2.28")));
+        let notes = render_notes(&manifest).unwrap();
+        assert!(notes.contains("| x86_64-unknown-linux-gnu | 2.28 |"));
+        assert!(!notes.contains("musl"));
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::config::HooksConfig;
+use crate::ui;
+
+/// Point in the release at which hooks run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreBuild,
+    PreUpload,
+    PostRelease,
+}
+
+impl HookStage {
+    fn name(self) -> &'static str {
+        match self {
+            HookStage::PreBuild => "pre_build",
+            HookStage::PreUpload => "pre_upload",
+            HookStage::PostRelease => "post_release",
+        }
+    }
+
+    fn commands(self, config: &HooksConfig) -> &[String] {
+        match self {
+            HookStage::PreBuild => &config.pre_build,
+            HookStage::PreUpload => &config.pre_upload,
+            HookStage::PostRelease => &config.post_release,
+        }
+    }
+}
+
+/// Release details exposed to hook commands as environment variables
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    pub tag: String,
+    pub version: String,
+    pub targets: Vec<String>,
+    pub assets: Vec<PathBuf>,
+    pub release_url: Option<String>,
+}
+
+impl HookContext {
+    /// `TAG`, `VERSION`, `TARGETS` and `ASSETS` (space-separated), plus `RELEASE_URL` once known
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![
+            ("TAG", self.tag.clone()),
+            ("VERSION", self.version.clone()),
+            ("TARGETS", self.targets.join(" ")),
+            (
+                "ASSETS",
+                self.assets
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+        ];
+        if let Some(url) = &self.release_url {
+            env.push(("RELEASE_URL", url.clone()));
+        }
+        env
+    }
+}
+
+/// Run `command` through the platform shell
+pub fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+/// Run the hooks configured for `stage` in order, stopping at the first failure
+pub fn run(config: &HooksConfig, stage: HookStage, context: &HookContext) -> Result<()> {
+    let env = context.env();
+
+    for command in stage.commands(config) {
+        tracing::info!("Running {} hook: {}", stage.name(), command);
+        let status = shell_command(command)
+            .envs(env.iter().map(|(key, value)| (key, value)))
+            .stdout(ui::child_stdout())
+            .status()
+            .with_context(|| format!("Failed to run {} hook: {command}", stage.name()))?;
+        if !status.success() {
+            anyhow::bail!("{} hook `{command}` failed with {status}", stage.name());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn context() -> HookContext {
+        HookContext {
+            tag: "v1.2.3".to_string(),
+            version: "1.2.3".to_string(),
+            targets: vec![
+                "x86_64-unknown-linux-gnu".to_string(),
+                "aarch64-apple-darwin".to_string(),
+            ],
+            assets: vec![PathBuf::from("dist/a.tar.gz"), PathBuf::from("dist/b.zip")],
+            release_url: None,
+        }
+    }
+
+    #[test]
+    fn test_env() {
+        let env = context().env();
+        assert_eq!(
+            env,
+            vec![
+                ("TAG", "v1.2.3".to_string()),
+                ("VERSION", "1.2.3".to_string()),
+                (
+                    "TARGETS",
+                    "x86_64-unknown-linux-gnu aarch64-apple-darwin".to_string()
+                ),
+                ("ASSETS", "dist/a.tar.gz dist/b.zip".to_string()),
+            ]
+        );
+
+        let with_url = HookContext {
+            release_url: Some("https://github.com/o/r/releases/tag/v1.2.3".to_string()),
+            ..context()
+        };
+        assert_eq!(with_url.env().len(), 5);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hooks() {
+        let temp_dir = tempdir().unwrap();
+        let out = temp_dir.path().join("out.txt");
+        let config = HooksConfig {
+            pre_build: vec![format!("echo \"$TAG $VERSION\" > {}", out.display())],
+            pre_upload: vec!["true".to_string(), "exit 3".to_string()],
+            post_release: Vec::new(),
+        };
+
+        run(&config, HookStage::PreBuild, &context()).unwrap();
+        assert_eq!(fs::read_to_string(&out).unwrap(), "v1.2.3 1.2.3\n");
+
+        let error = run(&config, HookStage::PreUpload, &context()).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("pre_upload hook `exit 3` failed"));
+
+        assert!(run(&config, HookStage::PostRelease, &context()).is_ok());
+    }
+}
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::github::GitHubClient;
+use crate::manifest::{ReleaseManifest, TargetManifest, MANIFEST_FILE_NAME};
+
+const CHECKSUM_FILE_NAME: &str = "SHA256SUMS";
+
+/// Parse `SHA256SUMS` into asset name -> hex digest
+pub fn parse_checksums(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once("  "))
+        .map(|(hash, name)| (name.trim().to_string(), hash.trim().to_lowercase()))
+        .collect()
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Assets of an earlier run of the same release that can stand in for a rebuild
+pub struct ExistingRelease {
+    manifest: ReleaseManifest,
+    checksums: HashMap<String, String>,
+    /// Asset name -> asset ID
+    assets: HashMap<String, u64>,
+}
+
+impl ExistingRelease {
+    /// Look up the release for `tag`; `None` unless it has both a manifest and SHA256SUMS
+    pub async fn fetch(
+        client: &GitHubClient,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+    ) -> Result<Option<Self>> {
+        let Some(release) = client.find_release(owner, repo, tag).await? else {
+            return Ok(None);
+        };
+        let assets = release
+            .assets
+            .iter()
+            .map(|asset| (asset.name.clone(), asset.id.0))
+            .collect::<HashMap<_, _>>();
+
+        let (Some(&manifest_id), Some(&checksums_id)) = (
+            assets.get(MANIFEST_FILE_NAME),
+            assets.get(CHECKSUM_FILE_NAME),
+        ) else {
+            return Ok(None);
+        };
+
+        let manifest =
+            ReleaseManifest::from_json(&client.download_asset(owner, repo, manifest_id).await?)?;
+        let checksums = String::from_utf8(client.download_asset(owner, repo, checksums_id).await?)?;
+
+        Ok(Some(Self {
+            manifest,
+            checksums: parse_checksums(&checksums),
+            assets,
+        }))
+    }
+
+    /// Download `target`'s assets into `dir` if every one of them is on the release
+    /// and matches its SHA256SUMS entry
+    ///
+    /// Returns the target's manifest entry and the downloaded files, or `None` when
+    /// the target has to be built.
+    pub async fn reuse_target(
+        &self,
+        client: &GitHubClient,
+        owner: &str,
+        repo: &str,
+        target: &str,
+        dir: &Path,
+    ) -> Result<Option<(TargetManifest, Vec<PathBuf>)>> {
+        let Some(entry) = self.manifest.targets.iter().find(|t| t.target == target) else {
+            return Ok(None);
+        };
+        if entry.assets.is_empty() {
+            return Ok(None);
+        }
+
+        let mut files = Vec::new();
+        for name in &entry.assets {
+            let (Some(&asset_id), Some(expected)) =
+                (self.assets.get(name), self.checksums.get(name))
+            else {
+                return Ok(None);
+            };
+
+            let bytes = client.download_asset(owner, repo, asset_id).await?;
+            if &sha256_hex(&bytes) != expected {
+                tracing::info!("{} does not match SHA256SUMS, rebuilding {}", name, target);
+                return Ok(None);
+            }
+
+            let path = dir.join(name);
+            fs::write(&path, bytes)?;
+            files.push(path);
+        }
+
+        Ok(Some((entry.clone(), files)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_checksums() {
+        let checksums = parse_checksums(
+            "ABC123  tool-x86_64-unknown-linux-gnu.tar.gz\ndef456  dist-manifest.json\n\nbroken\n",
+        );
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums["tool-x86_64-unknown-linux-gnu.tar.gz"], "abc123");
+        assert_eq!(checksums["dist-manifest.json"], "def456");
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"hello"),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+}
@@ -1,85 +1,870 @@
 use anyhow::Result;
+use git2::Repository;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
 
-const RELEASE_WORKFLOW_TEMPLATE: &str = r#"name: Release
+use crate::cli::CiProvider;
+use crate::workspace;
 
-on:
-  push:
-    tags:
-      - 'v*'       # SemVer
-      - '[0-9a-f]*' # Short-hash tags
+/// Targets offered when choosing what to build; the first four are the defaults
+const TARGET_CHOICES: &[&str] = &[
+    "x86_64-unknown-linux-gnu",
+    "aarch64-unknown-linux-gnu",
+    "x86_64-apple-darwin",
+    "aarch64-apple-darwin",
+    "x86_64-unknown-linux-musl",
+    "aarch64-unknown-linux-musl",
+    "x86_64-pc-windows-msvc",
+    "aarch64-pc-windows-msvc",
+];
 
-permissions:
-  contents: write  # For release creation and asset upload
+const DEFAULT_TARGETS: usize = 4;
+
+/// First line of every workflow init writes; files without it belong to the user
+const GENERATED_MARKER: &str = "# Generated by cargo ghdist init";
+
+const INSTALL_GHDIST: &str =
+    "cargo install --git https://github.com/mkusaka/cargo-gh cargo-ghdist --locked";
+
+/// What starts a release in the generated GitHub Actions workflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trigger {
+    /// Pushing a tag
+    TagPush,
+    /// Running the workflow by hand with the tag to release
+    Manual,
+    /// A nightly release of the default branch
+    Nightly,
+    /// Merging a `release/<tag>` pull request opened by the release-PR workflow
+    ReleasePr,
+}
+
+impl Trigger {
+    const ALL: [Trigger; 4] = [
+        Trigger::TagPush,
+        Trigger::Manual,
+        Trigger::Nightly,
+        Trigger::ReleasePr,
+    ];
+
+    fn describe(self) -> &'static str {
+        match self {
+            Trigger::TagPush => "Tag push",
+            Trigger::Manual => "Manual dispatch (tag created if missing)",
+            Trigger::Nightly => "Nightly schedule (nightly-YYYYMMDD tags)",
+            Trigger::ReleasePr => "Merged release PR (bump + changelog PR opened by a workflow)",
+        }
+    }
+}
+
+/// Choices that shape the generated config and workflow
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Answers {
+    targets: Vec<String>,
+    triggers: Vec<Trigger>,
+    /// Branch manual and nightly releases are cut from
+    default_branch: String,
+    /// owner/repo for the config, when Cargo.toml has no repository
+    repository: Option<String>,
+    sign_tags: bool,
+    sign_windows: bool,
+    attest: bool,
+}
+
+impl Answers {
+    /// What `--yes` uses; `--release-pr` adds the release PR trigger
+    fn defaults(default_branch: String, release_pr: bool) -> Self {
+        let mut triggers = vec![Trigger::TagPush];
+        if release_pr {
+            triggers.push(Trigger::ReleasePr);
+        }
+        Self {
+            targets: TARGET_CHOICES[..DEFAULT_TARGETS]
+                .iter()
+                .map(|target| target.to_string())
+                .collect(),
+            triggers,
+            default_branch,
+            repository: None,
+            sign_tags: false,
+            sign_windows: false,
+            attest: false,
+        }
+    }
+
+    fn creates_tags(&self) -> bool {
+        self.triggers
+            .iter()
+            .any(|trigger| *trigger != Trigger::TagPush)
+    }
+}
+
+/// Line-based questions, generic over the streams so they can be scripted in tests
+struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    fn read_line(&mut self) -> Result<String> {
+        self.output.flush()?;
+        let mut line = String::new();
+        self.input.read_line(&mut line)?;
+        Ok(line.trim().to_string())
+    }
+
+    /// Yes/no question; an empty answer picks `default`
+    fn confirm(&mut self, prompt: &str, default: bool) -> Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        write!(self.output, "{prompt} ({hint}): ")?;
+        let answer = self.read_line()?.to_ascii_lowercase();
+        Ok(match answer.as_str() {
+            "" => default,
+            "y" | "yes" => true,
+            _ => false,
+        })
+    }
+
+    /// Free-form answer; an empty one picks `default`
+    fn text(&mut self, prompt: &str, default: &str) -> Result<String> {
+        if default.is_empty() {
+            write!(self.output, "{prompt}: ")?;
+        } else {
+            write!(self.output, "{prompt} [{default}]: ")?;
+        }
+        let answer = self.read_line()?;
+        Ok(if answer.is_empty() {
+            default.to_string()
+        } else {
+            answer
+        })
+    }
+
+    /// Pick any of `choices` by number; an empty answer keeps the `selected` ones
+    fn multi_select(
+        &mut self,
+        prompt: &str,
+        choices: &[&str],
+        selected: &[usize],
+    ) -> Result<Vec<usize>> {
+        writeln!(self.output, "{prompt}")?;
+        for (index, choice) in choices.iter().enumerate() {
+            let mark = if selected.contains(&index) { "x" } else { " " };
+            writeln!(self.output, "  [{mark}] {}. {choice}", index + 1)?;
+        }
+        loop {
+            write!(
+                self.output,
+                "Numbers, comma-separated (Enter keeps the marked ones): "
+            )?;
+            let answer = self.read_line()?;
+            if answer.is_empty() {
+                return Ok(selected.to_vec());
+            }
+            match parse_selection(&answer, choices.len()) {
+                Some(picked) => return Ok(picked),
+                None => writeln!(self.output, "Pick numbers from 1 to {}", choices.len())?,
+            }
+        }
+    }
+}
+
+/// Zero-based indexes of a "1,3,4" answer, `None` unless every number is a choice
+fn parse_selection(answer: &str, choices: usize) -> Option<Vec<usize>> {
+    let mut picked = Vec::new();
+    for part in answer
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        let number = part.parse::<usize>().ok()?;
+        if number == 0 || number > choices {
+            return None;
+        }
+        if !picked.contains(&(number - 1)) {
+            picked.push(number - 1);
+        }
+    }
+    (!picked.is_empty()).then_some(picked)
+}
+
+/// What the project already says, used to prefill the answers
+#[derive(Debug, Clone, Default)]
+struct Detected {
+    default_branch: String,
+    /// Whether Cargo.toml points at the repository
+    has_repository: bool,
+}
+
+impl Detected {
+    fn from_project() -> Self {
+        let default_branch = Repository::open(".")
+            .ok()
+            .and_then(|repo| default_branch(&repo))
+            .unwrap_or_else(|| "main".to_string());
+        let has_repository = workspace::workspace_packages()
+            .map(|packages| packages.iter().any(|p| p.repository.is_some()))
+            .unwrap_or(false);
+        Self {
+            default_branch,
+            has_repository,
+        }
+    }
+}
+
+/// The branch origin/HEAD points at, or else the checked-out branch
+fn default_branch(repo: &Repository) -> Option<String> {
+    if let Ok(reference) = repo.find_reference("refs/remotes/origin/HEAD") {
+        if let Some(target) = reference.symbolic_target() {
+            return target
+                .strip_prefix("refs/remotes/origin/")
+                .map(String::from);
+        }
+    }
+    let head = repo.find_reference("HEAD").ok()?;
+    head.symbolic_target()?
+        .strip_prefix("refs/heads/")
+        .map(String::from)
+}
+
+/// Ask for everything `--yes` would otherwise take the defaults for
+fn ask<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    provider: CiProvider,
+    skip_ci: bool,
+    release_pr: bool,
+    detected: &Detected,
+) -> Result<Answers> {
+    let mut answers = Answers::defaults(detected.default_branch.clone(), release_pr);
+    let github_workflow = !skip_ci && provider == CiProvider::Github;
+
+    let default_targets = (0..DEFAULT_TARGETS).collect::<Vec<_>>();
+    answers.targets = prompter
+        .multi_select("Targets to build:", TARGET_CHOICES, &default_targets)?
+        .into_iter()
+        .map(|index| TARGET_CHOICES[index].to_string())
+        .collect();
+
+    if github_workflow {
+        let descriptions = Trigger::ALL.map(Trigger::describe);
+        let preselected = (0..Trigger::ALL.len())
+            .filter(|&index| answers.triggers.contains(&Trigger::ALL[index]))
+            .collect::<Vec<_>>();
+        answers.triggers = prompter
+            .multi_select("Start a release on:", &descriptions, &preselected)?
+            .into_iter()
+            .map(|index| Trigger::ALL[index])
+            .collect();
+        if answers.creates_tags() {
+            answers.default_branch =
+                prompter.text("Branch to release from", &detected.default_branch)?;
+        }
+    }
+
+    if !detected.has_repository {
+        let repository = prompter.text(
+            "GitHub repository (owner/repo), empty to set it in Cargo.toml later",
+            "",
+        )?;
+        answers.repository = Some(repository).filter(|r| r.split('/').count() == 2);
+    }
+
+    answers.sign_tags = prompter.confirm("GPG-sign the tags ghdist creates?", false)?;
+    if answers.targets.iter().any(|t| t.contains("windows")) {
+        answers.sign_windows = prompter.confirm("Authenticode-sign Windows executables?", false)?;
+    }
+    if github_workflow {
+        answers.attest = prompter.confirm("Attest build provenance of the assets?", false)?;
+    }
+    Ok(answers)
+}
+
+/// Linker for targets that need a cross toolchain on an x86_64 Linux runner
+fn cross_linker(target: &str) -> Option<&'static str> {
+    target
+        .starts_with("aarch64-unknown-linux")
+        .then_some("aarch64-linux-gnu-gcc")
+}
+
+/// GitHub-hosted runner that builds `target`
+fn runner(target: &str) -> &'static str {
+    if target.contains("apple") {
+        "macos-latest"
+    } else if target.contains("windows") {
+        "windows-latest"
+    } else {
+        "ubuntu-latest"
+    }
+}
+
+/// Targets grouped by the runner that builds them, in first-seen order
+fn runner_groups(targets: &[String]) -> Vec<(&'static str, Vec<&str>)> {
+    let mut groups: Vec<(&'static str, Vec<&str>)> = Vec::new();
+    for target in targets {
+        let os = runner(target);
+        match groups.iter_mut().find(|(runner, _)| *runner == os) {
+            Some((_, group)) => group.push(target),
+            None => groups.push((os, vec![target])),
+        }
+    }
+    groups
+}
+
+/// GitLab CI pipeline: one build job per Linux target, then a job that
+/// assembles their archives into a GitLab release
+const GITLAB_CI_TEMPLATE: &str = r#"# Generated by cargo ghdist init
+# Add GITLAB_TOKEN (api scope) as a masked CI/CD variable. To mirror the
+# release to GitHub, add GITHUB_TOKEN too and pass --provider github,gitlab.
+
+stages:
+  - build
+  - release
+
+variables:
+  CARGO_HOME: $CI_PROJECT_DIR/.cargo
+  CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_LINKER: aarch64-linux-gnu-gcc
+
+.ghdist:
+  image: rust:latest
+  rules:
+    - if: $CI_COMMIT_TAG
+  before_script:
+    - cargo install --git https://github.com/mkusaka/cargo-gh cargo-ghdist --locked
+
+build:
+  extends: .ghdist
+  stage: build
+  parallel:
+    matrix:
+      - TARGET:
+          - x86_64-unknown-linux-gnu
+          - aarch64-unknown-linux-gnu
+  cache:
+    key: cargo-$TARGET
+    paths:
+      - .cargo/bin/
+      - .cargo/registry/
+      - target/
+  script:
+    - apt-get update && apt-get install -y gcc-aarch64-linux-gnu
+    - rustup target add "$TARGET"
+    - cargo ghdist --tag "$CI_COMMIT_TAG" --targets "$TARGET" --no-upload --output-dir dist
+  artifacts:
+    paths:
+      - dist/
+
+release:
+  extends: .ghdist
+  stage: release
+  cache:
+    key: cargo-release
+    paths:
+      - .cargo/bin/
+      - .cargo/registry/
+  script:
+    - >
+      cargo ghdist --tag "$CI_COMMIT_TAG" --provider gitlab
+      --targets x86_64-unknown-linux-gnu,aarch64-unknown-linux-gnu
+      --from-artifacts "dist/$CI_COMMIT_TAG"
+"#;
+
+/// CircleCI config: one build job per target, then a job that assembles
+/// their archives into a GitHub release
+const CIRCLECI_TEMPLATE: &str = r#"# Generated by cargo ghdist init
+# Add GITHUB_TOKEN (contents: write) to the project's environment variables
+# or to a context used by the release job.
+version: 2.1
+
+commands:
+  install-ghdist:
+    steps:
+      - restore_cache:
+          keys:
+            - cargo-ghdist-v1
+      - run: cargo install --git https://github.com/mkusaka/cargo-gh cargo-ghdist --locked
+      - save_cache:
+          key: cargo-ghdist-v1
+          paths:
+            - ~/.cargo/bin
 
 jobs:
-  # Linux builds
-  release-linux:
-    name: Release (Linux)
-    runs-on: ubuntu-latest
+  build-linux:
+    docker:
+      - image: cimg/rust:1.85
+    parameters:
+      target:
+        type: string
+    environment:
+      CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_LINKER: aarch64-linux-gnu-gcc
+    steps:
+      - checkout
+      - install-ghdist
+      - restore_cache:
+          keys:
+            - cargo-<< parameters.target >>-{{ checksum "Cargo.lock" }}
+            - cargo-<< parameters.target >>-
+      - run: sudo apt-get update && sudo apt-get install -y gcc-aarch64-linux-gnu
+      - run: rustup target add << parameters.target >>
+      - run: cargo ghdist --tag "$CIRCLE_TAG" --targets << parameters.target >> --no-upload --output-dir dist
+      - save_cache:
+          key: cargo-<< parameters.target >>-{{ checksum "Cargo.lock" }}
+          paths:
+            - ~/.cargo/registry
+            - target
+      - persist_to_workspace:
+          root: .
+          paths:
+            - dist
 
+  build-macos:
+    macos:
+      xcode: 16.2.0
+    parameters:
+      target:
+        type: string
     steps:
-      - name: Checkout source
-        uses: actions/checkout@v4
+      - checkout
+      - run: curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh -s -- -y
+      - run: echo 'source "$HOME/.cargo/env"' >> "$BASH_ENV"
+      - install-ghdist
+      - restore_cache:
+          keys:
+            - cargo-<< parameters.target >>-{{ checksum "Cargo.lock" }}
+            - cargo-<< parameters.target >>-
+      - run: rustup target add << parameters.target >>
+      - run: cargo ghdist --tag "$CIRCLE_TAG" --targets << parameters.target >> --no-upload --output-dir dist
+      - save_cache:
+          key: cargo-<< parameters.target >>-{{ checksum "Cargo.lock" }}
+          paths:
+            - ~/.cargo/registry
+            - target
+      - persist_to_workspace:
+          root: .
+          paths:
+            - dist
 
-      - name: Install Rust
-        uses: dtolnay/rust-toolchain@stable
-        with:
-          targets: x86_64-unknown-linux-gnu,aarch64-unknown-linux-gnu
+  release:
+    docker:
+      - image: cimg/rust:1.85
+    steps:
+      - checkout
+      - install-ghdist
+      - attach_workspace:
+          at: .
+      - run: >
+          cargo ghdist --tag "$CIRCLE_TAG"
+          --targets x86_64-unknown-linux-gnu,aarch64-unknown-linux-gnu,x86_64-apple-darwin,aarch64-apple-darwin
+          --from-artifacts "dist/$CIRCLE_TAG"
 
-      - name: Install cross-compilation tools
-        run: |
-          sudo apt-get update
-          sudo apt-get install -y gcc-aarch64-linux-gnu
+workflows:
+  release:
+    jobs:
+      - build-linux:
+          filters:
+            tags:
+              only: /.*/
+            branches:
+              ignore: /.*/
+          matrix:
+            parameters:
+              target: [x86_64-unknown-linux-gnu, aarch64-unknown-linux-gnu]
+      - build-macos:
+          filters:
+            tags:
+              only: /.*/
+            branches:
+              ignore: /.*/
+          matrix:
+            parameters:
+              target: [x86_64-apple-darwin, aarch64-apple-darwin]
+      - release:
+          filters:
+            tags:
+              only: /.*/
+            branches:
+              ignore: /.*/
+          requires:
+            - build-linux
+            - build-macos
+"#;
 
-      - name: Build cargo-ghdist
-        run: |
-          cargo build --release --bin cargo-ghdist
-          cp target/release/cargo-ghdist ~/.cargo/bin/ || true
+/// GitHub Actions workflow: a job resolving (and if needed creating) the tag,
+/// one `cargo ghdist upload` job per runner, and a `cargo ghdist finalize` job
+fn github_workflow(answers: &Answers) -> String {
+    let mut workflow = format!("{GENERATED_MARKER}\nname: Release\n\non:\n");
+    for trigger in &answers.triggers {
+        workflow.push_str(&match trigger {
+            Trigger::TagPush => "  push:\n    tags:\n      - 'v*'\n".to_string(),
+            Trigger::Manual => {
+                "  workflow_dispatch:\n    inputs:\n      tag:\n        description: Tag to release\n        required: true\n".to_string()
+            }
+            Trigger::Nightly => "  schedule:\n    - cron: '0 3 * * *'\n".to_string(),
+            Trigger::ReleasePr => format!(
+                "  pull_request:\n    types: [closed]\n    branches: [{}]\n",
+                answers.default_branch
+            ),
+        });
+    }
 
-      - name: Run cargo-ghdist (Linux)
-        env:
-          GITHUB_TOKEN: ${{ secrets.GITHUB_TOKEN }}
-        run: |
-          TAG=${GITHUB_REF#refs/tags/}
-          cargo ghdist \
-            --tag "$TAG" \
-            --targets x86_64-unknown-linux-gnu,aarch64-unknown-linux-gnu \
-            --format tgz
+    workflow.push_str("\npermissions:\n  contents: write\n");
+    if answers.attest {
+        workflow.push_str("  id-token: write\n  attestations: write\n");
+    }
 
-  # macOS builds
-  release-macos:
-    name: Release (macOS)
-    runs-on: macos-latest
+    workflow.push_str(
+        r#"
+jobs:
+  tag:
+"#,
+    );
+    if answers.triggers.contains(&Trigger::ReleasePr) {
+        // Closing a pull request without merging it, or any other one, releases nothing
+        workflow.push_str(
+            "    if: github.event_name != 'pull_request' || (github.event.pull_request.merged && startsWith(github.head_ref, 'release/'))\n",
+        );
+    }
+    workflow.push_str(
+        r#"    runs-on: ubuntu-latest
+    outputs:
+      tag: ${{ steps.tag.outputs.tag }}
+    steps:
+"#,
+    );
+    if answers.creates_tags() {
+        workflow.push_str(&format!(
+            r#"      - uses: actions/checkout@v4
+        with:
+          ref: ${{{{ github.event_name == 'push' && github.ref || '{}' }}}}
+"#,
+            answers.default_branch
+        ));
+        if answers.sign_tags {
+            workflow.push_str(
+                r#"      - uses: crazy-max/ghaction-import-gpg@v6
+        with:
+          gpg_private_key: ${{ secrets.GPG_PRIVATE_KEY }}
+          passphrase: ${{ secrets.GPG_PASSPHRASE }}
+          git_user_signingkey: true
+          git_tag_gpgsign: true
+"#,
+            );
+        }
+    }
+    if answers.creates_tags() {
+        workflow
+            .push_str("      - id: tag\n        env:\n          INPUT_TAG: ${{ inputs.tag }}\n");
+        if answers.triggers.contains(&Trigger::ReleasePr) {
+            workflow.push_str("          HEAD_REF: ${{ github.head_ref }}\n");
+        }
+        workflow.push_str("        run: |\n          case \"$GITHUB_EVENT_NAME\" in\n");
+        if answers.triggers.contains(&Trigger::Manual) {
+            workflow.push_str("            workflow_dispatch) TAG=\"$INPUT_TAG\" ;;\n");
+        }
+        if answers.triggers.contains(&Trigger::Nightly) {
+            workflow.push_str("            schedule) TAG=\"nightly-$(date -u +%Y%m%d)\" ;;\n");
+        }
+        if answers.triggers.contains(&Trigger::ReleasePr) {
+            workflow.push_str("            pull_request) TAG=\"${HEAD_REF#release/}\" ;;\n");
+        }
+        workflow.push_str("            *) TAG=\"$GITHUB_REF_NAME\" ;;\n          esac\n");
+    } else {
+        workflow.push_str("      - id: tag\n        run: |\n          TAG=\"$GITHUB_REF_NAME\"\n");
+    }
+    if answers.creates_tags() {
+        let tag = if answers.sign_tags {
+            r#"git tag -m "$TAG" "$TAG""#
+        } else {
+            r#"git tag "$TAG""#
+        };
+        workflow.push_str(&format!(
+            r#"          if ! git rev-parse -q --verify "refs/tags/$TAG" >/dev/null; then
+            {tag}
+            git push origin "$TAG"
+          fi
+"#
+        ));
+    }
+    workflow.push_str(
+        r#"          echo "tag=$TAG" >> "$GITHUB_OUTPUT"
 
+  build:
+    needs: tag
+    strategy:
+      fail-fast: false
+      matrix:
+        include:
+"#,
+    );
+    let groups = runner_groups(&answers.targets);
+    for (os, targets) in &groups {
+        workflow.push_str(&format!(
+            "          - {{ os: {os}, targets: \"{}\" }}\n",
+            targets.join(",")
+        ));
+    }
+    workflow.push_str(
+        r#"    runs-on: ${{ matrix.os }}
+    env:
+      GITHUB_TOKEN: ${{ secrets.GITHUB_TOKEN }}
+      TAG: ${{ needs.tag.outputs.tag }}
     steps:
-      - name: Checkout source
-        uses: actions/checkout@v4
+      - uses: actions/checkout@v4
+        with:
+          ref: ${{ needs.tag.outputs.tag }}
+      - uses: dtolnay/rust-toolchain@stable
+        with:
+          targets: ${{ matrix.targets }}
+      - uses: Swatinem/rust-cache@v2
+        with:
+          key: ${{ matrix.os }}
+"#,
+    );
+    let linux = groups
+        .iter()
+        .find(|(os, _)| *os == "ubuntu-latest")
+        .map(|(_, targets)| targets.as_slice())
+        .unwrap_or_default();
+    let mut packages = Vec::new();
+    if linux.iter().any(|target| cross_linker(target).is_some()) {
+        packages.push("gcc-aarch64-linux-gnu");
+    }
+    if linux.iter().any(|target| target.contains("musl")) {
+        packages.push("musl-tools");
+    }
+    if !packages.is_empty() {
+        workflow.push_str(&format!(
+            r#"      - name: Install cross-compilation tools
+        if: runner.os == 'Linux'
+        run: sudo apt-get update && sudo apt-get install -y {}
+"#,
+            packages.join(" ")
+        ));
+    }
+    workflow.push_str(&format!(
+        "      - name: Install cargo-ghdist\n        run: {INSTALL_GHDIST}\n"
+    ));
+    if answers.sign_windows {
+        workflow.push_str(
+            r#"      - name: Decode the signing certificate
+        if: runner.os == 'Windows'
+        shell: pwsh
+        env:
+          PFX_BASE64: ${{ secrets.WINDOWS_PFX_BASE64 }}
+        run: '[IO.File]::WriteAllBytes("codesign.pfx", [Convert]::FromBase64String($env:PFX_BASE64))'
+"#,
+        );
+    }
+    workflow.push_str(
+        r#"      - name: Build and upload
+        shell: bash
+"#,
+    );
+    if answers.sign_windows {
+        workflow.push_str(
+            "        env:\n          WINDOWS_PFX_PASSWORD: ${{ secrets.WINDOWS_PFX_PASSWORD }}\n",
+        );
+    }
+    workflow.push_str(
+        r#"        run: cargo ghdist upload --tag "$TAG" --targets "${{ matrix.targets }}"
+"#,
+    );
+    if answers.attest {
+        workflow.push_str(
+            r#"      - uses: actions/attest-build-provenance@v2
+        with:
+          subject-path: target/dist/${{ needs.tag.outputs.tag }}/*
+"#,
+        );
+    }
 
-      - name: Install Rust
-        uses: dtolnay/rust-toolchain@stable
+    workflow.push_str(
+        r#"
+  finalize:
+    needs: [tag, build]
+    runs-on: ubuntu-latest
+    env:
+      GITHUB_TOKEN: ${{ secrets.GITHUB_TOKEN }}
+      TAG: ${{ needs.tag.outputs.tag }}
+    steps:
+      - uses: actions/checkout@v4
         with:
-          targets: x86_64-apple-darwin,aarch64-apple-darwin
+          ref: ${{ needs.tag.outputs.tag }}
+          fetch-depth: 0
+      - uses: dtolnay/rust-toolchain@stable
+"#,
+    );
+    workflow.push_str(&format!(
+        "      - name: Install cargo-ghdist\n        run: {INSTALL_GHDIST}\n"
+    ));
+    if answers.triggers.contains(&Trigger::Nightly) {
+        workflow.push_str(
+            r#"      - name: Publish the release
+        run: cargo ghdist finalize --tag "$TAG" ${{ github.event_name == 'schedule' && '--latest false' || '' }}
+"#,
+        );
+    } else {
+        workflow.push_str(
+            r#"      - name: Publish the release
+        run: cargo ghdist finalize --tag "$TAG"
+"#,
+        );
+    }
+    workflow
+}
 
-      - name: Build cargo-ghdist
-        run: |
-          cargo build --release --bin cargo-ghdist
-          cp target/release/cargo-ghdist ~/.cargo/bin/ || true
+/// Where the workflow opening release pull requests lives
+const RELEASE_PR_WORKFLOW_PATH: &str = ".github/workflows/release-pr.yml";
+
+/// GitHub Actions workflow run by hand that bumps the version, adds the changelog
+/// section and opens a `release/<tag>` pull request; merging it starts the release
+fn release_pr_workflow(answers: &Answers) -> String {
+    let mut workflow = format!(
+        r#"{GENERATED_MARKER}
+name: Release PR
 
-      - name: Run cargo-ghdist (macOS)
+on:
+  workflow_dispatch:
+    inputs:
+      level:
+        description: Part of the version to bump
+        type: choice
+        options: [patch, minor, major, pre]
+        default: patch
+
+permissions:
+  contents: write
+  pull-requests: write
+
+jobs:
+  release-pr:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+        with:
+          ref: {}
+          fetch-depth: 0
+      - uses: dtolnay/rust-toolchain@stable
+      - name: Install cargo-ghdist
+        run: {INSTALL_GHDIST}
+"#,
+        answers.default_branch
+    );
+    workflow.push_str(&format!(
+        r#"      - name: Bump the version and update the changelog
+        id: bump
         env:
-          GITHUB_TOKEN: ${{ secrets.GITHUB_TOKEN }}
+          LEVEL: ${{{{ inputs.level }}}}
         run: |
-          TAG=${GITHUB_REF#refs/tags/}
-          cargo ghdist \
-            --tag "$TAG" \
-            --targets x86_64-apple-darwin,aarch64-apple-darwin \
-            --format tgz
-"#;
+          cargo ghdist bump "$LEVEL" --changelog --no-commit
+          VERSION=$(cargo metadata --no-deps --format-version 1 | jq -r '.packages[0].version')
+          echo "tag=v$VERSION" >> "$GITHUB_OUTPUT"
+      - uses: peter-evans/create-pull-request@v7
+        with:
+          branch: release/${{{{ steps.bump.outputs.tag }}}}
+          base: {}
+          commit-message: "chore: release ${{{{ steps.bump.outputs.tag }}}}"
+          title: "chore: release ${{{{ steps.bump.outputs.tag }}}}"
+          body: Merging this pull request tags ${{{{ steps.bump.outputs.tag }}}} and publishes the release.
+"#,
+        answers.default_branch
+    ));
+    workflow
+}
+
+/// Where the release pipeline of `provider` lives
+fn workflow_path(provider: CiProvider) -> &'static str {
+    match provider {
+        CiProvider::Github => ".github/workflows/release.yml",
+        CiProvider::Gitlab => ".gitlab-ci.yml",
+        CiProvider::Circleci => ".circleci/config.yml",
+    }
+}
+
+fn workflow_contents(provider: CiProvider, answers: &Answers) -> String {
+    match provider {
+        CiProvider::Github => github_workflow(answers),
+        CiProvider::Gitlab => GITLAB_CI_TEMPLATE.to_string(),
+        CiProvider::Circleci => CIRCLECI_TEMPLATE.to_string(),
+    }
+}
 
-const GHDIST_CONFIG_TEMPLATE: &str = r#"# cargo-ghdist configuration file
+/// What to do with a workflow that may already exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WorkflowAction {
+    Create,
+    UpToDate,
+    /// Replace a workflow an earlier `init` generated
+    Update,
+    /// Leave the user's own workflow alone and write ours next to it,
+    /// replacing one written there by an earlier `init`
+    WriteAlongside(PathBuf),
+}
+
+fn plan_workflow(path: &Path, existing: Option<&str>, contents: &str) -> WorkflowAction {
+    match existing {
+        None => WorkflowAction::Create,
+        Some(existing) if existing == contents => WorkflowAction::UpToDate,
+        Some(existing) if existing.starts_with(GENERATED_MARKER) => WorkflowAction::Update,
+        Some(_) => WorkflowAction::WriteAlongside(alongside_path(path)),
+    }
+}
+
+/// `release-ghdist.yml` next to `release.yml`, numbered if the user has that name too
+fn alongside_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| {
+            let suffix = if n == 1 {
+                "-ghdist".to_string()
+            } else {
+                format!("-ghdist-{n}")
+            };
+            path.with_file_name(format!("{stem}{suffix}{extension}"))
+        })
+        .find(|candidate| match fs::read_to_string(candidate) {
+            Ok(existing) => existing.starts_with(GENERATED_MARKER),
+            Err(_) => !candidate.exists(),
+        })
+        .expect("some numbered name is free")
+}
+
+/// Unified diff from `old` to `new`
+fn diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_name, new_name)
+        .to_string()
+}
+
+/// How to hook a workflow written next to the user's into their CI
+fn alongside_hint(provider: CiProvider, existing: &Path, written: &Path) -> String {
+    let (existing, written) = (existing.display(), written.display());
+    match provider {
+        CiProvider::Github => format!(
+            "Both workflows run; drop any release steps from {existing} that {written} replaces"
+        ),
+        CiProvider::Gitlab => {
+            format!("Add `include: {{ local: {written} }}` to {existing} to run the release jobs")
+        }
+        CiProvider::Circleci => format!(
+            "CircleCI only reads {existing}; copy the jobs and workflow from {written} into it"
+        ),
+    }
+}
+
+/// The generated ghdist.toml
+fn render_config(answers: &Answers, provider: CiProvider, workflow_path: &Path) -> String {
+    let mut config = String::from(
+        r#"# cargo-ghdist configuration file
 # Generated by cargo ghdist init
 
 [default]
@@ -92,11 +877,13 @@ format = "tgz"
 
 # Default targets to build
 targets = [
-    "x86_64-unknown-linux-gnu",
-    "aarch64-unknown-linux-gnu",
-    "x86_64-apple-darwin",
-    "aarch64-apple-darwin",
-]
+"#,
+    );
+    for target in &answers.targets {
+        config.push_str(&format!("    \"{target}\",\n"));
+    }
+    config.push_str(
+        r#"]
 
 # Skip cargo publish by default
 skip_publish = true
@@ -104,32 +891,81 @@ skip_publish = true
 # Generate checksums by default
 generate_checksum = true
 
-# Repository information (if not in Cargo.toml)
-# [repository]
-# owner = "your-username"
-# repo = "your-repo"
-
 # Binaries to include (if not specified, includes all)
 # bins = []
 
-# CI configuration
-[ci]
-provider = "github"
-workflow_path = ".github/workflows/release.yml"
-"#;
+# Repository information (if not in Cargo.toml)
+"#,
+    );
+    match answers
+        .repository
+        .as_deref()
+        .and_then(|repository| repository.split_once('/'))
+    {
+        Some((owner, repo)) => config.push_str(&format!(
+            "[repository]\nowner = \"{owner}\"\nrepo = \"{repo}\"\n"
+        )),
+        None => {
+            config.push_str("# [repository]\n# owner = \"your-username\"\n# repo = \"your-repo\"\n")
+        }
+    }
+
+    for target in &answers.targets {
+        if let Some(linker) = cross_linker(target) {
+            config.push_str(&format!("\n[target.\"{target}\"]\nlinker = \"{linker}\"\n"));
+        }
+    }
+    if answers.triggers.contains(&Trigger::ReleasePr) {
+        config = config.replace(
+            "# Binaries to include",
+            "# Changelog `bump --changelog` writes and release notes are taken from\nchangelog_file = \"CHANGELOG.md\"\n\n# Binaries to include",
+        );
+    }
+    if answers.sign_tags {
+        config.push_str("\n# GPG-sign tags created with --create-tag\n[tag]\nsign = true\n");
+    }
+    if answers.sign_windows {
+        config.push_str(
+            r#"
+# Authenticode signing; the password is read from WINDOWS_PFX_PASSWORD
+[windows_signing]
+pfx = "codesign.pfx"
+pfx_password_env = "WINDOWS_PFX_PASSWORD"
+"#,
+        );
+    }
+
+    config.push_str(&format!(
+        "\n# CI configuration\n[ci]\nprovider = \"{provider}\"\nworkflow_path = \"{}\"\n",
+        workflow_path.display()
+    ));
+    if provider == CiProvider::Gitlab {
+        config.push_str(
+            r#"
+# GitLab project the release is published to
+[gitlab]
+# url = "https://gitlab.example.com"  # self-managed instances
+# project = "group/project"           # defaults to the repository in Cargo.toml
+"#,
+        );
+    }
+    config
+}
 
 pub struct Initializer {
     yes: bool,
-    ci_provider: String,
+    ci_provider: CiProvider,
     skip_ci: bool,
+    release_pr: bool,
 }
 
 impl Initializer {
-    pub fn new(yes: bool, ci: String, skip_ci: bool) -> Self {
+    pub fn new(yes: bool, ci: CiProvider, skip_ci: bool, release_pr: bool) -> Self {
         Self {
             yes,
             ci_provider: ci,
             skip_ci,
+            release_pr,
         }
     }
 
@@ -140,6 +976,11 @@ impl Initializer {
         if !Path::new("Cargo.toml").exists() {
             anyhow::bail!("No Cargo.toml found. Please run this command from a Rust project root.");
         }
+        if self.release_pr && (self.skip_ci || self.ci_provider != CiProvider::Github) {
+            anyhow::bail!(
+                "--release-pr generates GitHub Actions workflows; use it with --ci github"
+            );
+        }
 
         // Parse Cargo.toml for basic info
         let cargo_toml = fs::read_to_string("Cargo.toml")?;
@@ -154,6 +995,35 @@ impl Initializer {
 
         println!("📦 Found package: {package_name}");
 
+        let detected = Detected::from_project();
+        let answers = if self.yes {
+            Answers::defaults(detected.default_branch.clone(), self.release_pr)
+        } else {
+            let mut prompter = Prompter {
+                input: io::stdin().lock(),
+                output: io::stdout(),
+            };
+            let answers = ask(
+                &mut prompter,
+                self.ci_provider,
+                self.skip_ci,
+                self.release_pr,
+                &detected,
+            )?;
+            println!();
+            answers
+        };
+
+        // Generate CI workflow if requested
+        let written_workflow = if self.skip_ci {
+            None
+        } else {
+            self.generate_workflow(&answers)?
+        };
+        let recorded_workflow = written_workflow
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(workflow_path(self.ci_provider)));
+
         // Create .config directory if it doesn't exist
         let config_dir = PathBuf::from(".config");
         if !config_dir.exists() {
@@ -167,37 +1037,56 @@ impl Initializer {
             if !self.confirm("Configuration file already exists. Overwrite?")? {
                 println!("⏭️  Skipping config file generation");
             } else {
-                self.write_config(&config_path)?;
+                self.write_config(&config_path, &answers, &recorded_workflow)?;
             }
         } else if !config_path.exists() {
-            self.write_config(&config_path)?;
+            self.write_config(&config_path, &answers, &recorded_workflow)?;
         }
 
         // Add build profile to Cargo.toml
         self.update_cargo_toml(&cargo_toml)?;
 
-        // Generate CI workflow if requested
-        if !self.skip_ci && self.ci_provider == "github" {
-            self.generate_github_workflow()?;
-        }
-
         // Show next steps
         println!("\n✅ Initialization complete!");
         println!("\n📝 Next steps:");
         println!("  1. Review and customize .config/ghdist.toml");
         if !self.skip_ci {
-            println!("  2. Review .github/workflows/release.yml");
-            println!("  3. Set up GITHUB_TOKEN secret in your repository settings");
+            println!("  2. Review {}", recorded_workflow.display());
+            match self.ci_provider {
+                CiProvider::Github => {
+                    println!("  3. Set up GITHUB_TOKEN secret in your repository settings")
+                }
+                CiProvider::Gitlab => {
+                    println!("  3. Add a masked GITLAB_TOKEN CI/CD variable with the api scope")
+                }
+                CiProvider::Circleci => {
+                    println!(
+                        "  3. Add GITHUB_TOKEN to the CircleCI project's environment variables"
+                    )
+                }
+            }
+            if answers.sign_tags && answers.creates_tags() {
+                println!("     and the GPG_PRIVATE_KEY and GPG_PASSPHRASE secrets");
+            }
+            if answers.sign_windows {
+                println!("     and the WINDOWS_PFX_BASE64 and WINDOWS_PFX_PASSWORD secrets");
+            }
+        }
+        if answers.triggers.contains(&Trigger::ReleasePr) {
+            println!("  4. Allow GitHub Actions to create pull requests (Settings → Actions)");
+            println!("  5. Run the Release PR workflow to open a version bump pull request");
+            println!("  6. Merge it, and cargo ghdist will tag and release it!");
+        } else {
+            println!("  4. Create a git tag: git tag v0.1.0");
+            println!("  5. Push the tag: git push origin v0.1.0");
+            println!("  6. cargo ghdist will automatically build and release!");
         }
-        println!("  4. Create a git tag: git tag v0.1.0");
-        println!("  5. Push the tag: git push origin v0.1.0");
-        println!("  6. cargo ghdist will automatically build and release!");
 
         Ok(())
     }
 
-    fn write_config(&self, path: &Path) -> Result<()> {
-        fs::write(path, GHDIST_CONFIG_TEMPLATE)?;
+    fn write_config(&self, path: &Path, answers: &Answers, workflow: &Path) -> Result<()> {
+        fs::write(path, render_config(answers, self.ci_provider, workflow))?;
         println!("✏️  Created configuration file: {}", path.display());
         Ok(())
     }
@@ -225,31 +1114,103 @@ lto = "thin"
         Ok(())
     }
 
-    fn generate_github_workflow(&self) -> Result<()> {
-        let workflow_dir = PathBuf::from(".github/workflows");
-
-        if !workflow_dir.exists() {
-            fs::create_dir_all(&workflow_dir)?;
-            println!("📁 Created .github/workflows directory");
+    /// Write the release workflow, and the release PR one if asked for, returning
+    /// where the release workflow is unless it was skipped
+    fn generate_workflow(&self, answers: &Answers) -> Result<Option<PathBuf>> {
+        let written = self.write_workflow(
+            PathBuf::from(workflow_path(self.ci_provider)),
+            workflow_contents(self.ci_provider, answers),
+            "release workflow",
+        )?;
+        if answers.triggers.contains(&Trigger::ReleasePr) {
+            self.write_workflow(
+                PathBuf::from(RELEASE_PR_WORKFLOW_PATH),
+                release_pr_workflow(answers),
+                "release PR workflow",
+            )?;
         }
+        Ok(written)
+    }
 
-        let workflow_path = workflow_dir.join("release.yml");
+    /// Write a workflow without clobbering one the user wrote,
+    /// returning where it is unless it was skipped
+    fn write_workflow(
+        &self,
+        workflow_path: PathBuf,
+        contents: String,
+        what: &str,
+    ) -> Result<Option<PathBuf>> {
+        let existing = fs::read_to_string(&workflow_path).ok();
 
-        if workflow_path.exists()
-            && !self.yes
-            && !self.confirm("Release workflow already exists. Overwrite?")?
-        {
-            println!("⏭️  Skipping workflow generation");
-            return Ok(());
-        }
+        let destination = match plan_workflow(&workflow_path, existing.as_deref(), &contents) {
+            WorkflowAction::Create => workflow_path,
+            WorkflowAction::UpToDate => {
+                println!("ℹ️  {} is up to date", workflow_path.display());
+                return Ok(Some(workflow_path));
+            }
+            WorkflowAction::Update => {
+                let name = workflow_path.display().to_string();
+                print!(
+                    "{}",
+                    diff(
+                        existing.as_deref().unwrap_or_default(),
+                        &contents,
+                        &name,
+                        &name
+                    )
+                );
+                if !self.confirm(&format!("Update the generated {what}?"))? {
+                    println!("⏭️  Skipping workflow generation");
+                    return Ok(None);
+                }
+                workflow_path
+            }
+            WorkflowAction::WriteAlongside(alongside) => {
+                println!(
+                    "⚠️  {} was not generated by cargo ghdist, so it is left as is",
+                    workflow_path.display()
+                );
+                // Compare with an earlier ghdist workflow there, or else with the user's
+                let (base, base_path) = match fs::read_to_string(&alongside) {
+                    Ok(previous) => (previous, &alongside),
+                    Err(_) => (existing.unwrap_or_default(), &workflow_path),
+                };
+                if base == contents {
+                    println!("ℹ️  {} is up to date", alongside.display());
+                    return Ok(Some(alongside));
+                }
+                print!(
+                    "{}",
+                    diff(
+                        &base,
+                        &contents,
+                        &base_path.display().to_string(),
+                        &alongside.display().to_string()
+                    )
+                );
+                if !self.confirm(&format!(
+                    "Write the {what} to {} instead?",
+                    alongside.display()
+                ))? {
+                    println!("⏭️  Skipping workflow generation");
+                    return Ok(None);
+                }
+                println!(
+                    "ℹ️  {}",
+                    alongside_hint(self.ci_provider, &workflow_path, &alongside)
+                );
+                alongside
+            }
+        };
 
-        fs::write(&workflow_path, RELEASE_WORKFLOW_TEMPLATE)?;
-        println!(
-            "✏️  Created GitHub Actions workflow: {}",
-            workflow_path.display()
-        );
+        if let Some(workflow_dir) = destination.parent().filter(|dir| !dir.exists()) {
+            fs::create_dir_all(workflow_dir)?;
+            println!("📁 Created {} directory", workflow_dir.display());
+        }
+        fs::write(&destination, contents)?;
+        println!("✏️  Created {what}: {}", destination.display());
 
-        Ok(())
+        Ok(Some(destination))
     }
 
     fn confirm(&self, prompt: &str) -> Result<bool> {
@@ -257,12 +1218,337 @@ lto = "thin"
             return Ok(true);
         }
 
-        print!("{prompt} (y/N): ");
-        io::stdout().flush()?;
+        let mut prompter = Prompter {
+            input: io::stdin().lock(),
+            output: io::stdout(),
+        };
+        prompter.confirm(prompt, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::io::Cursor;
+
+    fn scripted(input: &str) -> Prompter<Cursor<Vec<u8>>, Vec<u8>> {
+        Prompter {
+            input: Cursor::new(input.as_bytes().to_vec()),
+            output: Vec::new(),
+        }
+    }
+
+    fn everything() -> Answers {
+        Answers {
+            targets: vec![
+                "x86_64-unknown-linux-gnu".to_string(),
+                "aarch64-unknown-linux-musl".to_string(),
+                "aarch64-apple-darwin".to_string(),
+                "x86_64-pc-windows-msvc".to_string(),
+            ],
+            triggers: Trigger::ALL.to_vec(),
+            default_branch: "trunk".to_string(),
+            repository: Some("owner/tool".to_string()),
+            sign_tags: true,
+            sign_windows: true,
+            attest: true,
+        }
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("1, 3,3", 4), Some(vec![0, 2]));
+        assert_eq!(parse_selection("0", 4), None);
+        assert_eq!(parse_selection("5", 4), None);
+        assert_eq!(parse_selection("x", 4), None);
+        assert_eq!(parse_selection(",", 4), None);
+    }
+
+    #[test]
+    fn test_multi_select_asks_again_until_valid() {
+        let mut prompter = scripted("9\n2,1\n");
+        let picked = prompter.multi_select("Pick:", &["a", "b"], &[0]).unwrap();
+        assert_eq!(picked, vec![1, 0]);
+        let output = String::from_utf8(prompter.output).unwrap();
+        assert!(output.contains("  [x] 1. a\n  [ ] 2. b\n"));
+        assert!(output.contains("Pick numbers from 1 to 2"));
+
+        let mut prompter = scripted("\n");
+        assert_eq!(
+            prompter.multi_select("Pick:", &["a"], &[0]).unwrap(),
+            vec![0]
+        );
+    }
+
+    #[test]
+    fn test_ask() {
+        let detected = Detected {
+            default_branch: "trunk".to_string(),
+            has_repository: false,
+        };
+        // targets, triggers, branch, repository, sign tags, sign windows, attest
+        let mut prompter = scripted("1,7\n1,3\n\nowner/tool\ny\nn\ny\n");
+        let answers = ask(&mut prompter, CiProvider::Github, false, false, &detected).unwrap();
+        assert_eq!(
+            answers,
+            Answers {
+                targets: vec![
+                    "x86_64-unknown-linux-gnu".to_string(),
+                    "x86_64-pc-windows-msvc".to_string()
+                ],
+                triggers: vec![Trigger::TagPush, Trigger::Nightly],
+                default_branch: "trunk".to_string(),
+                repository: Some("owner/tool".to_string()),
+                sign_tags: true,
+                sign_windows: false,
+                attest: true,
+            }
+        );
+
+        // Pressing Enter everywhere gives the --yes answers
+        let detected = Detected {
+            default_branch: "main".to_string(),
+            has_repository: true,
+        };
+        let answers = ask(
+            &mut scripted(""),
+            CiProvider::Github,
+            false,
+            false,
+            &detected,
+        )
+        .unwrap();
+        assert_eq!(answers, Answers::defaults("main".to_string(), false));
+    }
+
+    #[test]
+    fn test_ask_preselects_release_pr() {
+        let detected = Detected {
+            default_branch: "main".to_string(),
+            has_repository: true,
+        };
+        let mut prompter = scripted("");
+        let answers = ask(&mut prompter, CiProvider::Github, false, true, &detected).unwrap();
+        assert_eq!(answers, Answers::defaults("main".to_string(), true));
+        assert_eq!(answers.triggers, vec![Trigger::TagPush, Trigger::ReleasePr]);
+        let output = String::from_utf8(prompter.output).unwrap();
+        assert!(output.contains("  [x] 4. Merged release PR"));
+    }
+
+    #[test]
+    fn test_ask_skips_workflow_questions_for_other_providers() {
+        let detected = Detected {
+            default_branch: "main".to_string(),
+            has_repository: true,
+        };
+        // targets, sign tags
+        let answers = ask(
+            &mut scripted("2\ny\n"),
+            CiProvider::Gitlab,
+            false,
+            false,
+            &detected,
+        )
+        .unwrap();
+        assert_eq!(answers.targets, vec!["aarch64-unknown-linux-gnu"]);
+        assert_eq!(answers.triggers, vec![Trigger::TagPush]);
+        assert!(answers.sign_tags);
+        assert!(!answers.attest);
+    }
+
+    #[test]
+    fn test_default_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/trunk").unwrap();
+        assert_eq!(default_branch(&repo).as_deref(), Some("trunk"));
+
+        repo.reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/develop",
+            true,
+            "test",
+        )
+        .unwrap();
+        assert_eq!(default_branch(&repo).as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn test_render_config() {
+        for provider in [CiProvider::Github, CiProvider::Gitlab, CiProvider::Circleci] {
+            let answers = Answers::defaults("main".to_string(), false);
+            let path = PathBuf::from(workflow_path(provider));
+            let config: Config =
+                crate::validate::parse(&render_config(&answers, provider, &path)).unwrap();
+            assert_eq!(config.default.targets, answers.targets);
+            assert_eq!(config.ci.provider, Some(provider.to_string()));
+            assert_eq!(
+                config.ci.workflow_path,
+                Some(PathBuf::from(workflow_path(provider)))
+            );
+            assert!(config.repository.owner.is_none());
+            assert_eq!(
+                config.target["aarch64-unknown-linux-gnu"].linker.as_deref(),
+                Some("aarch64-linux-gnu-gcc")
+            );
+            assert!(!config.tag.sign);
+        }
+
+        let alongside = Path::new(".github/workflows/release-ghdist.yml");
+        let config: Config =
+            crate::validate::parse(&render_config(&everything(), CiProvider::Github, alongside))
+                .unwrap();
+        assert_eq!(config.ci.workflow_path.as_deref(), Some(alongside));
+        assert_eq!(config.repository.owner.as_deref(), Some("owner"));
+        assert_eq!(config.repository.repo.as_deref(), Some("tool"));
+        assert_eq!(
+            config.target["aarch64-unknown-linux-musl"]
+                .linker
+                .as_deref(),
+            Some("aarch64-linux-gnu-gcc")
+        );
+        assert!(config.tag.sign);
+        assert_eq!(
+            config.windows_signing.pfx_password_env.as_deref(),
+            Some("WINDOWS_PFX_PASSWORD")
+        );
+    }
+
+    #[test]
+    fn test_github_workflow_defaults() {
+        let workflow = github_workflow(&Answers::defaults("main".to_string(), false));
+        assert!(workflow.contains("  push:\n    tags:\n      - 'v*'\n"));
+        assert!(!workflow.contains("workflow_dispatch"));
+        assert!(!workflow.contains("schedule"));
+        assert!(!workflow.contains("git push"));
+        assert!(!workflow.contains("attestations"));
+        assert!(workflow.contains(
+            "          - { os: ubuntu-latest, targets: \"x86_64-unknown-linux-gnu,aarch64-unknown-linux-gnu\" }\n"
+        ));
+        assert!(workflow.contains(
+            "          - { os: macos-latest, targets: \"x86_64-apple-darwin,aarch64-apple-darwin\" }\n"
+        ));
+        assert!(workflow.contains("apt-get install -y gcc-aarch64-linux-gnu\n"));
+        assert!(workflow.contains("cargo ghdist finalize --tag \"$TAG\"\n"));
+        assert!(!workflow.contains('\t'));
+    }
+
+    #[test]
+    fn test_github_workflow_options() {
+        let workflow = github_workflow(&everything());
+        assert!(workflow.contains("  workflow_dispatch:\n"));
+        assert!(workflow.contains("    - cron: '0 3 * * *'\n"));
+        assert!(workflow.contains("github.event_name == 'push' && github.ref || 'trunk'"));
+        assert!(workflow.contains("crazy-max/ghaction-import-gpg@v6"));
+        assert!(workflow.contains("git tag -m \"$TAG\" \"$TAG\""));
+        assert!(workflow.contains("  id-token: write\n  attestations: write\n"));
+        assert!(workflow.contains("actions/attest-build-provenance@v2"));
+        assert!(workflow.contains("secrets.WINDOWS_PFX_BASE64"));
+        assert!(workflow.contains("{ os: windows-latest, targets: \"x86_64-pc-windows-msvc\" }"));
+        assert!(workflow.contains("gcc-aarch64-linux-gnu musl-tools"));
+        assert!(workflow.contains("'--latest false'"));
+    }
+
+    #[test]
+    fn test_github_workflow_release_pr() {
+        let answers = Answers::defaults("trunk".to_string(), true);
+        let workflow = github_workflow(&answers);
+        assert!(workflow.contains("  pull_request:\n    types: [closed]\n    branches: [trunk]\n"));
+        assert!(workflow.contains(
+            "github.event.pull_request.merged && startsWith(github.head_ref, 'release/')"
+        ));
+        assert!(workflow.contains("pull_request) TAG=\"${HEAD_REF#release/}\" ;;"));
+        assert!(workflow.contains("git push origin \"$TAG\""));
+        assert!(
+            !github_workflow(&Answers::defaults("trunk".to_string(), false)).contains("HEAD_REF")
+        );
+
+        let release_pr = release_pr_workflow(&answers);
+        assert!(release_pr.starts_with(GENERATED_MARKER));
+        assert!(release_pr.contains("          ref: trunk\n"));
+        assert!(release_pr.contains("cargo ghdist bump \"$LEVEL\" --changelog --no-commit"));
+        assert!(release_pr.contains("branch: release/${{ steps.bump.outputs.tag }}\n"));
+        assert!(release_pr.contains("base: trunk\n"));
+        assert!(!release_pr.contains('\t'));
+
+        let config: Config = crate::validate::parse(&render_config(
+            &answers,
+            CiProvider::Github,
+            Path::new(workflow_path(CiProvider::Github)),
+        ))
+        .unwrap();
+        assert_eq!(
+            config.default.changelog_file,
+            Some(PathBuf::from("CHANGELOG.md"))
+        );
+    }
+
+    #[test]
+    fn test_plan_workflow() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("release.yml");
+        let ours = github_workflow(&Answers::defaults("main".to_string(), false));
+
+        assert_eq!(plan_workflow(&path, None, &ours), WorkflowAction::Create);
+        assert_eq!(
+            plan_workflow(&path, Some(&ours), &ours),
+            WorkflowAction::UpToDate
+        );
+        let older = ours.replace("'v*'", "'release-*'");
+        assert_eq!(
+            plan_workflow(&path, Some(&older), &ours),
+            WorkflowAction::Update
+        );
+
+        let theirs = "name: Release\non: push\njobs: {}\n";
+        assert_eq!(
+            plan_workflow(&path, Some(theirs), &ours),
+            WorkflowAction::WriteAlongside(dir.path().join("release-ghdist.yml"))
+        );
+        // An earlier ghdist workflow there is replaced, a file of the user's is not
+        fs::write(dir.path().join("release-ghdist.yml"), &older).unwrap();
+        assert_eq!(
+            plan_workflow(&path, Some(theirs), &ours),
+            WorkflowAction::WriteAlongside(dir.path().join("release-ghdist.yml"))
+        );
+        fs::write(dir.path().join("release-ghdist.yml"), theirs).unwrap();
+        assert_eq!(
+            plan_workflow(&path, Some(theirs), &ours),
+            WorkflowAction::WriteAlongside(dir.path().join("release-ghdist-2.yml"))
+        );
+    }
+
+    #[test]
+    fn test_alongside_path_keeps_dotfile_names() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            alongside_path(&dir.path().join(".gitlab-ci.yml")),
+            dir.path().join(".gitlab-ci-ghdist.yml")
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let diff = diff("a\nb\n", "a\nc\n", "old.yml", "new.yml");
+        assert!(diff.starts_with("--- old.yml\n+++ new.yml\n"));
+        assert!(diff.contains("\n-b\n+c\n"));
+    }
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+    #[test]
+    fn test_other_providers_release_on_tags() {
+        let answers = Answers::defaults("main".to_string(), false);
+        let gitlab = workflow_contents(CiProvider::Gitlab, &answers);
+        assert_eq!(workflow_path(CiProvider::Gitlab), ".gitlab-ci.yml");
+        assert!(gitlab.contains("if: $CI_COMMIT_TAG"));
+        assert!(gitlab.contains("--provider gitlab"));
+        assert!(gitlab.contains("--from-artifacts \"dist/$CI_COMMIT_TAG\""));
 
-        Ok(input.trim().eq_ignore_ascii_case("y"))
+        let circleci = workflow_contents(CiProvider::Circleci, &answers);
+        assert_eq!(workflow_path(CiProvider::Circleci), ".circleci/config.yml");
+        assert!(circleci.contains("--tag \"$CIRCLE_TAG\""));
+        assert!(circleci.contains("--from-artifacts \"dist/$CIRCLE_TAG\""));
+        assert!(!circleci.contains('\t'));
+        assert!(!gitlab.contains('\t'));
     }
 }
@@ -11,12 +11,32 @@
 //! ## Features
 //!
 //! - Multi-platform builds (Linux, macOS, Windows)
+//! - Builds inside user-provided Docker or Podman images
+//! - Remote builds of other platforms through GitHub Actions
 //! - Automatic archive creation (tar.gz, zip)
+//! - Debian and RPM packages for Linux targets
+//! - MSI and NSIS installers for Windows targets
+//! - Authenticode signing of Windows executables
+//! - Container images pushed to ghcr.io
+//! - npm wrapper packages for `npx` users
+//! - Asset mirrors on S3, R2 or MinIO buckets
+//! - Static download pages on GitHub Pages
+//! - Conventional-commit changelogs in release notes
+//! - Contributor and first-time-contributor credits
+//! - Binary size reports compared with the previous release
 //! - SHA256 checksum generation
 //! - Beautiful formatted release notes
 //! - Continuous releases with `--hash` option
+//! - Retention pruning of old continuous releases
+//! - Pre-flight checks for dirty trees, unpushed commits and release branches
+//! - Pre-build, pre-upload and post-release hook commands
+//! - Slack, Discord and generic webhook notifications
 //! - Integration with GitHub's auto-generated release notes
-//! - Configuration file support
+//! - Releases mirrored to GitLab with `--provider github,gitlab`
+//! - Release plans as text or JSON without building anything
+//! - `doctor` checks of the token, git state, toolchains and configuration
+//! - `verify` checks of published checksums, archives, binaries and signatures
+//! - Configuration file support with typo suggestions for unknown keys
 //! - CI/CD workflow generation
 //!
 //! ## Usage
@@ -51,17 +71,146 @@
 /// Core distribution builder that orchestrates the entire release process
 pub mod builder;
 
+/// Version bumping across Cargo.toml, the workspace and Cargo.lock
+pub mod bump;
+
+/// Compiler cache (sccache) for the release builds
+pub mod cache;
+
+/// Conventional-commit changelog generation for release notes
+pub mod changelog;
+
 /// Command-line interface definitions and argument parsing
 pub mod cli;
 
+/// Shell completion scripts with project-specific candidates
+pub mod completions;
+
 /// Configuration file handling and default settings management
 pub mod config;
 
+/// Builds inside user-provided Docker or Podman images
+pub mod container;
+
+/// Contributor listing for release notes
+pub mod contributors;
+
+/// Debian (.deb) package creation for Linux targets
+pub mod deb;
+
+/// Container image publishing to ghcr.io
+pub mod docker;
+
+/// Environment checks for `cargo ghdist doctor`
+pub mod doctor;
+
 /// Error types and error handling utilities
 pub mod error;
 
+/// Checksum and manifest fragments for CI matrix jobs sharing one release
+pub mod fanout;
+
+/// Release gates such as cargo-semver-checks
+pub mod gates;
+
 /// GitHub API client for creating releases and uploading assets
 pub mod github;
 
+/// Minimum glibc that Linux binaries need
+pub mod glibc;
+
+/// GitLab Releases backend for `--provider gitlab`
+pub mod gitlab;
+
+/// User-defined shell hooks run around the release
+pub mod hooks;
+
+/// Reuse of verified assets from an earlier run of the same release
+pub mod incremental;
+
+/// cdylib and staticlib artifacts shipped in library archives
+pub mod library;
+
+/// Release manifest (dist-manifest.json) generation
+pub mod manifest;
+
+/// Mirroring of release assets to S3-compatible storage
+pub mod mirror;
+
+/// Slack, Discord and generic webhook notifications about releases
+pub mod notifications;
+
+/// npm wrapper package generation and publishing
+pub mod npm;
+
 /// Archive creation and checksum generation utilities
 pub mod packager;
+
+/// Static download page published to the GitHub Pages branch
+pub mod pages;
+
+/// Dry-run release plans for `cargo ghdist plan`
+pub mod plan;
+
+/// Git state checks run before releasing
+pub mod preflight;
+
+/// Publishing workspace crates to crates.io in dependency order
+pub mod publish;
+
+/// Promotion of draft releases to published
+pub mod publish_draft;
+
+/// Label-based grouping of merged pull requests for release notes
+pub mod pull_requests;
+
+/// Remote builds through GitHub Actions workflow_dispatch
+pub mod remote_build;
+
+/// Diffing local assets against a release's uploads, so unchanged ones are skipped
+pub mod resume;
+
+/// Retention pruning of continuous (`--hash`) releases
+pub mod retention;
+
+/// Deletion of a published release and its tag
+pub mod rollback;
+
+/// RPM (.rpm) package creation for Linux targets
+pub mod rpm;
+
+/// Authenticode signing of Windows executables
+pub mod signing;
+
+/// Binary size comparison against the previous release
+pub mod size_report;
+
+/// Post-build smoke tests of produced binaries
+pub mod smoke_test;
+
+/// Machine-readable result of a run for `--output json`
+pub mod summary;
+
+/// Creation and pushing of release tags
+pub mod tagging;
+
+/// rustup toolchain and target checks for release builds
+pub mod toolchain;
+
+/// Step headers and spinners for people watching a release
+pub mod ui;
+
+/// Strict config parsing: "did you mean" hints, target triple and format checks
+pub mod validate;
+
+/// End-to-end checks of a published release for `cargo ghdist verify`
+pub mod verify;
+
+/// WebAssembly targets and wasm-opt
+pub mod wasm;
+
+/// Windows installer (.msi/.exe) creation via WiX or NSIS
+pub mod windows_installer;
+
+/// Workspace member discovery through `cargo metadata`
+pub mod workspace;
@@ -0,0 +1,213 @@
+use crate::wasm;
+use crate::workspace::WorkspacePackage;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A library target built as a cdylib or staticlib, shipped in its own archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeLibrary {
+    /// Target name as cargo writes it, with `-` already replaced by `_`
+    pub name: String,
+    pub cdylib: bool,
+    pub staticlib: bool,
+}
+
+/// cdylib and staticlib targets of the packages
+pub fn detect(packages: &[WorkspacePackage]) -> Vec<NativeLibrary> {
+    packages
+        .iter()
+        .flat_map(|package| &package.targets)
+        .filter_map(|target| {
+            let cdylib = target.kind.iter().any(|k| k == "cdylib");
+            let staticlib = target.kind.iter().any(|k| k == "staticlib");
+            (cdylib || staticlib).then(|| NativeLibrary {
+                name: target.name.replace('-', "_"),
+                cdylib,
+                staticlib,
+            })
+        })
+        .collect()
+}
+
+/// Files cargo writes for `library` on `target`, e.g. `libfoo.so` and `libfoo.a`
+pub fn file_names(library: &NativeLibrary, target: &str) -> Vec<String> {
+    let name = &library.name;
+    let msvc = target.contains("windows-msvc");
+    let mut files = Vec::new();
+    if library.cdylib {
+        if target.contains("windows") {
+            files.push(format!("{name}.dll"));
+            // Import library to link against the DLL
+            files.push(if msvc {
+                format!("{name}.dll.lib")
+            } else {
+                format!("lib{name}.dll.a")
+            });
+        } else if target.contains("apple") {
+            files.push(format!("lib{name}.dylib"));
+        } else if wasm::is_wasm_target(target) {
+            files.push(format!("{name}.wasm"));
+        } else {
+            files.push(format!("lib{name}.so"));
+        }
+    }
+    if library.staticlib {
+        files.push(if msvc {
+            format!("{name}.lib")
+        } else {
+            format!("lib{name}.a")
+        });
+    }
+    files
+}
+
+/// Library artifacts of `libraries` present in a target's output directory
+pub fn find(dir: &Path, libraries: &[NativeLibrary], target: &str) -> Vec<PathBuf> {
+    libraries
+        .iter()
+        .flat_map(|library| file_names(library, target))
+        .map(|file_name| dir.join(file_name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Archive entries for the libraries at the top level and the header
+/// directory's files under `include/`
+pub fn archive_entries(
+    libraries: &[PathBuf],
+    headers: Option<&Path>,
+) -> Result<Vec<(PathBuf, String)>> {
+    let mut entries = libraries
+        .iter()
+        .map(|path| {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .with_context(|| format!("Invalid library path {}", path.display()))?;
+            Ok((path.clone(), name.to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(headers) = headers {
+        let mut files = Vec::new();
+        collect_files(headers, &mut files)
+            .with_context(|| format!("Failed to read headers from {}", headers.display()))?;
+        files.sort();
+        for file in files {
+            let relative = file.strip_prefix(headers)?;
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            entries.push((file, format!("include/{relative}")));
+        }
+    }
+    Ok(entries)
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library(cdylib: bool, staticlib: bool) -> NativeLibrary {
+        NativeLibrary {
+            name: "ffi_demo".to_string(),
+            cdylib,
+            staticlib,
+        }
+    }
+
+    fn package(targets: serde_json::Value) -> WorkspacePackage {
+        serde_json::from_value(serde_json::json!({
+            "name": "ffi-demo",
+            "version": "1.0.0",
+            "description": null,
+            "repository": null,
+            "manifest_path": "/work/ffi-demo/Cargo.toml",
+            "publish": null,
+            "targets": targets,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_detect() {
+        let mixed = package(serde_json::json!([
+            {"name": "ffi-demo", "kind": ["bin"]},
+            {"name": "ffi_demo", "kind": ["cdylib", "rlib"]},
+        ]));
+        assert_eq!(detect(&[mixed]), vec![library(true, false)]);
+
+        let rlib_only = package(serde_json::json!([{"name": "ffi_demo", "kind": ["lib"]}]));
+        assert!(detect(&[rlib_only]).is_empty());
+    }
+
+    #[test]
+    fn test_file_names() {
+        let both = library(true, true);
+        assert_eq!(
+            file_names(&both, "x86_64-unknown-linux-gnu"),
+            vec!["libffi_demo.so", "libffi_demo.a"]
+        );
+        assert_eq!(
+            file_names(&both, "aarch64-apple-darwin"),
+            vec!["libffi_demo.dylib", "libffi_demo.a"]
+        );
+        assert_eq!(
+            file_names(&both, "x86_64-pc-windows-msvc"),
+            vec!["ffi_demo.dll", "ffi_demo.dll.lib", "ffi_demo.lib"]
+        );
+        assert_eq!(
+            file_names(&both, "x86_64-pc-windows-gnu"),
+            vec!["ffi_demo.dll", "libffi_demo.dll.a", "libffi_demo.a"]
+        );
+        assert_eq!(
+            file_names(&library(false, true), "x86_64-unknown-linux-musl"),
+            vec!["libffi_demo.a"]
+        );
+    }
+
+    #[test]
+    fn test_find_and_archive_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        fs::create_dir(&out).unwrap();
+        fs::write(out.join("libffi_demo.so"), b"so").unwrap();
+        // Only the shared library was built, so the static one is skipped
+        let libraries = find(&out, &[library(true, true)], "x86_64-unknown-linux-gnu");
+        assert_eq!(libraries, vec![out.join("libffi_demo.so")]);
+
+        let headers = dir.path().join("include");
+        fs::create_dir_all(headers.join("ffi")).unwrap();
+        fs::write(headers.join("ffi_demo.h"), b"").unwrap();
+        fs::write(headers.join("ffi").join("types.h"), b"").unwrap();
+        let entries = archive_entries(&libraries, Some(&headers)).unwrap();
+        let names = entries
+            .iter()
+            .map(|(_, name)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "libffi_demo.so",
+                "include/ffi/types.h",
+                "include/ffi_demo.h"
+            ]
+        );
+        assert!(archive_entries(&libraries, Some(&dir.path().join("missing"))).is_err());
+    }
+}
@@ -1,26 +1,74 @@
 mod builder;
+mod bump;
+mod cache;
+mod changelog;
 mod cli;
+mod completions;
 mod config;
+mod container;
+mod contributors;
+mod deb;
+mod docker;
+mod doctor;
 mod error;
+mod fanout;
+mod gates;
 mod github;
+mod gitlab;
+mod glibc;
+mod hooks;
+mod incremental;
 mod init;
+mod library;
+mod manifest;
+mod mirror;
+mod notifications;
+mod npm;
 mod packager;
+mod pages;
+mod plan;
+mod preflight;
+mod publish;
+mod publish_draft;
+mod pull_requests;
+mod remote_build;
+mod resume;
+mod retention;
+mod rollback;
+mod rpm;
+mod signing;
+mod size_report;
+mod smoke_test;
+mod summary;
+mod tagging;
+mod toolchain;
+mod ui;
+mod validate;
+mod verify;
+mod wasm;
+mod windows_installer;
+mod workspace;
 
 use anyhow::Result;
+use cargo_ghinstall::auth;
 use clap::Parser;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 use tracing_subscriber::EnvFilter;
 
 use crate::builder::DistBuilder;
-use crate::cli::{CargoCli, Command};
+use crate::bump::Bump;
+use crate::cli::{AuthAction, CargoCli, Command, LogFormat, OutputFormat, SelfAction};
+use crate::completions::Candidates;
+use crate::doctor::Doctor;
 use crate::init::Initializer;
+use crate::publish_draft::PublishDraft;
+use crate::rollback::Rollback;
+use crate::summary::Summary;
+use crate::verify::Verify;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
-
     // Parse command line arguments - handle both cargo subcommand and direct invocation
     let cli = match CargoCli::try_parse() {
         Ok(CargoCli::Ghdist(cli)) => cli,
@@ -32,24 +80,153 @@ async fn main() -> Result<()> {
         }
     };
 
+    // Initialize tracing; stdout is kept for the result with --output json
+    let output = cli.output;
+    let writer = match output {
+        OutputFormat::Text => BoxMakeWriter::new(std::io::stdout),
+        OutputFormat::Json => {
+            ui::reserve_stdout();
+            BoxMakeWriter::new(std::io::stderr)
+        }
+    };
+    // Step headers replace the info lines unless --verbose asks for them
+    let level = if cli.quiet {
+        LevelFilter::ERROR
+    } else if cli.verbose {
+        LevelFilter::INFO
+    } else {
+        LevelFilter::WARN
+    };
+    match cli.log_format {
+        LogFormat::Text => tracing_subscriber::fmt()
+            .with_env_filter(
+                EnvFilter::builder()
+                    .with_default_directive(level.into())
+                    .from_env_lossy(),
+            )
+            .with_writer(writer)
+            .init(),
+        // Events are logged at info, so show them unless RUST_LOG says otherwise
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_target(false)
+            .with_env_filter(
+                EnvFilter::builder()
+                    .with_default_directive(LevelFilter::INFO.into())
+                    .from_env_lossy(),
+            )
+            .with_writer(writer)
+            .init(),
+    }
+
     if cli.verbose {
         tracing::info!("Running cargo-ghdist with verbose output");
     }
+    // The table would break up JSON log lines on stdout
+    let table = !cli.quiet && cli.log_format == LogFormat::Text;
 
     // Handle subcommands
     match cli.command {
-        Some(Command::Init { yes, ci, skip_ci }) => {
+        Some(Command::Init {
+            yes,
+            ci,
+            skip_ci,
+            release_pr,
+        }) => {
             // Run init command
-            let initializer = Initializer::new(yes, ci, skip_ci);
+            let initializer = Initializer::new(yes, ci, skip_ci, release_pr);
             initializer.run().await?;
         }
+        Some(Command::Bump {
+            level,
+            ref pre_id,
+            no_commit,
+            changelog,
+        }) => {
+            let bump = Bump::new(
+                cli.clone().into(),
+                level,
+                pre_id.clone(),
+                no_commit,
+                changelog,
+            )?;
+            bump.run()?;
+        }
+        Some(Command::Rollback {
+            ref release_tag,
+            keep_tag,
+            yes,
+        }) => {
+            let rollback = Rollback::new(cli.clone().into(), release_tag, keep_tag, yes)?;
+            rollback.run().await?;
+        }
+        Some(Command::PublishDraft {
+            ref release_tag,
+            prerelease,
+        }) => {
+            let publish = PublishDraft::new(cli.clone().into(), release_tag, prerelease)?;
+            publish.run().await?;
+        }
+        Some(Command::Upload) => {
+            let builder = DistBuilder::new(cli.into())?;
+            print_summary(&builder.upload().await?, output, table)?;
+        }
+        Some(Command::Finalize) => {
+            let builder = DistBuilder::new(cli.into())?;
+            print_summary(&builder.finalize().await?, output, table)?;
+        }
+        Some(Command::Doctor) => {
+            Doctor::new(cli.into()).run().await?;
+        }
+        Some(Command::Plan) => {
+            let plan = DistBuilder::new(cli.into())?.plan()?;
+            match output {
+                OutputFormat::Text => print!("{}", plan.render()),
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&plan)?),
+            }
+        }
+        Some(Command::Auth { action }) => match action {
+            AuthAction::Login { with_token } => {
+                auth::login(with_token, "repo scope, or Contents: read and write")?
+            }
+            AuthAction::Logout => auth::logout()?,
+            AuthAction::Status => auth::status("ghdist")?,
+        },
+        Some(Command::Verify { ref release_tag }) => {
+            Verify::new(cli.clone().into(), release_tag)?.run().await?;
+        }
+        Some(Command::SelfManagement { action }) => match action {
+            SelfAction::Update { tag } => {
+                cargo_ghinstall::self_update::run(
+                    "cargo-ghdist",
+                    env!("CARGO_PKG_VERSION"),
+                    tag.as_deref(),
+                )
+                .await?
+            }
+        },
+        Some(Command::Completions { shell }) => {
+            let candidates = Candidates::detect(&cli.config);
+            completions::generate(shell, &candidates, &mut std::io::stdout());
+        }
         None => {
             // Default behavior: build and distribute
             let args = cli.into();
             let builder = DistBuilder::new(args)?;
-            builder.run().await?;
+            print_summary(&builder.run().await?, output, table)?;
         }
     }
 
     Ok(())
 }
+
+/// Print the result of a release as JSON, or as a table unless `table` is off
+fn print_summary(summary: &Summary, output: OutputFormat, table: bool) -> Result<()> {
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(summary)?),
+        OutputFormat::Text if table => print!("{}", summary.render()),
+        OutputFormat::Text => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use crate::signing::SignatureStatus;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE_NAME: &str = "dist-manifest.json";
+
+/// Machine-readable summary of a release, uploaded as `dist-manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseManifest {
+    pub tag: String,
+    pub targets: Vec<TargetManifest>,
+}
+
+/// Assets, binary sizes and signing state for a single target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetManifest {
+    pub target: String,
+    pub assets: Vec<String>,
+    #[serde(default)]
+    pub binaries: Vec<BinarySize>,
+    pub signature: SignatureStatus,
+    /// Highest `GLIBC_` symbol version the binaries need, for `*-linux-gnu*` targets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_glibc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BinarySize {
+    pub name: String,
+    pub size: u64,
+}
+
+impl ReleaseManifest {
+    pub fn new(tag: &str) -> Self {
+        Self {
+            tag: tag.to_string(),
+            targets: Vec::new(),
+        }
+    }
+
+    /// Record the binaries and assets produced for a target
+    pub fn add_target(
+        &mut self,
+        target: &str,
+        binaries: &[PathBuf],
+        assets: &[PathBuf],
+        signature: SignatureStatus,
+    ) -> Result<()> {
+        let binaries = binaries
+            .iter()
+            .map(|binary| {
+                Ok(BinarySize {
+                    name: file_name(binary),
+                    size: fs::metadata(binary)?.len(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.targets.push(TargetManifest {
+            target: target.to_string(),
+            assets: assets.iter().map(|a| file_name(a)).collect(),
+            binaries,
+            signature,
+            min_glibc: None,
+        });
+        Ok(())
+    }
+
+    /// Record the minimum glibc of a target added earlier
+    pub fn set_min_glibc(&mut self, target: &str, version: String) {
+        if let Some(entry) = self.targets.iter_mut().find(|t| t.target == target) {
+            entry.min_glibc = Some(version);
+        }
+    }
+
+    /// Parse a manifest downloaded from an earlier release
+    pub fn from_json(content: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(content)?)
+    }
+
+    /// Write the manifest into the output directory
+    pub fn write(&self, output_dir: &Path) -> Result<PathBuf> {
+        let path = output_dir.join(MANIFEST_FILE_NAME);
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_manifest() {
+        let temp_dir = tempdir().unwrap();
+        let binary = temp_dir.path().join("tool.exe");
+        fs::write(&binary, b"binary").unwrap();
+
+        let mut manifest = ReleaseManifest::new("v1.0.0");
+        manifest
+            .add_target(
+                "x86_64-pc-windows-msvc",
+                &[binary],
+                &[PathBuf::from("dist/tool-x86_64-pc-windows-msvc-v1.0.0.zip")],
+                SignatureStatus::Signed {
+                    tool: "signtool".to_string(),
+                },
+            )
+            .unwrap();
+        manifest
+            .add_target(
+                "x86_64-unknown-linux-gnu",
+                &[],
+                &[PathBuf::from(
+                    "dist/tool-x86_64-unknown-linux-gnu-v1.0.0.tar.gz",
+                )],
+                SignatureStatus::NotApplicable,
+            )
+            .unwrap();
+        manifest.set_min_glibc("x86_64-unknown-linux-gnu", "2.28".to_string());
+
+        let path = manifest.write(temp_dir.path()).unwrap();
+        assert_eq!(path.file_name().unwrap(), MANIFEST_FILE_NAME);
+
+        let value: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(value["tag"], "v1.0.0");
+        assert_eq!(
+            value["targets"][0]["assets"][0],
+            "tool-x86_64-pc-windows-msvc-v1.0.0.zip"
+        );
+        assert_eq!(value["targets"][0]["signature"]["status"], "signed");
+        assert_eq!(value["targets"][0]["signature"]["tool"], "signtool");
+        assert_eq!(value["targets"][0]["binaries"][0]["name"], "tool.exe");
+        assert_eq!(value["targets"][0]["binaries"][0]["size"], 6);
+        assert_eq!(value["targets"][1]["signature"]["status"], "not_applicable");
+        assert!(value["targets"][0].get("min_glibc").is_none());
+        assert_eq!(value["targets"][1]["min_glibc"], "2.28");
+
+        let parsed = ReleaseManifest::from_json(fs::read(&path).unwrap().as_slice()).unwrap();
+        assert_eq!(parsed.targets.len(), 2);
+        assert_eq!(
+            parsed.targets[0].binaries,
+            vec![BinarySize {
+                name: "tool.exe".to_string(),
+                size: 6
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_manifest_without_binaries() {
+        let manifest = ReleaseManifest::from_json(
+            br#"{"tag":"v0.9.0","targets":[{"target":"x86_64-unknown-linux-gnu","assets":[],"signature":{"status":"not_applicable"}}]}"#,
+        )
+        .unwrap();
+        assert!(manifest.targets[0].binaries.is_empty());
+        assert_eq!(manifest.targets[0].min_glibc, None);
+    }
+}
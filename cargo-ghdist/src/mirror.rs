@@ -0,0 +1,295 @@
+use anyhow::{Context, Result};
+use cargo_ghinstall::progress::Progress;
+use futures_util::TryStreamExt;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Url};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio_util::io::ReaderStream;
+
+use crate::config::S3PublisherConfig;
+use crate::github::get_content_type;
+
+/// Payload hash for streamed uploads, which S3 accepts over TLS
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Access keys read from the standard AWS environment variables
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl Credentials {
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        Ok(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")
+                .context("[publishers.s3] needs AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")
+                .context("[publishers.s3] needs AWS_SECRET_ACCESS_KEY")?,
+            session_token: var("AWS_SESSION_TOKEN"),
+        })
+    }
+}
+
+/// Percent-encode an object key, keeping the slashes between its segments
+pub fn encode_key(key: &str) -> String {
+    key.split('/')
+        .map(|segment| {
+            segment
+                .bytes()
+                .map(|b| match b {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                        (b as char).to_string()
+                    }
+                    _ => format!("%{b:02X}"),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// SigV4 signing key for one day, region and service
+pub fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{secret}").as_bytes(), date);
+    let key = hmac_sha256(&key, region);
+    let key = hmac_sha256(&key, service);
+    hmac_sha256(&key, "aws4_request")
+}
+
+/// Headers of a SigV4-signed PUT, including `Authorization`
+///
+/// `timestamp` is the request time as `YYYYMMDD'T'HHMMSS'Z'`.
+pub fn sign_put(
+    url: &Url,
+    region: &str,
+    credentials: &Credentials,
+    timestamp: &str,
+) -> Result<Vec<(String, String)>> {
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().context("URL has no host")?),
+        None => url.host_str().context("URL has no host")?.to_string(),
+    };
+    let date = &timestamp[..8];
+
+    let mut headers = vec![
+        ("host".to_string(), host),
+        (
+            "x-amz-content-sha256".to_string(),
+            UNSIGNED_PAYLOAD.to_string(),
+        ),
+        ("x-amz-date".to_string(), timestamp.to_string()),
+    ];
+    if let Some(token) = &credentials.session_token {
+        headers.push(("x-amz-security-token".to_string(), token.clone()));
+    }
+
+    let canonical_headers = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+        .collect::<String>();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "PUT\n{}\n\n{canonical_headers}\n{signed_headers}\n{UNSIGNED_PAYLOAD}",
+        url.path()
+    );
+
+    let scope = format!("{date}/{region}/s3/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{timestamp}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signature = hex::encode(hmac_sha256(
+        &signing_key(&credentials.secret_access_key, date, region, "s3"),
+        &string_to_sign,
+    ));
+
+    headers.push((
+        "authorization".to_string(),
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            credentials.access_key_id
+        ),
+    ));
+    // reqwest sets Host itself from the URL
+    headers.retain(|(name, _)| name != "host");
+    Ok(headers)
+}
+
+/// Asset mirrored to the bucket
+#[derive(Debug, Clone)]
+pub struct MirroredAsset {
+    pub name: String,
+    pub url: String,
+}
+
+/// Upload every asset under the release's key prefix, returning their public URLs
+pub async fn publish(
+    config: &S3PublisherConfig,
+    assets: &[PathBuf],
+    prefix: &str,
+    progress: bool,
+) -> Result<Vec<MirroredAsset>> {
+    if config.bucket.is_empty() {
+        anyhow::bail!("[publishers.s3] needs a bucket");
+    }
+    let credentials = Credentials::from_env()?;
+    let http_client = Client::builder()
+        .user_agent("cargo-ghdist")
+        .timeout(std::time::Duration::from_secs(300))
+        .build()?;
+
+    let mut mirrored = Vec::new();
+    for path in assets {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .context("Invalid asset path")?;
+        let key = encode_key(&format!("{prefix}/{name}"));
+        let url = Url::parse(&format!("{}/{}/{key}", config.endpoint(), config.bucket))
+            .context("Invalid [publishers.s3] endpoint")?;
+
+        tracing::info!("Mirroring {} to s3://{}", name, config.bucket);
+        upload(
+            &http_client,
+            &url,
+            config.region(),
+            &credentials,
+            path,
+            progress,
+        )
+        .await
+        .with_context(|| format!("Failed to mirror {name}"))?;
+        mirrored.push(MirroredAsset {
+            name: name.to_string(),
+            url: format!("{}/{key}", config.public_url()),
+        });
+    }
+    Ok(mirrored)
+}
+
+async fn upload(
+    http_client: &Client,
+    url: &Url,
+    region: &str,
+    credentials: &Credentials,
+    path: &Path,
+    progress: bool,
+) -> Result<()> {
+    let file = tokio::fs::File::open(path).await?;
+    let size = file.metadata().await?.len();
+    let timestamp = chrono::DateTime::<chrono::Utc>::from(std::time::SystemTime::now())
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string();
+
+    let mut request = http_client
+        .put(url.clone())
+        .header("Content-Type", get_content_type(path))
+        .header("Content-Length", size);
+    for (name, value) in sign_put(url, region, credentials, &timestamp)? {
+        request = request.header(name, value);
+    }
+
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let progress = Progress::new(name, Some(size), progress);
+    let counter = progress.clone();
+    let body = ReaderStream::new(file).inspect_ok(move |chunk| counter.inc(chunk.len() as u64));
+    let response = request.body(reqwest::Body::wrap_stream(body)).send().await;
+    progress.finish();
+
+    let response = response?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        anyhow::bail!("S3 error: {status} - {error_text}");
+    }
+    Ok(())
+}
+
+/// Release notes section listing the mirror URLs
+pub fn render_notes(assets: &[MirroredAsset]) -> String {
+    let mut notes =
+        String::from("\n### 🪞 Mirror\n\nThe assets are also available outside GitHub:\n\n");
+    for asset in assets {
+        notes.push_str(&format!("- [{}]({})\n", asset.name, asset.url));
+    }
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_key() {
+        assert_eq!(
+            encode_key("tool/v1.2.3+build/tool x86.tar.gz"),
+            "tool/v1.2.3%2Bbuild/tool%20x86.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_signing_key() {
+        // Example from the AWS SigV4 documentation
+        assert_eq!(
+            hex::encode(signing_key(
+                "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+                "20120215",
+                "us-east-1",
+                "iam"
+            )),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_sign_put() {
+        let credentials = Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: None,
+        };
+        let url = Url::parse("http://localhost:9000/releases/tool/v1.2.3/SHA256SUMS").unwrap();
+        let headers = sign_put(&url, "us-east-1", &credentials, "20250101T000000Z").unwrap();
+
+        let authorization = &headers
+            .iter()
+            .find(|(n, _)| n == "authorization")
+            .unwrap()
+            .1;
+        assert!(authorization.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20250101/us-east-1/s3/aws4_request, \
+             SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="
+        ));
+        assert!(!headers.iter().any(|(n, _)| n == "host"));
+    }
+
+    #[test]
+    fn test_render_notes() {
+        let notes = render_notes(&[MirroredAsset {
+            name: "SHA256SUMS".to_string(),
+            url: "https://mirror.example.com/tool/v1.2.3/SHA256SUMS".to_string(),
+        }]);
+        assert!(
+            notes.contains("- [SHA256SUMS](https://mirror.example.com/tool/v1.2.3/SHA256SUMS)\n")
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use crate::config::{NotificationsConfig, WebhookConfig, WebhookKind};
+
+const DEFAULT_TEMPLATE: &str = "🚀 {repository} {tag} released\n{url}\n{assets}";
+const DEFAULT_FAILURE_TEMPLATE: &str = "❌ {repository} {tag} release failed: {error}";
+
+/// Discord rejects messages longer than this
+const DISCORD_MESSAGE_LIMIT: usize = 2000;
+
+/// Outcome of a release run
+#[derive(Debug, Clone)]
+pub enum Event<'a> {
+    Released { url: &'a str, assets: &'a [String] },
+    Failed { error: &'a str },
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification<'a> {
+    /// `owner/repo`
+    pub repository: &'a str,
+    pub tag: &'a str,
+    pub event: Event<'a>,
+}
+
+/// Short asset listing such as `3 assets: a.tar.gz, b.zip, SHA256SUMS`
+fn asset_summary(assets: &[String]) -> String {
+    match assets.len() {
+        0 => "No assets".to_string(),
+        1 => format!("1 asset: {}", assets[0]),
+        n => format!("{n} assets: {}", assets.join(", ")),
+    }
+}
+
+/// Fill in the configured (or default) template for this notification
+pub fn render_message(config: &NotificationsConfig, notification: &Notification) -> String {
+    let message = match notification.event {
+        Event::Released { url, assets } => config
+            .template
+            .as_deref()
+            .unwrap_or(DEFAULT_TEMPLATE)
+            .replace("{url}", url)
+            .replace("{assets}", &asset_summary(assets)),
+        Event::Failed { error } => config
+            .failure_template
+            .as_deref()
+            .unwrap_or(DEFAULT_FAILURE_TEMPLATE)
+            .replace("{error}", error),
+    };
+
+    message
+        .replace("{repository}", notification.repository)
+        .replace("{tag}", notification.tag)
+}
+
+/// Request body for a webhook of the given kind
+pub fn payload(kind: WebhookKind, notification: &Notification, message: &str) -> Value {
+    match kind {
+        WebhookKind::Slack => json!({ "text": message }),
+        WebhookKind::Discord => {
+            let content = if message.chars().count() > DISCORD_MESSAGE_LIMIT {
+                let mut truncated = message
+                    .chars()
+                    .take(DISCORD_MESSAGE_LIMIT - 1)
+                    .collect::<String>();
+                truncated.push('…');
+                truncated
+            } else {
+                message.to_string()
+            };
+            json!({ "content": content })
+        }
+        WebhookKind::Generic => match notification.event {
+            Event::Released { url, assets } => json!({
+                "event": "released",
+                "repository": notification.repository,
+                "tag": notification.tag,
+                "url": url,
+                "assets": assets,
+                "message": message,
+            }),
+            Event::Failed { error } => json!({
+                "event": "failed",
+                "repository": notification.repository,
+                "tag": notification.tag,
+                "error": error,
+                "message": message,
+            }),
+        },
+    }
+}
+
+async fn post(client: &reqwest::Client, webhook: &WebhookConfig, body: &Value) -> Result<()> {
+    let response = client
+        .post(webhook.resolve_url()?)
+        .json(body)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        anyhow::bail!("{status} - {error_text}");
+    }
+
+    Ok(())
+}
+
+/// Post the notification to every configured webhook
+///
+/// Delivery problems are logged rather than returned so they never fail a release.
+pub async fn send(config: &NotificationsConfig, notification: &Notification<'_>) {
+    if matches!(notification.event, Event::Failed { .. }) && !config.on_failure {
+        return;
+    }
+
+    let message = render_message(config, notification);
+    let client = reqwest::Client::new();
+
+    for (index, webhook) in config.webhooks.iter().enumerate() {
+        let result = match webhook.kind() {
+            Ok(kind) => post(&client, webhook, &payload(kind, notification, &message)).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => tracing::info!("Sent release notification to webhook #{}", index + 1),
+            Err(e) => tracing::warn!("Failed to notify webhook #{}: {}", index + 1, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn released<'a>(assets: &'a [String]) -> Notification<'a> {
+        Notification {
+            repository: "owner/tool",
+            tag: "v1.0.0",
+            event: Event::Released {
+                url: "https://github.com/owner/tool/releases/tag/v1.0.0",
+                assets,
+            },
+        }
+    }
+
+    #[test]
+    fn test_render_default_templates() {
+        let assets = vec!["tool.tar.gz".to_string(), "SHA256SUMS".to_string()];
+        let config = NotificationsConfig::default();
+
+        assert_eq!(
+            render_message(&config, &released(&assets)),
+            "🚀 owner/tool v1.0.0 released\nhttps://github.com/owner/tool/releases/tag/v1.0.0\n2 assets: tool.tar.gz, SHA256SUMS"
+        );
+
+        let failed = Notification {
+            repository: "owner/tool",
+            tag: "v1.0.0",
+            event: Event::Failed {
+                error: "build failed",
+            },
+        };
+        assert_eq!(
+            render_message(&config, &failed),
+            "❌ owner/tool v1.0.0 release failed: build failed"
+        );
+    }
+
+    #[test]
+    fn test_render_custom_template() {
+        let config = NotificationsConfig {
+            template: Some("{tag} is out ({assets})".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_message(&config, &released(&["a.zip".to_string()])),
+            "v1.0.0 is out (1 asset: a.zip)"
+        );
+    }
+
+    #[test]
+    fn test_payloads() {
+        let assets = vec!["a.zip".to_string()];
+        let notification = released(&assets);
+
+        assert_eq!(
+            payload(WebhookKind::Slack, &notification, "hi"),
+            json!({ "text": "hi" })
+        );
+        assert_eq!(
+            payload(WebhookKind::Discord, &notification, "hi"),
+            json!({ "content": "hi" })
+        );
+
+        let long = "x".repeat(3000);
+        let discord = payload(WebhookKind::Discord, &notification, &long);
+        assert_eq!(
+            discord["content"].as_str().unwrap().chars().count(),
+            DISCORD_MESSAGE_LIMIT
+        );
+
+        let generic = payload(WebhookKind::Generic, &notification, "hi");
+        assert_eq!(generic["event"], "released");
+        assert_eq!(generic["tag"], "v1.0.0");
+        assert_eq!(generic["assets"], json!(["a.zip"]));
+    }
+}
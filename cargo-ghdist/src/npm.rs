@@ -0,0 +1,385 @@
+use crate::config::NpmPublisherConfig;
+use crate::error::GhDistError;
+use crate::packager::PackageMetadata;
+use crate::ui;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const DEFAULT_REGISTRY: &str = "https://registry.npmjs.org";
+
+const INSTALL_JS: &str = r#"#!/usr/bin/env node
+"use strict";
+
+// Generated by cargo-ghdist: downloads the prebuilt binary for this platform.
+const crypto = require("crypto");
+const fs = require("fs");
+const https = require("https");
+const path = require("path");
+const { execFileSync } = require("child_process");
+
+const BASE_URL = __BASE_URL__;
+const PLATFORMS = __PLATFORMS__;
+
+function download(url, redirects = 5) {
+  return new Promise((resolve, reject) => {
+    https
+      .get(url, { headers: { "User-Agent": "cargo-ghdist-npm" } }, (res) => {
+        if (res.statusCode >= 300 && res.statusCode < 400 && res.headers.location && redirects > 0) {
+          res.resume();
+          resolve(download(new URL(res.headers.location, url).toString(), redirects - 1));
+          return;
+        }
+        if (res.statusCode !== 200) {
+          res.resume();
+          reject(new Error(`download failed with status ${res.statusCode}: ${url}`));
+          return;
+        }
+        const chunks = [];
+        res.on("data", (chunk) => chunks.push(chunk));
+        res.on("end", () => resolve(Buffer.concat(chunks)));
+        res.on("error", reject);
+      })
+      .on("error", reject);
+  });
+}
+
+async function main() {
+  const key = `${process.platform}-${process.arch}`;
+  const asset = PLATFORMS[key];
+  if (!asset) {
+    throw new Error(`unsupported platform ${key} (supported: ${Object.keys(PLATFORMS).join(", ")})`);
+  }
+
+  const data = await download(`${BASE_URL}/${asset.name}`);
+  const digest = crypto.createHash("sha256").update(data).digest("hex");
+  if (digest !== asset.sha256) {
+    throw new Error(`checksum mismatch for ${asset.name}: expected ${asset.sha256}, got ${digest}`);
+  }
+
+  const binDir = path.join(__dirname, "bin");
+  fs.mkdirSync(binDir, { recursive: true });
+  const archive = path.join(binDir, asset.name);
+  fs.writeFileSync(archive, data);
+
+  if (!asset.name.endsWith(".zip")) {
+    execFileSync("tar", ["-xzf", archive, "-C", binDir]);
+  } else if (process.platform === "win32") {
+    execFileSync("powershell", [
+      "-NoProfile",
+      "-Command",
+      `Expand-Archive -Force -LiteralPath '${archive}' -DestinationPath '${binDir}'`,
+    ]);
+  } else {
+    execFileSync("unzip", ["-o", "-q", archive, "-d", binDir]);
+  }
+  fs.unlinkSync(archive);
+}
+
+main().catch((err) => {
+  console.error(`Failed to install prebuilt binary: ${err.message}`);
+  process.exit(1);
+});
+"#;
+
+const SHIM_JS: &str = r#"#!/usr/bin/env node
+"use strict";
+
+// Generated by cargo-ghdist: forwards to the downloaded binary.
+const path = require("path");
+const { spawnSync } = require("child_process");
+
+const exe = process.platform === "win32" ? "__BINARY__.exe" : "__BINARY__";
+const result = spawnSync(path.join(__dirname, "..", "bin", exe), process.argv.slice(2), {
+  stdio: "inherit",
+});
+if (result.error) {
+  console.error(result.error.message);
+  process.exit(1);
+}
+process.exit(result.status === null ? 1 : result.status);
+"#;
+
+/// Release information needed to generate the npm package
+pub struct NpmRelease<'a> {
+    pub owner: &'a str,
+    pub repo: &'a str,
+    pub tag: &'a str,
+    /// Archive asset for each built target
+    pub archives: &'a [(String, PathBuf)],
+    /// Binary names (without .exe) exposed through `bin` shims
+    pub binaries: &'a [String],
+}
+
+/// Map a Rust target to Node's `${process.platform}-${process.arch}`
+pub fn node_platform(target: &str) -> Option<String> {
+    let platform = if target.contains("linux") {
+        "linux"
+    } else if target.contains("apple-darwin") {
+        "darwin"
+    } else if target.contains("windows") {
+        "win32"
+    } else if target.contains("freebsd") {
+        "freebsd"
+    } else {
+        return None;
+    };
+
+    let arch = match target.split('-').next().unwrap_or("") {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        "i686" => "ia32",
+        "armv7" => "arm",
+        _ => return None,
+    };
+
+    Some(format!("{platform}-{arch}"))
+}
+
+/// npm requires a SemVer version; accept tags such as `v1.2.3` or `1.2.3-rc.1`
+pub fn npm_version(tag: &str) -> Result<String> {
+    let version = tag.strip_prefix('v').unwrap_or(tag);
+    let core = version.split(['-', '+']).next().unwrap_or("");
+    let parts = core.split('.').collect::<Vec<_>>();
+
+    if parts.len() != 3
+        || parts
+            .iter()
+            .any(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(GhDistError::Config(format!(
+            "npm publishing requires a SemVer tag, got '{tag}'"
+        ))
+        .into());
+    }
+
+    Ok(version.to_string())
+}
+
+/// Build the platform table embedded in install.js, preferring musl over glibc archives
+fn platform_table(archives: &[(String, PathBuf)]) -> Result<serde_json::Value> {
+    let mut table = BTreeMap::new();
+
+    for (target, archive) in archives {
+        let Some(key) = node_platform(target) else {
+            continue;
+        };
+        if table.contains_key(&key) && !target.contains("musl") {
+            continue;
+        }
+
+        let name = archive
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| GhDistError::Package("Invalid archive path".to_string()))?;
+        let sha256 = hex::encode(Sha256::digest(fs::read(archive)?));
+        table.insert(key, serde_json::json!({ "name": name, "sha256": sha256 }));
+    }
+
+    Ok(serde_json::to_value(table)?)
+}
+
+/// Write package.json, install.js and bin shims into `{output_dir}/npm`
+pub fn generate_package(
+    config: &NpmPublisherConfig,
+    metadata: &PackageMetadata,
+    release: &NpmRelease,
+    output_dir: &Path,
+) -> Result<PathBuf> {
+    let platforms = platform_table(release.archives)?;
+    if platforms.as_object().is_none_or(|p| p.is_empty()) {
+        return Err(GhDistError::Package(
+            "No archives for npm-supported platforms were built".to_string(),
+        )
+        .into());
+    }
+
+    let package_dir = output_dir.join("npm");
+    if package_dir.exists() {
+        fs::remove_dir_all(&package_dir)?;
+    }
+    fs::create_dir_all(package_dir.join("shims"))?;
+
+    let bin = release
+        .binaries
+        .iter()
+        .map(|name| (name.clone(), format!("shims/{name}.js")))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut package = serde_json::json!({
+        "name": config.package.clone().unwrap_or_else(|| metadata.name.clone()),
+        "version": npm_version(release.tag)?,
+        "description": metadata.description.clone().unwrap_or_default(),
+        "repository": {
+            "type": "git",
+            "url": format!("https://github.com/{}/{}", release.owner, release.repo),
+        },
+        "bin": bin,
+        "scripts": { "postinstall": "node install.js" },
+        "files": ["install.js", "shims"],
+    });
+    if let Some(license) = &metadata.license {
+        package["license"] = serde_json::json!(license);
+    }
+    if let Some(homepage) = &metadata.homepage {
+        package["homepage"] = serde_json::json!(homepage);
+    }
+    fs::write(
+        package_dir.join("package.json"),
+        serde_json::to_string_pretty(&package)?,
+    )?;
+
+    let base_url = format!(
+        "https://github.com/{}/{}/releases/download/{}",
+        release.owner, release.repo, release.tag
+    );
+    let install_js = INSTALL_JS
+        .replace("__BASE_URL__", &serde_json::to_string(&base_url)?)
+        .replace("__PLATFORMS__", &serde_json::to_string_pretty(&platforms)?);
+    fs::write(package_dir.join("install.js"), install_js)?;
+
+    for name in release.binaries {
+        fs::write(
+            package_dir.join("shims").join(format!("{name}.js")),
+            SHIM_JS.replace("__BINARY__", name),
+        )?;
+    }
+
+    tracing::info!("Generated npm package: {}", package_dir.display());
+    Ok(package_dir)
+}
+
+/// Publish the generated package with `npm publish`, authenticating via NPM_TOKEN
+pub fn publish(config: &NpmPublisherConfig, package_dir: &Path) -> Result<()> {
+    if std::env::var("NPM_TOKEN").is_err() {
+        return Err(GhDistError::Config(
+            "NPM_TOKEN must be set to publish the npm package".to_string(),
+        )
+        .into());
+    }
+
+    let registry = config.registry.as_deref().unwrap_or(DEFAULT_REGISTRY);
+    // npm expands ${NPM_TOKEN} itself, so the token is never written to disk
+    let registry_host = registry
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/');
+    fs::write(
+        package_dir.join(".npmrc"),
+        format!("//{registry_host}/:_authToken=${{NPM_TOKEN}}\n"),
+    )?;
+
+    let status = Command::new("npm")
+        .arg("publish")
+        .arg("--access")
+        .arg(config.access.as_deref().unwrap_or("public"))
+        .arg("--registry")
+        .arg(registry)
+        .current_dir(package_dir)
+        .stdout(ui::child_stdout())
+        .status()
+        .context("Failed to run `npm`. Is Node.js installed?")?;
+
+    if !status.success() {
+        return Err(GhDistError::Package("npm publish failed".to_string()).into());
+    }
+
+    tracing::info!("Published npm package from {}", package_dir.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_node_platform() {
+        assert_eq!(
+            node_platform("x86_64-unknown-linux-gnu").as_deref(),
+            Some("linux-x64")
+        );
+        assert_eq!(
+            node_platform("aarch64-apple-darwin").as_deref(),
+            Some("darwin-arm64")
+        );
+        assert_eq!(
+            node_platform("x86_64-pc-windows-msvc").as_deref(),
+            Some("win32-x64")
+        );
+        assert_eq!(node_platform("wasm32-wasip1"), None);
+    }
+
+    #[test]
+    fn test_npm_version() {
+        assert_eq!(npm_version("v1.2.3").unwrap(), "1.2.3");
+        assert_eq!(npm_version("1.2.3-rc.1").unwrap(), "1.2.3-rc.1");
+        assert!(npm_version("main").is_err());
+        assert!(npm_version("v1.2").is_err());
+    }
+
+    #[test]
+    fn test_generate_package() {
+        let temp_dir = tempdir().unwrap();
+        let gnu = temp_dir
+            .path()
+            .join("tool-x86_64-unknown-linux-gnu-v1.0.0.tar.gz");
+        let musl = temp_dir
+            .path()
+            .join("tool-x86_64-unknown-linux-musl-v1.0.0.tar.gz");
+        let mac = temp_dir
+            .path()
+            .join("tool-aarch64-apple-darwin-v1.0.0.tar.gz");
+        fs::write(&gnu, b"gnu").unwrap();
+        fs::write(&musl, b"musl").unwrap();
+        fs::write(&mac, b"mac").unwrap();
+
+        let archives = vec![
+            ("x86_64-unknown-linux-musl".to_string(), musl),
+            ("x86_64-unknown-linux-gnu".to_string(), gnu),
+            ("aarch64-apple-darwin".to_string(), mac),
+        ];
+        let binaries = vec!["tool".to_string()];
+        let metadata = PackageMetadata {
+            name: "tool".to_string(),
+            version: "1.0.0".to_string(),
+            license: Some("MIT".to_string()),
+            ..Default::default()
+        };
+        let config = NpmPublisherConfig {
+            package: Some("@owner/tool".to_string()),
+            ..Default::default()
+        };
+        let release = NpmRelease {
+            owner: "owner",
+            repo: "tool",
+            tag: "v1.0.0",
+            archives: &archives,
+            binaries: &binaries,
+        };
+
+        let dir = generate_package(&config, &metadata, &release, temp_dir.path()).unwrap();
+
+        let package: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("package.json")).unwrap()).unwrap();
+        assert_eq!(package["name"], "@owner/tool");
+        assert_eq!(package["version"], "1.0.0");
+        assert_eq!(package["license"], "MIT");
+        assert_eq!(package["bin"]["tool"], "shims/tool.js");
+        assert_eq!(package["scripts"]["postinstall"], "node install.js");
+
+        let install_js = fs::read_to_string(dir.join("install.js")).unwrap();
+        assert!(install_js.contains(
+            r#"const BASE_URL = "https://github.com/owner/tool/releases/download/v1.0.0";"#
+        ));
+        assert!(install_js.contains("tool-x86_64-unknown-linux-musl-v1.0.0.tar.gz"));
+        assert!(!install_js.contains("tool-x86_64-unknown-linux-gnu-v1.0.0.tar.gz"));
+        assert!(install_js.contains(&hex::encode(Sha256::digest(b"mac"))));
+
+        let shim = fs::read_to_string(dir.join("shims").join("tool.js")).unwrap();
+        assert!(shim.contains(r#""tool.exe" : "tool""#));
+    }
+}
@@ -1,9 +1,136 @@
 use crate::cli::ArchiveFormat;
+use crate::config::CompletionsConfig;
 use crate::error::GhDistError;
 use anyhow::Result;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use std::fs::{self, File};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Package metadata shared by the system package backends
+#[derive(Debug, Clone, Default)]
+pub struct PackageMetadata {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub authors: Vec<String>,
+    pub homepage: Option<String>,
+    pub license: Option<String>,
+}
+
+/// A file installed by a system package
+pub struct PackageFile {
+    pub path: String,
+    pub content: Vec<u8>,
+    pub mode: u32,
+}
+
+/// Collect binaries, completions and man pages with their install paths
+///
+/// Paths are relative to the filesystem root; `zsh_dir` differs between
+/// distributions (e.g. `vendor-completions` on Debian, `site-functions` on Fedora).
+pub fn collect_package_files(
+    binaries: &[PathBuf],
+    name: &str,
+    completions: &CompletionsConfig,
+    man_pages: &[PathBuf],
+    zsh_dir: &str,
+) -> Result<Vec<PackageFile>> {
+    let mut files = Vec::new();
+
+    for binary in binaries {
+        let file_name = binary
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| GhDistError::Package("Invalid file path".to_string()))?;
+        files.push(PackageFile {
+            path: format!("usr/bin/{file_name}"),
+            content: fs::read(binary)?,
+            mode: 0o755,
+        });
+    }
+
+    let completions = [
+        (
+            &completions.bash,
+            format!("usr/share/bash-completion/completions/{name}"),
+        ),
+        (&completions.zsh, format!("{zsh_dir}/_{name}")),
+        (
+            &completions.fish,
+            format!("usr/share/fish/vendor_completions.d/{name}.fish"),
+        ),
+    ];
+    for (source, path) in completions {
+        if let Some(source) = source {
+            files.push(PackageFile {
+                path,
+                content: fs::read(source)?,
+                mode: 0o644,
+            });
+        }
+    }
+
+    for page in man_pages {
+        files.push(man_page(page)?);
+    }
+
+    Ok(files)
+}
+
+/// Read a man page, gzip it and place it in the matching section directory
+fn man_page(source: &Path) -> Result<PackageFile> {
+    let content = fs::read(source)?;
+    let already_compressed = source.extension().is_some_and(|ext| ext == "gz");
+    let page = if already_compressed {
+        source.file_stem()
+    } else {
+        source.file_name()
+    }
+    .and_then(|n| n.to_str())
+    .ok_or_else(|| GhDistError::Package(format!("Invalid man page: {}", source.display())))?;
+
+    let section = Path::new(page)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(|ext| ext.chars().next())
+        .filter(|ch| ch.is_ascii_digit())
+        .ok_or_else(|| {
+            GhDistError::Package(format!(
+                "Man page {} has no section suffix (e.g. .1)",
+                source.display()
+            ))
+        })?;
+
+    let content = if already_compressed {
+        content
+    } else {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&content)?;
+        encoder.finish()?
+    };
+
+    Ok(PackageFile {
+        path: format!("usr/share/man/man{section}/{page}.gz"),
+        content,
+        mode: 0o644,
+    })
+}
+
+/// Timestamp for archive entries, honoring SOURCE_DATE_EPOCH for reproducible builds
+pub fn source_date_epoch() -> u64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        })
+}
 
 /// Package binaries into an archive
 pub fn create_archive(
@@ -11,16 +138,36 @@ pub fn create_archive(
     output_dir: &Path,
     archive_name: &str,
     format: ArchiveFormat,
+) -> Result<PathBuf> {
+    let entries = binaries
+        .iter()
+        .map(|path| {
+            let file_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| GhDistError::Package("Invalid file path".to_string()))?;
+            Ok((path.clone(), file_name.to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    create_archive_with_entries(&entries, output_dir, archive_name, format)
+}
+
+/// Package files into an archive, each under its own path in the archive
+pub fn create_archive_with_entries(
+    entries: &[(PathBuf, String)],
+    output_dir: &Path,
+    archive_name: &str,
+    format: ArchiveFormat,
 ) -> Result<PathBuf> {
     let archive_path = match format {
         ArchiveFormat::Tgz => {
             let path = output_dir.join(format!("{archive_name}.tar.gz"));
-            create_tar_gz(&path, binaries)?;
+            create_tar_gz(&path, entries)?;
             path
         }
         ArchiveFormat::Zip => {
             let path = output_dir.join(format!("{archive_name}.zip"));
-            create_zip(&path, binaries)?;
+            create_zip(&path, entries)?;
             path
         }
     };
@@ -30,18 +177,14 @@ pub fn create_archive(
 }
 
 /// Create a tar.gz archive
-fn create_tar_gz(archive_path: &Path, files: &[PathBuf]) -> Result<()> {
+fn create_tar_gz(archive_path: &Path, entries: &[(PathBuf, String)]) -> Result<()> {
     let tar_file = File::create(archive_path)?;
     let gz_encoder = flate2::write::GzEncoder::new(tar_file, flate2::Compression::default());
     let mut tar_builder = tar::Builder::new(gz_encoder);
 
-    for file_path in files {
-        let file_name = file_path
-            .file_name()
-            .ok_or_else(|| GhDistError::Package("Invalid file path".to_string()))?;
-
+    for (file_path, name) in entries {
         let mut file = File::open(file_path)?;
-        tar_builder.append_file(file_name, &mut file)?;
+        tar_builder.append_file(name, &mut file)?;
     }
 
     tar_builder.finish()?;
@@ -49,7 +192,7 @@ fn create_tar_gz(archive_path: &Path, files: &[PathBuf]) -> Result<()> {
 }
 
 /// Create a zip archive
-fn create_zip(archive_path: &Path, files: &[PathBuf]) -> Result<()> {
+fn create_zip(archive_path: &Path, entries: &[(PathBuf, String)]) -> Result<()> {
     let file = File::create(archive_path)?;
     let mut zip = zip::ZipWriter::new(file);
 
@@ -57,13 +200,8 @@ fn create_zip(archive_path: &Path, files: &[PathBuf]) -> Result<()> {
         .compression_method(zip::CompressionMethod::Deflated)
         .unix_permissions(0o755);
 
-    for file_path in files {
-        let file_name = file_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| GhDistError::Package("Invalid file path".to_string()))?;
-
-        zip.start_file(file_name, options)?;
+    for (file_path, name) in entries {
+        zip.start_file(name.as_str(), options)?;
 
         let file_content = fs::read(file_path)?;
         zip.write_all(&file_content)?;
@@ -73,25 +211,19 @@ fn create_zip(archive_path: &Path, files: &[PathBuf]) -> Result<()> {
     Ok(())
 }
 
+/// Checksums of every asset of a release
+pub const CHECKSUM_FILE_NAME: &str = "SHA256SUMS";
+
+/// Extension of the per-asset checksum files next to each asset
+pub const SIDECAR_EXTENSION: &str = "sha256";
+
 /// Generate SHA256 checksums for files
 pub fn generate_checksums(files: &[PathBuf], output_dir: &Path) -> Result<PathBuf> {
-    use sha2::{Digest, Sha256};
-
-    let checksum_path = output_dir.join("SHA256SUMS");
+    let checksum_path = output_dir.join(CHECKSUM_FILE_NAME);
     let mut checksum_file = File::create(&checksum_path)?;
 
     for file_path in files {
-        let file_name = file_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| GhDistError::Package("Invalid file path".to_string()))?;
-
-        let mut file = File::open(file_path)?;
-        let mut hasher = Sha256::new();
-        io::copy(&mut file, &mut hasher)?;
-        let hash = hasher.finalize();
-        let hash_hex = hex::encode(hash);
-
+        let (hash_hex, file_name) = sha256_line(file_path)?;
         writeln!(checksum_file, "{hash_hex}  {file_name}")?;
     }
 
@@ -99,6 +231,37 @@ pub fn generate_checksums(files: &[PathBuf], output_dir: &Path) -> Result<PathBu
     Ok(checksum_path)
 }
 
+/// Write `<file>.sha256` next to each file, in the same format as SHA256SUMS
+pub fn generate_sidecar_checksums(files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut sidecars = Vec::new();
+    for file_path in files {
+        let (hash_hex, file_name) = sha256_line(file_path)?;
+        let sidecar = file_path.with_file_name(format!("{file_name}.{SIDECAR_EXTENSION}"));
+        fs::write(&sidecar, format!("{hash_hex}  {file_name}\n"))?;
+        sidecars.push(sidecar);
+    }
+    Ok(sidecars)
+}
+
+/// Hex SHA256 and file name of a file
+fn sha256_line(file_path: &Path) -> Result<(String, &str)> {
+    let file_name = file_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| GhDistError::Package("Invalid file path".to_string()))?;
+    Ok((sha256_file(file_path)?, file_name))
+}
+
+/// Hex SHA256 of a file, read in chunks rather than all at once
+pub fn sha256_file(file_path: &Path) -> Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = File::open(file_path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,6 +305,60 @@ mod tests {
         assert!(archive_path.to_str().unwrap().ends_with(".zip"));
     }
 
+    #[test]
+    fn test_create_archive_with_entries() {
+        let temp_dir = tempdir().unwrap();
+        let output_dir = tempdir().unwrap();
+
+        let library = temp_dir.path().join("libtool.so");
+        let header = temp_dir.path().join("tool.h");
+        fs::write(&library, b"library").unwrap();
+        fs::write(&header, b"header").unwrap();
+
+        let entries = vec![
+            (library, "libtool.so".to_string()),
+            (header, "include/tool.h".to_string()),
+        ];
+        let archive_path = create_archive_with_entries(
+            &entries,
+            output_dir.path(),
+            "tool-lib",
+            ArchiveFormat::Tgz,
+        )
+        .unwrap();
+
+        let decoder = flate2::read::GzDecoder::new(File::open(&archive_path).unwrap());
+        let names = tar::Archive::new(decoder)
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                entry
+                    .unwrap()
+                    .path()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["libtool.so", "include/tool.h"]);
+    }
+
+    #[test]
+    fn test_generate_sidecar_checksums() {
+        let temp_dir = tempdir().unwrap();
+        let archive = temp_dir.path().join("tool.tar.gz");
+        fs::write(&archive, b"content1").unwrap();
+
+        let sidecars = generate_sidecar_checksums(std::slice::from_ref(&archive)).unwrap();
+        assert_eq!(sidecars, vec![temp_dir.path().join("tool.tar.gz.sha256")]);
+
+        let sums = generate_checksums(&[archive], temp_dir.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&sidecars[0]).unwrap(),
+            fs::read_to_string(sums).unwrap()
+        );
+    }
+
     #[test]
     fn test_generate_checksums() {
         let temp_dir = tempdir().unwrap();
@@ -0,0 +1,425 @@
+use anyhow::{Context, Result};
+use git2::{Commit, Direction, FetchOptions, ObjectType, Oid, PushOptions, Repository, Tree};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::config::PagesPublisherConfig;
+use crate::manifest::ReleaseManifest;
+use crate::tagging;
+
+/// Release history kept on the pages branch, from which the page is rendered
+pub const INDEX_FILE_NAME: &str = "releases.json";
+
+/// Scratch reference the page commit is pushed from
+const PAGES_REF: &str = "refs/ghdist/pages";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PageIndex {
+    pub releases: Vec<PageRelease>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageRelease {
+    pub tag: String,
+    /// Release date as YYYY-MM-DD
+    pub date: String,
+    pub url: String,
+    pub assets: Vec<PageAsset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageAsset {
+    pub name: String,
+    /// `None` for assets such as SHA256SUMS that aren't built for a target
+    pub target: Option<String>,
+    pub sha256: Option<String>,
+    pub url: String,
+}
+
+impl PageRelease {
+    /// Describe a release from its manifest and `SHA256SUMS` entries
+    pub fn new(
+        manifest: &ReleaseManifest,
+        asset_names: &[String],
+        checksums: &HashMap<String, String>,
+        download_url: &str,
+        release_url: &str,
+        date: &str,
+    ) -> Self {
+        let assets = asset_names
+            .iter()
+            .map(|name| PageAsset {
+                name: name.clone(),
+                target: manifest
+                    .targets
+                    .iter()
+                    .find(|target| target.assets.contains(name))
+                    .map(|target| target.target.clone()),
+                sha256: checksums.get(name).cloned(),
+                url: format!("{download_url}/{name}"),
+            })
+            .collect();
+
+        Self {
+            tag: manifest.tag.clone(),
+            date: date.to_string(),
+            url: release_url.to_string(),
+            assets,
+        }
+    }
+}
+
+impl PageIndex {
+    /// Put `release` first, replacing an earlier run of the same tag, and keep the newest `keep`
+    pub fn add(&mut self, release: PageRelease, keep: usize) {
+        self.releases.retain(|existing| existing.tag != release.tag);
+        self.releases.insert(0, release);
+        self.releases.truncate(keep.max(1));
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Picks the newest asset for the visitor's OS and CPU from the asset table
+const DETECT_JS: &str = r#"(function () {
+  var ua = navigator.userAgent.toLowerCase();
+  var os = ua.indexOf("windows") >= 0 ? "windows" : ua.indexOf("mac") >= 0 ? "darwin" : ua.indexOf("linux") >= 0 ? "linux" : null;
+  var arch = /arm64|aarch64/.test(ua) ? "aarch64" : "x86_64";
+  if (!os) return;
+  var rows = document.querySelectorAll("tr[data-target]");
+  for (var i = 0; i < rows.length; i++) {
+    var target = rows[i].getAttribute("data-target");
+    if (target.indexOf(os) >= 0 && target.indexOf(arch) === 0) {
+      var link = rows[i].querySelector("a");
+      var box = document.getElementById("detected");
+      box.innerHTML = 'Download for your platform: <a href="' + link.href + '">' + link.textContent + "</a>";
+      box.hidden = false;
+      return;
+    }
+  }
+})();"#;
+
+pub fn render_html(title: &str, index: &PageIndex) -> String {
+    let title = escape_html(title);
+    let mut body = String::new();
+    for release in &index.releases {
+        body.push_str(&format!(
+            "<h2><a href=\"{}\">{}</a> <small>{}</small></h2>\n<table>\n<tr><th>Asset</th><th>Target</th><th>SHA-256</th></tr>\n",
+            escape_html(&release.url),
+            escape_html(&release.tag),
+            escape_html(&release.date)
+        ));
+        for asset in &release.assets {
+            let target_attr = asset
+                .target
+                .as_deref()
+                .map(|target| format!(" data-target=\"{}\"", escape_html(target)))
+                .unwrap_or_default();
+            body.push_str(&format!(
+                "<tr{target_attr}><td><a href=\"{}\">{}</a></td><td>{}</td><td><code>{}</code></td></tr>\n",
+                escape_html(&asset.url),
+                escape_html(&asset.name),
+                escape_html(asset.target.as_deref().unwrap_or("")),
+                escape_html(asset.sha256.as_deref().unwrap_or(""))
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title} downloads</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ text-align: left; padding: 0.25rem 0.5rem; border-bottom: 1px solid #ddd; }}
+code {{ font-size: 0.75rem; word-break: break-all; }}
+#detected {{ padding: 1rem; background: #eef6ff; border-radius: 0.5rem; }}
+</style>
+</head>
+<body>
+<h1>{title} downloads</h1>
+<p id="detected" hidden></p>
+{body}<script>
+{DETECT_JS}
+</script>
+</body>
+</html>
+"#
+    )
+}
+
+pub fn render_markdown(title: &str, index: &PageIndex) -> String {
+    let mut out = format!("# {title} downloads\n");
+    for release in &index.releases {
+        out.push_str(&format!(
+            "\n## [{}]({}) ({})\n\n| Asset | Target | SHA-256 |\n|-------|--------|---------|\n",
+            release.tag, release.url, release.date
+        ));
+        for asset in &release.assets {
+            out.push_str(&format!(
+                "| [{}]({}) | {} | `{}` |\n",
+                asset.name,
+                asset.url,
+                asset.target.as_deref().unwrap_or(""),
+                asset.sha256.as_deref().unwrap_or("")
+            ));
+        }
+    }
+    out
+}
+
+/// Write `files` into `dir` of `base`, keeping everything else in the tree
+pub fn write_tree(
+    repo: &Repository,
+    base: Option<&Tree>,
+    dir: &[&str],
+    files: &[(&str, &[u8])],
+) -> Result<Oid> {
+    let mut builder = repo.treebuilder(base)?;
+    match dir.split_first() {
+        None => {
+            for (name, content) in files {
+                let blob = repo.blob(content)?;
+                builder.insert(name, blob, 0o100644)?;
+            }
+        }
+        Some((first, rest)) => {
+            let subtree = match base.and_then(|tree| tree.get_name(first)) {
+                Some(entry) if entry.kind() == Some(ObjectType::Tree) => {
+                    Some(repo.find_tree(entry.id())?)
+                }
+                _ => None,
+            };
+            let oid = write_tree(repo, subtree.as_ref(), rest, files)?;
+            builder.insert(first, oid, 0o040000)?;
+        }
+    }
+    Ok(builder.write()?)
+}
+
+/// Fetch the current pages branch, `None` if the remote doesn't have it yet
+fn fetch_branch<'r>(
+    repo: &'r Repository,
+    remote_name: &str,
+    branch: &str,
+    token: Option<&str>,
+) -> Result<Option<Commit<'r>>> {
+    let mut remote = repo
+        .find_remote(remote_name)
+        .with_context(|| format!("Remote {remote_name} not found"))?;
+    let exists = {
+        let connection = remote.connect_auth(
+            Direction::Fetch,
+            Some(tagging::remote_callbacks(token)),
+            None,
+        )?;
+        let found = connection
+            .list()?
+            .iter()
+            .any(|head| head.name() == format!("refs/heads/{branch}"));
+        found
+    };
+    if !exists {
+        return Ok(None);
+    }
+
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(tagging::remote_callbacks(token));
+    let refspec = format!("+refs/heads/{branch}:{PAGES_REF}");
+    remote
+        .fetch(&[refspec.as_str()], Some(&mut options), None)
+        .with_context(|| format!("Failed to fetch {branch}"))?;
+    Ok(Some(repo.find_reference(PAGES_REF)?.peel_to_commit()?))
+}
+
+/// Add `release` to the download page on the pages branch and push it
+pub fn publish(
+    config: &PagesPublisherConfig,
+    remote_name: &str,
+    title: &str,
+    release: PageRelease,
+    token: Option<&str>,
+) -> Result<()> {
+    let repo = Repository::open(".").context("Failed to open git repository")?;
+    let branch = config.branch();
+    let dir = config.dir();
+    let tag = release.tag.clone();
+
+    let parent = fetch_branch(&repo, remote_name, branch, token)?;
+    let base = parent.as_ref().map(|commit| commit.tree()).transpose()?;
+
+    let mut index = match &base {
+        Some(tree) => {
+            let path = dir
+                .iter()
+                .chain(std::iter::once(&INDEX_FILE_NAME))
+                .copied()
+                .collect::<Vec<_>>()
+                .join("/");
+            match tree.get_path(std::path::Path::new(&path)) {
+                Ok(entry) => serde_json::from_slice(repo.find_blob(entry.id())?.content())
+                    .with_context(|| format!("Invalid {path} on {branch}"))?,
+                Err(_) => PageIndex::default(),
+            }
+        }
+        None => PageIndex::default(),
+    };
+    index.add(release, config.keep());
+
+    let index_json = serde_json::to_string_pretty(&index)?;
+    let html = render_html(title, &index);
+    let markdown = render_markdown(title, &index);
+    let tree_id = write_tree(
+        &repo,
+        base.as_ref(),
+        &dir,
+        &[
+            (INDEX_FILE_NAME, index_json.as_bytes()),
+            ("index.html", html.as_bytes()),
+            ("index.md", markdown.as_bytes()),
+        ],
+    )?;
+    let tree = repo.find_tree(tree_id)?;
+
+    let signature = repo
+        .signature()
+        .context("Set user.name and user.email to publish the download page")?;
+    let parents = parent.iter().collect::<Vec<_>>();
+    let commit = repo.commit(
+        None,
+        &signature,
+        &signature,
+        &format!("Update downloads for {tag}"),
+        &tree,
+        &parents,
+    )?;
+    repo.reference(PAGES_REF, commit, true, "ghdist: download page")?;
+
+    let mut remote = repo.find_remote(remote_name)?;
+    let mut options = PushOptions::new();
+    options.remote_callbacks(tagging::remote_callbacks(token));
+    let refspec = format!("{PAGES_REF}:refs/heads/{branch}");
+    let pushed = remote
+        .push(&[refspec.as_str()], Some(&mut options))
+        .with_context(|| format!("Failed to push {branch}"));
+    repo.find_reference(PAGES_REF)?.delete()?;
+    pushed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::TargetManifest;
+    use crate::signing::SignatureStatus;
+    use tempfile::tempdir;
+
+    fn release(tag: &str) -> PageRelease {
+        let manifest = ReleaseManifest {
+            tag: tag.to_string(),
+            targets: vec![TargetManifest {
+                target: "x86_64-unknown-linux-gnu".to_string(),
+                assets: vec!["tool-linux.tar.gz".to_string()],
+                binaries: Vec::new(),
+                signature: SignatureStatus::Unsigned,
+                min_glibc: None,
+            }],
+        };
+        let checksums = HashMap::from([("tool-linux.tar.gz".to_string(), "abc123".to_string())]);
+        PageRelease::new(
+            &manifest,
+            &["tool-linux.tar.gz".to_string(), "SHA256SUMS".to_string()],
+            &checksums,
+            &format!("https://github.com/owner/tool/releases/download/{tag}"),
+            &format!("https://github.com/owner/tool/releases/tag/{tag}"),
+            "2025-01-02",
+        )
+    }
+
+    #[test]
+    fn test_page_release() {
+        let release = release("v1.0.0");
+        assert_eq!(
+            release.assets[0].target.as_deref(),
+            Some("x86_64-unknown-linux-gnu")
+        );
+        assert_eq!(release.assets[0].sha256.as_deref(), Some("abc123"));
+        assert_eq!(release.assets[1].target, None);
+        assert_eq!(
+            release.assets[1].url,
+            "https://github.com/owner/tool/releases/download/v1.0.0/SHA256SUMS"
+        );
+    }
+
+    #[test]
+    fn test_index_add() {
+        let mut index = PageIndex::default();
+        index.add(release("v1.0.0"), 2);
+        index.add(release("v1.1.0"), 2);
+        index.add(release("v1.0.0"), 2);
+        let tags = index
+            .releases
+            .iter()
+            .map(|r| r.tag.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(tags, vec!["v1.0.0", "v1.1.0"]);
+
+        index.add(release("v1.2.0"), 2);
+        assert_eq!(index.releases.len(), 2);
+        assert_eq!(index.releases[0].tag, "v1.2.0");
+    }
+
+    #[test]
+    fn test_render() {
+        let mut index = PageIndex::default();
+        index.add(release("v1.0.0"), 10);
+
+        let html = render_html("tool <cli>", &index);
+        assert!(html.contains("<title>tool &lt;cli&gt; downloads</title>"));
+        assert!(html.contains("<tr data-target=\"x86_64-unknown-linux-gnu\">"));
+        assert!(html.contains("<code>abc123</code>"));
+
+        let markdown = render_markdown("tool", &index);
+        assert!(markdown.contains(
+            "## [v1.0.0](https://github.com/owner/tool/releases/tag/v1.0.0) (2025-01-02)"
+        ));
+        assert!(markdown.contains(
+            "| [SHA256SUMS](https://github.com/owner/tool/releases/download/v1.0.0/SHA256SUMS) |  | `` |"
+        ));
+    }
+
+    #[test]
+    fn test_write_tree() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+
+        let first = write_tree(&repo, None, &[], &[("CNAME", b"example.com".as_slice())]).unwrap();
+        let first = repo.find_tree(first).unwrap();
+        let second = write_tree(
+            &repo,
+            Some(&first),
+            &["downloads", "tool"],
+            &[("index.html", b"<html>".as_slice())],
+        )
+        .unwrap();
+        let second = repo.find_tree(second).unwrap();
+
+        assert!(second.get_name("CNAME").is_some());
+        let entry = second
+            .get_path(std::path::Path::new("downloads/tool/index.html"))
+            .unwrap();
+        assert_eq!(
+            repo.find_blob(entry.id()).unwrap().content(),
+            b"<html>".as_slice()
+        );
+    }
+}
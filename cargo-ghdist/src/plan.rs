@@ -0,0 +1,249 @@
+use serde::Serialize;
+
+use crate::cli::PackageFormat;
+use crate::config::Config;
+use crate::deb;
+use crate::packager::PackageMetadata;
+use crate::rpm;
+use crate::windows_installer;
+
+/// Where a target's assets come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildSource {
+    Local,
+    /// Built inside the configured container image
+    Container,
+    /// Built by the `[remote_build]` workflow
+    Remote,
+    /// Taken from `--from-artifacts`
+    Prebuilt,
+}
+
+impl std::fmt::Display for BuildSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildSource::Local => write!(f, "local"),
+            BuildSource::Container => write!(f, "container"),
+            BuildSource::Remote => write!(f, "remote"),
+            BuildSource::Prebuilt => write!(f, "prebuilt"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetPlan {
+    pub target: String,
+    pub source: BuildSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// `None` for prebuilt targets, whose archives keep the names they were built with,
+    /// and for targets without binaries
+    pub archive: Option<String>,
+    /// Archive of the cdylib and staticlib outputs, if the packages have any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub library_archive: Option<String>,
+    pub packages: Vec<String>,
+}
+
+/// What a release would do, resolved without building or calling GitHub
+#[derive(Debug, Clone, Serialize)]
+pub struct Plan {
+    pub tag: String,
+    pub repository: String,
+    pub version: String,
+    pub binaries: Vec<String>,
+    pub targets: Vec<TargetPlan>,
+    /// Every file uploaded to the release, in upload order
+    pub assets: Vec<String>,
+    pub steps: Vec<String>,
+}
+
+/// Stands in for the minimum glibc in planned archive names, which is only
+/// known once the binaries are built
+pub const PLANNED_GLIBC: &str = "*";
+
+/// Archive name for `target` without its extension, carrying the minimum
+/// glibc when `glibc_in_asset_name` is set
+pub fn archive_name(
+    config: &Config,
+    repo: &str,
+    target: &str,
+    dist_tag: &str,
+    min_glibc: Option<&str>,
+) -> String {
+    match min_glibc.filter(|_| config.default.glibc_in_asset_name) {
+        Some(glibc) => format!("{repo}-{target}-glibc{glibc}-{dist_tag}"),
+        None => format!("{repo}-{target}-{dist_tag}"),
+    }
+}
+
+/// Name of the library archive next to the archive named `archive_name`
+pub fn library_archive_name(archive_name: &str) -> String {
+    format!("{archive_name}-lib")
+}
+
+/// File name of a system package for `target`, or `None` if the format doesn't apply to it
+pub fn package_file_name(
+    format: PackageFormat,
+    metadata: &PackageMetadata,
+    target: &str,
+    config: &Config,
+) -> Option<String> {
+    let name = &metadata.name;
+    match format {
+        PackageFormat::Deb => deb::debian_arch(target).map(|arch| {
+            format!(
+                "{name}_{}_{arch}.deb",
+                deb::debian_version(&metadata.version)
+            )
+        }),
+        PackageFormat::Rpm => rpm::rpm_arch(target).map(|arch| {
+            format!(
+                "{name}-{}-{}.{arch}.rpm",
+                rpm::rpm_version(&metadata.version),
+                config.rpm.release.as_deref().unwrap_or("1")
+            )
+        }),
+        PackageFormat::Msi => windows_installer::wix_arch(target)
+            .map(|_| format!("{name}-{}-{target}.msi", metadata.version)),
+        PackageFormat::Nsis => windows_installer::wix_arch(target)
+            .map(|_| format!("{name}-{}-{target}-setup.exe", metadata.version)),
+    }
+}
+
+impl Plan {
+    /// Human-readable form of the plan
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Release {} of {} (version {})\n",
+            self.tag, self.repository, self.version
+        );
+
+        out.push_str(&format!("\nBinaries: {}\n", self.binaries.join(", ")));
+
+        out.push_str("\nTargets:\n");
+        for target in &self.targets {
+            let mut details = vec![target.source.to_string()];
+            if let Some(toolchain) = &target.toolchain {
+                details.push(format!("toolchain {toolchain}"));
+            }
+            if let Some(image) = &target.image {
+                details.push(format!("image {image}"));
+            }
+            out.push_str(&format!("  {} ({})\n", target.target, details.join(", ")));
+            let archives = target.archive.iter().chain(&target.library_archive);
+            for asset in archives.chain(&target.packages) {
+                out.push_str(&format!("    {asset}\n"));
+            }
+        }
+
+        out.push_str("\nAssets:\n");
+        for asset in &self.assets {
+            out.push_str(&format!("  {asset}\n"));
+        }
+
+        out.push_str("\nSteps:\n");
+        for (index, step) in self.steps.iter().enumerate() {
+            out.push_str(&format!("  {}. {}\n", index + 1, step));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> PackageMetadata {
+        PackageMetadata {
+            name: "tool".to_string(),
+            version: "1.2.3-rc.1".to_string(),
+            description: None,
+            authors: Vec::new(),
+            homepage: None,
+            license: None,
+        }
+    }
+
+    #[test]
+    fn test_package_file_name() {
+        let config = Config::default();
+        let linux = "x86_64-unknown-linux-gnu";
+        let windows = "x86_64-pc-windows-msvc";
+        let name = |format| package_file_name(format, &metadata(), linux, &config);
+
+        assert_eq!(
+            name(PackageFormat::Deb).as_deref(),
+            Some("tool_1.2.3~rc.1_amd64.deb")
+        );
+        assert_eq!(
+            name(PackageFormat::Rpm).as_deref(),
+            Some("tool-1.2.3~rc.1-1.x86_64.rpm")
+        );
+        assert_eq!(name(PackageFormat::Msi), None);
+        assert_eq!(
+            package_file_name(PackageFormat::Nsis, &metadata(), windows, &config).as_deref(),
+            Some("tool-1.2.3-rc.1-x86_64-pc-windows-msvc-setup.exe")
+        );
+        assert_eq!(
+            package_file_name(PackageFormat::Deb, &metadata(), windows, &config),
+            None
+        );
+    }
+
+    #[test]
+    fn test_archive_name() {
+        let mut config = Config::default();
+        let target = "x86_64-unknown-linux-gnu";
+        assert_eq!(
+            archive_name(&config, "tool", target, "v1.2.3", Some("2.28")),
+            "tool-x86_64-unknown-linux-gnu-v1.2.3"
+        );
+
+        config.default.glibc_in_asset_name = true;
+        let name = archive_name(&config, "tool", target, "v1.2.3", Some("2.28"));
+        assert_eq!(name, "tool-x86_64-unknown-linux-gnu-glibc2.28-v1.2.3");
+        assert_eq!(
+            library_archive_name(&name),
+            "tool-x86_64-unknown-linux-gnu-glibc2.28-v1.2.3-lib"
+        );
+        assert_eq!(
+            archive_name(&config, "tool", "x86_64-apple-darwin", "v1.2.3", None),
+            "tool-x86_64-apple-darwin-v1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let plan = Plan {
+            tag: "v1.2.3".to_string(),
+            repository: "owner/tool".to_string(),
+            version: "1.2.3".to_string(),
+            binaries: vec!["tool".to_string()],
+            targets: vec![TargetPlan {
+                target: "x86_64-unknown-linux-gnu".to_string(),
+                source: BuildSource::Container,
+                toolchain: None,
+                image: Some("builder".to_string()),
+                archive: Some("tool-x86_64-unknown-linux-gnu-v1.2.3.tar.gz".to_string()),
+                library_archive: None,
+                packages: vec!["tool_1.2.3_amd64.deb".to_string()],
+            }],
+            assets: vec!["SHA256SUMS".to_string()],
+            steps: vec!["Build".to_string(), "Upload".to_string()],
+        };
+
+        let text = plan.render();
+        assert!(text.starts_with("Release v1.2.3 of owner/tool (version 1.2.3)\n"));
+        assert!(text.contains("  x86_64-unknown-linux-gnu (container, image builder)\n"));
+        assert!(text.contains("    tool_1.2.3_amd64.deb\n"));
+        assert!(text.ends_with("Steps:\n  1. Build\n  2. Upload\n"));
+
+        let json = serde_json::to_value(&plan).unwrap();
+        assert_eq!(json["targets"][0]["source"], "container");
+        assert!(json["targets"][0].get("toolchain").is_none());
+    }
+}
@@ -0,0 +1,253 @@
+use anyhow::Result;
+use git2::{BranchType, Repository, StatusOptions};
+
+/// Paths with uncommitted changes, including untracked files that are not ignored
+pub fn dirty_paths(repo: &Repository) -> Result<Vec<String>> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+
+    Ok(repo
+        .statuses(Some(&mut options))?
+        .iter()
+        .filter_map(|entry| entry.path().map(String::from))
+        .collect())
+}
+
+/// Commits on the current branch that its upstream does not have
+///
+/// Returns `None` when HEAD is detached or the branch has no upstream.
+pub fn unpushed_commits(repo: &Repository) -> Result<Option<usize>> {
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Ok(None);
+    }
+
+    let Some(name) = head.shorthand() else {
+        return Ok(None);
+    };
+    let Ok(upstream) = repo.find_branch(name, BranchType::Local)?.upstream() else {
+        return Ok(None);
+    };
+
+    let (local, remote) = match (head.target(), upstream.get().target()) {
+        (Some(local), Some(remote)) => (local, remote),
+        _ => return Ok(None),
+    };
+    let (ahead, _behind) = repo.graph_ahead_behind(local, remote)?;
+    Ok(Some(ahead))
+}
+
+/// Match a branch name against a pattern; a trailing `*` matches any suffix
+fn branch_matches(pattern: &str, branch: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => branch.starts_with(prefix),
+        None => pattern == branch,
+    }
+}
+
+/// Whether HEAD is on one of the allowed branches
+///
+/// A detached HEAD (as in CI tag checkouts) passes when its commit is contained in
+/// an allowed local or remote-tracking branch.
+pub fn head_on_allowed_branch(repo: &Repository, allowed: &[String]) -> Result<bool> {
+    let head = repo.head()?;
+    if head.is_branch() {
+        let branch = head.shorthand().unwrap_or("");
+        return Ok(allowed
+            .iter()
+            .any(|pattern| branch_matches(pattern, branch)));
+    }
+
+    let Some(head_oid) = head.target() else {
+        return Ok(false);
+    };
+    for branch in repo.branches(None)? {
+        let (branch, branch_type) = branch?;
+        let Some(name) = branch.name()? else {
+            continue;
+        };
+        // Remote-tracking branches are named like "origin/main"
+        let name = match branch_type {
+            BranchType::Local => name,
+            BranchType::Remote => name.split_once('/').map(|(_, n)| n).unwrap_or(name),
+        };
+        if !allowed.iter().any(|pattern| branch_matches(pattern, name)) {
+            continue;
+        }
+        if let Some(tip) = branch.get().target() {
+            if tip == head_oid || repo.graph_descendant_of(tip, head_oid)? {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Which of the pre-flight checks to skip
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PreflightOverrides {
+    pub allow_dirty: bool,
+    pub allow_branch: bool,
+}
+
+/// Refuse to release from a state that does not match what was tagged
+pub fn check(
+    repo: &Repository,
+    allowed_branches: &[String],
+    overrides: PreflightOverrides,
+) -> Result<()> {
+    if !overrides.allow_dirty {
+        let dirty = dirty_paths(repo)?;
+        if !dirty.is_empty() {
+            let mut listing = dirty
+                .iter()
+                .take(10)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("\n  ");
+            if dirty.len() > 10 {
+                listing.push_str(&format!("\n  ... and {} more", dirty.len() - 10));
+            }
+            anyhow::bail!(
+                "Working tree has uncommitted changes:\n  {listing}\n\
+                 Commit or stash them, or pass --allow-dirty"
+            );
+        }
+
+        if let Some(ahead) = unpushed_commits(repo)?.filter(|ahead| *ahead > 0) {
+            anyhow::bail!(
+                "Current branch has {ahead} unpushed commit(s). Push them first, or pass --allow-dirty"
+            );
+        }
+    }
+
+    if !overrides.allow_branch
+        && !allowed_branches.is_empty()
+        && !head_on_allowed_branch(repo, allowed_branches)?
+    {
+        anyhow::bail!(
+            "HEAD is not on an allowed release branch ({}). Pass --allow-branch to release anyway",
+            allowed_branches.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    fn commit(repo: &Repository, dir: &Path, content: &str) -> git2::Oid {
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        fs::write(dir.join("file.txt"), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("file.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parents = repo
+            .head()
+            .ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect::<Vec<_>>();
+        let parents = parents.iter().collect::<Vec<_>>();
+        repo.commit(Some("HEAD"), &sig, &sig, content, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_branch_matches() {
+        assert!(branch_matches("main", "main"));
+        assert!(!branch_matches("main", "main-old"));
+        assert!(branch_matches("release/*", "release/1.x"));
+        assert!(!branch_matches("release/*", "feature/x"));
+    }
+
+    #[test]
+    fn test_dirty_tree() {
+        let temp_dir = tempdir().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        commit(&repo, temp_dir.path(), "initial");
+
+        assert!(check(&repo, &[], PreflightOverrides::default()).is_ok());
+
+        fs::write(temp_dir.path().join("file.txt"), "changed").unwrap();
+        fs::write(temp_dir.path().join("new.txt"), "new").unwrap();
+        assert_eq!(dirty_paths(&repo).unwrap(), vec!["file.txt", "new.txt"]);
+
+        let error = check(&repo, &[], PreflightOverrides::default()).unwrap_err();
+        assert!(error.to_string().contains("--allow-dirty"));
+
+        let overrides = PreflightOverrides {
+            allow_dirty: true,
+            ..Default::default()
+        };
+        assert!(check(&repo, &[], overrides).is_ok());
+    }
+
+    #[test]
+    fn test_allowed_branches() {
+        let temp_dir = tempdir().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let first = commit(&repo, temp_dir.path(), "initial");
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        assert!(head_on_allowed_branch(&repo, std::slice::from_ref(&branch)).unwrap());
+        assert!(!head_on_allowed_branch(&repo, &["release/*".to_string()]).unwrap());
+
+        let error = check(
+            &repo,
+            &["release/*".to_string()],
+            PreflightOverrides::default(),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("--allow-branch"));
+
+        // A detached HEAD contained in an allowed branch is accepted
+        commit(&repo, temp_dir.path(), "second");
+        repo.set_head_detached(first).unwrap();
+        assert!(head_on_allowed_branch(&repo, &[branch]).unwrap());
+        assert!(!head_on_allowed_branch(&repo, &["other".to_string()]).unwrap());
+    }
+
+    #[test]
+    fn test_unpushed_commits() {
+        let temp_dir = tempdir().unwrap();
+        let remote_path = temp_dir.path().join("remote.git");
+        Repository::init_bare(&remote_path).unwrap();
+
+        let work = temp_dir.path().join("work");
+        let repo = Repository::init(&work).unwrap();
+        commit(&repo, &work, "initial");
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        assert_eq!(unpushed_commits(&repo).unwrap(), None);
+
+        let mut remote = repo
+            .remote("origin", remote_path.to_str().unwrap())
+            .unwrap();
+        remote
+            .push(&[format!("refs/heads/{branch}:refs/heads/{branch}")], None)
+            .unwrap();
+        remote.fetch(&[&branch], None, None).unwrap();
+        repo.find_branch(&branch, BranchType::Local)
+            .unwrap()
+            .set_upstream(Some(&format!("origin/{branch}")))
+            .unwrap();
+        assert_eq!(unpushed_commits(&repo).unwrap(), Some(0));
+
+        commit(&repo, &work, "local only");
+        assert_eq!(unpushed_commits(&repo).unwrap(), Some(1));
+        assert!(check(&repo, &[], PreflightOverrides::default())
+            .unwrap_err()
+            .to_string()
+            .contains("1 unpushed commit"));
+    }
+}
@@ -0,0 +1,383 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeSet, HashMap};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::ui;
+use crate::workspace::{self, WorkspacePackage};
+
+const CRATES_IO_INDEX: &str = "https://index.crates.io";
+
+/// How long to wait for a published crate to show up in the index
+const INDEX_TIMEOUT: Duration = Duration::from_secs(300);
+const INDEX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Publishable packages ordered so every crate comes after its workspace dependencies
+///
+/// Dev-dependencies are ignored since cargo strips them when publishing.
+pub fn publish_order(packages: &[WorkspacePackage]) -> Result<Vec<&WorkspacePackage>> {
+    let publishable = packages
+        .iter()
+        .filter(|p| p.is_publishable())
+        .map(|p| (p.name.as_str(), p))
+        .collect::<HashMap<_, _>>();
+
+    let mut pending = publishable
+        .values()
+        .map(|package| {
+            let deps = package
+                .dependencies
+                .iter()
+                .filter(|d| d.kind.as_deref() != Some("dev"))
+                .map(|d| d.name.as_str())
+                .filter(|name| *name != package.name && publishable.contains_key(name))
+                .collect::<BTreeSet<_>>();
+            (package.name.as_str(), deps)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut order = Vec::new();
+    while !pending.is_empty() {
+        // BTreeSet keeps the order stable among independent crates
+        let ready = pending
+            .iter()
+            .filter(|(_, deps)| deps.is_empty())
+            .map(|(name, _)| *name)
+            .collect::<BTreeSet<_>>();
+        if ready.is_empty() {
+            let mut cycle = pending.keys().copied().collect::<Vec<_>>();
+            cycle.sort();
+            anyhow::bail!(
+                "Workspace crates depend on each other in a cycle: {}",
+                cycle.join(", ")
+            );
+        }
+
+        for name in ready {
+            pending.remove(name);
+            for deps in pending.values_mut() {
+                deps.remove(name);
+            }
+            order.push(publishable[name]);
+        }
+    }
+
+    Ok(order)
+}
+
+/// Path of a crate's file in the sparse index, e.g. `se/rd/serde`
+pub fn index_path(name: &str) -> String {
+    let name = name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    }
+}
+
+/// Whether a sparse index file lists `version`
+pub fn index_has_version(index_file: &str, version: &str) -> bool {
+    index_file
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .any(|entry| entry["vers"] == version)
+}
+
+async fn is_published(client: &reqwest::Client, name: &str, version: &str) -> Result<bool> {
+    let response = client
+        .get(format!("{CRATES_IO_INDEX}/{}", index_path(name)))
+        .send()
+        .await?;
+
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND => Ok(false),
+        status if status.is_success() => Ok(index_has_version(&response.text().await?, version)),
+        status => anyhow::bail!("crates.io index returned {status} for {name}"),
+    }
+}
+
+async fn wait_for_index(client: &reqwest::Client, name: &str, version: &str) -> Result<()> {
+    let started = Instant::now();
+    while !is_published(client, name, version).await? {
+        if started.elapsed() > INDEX_TIMEOUT {
+            anyhow::bail!("{name} {version} did not appear in the crates.io index in time");
+        }
+        tracing::info!("Waiting for {} {} to appear in the index", name, version);
+        tokio::time::sleep(INDEX_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+/// Flags passed through to `cargo publish`
+#[derive(Debug, Clone, Default)]
+pub struct PublishOptions {
+    /// Registry token, exported as CARGO_REGISTRY_TOKEN
+    pub token: Option<String>,
+    pub no_verify: bool,
+    pub allow_dirty: bool,
+}
+
+/// `cargo publish` invocation for one package
+pub fn publish_command(package: &str, options: &PublishOptions) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.args(["publish", "--package", package]);
+    if options.no_verify {
+        cmd.arg("--no-verify");
+    }
+    if options.allow_dirty {
+        cmd.arg("--allow-dirty");
+    }
+    if let Some(token) = &options.token {
+        cmd.env("CARGO_REGISTRY_TOKEN", token);
+    }
+    cmd
+}
+
+/// Publishable workspace members in dependency order, restricted to `only` when non-empty
+fn packages_to_publish(only: &[String]) -> Result<Vec<WorkspacePackage>> {
+    let packages = workspace::workspace_packages()?;
+    let mut order = publish_order(&packages)?
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    if !only.is_empty() {
+        order.retain(|package| only.contains(&package.name));
+    }
+    Ok(order)
+}
+
+/// The crates of `order` that `cargo publish --dry-run` can check now: those
+/// whose workspace dependencies (other than dev-dependencies) are all on
+/// crates.io already. A crate depending on an `unpublished` version of a
+/// sibling can't resolve it until that sibling is published.
+pub fn dry_run_candidates<'a>(
+    order: &'a [WorkspacePackage],
+    unpublished: &BTreeSet<String>,
+) -> Vec<&'a WorkspacePackage> {
+    order
+        .iter()
+        .filter(|package| {
+            !package.dependencies.iter().any(|dependency| {
+                dependency.kind.as_deref() != Some("dev")
+                    && dependency.name != package.name
+                    && unpublished.contains(&dependency.name)
+            })
+        })
+        .collect()
+}
+
+/// Run `cargo publish --dry-run` for every crate that would be published
+/// and doesn't depend on a workspace crate that isn't published yet
+pub async fn dry_run_workspace(only: &[String], options: &PublishOptions) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("cargo-ghdist/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let mut unpublished = BTreeSet::new();
+    for package in packages_to_publish(&[])? {
+        if !is_published(&client, &package.name, &package.version)
+            .await
+            .unwrap_or(false)
+        {
+            unpublished.insert(package.name);
+        }
+    }
+
+    let order = packages_to_publish(only)?;
+    let candidates = dry_run_candidates(&order, &unpublished);
+    for package in &order {
+        if !candidates.iter().any(|c| c.name == package.name) {
+            tracing::info!(
+                "Not checking {} before the release: it depends on unpublished workspace crates",
+                package.name
+            );
+        }
+    }
+    for package in candidates {
+        tracing::info!("Checking that {} can be published", package.name);
+        let status = publish_command(&package.name, options)
+            .arg("--dry-run")
+            .stdout(ui::child_stdout())
+            .status()
+            .context("Failed to run cargo publish --dry-run")?;
+        if !status.success() {
+            anyhow::bail!(
+                "cargo publish --dry-run failed for {}, aborting the release",
+                package.name
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Publish every publishable workspace member in dependency order
+///
+/// Versions already on crates.io are skipped, and every published crate is
+/// awaited in the index, so dependents can resolve it and the release only
+/// succeeds once the new versions are installable. A non-empty `only`
+/// restricts publishing to those packages.
+pub async fn publish_workspace(only: &[String], options: &PublishOptions) -> Result<()> {
+    let order = packages_to_publish(only)?;
+    if order.is_empty() {
+        tracing::info!("No publishable packages in the workspace");
+        return Ok(());
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("cargo-ghdist/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+
+    for package in &order {
+        match is_published(&client, &package.name, &package.version).await {
+            Ok(true) => {
+                tracing::info!(
+                    "{} {} is already published, skipping",
+                    package.name,
+                    package.version
+                );
+                continue;
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to query the crates.io index: {}", e),
+        }
+
+        tracing::info!("Publishing {} {}", package.name, package.version);
+        let status = publish_command(&package.name, options)
+            .stdout(ui::child_stdout())
+            .status()
+            .context("Failed to run cargo publish")?;
+        if !status.success() {
+            anyhow::bail!("cargo publish failed for {}", package.name);
+        }
+
+        wait_for_index(&client, &package.name, &package.version).await?;
+        tracing::info!("{} {} is live on crates.io", package.name, package.version);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::Dependency;
+
+    fn package(name: &str, deps: &[(&str, Option<&str>)]) -> WorkspacePackage {
+        WorkspacePackage {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            dependencies: deps
+                .iter()
+                .map(|(name, kind)| Dependency {
+                    name: name.to_string(),
+                    kind: kind.map(String::from),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn names(order: &[&WorkspacePackage]) -> Vec<String> {
+        order.iter().map(|p| p.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_publish_order() {
+        let mut internal = package("internal", &[]);
+        internal.publish = Some(Vec::new());
+
+        let packages = vec![
+            package("cli", &[("core", None), ("macros", None), ("anyhow", None)]),
+            package("macros", &[("core", Some("build"))]),
+            package("core", &[("cli", Some("dev")), ("internal", None)]),
+            internal,
+        ];
+
+        let order = publish_order(&packages).unwrap();
+        assert_eq!(names(&order), vec!["core", "macros", "cli"]);
+    }
+
+    #[test]
+    fn test_dry_run_candidates() {
+        let order = vec![
+            package("core", &[]),
+            package("macros", &[("core", Some("dev"))]),
+            package("cli", &[("core", None), ("anyhow", None)]),
+        ];
+
+        // A bumped core holds back the crates that need it to resolve
+        let unpublished = BTreeSet::from(["core".to_string()]);
+        let candidates = dry_run_candidates(&order, &unpublished);
+        assert_eq!(names(&candidates), vec!["core", "macros"]);
+
+        let candidates = dry_run_candidates(&order, &BTreeSet::new());
+        assert_eq!(names(&candidates), vec!["core", "macros", "cli"]);
+    }
+
+    #[test]
+    fn test_publish_order_cycle() {
+        let packages = vec![package("a", &[("b", None)]), package("b", &[("a", None)])];
+        let error = publish_order(&packages).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Workspace crates depend on each other in a cycle: a, b"
+        );
+    }
+
+    #[test]
+    fn test_index_path() {
+        assert_eq!(index_path("a"), "1/a");
+        assert_eq!(index_path("ab"), "2/ab");
+        assert_eq!(index_path("abc"), "3/a/abc");
+        assert_eq!(index_path("Serde"), "se/rd/serde");
+        assert_eq!(index_path("cargo-ghdist"), "ca/rg/cargo-ghdist");
+    }
+
+    #[test]
+    fn test_index_has_version() {
+        let index = "{\"name\":\"x\",\"vers\":\"0.1.0\"}\n{\"name\":\"x\",\"vers\":\"0.2.0\"}\n";
+        assert!(index_has_version(index, "0.2.0"));
+        assert!(!index_has_version(index, "0.3.0"));
+        assert!(!index_has_version("", "0.1.0"));
+    }
+
+    #[test]
+    fn test_publish_command() {
+        let args = |cmd: &Command| {
+            cmd.get_args()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let cmd = publish_command("cli", &PublishOptions::default());
+        assert_eq!(args(&cmd), vec!["publish", "--package", "cli"]);
+        assert_eq!(cmd.get_envs().count(), 0);
+
+        let cmd = publish_command(
+            "cli",
+            &PublishOptions {
+                token: Some("secret".to_string()),
+                no_verify: true,
+                allow_dirty: true,
+            },
+        );
+        assert_eq!(
+            args(&cmd),
+            vec![
+                "publish",
+                "--package",
+                "cli",
+                "--no-verify",
+                "--allow-dirty"
+            ]
+        );
+        assert_eq!(
+            cmd.get_envs().collect::<Vec<_>>(),
+            vec![(
+                std::ffi::OsStr::new("CARGO_REGISTRY_TOKEN"),
+                Some(std::ffi::OsStr::new("secret"))
+            )]
+        );
+    }
+}
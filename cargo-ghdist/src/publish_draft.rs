@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+
+use crate::cli::Args;
+use crate::config::Config;
+use crate::github::GitHubClient;
+use crate::ui::{Ui, UiMode};
+
+/// Flips an existing draft release to published
+pub struct PublishDraft {
+    args: Args,
+    tag: String,
+    prerelease: bool,
+    github_client: GitHubClient,
+    ui: Ui,
+}
+
+impl PublishDraft {
+    pub fn new(mut args: Args, tag: &str, prerelease: bool) -> Result<Self> {
+        Config::load_for(&mut args)?;
+
+        let github_client = GitHubClient::from_args(&args)?;
+
+        Ok(Self {
+            ui: Ui::new(UiMode::from_args(&args)),
+            prerelease: prerelease || args.prerelease,
+            args,
+            tag: tag.to_string(),
+            github_client,
+        })
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        let (owner, repo) = self.args.parse_repository()?;
+
+        let release = self
+            .github_client
+            .find_release(&owner, &repo, &self.tag)
+            .await?
+            .with_context(|| format!("No release found for tag {} in {owner}/{repo}", self.tag))?;
+
+        if !release.draft {
+            anyhow::bail!("Release {} is already published", self.tag);
+        }
+
+        self.ui.step(format!("Publishing release {}", self.tag));
+        let release = self
+            .github_client
+            .publish_release(
+                &owner,
+                &repo,
+                release.id.0,
+                self.prerelease,
+                self.args.latest,
+            )
+            .await
+            .with_context(|| format!("Failed to publish release {}", self.tag))?;
+
+        self.ui.finish();
+        tracing::info!(
+            event = "release_published",
+            tag = %self.tag,
+            url = %release.html_url,
+            "Published release {}",
+            self.tag
+        );
+        // The release URL is the result, for scripts to pick up
+        println!("{}", release.html_url);
+
+        Ok(())
+    }
+}
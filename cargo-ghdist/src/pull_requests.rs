@@ -0,0 +1,134 @@
+use crate::config::NotesCategory;
+
+/// A merged pull request as listed in release notes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    pub author: String,
+    pub labels: Vec<String>,
+}
+
+/// Categories used when none are configured
+pub fn default_categories() -> Vec<NotesCategory> {
+    vec![
+        NotesCategory {
+            title: "🚀 Features".to_string(),
+            labels: vec!["feature".to_string(), "enhancement".to_string()],
+        },
+        NotesCategory {
+            title: "🐛 Bug Fixes".to_string(),
+            labels: vec!["bug".to_string(), "fix".to_string()],
+        },
+        NotesCategory {
+            title: "📦 Dependencies".to_string(),
+            labels: vec!["dependencies".to_string()],
+        },
+    ]
+}
+
+fn has_any_label(pr: &PullRequest, labels: &[String]) -> bool {
+    pr.labels
+        .iter()
+        .any(|label| labels.iter().any(|l| l.eq_ignore_ascii_case(label)))
+}
+
+fn render_line(pr: &PullRequest) -> String {
+    format!(
+        "- {} by @{} in [#{}]({})\n",
+        pr.title.trim(),
+        pr.author,
+        pr.number,
+        pr.url
+    )
+}
+
+/// Group pull requests into label-based categories; each PR lands in the first matching one
+pub fn render(
+    pull_requests: &[PullRequest],
+    categories: &[NotesCategory],
+    exclude_labels: &[String],
+) -> Option<String> {
+    let included = pull_requests
+        .iter()
+        .filter(|pr| !has_any_label(pr, exclude_labels))
+        .collect::<Vec<_>>();
+    if included.is_empty() {
+        return None;
+    }
+
+    let mut groups = vec![Vec::new(); categories.len()];
+    let mut other = Vec::new();
+    for pr in included {
+        match categories
+            .iter()
+            .position(|category| has_any_label(pr, &category.labels))
+        {
+            Some(index) => groups[index].push(pr),
+            None => other.push(pr),
+        }
+    }
+
+    let mut notes = String::from("## 🔀 Merged Pull Requests\n");
+    let sections = categories
+        .iter()
+        .map(|c| c.title.as_str())
+        .zip(groups)
+        .chain(std::iter::once(("📝 Other Changes", other)));
+    for (title, prs) in sections {
+        if prs.is_empty() {
+            continue;
+        }
+        notes.push_str(&format!("\n### {title}\n\n"));
+        for pr in prs {
+            notes.push_str(&render_line(pr));
+        }
+    }
+
+    Some(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pr(number: u64, labels: &[&str]) -> PullRequest {
+        PullRequest {
+            number,
+            title: format!("Change {number}"),
+            url: format!("https://github.com/owner/repo/pull/{number}"),
+            author: "octocat".to_string(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_render_groups_by_label() {
+        let prs = vec![
+            pr(1, &["Enhancement"]),
+            pr(2, &["bug"]),
+            pr(3, &["dependencies", "bug"]),
+            pr(4, &[]),
+            pr(5, &["skip-changelog", "feature"]),
+        ];
+
+        let notes = render(&prs, &default_categories(), &["skip-changelog".to_string()]).unwrap();
+
+        let features = notes.find("### 🚀 Features").unwrap();
+        let fixes = notes.find("### 🐛 Bug Fixes").unwrap();
+        let other = notes.find("### 📝 Other Changes").unwrap();
+        assert!(features < fixes && fixes < other);
+        assert!(!notes.contains("Dependencies"));
+        assert!(notes
+            .contains("- Change 1 by @octocat in [#1](https://github.com/owner/repo/pull/1)\n"));
+        assert!(notes[fixes..other].contains("[#3]"));
+        assert!(!notes.contains("[#5]"));
+    }
+
+    #[test]
+    fn test_render_empty() {
+        assert!(render(&[], &default_categories(), &[]).is_none());
+        assert!(render(&[pr(1, &["skip"])], &[], &["skip".to_string()]).is_none());
+    }
+}
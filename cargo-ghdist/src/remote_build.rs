@@ -0,0 +1,459 @@
+use anyhow::{Context, Result};
+use git2::Repository;
+use serde::Deserialize;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::builder::RELEASE_NOTES_FILE_NAME;
+use crate::config::RemoteBuildConfig;
+use crate::github::GitHubClient;
+use crate::manifest::MANIFEST_FILE_NAME;
+use crate::packager::{CHECKSUM_FILE_NAME, SIDECAR_EXTENSION};
+use crate::size_report::SIZE_REPORT_FILE_NAME;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long to wait for a dispatched run to show up in the run list
+const RUN_LOOKUP_TIMEOUT: Duration = Duration::from_secs(120);
+const RUN_LOOKUP_INTERVAL: Duration = Duration::from_secs(5);
+
+const DEFAULT_TIMEOUT_MINUTES: u64 = 60;
+
+/// GitHub Actions workflow run, as returned by the REST API
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowRun {
+    pub id: u64,
+    /// "queued", "in_progress" or "completed"
+    pub status: String,
+    /// "success", "failure", "cancelled", ... once completed
+    pub conclusion: Option<String>,
+    pub html_url: String,
+    /// The run name, which the workflow's `run-name` can set from its inputs
+    #[serde(default)]
+    pub display_title: String,
+}
+
+/// Artifact uploaded by a workflow run
+#[derive(Debug, Clone, Deserialize)]
+pub struct RunArtifact {
+    pub name: String,
+    pub archive_download_url: String,
+    #[serde(default)]
+    pub expired: bool,
+}
+
+/// Requested targets that the workflow builds instead of this machine
+pub fn remote_targets(config: &RemoteBuildConfig, targets: &[String]) -> Vec<String> {
+    targets
+        .iter()
+        .filter(|target| config.targets.contains(target))
+        .cloned()
+        .collect()
+}
+
+/// Workflow inputs: the configured ones plus `tag`, comma-separated `targets`
+/// and `dispatch_id`, which identifies the run when the workflow echoes it in
+/// its `run-name`
+pub fn dispatch_inputs(
+    config: &RemoteBuildConfig,
+    tag: &str,
+    targets: &[String],
+    dispatch_id: &str,
+) -> serde_json::Value {
+    let mut inputs = config
+        .inputs
+        .iter()
+        .map(|(key, value)| (key.clone(), serde_json::Value::from(value.as_str())))
+        .collect::<serde_json::Map<_, _>>();
+    inputs.insert("tag".to_string(), tag.into());
+    inputs.insert("targets".to_string(), targets.join(",").into());
+    inputs.insert("dispatch_id".to_string(), dispatch_id.into());
+    serde_json::Value::Object(inputs)
+}
+
+/// An ID for one dispatch, unique enough to tell its run from concurrent ones
+fn new_dispatch_id(tag: &str) -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!("ghdist-{tag}-{nanos:x}-{:x}", std::process::id())
+}
+
+/// The branch checked out in the current repository, which the workflow runs
+/// on unless `ref` is configured; the release tag may not be pushed yet
+fn current_branch() -> Result<String> {
+    let repository = Repository::open(".").context("Failed to open git repository")?;
+    let head = repository.head().context("Failed to read HEAD")?;
+    if !head.is_branch() {
+        anyhow::bail!(
+            "HEAD is detached; set [remote_build] ref to the branch the workflow should run on"
+        );
+    }
+    head.shorthand()
+        .map(str::to_string)
+        .context("The current branch name isn't valid UTF-8")
+}
+
+/// Unpack an artifact zip into `dir`, flattening any directories inside it
+pub fn extract_artifact(bytes: &[u8], dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let mut files = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(name) = entry
+            .enclosed_name()
+            .and_then(|path| path.file_name().map(PathBuf::from))
+        else {
+            continue;
+        };
+
+        let path = dir.join(name);
+        std::io::copy(&mut entry, &mut fs::File::create(&path)?)?;
+        files.push(path);
+    }
+    Ok(files)
+}
+
+/// Download and unpack every unexpired artifact of a workflow run into `dir`,
+/// returning the files other than generated ones
+pub async fn download_run_artifacts(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    run_id: u64,
+    dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for artifact in client.list_run_artifacts(owner, repo, run_id).await? {
+        if artifact.expired {
+            tracing::warn!("Artifact {} has expired, skipping", artifact.name);
+            continue;
+        }
+        tracing::info!("Downloading artifact: {}", artifact.name);
+        let bytes = client.download_artifact(owner, repo, &artifact).await?;
+        files.extend(
+            extract_artifact(&bytes, dir)
+                .with_context(|| format!("Failed to unpack artifact {}", artifact.name))?
+                .into_iter()
+                .filter(|path| !is_generated(path)),
+        );
+    }
+
+    if files.is_empty() {
+        anyhow::bail!("Workflow run {run_id} has no artifacts to release");
+    }
+    Ok(files)
+}
+
+/// Where `--from-artifacts` takes prebuilt archives from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArtifactSource {
+    /// GitHub Actions workflow run ID
+    Run(u64),
+    Dir(PathBuf),
+}
+
+impl ArtifactSource {
+    /// An existing directory, otherwise a numeric workflow run ID
+    pub fn parse(value: &str) -> Result<Self> {
+        let path = Path::new(value);
+        if path.is_dir() {
+            return Ok(Self::Dir(path.to_path_buf()));
+        }
+        value.parse().map(Self::Run).map_err(|_| {
+            anyhow::anyhow!("--from-artifacts {value} is neither a directory nor a workflow run ID")
+        })
+    }
+}
+
+/// Whether ghdist generates `path` next to the archives (checksums, sidecars,
+/// manifests, size reports and release notes), so a release makes its own
+fn is_generated(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    [
+        CHECKSUM_FILE_NAME,
+        MANIFEST_FILE_NAME,
+        SIZE_REPORT_FILE_NAME,
+        RELEASE_NOTES_FILE_NAME,
+    ]
+    .contains(&name)
+        || path.extension().is_some_and(|ext| ext == SIDECAR_EXTENSION)
+}
+
+/// Files in a directory of prebuilt archives, including subdirectories, sorted by path
+///
+/// Files generated by an earlier run are left out since they are generated
+/// again for the release.
+pub fn local_artifacts(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in
+            fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if !is_generated(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Prebuilt assets whose file name mentions `target`
+pub fn assets_for_target(assets: &[PathBuf], target: &str) -> Vec<PathBuf> {
+    assets
+        .iter()
+        .filter(|asset| {
+            asset
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.contains(target))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Dispatched workflow run building the remote targets
+pub struct RemoteBuild {
+    run: WorkflowRun,
+}
+
+/// Dispatch the configured workflow and find the run it started
+///
+/// The run builds in parallel with the local targets; [`RemoteBuild::finish`]
+/// waits for it afterwards.
+pub async fn dispatch(
+    client: &GitHubClient,
+    config: &RemoteBuildConfig,
+    owner: &str,
+    repo: &str,
+    tag: &str,
+    targets: &[String],
+) -> Result<RemoteBuild> {
+    let workflow = config
+        .workflow
+        .as_deref()
+        .context("[remote_build] targets are set but no workflow is configured")?;
+    let git_ref = match &config.git_ref {
+        Some(git_ref) => git_ref.clone(),
+        None => current_branch()?,
+    };
+    let dispatch_id = new_dispatch_id(tag);
+
+    tracing::info!(
+        "Dispatching {} on {} for {}",
+        workflow,
+        git_ref,
+        targets.join(", ")
+    );
+    client
+        .dispatch_workflow(
+            owner,
+            repo,
+            workflow,
+            &git_ref,
+            &dispatch_inputs(config, tag, targets, &dispatch_id),
+        )
+        .await?;
+
+    // The dispatch API does not return the run, so look for the one named after the ID
+    let started = Instant::now();
+    loop {
+        tokio::time::sleep(RUN_LOOKUP_INTERVAL).await;
+        let runs = client.list_workflow_runs(owner, repo, workflow).await?;
+        if let Some(run) = runs
+            .into_iter()
+            .find(|run| run.display_title.contains(&dispatch_id))
+        {
+            tracing::info!("Remote build started: {}", run.html_url);
+            return Ok(RemoteBuild { run });
+        }
+        if started.elapsed() > RUN_LOOKUP_TIMEOUT {
+            anyhow::bail!(
+                "Dispatched {workflow}, but no run named after {dispatch_id} appeared; \
+                 set the workflow's run-name to include ${{{{ inputs.dispatch_id }}}}"
+            );
+        }
+    }
+}
+
+impl RemoteBuild {
+    /// Wait for the run to succeed and download its artifacts into `output_dir`
+    pub async fn finish(
+        self,
+        client: &GitHubClient,
+        config: &RemoteBuildConfig,
+        owner: &str,
+        repo: &str,
+        output_dir: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        let timeout =
+            Duration::from_secs(60 * config.timeout_minutes.unwrap_or(DEFAULT_TIMEOUT_MINUTES));
+        let started = Instant::now();
+
+        let mut run = self.run;
+        while run.status != "completed" {
+            if started.elapsed() > timeout {
+                anyhow::bail!("Remote build did not finish in time: {}", run.html_url);
+            }
+            tracing::info!(
+                "Waiting for remote build ({}): {}",
+                run.status,
+                run.html_url
+            );
+            tokio::time::sleep(POLL_INTERVAL).await;
+            run = client.get_workflow_run(owner, repo, run.id).await?;
+        }
+
+        if run.conclusion.as_deref() != Some("success") {
+            anyhow::bail!(
+                "Remote build finished with {}: {}",
+                run.conclusion.as_deref().unwrap_or("no conclusion"),
+                run.html_url
+            );
+        }
+
+        download_run_artifacts(client, owner, repo, run.id, output_dir).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn config() -> RemoteBuildConfig {
+        RemoteBuildConfig {
+            workflow: Some("remote-build.yml".to_string()),
+            targets: vec![
+                "x86_64-apple-darwin".to_string(),
+                "aarch64-apple-darwin".to_string(),
+            ],
+            inputs: [("profile".to_string(), "dist".to_string())].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_remote_targets() {
+        let targets = vec![
+            "x86_64-unknown-linux-gnu".to_string(),
+            "aarch64-apple-darwin".to_string(),
+        ];
+        assert_eq!(
+            remote_targets(&config(), &targets),
+            vec!["aarch64-apple-darwin"]
+        );
+        assert!(remote_targets(&RemoteBuildConfig::default(), &targets).is_empty());
+    }
+
+    #[test]
+    fn test_dispatch_inputs() {
+        let targets = vec![
+            "x86_64-apple-darwin".to_string(),
+            "aarch64-apple-darwin".to_string(),
+        ];
+        assert_eq!(
+            dispatch_inputs(&config(), "v1.0.0", &targets, "ghdist-v1.0.0-1f-2a"),
+            serde_json::json!({
+                "profile": "dist",
+                "tag": "v1.0.0",
+                "targets": "x86_64-apple-darwin,aarch64-apple-darwin",
+                "dispatch_id": "ghdist-v1.0.0-1f-2a",
+            })
+        );
+    }
+
+    #[test]
+    fn test_artifact_source() {
+        let dir = tempdir().unwrap();
+        assert_eq!(
+            ArtifactSource::parse(dir.path().to_str().unwrap()).unwrap(),
+            ArtifactSource::Dir(dir.path().to_path_buf())
+        );
+        assert_eq!(
+            ArtifactSource::parse("9876543210").unwrap(),
+            ArtifactSource::Run(9876543210)
+        );
+        assert!(ArtifactSource::parse("missing-dir").is_err());
+    }
+
+    #[test]
+    fn test_local_artifacts() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("macos")).unwrap();
+        for name in [
+            "tool-x86_64-unknown-linux-gnu.tar.gz",
+            "tool_1.0.0_amd64-x86_64-unknown-linux-gnu.deb",
+            "macos/tool-aarch64-apple-darwin.tar.gz",
+            "SHA256SUMS",
+            "dist-manifest.json",
+            "size-report.json",
+            "RELEASE_NOTES.md",
+            "tool-x86_64-unknown-linux-gnu.tar.gz.sha256",
+        ] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+
+        let files = local_artifacts(dir.path()).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                dir.path().join("macos/tool-aarch64-apple-darwin.tar.gz"),
+                dir.path().join("tool-x86_64-unknown-linux-gnu.tar.gz"),
+                dir.path()
+                    .join("tool_1.0.0_amd64-x86_64-unknown-linux-gnu.deb"),
+            ]
+        );
+
+        assert_eq!(
+            assets_for_target(&files, "x86_64-unknown-linux-gnu").len(),
+            2
+        );
+        assert_eq!(
+            assets_for_target(&files, "aarch64-apple-darwin"),
+            vec![dir.path().join("macos/tool-aarch64-apple-darwin.tar.gz")]
+        );
+        assert!(assets_for_target(&files, "x86_64-pc-windows-msvc").is_empty());
+    }
+
+    #[test]
+    fn test_extract_artifact() {
+        let mut bytes = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut bytes));
+            let options = zip::write::SimpleFileOptions::default();
+            zip.add_directory("dist/", options).unwrap();
+            zip.start_file("dist/tool-x86_64-apple-darwin.tar.gz", options)
+                .unwrap();
+            zip.write_all(b"archive").unwrap();
+            zip.start_file("../escape.txt", options).unwrap();
+            zip.write_all(b"nope").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let dir = tempdir().unwrap();
+        let files = extract_artifact(&bytes, dir.path()).unwrap();
+        assert_eq!(
+            files,
+            vec![dir.path().join("tool-x86_64-apple-darwin.tar.gz")]
+        );
+        assert_eq!(fs::read(&files[0]).unwrap(), b"archive");
+    }
+}
@@ -0,0 +1,153 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::manifest::MANIFEST_FILE_NAME;
+use crate::size_report::SIZE_REPORT_FILE_NAME;
+
+/// Files regenerated by every run, so their uploaded copies are never reused
+const GENERATED_ASSETS: [&str; 3] = ["SHA256SUMS", MANIFEST_FILE_NAME, SIZE_REPORT_FILE_NAME];
+
+/// Release asset as listed by the REST API, including its upload state and digest
+#[derive(Debug, Clone, Deserialize)]
+pub struct UploadedAsset {
+    pub id: u64,
+    pub name: String,
+    /// "uploaded" once complete; anything else is a broken upload
+    pub state: String,
+    /// e.g. "sha256:2cf24d..."; missing on assets uploaded before GitHub added digests
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+impl UploadedAsset {
+    pub fn sha256(&self) -> Option<&str> {
+        self.digest.as_deref()?.strip_prefix("sha256:")
+    }
+
+    /// Whether the upload is complete and GitHub's digest matches the local file
+    pub fn is_identical(&self, local_sha256: &str) -> bool {
+        self.state == "uploaded" && self.sha256() == Some(local_sha256)
+    }
+}
+
+/// What a resumed upload does with one local asset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadAction {
+    /// Already uploaded with the same content
+    Skip,
+    Upload,
+    /// Uploaded but incomplete or different; delete this asset ID first
+    Replace(u64),
+}
+
+/// State of a release whose upload is being resumed
+#[derive(Debug, Clone, Default)]
+pub struct ResumeState {
+    pub uploaded: Vec<UploadedAsset>,
+    /// Entries of the SHA256SUMS uploaded by the earlier run, if it got that far
+    pub previous_checksums: HashMap<String, String>,
+}
+
+impl ResumeState {
+    /// Checksum of an uploaded asset, from GitHub's digest or the earlier SHA256SUMS
+    fn uploaded_sha256<'a>(&'a self, asset: &'a UploadedAsset) -> Option<&'a str> {
+        asset
+            .sha256()
+            .or_else(|| self.previous_checksums.get(&asset.name).map(String::as_str))
+    }
+
+    pub fn plan(&self, name: &str, local_sha256: &str) -> UploadAction {
+        let Some(asset) = self.uploaded.iter().find(|a| a.name == name) else {
+            return UploadAction::Upload;
+        };
+        if asset.state == "uploaded"
+            && !GENERATED_ASSETS.contains(&name)
+            && self.uploaded_sha256(asset) == Some(local_sha256)
+        {
+            UploadAction::Skip
+        } else {
+            UploadAction::Replace(asset.id)
+        }
+    }
+
+    /// `(name, sha256)` of completed uploads this run does not produce, for SHA256SUMS
+    ///
+    /// Assets whose checksum is unknown are left out with a warning.
+    pub fn remote_only_checksums(&self, local_names: &[&str]) -> Vec<(String, String)> {
+        let mut checksums = Vec::new();
+        for asset in &self.uploaded {
+            if asset.state != "uploaded"
+                || local_names.contains(&asset.name.as_str())
+                || GENERATED_ASSETS.contains(&asset.name.as_str())
+            {
+                continue;
+            }
+            match self.uploaded_sha256(asset) {
+                Some(sha256) => checksums.push((asset.name.clone(), sha256.to_string())),
+                None => tracing::warn!(
+                    "No checksum known for {}, leaving it out of SHA256SUMS",
+                    asset.name
+                ),
+            }
+        }
+        checksums.sort();
+        checksums
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(id: u64, name: &str, state: &str, digest: Option<&str>) -> UploadedAsset {
+        UploadedAsset {
+            id,
+            name: name.to_string(),
+            state: state.to_string(),
+            digest: digest.map(|d| format!("sha256:{d}")),
+        }
+    }
+
+    fn state() -> ResumeState {
+        ResumeState {
+            uploaded: vec![
+                asset(1, "linux.tar.gz", "uploaded", Some("aaa")),
+                asset(2, "macos.tar.gz", "uploaded", None),
+                asset(3, "windows.zip", "starter", Some("ccc")),
+                asset(4, "SHA256SUMS", "uploaded", Some("ddd")),
+                asset(5, "freebsd.tar.gz", "uploaded", None),
+            ],
+            previous_checksums: [("macos.tar.gz".to_string(), "bbb".to_string())].into(),
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let state = state();
+        assert_eq!(state.plan("linux.tar.gz", "aaa"), UploadAction::Skip);
+        assert_eq!(
+            state.plan("linux.tar.gz", "changed"),
+            UploadAction::Replace(1)
+        );
+        assert_eq!(state.plan("macos.tar.gz", "bbb"), UploadAction::Skip);
+        assert_eq!(state.plan("windows.zip", "ccc"), UploadAction::Replace(3));
+        assert_eq!(state.plan("SHA256SUMS", "ddd"), UploadAction::Replace(4));
+        assert_eq!(state.plan("new.tar.gz", "eee"), UploadAction::Upload);
+    }
+
+    #[test]
+    fn test_is_identical() {
+        assert!(asset(1, "linux.tar.gz", "uploaded", Some("aaa")).is_identical("aaa"));
+        assert!(!asset(1, "linux.tar.gz", "uploaded", Some("aaa")).is_identical("bbb"));
+        assert!(!asset(1, "linux.tar.gz", "starter", Some("aaa")).is_identical("aaa"));
+        assert!(!asset(1, "linux.tar.gz", "uploaded", None).is_identical("aaa"));
+    }
+
+    #[test]
+    fn test_remote_only_checksums() {
+        assert_eq!(
+            state().remote_only_checksums(&["linux.tar.gz"]),
+            vec![("macos.tar.gz".to_string(), "bbb".to_string())]
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use crate::config::RetentionConfig;
+use chrono::{DateTime, Duration, Utc};
+
+/// Hidden line in the notes of every continuous release, so pruning only
+/// ever touches releases this tool created
+pub const CONTINUOUS_MARKER: &str = "<!-- cargo-ghdist:continuous -->";
+
+/// A published release as seen by the pruning step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseEntry {
+    pub id: u64,
+    pub tag: String,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Whether its notes carry [`CONTINUOUS_MARKER`]
+    pub marked: bool,
+}
+
+/// Whether `tag` is exactly what `--hash` produces: `{prefix}{version}-{sha}`
+/// with the package's tag prefix (if any), a semver version and the first 8
+/// hex digits of the commit
+pub fn is_continuous_tag(tag: &str, prefix: Option<&str>) -> bool {
+    let Some(rest) = tag.strip_prefix(prefix.unwrap_or_default()) else {
+        return false;
+    };
+    rest.rsplit_once('-').is_some_and(|(version, sha)| {
+        semver::Version::parse(version).is_ok()
+            && sha.len() == 8
+            && sha
+                .chars()
+                .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+    })
+}
+
+/// Select continuous releases that fall outside the retention policy
+///
+/// Only marked releases whose tag [`is_continuous_tag`] with `prefix` count.
+/// The release for `current_tag` is never selected, and it counts towards `keep`.
+pub fn select_for_pruning(
+    releases: &[ReleaseEntry],
+    current_tag: &str,
+    prefix: Option<&str>,
+    policy: &RetentionConfig,
+    now: DateTime<Utc>,
+) -> Vec<ReleaseEntry> {
+    let mut continuous = releases
+        .iter()
+        .filter(|r| r.tag != current_tag && r.marked && is_continuous_tag(&r.tag, prefix))
+        .collect::<Vec<_>>();
+    // Newest first; releases without a timestamp sort last
+    continuous.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let keep_others = policy.keep.map(|keep| keep.saturating_sub(1));
+    let cutoff = policy
+        .max_age_days
+        .map(|days| now - Duration::days(days as i64));
+
+    continuous
+        .into_iter()
+        .enumerate()
+        .filter(|(index, release)| {
+            let beyond_count = keep_others.is_some_and(|keep| *index >= keep);
+            let too_old = cutoff
+                .zip(release.created_at)
+                .is_some_and(|(cutoff, created)| created < cutoff);
+            beyond_count || too_old
+        })
+        .map(|(_, release)| release.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(id: u64, tag: &str, days_ago: i64, now: DateTime<Utc>) -> ReleaseEntry {
+        ReleaseEntry {
+            id,
+            tag: tag.to_string(),
+            created_at: Some(now - Duration::days(days_ago)),
+            marked: true,
+        }
+    }
+
+    #[test]
+    fn test_is_continuous_tag() {
+        assert!(is_continuous_tag("0.1.0-abc12345", None));
+        assert!(is_continuous_tag("1.2.3-beta.1-0123abcd", None));
+        assert!(is_continuous_tag("core-v0.1.0-abc12345", Some("core-v")));
+        assert!(!is_continuous_tag("0.1.0-abc12345", Some("core-v")));
+        assert!(!is_continuous_tag("v1.0.0", None));
+        assert!(!is_continuous_tag("1.0.0-beta.1", None));
+        assert!(!is_continuous_tag("1.0.0-ABC12345", None));
+        assert!(!is_continuous_tag("-abc12345", None));
+        // Other tools' date-stamped tags aren't ours
+        assert!(!is_continuous_tag("nightly-20240101", None));
+    }
+
+    #[test]
+    fn test_select_by_count() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let releases = vec![
+            release(1, "0.1.0-00000001", 3, now),
+            release(2, "0.1.0-00000002", 2, now),
+            release(3, "v0.1.0", 10, now),
+            release(4, "0.1.0-00000003", 1, now),
+            release(5, "0.1.0-00000004", 0, now),
+        ];
+        let policy = RetentionConfig {
+            keep: Some(2),
+            max_age_days: None,
+        };
+
+        let pruned = select_for_pruning(&releases, "0.1.0-00000004", None, &policy, now);
+        let ids = pruned.iter().map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![2, 1]);
+
+        // A release without the marker wasn't created by ghdist
+        let mut releases = releases;
+        releases[0].marked = false;
+        releases[1].marked = false;
+        assert!(select_for_pruning(&releases, "0.1.0-00000004", None, &policy, now).is_empty());
+    }
+
+    #[test]
+    fn test_select_by_age() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let releases = vec![
+            release(1, "0.1.0-00000001", 40, now),
+            release(2, "0.1.0-00000002", 5, now),
+            release(3, "v0.0.1", 100, now),
+        ];
+        let policy = RetentionConfig {
+            keep: None,
+            max_age_days: Some(30),
+        };
+
+        let pruned = select_for_pruning(&releases, "0.1.0-00000003", None, &policy, now);
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].tag, "0.1.0-00000001");
+
+        assert!(
+            select_for_pruning(&releases, "x", None, &RetentionConfig::default(), now).is_empty()
+        );
+    }
+}
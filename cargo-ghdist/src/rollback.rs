@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use std::io::{self, Write};
+
+use crate::cli::Args;
+use crate::config::Config;
+use crate::github::GitHubClient;
+use crate::ui::{Ui, UiMode};
+
+/// Deletes a published release and, unless asked not to, its tag
+pub struct Rollback {
+    args: Args,
+    tag: String,
+    keep_tag: bool,
+    yes: bool,
+    github_client: GitHubClient,
+    ui: Ui,
+}
+
+impl Rollback {
+    pub fn new(mut args: Args, tag: &str, keep_tag: bool, yes: bool) -> Result<Self> {
+        Config::load_for(&mut args)?;
+
+        let github_client = GitHubClient::from_args(&args)?;
+
+        Ok(Self {
+            ui: Ui::new(UiMode::from_args(&args)),
+            args,
+            tag: tag.to_string(),
+            keep_tag,
+            yes,
+            github_client,
+        })
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        let (owner, repo) = self.args.parse_repository()?;
+
+        let release = self
+            .github_client
+            .find_release(&owner, &repo, &self.tag)
+            .await?
+            .with_context(|| format!("No release found for tag {} in {owner}/{repo}", self.tag))?;
+
+        let prompt = if self.keep_tag {
+            format!("Delete release {} from {owner}/{repo}?", self.tag)
+        } else {
+            format!(
+                "Delete release {} and its tag from {owner}/{repo}?",
+                self.tag
+            )
+        };
+        if !self.confirm(&prompt)? {
+            tracing::warn!("Rollback cancelled, nothing was deleted");
+            return Ok(());
+        }
+
+        self.ui.step(format!("Deleting release {}", self.tag));
+        self.github_client
+            .delete_release(&owner, &repo, release.id.0)
+            .await?;
+        tracing::info!(event = "release_deleted", tag = %self.tag, "Deleted release {}", self.tag);
+
+        if !self.keep_tag {
+            self.ui.step(format!("Deleting tag {}", self.tag));
+            self.github_client
+                .delete_tag(&owner, &repo, &self.tag)
+                .await
+                .with_context(|| {
+                    format!("Release deleted, but failed to delete tag {}", self.tag)
+                })?;
+            tracing::info!(event = "tag_deleted", tag = %self.tag, "Deleted tag {}", self.tag);
+        }
+        self.ui.finish();
+
+        Ok(())
+    }
+
+    fn confirm(&self, prompt: &str) -> Result<bool> {
+        if self.yes {
+            return Ok(true);
+        }
+
+        print!("{prompt} (y/N): ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        Ok(input.trim().eq_ignore_ascii_case("y"))
+    }
+}
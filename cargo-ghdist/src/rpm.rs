@@ -0,0 +1,608 @@
+use crate::config::RpmConfig;
+use crate::error::GhDistError;
+use crate::packager::{self, PackageFile, PackageMetadata};
+use anyhow::Result;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Header tags (see rpmtag.h)
+const RPMTAG_HEADERSIGNATURES: u32 = 62;
+const RPMTAG_HEADERIMMUTABLE: u32 = 63;
+const RPMTAG_SHA256HEADER: u32 = 273;
+const RPMSIGTAG_SIZE: u32 = 1000;
+const RPMSIGTAG_PAYLOADSIZE: u32 = 1007;
+const RPMTAG_NAME: u32 = 1000;
+const RPMTAG_VERSION: u32 = 1001;
+const RPMTAG_RELEASE: u32 = 1002;
+const RPMTAG_SUMMARY: u32 = 1004;
+const RPMTAG_DESCRIPTION: u32 = 1005;
+const RPMTAG_BUILDTIME: u32 = 1006;
+const RPMTAG_BUILDHOST: u32 = 1007;
+const RPMTAG_SIZE: u32 = 1009;
+const RPMTAG_LICENSE: u32 = 1014;
+const RPMTAG_GROUP: u32 = 1016;
+const RPMTAG_URL: u32 = 1020;
+const RPMTAG_OS: u32 = 1021;
+const RPMTAG_ARCH: u32 = 1022;
+const RPMTAG_FILESIZES: u32 = 1028;
+const RPMTAG_FILEMODES: u32 = 1030;
+const RPMTAG_FILERDEVS: u32 = 1033;
+const RPMTAG_FILEMTIMES: u32 = 1034;
+const RPMTAG_FILEDIGESTS: u32 = 1035;
+const RPMTAG_FILELINKTOS: u32 = 1036;
+const RPMTAG_FILEFLAGS: u32 = 1037;
+const RPMTAG_FILEUSERNAME: u32 = 1039;
+const RPMTAG_FILEGROUPNAME: u32 = 1040;
+const RPMTAG_PROVIDENAME: u32 = 1047;
+const RPMTAG_REQUIREFLAGS: u32 = 1048;
+const RPMTAG_REQUIRENAME: u32 = 1049;
+const RPMTAG_REQUIREVERSION: u32 = 1050;
+const RPMTAG_RPMVERSION: u32 = 1064;
+const RPMTAG_FILEDEVICES: u32 = 1095;
+const RPMTAG_FILEINODES: u32 = 1096;
+const RPMTAG_FILELANGS: u32 = 1097;
+const RPMTAG_PROVIDEFLAGS: u32 = 1112;
+const RPMTAG_PROVIDEVERSION: u32 = 1113;
+const RPMTAG_DIRINDEXES: u32 = 1116;
+const RPMTAG_BASENAMES: u32 = 1117;
+const RPMTAG_DIRNAMES: u32 = 1118;
+const RPMTAG_PAYLOADFORMAT: u32 = 1124;
+const RPMTAG_PAYLOADCOMPRESSOR: u32 = 1125;
+const RPMTAG_PAYLOADFLAGS: u32 = 1126;
+const RPMTAG_FILEDIGESTALGO: u32 = 5011;
+const RPMTAG_PAYLOADDIGEST: u32 = 5092;
+const RPMTAG_PAYLOADDIGESTALGO: u32 = 5093;
+
+const PGPHASHALGO_SHA256: u32 = 8;
+const RPMSENSE_LESS: u32 = 0x02;
+const RPMSENSE_EQUAL: u32 = 0x08;
+const RPMSENSE_RPMLIB: u32 = 0x0100_0000;
+
+/// A typed value stored in an RPM header
+enum HeaderValue {
+    Int16(Vec<u16>),
+    Int32(Vec<u32>),
+    String(String),
+    StringArray(Vec<String>),
+    I18nString(String),
+}
+
+impl HeaderValue {
+    fn type_id(&self) -> u32 {
+        match self {
+            HeaderValue::Int16(_) => 3,
+            HeaderValue::Int32(_) => 4,
+            HeaderValue::String(_) => 6,
+            HeaderValue::StringArray(_) => 8,
+            HeaderValue::I18nString(_) => 9,
+        }
+    }
+
+    fn count(&self) -> usize {
+        match self {
+            HeaderValue::Int16(values) => values.len(),
+            HeaderValue::Int32(values) => values.len(),
+            HeaderValue::String(_) | HeaderValue::I18nString(_) => 1,
+            HeaderValue::StringArray(values) => values.len(),
+        }
+    }
+
+    fn alignment(&self) -> usize {
+        match self {
+            HeaderValue::Int16(_) => 2,
+            HeaderValue::Int32(_) => 4,
+            _ => 1,
+        }
+    }
+
+    fn write_to(&self, store: &mut Vec<u8>) {
+        match self {
+            HeaderValue::Int16(values) => {
+                values
+                    .iter()
+                    .for_each(|v| store.extend_from_slice(&v.to_be_bytes()));
+            }
+            HeaderValue::Int32(values) => {
+                values
+                    .iter()
+                    .for_each(|v| store.extend_from_slice(&v.to_be_bytes()));
+            }
+            HeaderValue::String(value) | HeaderValue::I18nString(value) => {
+                store.extend_from_slice(value.as_bytes());
+                store.push(0);
+            }
+            HeaderValue::StringArray(values) => {
+                for value in values {
+                    store.extend_from_slice(value.as_bytes());
+                    store.push(0);
+                }
+            }
+        }
+    }
+}
+
+/// Serialize a header with its immutable region trailer
+fn encode_header(entries: BTreeMap<u32, HeaderValue>, region_tag: u32) -> Vec<u8> {
+    let entry_count = entries.len() + 1;
+    let mut index = Vec::with_capacity(entry_count * 16);
+    let mut store = Vec::new();
+
+    for (tag, value) in &entries {
+        while store.len() % value.alignment() != 0 {
+            store.push(0);
+        }
+        push_entry(
+            &mut index,
+            *tag,
+            value.type_id(),
+            store.len() as i32,
+            value.count(),
+        );
+        value.write_to(&mut store);
+    }
+
+    // The region entry (type BIN) comes first in the index and points at a trailer that
+    // repeats the entry with a negative offset covering the whole index.
+    let mut region = Vec::with_capacity(16);
+    push_entry(&mut region, region_tag, 7, store.len() as i32, 16);
+    let mut trailer = Vec::with_capacity(16);
+    push_entry(&mut trailer, region_tag, 7, -(entry_count as i32 * 16), 16);
+    store.extend_from_slice(&trailer);
+
+    let mut header = vec![0x8e, 0xad, 0xe8, 0x01, 0, 0, 0, 0];
+    header.extend_from_slice(&(entry_count as u32).to_be_bytes());
+    header.extend_from_slice(&(store.len() as u32).to_be_bytes());
+    header.extend_from_slice(&region);
+    header.extend_from_slice(&index);
+    header.extend_from_slice(&store);
+    header
+}
+
+fn push_entry(buffer: &mut Vec<u8>, tag: u32, type_id: u32, offset: i32, count: usize) {
+    buffer.extend_from_slice(&tag.to_be_bytes());
+    buffer.extend_from_slice(&type_id.to_be_bytes());
+    buffer.extend_from_slice(&offset.to_be_bytes());
+    buffer.extend_from_slice(&(count as u32).to_be_bytes());
+}
+
+/// Map a Rust target triple to an RPM architecture name
+pub fn rpm_arch(target: &str) -> Option<&'static str> {
+    if !target.contains("linux") {
+        return None;
+    }
+
+    let arch = target.split('-').next().unwrap_or("");
+    match arch {
+        "x86_64" => Some("x86_64"),
+        "aarch64" => Some("aarch64"),
+        "i686" => Some("i686"),
+        "i586" => Some("i586"),
+        "armv7" => Some("armv7hl"),
+        "riscv64gc" => Some("riscv64"),
+        "powerpc64le" => Some("ppc64le"),
+        "s390x" => Some("s390x"),
+        "loongarch64" => Some("loongarch64"),
+        _ => None,
+    }
+}
+
+/// Convert a Cargo version into an RPM-compatible version string
+pub fn rpm_version(version: &str) -> String {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    // '-' separates version and release in RPM; '~' sorts pre-releases first
+    version.replace('-', "~")
+}
+
+/// Build an .rpm package containing the given binaries
+pub fn create_rpm(
+    binaries: &[PathBuf],
+    output_dir: &Path,
+    metadata: &PackageMetadata,
+    target: &str,
+    config: &RpmConfig,
+) -> Result<PathBuf> {
+    let arch = rpm_arch(target).ok_or_else(|| {
+        GhDistError::Package(format!("No RPM architecture known for target {target}"))
+    })?;
+    let version = rpm_version(&metadata.version);
+    let release = config.release.as_deref().unwrap_or("1");
+    let mtime = packager::source_date_epoch() as u32;
+
+    let files = packager::collect_package_files(
+        binaries,
+        &metadata.name,
+        &config.completions,
+        &config.man_pages,
+        "usr/share/zsh/site-functions",
+    )?;
+
+    let cpio = build_cpio(&files, mtime);
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&cpio)?;
+    let payload = encoder.finish()?;
+
+    let header = encode_header(
+        main_header_entries(
+            metadata, &version, release, arch, config, &files, &payload, mtime,
+        ),
+        RPMTAG_HEADERIMMUTABLE,
+    );
+
+    let mut signature_entries = BTreeMap::new();
+    signature_entries.insert(
+        RPMTAG_SHA256HEADER,
+        HeaderValue::String(hex::encode(Sha256::digest(&header))),
+    );
+    signature_entries.insert(
+        RPMSIGTAG_SIZE,
+        HeaderValue::Int32(vec![(header.len() + payload.len()) as u32]),
+    );
+    signature_entries.insert(
+        RPMSIGTAG_PAYLOADSIZE,
+        HeaderValue::Int32(vec![cpio.len() as u32]),
+    );
+    let mut signature = encode_header(signature_entries, RPMTAG_HEADERSIGNATURES);
+    while signature.len() % 8 != 0 {
+        signature.push(0);
+    }
+
+    let nvr = format!("{}-{version}-{release}", metadata.name);
+    let rpm_path = output_dir.join(format!("{nvr}.{arch}.rpm"));
+
+    let mut rpm = Vec::with_capacity(96 + signature.len() + header.len() + payload.len());
+    rpm.extend_from_slice(&lead(&nvr));
+    rpm.extend_from_slice(&signature);
+    rpm.extend_from_slice(&header);
+    rpm.extend_from_slice(&payload);
+    fs::write(&rpm_path, rpm)?;
+
+    tracing::info!("Created RPM package: {}", rpm_path.display());
+    Ok(rpm_path)
+}
+
+/// The legacy 96-byte lead that precedes the signature header
+fn lead(nvr: &str) -> Vec<u8> {
+    let mut lead = vec![0xed, 0xab, 0xee, 0xdb, 3, 0];
+    lead.extend_from_slice(&0u16.to_be_bytes()); // binary package
+    lead.extend_from_slice(&1u16.to_be_bytes()); // archnum
+    let mut name = [0u8; 66];
+    let bytes = nvr.as_bytes();
+    let len = bytes.len().min(65);
+    name[..len].copy_from_slice(&bytes[..len]);
+    lead.extend_from_slice(&name);
+    lead.extend_from_slice(&1u16.to_be_bytes()); // osnum (Linux)
+    lead.extend_from_slice(&5u16.to_be_bytes()); // header-style signature
+    lead.extend_from_slice(&[0u8; 16]);
+    lead
+}
+
+#[allow(clippy::too_many_arguments)]
+fn main_header_entries(
+    metadata: &PackageMetadata,
+    version: &str,
+    release: &str,
+    arch: &str,
+    config: &RpmConfig,
+    files: &[PackageFile],
+    payload: &[u8],
+    mtime: u32,
+) -> BTreeMap<u32, HeaderValue> {
+    let description = metadata
+        .description
+        .clone()
+        .unwrap_or_else(|| metadata.name.clone());
+    let summary = description
+        .lines()
+        .next()
+        .unwrap_or(&metadata.name)
+        .to_string();
+    let license = config
+        .license
+        .clone()
+        .or_else(|| metadata.license.clone())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let mut dirnames: Vec<String> = Vec::new();
+    let mut dirindexes = Vec::new();
+    let mut basenames = Vec::new();
+    for file in files {
+        let (dir, base) = file.path.rsplit_once('/').unwrap_or(("", &file.path));
+        let dir = format!("/{dir}/");
+        let index = dirnames.iter().position(|d| d == &dir).unwrap_or_else(|| {
+            dirnames.push(dir);
+            dirnames.len() - 1
+        });
+        dirindexes.push(index as u32);
+        basenames.push(base.to_string());
+    }
+
+    let file_count = files.len();
+    let mut requires = vec![
+        ("rpmlib(CompressedFileNames)".to_string(), "3.0.4-1"),
+        ("rpmlib(FileDigests)".to_string(), "4.6.0-1"),
+        ("rpmlib(PayloadFilesHavePrefix)".to_string(), "4.0-1"),
+    ];
+    let rpmlib_flags = RPMSENSE_RPMLIB | RPMSENSE_LESS | RPMSENSE_EQUAL;
+    let mut require_flags = vec![rpmlib_flags; requires.len()];
+    for requirement in &config.requires {
+        requires.push((requirement.clone(), ""));
+        require_flags.push(0);
+    }
+
+    let mut entries = BTreeMap::new();
+    entries.insert(RPMTAG_NAME, HeaderValue::String(metadata.name.clone()));
+    entries.insert(RPMTAG_VERSION, HeaderValue::String(version.to_string()));
+    entries.insert(RPMTAG_RELEASE, HeaderValue::String(release.to_string()));
+    entries.insert(RPMTAG_SUMMARY, HeaderValue::I18nString(summary));
+    entries.insert(RPMTAG_DESCRIPTION, HeaderValue::I18nString(description));
+    entries.insert(RPMTAG_BUILDTIME, HeaderValue::Int32(vec![mtime]));
+    entries.insert(
+        RPMTAG_BUILDHOST,
+        HeaderValue::String("localhost".to_string()),
+    );
+    entries.insert(
+        RPMTAG_SIZE,
+        HeaderValue::Int32(vec![files.iter().map(|f| f.content.len() as u32).sum()]),
+    );
+    entries.insert(RPMTAG_LICENSE, HeaderValue::String(license));
+    entries.insert(
+        RPMTAG_GROUP,
+        HeaderValue::I18nString(
+            config
+                .group
+                .clone()
+                .unwrap_or_else(|| "Applications/System".to_string()),
+        ),
+    );
+    if let Some(homepage) = &metadata.homepage {
+        entries.insert(RPMTAG_URL, HeaderValue::String(homepage.clone()));
+    }
+    entries.insert(RPMTAG_OS, HeaderValue::String("linux".to_string()));
+    entries.insert(RPMTAG_ARCH, HeaderValue::String(arch.to_string()));
+
+    entries.insert(
+        RPMTAG_FILESIZES,
+        HeaderValue::Int32(files.iter().map(|f| f.content.len() as u32).collect()),
+    );
+    entries.insert(
+        RPMTAG_FILEMODES,
+        HeaderValue::Int16(files.iter().map(|f| (0o100000 | f.mode) as u16).collect()),
+    );
+    entries.insert(RPMTAG_FILERDEVS, HeaderValue::Int16(vec![0; file_count]));
+    entries.insert(
+        RPMTAG_FILEMTIMES,
+        HeaderValue::Int32(vec![mtime; file_count]),
+    );
+    entries.insert(
+        RPMTAG_FILEDIGESTS,
+        HeaderValue::StringArray(
+            files
+                .iter()
+                .map(|f| hex::encode(Sha256::digest(&f.content)))
+                .collect(),
+        ),
+    );
+    entries.insert(
+        RPMTAG_FILELINKTOS,
+        HeaderValue::StringArray(vec![String::new(); file_count]),
+    );
+    entries.insert(RPMTAG_FILEFLAGS, HeaderValue::Int32(vec![0; file_count]));
+    entries.insert(
+        RPMTAG_FILEUSERNAME,
+        HeaderValue::StringArray(vec!["root".to_string(); file_count]),
+    );
+    entries.insert(
+        RPMTAG_FILEGROUPNAME,
+        HeaderValue::StringArray(vec!["root".to_string(); file_count]),
+    );
+    entries.insert(
+        RPMTAG_PROVIDENAME,
+        HeaderValue::StringArray(vec![metadata.name.clone()]),
+    );
+    entries.insert(
+        RPMTAG_PROVIDEFLAGS,
+        HeaderValue::Int32(vec![RPMSENSE_EQUAL]),
+    );
+    entries.insert(
+        RPMTAG_PROVIDEVERSION,
+        HeaderValue::StringArray(vec![format!("{version}-{release}")]),
+    );
+    entries.insert(RPMTAG_REQUIREFLAGS, HeaderValue::Int32(require_flags));
+    entries.insert(
+        RPMTAG_REQUIRENAME,
+        HeaderValue::StringArray(requires.iter().map(|(name, _)| name.clone()).collect()),
+    );
+    entries.insert(
+        RPMTAG_REQUIREVERSION,
+        HeaderValue::StringArray(requires.iter().map(|(_, v)| v.to_string()).collect()),
+    );
+    entries.insert(RPMTAG_RPMVERSION, HeaderValue::String("4.16.0".to_string()));
+    entries.insert(RPMTAG_FILEDEVICES, HeaderValue::Int32(vec![1; file_count]));
+    entries.insert(
+        RPMTAG_FILEINODES,
+        HeaderValue::Int32((1..=file_count as u32).collect()),
+    );
+    entries.insert(
+        RPMTAG_FILELANGS,
+        HeaderValue::StringArray(vec![String::new(); file_count]),
+    );
+    entries.insert(RPMTAG_DIRINDEXES, HeaderValue::Int32(dirindexes));
+    entries.insert(RPMTAG_BASENAMES, HeaderValue::StringArray(basenames));
+    entries.insert(RPMTAG_DIRNAMES, HeaderValue::StringArray(dirnames));
+    entries.insert(
+        RPMTAG_PAYLOADFORMAT,
+        HeaderValue::String("cpio".to_string()),
+    );
+    entries.insert(
+        RPMTAG_PAYLOADCOMPRESSOR,
+        HeaderValue::String("gzip".to_string()),
+    );
+    entries.insert(RPMTAG_PAYLOADFLAGS, HeaderValue::String("9".to_string()));
+    entries.insert(
+        RPMTAG_FILEDIGESTALGO,
+        HeaderValue::Int32(vec![PGPHASHALGO_SHA256]),
+    );
+    entries.insert(
+        RPMTAG_PAYLOADDIGEST,
+        HeaderValue::StringArray(vec![hex::encode(Sha256::digest(payload))]),
+    );
+    entries.insert(
+        RPMTAG_PAYLOADDIGESTALGO,
+        HeaderValue::Int32(vec![PGPHASHALGO_SHA256]),
+    );
+
+    entries
+}
+
+/// Build an uncompressed cpio archive in "newc" format
+fn build_cpio(files: &[PackageFile], mtime: u32) -> Vec<u8> {
+    let mut cpio = Vec::new();
+
+    for (index, file) in files.iter().enumerate() {
+        let name = format!("./{}", file.path);
+        append_cpio_entry(
+            &mut cpio,
+            &name,
+            index as u32 + 1,
+            0o100000 | file.mode,
+            mtime,
+            &file.content,
+        );
+    }
+    append_cpio_entry(&mut cpio, "TRAILER!!!", 0, 0, 0, &[]);
+
+    cpio
+}
+
+fn append_cpio_entry(
+    cpio: &mut Vec<u8>,
+    name: &str,
+    inode: u32,
+    mode: u32,
+    mtime: u32,
+    content: &[u8],
+) {
+    let nlink = if mode == 0 { 0 } else { 1 };
+    let header = format!(
+        "070701{inode:08X}{mode:08X}{:08X}{:08X}{nlink:08X}{mtime:08X}{:08X}{:08X}{:08X}{:08X}{:08X}{:08X}{:08X}",
+        0,
+        0,
+        content.len(),
+        0,
+        0,
+        0,
+        0,
+        name.len() + 1,
+        0
+    );
+    cpio.extend_from_slice(header.as_bytes());
+    cpio.extend_from_slice(name.as_bytes());
+    cpio.push(0);
+    pad_to_four(cpio);
+    cpio.extend_from_slice(content);
+    pad_to_four(cpio);
+}
+
+fn pad_to_four(buffer: &mut Vec<u8>) {
+    while buffer.len() % 4 != 0 {
+        buffer.push(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    fn test_metadata() -> PackageMetadata {
+        PackageMetadata {
+            name: "mytool".to_string(),
+            version: "1.2.3-beta.1".to_string(),
+            description: Some("A useful tool".to_string()),
+            authors: vec!["Jane Doe <jane@example.com>".to_string()],
+            homepage: Some("https://github.com/owner/mytool".to_string()),
+            license: Some("MIT".to_string()),
+        }
+    }
+
+    fn header_len(bytes: &[u8]) -> usize {
+        assert_eq!(&bytes[..4], &[0x8e, 0xad, 0xe8, 0x01]);
+        let count = u32::from_be_bytes(bytes[8..12].try_into().unwrap()) as usize;
+        let size = u32::from_be_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        16 + count * 16 + size
+    }
+
+    #[test]
+    fn test_rpm_arch() {
+        assert_eq!(rpm_arch("x86_64-unknown-linux-gnu"), Some("x86_64"));
+        assert_eq!(rpm_arch("aarch64-unknown-linux-musl"), Some("aarch64"));
+        assert_eq!(rpm_arch("armv7-unknown-linux-gnueabihf"), Some("armv7hl"));
+        assert_eq!(rpm_arch("x86_64-pc-windows-msvc"), None);
+    }
+
+    #[test]
+    fn test_rpm_version() {
+        assert_eq!(rpm_version("1.2.3"), "1.2.3");
+        assert_eq!(rpm_version("v1.2.3-rc.1"), "1.2.3~rc.1");
+    }
+
+    #[test]
+    fn test_encode_header_region_trailer() {
+        let mut entries = BTreeMap::new();
+        entries.insert(RPMTAG_NAME, HeaderValue::String("mytool".to_string()));
+        let header = encode_header(entries, RPMTAG_HEADERIMMUTABLE);
+
+        // Two index entries: the region tag and NAME
+        assert_eq!(u32::from_be_bytes(header[8..12].try_into().unwrap()), 2);
+        assert_eq!(header.len(), header_len(&header));
+
+        let trailer = &header[header.len() - 16..];
+        assert_eq!(
+            u32::from_be_bytes(trailer[..4].try_into().unwrap()),
+            RPMTAG_HEADERIMMUTABLE
+        );
+        assert_eq!(i32::from_be_bytes(trailer[8..12].try_into().unwrap()), -32);
+    }
+
+    #[test]
+    fn test_create_rpm() {
+        let temp_dir = tempdir().unwrap();
+        let binary = temp_dir.path().join("mytool");
+        fs::write(&binary, b"binary").unwrap();
+
+        let rpm_path = create_rpm(
+            &[binary],
+            temp_dir.path(),
+            &test_metadata(),
+            "x86_64-unknown-linux-gnu",
+            &RpmConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            rpm_path.file_name().unwrap(),
+            "mytool-1.2.3~beta.1-1.x86_64.rpm"
+        );
+
+        let bytes = fs::read(&rpm_path).unwrap();
+        assert_eq!(&bytes[..4], &[0xed, 0xab, 0xee, 0xdb]);
+
+        let mut offset = 96 + header_len(&bytes[96..]);
+        offset = offset.div_ceil(8) * 8;
+        offset += header_len(&bytes[offset..]);
+
+        let mut cpio = String::new();
+        flate2::read::GzDecoder::new(&bytes[offset..])
+            .read_to_string(&mut cpio)
+            .unwrap();
+        assert!(cpio.starts_with("070701"));
+        assert!(cpio.contains("./usr/bin/mytool\0"));
+        assert!(cpio.contains("TRAILER!!!"));
+    }
+}
@@ -0,0 +1,443 @@
+use crate::config::WindowsSigningConfig;
+use crate::error::GhDistError;
+use crate::ui;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const DEFAULT_TIMESTAMP_URL: &str = "http://timestamp.digicert.com";
+const DEFAULT_PFX_PASSWORD_ENV: &str = "GHDIST_SIGN_PFX_PASSWORD";
+/// Variable handing the PFX password to the PowerShell that imports it
+const PFX_IMPORT_PASSWORD_ENV: &str = "GHDIST_PFX_IMPORT_PASSWORD";
+
+/// Whether the binaries of a target were Authenticode-signed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SignatureStatus {
+    Signed { tool: String },
+    Unsigned,
+    NotApplicable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningTool {
+    Signtool,
+    Osslsigncode,
+}
+
+impl std::fmt::Display for SigningTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SigningTool::Signtool => write!(f, "signtool"),
+            SigningTool::Osslsigncode => write!(f, "osslsigncode"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Certificate {
+    /// Certificate in the Windows certificate store, selected by SHA-1 thumbprint
+    Thumbprint(String),
+    /// PKCS#12 file with an optional password
+    Pfx {
+        path: PathBuf,
+        password: Option<String>,
+    },
+}
+
+/// Authenticode signer for Windows executables
+#[derive(Debug, Clone)]
+pub struct Signer {
+    tool: SigningTool,
+    certificate: Certificate,
+    timestamp_url: String,
+    description: Option<String>,
+}
+
+impl Signer {
+    /// Build a signer from config and environment; `None` when no certificate is configured
+    pub fn from_config(config: &WindowsSigningConfig) -> Result<Option<Self>> {
+        Self::resolve(config, |key| std::env::var(key).ok())
+    }
+
+    fn resolve(
+        config: &WindowsSigningConfig,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Option<Self>> {
+        let pfx = env("GHDIST_SIGN_PFX")
+            .map(PathBuf::from)
+            .or_else(|| config.pfx.clone());
+        let thumbprint =
+            env("GHDIST_SIGN_THUMBPRINT").or_else(|| config.certificate_thumbprint.clone());
+
+        let certificate = match (pfx, thumbprint) {
+            (Some(path), _) => {
+                let password_env = config
+                    .pfx_password_env
+                    .as_deref()
+                    .unwrap_or(DEFAULT_PFX_PASSWORD_ENV);
+                Certificate::Pfx {
+                    path,
+                    password: env(password_env),
+                }
+            }
+            (None, Some(thumbprint)) => Certificate::Thumbprint(thumbprint),
+            (None, None) => return Ok(None),
+        };
+
+        let tool = match config.tool.as_deref() {
+            Some("signtool") => SigningTool::Signtool,
+            Some("osslsigncode") => SigningTool::Osslsigncode,
+            Some(other) => {
+                return Err(
+                    GhDistError::Config(format!("Unsupported signing tool: {other}")).into(),
+                )
+            }
+            None if cfg!(windows) => SigningTool::Signtool,
+            None => SigningTool::Osslsigncode,
+        };
+
+        if tool == SigningTool::Osslsigncode && matches!(certificate, Certificate::Thumbprint(_)) {
+            return Err(GhDistError::Config(
+                "osslsigncode cannot sign with a certificate thumbprint; provide a PFX file"
+                    .to_string(),
+            )
+            .into());
+        }
+
+        Ok(Some(Self {
+            tool,
+            certificate,
+            timestamp_url: config
+                .timestamp_url
+                .clone()
+                .unwrap_or_else(|| DEFAULT_TIMESTAMP_URL.to_string()),
+            description: config.description.clone(),
+        }))
+    }
+
+    /// Command-line arguments for signing `input` with `certificate`, writing
+    /// the result to `output`. A PFX password never goes on the command line,
+    /// where other users could read it: osslsigncode reads it from
+    /// `password_file`, and signtool signs with the imported certificate.
+    fn sign_args(
+        &self,
+        certificate: &Certificate,
+        input: &Path,
+        output: &Path,
+        password_file: Option<&Path>,
+    ) -> Vec<String> {
+        let mut args = vec!["sign".to_string()];
+
+        match self.tool {
+            SigningTool::Signtool => {
+                args.extend(["/fd", "SHA256", "/td", "SHA256", "/tr"].map(String::from));
+                args.push(self.timestamp_url.clone());
+                match certificate {
+                    Certificate::Thumbprint(thumbprint) => {
+                        args.push("/sha1".to_string());
+                        args.push(thumbprint.clone());
+                    }
+                    Certificate::Pfx { path, .. } => {
+                        args.push("/f".to_string());
+                        args.push(path.display().to_string());
+                    }
+                }
+                if let Some(description) = &self.description {
+                    args.push("/d".to_string());
+                    args.push(description.clone());
+                }
+                args.push(input.display().to_string());
+            }
+            SigningTool::Osslsigncode => {
+                if let Certificate::Pfx { path, .. } = certificate {
+                    args.push("-pkcs12".to_string());
+                    args.push(path.display().to_string());
+                    if let Some(password_file) = password_file {
+                        args.push("-readpass".to_string());
+                        args.push(password_file.display().to_string());
+                    }
+                }
+                args.extend(["-h", "sha256", "-ts"].map(String::from));
+                args.push(self.timestamp_url.clone());
+                if let Some(description) = &self.description {
+                    args.push("-n".to_string());
+                    args.push(description.clone());
+                }
+                args.push("-in".to_string());
+                args.push(input.display().to_string());
+                args.push("-out".to_string());
+                args.push(output.display().to_string());
+            }
+        }
+
+        args
+    }
+
+    /// The certificate to sign with and, for osslsigncode, a private file
+    /// holding the PFX password. signtool can only take a password on its
+    /// command line, so a password-protected PFX is imported into the current
+    /// user's certificate store and selected by thumbprint instead.
+    fn credentials(&self) -> Result<(Certificate, Option<tempfile::NamedTempFile>)> {
+        let Certificate::Pfx {
+            path,
+            password: Some(password),
+        } = &self.certificate
+        else {
+            return Ok((self.certificate.clone(), None));
+        };
+
+        match self.tool {
+            SigningTool::Signtool => {
+                Ok((Certificate::Thumbprint(import_pfx(path, password)?), None))
+            }
+            SigningTool::Osslsigncode => {
+                // Created readable by the current user only
+                let mut file = tempfile::NamedTempFile::new()?;
+                file.write_all(password.as_bytes())?;
+                file.flush()?;
+                Ok((self.certificate.clone(), Some(file)))
+            }
+        }
+    }
+
+    /// Sign a single executable in place
+    fn sign(
+        &self,
+        file: &Path,
+        certificate: &Certificate,
+        password_file: Option<&Path>,
+    ) -> Result<()> {
+        tracing::info!("Signing {} with {}", file.display(), self.tool);
+
+        // osslsigncode cannot write over its input
+        let output = match self.tool {
+            SigningTool::Signtool => file.to_path_buf(),
+            SigningTool::Osslsigncode => file.with_extension("signed.exe"),
+        };
+
+        let status = Command::new(self.tool.to_string())
+            .args(self.sign_args(certificate, file, &output, password_file))
+            .stdout(ui::child_stdout())
+            .status()
+            .with_context(|| format!("Failed to run `{}`", self.tool))?;
+
+        if !status.success() {
+            return Err(GhDistError::Package(format!(
+                "{} failed to sign {}",
+                self.tool,
+                file.display()
+            ))
+            .into());
+        }
+
+        if output != file {
+            fs::rename(&output, file)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sign every .exe among the binaries; `NotApplicable` when there are none
+    pub fn sign_all(&self, binaries: &[PathBuf]) -> Result<SignatureStatus> {
+        let executables = binaries
+            .iter()
+            .filter(|b| b.extension().is_some_and(|ext| ext == "exe"))
+            .collect::<Vec<_>>();
+        if executables.is_empty() {
+            return Ok(SignatureStatus::NotApplicable);
+        }
+
+        let (certificate, password_file) = self.credentials()?;
+        for binary in executables {
+            self.sign(
+                binary,
+                &certificate,
+                password_file.as_ref().map(|file| file.path()),
+            )?;
+        }
+
+        Ok(SignatureStatus::Signed {
+            tool: self.tool.to_string(),
+        })
+    }
+}
+
+/// Import a PFX file into the current user's certificate store, returning
+/// the certificate's thumbprint. The password reaches PowerShell through its
+/// environment, not its command line.
+fn import_pfx(path: &Path, password: &str) -> Result<String> {
+    let quoted = path.display().to_string().replace('\'', "''");
+    let script = format!(
+        "$password = ConvertTo-SecureString $env:{PFX_IMPORT_PASSWORD_ENV} -AsPlainText -Force; \
+         (Import-PfxCertificate -FilePath '{quoted}' -CertStoreLocation Cert:\\CurrentUser\\My \
+         -Password $password).Thumbprint"
+    );
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .env(PFX_IMPORT_PASSWORD_ENV, password)
+        .output()
+        .context("Failed to run powershell to import the PFX certificate")?;
+    let thumbprint = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || thumbprint.is_empty() {
+        return Err(GhDistError::Package(format!(
+            "Failed to import {} into the certificate store: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into());
+    }
+    Ok(thumbprint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env_from(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn test_no_certificate_disables_signing() {
+        let signer = Signer::resolve(&WindowsSigningConfig::default(), env_from(&[])).unwrap();
+        assert!(signer.is_none());
+    }
+
+    #[test]
+    fn test_pfx_from_env() {
+        let config = WindowsSigningConfig {
+            tool: Some("osslsigncode".to_string()),
+            ..Default::default()
+        };
+        let signer = Signer::resolve(
+            &config,
+            env_from(&[
+                ("GHDIST_SIGN_PFX", "cert.pfx"),
+                ("GHDIST_SIGN_PFX_PASSWORD", "secret"),
+            ]),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            signer.certificate,
+            Certificate::Pfx {
+                path: PathBuf::from("cert.pfx"),
+                password: Some("secret".to_string()),
+            }
+        );
+
+        let (certificate, password_file) = signer.credentials().unwrap();
+        let password_file = password_file.unwrap();
+        assert_eq!(fs::read_to_string(password_file.path()).unwrap(), "secret");
+        let args = signer.sign_args(
+            &certificate,
+            Path::new("tool.exe"),
+            Path::new("tool.signed.exe"),
+            Some(password_file.path()),
+        );
+        assert!(!args.contains(&"secret".to_string()));
+        let password_path = password_file.path().display().to_string();
+        assert_eq!(
+            args,
+            vec![
+                "sign",
+                "-pkcs12",
+                "cert.pfx",
+                "-readpass",
+                password_path.as_str(),
+                "-h",
+                "sha256",
+                "-ts",
+                DEFAULT_TIMESTAMP_URL,
+                "-in",
+                "tool.exe",
+                "-out",
+                "tool.signed.exe",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_signtool_thumbprint_args() {
+        let config = WindowsSigningConfig {
+            tool: Some("signtool".to_string()),
+            certificate_thumbprint: Some("ABCDEF".to_string()),
+            description: Some("My Tool".to_string()),
+            ..Default::default()
+        };
+        let signer = Signer::resolve(&config, env_from(&[])).unwrap().unwrap();
+
+        let args = signer.sign_args(
+            &signer.certificate,
+            Path::new("tool.exe"),
+            Path::new("tool.exe"),
+            None,
+        );
+        assert_eq!(
+            args,
+            vec![
+                "sign",
+                "/fd",
+                "SHA256",
+                "/td",
+                "SHA256",
+                "/tr",
+                DEFAULT_TIMESTAMP_URL,
+                "/sha1",
+                "ABCDEF",
+                "/d",
+                "My Tool",
+                "tool.exe",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sign_all_without_executables() {
+        let config = WindowsSigningConfig {
+            certificate_thumbprint: Some("ABCDEF".to_string()),
+            tool: Some("signtool".to_string()),
+            ..Default::default()
+        };
+        let signer = Signer::resolve(&config, env_from(&[])).unwrap().unwrap();
+        assert_eq!(
+            signer.sign_all(&[PathBuf::from("tool")]).unwrap(),
+            SignatureStatus::NotApplicable
+        );
+    }
+
+    #[test]
+    fn test_osslsigncode_rejects_thumbprint() {
+        let config = WindowsSigningConfig {
+            tool: Some("osslsigncode".to_string()),
+            certificate_thumbprint: Some("ABCDEF".to_string()),
+            ..Default::default()
+        };
+        assert!(Signer::resolve(&config, env_from(&[])).is_err());
+    }
+
+    #[test]
+    fn test_signature_status_serialization() {
+        let status = SignatureStatus::Signed {
+            tool: "signtool".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#"{"status":"signed","tool":"signtool"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&SignatureStatus::NotApplicable).unwrap(),
+            r#"{"status":"not_applicable"}"#
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use crate::manifest::ReleaseManifest;
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SIZE_REPORT_FILE_NAME: &str = "size-report.json";
+
+/// Size of one binary compared with the previous release
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SizeEntry {
+    pub target: String,
+    pub binary: String,
+    pub size: u64,
+    pub previous_size: Option<u64>,
+    pub delta: Option<i64>,
+    pub delta_percent: Option<f64>,
+}
+
+/// Binary size report uploaded as `size-report.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct SizeReport {
+    pub tag: String,
+    pub previous_tag: Option<String>,
+    pub entries: Vec<SizeEntry>,
+}
+
+impl SizeReport {
+    /// Compare the binaries of `current` against an optional earlier manifest
+    pub fn compare(current: &ReleaseManifest, previous: Option<&ReleaseManifest>) -> Self {
+        let mut entries = Vec::new();
+
+        for target in &current.targets {
+            let previous_target =
+                previous.and_then(|p| p.targets.iter().find(|t| t.target == target.target));
+
+            for binary in &target.binaries {
+                let previous_size = previous_target
+                    .and_then(|t| t.binaries.iter().find(|b| b.name == binary.name))
+                    .map(|b| b.size);
+                let delta = previous_size.map(|prev| binary.size as i64 - prev as i64);
+                let delta_percent = previous_size
+                    .filter(|prev| *prev > 0)
+                    .zip(delta)
+                    .map(|(prev, delta)| (delta as f64 / prev as f64 * 1000.0).round() / 10.0);
+
+                entries.push(SizeEntry {
+                    target: target.target.clone(),
+                    binary: binary.name.clone(),
+                    size: binary.size,
+                    previous_size,
+                    delta,
+                    delta_percent,
+                });
+            }
+        }
+
+        Self {
+            tag: current.tag.clone(),
+            previous_tag: previous.map(|p| p.tag.clone()),
+            entries,
+        }
+    }
+
+    /// Render a Markdown table; `None` when no binaries were measured
+    pub fn render(&self) -> Option<String> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let mut table = String::from("### 📏 Binary Sizes\n\n");
+        if let Some(previous_tag) = &self.previous_tag {
+            table.push_str(&format!("Compared with {previous_tag}.\n\n"));
+        }
+        table
+            .push_str("| Target | Binary | Size | Change |\n|--------|--------|------|--------|\n");
+
+        for entry in &self.entries {
+            let change = match (entry.delta, entry.delta_percent) {
+                (Some(0), _) => "—".to_string(),
+                (Some(delta), Some(percent)) => format!(
+                    "{}{} ({percent:+.1}%)",
+                    if delta > 0 { "+" } else { "-" },
+                    format_size(delta.unsigned_abs())
+                ),
+                (Some(delta), None) => format!(
+                    "{}{}",
+                    if delta > 0 { "+" } else { "-" },
+                    format_size(delta.unsigned_abs())
+                ),
+                (None, _) => "new".to_string(),
+            };
+            table.push_str(&format!(
+                "| `{}` | `{}` | {} | {change} |\n",
+                entry.target,
+                entry.binary,
+                format_size(entry.size)
+            ));
+        }
+
+        Some(table)
+    }
+
+    /// Write the report into the output directory
+    pub fn write(&self, output_dir: &Path) -> Result<PathBuf> {
+        let path = output_dir.join(SIZE_REPORT_FILE_NAME);
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+/// Format a byte count with binary units
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.2} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{BinarySize, TargetManifest};
+    use crate::signing::SignatureStatus;
+
+    fn manifest(tag: &str, binaries: &[(&str, u64)]) -> ReleaseManifest {
+        ReleaseManifest {
+            tag: tag.to_string(),
+            targets: vec![TargetManifest {
+                target: "x86_64-unknown-linux-gnu".to_string(),
+                assets: Vec::new(),
+                binaries: binaries
+                    .iter()
+                    .map(|(name, size)| BinarySize {
+                        name: name.to_string(),
+                        size: *size,
+                    })
+                    .collect(),
+                signature: SignatureStatus::NotApplicable,
+                min_glibc: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.00 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 + 512 * 1024), "3.50 MiB");
+    }
+
+    #[test]
+    fn test_compare_with_previous() {
+        let previous = manifest("v1.0.0", &[("tool", 1000), ("helper", 500)]);
+        let current = manifest("v1.1.0", &[("tool", 1100), ("helper", 500), ("extra", 10)]);
+
+        let report = SizeReport::compare(&current, Some(&previous));
+        assert_eq!(report.previous_tag.as_deref(), Some("v1.0.0"));
+        assert_eq!(report.entries[0].delta, Some(100));
+        assert_eq!(report.entries[0].delta_percent, Some(10.0));
+        assert_eq!(report.entries[1].delta, Some(0));
+        assert_eq!(report.entries[2].previous_size, None);
+
+        let table = report.render().unwrap();
+        assert!(table.contains("Compared with v1.0.0."));
+        assert!(
+            table.contains("| `x86_64-unknown-linux-gnu` | `tool` | 1.07 KiB | +100 B (+10.0%) |")
+        );
+        assert!(table.contains("| `helper` | 500 B | — |"));
+        assert!(table.contains("| `extra` | 10 B | new |"));
+    }
+
+    #[test]
+    fn test_compare_without_previous() {
+        let report = SizeReport::compare(&manifest("v1.0.0", &[("tool", 1)]), None);
+        assert!(report.previous_tag.is_none());
+        assert!(report.render().unwrap().contains("| new |"));
+
+        assert!(SizeReport::compare(&manifest("v1.0.0", &[]), None)
+            .render()
+            .is_none());
+    }
+}
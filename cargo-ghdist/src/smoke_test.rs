@@ -0,0 +1,142 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::config::SmokeTestConfig;
+
+/// How to execute binaries built for `target` on this host
+///
+/// Host targets run directly (`Some(vec![])`), other targets go through their
+/// configured runner (e.g. `qemu-aarch64` or `wine`), and `None` means skip.
+pub fn runner_for(config: &SmokeTestConfig, target: &str, host: &str) -> Option<Vec<String>> {
+    if let Some(runner) = config.runners.get(target) {
+        return Some(runner.split_whitespace().map(String::from).collect());
+    }
+    (target == host).then(Vec::new)
+}
+
+/// Arguments passed to each binary; defaults to `--version`
+pub fn smoke_args(config: &SmokeTestConfig) -> Vec<String> {
+    config
+        .args
+        .clone()
+        .unwrap_or_else(|| vec!["--version".to_string()])
+}
+
+fn smoke_test_binary(runner: &[String], binary: &Path, args: &[String]) -> Result<()> {
+    let mut cmd = match runner.split_first() {
+        Some((program, runner_args)) => {
+            let mut cmd = Command::new(program);
+            cmd.args(runner_args).arg(binary);
+            cmd
+        }
+        None => Command::new(binary),
+    };
+
+    let output = cmd
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to execute {}", binary.display()))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} exited with {}: {}",
+            binary.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    tracing::debug!(
+        "{}: {}",
+        binary.display(),
+        String::from_utf8_lossy(&output.stdout).trim()
+    );
+    Ok(())
+}
+
+/// Run every binary of `target` with the smoke command; fails if any of them does not execute
+pub fn run(
+    config: &SmokeTestConfig,
+    target: &str,
+    host: &str,
+    binaries: &[impl AsRef<Path>],
+) -> Result<()> {
+    let Some(runner) = runner_for(config, target, host) else {
+        tracing::warn!(
+            "Skipping smoke test for {}: no runner configured for this target",
+            target
+        );
+        return Ok(());
+    };
+
+    let args = smoke_args(config);
+    for binary in binaries {
+        smoke_test_binary(&runner, binary.as_ref(), &args)
+            .with_context(|| format!("Smoke test failed for {target}"))?;
+        tracing::info!("Smoke test passed: {}", binary.as_ref().display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn config(runners: &[(&str, &str)]) -> SmokeTestConfig {
+        SmokeTestConfig {
+            enabled: true,
+            args: None,
+            runners: runners
+                .iter()
+                .map(|(target, runner)| (target.to_string(), runner.to_string()))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_runner_for() {
+        let config = config(&[(
+            "aarch64-unknown-linux-gnu",
+            "qemu-aarch64 -L /usr/aarch64-linux-gnu",
+        )]);
+        let host = "x86_64-unknown-linux-gnu";
+
+        assert_eq!(runner_for(&config, host, host), Some(vec![]));
+        assert_eq!(
+            runner_for(&config, "aarch64-unknown-linux-gnu", host),
+            Some(vec![
+                "qemu-aarch64".to_string(),
+                "-L".to_string(),
+                "/usr/aarch64-linux-gnu".to_string()
+            ])
+        );
+        assert_eq!(runner_for(&config, "x86_64-pc-windows-msvc", host), None);
+        assert_eq!(smoke_args(&config), vec!["--version"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_through_runner() {
+        let temp_dir = tempdir().unwrap();
+        let good = temp_dir.path().join("good");
+        let bad = temp_dir.path().join("bad");
+        fs::write(&good, "test \"$1\" = --version\n").unwrap();
+        fs::write(&bad, "exit 1\n").unwrap();
+
+        // Scripts are run through `sh`, standing in for qemu/wine
+        let config = config(&[("fake-target", "sh")]);
+        assert!(run(&config, "fake-target", "host", &[&good]).is_ok());
+
+        let error = run(&config, "fake-target", "host", &[&good, &bad]).unwrap_err();
+        assert!(format!("{error:#}").contains("Smoke test failed for fake-target"));
+
+        // Targets without a runner are skipped
+        assert!(run(&config, "other-target", "host", &[&bad]).is_ok());
+    }
+}
@@ -0,0 +1,195 @@
+use crate::cache::CacheStats;
+use indicatif::HumanBytes;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What a run produced, printed with `--output json`
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct Summary {
+    pub tag: String,
+    /// Targets whose assets are part of the release
+    pub targets: Vec<String>,
+    /// Targets that failed to build when `continue_on_error` let the run go on
+    pub failed_targets: Vec<String>,
+    pub assets: Vec<AssetSummary>,
+    /// Where the assets were written; `None` when they were only on the release
+    pub output_dir: Option<PathBuf>,
+    /// Assets uploaded by this run; earlier runs' identical uploads are not counted
+    pub uploaded_assets: usize,
+    /// The release (the shared draft for `upload`); `None` with `--no-upload`
+    pub release_url: Option<String>,
+    /// Compiler cache hits and misses of the builds, when sccache was used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheStats>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct AssetSummary {
+    pub name: String,
+    pub size: u64,
+}
+
+impl AssetSummary {
+    /// Name and size of a local file; a missing file counts as empty
+    pub fn from_path(path: &Path) -> Self {
+        Self {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        }
+    }
+}
+
+impl Summary {
+    pub fn total_size(&self) -> u64 {
+        self.assets.iter().map(|asset| asset.size).sum()
+    }
+
+    /// Table of the assets followed by the totals, printed when a release is done
+    pub fn render(&self) -> String {
+        let width = self
+            .assets
+            .iter()
+            .map(|asset| asset.name.len())
+            .chain(["Asset".len(), "Total".len()])
+            .max()
+            .unwrap_or_default();
+        let mut out = format!("\n{:<width$}  {:>10}\n", "Asset", "Size");
+        for asset in &self.assets {
+            out.push_str(&format!(
+                "{:<width$}  {:>10}\n",
+                asset.name,
+                HumanBytes(asset.size).to_string()
+            ));
+        }
+        out.push_str(&format!(
+            "{:<width$}  {:>10}\n\n",
+            "Total",
+            HumanBytes(self.total_size()).to_string()
+        ));
+
+        let none = || "-".to_string();
+        let mut rows = vec![
+            ("Release", self.tag.clone()),
+            ("Targets", list(&self.targets).unwrap_or_else(none)),
+        ];
+        if let Some(failed) = list(&self.failed_targets) {
+            rows.push(("Failed", failed));
+        }
+        rows.push((
+            "Uploaded",
+            format!("{} of {} assets", self.uploaded_assets, self.assets.len()),
+        ));
+        if let Some(dir) = &self.output_dir {
+            rows.push(("Output", dir.display().to_string()));
+        }
+        if let Some(cache) = &self.cache {
+            rows.push(("Cache", cache.to_string()));
+        }
+        rows.push(("URL", self.release_url.clone().unwrap_or_else(none)));
+        for (label, value) in rows {
+            out.push_str(&format!("{label:<9} {value}\n"));
+        }
+        out
+    }
+}
+
+fn list(values: &[String]) -> Option<String> {
+    (!values.is_empty()).then(|| values.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir
+            .path()
+            .join("tool-x86_64-unknown-linux-gnu-v1.0.0.tar.gz");
+        fs::write(&archive, [0u8; 42]).unwrap();
+
+        let summary = Summary {
+            tag: "v1.0.0".to_string(),
+            targets: vec!["x86_64-unknown-linux-gnu".to_string()],
+            failed_targets: Vec::new(),
+            assets: vec![
+                AssetSummary::from_path(&archive),
+                AssetSummary::from_path(&dir.path().join("missing")),
+            ],
+            output_dir: Some(dir.path().to_path_buf()),
+            uploaded_assets: 1,
+            release_url: Some("https://github.com/o/r/releases/tag/v1.0.0".to_string()),
+            cache: None,
+        };
+        let json: serde_json::Value = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["tag"], "v1.0.0");
+        assert_eq!(
+            json["assets"][0]["name"],
+            "tool-x86_64-unknown-linux-gnu-v1.0.0.tar.gz"
+        );
+        assert_eq!(json["assets"][0]["size"], 42);
+        assert_eq!(
+            json["release_url"],
+            "https://github.com/o/r/releases/tag/v1.0.0"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let summary = Summary {
+            tag: "v1.0.0".to_string(),
+            targets: vec![
+                "x86_64-unknown-linux-gnu".to_string(),
+                "aarch64-apple-darwin".to_string(),
+            ],
+            failed_targets: vec!["x86_64-pc-windows-msvc".to_string()],
+            assets: vec![
+                AssetSummary {
+                    name: "tool-x86_64-unknown-linux-gnu.tar.gz".to_string(),
+                    size: 3 * 1024 * 1024,
+                },
+                AssetSummary {
+                    name: "SHA256SUMS".to_string(),
+                    size: 512,
+                },
+            ],
+            output_dir: None,
+            uploaded_assets: 2,
+            release_url: Some("https://github.com/o/r/releases/tag/v1.0.0".to_string()),
+            cache: None,
+        };
+        assert_eq!(
+            summary.render(),
+            "
+Asset                                       Size
+tool-x86_64-unknown-linux-gnu.tar.gz    3.00 MiB
+SHA256SUMS                                 512 B
+Total                                   3.00 MiB
+
+Release   v1.0.0
+Targets   x86_64-unknown-linux-gnu, aarch64-apple-darwin
+Failed    x86_64-pc-windows-msvc
+Uploaded  2 of 2 assets
+URL       https://github.com/o/r/releases/tag/v1.0.0
+"
+        );
+
+        let packaged = Summary {
+            tag: "v1.0.0".to_string(),
+            output_dir: Some(PathBuf::from("target/dist/v1.0.0")),
+            cache: Some(CacheStats { hits: 9, misses: 1 }),
+            ..Summary::default()
+        };
+        let rendered = packaged.render();
+        assert!(rendered.contains("Targets   -\n"));
+        assert!(rendered.contains("Uploaded  0 of 0 assets\n"));
+        assert!(rendered.contains("Output    target/dist/v1.0.0\n"));
+        assert!(rendered.contains("Cache     9 hits, 1 misses (90% hit rate)\n"));
+        assert!(rendered.contains("URL       -\n"));
+    }
+}
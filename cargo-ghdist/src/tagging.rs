@@ -0,0 +1,269 @@
+use anyhow::{Context, Result};
+use git2::{Cred, CredentialType, ObjectType, Oid, PushOptions, RemoteCallbacks, Repository};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Tag message used when `[tag] message` is not configured
+pub const DEFAULT_TAG_MESSAGE: &str = "Release {tag}";
+
+/// Substitute `{tag}` and `{version}` (the tag without a leading `v`) in a message template
+pub fn render_message(template: &str, tag: &str) -> String {
+    let version = tag.strip_prefix('v').unwrap_or(tag);
+    template.replace("{tag}", tag).replace("{version}", version)
+}
+
+/// Tag name for `version` with the configured prefix (e.g. `v1.2.3`)
+pub fn tag_for_version(prefix: &str, version: &semver::Version) -> String {
+    format!("{prefix}{version}")
+}
+
+/// Check that a version-like tag matches the Cargo.toml version
+///
+/// Tags that are not SemVer after removing `prefix` (branch names, hashes) are accepted.
+pub fn verify_tag_version(tag: &str, prefix: &str, package_version: &str) -> Result<()> {
+    let candidate = tag.strip_prefix(prefix).unwrap_or(tag);
+    let Ok(tag_version) = semver::Version::parse(candidate) else {
+        return Ok(());
+    };
+
+    if semver::Version::parse(package_version).ok() != Some(tag_version.clone()) {
+        anyhow::bail!(
+            "Tag {tag} does not match the version in Cargo.toml:\n\
+             \x20 tag:        {tag_version}\n\
+             \x20 Cargo.toml: {package_version}\n\
+             Update Cargo.toml (e.g. `cargo ghdist bump`) or pass --no-verify-version"
+        );
+    }
+
+    Ok(())
+}
+
+pub fn tag_exists(repo: &Repository, tag: &str) -> bool {
+    repo.find_reference(&format!("refs/tags/{tag}")).is_ok()
+}
+
+fn format_signature(sig: &git2::Signature) -> String {
+    let when = sig.when();
+    let offset = when.offset_minutes();
+    format!(
+        "{} <{}> {} {}{:02}{:02}",
+        sig.name().unwrap_or(""),
+        sig.email().unwrap_or(""),
+        when.seconds(),
+        if offset < 0 { '-' } else { '+' },
+        offset.abs() / 60,
+        offset.abs() % 60
+    )
+}
+
+/// Raw contents of an annotated tag object pointing at a commit
+fn tag_object(target: Oid, tag: &str, tagger: &git2::Signature, message: &str) -> String {
+    let mut object = format!(
+        "object {target}\ntype commit\ntag {tag}\ntagger {}\n\n{}",
+        format_signature(tagger),
+        message.trim_end()
+    );
+    object.push('\n');
+    object
+}
+
+/// Detached, armored signature of `payload` using git's configured GPG program and key
+fn gpg_sign(repo: &Repository, payload: &str) -> Result<String> {
+    let config = repo.config()?;
+    let program = config
+        .get_string("gpg.program")
+        .unwrap_or_else(|_| "gpg".to_string());
+
+    let mut cmd = Command::new(&program);
+    cmd.arg("--detach-sign").arg("--armor");
+    if let Ok(key) = config.get_string("user.signingkey") {
+        cmd.arg("--local-user").arg(key);
+    }
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {program}"))?;
+    child
+        .stdin
+        .take()
+        .context("Failed to open gpg stdin")?
+        .write_all(payload.as_bytes())?;
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!("{program} failed to sign the tag");
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Create an annotated tag at HEAD, GPG-signed if requested
+pub fn create_tag(repo: &Repository, tag: &str, message: &str, sign: bool) -> Result<Oid> {
+    let head = repo.head()?.peel_to_commit()?;
+    let tagger = repo
+        .signature()
+        .context("Set user.name and user.email to create tags")?;
+
+    if !sign {
+        return Ok(repo.tag(tag, head.as_object(), &tagger, message, false)?);
+    }
+
+    let mut object = tag_object(head.id(), tag, &tagger, message);
+    object.push_str(&gpg_sign(repo, &object)?);
+
+    let oid = repo.odb()?.write(ObjectType::Tag, object.as_bytes())?;
+    repo.reference(
+        &format!("refs/tags/{tag}"),
+        oid,
+        false,
+        &format!("ghdist: create tag {tag}"),
+    )?;
+    Ok(oid)
+}
+
+/// Push `refs/tags/{tag}` to `remote`, authenticating HTTPS remotes with the GitHub token
+pub fn push_tag(repo: &Repository, remote: &str, tag: &str, token: Option<&str>) -> Result<()> {
+    let mut remote = repo
+        .find_remote(remote)
+        .with_context(|| format!("Remote {remote} not found"))?;
+
+    let mut options = PushOptions::new();
+    options.remote_callbacks(remote_callbacks(token));
+
+    let refspec = format!("refs/tags/{tag}:refs/tags/{tag}");
+    remote
+        .push(&[refspec.as_str()], Some(&mut options))
+        .with_context(|| format!("Failed to push tag {tag}"))?;
+    Ok(())
+}
+
+/// Callbacks authenticating HTTPS remotes with the GitHub token and SSH remotes with the agent
+///
+/// Rejected reference updates are reported as errors.
+pub fn remote_callbacks(token: Option<&str>) -> RemoteCallbacks<'_> {
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |_url, username, allowed| {
+        attempts += 1;
+        if attempts > 3 {
+            return Err(git2::Error::from_str("authentication failed"));
+        }
+
+        match token {
+            Some(token) if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) => {
+                Cred::userpass_plaintext("x-access-token", token)
+            }
+            _ if allowed.contains(CredentialType::SSH_KEY) => {
+                Cred::ssh_key_from_agent(username.unwrap_or("git"))
+            }
+            _ => Cred::default(),
+        }
+    });
+    callbacks.push_update_reference(|refname, status| match status {
+        Some(message) => Err(git2::Error::from_str(&format!(
+            "{refname} was rejected: {message}"
+        ))),
+        None => Ok(()),
+    });
+    callbacks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    fn init_repo(path: &Path) -> Repository {
+        let repo = Repository::init(path).unwrap();
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Test User").unwrap();
+            config.set_str("user.email", "test@example.com").unwrap();
+
+            let sig = repo.signature().unwrap();
+            let tree = repo
+                .find_tree(repo.index().unwrap().write_tree().unwrap())
+                .unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+                .unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn test_render_message() {
+        assert_eq!(
+            render_message(DEFAULT_TAG_MESSAGE, "v1.2.3"),
+            "Release v1.2.3"
+        );
+        assert_eq!(
+            render_message("Version {version} ({tag})", "v1.2.3"),
+            "Version 1.2.3 (v1.2.3)"
+        );
+    }
+
+    #[test]
+    fn test_verify_tag_version() {
+        assert!(verify_tag_version("v1.2.3", "v", "1.2.3").is_ok());
+        assert!(verify_tag_version("1.2.3", "v", "1.2.3").is_ok());
+        assert!(verify_tag_version("release-1.2.3", "release-", "1.2.3").is_ok());
+        assert!(verify_tag_version("main", "v", "1.2.3").is_ok());
+        assert!(verify_tag_version("abcdef0", "v", "1.2.3").is_ok());
+
+        let error = verify_tag_version("v1.3.0", "v", "1.2.0").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Tag v1.3.0 does not match the version in Cargo.toml:\n  tag:        1.3.0\n  Cargo.toml: 1.2.0\nUpdate Cargo.toml (e.g. `cargo ghdist bump`) or pass --no-verify-version"
+        );
+        assert!(verify_tag_version("v2.0.0-rc.1", "v", "2.0.0").is_err());
+    }
+
+    #[test]
+    fn test_tag_object_format() {
+        let tagger = git2::Signature::new(
+            "Jane",
+            "jane@example.com",
+            &git2::Time::new(1700000000, -330),
+        )
+        .unwrap();
+        let object = tag_object(Oid::zero(), "v1.0.0", &tagger, "Release v1.0.0\n\n");
+        assert_eq!(
+            object,
+            format!(
+                "object {}\ntype commit\ntag v1.0.0\ntagger Jane <jane@example.com> 1700000000 -0530\n\nRelease v1.0.0\n",
+                Oid::zero()
+            )
+        );
+    }
+
+    #[test]
+    fn test_create_and_push_tag() {
+        let temp_dir = tempdir().unwrap();
+        let repo = init_repo(&temp_dir.path().join("work"));
+        let remote_path = temp_dir.path().join("remote.git");
+        let remote_repo = Repository::init_bare(&remote_path).unwrap();
+        repo.remote("origin", remote_path.to_str().unwrap())
+            .unwrap();
+
+        assert!(!tag_exists(&repo, "v0.1.0"));
+        create_tag(&repo, "v0.1.0", "Release v0.1.0", false).unwrap();
+        assert!(tag_exists(&repo, "v0.1.0"));
+
+        let tag = repo
+            .find_reference("refs/tags/v0.1.0")
+            .unwrap()
+            .peel(ObjectType::Tag)
+            .unwrap()
+            .into_tag()
+            .unwrap();
+        assert_eq!(tag.message(), Some("Release v0.1.0"));
+
+        push_tag(&repo, "origin", "v0.1.0", None).unwrap();
+        assert!(tag_exists(&remote_repo, "v0.1.0"));
+
+        assert!(push_tag(&repo, "upstream", "v0.1.0", None).is_err());
+    }
+}
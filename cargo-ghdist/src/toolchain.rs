@@ -0,0 +1,225 @@
+use anyhow::{Context, Result};
+use std::process::Command;
+
+use crate::config::Config;
+use crate::ui;
+
+/// Whether a `rustup toolchain list` entry is the requested toolchain
+///
+/// Installed toolchains carry the host triple, e.g. "1.78.0-x86_64-unknown-linux-gnu",
+/// while config values usually leave it off.
+pub fn toolchain_matches(requested: &str, installed: &str, host: &str) -> bool {
+    let installed = installed.split_whitespace().next().unwrap_or(installed);
+    installed == requested || installed.strip_suffix(&format!("-{host}")) == Some(requested)
+}
+
+fn rustup(args: &[&str]) -> Result<String> {
+    let output = Command::new("rustup")
+        .args(args)
+        .output()
+        .context("Failed to run rustup")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "rustup {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+fn rustup_install(args: &[&str]) -> Result<()> {
+    tracing::info!("Running rustup {}", args.join(" "));
+    let status = Command::new("rustup")
+        .args(args)
+        .stdout(ui::child_stdout())
+        .status()
+        .context("Failed to run rustup")?;
+    if !status.success() {
+        anyhow::bail!("rustup {} failed", args.join(" "));
+    }
+    Ok(())
+}
+
+fn toolchain_args<'a>(args: &[&'a str], toolchain: Option<&'a str>) -> Vec<&'a str> {
+    let mut args = args.to_vec();
+    if let Some(toolchain) = toolchain {
+        args.extend(["--toolchain", toolchain]);
+    }
+    args
+}
+
+/// What a release build needs from rustup for one target
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    pub target: String,
+    /// `None` uses the active toolchain
+    pub toolchain: Option<String>,
+    /// build-std targets need rust-src instead of a prebuilt rust-std
+    pub build_std: bool,
+}
+
+/// Requirements of the targets built on this machine
+///
+/// Container and remote targets don't need a local toolchain.
+pub fn requirements(config: &Config, targets: Vec<String>) -> Vec<Requirement> {
+    targets
+        .into_iter()
+        .filter(|target| {
+            config.container_image_for(target).is_none()
+                && !config.remote_build.targets.contains(target)
+        })
+        .map(|target| Requirement {
+            toolchain: config.toolchain_for(&target).map(String::from),
+            build_std: config
+                .target
+                .get(&target)
+                .is_some_and(|t| !t.build_std.is_empty()),
+            target,
+        })
+        .collect()
+}
+
+/// Whether rustup can be run at all
+pub fn rustup_available() -> bool {
+    Command::new("rustup").arg("--version").output().is_ok()
+}
+
+/// Make sure every toolchain, target and component needed for the build is installed
+///
+/// Missing pieces are installed when `install` is set, otherwise the release stops
+/// before anything is built. The check is skipped when rustup is not available.
+pub fn ensure_installed(requirements: &[Requirement], install: bool) -> Result<()> {
+    if !rustup_available() {
+        tracing::warn!("rustup not found, skipping the toolchain check");
+        return Ok(());
+    }
+
+    let host = crate::gates::host_triple()?;
+    let installed = rustup(&["toolchain", "list"])?;
+    let mut checked_toolchains = Vec::new();
+
+    for requirement in requirements {
+        let toolchain = requirement.toolchain.as_deref();
+
+        if let Some(toolchain) = toolchain {
+            let present = checked_toolchains.iter().any(|t| t == toolchain)
+                || installed
+                    .lines()
+                    .any(|line| toolchain_matches(toolchain, line, &host));
+            if !present {
+                if !install {
+                    anyhow::bail!(
+                        "Toolchain {toolchain} is not installed. Run `rustup toolchain install {toolchain}` \
+                         or set install_toolchains = true under [preflight]"
+                    );
+                }
+                rustup_install(&["toolchain", "install", toolchain, "--profile", "minimal"])?;
+            }
+            checked_toolchains.push(toolchain.to_string());
+        }
+
+        if requirement.build_std {
+            let components = rustup(&toolchain_args(
+                &["component", "list", "--installed"],
+                toolchain,
+            ))?;
+            if !components.lines().any(|line| line.starts_with("rust-src")) {
+                if !install {
+                    anyhow::bail!(
+                        "{} uses build-std, which needs the rust-src component. \
+                         Run `rustup component add rust-src{}`",
+                        requirement.target,
+                        toolchain
+                            .map(|t| format!(" --toolchain {t}"))
+                            .unwrap_or_default()
+                    );
+                }
+                rustup_install(&toolchain_args(
+                    &["component", "add", "rust-src"],
+                    toolchain,
+                ))?;
+            }
+            continue;
+        }
+
+        let targets = rustup(&toolchain_args(
+            &["target", "list", "--installed"],
+            toolchain,
+        ))?;
+        if !targets
+            .lines()
+            .any(|line| line.trim() == requirement.target)
+        {
+            if !install {
+                anyhow::bail!(
+                    "Target {} is not installed. Run `rustup target add {}{}` \
+                     or set install_toolchains = true under [preflight]",
+                    requirement.target,
+                    requirement.target,
+                    toolchain
+                        .map(|t| format!(" --toolchain {t}"))
+                        .unwrap_or_default()
+                );
+            }
+            rustup_install(&toolchain_args(
+                &["target", "add", &requirement.target],
+                toolchain,
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toolchain_matches() {
+        let host = "x86_64-unknown-linux-gnu";
+        assert!(toolchain_matches(
+            "stable",
+            "stable-x86_64-unknown-linux-gnu (default)",
+            host
+        ));
+        assert!(toolchain_matches(
+            "1.78.0",
+            "1.78.0-x86_64-unknown-linux-gnu",
+            host
+        ));
+        assert!(toolchain_matches(
+            "nightly-2025-01-01",
+            "nightly-2025-01-01-x86_64-unknown-linux-gnu",
+            host
+        ));
+        assert!(toolchain_matches(
+            "1.78.0-x86_64-unknown-linux-gnu",
+            "1.78.0-x86_64-unknown-linux-gnu",
+            host
+        ));
+        assert!(!toolchain_matches(
+            "nightly",
+            "nightly-2025-01-01-x86_64-unknown-linux-gnu",
+            host
+        ));
+        assert!(!toolchain_matches(
+            "1.78",
+            "1.78.0-x86_64-unknown-linux-gnu",
+            host
+        ));
+    }
+
+    #[test]
+    fn test_toolchain_args() {
+        assert_eq!(
+            toolchain_args(&["target", "list"], None),
+            vec!["target", "list"]
+        );
+        assert_eq!(
+            toolchain_args(&["target", "list"], Some("1.78.0")),
+            vec!["target", "list", "--toolchain", "1.78.0"]
+        );
+    }
+}
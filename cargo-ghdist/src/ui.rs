@@ -0,0 +1,139 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::cli::{Args, LogFormat};
+
+/// Whether stdout is reserved for the `--output json` result
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+
+/// Keep stdout for the `--output json` result, so child processes write to
+/// stderr from now on
+pub fn reserve_stdout() {
+    STDOUT_RESERVED.store(true, Ordering::Relaxed);
+}
+
+/// Standard output for a child process: stderr while stdout is reserved for
+/// the result, otherwise inherited
+pub fn child_stdout() -> Stdio {
+    if STDOUT_RESERVED.load(Ordering::Relaxed) {
+        std::io::stderr().into()
+    } else {
+        Stdio::inherit()
+    }
+}
+
+/// How a release reports its steps on stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiMode {
+    /// Step headers, with a spinner on a terminal
+    Steps,
+    /// Nothing; `--quiet`
+    Quiet,
+    /// Nothing, because log lines already say it (`--verbose`, `--log-format json`)
+    Log,
+}
+
+impl UiMode {
+    pub fn from_args(args: &Args) -> Self {
+        if args.quiet {
+            UiMode::Quiet
+        } else if args.verbose || args.log_format == LogFormat::Json {
+            UiMode::Log
+        } else {
+            UiMode::Steps
+        }
+    }
+}
+
+/// Concise step headers for people watching a release
+pub struct Ui {
+    mode: UiMode,
+    interactive: bool,
+    /// Spinner of the step in progress, on a terminal
+    current: Mutex<Option<(ProgressBar, String)>>,
+}
+
+impl Ui {
+    pub fn new(mode: UiMode) -> Self {
+        Self {
+            mode,
+            interactive: std::io::stderr().is_terminal(),
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Start a step, finishing the one before
+    pub fn step(&self, message: impl Into<String>) {
+        if self.mode != UiMode::Steps {
+            return;
+        }
+        let message = message.into();
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((bar, previous)) = current.take() {
+            bar.finish_with_message(format!("✓ {previous}"));
+        }
+        if self.interactive {
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(
+                ProgressStyle::with_template("{spinner} {msg} {elapsed:.dim}")
+                    .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+            );
+            bar.set_message(message.clone());
+            bar.enable_steady_tick(Duration::from_millis(100));
+            *current = Some((bar, message));
+        } else {
+            eprintln!("==> {message}");
+        }
+    }
+
+    /// Mark the step in progress as failed when the run goes on anyway
+    pub fn fail(&self) {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((bar, message)) = current.take() {
+            bar.abandon_with_message(format!("✗ {message}"));
+        }
+    }
+
+    /// Finish the step in progress
+    pub fn finish(&self) {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((bar, message)) = current.take() {
+            bar.finish_with_message(format!("✓ {message}"));
+        }
+    }
+}
+
+impl Drop for Ui {
+    /// A step left open ended in an error, which is reported on its own
+    fn drop(&mut self) {
+        let current = self.current.get_mut().unwrap_or_else(|e| e.into_inner());
+        if let Some((bar, message)) = current.take() {
+            bar.abandon_with_message(format!("✗ {message}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::GhdistCli;
+    use clap::Parser;
+
+    fn mode(args: &[&str]) -> UiMode {
+        let cli = GhdistCli::try_parse_from(args).unwrap();
+        UiMode::from_args(&cli.into())
+    }
+
+    #[test]
+    fn test_ui_mode() {
+        assert_eq!(mode(&["cargo-ghdist"]), UiMode::Steps);
+        assert_eq!(mode(&["cargo-ghdist", "-q"]), UiMode::Quiet);
+        assert_eq!(mode(&["cargo-ghdist", "--verbose"]), UiMode::Log);
+        assert_eq!(mode(&["cargo-ghdist", "--log-format", "json"]), UiMode::Log);
+        assert!(GhdistCli::try_parse_from(["cargo-ghdist", "-q", "--verbose"]).is_err());
+    }
+}
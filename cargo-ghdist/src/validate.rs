@@ -0,0 +1,88 @@
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+
+pub use cargo_ghinstall::validate::{check_target, explain, parse, suggest};
+
+/// Check that `value` is one of `allowed`, suggesting the closest one otherwise
+pub fn check_one_of(what: &str, value: &str, allowed: &[&str]) -> Result<(), String> {
+    if allowed.contains(&value) {
+        return Ok(());
+    }
+    let mut message = format!(
+        "unsupported {what} `{value}`, expected one of {}",
+        allowed
+            .iter()
+            .map(|value| format!("`{value}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if let Some(candidate) = suggest(value, allowed.iter().copied()) {
+        message.push_str(&format!(", did you mean `{candidate}`?"));
+    }
+    Err(message)
+}
+
+/// Deserialize a list of target triples, rejecting unknown ones
+pub fn targets<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let targets = Vec::<String>::deserialize(deserializer)?;
+    for target in &targets {
+        check_target(target).map_err(D::Error::custom)?;
+    }
+    Ok(targets)
+}
+
+/// Deserialize a table keyed by target triple, rejecting unknown triples
+pub fn target_table<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+    deserializer: D,
+) -> Result<HashMap<String, T>, D::Error> {
+    let table = HashMap::<String, T>::deserialize(deserializer)?;
+    for target in table.keys() {
+        check_target(target).map_err(D::Error::custom)?;
+    }
+    Ok(table)
+}
+
+/// Deserialize an archive format: "tgz" or "zip"
+pub fn archive_format<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let format = String::deserialize(deserializer)?;
+    check_one_of("archive format", &format, &["tgz", "zip"]).map_err(D::Error::custom)?;
+    Ok(format)
+}
+
+/// Deserialize a list of package formats
+pub fn package_formats<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    let formats = Vec::<String>::deserialize(deserializer)?;
+    for format in &formats {
+        check_one_of("package format", format, &["deb", "rpm", "msi", "nsis"])
+            .map_err(D::Error::custom)?;
+    }
+    Ok(formats)
+}
+
+/// Deserialize a make_latest setting: "true", "false" or "legacy"
+pub fn latest_mode<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let latest = Option::<String>::deserialize(deserializer)?;
+    if let Some(latest) = &latest {
+        check_one_of("latest setting", latest, &["true", "false", "legacy"])
+            .map_err(D::Error::custom)?;
+    }
+    Ok(latest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_one_of() {
+        assert!(check_one_of("archive format", "zip", &["tgz", "zip"]).is_ok());
+        let error = check_one_of("archive format", "zpi", &["tgz", "zip"]).unwrap_err();
+        assert_eq!(
+            error,
+            "unsupported archive format `zpi`, expected one of `tgz`, `zip`, did you mean `zip`?"
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+use crate::cli::Args;
+use crate::config::Config;
+use crate::github::GitHubClient;
+use crate::incremental::{parse_checksums, sha256_hex};
+use crate::manifest::{ReleaseManifest, MANIFEST_FILE_NAME};
+use crate::signing::SignatureStatus;
+
+const CHECKSUM_FILE_NAME: &str = "SHA256SUMS";
+
+/// Path and content of a file inside an archive
+type ArchiveEntry = (String, Vec<u8>);
+
+/// Entry names and contents of a `.tar.gz` or `.zip` asset, or `None` for other assets
+///
+/// Every entry is read to the end, so a truncated or corrupt archive fails here.
+pub fn read_archive(name: &str, bytes: &[u8]) -> Result<Option<Vec<ArchiveEntry>>> {
+    let mut entries = Vec::new();
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(bytes));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().into_owned();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            entries.push((path, content));
+        }
+    } else if name.ends_with(".zip") {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index)?;
+            let path = file.name().to_string();
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)?;
+            entries.push((path, content));
+        }
+    } else {
+        return Ok(None);
+    }
+    Ok(Some(entries))
+}
+
+/// Binaries listed for the archive's target in the manifest that the archive lacks
+pub fn missing_binaries(expected: &[String], entries: &[ArchiveEntry]) -> Vec<String> {
+    expected
+        .iter()
+        .filter(|binary| {
+            !entries.iter().any(|(path, _)| {
+                Path::new(path)
+                    .file_name()
+                    .is_some_and(|name| name == binary.as_str())
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// Downloads every asset of a published release and checks it against the release's own records
+pub struct Verify {
+    args: Args,
+    tag: String,
+    github_client: GitHubClient,
+}
+
+impl Verify {
+    pub fn new(mut args: Args, tag: &str) -> Result<Self> {
+        Config::load_for(&mut args)?;
+
+        let github_client = GitHubClient::from_args(&args)?;
+
+        Ok(Self {
+            args,
+            tag: tag.to_string(),
+            github_client,
+        })
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        let (owner, repo) = self.args.parse_repository()?;
+        let release = self
+            .github_client
+            .find_release(&owner, &repo, &self.tag)
+            .await?
+            .with_context(|| format!("No release found for tag {} in {owner}/{repo}", self.tag))?;
+        let assets = release
+            .assets
+            .iter()
+            .map(|asset| (asset.name.clone(), asset.id.0))
+            .collect::<HashMap<_, _>>();
+
+        let download = |name: &str| self.download(&owner, &repo, assets.get(name).copied());
+
+        let checksums = download(CHECKSUM_FILE_NAME)
+            .await?
+            .with_context(|| format!("Release {} has no {CHECKSUM_FILE_NAME}", self.tag))?;
+        let checksums = parse_checksums(&String::from_utf8_lossy(&checksums));
+        let manifest = match download(MANIFEST_FILE_NAME).await? {
+            Some(bytes) => Some(ReleaseManifest::from_json(&bytes)?),
+            None => {
+                println!("⚠️  {MANIFEST_FILE_NAME} not found, binaries can't be checked");
+                None
+            }
+        };
+
+        let work_dir = tempfile::tempdir()?;
+        let mut failures = 0;
+        let mut names = assets.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        for name in names.iter().filter(|name| *name != CHECKSUM_FILE_NAME) {
+            let bytes = download(name).await?.unwrap_or_default();
+            match self.verify_asset(name, &bytes, &checksums, manifest.as_ref(), work_dir.path()) {
+                Ok(detail) => println!("✅ {name}: {detail}"),
+                Err(e) => {
+                    failures += 1;
+                    println!("❌ {name}: {e:#}");
+                }
+            }
+        }
+
+        let mut missing = checksums
+            .keys()
+            .filter(|name| !assets.contains_key(*name))
+            .collect::<Vec<_>>();
+        missing.sort();
+        for name in missing {
+            failures += 1;
+            println!("❌ {name}: listed in {CHECKSUM_FILE_NAME} but not on the release");
+        }
+
+        if failures > 0 {
+            anyhow::bail!("{failures} problem(s) found in release {}", self.tag);
+        }
+        println!("\n🔒 Release {} verified", self.tag);
+        Ok(())
+    }
+
+    /// Download an asset by ID; `None` if the release doesn't have it
+    async fn download(&self, owner: &str, repo: &str, id: Option<u64>) -> Result<Option<Vec<u8>>> {
+        match id {
+            Some(id) => Ok(Some(
+                self.github_client.download_asset(owner, repo, id).await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    fn verify_asset(
+        &self,
+        name: &str,
+        bytes: &[u8],
+        checksums: &HashMap<String, String>,
+        manifest: Option<&ReleaseManifest>,
+        work_dir: &Path,
+    ) -> Result<String> {
+        let expected = checksums
+            .get(name)
+            .with_context(|| format!("not listed in {CHECKSUM_FILE_NAME}"))?;
+        let actual = sha256_hex(bytes);
+        if &actual != expected {
+            anyhow::bail!("SHA-256 is {actual}, {CHECKSUM_FILE_NAME} says {expected}");
+        }
+
+        let Some(entries) = read_archive(name, bytes).context("archive can't be extracted")? else {
+            return Ok("checksum matches".to_string());
+        };
+        let target = manifest.and_then(|manifest| {
+            manifest
+                .targets
+                .iter()
+                .find(|target| target.assets.iter().any(|asset| asset == name))
+        });
+        let Some(target) = target else {
+            return Ok(format!(
+                "checksum matches, {} file(s) extracted",
+                entries.len()
+            ));
+        };
+
+        let expected_binaries = target
+            .binaries
+            .iter()
+            .map(|binary| binary.name.clone())
+            .collect::<Vec<_>>();
+        let missing = missing_binaries(&expected_binaries, &entries);
+        if !missing.is_empty() {
+            anyhow::bail!("archive is missing {}", missing.join(", "));
+        }
+
+        let mut detail = format!(
+            "checksum matches, contains {}",
+            expected_binaries.join(", ")
+        );
+        if let SignatureStatus::Signed { .. } = target.signature {
+            detail.push_str(&Self::verify_signatures(&entries, work_dir)?);
+        }
+        Ok(detail)
+    }
+
+    /// Check the Authenticode signatures of the `.exe` files in a signed target's archive
+    fn verify_signatures(entries: &[ArchiveEntry], work_dir: &Path) -> Result<String> {
+        let available = Command::new("osslsigncode")
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if !available {
+            return Ok(", signature not checked (osslsigncode not found)".to_string());
+        }
+
+        for (path, content) in entries.iter().filter(|(path, _)| path.ends_with(".exe")) {
+            let file_name = Path::new(path)
+                .file_name()
+                .context("Invalid archive entry")?;
+            let exe = work_dir.join(file_name);
+            std::fs::write(&exe, content)?;
+            let status = Command::new("osslsigncode")
+                .args(["verify", "-in"])
+                .arg(&exe)
+                .output()?
+                .status;
+            if !status.success() {
+                anyhow::bail!("Authenticode signature of {path} doesn't verify");
+            }
+        }
+        Ok(", signature verified".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::ArchiveFormat;
+    use crate::packager;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_archive() {
+        let dir = tempdir().unwrap();
+        let binary = dir.path().join("tool");
+        std::fs::write(&binary, b"binary").unwrap();
+
+        for format in [ArchiveFormat::Tgz, ArchiveFormat::Zip] {
+            let archive =
+                packager::create_archive(&[binary.clone()], dir.path(), "tool-linux", format)
+                    .unwrap();
+            let name = archive.file_name().unwrap().to_str().unwrap();
+            let bytes = std::fs::read(&archive).unwrap();
+
+            let entries = read_archive(name, &bytes).unwrap().unwrap();
+            assert_eq!(entries, vec![("tool".to_string(), b"binary".to_vec())]);
+            assert!(read_archive(name, &bytes[..bytes.len() / 2]).is_err());
+        }
+
+        assert!(read_archive("SHA256SUMS", b"").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_missing_binaries() {
+        let entries = vec![("dist/tool".to_string(), Vec::new())];
+        let expected = vec!["tool".to_string(), "tool-helper".to_string()];
+        assert_eq!(missing_binaries(&expected, &entries), vec!["tool-helper"]);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::config::WasmConfig;
+use crate::ui;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Arguments for wasm-opt when `opt_args` is not set: optimize for size
+const DEFAULT_OPT_ARGS: &[&str] = &["-Oz"];
+
+/// Whether `target` builds WebAssembly modules, e.g. wasm32-wasip1
+pub fn is_wasm_target(target: &str) -> bool {
+    target.starts_with("wasm32") || target.starts_with("wasm64")
+}
+
+/// Whether a build output is a WebAssembly module
+pub fn is_wasm_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("wasm"))
+}
+
+/// Run wasm-opt over the modules in place, when `[wasm] optimize` is set
+pub fn optimize(config: &WasmConfig, artifacts: &[PathBuf]) -> Result<()> {
+    if !config.optimize {
+        return Ok(());
+    }
+    for module in artifacts.iter().filter(|path| is_wasm_file(path)) {
+        tracing::info!("Optimizing {} with wasm-opt", module.display());
+        let status = Command::new("wasm-opt")
+            .args(opt_args(config))
+            .arg(module)
+            .arg("-o")
+            .arg(module)
+            .stdout(ui::child_stdout())
+            .status()
+            .context("Failed to run wasm-opt; install binaryen or unset [wasm] optimize")?;
+        if !status.success() {
+            anyhow::bail!("wasm-opt failed for {}", module.display());
+        }
+    }
+    Ok(())
+}
+
+fn opt_args(config: &WasmConfig) -> Vec<String> {
+    config
+        .opt_args
+        .clone()
+        .unwrap_or_else(|| DEFAULT_OPT_ARGS.iter().map(|a| a.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_wasm_target() {
+        assert!(is_wasm_target("wasm32-wasip1"));
+        assert!(is_wasm_target("wasm32-unknown-unknown"));
+        assert!(!is_wasm_target("x86_64-unknown-linux-gnu"));
+    }
+
+    #[test]
+    fn test_is_wasm_file() {
+        assert!(is_wasm_file(Path::new(
+            "target/wasm32-wasip1/release/tool.wasm"
+        )));
+        assert!(!is_wasm_file(Path::new("target/release/tool")));
+        assert!(!is_wasm_file(Path::new("target/release/tool.d")));
+    }
+
+    #[test]
+    fn test_opt_args() {
+        assert_eq!(opt_args(&WasmConfig::default()), vec!["-Oz"]);
+        let config = WasmConfig {
+            optimize: true,
+            opt_args: Some(vec!["-O3".to_string(), "--strip-debug".to_string()]),
+        };
+        assert_eq!(opt_args(&config), vec!["-O3", "--strip-debug"]);
+    }
+
+    #[test]
+    fn test_optimize_disabled_leaves_modules_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let module = dir.path().join("tool.wasm");
+        std::fs::write(&module, b"\0asm").unwrap();
+        optimize(&WasmConfig::default(), &[module.clone()]).unwrap();
+        assert_eq!(std::fs::read(&module).unwrap(), b"\0asm");
+    }
+}
@@ -0,0 +1,392 @@
+use crate::config::WindowsInstallerConfig;
+use crate::error::GhDistError;
+use crate::packager::PackageMetadata;
+use crate::ui;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Map a Rust Windows target to the architecture name used by WiX
+pub fn wix_arch(target: &str) -> Option<&'static str> {
+    if !target.contains("windows") {
+        return None;
+    }
+
+    match target.split('-').next().unwrap_or("") {
+        "x86_64" => Some("x64"),
+        "i686" | "i586" => Some("x86"),
+        "aarch64" => Some("arm64"),
+        _ => None,
+    }
+}
+
+/// MSI versions must be numeric `major.minor.build`; drop pre-release and build metadata
+pub fn msi_version(version: &str) -> String {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let core = version.split(['-', '+']).next().unwrap_or(version);
+    let mut parts = core
+        .split('.')
+        .map(|part| part.parse::<u32>().unwrap_or(0))
+        .collect::<Vec<_>>();
+    parts.resize(3, 0);
+    format!("{}.{}.{}", parts[0], parts[1], parts[2])
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Quote-safe text for an NSIS string: `$` starts a variable and `$\"` is
+/// an escaped quote
+fn nsis_escape(value: &str) -> String {
+    value
+        .replace('$', "$$")
+        .replace('"', "$\\\"")
+        .replace('\r', "$\\r")
+        .replace('\n', "$\\n")
+}
+
+fn product_name(metadata: &PackageMetadata, config: &WindowsInstallerConfig) -> String {
+    config
+        .product_name
+        .clone()
+        .unwrap_or_else(|| metadata.name.clone())
+}
+
+fn manufacturer(metadata: &PackageMetadata, config: &WindowsInstallerConfig) -> String {
+    config
+        .manufacturer
+        .clone()
+        .or_else(|| metadata.authors.first().cloned())
+        .unwrap_or_else(|| product_name(metadata, config))
+}
+
+/// Render a WiX v4 source file that installs the binaries and adds them to PATH
+fn render_wxs(
+    binaries: &[PathBuf],
+    metadata: &PackageMetadata,
+    target: &str,
+    config: &WindowsInstallerConfig,
+) -> Result<String> {
+    let upgrade_guid = config.upgrade_guid.as_deref().ok_or_else(|| {
+        GhDistError::Config(
+            "windows_installer.upgrade_guid is required to build an MSI installer".to_string(),
+        )
+    })?;
+    let product = xml_escape(&product_name(metadata, config));
+    let program_files = if wix_arch(target) == Some("x86") {
+        "ProgramFilesFolder"
+    } else {
+        "ProgramFiles64Folder"
+    };
+
+    let files = binaries
+        .iter()
+        .map(|binary| {
+            format!(
+                "          <File Source=\"{}\" />\n",
+                xml_escape(&binary.display().to_string())
+            )
+        })
+        .collect::<String>();
+
+    let icon = config
+        .icon
+        .as_ref()
+        .map(|icon| {
+            format!(
+                "    <Icon Id=\"ProductIcon\" SourceFile=\"{}\" />\n    <Property Id=\"ARPPRODUCTICON\" Value=\"ProductIcon\" />\n",
+                xml_escape(&icon.display().to_string())
+            )
+        })
+        .unwrap_or_default();
+
+    Ok(format!(
+        r#"<Wix xmlns="http://wixtoolset.org/schemas/v4/wxs">
+  <Package Name="{product}" Manufacturer="{manufacturer}" Version="{version}" UpgradeCode="{upgrade_guid}" Scope="perMachine">
+    <MajorUpgrade DowngradeErrorMessage="A newer version of [ProductName] is already installed." />
+    <MediaTemplate EmbedCab="yes" />
+{icon}    <StandardDirectory Id="{program_files}">
+      <Directory Id="INSTALLFOLDER" Name="{product}">
+        <Component Id="Binaries" Guid="{component_guid}">
+{files}          <Environment Id="PATH" Name="PATH" Value="[INSTALLFOLDER]" Permanent="no" Part="last" Action="set" System="yes" />
+        </Component>
+      </Directory>
+    </StandardDirectory>
+    <Feature Id="Main">
+      <ComponentRef Id="Binaries" />
+    </Feature>
+  </Package>
+</Wix>
+"#,
+        manufacturer = xml_escape(&manufacturer(metadata, config)),
+        version = msi_version(&metadata.version),
+        component_guid = config.path_guid.as_deref().unwrap_or("*"),
+    ))
+}
+
+/// Render an NSIS script that installs the binaries and registers them on PATH
+///
+/// PATH is edited with the EnVar plugin, which reads and writes the registry
+/// value without NSIS's 1024-character string limit, keeps it REG_EXPAND_SZ
+/// and only adds the directory when it isn't there yet
+fn render_nsi(
+    binaries: &[PathBuf],
+    metadata: &PackageMetadata,
+    target: &str,
+    config: &WindowsInstallerConfig,
+    out_file: &Path,
+) -> String {
+    let product = nsis_escape(&product_name(metadata, config));
+    let program_files = if wix_arch(target) == Some("x86") {
+        "$PROGRAMFILES32"
+    } else {
+        "$PROGRAMFILES64"
+    };
+
+    let mut install_files = String::new();
+    let mut delete_files = String::new();
+    for binary in binaries {
+        install_files.push_str(&format!(
+            "  File \"{}\"\n",
+            nsis_escape(&binary.display().to_string())
+        ));
+        if let Some(name) = binary.file_name().and_then(|n| n.to_str()) {
+            let name = nsis_escape(name);
+            delete_files.push_str(&format!("  Delete \"$INSTDIR\\{name}\"\n"));
+        }
+    }
+
+    let icon = config
+        .icon
+        .as_ref()
+        .map(|icon| {
+            format!(
+                "!define MUI_ICON \"{}\"\n",
+                nsis_escape(&icon.display().to_string())
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"Unicode true
+!include "MUI2.nsh"
+
+!define PRODUCT "{product}"
+!define UNINSTALL_KEY "Software\Microsoft\Windows\CurrentVersion\Uninstall\${{PRODUCT}}"
+{icon}
+Name "${{PRODUCT}} {version}"
+OutFile "{out_file}"
+InstallDir "{program_files}\${{PRODUCT}}"
+RequestExecutionLevel admin
+
+!insertmacro MUI_PAGE_DIRECTORY
+!insertmacro MUI_PAGE_INSTFILES
+!insertmacro MUI_UNPAGE_INSTFILES
+!insertmacro MUI_LANGUAGE "English"
+
+Section "Install"
+  SetOutPath "$INSTDIR"
+{install_files}  WriteUninstaller "$INSTDIR\uninstall.exe"
+  WriteRegStr HKLM "${{UNINSTALL_KEY}}" "DisplayName" "${{PRODUCT}}"
+  WriteRegStr HKLM "${{UNINSTALL_KEY}}" "DisplayVersion" "{version}"
+  WriteRegStr HKLM "${{UNINSTALL_KEY}}" "Publisher" "{manufacturer}"
+  WriteRegStr HKLM "${{UNINSTALL_KEY}}" "UninstallString" "$INSTDIR\uninstall.exe"
+
+  EnVar::SetHKLM
+  EnVar::AddValueEx "Path" "$INSTDIR"
+  Pop $0
+  SendMessage ${{HWND_BROADCAST}} ${{WM_WININICHANGE}} 0 "STR:Environment" /TIMEOUT=5000
+SectionEnd
+
+Section "Uninstall"
+  EnVar::SetHKLM
+  EnVar::DeleteValue "Path" "$INSTDIR"
+  Pop $0
+  SendMessage ${{HWND_BROADCAST}} ${{WM_WININICHANGE}} 0 "STR:Environment" /TIMEOUT=5000
+
+{delete_files}  Delete "$INSTDIR\uninstall.exe"
+  RMDir "$INSTDIR"
+  DeleteRegKey HKLM "${{UNINSTALL_KEY}}"
+SectionEnd
+"#,
+        version = nsis_escape(&metadata.version),
+        manufacturer = nsis_escape(&manufacturer(metadata, config)),
+        out_file = nsis_escape(&out_file.display().to_string()),
+    )
+}
+
+/// Build an MSI installer with the WiX toolset (`wix build`)
+pub fn create_msi(
+    binaries: &[PathBuf],
+    output_dir: &Path,
+    metadata: &PackageMetadata,
+    target: &str,
+    config: &WindowsInstallerConfig,
+) -> Result<PathBuf> {
+    let arch = wix_arch(target).ok_or_else(|| {
+        GhDistError::Package(format!("No Windows architecture known for target {target}"))
+    })?;
+
+    let stem = format!("{}-{}-{target}", metadata.name, metadata.version);
+    let wxs_path = output_dir.join(format!("{stem}.wxs"));
+    let msi_path = output_dir.join(format!("{stem}.msi"));
+    fs::write(&wxs_path, render_wxs(binaries, metadata, target, config)?)?;
+
+    let status = Command::new("wix")
+        .arg("build")
+        .arg("-arch")
+        .arg(arch)
+        .arg("-o")
+        .arg(&msi_path)
+        .arg(&wxs_path)
+        .stdout(ui::child_stdout())
+        .status()
+        .context(
+            "Failed to run `wix`. Install the WiX toolset (dotnet tool install --global wix)",
+        )?;
+
+    if !status.success() {
+        return Err(GhDistError::Package(format!("wix build failed for {target}")).into());
+    }
+
+    tracing::info!("Created MSI installer: {}", msi_path.display());
+    Ok(msi_path)
+}
+
+/// Build an NSIS setup executable with `makensis`
+pub fn create_nsis(
+    binaries: &[PathBuf],
+    output_dir: &Path,
+    metadata: &PackageMetadata,
+    target: &str,
+    config: &WindowsInstallerConfig,
+) -> Result<PathBuf> {
+    if wix_arch(target).is_none() {
+        return Err(GhDistError::Package(format!(
+            "No Windows architecture known for target {target}"
+        ))
+        .into());
+    }
+
+    let stem = format!("{}-{}-{target}", metadata.name, metadata.version);
+    let nsi_path = output_dir.join(format!("{stem}.nsi"));
+    let exe_path = output_dir.join(format!("{stem}-setup.exe"));
+    fs::write(
+        &nsi_path,
+        render_nsi(binaries, metadata, target, config, &exe_path),
+    )?;
+
+    let status = Command::new("makensis")
+        .arg("-V2")
+        .arg(&nsi_path)
+        .stdout(ui::child_stdout())
+        .status()
+        .context(
+            "Failed to run `makensis`. Install NSIS and its EnVar plugin to build .exe installers",
+        )?;
+
+    if !status.success() {
+        return Err(GhDistError::Package(format!("makensis failed for {target}")).into());
+    }
+
+    tracing::info!("Created NSIS installer: {}", exe_path.display());
+    Ok(exe_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_metadata() -> PackageMetadata {
+        PackageMetadata {
+            name: "mytool".to_string(),
+            version: "1.2.3-beta.1".to_string(),
+            authors: vec!["Jane & Co".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_msi_version() {
+        assert_eq!(msi_version("1.2.3"), "1.2.3");
+        assert_eq!(msi_version("v1.2.3-rc.1"), "1.2.3");
+        assert_eq!(msi_version("2.0"), "2.0.0");
+    }
+
+    #[test]
+    fn test_wix_arch() {
+        assert_eq!(wix_arch("x86_64-pc-windows-msvc"), Some("x64"));
+        assert_eq!(wix_arch("i686-pc-windows-gnu"), Some("x86"));
+        assert_eq!(wix_arch("aarch64-pc-windows-msvc"), Some("arm64"));
+        assert_eq!(wix_arch("x86_64-unknown-linux-gnu"), None);
+    }
+
+    #[test]
+    fn test_render_wxs_requires_upgrade_guid() {
+        let result = render_wxs(
+            &[PathBuf::from("mytool.exe")],
+            &test_metadata(),
+            "x86_64-pc-windows-msvc",
+            &WindowsInstallerConfig::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_render_wxs() {
+        let config = WindowsInstallerConfig {
+            product_name: Some("My Tool".to_string()),
+            upgrade_guid: Some("6f0c1b5e-3c1f-4e4e-9c7e-3b0a1d2c4e5f".to_string()),
+            icon: Some(PathBuf::from("assets/icon.ico")),
+            ..Default::default()
+        };
+        let wxs = render_wxs(
+            &[PathBuf::from("target/mytool.exe")],
+            &test_metadata(),
+            "x86_64-pc-windows-msvc",
+            &config,
+        )
+        .unwrap();
+
+        assert!(wxs.contains(r#"Name="My Tool""#));
+        assert!(wxs.contains(r#"Manufacturer="Jane &amp; Co""#));
+        assert!(wxs.contains(r#"Version="1.2.3""#));
+        assert!(wxs.contains(r#"UpgradeCode="6f0c1b5e-3c1f-4e4e-9c7e-3b0a1d2c4e5f""#));
+        assert!(wxs.contains(r#"<File Source="target/mytool.exe" />"#));
+        assert!(wxs.contains(r#"<Environment Id="PATH""#));
+        assert!(wxs.contains(r#"SourceFile="assets/icon.ico""#));
+        assert!(wxs.contains("ProgramFiles64Folder"));
+    }
+
+    #[test]
+    fn test_render_nsi() {
+        let nsi = render_nsi(
+            &[PathBuf::from("target/mytool.exe")],
+            &test_metadata(),
+            "i686-pc-windows-msvc",
+            &WindowsInstallerConfig::default(),
+            Path::new("dist/mytool-setup.exe"),
+        );
+
+        assert!(nsi.contains(r#"!define PRODUCT "mytool""#));
+        assert!(nsi.contains(r#"OutFile "dist/mytool-setup.exe""#));
+        assert!(nsi.contains(r#"File "target/mytool.exe""#));
+        assert!(nsi.contains(r#"Delete "$INSTDIR\mytool.exe""#));
+        assert!(nsi.contains("$PROGRAMFILES32"));
+        assert!(nsi.contains(r#"EnVar::AddValueEx "Path" "$INSTDIR""#));
+        assert!(nsi.contains(r#"EnVar::DeleteValue "Path" "$INSTDIR""#));
+        assert!(!nsi.contains("ReadRegStr"));
+    }
+
+    #[test]
+    fn test_nsis_escape() {
+        assert_eq!(nsis_escape("My \"$Tool\""), "My $\\\"$$Tool$\\\"");
+        assert_eq!(nsis_escape("plain"), "plain");
+    }
+}
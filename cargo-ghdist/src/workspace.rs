@@ -0,0 +1,176 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    packages: Vec<WorkspacePackage>,
+    target_directory: PathBuf,
+}
+
+/// Workspace member as reported by `cargo metadata --no-deps`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WorkspacePackage {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub repository: Option<String>,
+    pub manifest_path: PathBuf,
+    /// `None` publishes anywhere, an empty list means `publish = false`
+    pub publish: Option<Vec<String>>,
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+    #[serde(default)]
+    pub targets: Vec<PackageTarget>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Dependency {
+    pub name: String,
+    /// `None` for normal dependencies, otherwise "dev" or "build"
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackageTarget {
+    pub name: String,
+    /// e.g. ["bin"], ["lib"] or ["proc-macro"]
+    pub kind: Vec<String>,
+}
+
+impl WorkspacePackage {
+    pub fn is_publishable(&self) -> bool {
+        match &self.publish {
+            None => true,
+            Some(registries) => registries.iter().any(|r| r == "crates-io"),
+        }
+    }
+
+    /// Names of the binaries this package builds
+    pub fn bin_names(&self) -> Vec<String> {
+        self.targets
+            .iter()
+            .filter(|t| t.kind.iter().any(|k| k == "bin"))
+            .map(|t| t.name.clone())
+            .collect()
+    }
+
+    /// Directory containing the package's Cargo.toml
+    pub fn dir(&self) -> &Path {
+        self.manifest_path.parent().unwrap_or(Path::new("."))
+    }
+}
+
+/// Members of the workspace in the current directory
+pub fn workspace_packages() -> Result<Vec<WorkspacePackage>> {
+    Ok(metadata()?.packages)
+}
+
+/// Cargo's target directory, which honours CARGO_TARGET_DIR and `build.target-dir`
+pub fn target_directory() -> Result<PathBuf> {
+    Ok(metadata()?.target_directory)
+}
+
+fn metadata() -> Result<Metadata> {
+    let output = Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .output()
+        .context("Failed to run cargo metadata")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "cargo metadata failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// Apply cargo-style `-p`, `--workspace` and `--exclude` selection to the workspace members
+///
+/// Returns an empty list when nothing is selected, leaving cargo's default members in effect.
+pub fn select_packages(
+    packages: &[WorkspacePackage],
+    names: &[String],
+    workspace: bool,
+    exclude: &[String],
+) -> Result<Vec<WorkspacePackage>> {
+    for name in names.iter().chain(exclude) {
+        if !packages.iter().any(|p| &p.name == name) {
+            let available = packages
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            anyhow::bail!("Package {name} is not in the workspace (found: {available})");
+        }
+    }
+
+    Ok(packages
+        .iter()
+        .filter(|p| (workspace && !exclude.contains(&p.name)) || names.contains(&p.name))
+        .cloned()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_publishable() {
+        let mut package = WorkspacePackage::default();
+        assert!(package.is_publishable());
+        package.publish = Some(vec!["my-registry".to_string()]);
+        assert!(!package.is_publishable());
+        package.publish = Some(vec!["crates-io".to_string()]);
+        assert!(package.is_publishable());
+        package.publish = Some(Vec::new());
+        assert!(!package.is_publishable());
+    }
+
+    #[test]
+    fn test_workspace_packages() {
+        let packages = workspace_packages().unwrap();
+        let ghdist = packages.iter().find(|p| p.name == "cargo-ghdist").unwrap();
+        assert!(ghdist.is_publishable());
+        assert!(ghdist.dependencies.iter().any(|d| d.name == "anyhow"));
+        assert_eq!(ghdist.bin_names(), vec!["cargo-ghdist"]);
+        assert!(ghdist.dir().ends_with("cargo-ghdist"));
+    }
+
+    #[test]
+    fn test_select_packages() {
+        let packages = ["cli", "core", "xtask"]
+            .iter()
+            .map(|name| WorkspacePackage {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let names = |selected: Vec<WorkspacePackage>| {
+            selected.into_iter().map(|p| p.name).collect::<Vec<_>>()
+        };
+        let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+        assert!(select_packages(&packages, &[], false, &[])
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            names(select_packages(&packages, &strings(&["core", "cli"]), false, &[]).unwrap()),
+            vec!["cli", "core"]
+        );
+        assert_eq!(
+            names(select_packages(&packages, &[], true, &strings(&["xtask"])).unwrap()),
+            vec!["cli", "core"]
+        );
+
+        let error = select_packages(&packages, &strings(&["missing"]), false, &[]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Package missing is not in the workspace (found: cli, core, xtask)"
+        );
+        assert!(select_packages(&packages, &[], true, &strings(&["missing"])).is_err());
+    }
+}
@@ -1,4 +1,9 @@
-use cargo_ghdist::cli::{ArchiveFormat, Args};
+use cargo_ghdist::cli::{
+    ArchiveFormat, Args, AuthAction, BumpLevel, CiProvider, Command, GhdistCli, LatestMode,
+    LogFormat, OutputFormat, Provider,
+};
+use clap::Parser;
+use std::path::PathBuf;
 
 #[test]
 fn test_targets_default() {
@@ -16,6 +21,42 @@ fn test_targets_default() {
         github_token: None,
         bins: None,
         profile: Some("release".to_string()),
+        packages: None,
+        changelog_file: None,
+        require_changelog: false,
+        latest: None,
+        discussion_category: None,
+        no_prune: false,
+        create_tag: false,
+        sign_tag: false,
+        allow_dirty: false,
+        allow_branch: false,
+        no_verify_version: false,
+        run_tests: false,
+        package: Vec::new(),
+        workspace: false,
+        exclude: Vec::new(),
+        registry_token: None,
+        no_verify: false,
+        features: None,
+        no_default_features: false,
+        all_features: false,
+        from_artifacts: None,
+        force: false,
+        resume: false,
+        no_upload: false,
+        no_progress: false,
+        provider: None,
+        app_id: None,
+        app_private_key: None,
+        app_installation_id: None,
+        profile_name: None,
+        prerelease: false,
+        target_dir: None,
+        output_dir: None,
+        switches: Default::default(),
+        quiet: false,
+        log_format: LogFormat::Text,
     };
 
     let targets = args.targets();
@@ -43,6 +84,42 @@ fn test_targets_override() {
         github_token: None,
         bins: None,
         profile: Some("release".to_string()),
+        packages: None,
+        changelog_file: None,
+        require_changelog: false,
+        latest: None,
+        discussion_category: None,
+        no_prune: false,
+        create_tag: false,
+        sign_tag: false,
+        allow_dirty: false,
+        allow_branch: false,
+        no_verify_version: false,
+        run_tests: false,
+        package: Vec::new(),
+        workspace: false,
+        exclude: Vec::new(),
+        registry_token: None,
+        no_verify: false,
+        features: None,
+        no_default_features: false,
+        all_features: false,
+        from_artifacts: None,
+        force: false,
+        resume: false,
+        no_upload: false,
+        no_progress: false,
+        provider: None,
+        app_id: None,
+        app_private_key: None,
+        app_installation_id: None,
+        profile_name: None,
+        prerelease: false,
+        target_dir: None,
+        output_dir: None,
+        switches: Default::default(),
+        quiet: false,
+        log_format: LogFormat::Text,
     };
 
     let targets = args.targets();
@@ -67,6 +144,42 @@ fn test_parse_repository_from_arg() {
         github_token: None,
         bins: None,
         profile: Some("release".to_string()),
+        packages: None,
+        changelog_file: None,
+        require_changelog: false,
+        latest: None,
+        discussion_category: None,
+        no_prune: false,
+        create_tag: false,
+        sign_tag: false,
+        allow_dirty: false,
+        allow_branch: false,
+        no_verify_version: false,
+        run_tests: false,
+        package: Vec::new(),
+        workspace: false,
+        exclude: Vec::new(),
+        registry_token: None,
+        no_verify: false,
+        features: None,
+        no_default_features: false,
+        all_features: false,
+        from_artifacts: None,
+        force: false,
+        resume: false,
+        no_upload: false,
+        no_progress: false,
+        provider: None,
+        app_id: None,
+        app_private_key: None,
+        app_installation_id: None,
+        profile_name: None,
+        prerelease: false,
+        target_dir: None,
+        output_dir: None,
+        switches: Default::default(),
+        quiet: false,
+        log_format: LogFormat::Text,
     };
 
     let (owner, repo) = args.parse_repository().unwrap();
@@ -90,6 +203,42 @@ fn test_parse_repository_invalid_format() {
         github_token: None,
         bins: None,
         profile: Some("release".to_string()),
+        packages: None,
+        changelog_file: None,
+        require_changelog: false,
+        latest: None,
+        discussion_category: None,
+        no_prune: false,
+        create_tag: false,
+        sign_tag: false,
+        allow_dirty: false,
+        allow_branch: false,
+        no_verify_version: false,
+        run_tests: false,
+        package: Vec::new(),
+        workspace: false,
+        exclude: Vec::new(),
+        registry_token: None,
+        no_verify: false,
+        features: None,
+        no_default_features: false,
+        all_features: false,
+        from_artifacts: None,
+        force: false,
+        resume: false,
+        no_upload: false,
+        no_progress: false,
+        provider: None,
+        app_id: None,
+        app_private_key: None,
+        app_installation_id: None,
+        profile_name: None,
+        prerelease: false,
+        target_dir: None,
+        output_dir: None,
+        switches: Default::default(),
+        quiet: false,
+        log_format: LogFormat::Text,
     };
 
     assert!(args.parse_repository().is_err());
@@ -117,6 +266,42 @@ fn test_profile_default() {
         github_token: None,
         bins: None,
         profile: Some("release".to_string()),
+        packages: None,
+        changelog_file: None,
+        require_changelog: false,
+        latest: None,
+        discussion_category: None,
+        no_prune: false,
+        create_tag: false,
+        sign_tag: false,
+        allow_dirty: false,
+        allow_branch: false,
+        no_verify_version: false,
+        run_tests: false,
+        package: Vec::new(),
+        workspace: false,
+        exclude: Vec::new(),
+        registry_token: None,
+        no_verify: false,
+        features: None,
+        no_default_features: false,
+        all_features: false,
+        from_artifacts: None,
+        force: false,
+        resume: false,
+        no_upload: false,
+        no_progress: false,
+        provider: None,
+        app_id: None,
+        app_private_key: None,
+        app_installation_id: None,
+        profile_name: None,
+        prerelease: false,
+        target_dir: None,
+        output_dir: None,
+        switches: Default::default(),
+        quiet: false,
+        log_format: LogFormat::Text,
     };
 
     assert_eq!(args.profile(), "release");
@@ -138,6 +323,42 @@ fn test_draft_mode() {
         github_token: None,
         bins: None,
         profile: Some("release".to_string()),
+        packages: None,
+        changelog_file: None,
+        require_changelog: false,
+        latest: None,
+        discussion_category: None,
+        no_prune: false,
+        create_tag: false,
+        sign_tag: false,
+        allow_dirty: false,
+        allow_branch: false,
+        no_verify_version: false,
+        run_tests: false,
+        package: Vec::new(),
+        workspace: false,
+        exclude: Vec::new(),
+        registry_token: None,
+        no_verify: false,
+        features: None,
+        no_default_features: false,
+        all_features: false,
+        from_artifacts: None,
+        force: false,
+        resume: false,
+        no_upload: false,
+        no_progress: false,
+        provider: None,
+        app_id: None,
+        app_private_key: None,
+        app_installation_id: None,
+        profile_name: None,
+        prerelease: false,
+        target_dir: None,
+        output_dir: None,
+        switches: Default::default(),
+        quiet: false,
+        log_format: LogFormat::Text,
     };
 
     assert!(args.draft);
@@ -159,6 +380,42 @@ fn test_checksum_generation() {
         github_token: None,
         bins: None,
         profile: Some("release".to_string()),
+        packages: None,
+        changelog_file: None,
+        require_changelog: false,
+        latest: None,
+        discussion_category: None,
+        no_prune: false,
+        create_tag: false,
+        sign_tag: false,
+        allow_dirty: false,
+        allow_branch: false,
+        no_verify_version: false,
+        run_tests: false,
+        package: Vec::new(),
+        workspace: false,
+        exclude: Vec::new(),
+        registry_token: None,
+        no_verify: false,
+        features: None,
+        no_default_features: false,
+        all_features: false,
+        from_artifacts: None,
+        force: false,
+        resume: false,
+        no_upload: false,
+        no_progress: false,
+        provider: None,
+        app_id: None,
+        app_private_key: None,
+        app_installation_id: None,
+        profile_name: None,
+        prerelease: false,
+        target_dir: None,
+        output_dir: None,
+        switches: Default::default(),
+        quiet: false,
+        log_format: LogFormat::Text,
     };
 
     assert!(!args.no_checksum);
@@ -180,7 +437,519 @@ fn test_bins_filter() {
         github_token: None,
         bins: Some(vec!["bin1".to_string(), "bin2".to_string()]),
         profile: Some("release".to_string()),
+        packages: None,
+        changelog_file: None,
+        require_changelog: false,
+        latest: None,
+        discussion_category: None,
+        no_prune: false,
+        create_tag: false,
+        sign_tag: false,
+        allow_dirty: false,
+        allow_branch: false,
+        no_verify_version: false,
+        run_tests: false,
+        package: Vec::new(),
+        workspace: false,
+        exclude: Vec::new(),
+        registry_token: None,
+        no_verify: false,
+        features: None,
+        no_default_features: false,
+        all_features: false,
+        from_artifacts: None,
+        force: false,
+        resume: false,
+        no_upload: false,
+        no_progress: false,
+        provider: None,
+        app_id: None,
+        app_private_key: None,
+        app_installation_id: None,
+        profile_name: None,
+        prerelease: false,
+        target_dir: None,
+        output_dir: None,
+        switches: Default::default(),
+        quiet: false,
+        log_format: LogFormat::Text,
     };
 
     assert_eq!(args.bins.unwrap().len(), 2);
 }
+
+#[test]
+fn test_require_changelog_defaults_to_changelog_md() {
+    let mut args = Args {
+        tag: None,
+        hash: false,
+        targets: None,
+        format: Some(ArchiveFormat::Tgz),
+        draft: false,
+        skip_publish: true,
+        no_checksum: false,
+        config: None,
+        verbose: false,
+        repository: Some("owner/repo".to_string()),
+        github_token: None,
+        bins: None,
+        profile: Some("release".to_string()),
+        packages: None,
+        changelog_file: None,
+        require_changelog: false,
+        latest: None,
+        discussion_category: None,
+        no_prune: false,
+        create_tag: false,
+        sign_tag: false,
+        allow_dirty: false,
+        allow_branch: false,
+        no_verify_version: false,
+        run_tests: false,
+        package: Vec::new(),
+        workspace: false,
+        exclude: Vec::new(),
+        registry_token: None,
+        no_verify: false,
+        features: None,
+        no_default_features: false,
+        all_features: false,
+        from_artifacts: None,
+        force: false,
+        resume: false,
+        no_upload: false,
+        no_progress: false,
+        provider: None,
+        app_id: None,
+        app_private_key: None,
+        app_installation_id: None,
+        profile_name: None,
+        prerelease: false,
+        target_dir: None,
+        output_dir: None,
+        switches: Default::default(),
+        quiet: false,
+        log_format: LogFormat::Text,
+    };
+
+    assert_eq!(args.changelog_file(), None);
+
+    args.require_changelog = true;
+    assert_eq!(
+        args.changelog_file(),
+        Some(std::path::PathBuf::from("CHANGELOG.md"))
+    );
+
+    args.changelog_file = Some(std::path::PathBuf::from("docs/CHANGES.md"));
+    assert_eq!(
+        args.changelog_file(),
+        Some(std::path::PathBuf::from("docs/CHANGES.md"))
+    );
+}
+
+#[test]
+fn test_hash_releases_are_not_latest_by_default() {
+    let mut args = Args {
+        tag: None,
+        hash: false,
+        targets: None,
+        format: Some(ArchiveFormat::Tgz),
+        draft: false,
+        skip_publish: true,
+        no_checksum: false,
+        config: None,
+        verbose: false,
+        repository: Some("owner/repo".to_string()),
+        github_token: None,
+        bins: None,
+        profile: Some("release".to_string()),
+        packages: None,
+        changelog_file: None,
+        require_changelog: false,
+        latest: None,
+        discussion_category: None,
+        no_prune: false,
+        create_tag: false,
+        sign_tag: false,
+        allow_dirty: false,
+        allow_branch: false,
+        no_verify_version: false,
+        run_tests: false,
+        package: Vec::new(),
+        workspace: false,
+        exclude: Vec::new(),
+        registry_token: None,
+        no_verify: false,
+        features: None,
+        no_default_features: false,
+        all_features: false,
+        from_artifacts: None,
+        force: false,
+        resume: false,
+        no_upload: false,
+        no_progress: false,
+        provider: None,
+        app_id: None,
+        app_private_key: None,
+        app_installation_id: None,
+        profile_name: None,
+        prerelease: false,
+        target_dir: None,
+        output_dir: None,
+        switches: Default::default(),
+        quiet: false,
+        log_format: LogFormat::Text,
+    };
+
+    assert_eq!(args.latest(), None);
+
+    args.hash = true;
+    assert_eq!(args.latest(), Some(LatestMode::False));
+
+    args.latest = Some(LatestMode::True);
+    assert_eq!(args.latest(), Some(LatestMode::True));
+}
+
+#[test]
+fn test_rollback_subcommand() {
+    let cli =
+        GhdistCli::try_parse_from(["cargo-ghdist", "rollback", "v1.2.3", "--keep-tag"]).unwrap();
+    match cli.command {
+        Some(Command::Rollback {
+            release_tag,
+            keep_tag,
+            yes,
+        }) => {
+            assert_eq!(release_tag, "v1.2.3");
+            assert!(keep_tag);
+            assert!(!yes);
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+
+    assert!(GhdistCli::try_parse_from(["cargo-ghdist", "rollback"]).is_err());
+}
+
+#[test]
+fn test_publish_draft_subcommand() {
+    let cli = GhdistCli::try_parse_from([
+        "cargo-ghdist",
+        "publish-draft",
+        "v1.2.3",
+        "--prerelease",
+        "--latest",
+        "false",
+    ])
+    .unwrap();
+
+    assert_eq!(cli.latest, Some(LatestMode::False));
+    match cli.command {
+        Some(Command::PublishDraft {
+            release_tag,
+            prerelease,
+        }) => {
+            assert_eq!(release_tag, "v1.2.3");
+            assert!(prerelease);
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+}
+
+#[test]
+fn test_from_artifacts_flag() {
+    let cli =
+        GhdistCli::try_parse_from(["cargo-ghdist", "--from-artifacts", "9876543210"]).unwrap();
+    let args: Args = cli.into();
+    assert_eq!(args.from_artifacts.as_deref(), Some("9876543210"));
+}
+
+#[test]
+fn test_no_upload_flag() {
+    let cli =
+        GhdistCli::try_parse_from(["cargo-ghdist", "--no-upload", "--tag", "v1.2.3"]).unwrap();
+    let args: Args = cli.into();
+    assert!(args.no_upload);
+    assert_eq!(args.tag.as_deref(), Some("v1.2.3"));
+}
+
+#[test]
+fn test_provider_flag() {
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist"]).unwrap();
+    let args: Args = cli.into();
+    assert_eq!(args.providers(), vec![Provider::Github]);
+
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "--provider", "github,gitlab"]).unwrap();
+    let args: Args = cli.into();
+    assert_eq!(args.providers(), vec![Provider::Github, Provider::Gitlab]);
+
+    // GitHub goes first whatever the order given
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "--provider", "gitlab,github"]).unwrap();
+    let args: Args = cli.into();
+    assert_eq!(args.providers(), vec![Provider::Github, Provider::Gitlab]);
+}
+
+#[test]
+fn test_verify_subcommand() {
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "verify", "v1.2.3"]).unwrap();
+    match cli.command {
+        Some(Command::Verify { release_tag }) => assert_eq!(release_tag, "v1.2.3"),
+        _ => panic!("Expected Verify command"),
+    }
+}
+
+#[test]
+fn test_self_update_subcommand() {
+    use cargo_ghdist::cli::SelfAction;
+    let cli =
+        GhdistCli::try_parse_from(["cargo-ghdist", "self", "update", "--tag", "v0.2.0"]).unwrap();
+    match cli.command {
+        Some(Command::SelfManagement {
+            action: SelfAction::Update { tag },
+        }) => assert_eq!(tag.as_deref(), Some("v0.2.0")),
+        _ => panic!("Expected self update command"),
+    }
+}
+
+#[test]
+fn test_auth_subcommand() {
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "auth", "login", "--with-token"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Auth {
+            action: AuthAction::Login { with_token: true }
+        })
+    ));
+}
+
+#[test]
+fn test_app_flags() {
+    let cli = GhdistCli::try_parse_from([
+        "cargo-ghdist",
+        "--app-id",
+        "12345",
+        "--app-private-key",
+        "app.pem",
+    ])
+    .unwrap();
+    let args: Args = cli.into();
+    assert_eq!(args.app_id, Some(12345));
+    assert_eq!(args.app_private_key, Some(PathBuf::from("app.pem")));
+}
+
+#[test]
+fn test_profile_name_flag() {
+    let cli =
+        GhdistCli::try_parse_from(["cargo-ghdist", "--profile-name", "nightly", "--hash"]).unwrap();
+    let args: Args = cli.into();
+    assert_eq!(args.profile_name.as_deref(), Some("nightly"));
+    assert!(args.hash);
+}
+
+#[test]
+fn test_switch_flags_take_optional_value() {
+    let cli = GhdistCli::try_parse_from([
+        "cargo-ghdist",
+        "--draft",
+        "--skip-publish=false",
+        "--allow-dirty=yes",
+        "--hash",
+    ])
+    .unwrap();
+    let args: Args = cli.into();
+    assert!(args.draft);
+    assert_eq!(args.switches.draft, Some(true));
+    assert!(!args.skip_publish);
+    assert_eq!(args.switches.skip_publish, Some(false));
+    assert!(args.allow_dirty);
+    assert_eq!(args.switches.create_tag, None);
+    assert!(args.hash);
+
+    assert!(GhdistCli::try_parse_from(["cargo-ghdist", "--draft=maybe"]).is_err());
+}
+
+#[test]
+fn test_target_and_output_dir_flags() {
+    let cli = GhdistCli::try_parse_from([
+        "cargo-ghdist",
+        "--no-upload",
+        "--target-dir",
+        "/tmp/cargo-target",
+        "--output-dir",
+        "out",
+    ])
+    .unwrap();
+    let args: Args = cli.into();
+    assert_eq!(args.target_dir, Some(PathBuf::from("/tmp/cargo-target")));
+    assert_eq!(args.output_dir, Some(PathBuf::from("out")));
+
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "--hash"]).unwrap();
+    let args: Args = cli.into();
+    assert_eq!(args.target_dir, None);
+    assert_eq!(args.output_dir, None);
+}
+
+#[test]
+fn test_init_ci_provider() {
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "init", "-y"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Init {
+            ci: CiProvider::Github,
+            ..
+        })
+    ));
+
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "init", "--ci", "circleci"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Init {
+            ci: CiProvider::Circleci,
+            ..
+        })
+    ));
+
+    assert!(GhdistCli::try_parse_from(["cargo-ghdist", "init", "--ci", "jenkins"]).is_err());
+
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "init", "-y", "--release-pr"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Init {
+            release_pr: true,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_completions_subcommand() {
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "completions", "zsh"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Command::Completions {
+            shell: clap_complete::Shell::Zsh
+        })
+    ));
+    assert!(GhdistCli::try_parse_from(["cargo-ghdist", "completions", "tcsh"]).is_err());
+}
+
+#[test]
+fn test_doctor_subcommand() {
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "doctor", "--hash"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::Doctor)));
+    assert!(cli.hash);
+}
+
+#[test]
+fn test_plan_subcommand() {
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "plan", "--tag", "v1.2.3"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::Plan)));
+    assert_eq!(cli.output, OutputFormat::Text);
+
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "plan", "--output", "json"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::Plan)));
+    assert_eq!(cli.output, OutputFormat::Json);
+    assert!(GhdistCli::try_parse_from(["cargo-ghdist", "plan", "--output", "yaml"]).is_err());
+}
+
+#[test]
+fn test_log_format_and_output() {
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist"]).unwrap();
+    assert_eq!(cli.log_format, LogFormat::Text);
+    assert_eq!(cli.output, OutputFormat::Text);
+    assert!(!Args::from(cli).no_progress);
+
+    let cli = GhdistCli::try_parse_from([
+        "cargo-ghdist",
+        "upload",
+        "--log-format",
+        "json",
+        "--output",
+        "json",
+    ])
+    .unwrap();
+    assert_eq!(cli.log_format, LogFormat::Json);
+    assert_eq!(cli.output, OutputFormat::Json);
+    // Progress bars would interleave with the JSON lines
+    assert!(Args::from(cli).no_progress);
+
+    assert!(GhdistCli::try_parse_from(["cargo-ghdist", "--log-format", "yaml"]).is_err());
+}
+
+#[test]
+fn test_upload_and_finalize_subcommands() {
+    let cli = GhdistCli::try_parse_from([
+        "cargo-ghdist",
+        "upload",
+        "--tag",
+        "v1.2.3",
+        "--targets",
+        "aarch64-apple-darwin",
+    ])
+    .unwrap();
+    assert!(matches!(cli.command, Some(Command::Upload)));
+    assert_eq!(cli.tag.as_deref(), Some("v1.2.3"));
+    assert_eq!(cli.targets, Some(vec!["aarch64-apple-darwin".to_string()]));
+
+    let cli = GhdistCli::try_parse_from(["cargo-ghdist", "finalize", "--tag", "v1.2.3"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::Finalize)));
+}
+
+#[test]
+fn test_bump_subcommand() {
+    let cli = GhdistCli::try_parse_from([
+        "cargo-ghdist",
+        "bump",
+        "pre",
+        "--pre-id",
+        "beta",
+        "--create-tag",
+    ])
+    .unwrap();
+
+    assert_eq!(cli.create_tag, Some(true));
+    match cli.command {
+        Some(Command::Bump {
+            level,
+            pre_id,
+            no_commit,
+            changelog,
+        }) => {
+            assert_eq!(level, BumpLevel::Pre);
+            assert_eq!(pre_id, "beta");
+            assert!(!no_commit);
+            assert!(!changelog);
+        }
+        other => panic!("unexpected command: {other:?}"),
+    }
+
+    assert!(GhdistCli::try_parse_from(["cargo-ghdist", "bump", "huge"]).is_err());
+}
+
+#[test]
+fn test_package_flag() {
+    let cli =
+        GhdistCli::try_parse_from(["cargo-ghdist", "-p", "cli", "--tag", "cli-v1.2.0"]).unwrap();
+    let args: Args = cli.into();
+    assert_eq!(args.package, vec!["cli"]);
+    assert_eq!(args.release_package(), Some("cli"));
+    assert_eq!(args.tag.as_deref(), Some("cli-v1.2.0"));
+}
+
+#[test]
+fn test_package_selection_flags() {
+    let args: Args = GhdistCli::try_parse_from(["cargo-ghdist", "-p", "cli,core", "-p", "extra"])
+        .unwrap()
+        .into();
+    assert_eq!(args.package, vec!["cli", "core", "extra"]);
+    assert_eq!(args.release_package(), None);
+
+    let args: Args =
+        GhdistCli::try_parse_from(["cargo-ghdist", "--workspace", "--exclude", "xtask"])
+            .unwrap()
+            .into();
+    assert!(args.workspace);
+    assert_eq!(args.exclude, vec!["xtask"]);
+
+    assert!(GhdistCli::try_parse_from(["cargo-ghdist", "--exclude", "xtask"]).is_err());
+    assert!(GhdistCli::try_parse_from(["cargo-ghdist", "--workspace", "-p", "cli"]).is_err());
+}
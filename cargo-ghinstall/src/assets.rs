@@ -0,0 +1,165 @@
+use anyhow::Result;
+use indicatif::HumanBytes;
+use octocrab::models::repos::Release;
+
+use crate::cli::AssetsArgs;
+use crate::github::{AssetPattern, GitHubClient};
+use crate::matcher;
+use crate::utils;
+
+/// Print the release's assets and which one an install would download
+pub async fn run(args: &AssetsArgs) -> Result<()> {
+    let (owner, repo, tag) = args.parse_repo()?;
+    let client = GitHubClient::new()?
+        .with_prereleases(args.selection.pre)
+        .with_tag_prefix(args.selection.tag_prefix.clone())
+        .with_published_before(args.selection.published_before());
+    let release = client.get_release(&owner, &repo, tag.as_deref()).await?;
+    let target = args.target();
+
+    println!("{owner}/{repo} {} for {target}\n", release.tag_name);
+    if release.assets.is_empty() {
+        println!("The release has no assets");
+        return Ok(());
+    }
+    let pattern = args
+        .selection
+        .asset
+        .as_deref()
+        .map(AssetPattern::parse)
+        .transpose()?;
+    print!(
+        "{}",
+        render(&release, &target, args.bin.as_deref(), pattern.as_ref())
+    );
+    Ok(())
+}
+
+/// The asset table with each asset's match status, and the selection; a
+/// `pattern` replaces target matching as `--asset` does for installs
+fn render(
+    release: &Release,
+    target: &str,
+    bin: Option<&str>,
+    pattern: Option<&AssetPattern>,
+) -> String {
+    let scores = release
+        .assets
+        .iter()
+        .map(|asset| match pattern {
+            Some(pattern) if pattern.matches(&asset.name) => Ok(0),
+            Some(pattern) => Err(format!("doesn't match {pattern}")),
+            None => matcher::score(&asset.name, target, bin),
+        })
+        .collect::<Vec<_>>();
+    let ranks = release
+        .assets
+        .iter()
+        .zip(&scores)
+        .map(|(asset, score)| {
+            score
+                .as_ref()
+                .ok()
+                .map(|score| matcher::rank(&asset.name, *score))
+        })
+        .collect::<Vec<_>>();
+    let best = ranks.iter().flatten().max();
+    let mut selected = None;
+    let rows = release
+        .assets
+        .iter()
+        .zip(scores.iter().zip(&ranks))
+        .map(|(asset, (score, rank))| {
+            let status = match score {
+                Err(reason) => reason.clone(),
+                Ok(_) if rank.as_ref() != best => {
+                    "matches, but a better build is selected".to_string()
+                }
+                Ok(_) if selected.is_none() => {
+                    selected = Some(asset.name.as_str());
+                    "selected".to_string()
+                }
+                // find_asset takes the first of the best matches in the
+                // release's order
+                Ok(_) => "matches, but an earlier asset is selected".to_string(),
+            };
+            vec![
+                asset.name.clone(),
+                HumanBytes(asset.size.max(0) as u64).to_string(),
+                asset.download_count.to_string(),
+                status,
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut out = utils::render_table(&["Asset", "Size", "Downloads", "Status"], &rows);
+    out.push('\n');
+    match selected {
+        Some(name) => out.push_str(&format!("Would install {name}\n")),
+        None => out.push_str(&format!(
+            "No asset matches {}; the install would fall back to cargo install --git\n",
+            pattern.map_or_else(|| target.to_string(), ToString::to_string)
+        )),
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::test_release;
+
+    fn release(assets: &[(&str, i64)]) -> Release {
+        test_release("v1.0.0", false, Some("2024-01-01T00:00:00Z"), assets)
+    }
+
+    #[test]
+    fn test_render() {
+        let release = release(&[
+            ("tool-aarch64-apple-darwin.tar.gz", 2048),
+            ("tool-linux-amd64.tar.gz", 1024),
+            ("tool_1.0.0_amd64.deb", 1024),
+            ("tool-x86_64-unknown-linux-gnu.tar.gz", 3 * 1024 * 1024),
+            ("tool-x86_64-unknown-linux-gnu.zip", 1024),
+            ("SHA256SUMS", 120),
+        ]);
+        assert_eq!(
+            render(&release, "x86_64-unknown-linux-gnu", None, None),
+            "\
+Asset                                 Size      Downloads  Status
+tool-aarch64-apple-darwin.tar.gz      2.00 KiB  7          built for macos, not linux
+tool-linux-amd64.tar.gz               1.00 KiB  7          matches, but a better build is selected
+tool_1.0.0_amd64.deb                  1.00 KiB  7          matches, but a better build is selected
+tool-x86_64-unknown-linux-gnu.tar.gz  3.00 MiB  7          selected
+tool-x86_64-unknown-linux-gnu.zip     1.00 KiB  7          matches, but an earlier asset is selected
+SHA256SUMS                            120 B     7          name has no operating system
+
+Would install tool-x86_64-unknown-linux-gnu.tar.gz
+"
+        );
+
+        let output = render(&release, "x86_64-pc-windows-msvc", None, None);
+        assert!(output.ends_with(
+            "No asset matches x86_64-pc-windows-msvc; the install would fall back to cargo install --git\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_with_pattern() {
+        let release = release(&[
+            ("tool-x86_64-unknown-linux-gnu.tar.gz", 1024),
+            ("tool-x86_64-unknown-linux-gnu.zip", 1024),
+        ]);
+        let pattern = AssetPattern::parse("/\\.zip$/").unwrap();
+        let output = render(&release, "x86_64-unknown-linux-gnu", None, Some(&pattern));
+        assert!(
+            output.contains("tool-x86_64-unknown-linux-gnu.tar.gz  1.00 KiB  7          doesn't match /\\.zip$/"),
+            "{output}"
+        );
+        assert!(output.ends_with("Would install tool-x86_64-unknown-linux-gnu.zip\n"));
+
+        let pattern = AssetPattern::parse("*.deb").unwrap();
+        let output = render(&release, "x86_64-unknown-linux-gnu", None, Some(&pattern));
+        assert!(output.contains("No asset matches *.deb;"), "{output}");
+    }
+}
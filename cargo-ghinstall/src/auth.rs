@@ -0,0 +1,204 @@
+use anyhow::{Context, Result};
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Keyring entry shared with cargo-ghdist, so one login serves both tools
+const KEYRING_SERVICE: &str = "cargo-gh";
+const KEYRING_USER: &str = "github.com";
+
+/// Where a GitHub token was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSource {
+    Environment,
+    Keyring,
+    GhCli,
+    GhHosts,
+}
+
+impl std::fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenSource::Environment => write!(f, "GITHUB_TOKEN"),
+            TokenSource::Keyring => write!(f, "the OS keyring"),
+            TokenSource::GhCli => write!(f, "`gh auth token`"),
+            TokenSource::GhHosts => write!(f, "the gh CLI hosts.yml"),
+        }
+    }
+}
+
+/// Find a token in GITHUB_TOKEN, the OS keyring, `gh auth token` or gh's hosts.yml, in that order
+pub fn find_token() -> Option<(String, TokenSource)> {
+    let non_empty = |token: String| {
+        let token = token.trim().to_string();
+        (!token.is_empty()).then_some(token)
+    };
+
+    if let Some(token) = std::env::var("GITHUB_TOKEN").ok().and_then(non_empty) {
+        return Some((token, TokenSource::Environment));
+    }
+    if let Some(token) = keyring_token().and_then(non_empty) {
+        return Some((token, TokenSource::Keyring));
+    }
+    if let Some(token) = gh_cli_token().and_then(non_empty) {
+        return Some((token, TokenSource::GhCli));
+    }
+    let hosts = std::fs::read_to_string(gh_hosts_path()?).ok()?;
+    hosts_yml_token(&hosts, KEYRING_USER)
+        .and_then(non_empty)
+        .map(|token| (token, TokenSource::GhHosts))
+}
+
+/// Run `f` on the keyring entry in its own thread
+///
+/// The Secret Service backend drives its own tokio runtime, which can't start inside ours.
+fn with_keyring<T: Send>(
+    f: impl FnOnce(&keyring::Entry) -> keyring::Result<T> + Send,
+) -> keyring::Result<T> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| f(&keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?))
+            .join()
+            .unwrap_or_else(|_| {
+                Err(keyring::Error::PlatformFailure(
+                    "keyring thread panicked".into(),
+                ))
+            })
+    })
+}
+
+fn keyring_token() -> Option<String> {
+    match with_keyring(|entry| entry.get_password()) {
+        Ok(token) => Some(token),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            tracing::debug!("OS keyring unavailable: {}", e);
+            None
+        }
+    }
+}
+
+fn gh_cli_token() -> Option<String> {
+    let output = Command::new("gh")
+        .args(["auth", "token", "--hostname", KEYRING_USER])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// gh's hosts.yml, honouring GH_CONFIG_DIR and XDG_CONFIG_HOME like gh does
+fn gh_hosts_path() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("GH_CONFIG_DIR") {
+        return Some(PathBuf::from(dir).join("hosts.yml"));
+    }
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("gh").join("hosts.yml"));
+    }
+    if cfg!(windows) {
+        if let Some(dir) = std::env::var_os("AppData") {
+            return Some(PathBuf::from(dir).join("GitHub CLI").join("hosts.yml"));
+        }
+    }
+    let home = directories::BaseDirs::new()?.home_dir().to_path_buf();
+    Some(home.join(".config").join("gh").join("hosts.yml"))
+}
+
+/// The `oauth_token` of `host` in gh's hosts.yml
+///
+/// Tokens directly under the host win over the per-user entries newer gh versions write.
+pub fn hosts_yml_token(content: &str, host: &str) -> Option<String> {
+    let mut lines = content.lines().skip_while(|line| {
+        line.trim_end().trim_end_matches(':') != host || line.starts_with(char::is_whitespace)
+    });
+    lines.next()?;
+
+    lines
+        .take_while(|line| line.trim().is_empty() || line.starts_with(char::is_whitespace))
+        .filter_map(|line| {
+            let indent = line.len() - line.trim_start().len();
+            let value = line.trim().strip_prefix("oauth_token:")?;
+            Some((indent, value.trim().trim_matches(['"', '\'']).to_string()))
+        })
+        .min_by_key(|(indent, _)| *indent)
+        .map(|(_, token)| token)
+}
+
+/// Store a token in the OS keyring, read from stdin or prompted for without
+/// echoing it; `scopes` tells which token permissions the tool needs
+pub fn login(with_token: bool, scopes: &str) -> Result<()> {
+    let token = if with_token {
+        let mut token = String::new();
+        io::stdin().read_to_string(&mut token)?;
+        token
+    } else {
+        let term = console::Term::stderr();
+        term.write_str(&format!("Paste a GitHub token ({scopes}): "))?;
+        term.read_secure_line()
+            .context("Failed to read the token; pipe it in with --with-token instead")?
+    };
+    let token = token.trim();
+    if token.is_empty() {
+        anyhow::bail!("No token given");
+    }
+
+    with_keyring(|entry| entry.set_password(token))
+        .context("Failed to store the token in the OS keyring")?;
+    println!("🔑 Token stored in the OS keyring");
+    Ok(())
+}
+
+pub fn logout() -> Result<()> {
+    match with_keyring(|entry| entry.delete_credential()) {
+        Ok(()) => println!("🔑 Token removed from the OS keyring"),
+        Err(keyring::Error::NoEntry) => println!("No token stored in the OS keyring"),
+        Err(e) => return Err(e).context("Failed to remove the token from the OS keyring"),
+    }
+    Ok(())
+}
+
+/// Report where the token comes from; `tool` is the subcommand to suggest
+/// logging in with, e.g. "ghinstall"
+pub fn status(tool: &str) -> Result<()> {
+    match find_token() {
+        Some((_, source)) => println!("🔑 Using the GitHub token from {source}"),
+        None => anyhow::bail!(
+            "No GitHub token found. Set GITHUB_TOKEN, run `cargo {tool} auth login`, or `gh auth login`"
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hosts_yml_token() {
+        let hosts = r#"
+github.example.com:
+    oauth_token: enterprise
+github.com:
+    users:
+        octocat:
+            oauth_token: gho_user
+    oauth_token: "gho_active"
+    user: octocat
+    git_protocol: https
+"#;
+        assert_eq!(
+            hosts_yml_token(hosts, "github.com").as_deref(),
+            Some("gho_active")
+        );
+        assert_eq!(
+            hosts_yml_token(hosts, "github.example.com").as_deref(),
+            Some("enterprise")
+        );
+
+        let keyring_only = "github.com:\n    user: octocat\n    git_protocol: ssh\n";
+        assert_eq!(hosts_yml_token(keyring_only, "github.com"), None);
+        assert_eq!(hosts_yml_token("", "github.com"), None);
+    }
+}
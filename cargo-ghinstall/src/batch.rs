@@ -0,0 +1,372 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::cli::Args;
+use crate::installer::Installer;
+use crate::receipts::Receipt;
+use crate::utils;
+
+/// A tools manifest: the tools to install with `--from-file`
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ToolsManifest {
+    #[serde(default, rename = "tool")]
+    pub tools: Vec<ToolSpec>,
+}
+
+/// One `[[tool]]` entry; unset fields fall back to the command line
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ToolSpec {
+    /// `owner/repo`
+    pub repo: String,
+    /// Release tag; the latest release when unset
+    pub version: Option<String>,
+    pub bin: Option<String>,
+    pub target: Option<String>,
+    /// Asset glob, or regex between slashes, for releases the target matching gets wrong
+    #[serde(default, deserialize_with = "crate::validate::optional_asset_pattern")]
+    pub asset: Option<String>,
+}
+
+/// The releases and assets a manifest resolved to, per target, written next
+/// to the manifest (`tools.toml` -> `tools.lock`)
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Lockfile {
+    #[serde(default, rename = "tool")]
+    pub tools: Vec<LockedTool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LockedTool {
+    pub repo: String,
+    pub target: String,
+    pub tag: String,
+    pub asset: String,
+    pub sha256: String,
+}
+
+impl ToolsManifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid tools manifest {}", path.display()))
+    }
+}
+
+impl Lockfile {
+    /// The lockfile of a manifest
+    pub fn path(manifest: &Path) -> PathBuf {
+        manifest.with_extension("lock")
+    }
+
+    /// Load the lockfile, or an empty one when it doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid lockfile {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut tools = self.tools.clone();
+        tools.sort_by(|a, b| (&a.repo, &a.target).cmp(&(&b.repo, &b.target)));
+        let content = format!(
+            "# Generated by cargo-ghinstall; install these exact assets with --locked\n\n{}",
+            toml::to_string_pretty(&Self { tools })?
+        );
+        std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn get(&self, repo: &str, target: &str) -> Option<&LockedTool> {
+        self.tools
+            .iter()
+            .find(|tool| tool.repo == repo && tool.target == target)
+    }
+
+    /// Lock an install, replacing the entry for the same repository and target
+    pub fn record(&mut self, receipt: &Receipt, repo: &str) {
+        self.tools
+            .retain(|tool| !(tool.repo == repo && tool.target == receipt.target));
+        self.tools.push(LockedTool {
+            repo: repo.to_string(),
+            target: receipt.target.clone(),
+            tag: receipt.tag.clone(),
+            asset: receipt.asset.clone(),
+            sha256: receipt.asset_sha256.clone(),
+        });
+    }
+
+    /// Drop the entries of tools that were removed from the manifest
+    pub fn retain_manifest(&mut self, manifest: &ToolsManifest) {
+        self.tools
+            .retain(|locked| manifest.tools.iter().any(|tool| tool.repo == locked.repo));
+    }
+}
+
+impl ToolSpec {
+    /// The install arguments for this tool, starting from the command line's
+    pub fn args(&self, base: &Args) -> Args {
+        let mut args = base.clone();
+        args.repo = Some(self.repo.clone());
+        args.tag = self.version.clone();
+        if self.bin.is_some() {
+            args.bin = self.bin.clone();
+            args.bins = false;
+        }
+        args.selection.target = self
+            .target
+            .clone()
+            .or_else(|| base.selection.target.clone());
+        args.selection.asset = self.asset.clone();
+        args.from_file = None;
+        // A manifest declares the versions, so don't ask for them
+        args.interactive = false;
+        args
+    }
+
+    /// The arguments to install exactly the locked release asset
+    pub fn locked_args(&self, base: &Args, locked: &LockedTool) -> Args {
+        let mut args = self.args(base);
+        args.tag = Some(locked.tag.clone());
+        args.selection.asset = Some(locked.asset.clone());
+        args.expected_sha256 = Some(locked.sha256.clone());
+        // Building from source instead would install something else than
+        // what was locked
+        args.no_fallback = true;
+        args
+    }
+}
+
+/// Install every tool of the manifest, then summarize; fails when any install did
+///
+/// Without `--locked` the resolved releases are written to the lockfile; with
+/// it, the tools are installed from the lockfile's releases and assets.
+pub async fn run(base: &Args, manifest_path: &Path) -> Result<()> {
+    let manifest = ToolsManifest::load(manifest_path)?;
+    if manifest.tools.is_empty() {
+        println!("No [[tool]] entries in {}", manifest_path.display());
+        return Ok(());
+    }
+    let lock_path = Lockfile::path(manifest_path);
+    let mut lockfile = Lockfile::load(&lock_path)?;
+
+    let mut rows = Vec::new();
+    let mut failures = 0;
+    for tool in &manifest.tools {
+        tracing::info!("Installing {}", tool.repo);
+        let args = tool.args(base);
+        let result = if base.locked {
+            let target = args.target();
+            match lockfile.get(&tool.repo, &target) {
+                Some(locked) => install(tool.locked_args(base, locked)).await,
+                None => Err(anyhow::anyhow!(
+                    "not locked for {target} in {}; install without --locked to lock it",
+                    lock_path.display()
+                )),
+            }
+        } else {
+            install(args).await
+        };
+
+        let (version, status) = match result {
+            Ok(Some(receipt)) => {
+                if !base.locked {
+                    lockfile.record(&receipt, &tool.repo);
+                }
+                (receipt.tag, "installed".to_string())
+            }
+            Ok(None) if base.locked => {
+                failures += 1;
+                (
+                    tool.version.clone().unwrap_or_else(|| "latest".to_string()),
+                    "failed: the locked release asset is gone".to_string(),
+                )
+            }
+            Ok(None) => (
+                tool.version.clone().unwrap_or_else(|| "latest".to_string()),
+                "installed with cargo install, not locked".to_string(),
+            ),
+            Err(e) => {
+                failures += 1;
+                (
+                    tool.version.clone().unwrap_or_else(|| "latest".to_string()),
+                    format!("failed: {e:#}"),
+                )
+            }
+        };
+        rows.push(vec![tool.repo.clone(), version, status]);
+    }
+
+    print!(
+        "{}",
+        utils::render_table(&["Repository", "Version", "Result"], &rows)
+    );
+    if !base.locked {
+        lockfile.retain_manifest(&manifest);
+        lockfile.save(&lock_path)?;
+    }
+    if failures > 0 {
+        anyhow::bail!(
+            "{failures} of {} tools failed to install",
+            manifest.tools.len()
+        );
+    }
+    println!("Installed {} tools", manifest.tools.len());
+    Ok(())
+}
+
+async fn install(args: Args) -> Result<Option<Receipt>> {
+    Installer::new(args)?.run().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_load_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tools.toml");
+        std::fs::write(
+            &path,
+            r#"
+[[tool]]
+repo = "BurntSushi/ripgrep"
+version = "14.1.0"
+bin = "rg"
+
+[[tool]]
+repo = "sharkdp/fd"
+target = "x86_64-unknown-linux-musl"
+asset = "fd-*-x86_64-unknown-linux-musl.tar.gz"
+"#,
+        )
+        .unwrap();
+        let manifest = ToolsManifest::load(&path).unwrap();
+        assert_eq!(manifest.tools.len(), 2);
+        assert_eq!(manifest.tools[0].version.as_deref(), Some("14.1.0"));
+        assert_eq!(manifest.tools[1].version, None);
+
+        std::fs::write(&path, "[[tool]]\nrepo = \"a/b\"\nverison = \"1\"\n").unwrap();
+        let error = format!("{:#}", ToolsManifest::load(&path).unwrap_err());
+        assert!(error.contains("unknown field `verison`"), "{error}");
+    }
+
+    #[test]
+    fn test_tool_args() {
+        let base = Args::parse_from([
+            "cargo-ghinstall",
+            "--from-file",
+            "tools.toml",
+            "--install-dir",
+            "/opt/bin",
+            "--bins",
+            "--target",
+            "aarch64-apple-darwin",
+        ]);
+        let tool = ToolSpec {
+            repo: "BurntSushi/ripgrep".to_string(),
+            version: Some("14.1.0".to_string()),
+            bin: Some("rg".to_string()),
+            target: None,
+            asset: None,
+        };
+        let args = tool.args(&base);
+        assert_eq!(
+            args.parse_repo().unwrap(),
+            (
+                "BurntSushi".to_string(),
+                "ripgrep".to_string(),
+                Some("14.1.0".to_string())
+            )
+        );
+        assert_eq!(args.bin.as_deref(), Some("rg"));
+        assert!(!args.bins);
+        assert_eq!(args.target(), "aarch64-apple-darwin");
+        assert_eq!(args.install_dir, "/opt/bin");
+        assert_eq!(args.from_file, None);
+
+        let locked = LockedTool {
+            repo: "BurntSushi/ripgrep".to_string(),
+            target: "aarch64-apple-darwin".to_string(),
+            tag: "14.1.1".to_string(),
+            asset: "ripgrep-14.1.1-aarch64-apple-darwin.tar.gz".to_string(),
+            sha256: "a".repeat(64),
+        };
+        let args = tool.locked_args(&base, &locked);
+        assert_eq!(args.tag.as_deref(), Some("14.1.1"));
+        assert_eq!(args.selection.asset.as_deref(), Some(locked.asset.as_str()));
+        assert_eq!(args.expected_sha256, Some(locked.sha256));
+        assert!(args.no_fallback);
+    }
+
+    #[test]
+    fn test_lockfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("tools.toml");
+        let lock_path = Lockfile::path(&manifest_path);
+        assert_eq!(lock_path, dir.path().join("tools.lock"));
+        assert_eq!(Lockfile::load(&lock_path).unwrap(), Lockfile::default());
+
+        let receipt = |tag: &str, target: &str| Receipt {
+            tag: tag.to_string(),
+            target: target.to_string(),
+            asset: format!("fd-{tag}-{target}.tar.gz"),
+            asset_sha256: "b".repeat(64),
+            tag_prefix: None,
+            installed_at: 0,
+            binaries: Vec::new(),
+            extras: Vec::new(),
+            previous: None,
+        };
+        let mut lockfile = Lockfile::default();
+        lockfile.record(
+            &receipt("v10.1.0", "x86_64-unknown-linux-gnu"),
+            "sharkdp/fd",
+        );
+        lockfile.record(&receipt("v10.1.0", "aarch64-apple-darwin"), "sharkdp/fd");
+        lockfile.record(
+            &receipt("v10.2.0", "x86_64-unknown-linux-gnu"),
+            "sharkdp/fd",
+        );
+        lockfile.record(
+            &receipt("14.1.1", "x86_64-unknown-linux-gnu"),
+            "removed/tool",
+        );
+        assert_eq!(lockfile.tools.len(), 3);
+        assert_eq!(
+            lockfile
+                .get("sharkdp/fd", "x86_64-unknown-linux-gnu")
+                .unwrap()
+                .tag,
+            "v10.2.0"
+        );
+
+        let manifest = ToolsManifest {
+            tools: vec![ToolSpec {
+                repo: "sharkdp/fd".to_string(),
+                version: None,
+                bin: None,
+                target: None,
+                asset: None,
+            }],
+        };
+        lockfile.retain_manifest(&manifest);
+        lockfile.save(&lock_path).unwrap();
+        let loaded = Lockfile::load(&lock_path).unwrap();
+        assert_eq!(loaded.tools.len(), 2);
+        // Sorted by repository and target for stable diffs
+        assert_eq!(loaded.tools[0].target, "aarch64-apple-darwin");
+        assert!(std::fs::read_to_string(&lock_path)
+            .unwrap()
+            .starts_with("# Generated by cargo-ghinstall"));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::utils;
+use anyhow::Result;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// A hash algorithm release checksum files are written with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+/// Checksum files covering every asset of a release, in order of preference
+pub const AGGREGATE_FILES: &[(&str, Algorithm)] = &[
+    ("SHA256SUMS", Algorithm::Sha256),
+    ("checksums.txt", Algorithm::Sha256),
+    ("sha256sums.txt", Algorithm::Sha256),
+    ("SHA512SUMS", Algorithm::Sha512),
+    ("sha512sums.txt", Algorithm::Sha512),
+    ("B3SUMS", Algorithm::Blake3),
+    ("b3sums.txt", Algorithm::Blake3),
+];
+
+/// Extensions of the checksum file of a single asset, `<asset>.sha256`
+pub const SIDECAR_EXTENSIONS: &[(&str, Algorithm)] = &[
+    ("sha256", Algorithm::Sha256),
+    ("sha512", Algorithm::Sha512),
+    ("b3", Algorithm::Blake3),
+];
+
+impl Algorithm {
+    /// The name BSD-style lines tag hashes with, `SHA256 (file) = ...`
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Sha256 => "SHA256",
+            Self::Sha512 => "SHA512",
+            Self::Blake3 => "BLAKE3",
+        }
+    }
+
+    /// Length of a hash in hex digits
+    fn hex_len(self) -> usize {
+        match self {
+            Self::Sha256 | Self::Blake3 => 64,
+            Self::Sha512 => 128,
+        }
+    }
+
+    /// The lowercase hex digest of the file at `path`
+    pub fn digest(self, path: &Path) -> Result<String> {
+        match self {
+            Self::Sha256 => utils::calculate_sha256(path),
+            Self::Sha512 => stream::<sha2::Sha512>(path),
+            Self::Blake3 => {
+                let mut hasher = blake3::Hasher::new();
+                hasher.update_reader(fs::File::open(path)?)?;
+                Ok(hasher.finalize().to_hex().to_string())
+            }
+        }
+    }
+}
+
+/// The lowercase hex digest of the file at `path` under the SHA-2 hash `D`
+fn stream<D: sha2::Digest>(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = D::new();
+    let mut buffer = [0; 8192];
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// The hash and file name of a checksum line: GNU style (`<hash>  <file>`,
+/// with `*` marking binary mode) or BSD style (`SHA256 (<file>) = <hash>`,
+/// whose tag must be `algorithm`'s)
+fn parse_line(line: &str, algorithm: Algorithm) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    if let Some((tag, rest)) = line.split_once(" (") {
+        if let Some((file, hash)) = rest.rsplit_once(") = ") {
+            return tag
+                .eq_ignore_ascii_case(algorithm.tag())
+                .then(|| (hash.trim(), file));
+        }
+    }
+    let (hash, file) = line.split_once(char::is_whitespace)?;
+    let file = file.trim_start();
+    Some((hash, file.strip_prefix('*').unwrap_or(file)))
+}
+
+/// Whether a path listed in a checksum file names `filename`, with `/` or
+/// `\` separated directories before it
+fn names_file(path: &str, filename: &str) -> bool {
+    path.rsplit(['/', '\\']).next() == Some(filename)
+}
+
+/// The hash a checksum file lists for `filename`, from its lines in any of
+/// the styles of [`parse_line`] and with Windows line endings
+pub fn find(content: &str, filename: &str, algorithm: Algorithm) -> Option<String> {
+    content
+        .lines()
+        .filter_map(|line| parse_line(line, algorithm))
+        .find(|(_, file)| names_file(file, filename))
+        .map(|(hash, _)| hash.to_string())
+}
+
+/// The hash in the checksum file of a single asset, which may list it
+/// without a file name; `None` unless it is a hash of `algorithm`
+pub fn sidecar(content: &str, algorithm: Algorithm) -> Option<String> {
+    let first = content.lines().find(|line| !line.trim().is_empty())?;
+    let hash = parse_line(first, algorithm)
+        .map(|(hash, _)| hash)
+        .unwrap_or_else(|| first.trim());
+    (hash.len() == algorithm.hex_len() && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| hash.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let hash = "AB".repeat(32);
+        let content = format!(
+            "# checksums\r\n\
+             {hash} *tool-windows.zip\r\n\
+             SHA256 (tool-linux.tar.gz) = {hash}\r\n\
+             SHA512 (tool-macos.tar.gz) = {hash}\r\n\
+             {hash}  dist\\release\\tool-arm.tar.gz\r\n"
+        );
+        let find = |filename: &str| find(&content, filename, Algorithm::Sha256);
+        assert_eq!(find("tool-windows.zip"), Some(hash.clone()));
+        assert_eq!(find("tool-linux.tar.gz"), Some(hash.clone()));
+        assert_eq!(find("tool-arm.tar.gz"), Some(hash.clone()));
+        // A BSD line of another algorithm doesn't count
+        assert_eq!(find("tool-macos.tar.gz"), None);
+        assert_eq!(find("linux.tar.gz"), None);
+    }
+
+    #[test]
+    fn test_sidecar() {
+        let hash = "a".repeat(64);
+        assert_eq!(
+            sidecar(
+                &format!("{hash}  tool-x86_64-unknown-linux-gnu.tar.gz\n"),
+                Algorithm::Sha256
+            ),
+            Some(hash.clone())
+        );
+        assert_eq!(
+            sidecar(&format!("{}\r\n", hash.to_uppercase()), Algorithm::Sha256),
+            Some(hash.clone())
+        );
+        assert_eq!(
+            sidecar(
+                &format!("SHA256 (tool.tar.gz) = {hash}\n"),
+                Algorithm::Sha256
+            ),
+            Some(hash.clone())
+        );
+        assert_eq!(sidecar(&hash, Algorithm::Sha512), None);
+        assert_eq!(sidecar("", Algorithm::Sha256), None);
+        assert_eq!(
+            sidecar("not-a-checksum  tool.tar.gz", Algorithm::Sha256),
+            None
+        );
+    }
+
+    #[test]
+    fn test_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            Algorithm::Sha256.digest(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(Algorithm::Sha512
+            .digest(&path)
+            .unwrap()
+            .starts_with("ddaf35a193617aba"));
+        assert_eq!(
+            Algorithm::Blake3.digest(&path).unwrap(),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+    }
+}
@@ -1,5 +1,7 @@
-use clap::Parser;
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Parser, Debug)]
 #[clap(
@@ -19,14 +21,28 @@ pub enum CargoCli {
 pub struct Args {
     /// Repository to install from
     /// Format: owner/repo[@tag]
-    /// Examples: rust-lang/rust-analyzer@v1.2.3, owner/repo@abcdef0, owner/repo@main
-    #[clap(value_name = "OWNER/REPO[@TAG]")]
-    pub repo: String,
+    /// Examples: rust-lang/rust-analyzer@v1.2.3, owner/repo@^1.2, owner/repo@abcdef0, owner/repo@main
+    #[clap(value_name = "OWNER/REPO[@TAG]", required_unless_present = "from_file")]
+    pub repo: Option<String>,
 
-    /// Release tag (e.g., v1.2.3, abcdef0, main, or any git ref)
+    /// Release tag (e.g., v1.2.3, abcdef0, main, or any git ref), or a
+    /// version requirement (^1.2, ~1.4, 1) resolved to the highest matching release.
+    /// `latest-pre` is the newest prerelease
     #[clap(short, long)]
     pub tag: Option<String>,
 
+    #[clap(flatten)]
+    pub selection: ReleaseSelection,
+
+    /// Without a tag, choose the release from a list of recent ones when
+    /// stdin is a terminal
+    #[clap(short, long)]
+    pub interactive: bool,
+
+    /// Never prompt: take the latest release and the first matching asset
+    #[clap(short, long)]
+    pub yes: bool,
+
     /// Binary name or pattern to install
     #[clap(short, long)]
     pub bin: Option<String>,
@@ -35,26 +51,89 @@ pub struct Args {
     #[clap(long, conflicts_with = "bin")]
     pub bins: bool,
 
-    /// Target platform triple (e.g., aarch64-apple-darwin)
-    #[clap(short = 'T', long)]
-    pub target: Option<String>,
+    /// Binaries to install by name, from a repository's `bins` config
+    #[clap(skip)]
+    pub bin_names: Vec<String>,
+
+    /// Install the selected binary under this name (e.g. fdfind or tool-1.2),
+    /// still selecting it by its original name
+    #[clap(
+        long = "as",
+        visible_alias = "rename",
+        value_name = "NAME",
+        conflicts_with_all = ["bins", "from_file"],
+        value_parser = parse_binary_name
+    )]
+    pub rename: Option<String>,
+
+    /// On Linux, prefer -musl builds to -gnu ones even where glibc is
+    /// recent enough for them
+    #[clap(long)]
+    pub prefer_musl: bool,
+
+    /// On Windows, prefer -pc-windows-gnu builds to -msvc ones (the default
+    /// in MSYS2 and Cygwin shells)
+    #[clap(long)]
+    pub prefer_gnu: bool,
 
     /// Installation directory
     #[clap(short = 'd', long, default_value = "~/.cargo/bin")]
     pub install_dir: String,
 
+    /// Replace binaries in the install directory that ghinstall didn't install
+    #[clap(short, long)]
+    pub force: bool,
+
+    /// Keep a copy of the replaced binary, next to the receipts file, for
+    /// `rollback`
+    #[clap(long)]
+    pub backup: bool,
+
+    /// Also install the shell completions and man pages the archive ships
+    /// for the installed binaries, into the user's XDG directories
+    #[clap(long)]
+    pub extras: bool,
+
     /// Show release notes
     #[clap(long)]
     pub show_notes: bool,
 
-    /// Require GPG signature verification (currently unsupported and fails safely)
+    /// Require a valid OpenPGP signature (`<asset>.sig` or `<asset>.asc`) of the asset
     #[clap(long)]
     pub verify_signature: bool,
 
+    /// Key file or https:// URL trusted to sign the release, implying --verify-signature
+    /// [default: the owner's GitHub keys]
+    #[clap(long, value_name = "PATH|URL")]
+    pub signing_key: Option<String>,
+
+    /// Verify the asset's build provenance attestation, when GitHub has one,
+    /// with `gh attestation verify` and check it came from the repository's
+    /// own workflows
+    #[clap(long)]
+    pub verify_provenance: bool,
+
+    /// Like --verify-provenance, but also refuse assets without provenance
+    #[clap(long)]
+    pub require_provenance: bool,
+
+    /// Workflow file the provenance must name, such as `release.yml`
+    #[clap(long, value_name = "WORKFLOW")]
+    pub provenance_workflow: Option<String>,
+
+    /// On macOS, refuse binaries that are unsigned or only ad-hoc signed
+    #[clap(long)]
+    pub require_signed: bool,
+
     /// Disable fallback to cargo install --git
     #[clap(long)]
     pub no_fallback: bool,
 
+    /// On Apple Silicon, don't fall back to x86_64 builds run by Rosetta
+    /// when a release has no aarch64 one
+    #[clap(long)]
+    pub no_arch_fallback: bool,
+
     /// Skip SHA256 checksum verification
     #[clap(long)]
     pub skip_checksum: bool,
@@ -63,6 +142,9 @@ pub struct Args {
     #[clap(long, default_value = "~/.config/ghinstall.toml")]
     pub config: PathBuf,
 
+    #[clap(flatten)]
+    pub receipts: ReceiptsArg,
+
     /// Enable verbose output
     #[clap(long)]
     pub verbose: bool,
@@ -74,38 +156,363 @@ pub struct Args {
     /// Disable retry logic for network operations
     #[clap(long)]
     pub no_retry: bool,
+
+    /// Don't show download progress bars or progress log lines
+    #[clap(long)]
+    pub no_progress: bool,
+
+    /// Install every tool declared in this manifest (a `[[tool]]` table per tool)
+    #[clap(long, value_name = "FILE", conflicts_with = "repo")]
+    pub from_file: Option<PathBuf>,
+
+    /// With --from-file, install exactly the releases and assets recorded in
+    /// the lockfile, failing if an asset changed since
+    #[clap(long)]
+    pub locked: bool,
+
+    /// SHA256 the downloaded asset must have (set for --locked installs)
+    #[clap(skip)]
+    pub expected_sha256: Option<String>,
 }
 
-impl Args {
-    /// Parse repository string to extract owner, repo, and optional tag
-    pub fn parse_repo(&self) -> anyhow::Result<(String, String, Option<String>)> {
-        let repo_str = &self.repo;
-
-        // Check if tag is specified with @
-        let (repo_part, tag_part) = if let Some(idx) = repo_str.rfind('@') {
-            let repo = &repo_str[..idx];
-            let tag = &repo_str[idx + 1..];
-            (repo, Some(tag.to_string()))
-        } else {
-            (repo_str.as_str(), None)
-        };
-
-        // Split owner/repo
-        let parts: Vec<&str> = repo_part.split('/').collect();
-        if parts.len() != 2 {
-            return Err(crate::error::GhInstallError::InvalidRepo {
+/// `cargo ghinstall auth ...`, parsed on its own since installs take a positional repository
+#[derive(Parser, Debug)]
+#[clap(
+    name = "cargo ghinstall auth",
+    bin_name = "cargo ghinstall auth",
+    about = "Store, remove or show the GitHub token used when GITHUB_TOKEN isn't set"
+)]
+pub struct AuthArgs {
+    #[clap(subcommand)]
+    pub action: AuthAction,
+}
+
+#[derive(Subcommand, Debug, Clone, Copy)]
+pub enum AuthAction {
+    /// Store a token in the OS keyring
+    Login {
+        /// Read the token from stdin instead of prompting
+        #[clap(long)]
+        with_token: bool,
+    },
+    /// Remove the token from the OS keyring
+    Logout,
+    /// Show where the token comes from
+    Status,
+}
+
+impl AuthArgs {
+    /// Parse the process arguments if they start with `auth`, exiting on invalid ones
+    pub fn parse_if_requested() -> Option<Self> {
+        Self::parse_from_if_requested(std::env::args())
+    }
+
+    pub fn parse_from_if_requested(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        subcommand_args(args, "auth").map(Self::parse_from)
+    }
+}
+
+/// `cargo ghinstall self ...`: manage the cargo-ghinstall installation itself
+#[derive(Parser, Debug)]
+#[clap(
+    name = "cargo ghinstall self",
+    bin_name = "cargo ghinstall self",
+    about = "Update cargo-ghinstall from its own GitHub releases"
+)]
+pub struct SelfArgs {
+    #[clap(subcommand)]
+    pub action: SelfAction,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SelfAction {
+    /// Replace this executable with the latest release, verifying its checksum
+    Update {
+        /// Install this release tag instead, even if it is older
+        #[clap(long)]
+        tag: Option<String>,
+    },
+}
+
+impl SelfArgs {
+    /// Parse the process arguments if they start with `self`, exiting on invalid ones
+    pub fn parse_if_requested() -> Option<Self> {
+        Self::parse_from_if_requested(std::env::args())
+    }
+
+    pub fn parse_from_if_requested(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        subcommand_args(args, "self").map(Self::parse_from)
+    }
+}
+
+/// `cargo ghinstall outdated`: compare recorded installs with the latest releases
+#[derive(Parser, Debug)]
+#[clap(
+    name = "cargo ghinstall outdated",
+    bin_name = "cargo ghinstall outdated",
+    about = "List installed tools with newer releases, exiting non-zero when there are any"
+)]
+pub struct OutdatedArgs {
+    #[clap(flatten)]
+    pub receipts: ReceiptsArg,
+}
+
+impl OutdatedArgs {
+    /// Parse the process arguments if they start with `outdated`, exiting on invalid ones
+    pub fn parse_if_requested() -> Option<Self> {
+        Self::parse_from_if_requested(std::env::args())
+    }
+
+    pub fn parse_from_if_requested(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        subcommand_args(args, "outdated").map(Self::parse_from)
+    }
+}
+
+/// `cargo ghinstall rollback`: restore the binaries an install replaced
+#[derive(Parser, Debug)]
+#[clap(
+    name = "cargo ghinstall rollback",
+    bin_name = "cargo ghinstall rollback",
+    about = "Restore the previous version of an installed binary from its backup"
+)]
+pub struct RollbackArgs {
+    /// Installed binary to roll back
+    #[clap(value_name = "BIN")]
+    pub bin: String,
+
+    /// Directory to look for the binary in when no receipt records it
+    #[clap(short = 'd', long, default_value = "~/.cargo/bin")]
+    pub install_dir: String,
+
+    #[clap(flatten)]
+    pub receipts: ReceiptsArg,
+}
+
+impl RollbackArgs {
+    /// Parse the process arguments if they start with `rollback`, exiting on invalid ones
+    pub fn parse_if_requested() -> Option<Self> {
+        Self::parse_from_if_requested(std::env::args())
+    }
+
+    pub fn parse_from_if_requested(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        subcommand_args(args, "rollback").map(Self::parse_from)
+    }
+
+    /// Get the installation directory as PathBuf, expanding ~
+    pub fn install_dir(&self) -> PathBuf {
+        expand_tilde(&self.install_dir)
+    }
+}
+
+/// `cargo ghinstall releases`: list the releases of a repository
+#[derive(Parser, Debug)]
+#[clap(
+    name = "cargo ghinstall releases",
+    bin_name = "cargo ghinstall releases",
+    about = "List a repository's releases with their dates, flags and asset counts"
+)]
+pub struct ReleasesArgs {
+    /// Repository to list releases of
+    #[clap(value_name = "OWNER/REPO")]
+    pub repo: String,
+
+    /// Show at most this many releases, newest first
+    #[clap(short = 'n', long, default_value = "30")]
+    pub limit: usize,
+}
+
+impl ReleasesArgs {
+    /// Parse the process arguments if they start with `releases`, exiting on invalid ones
+    pub fn parse_if_requested() -> Option<Self> {
+        Self::parse_from_if_requested(std::env::args())
+    }
+
+    pub fn parse_from_if_requested(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        subcommand_args(args, "releases").map(Self::parse_from)
+    }
+
+    /// Split the repository into owner and name
+    pub fn parse_repo(&self) -> anyhow::Result<(String, String)> {
+        match self.repo.split_once('/') {
+            Some((owner, repo)) if !owner.is_empty() && !repo.is_empty() && !repo.contains('/') => {
+                Ok((owner.to_string(), repo.to_string()))
+            }
+            _ => Err(crate::error::GhInstallError::InvalidRepo {
                 input: self.repo.clone(),
             }
-            .into());
+            .into()),
+        }
+    }
+}
+
+/// `cargo ghinstall assets`: show a release's assets and which one would be installed
+#[derive(Parser, Debug)]
+#[clap(
+    name = "cargo ghinstall assets",
+    bin_name = "cargo ghinstall assets",
+    about = "List a release's assets and show which one would be installed, and why"
+)]
+pub struct AssetsArgs {
+    /// Repository and release to inspect (latest release without a tag)
+    #[clap(value_name = "OWNER/REPO[@TAG]")]
+    pub repo: String,
+
+    /// Release tag
+    #[clap(short, long)]
+    pub tag: Option<String>,
+
+    #[clap(flatten)]
+    pub selection: ReleaseSelection,
+
+    /// Binary name or pattern the install would ask for
+    #[clap(short, long)]
+    pub bin: Option<String>,
+}
+
+impl AssetsArgs {
+    /// Parse the process arguments if they start with `assets`, exiting on invalid ones
+    pub fn parse_if_requested() -> Option<Self> {
+        Self::parse_from_if_requested(std::env::args())
+    }
+
+    pub fn parse_from_if_requested(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        subcommand_args(args, "assets").map(Self::parse_from)
+    }
+
+    /// Parse repository string to extract owner, repo, and optional tag
+    pub fn parse_repo(&self) -> anyhow::Result<(String, String, Option<String>)> {
+        parse_repo_spec(&self.repo, self.tag.as_deref())
+    }
+
+    /// Get the target triple, defaulting to current platform
+    pub fn target(&self) -> String {
+        self.selection
+            .target
+            .clone()
+            .unwrap_or_else(|| host_target(false, false))
+    }
+}
+
+/// `cargo ghinstall run`: run a release binary without installing it
+#[derive(Parser, Debug)]
+#[clap(
+    name = "cargo ghinstall run",
+    bin_name = "cargo ghinstall run",
+    about = "Download a release binary into a cache and run it, without installing it"
+)]
+pub struct RunArgs {
+    /// Repository and release to run (latest release without a tag), then
+    /// the arguments passed to the binary
+    ///
+    /// Everything after the repository goes to the binary, options included.
+    #[clap(
+        value_name = "OWNER/REPO[@TAG] [ARGS]",
+        required = true,
+        num_args = 1..,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    pub command: Vec<String>,
+
+    /// Release tag
+    #[clap(short, long)]
+    pub tag: Option<String>,
+
+    #[clap(flatten)]
+    pub selection: ReleaseSelection,
+
+    /// Binary to run from a multi-binary release
+    #[clap(short, long)]
+    pub bin: Option<String>,
+
+    /// Skip checksum verification (not recommended)
+    #[clap(long)]
+    pub skip_checksum: bool,
+
+    /// Never prompt: take the first asset matching the target
+    #[clap(short, long)]
+    pub yes: bool,
+
+    /// Disable progress bars, logging download progress as plain lines instead
+    #[clap(long)]
+    pub no_progress: bool,
+
+    /// Directory downloaded binaries are cached in
+    #[clap(
+        long,
+        default_value = "~/.cache/ghinstall/run",
+        env = "CARGO_GHINSTALL_CACHE_DIR"
+    )]
+    pub cache_dir: PathBuf,
+}
+
+impl RunArgs {
+    /// Parse the process arguments if they start with `run`, exiting on invalid ones
+    pub fn parse_if_requested() -> Option<Self> {
+        Self::parse_from_if_requested(std::env::args())
+    }
+
+    pub fn parse_from_if_requested(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        subcommand_args(args, "run").map(Self::parse_from)
+    }
+
+    /// Get the cache directory as PathBuf, expanding ~
+    pub fn cache_path(&self) -> PathBuf {
+        self.cache_dir
+            .to_str()
+            .map(expand_tilde)
+            .unwrap_or_else(|| self.cache_dir.clone())
+    }
+
+    /// The repository to run from, `owner/repo[@tag]`
+    pub fn repo(&self) -> &str {
+        self.command.first().map_or("", String::as_str)
+    }
+
+    /// The arguments for the binary, without a `--` separating them
+    pub fn binary_args(&self) -> &[String] {
+        let args = self.command.get(1..).unwrap_or_default();
+        match args.first() {
+            Some(first) if first == "--" => &args[1..],
+            _ => args,
         }
+    }
 
-        let owner = parts[0].to_string();
-        let repo = parts[1].to_string();
+    /// The install arguments that fetch the same release asset
+    pub fn install_args(&self) -> Args {
+        let mut args = Args::parse_from(["cargo-ghinstall", self.repo(), "--no-fallback"]);
+        args.tag = self.tag.clone();
+        args.selection = self.selection.clone();
+        args.bin = self.bin.clone();
+        args.skip_checksum = self.skip_checksum;
+        args.yes = self.yes;
+        args.no_progress = self.no_progress;
+        args
+    }
+}
 
-        // Combine tag from @ notation or --tag flag
-        let final_tag = tag_part.or_else(|| self.tag.clone());
+/// The arguments from `name` on, when they start with that subcommand
+fn subcommand_args(args: impl IntoIterator<Item = String>, name: &str) -> Option<Vec<String>> {
+    let args = args.into_iter().collect::<Vec<_>>();
+    // Skip the binary, and `ghinstall` when run as `cargo ghinstall`
+    let start = if args.get(1).is_some_and(|arg| arg == "ghinstall") {
+        2
+    } else {
+        1
+    };
+    if args.get(start).is_none_or(|arg| arg != name) {
+        return None;
+    }
+    Some(args[start..].to_vec())
+}
 
-        Ok((owner, repo, final_tag))
+impl Args {
+    /// Parse repository string to extract owner, repo, and optional tag
+    pub fn parse_repo(&self) -> anyhow::Result<(String, String, Option<String>)> {
+        parse_repo_spec(
+            self.repo.as_deref().unwrap_or_default(),
+            self.tag.as_deref(),
+        )
     }
 
     /// Get the installation directory as PathBuf, expanding ~
@@ -123,26 +530,219 @@ impl Args {
 
     /// Get the target triple, defaulting to current platform
     pub fn target(&self) -> String {
-        self.target.clone().unwrap_or_else(|| {
-            // Construct target triple for current platform
-            let arch = std::env::consts::ARCH;
-            let os = std::env::consts::OS;
-
-            match (arch, os) {
-                ("x86_64", "linux") => "x86_64-unknown-linux-gnu",
-                ("x86_64", "macos") => "x86_64-apple-darwin",
-                ("x86_64", "windows") => "x86_64-pc-windows-msvc",
-                ("aarch64", "linux") => "aarch64-unknown-linux-gnu",
-                ("aarch64", "macos") => "aarch64-apple-darwin",
-                ("aarch64", "windows") => "aarch64-pc-windows-msvc",
-                _ => panic!("Unsupported platform: {arch}-{os}"),
-            }
-            .to_string()
-        })
+        self.selection
+            .target
+            .clone()
+            .unwrap_or_else(|| host_target(self.prefer_musl, self.prefer_gnu))
+    }
+
+    /// The target to fall back to when no asset matches this one: -gnu for
+    /// the -musl target picked for a glibc host, and x86_64 macOS on Apple
+    /// Silicon with Rosetta installed
+    pub fn fallback_target(&self) -> Option<String> {
+        if self.selection.target.is_some() {
+            return None;
+        }
+        let target = self.target();
+        if let (Some(triple), Some(crate::utils::HostLibc::Glibc(_))) =
+            (target.strip_suffix("-musl"), crate::utils::host_libc())
+        {
+            return Some(format!("{triple}-gnu"));
+        }
+        (target == "aarch64-apple-darwin" && !self.no_arch_fallback && crate::utils::has_rosetta())
+            .then(|| "x86_64-apple-darwin".to_string())
+    }
+}
+
+/// The install receipts file, shared by install, `outdated` and `rollback`
+#[derive(clap::Args, Debug, Clone)]
+pub struct ReceiptsArg {
+    /// Install receipts file, recording what each install put where
+    #[clap(
+        long,
+        default_value = "~/.config/ghinstall/installed.toml",
+        env = "CARGO_GHINSTALL_RECEIPTS"
+    )]
+    pub receipts: PathBuf,
+}
+
+impl ReceiptsArg {
+    /// Get the receipts file path as PathBuf, expanding ~
+    pub fn receipts_path(&self) -> PathBuf {
+        self.receipts
+            .to_str()
+            .map(expand_tilde)
+            .unwrap_or_else(|| self.receipts.clone())
+    }
+
+    /// Where --backup keeps replaced binaries: `backups` next to the receipts
+    pub fn backup_dir(&self) -> PathBuf {
+        self.receipts_path().with_file_name("backups")
+    }
+}
+
+/// Which release and asset to take, shared by install, `assets` and `run`
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ReleaseSelection {
+    /// Let the latest release be a prerelease
+    #[clap(long)]
+    pub pre: bool,
+
+    /// Only consider tags starting with this (e.g. cli-v), for monorepos
+    /// tagging each package separately
+    #[clap(long, value_name = "PREFIX")]
+    pub tag_prefix: Option<String>,
+
+    /// Take the newest release published before this date (2024-06-01) or
+    /// time (2024-06-01T12:00:00Z)
+    #[clap(long, value_name = "DATE", conflicts_with = "at")]
+    pub before: Option<ReleaseDate>,
+
+    /// Take the release that was current at this date (2024-06-01, the
+    /// end of that day) or time (2024-06-01T12:00:00Z)
+    #[clap(long, value_name = "DATE")]
+    pub at: Option<ReleaseDate>,
+
+    /// Take the asset matching this glob, or regex between slashes
+    /// (/musl\.tar\.gz$/), instead of the one matching the target
+    #[clap(long, value_name = "PATTERN", value_parser = parse_asset_pattern)]
+    pub asset: Option<String>,
+
+    /// Target platform triple (e.g., aarch64-apple-darwin)
+    #[clap(short = 'T', long)]
+    pub target: Option<String>,
+}
+
+impl ReleaseSelection {
+    /// The time releases must be published before, from --before or --at
+    pub fn published_before(&self) -> Option<DateTime<Utc>> {
+        published_before(self.before, self.at)
+    }
+}
+
+/// A `--before` or `--at` date: a whole day or an RFC 3339 time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseDate {
+    Day(NaiveDate),
+    Time(DateTime<Utc>),
+}
+
+impl ReleaseDate {
+    /// The start of the day, or the time
+    pub fn start(&self) -> DateTime<Utc> {
+        match self {
+            Self::Day(day) => day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+            Self::Time(time) => *time,
+        }
+    }
+
+    /// The first moment after the day, or after the time at the second
+    /// granularity of GitHub's timestamps
+    pub fn end(&self) -> DateTime<Utc> {
+        match self {
+            Self::Day(day) => Self::Day(day.succ_opt().unwrap_or(*day)).start(),
+            Self::Time(time) => *time + chrono::Duration::seconds(1),
+        }
+    }
+}
+
+impl FromStr for ReleaseDate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(day) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Ok(Self::Day(day));
+        }
+        DateTime::parse_from_rfc3339(s)
+            .map(|time| Self::Time(time.with_timezone(&Utc)))
+            .map_err(|_| {
+                format!("expected a date (2024-06-01) or RFC 3339 time (2024-06-01T12:00:00Z), got '{s}'")
+            })
+    }
+}
+
+/// Releases must be published before the start of `before`, or up to the
+/// end of `at`
+fn published_before(before: Option<ReleaseDate>, at: Option<ReleaseDate>) -> Option<DateTime<Utc>> {
+    before
+        .map(|date| date.start())
+        .or_else(|| at.map(|date| date.end()))
+}
+
+/// Check an `--asset` pattern, keeping it as written
+fn parse_asset_pattern(pattern: &str) -> Result<String, String> {
+    crate::github::AssetPattern::parse(pattern)
+        .map(|_| pattern.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Check an `--as` name, which must be a file name rather than a path
+fn parse_binary_name(name: &str) -> Result<String, String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        return Err(format!("'{name}' is not a file name"));
+    }
+    Ok(name.to_string())
+}
+
+/// Split `owner/repo[@tag]` into its parts; `tag` (from --tag) is used when
+/// the spec has none
+fn parse_repo_spec(
+    spec: &str,
+    tag: Option<&str>,
+) -> anyhow::Result<(String, String, Option<String>)> {
+    // Check if tag is specified with @
+    let (repo_part, tag_part) = if let Some(idx) = spec.rfind('@') {
+        let repo = &spec[..idx];
+        let tag = &spec[idx + 1..];
+        (repo, Some(tag.to_string()))
+    } else {
+        (spec, None)
+    };
+
+    // Split owner/repo
+    let parts: Vec<&str> = repo_part.split('/').collect();
+    if parts.len() != 2 {
+        return Err(crate::error::GhInstallError::InvalidRepo {
+            input: spec.to_string(),
+        }
+        .into());
+    }
+
+    let owner = parts[0].to_string();
+    let repo = parts[1].to_string();
+
+    // Combine tag from @ notation or --tag flag
+    let final_tag = tag_part.or_else(|| tag.map(String::from));
+
+    Ok((owner, repo, final_tag))
+}
+
+/// The host's target triple; on Linux the -musl one on musl hosts, on glibc
+/// too old for -gnu builds, and when `prefer_musl` asks for it; on Windows
+/// the -gnu one in MSYS2 and Cygwin shells and when `prefer_gnu` asks for it
+fn host_target(prefer_musl: bool, prefer_gnu: bool) -> String {
+    let arch = std::env::consts::ARCH;
+    let os = std::env::consts::OS;
+
+    let target = match (arch, os) {
+        ("x86_64", "linux") => "x86_64-unknown-linux-gnu",
+        ("x86_64", "macos") => "x86_64-apple-darwin",
+        ("x86_64", "windows") => "x86_64-pc-windows-msvc",
+        ("aarch64", "linux") => "aarch64-unknown-linux-gnu",
+        ("aarch64", "macos") => "aarch64-apple-darwin",
+        ("aarch64", "windows") => "aarch64-pc-windows-msvc",
+        _ => panic!("Unsupported platform: {arch}-{os}"),
+    };
+    let musl = crate::utils::host_libc().is_some_and(|libc| prefer_musl || libc.prefers_musl());
+    let gnu = os == "windows" && (prefer_gnu || crate::utils::in_msys_shell());
+    match (target.strip_suffix("-gnu"), target.strip_suffix("-msvc")) {
+        (Some(triple), _) if musl => format!("{triple}-musl"),
+        (_, Some(triple)) if gnu => format!("{triple}-gnu"),
+        _ => target.to_string(),
     }
 }
 
-fn expand_tilde(path: &str) -> PathBuf {
+pub(crate) fn expand_tilde(path: &str) -> PathBuf {
     if path.starts_with("~") {
         if let Some(home) = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf()) {
             let rest = path.strip_prefix("~").unwrap_or(path);
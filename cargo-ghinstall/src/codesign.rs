@@ -0,0 +1,180 @@
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+/// How a macOS binary is signed, from `codesign` and Gatekeeper's `spctl`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Signature {
+    /// Signed with a Developer ID and notarized by Apple
+    Notarized {
+        authority: String,
+    },
+    /// Signed with a certificate, but not accepted by Gatekeeper
+    Signed {
+        authority: String,
+    },
+    /// Signed without an identity, as the linker does on Apple Silicon
+    AdHoc,
+    Unsigned,
+    /// Carries a signature that doesn't match the binary, as after tampering
+    Invalid {
+        reason: String,
+    },
+}
+
+impl Signature {
+    /// Whether the signature identifies who built the binary
+    pub fn is_trusted(&self) -> bool {
+        matches!(self, Self::Notarized { .. } | Self::Signed { .. })
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Notarized { authority } => write!(f, "signed by {authority} and notarized"),
+            Self::Signed { authority } => write!(f, "signed by {authority}, not notarized"),
+            Self::AdHoc => write!(f, "ad-hoc signed, with no identity"),
+            Self::Unsigned => write!(f, "unsigned"),
+            Self::Invalid { reason } => write!(f, "invalid signature ({reason})"),
+        }
+    }
+}
+
+/// Assess the signature of the binary at `path` with `codesign` and `spctl`
+pub fn assess(path: &Path) -> Result<Signature> {
+    // `--display` only prints the embedded signature; `--verify` checks it
+    // against the binary's contents
+    let verify = Command::new("codesign")
+        .args(["--verify", "--strict", "--verbose=2"])
+        .arg(path)
+        .output()
+        .context("Failed to run codesign")?;
+    if !verify.status.success() {
+        let reason = String::from_utf8_lossy(&verify.stderr);
+        return Ok(parse_verify_failure(&reason));
+    }
+
+    let codesign = Command::new("codesign")
+        .args(["--display", "--verbose=2"])
+        .arg(path)
+        .output()
+        .context("Failed to run codesign")?;
+    // codesign describes the signature on stderr
+    let details = String::from_utf8_lossy(&codesign.stderr);
+    let Some(authority) = parse_codesign(&details) else {
+        return Ok(Signature::AdHoc);
+    };
+
+    // `--type execute` rejects anything outside an app bundle, and `install`
+    // is for installer packages; a bare Mach-O binary is assessed as a file
+    // opened on its own, by its primary signature
+    let spctl = Command::new("spctl")
+        .args(["--assess", "--verbose=2", "--type", "open"])
+        .args(["--context", "context:primary-signature"])
+        .arg(path)
+        .output()
+        .context("Failed to run spctl")?;
+    let assessment = String::from_utf8_lossy(&spctl.stderr);
+    Ok(
+        if spctl.status.success() && assessment.contains("source=Notarized") {
+            Signature::Notarized { authority }
+        } else {
+            Signature::Signed { authority }
+        },
+    )
+}
+
+/// The signature of a binary `codesign --verify` rejected with `reason`:
+/// unsigned when there is no signature at all, otherwise invalid
+fn parse_verify_failure(reason: &str) -> Signature {
+    if reason.contains("not signed at all") {
+        return Signature::Unsigned;
+    }
+    let reason = reason
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(|line| {
+            // Drop the `<path>: ` codesign starts its messages with
+            line.split_once(": ").map_or(line, |(_, message)| message)
+        })
+        .unwrap_or("codesign --verify failed");
+    Signature::Invalid {
+        reason: reason.to_string(),
+    }
+}
+
+/// The signing identity in `codesign --display` output: the first
+/// `Authority=` line, `None` for ad-hoc and unsigned binaries
+fn parse_codesign(details: &str) -> Option<String> {
+    details
+        .lines()
+        .find_map(|line| line.strip_prefix("Authority="))
+        .map(|authority| authority.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_codesign() {
+        let signed = "Executable=/tmp/tool\n\
+            Identifier=tool\n\
+            Format=Mach-O thin (arm64)\n\
+            Authority=Developer ID Application: Example Corp (ABCDE12345)\n\
+            Authority=Developer ID Certification Authority\n\
+            Authority=Apple Root CA\n\
+            Timestamp=1 Jun 2024 at 12:00:00\n";
+        assert_eq!(
+            parse_codesign(signed).as_deref(),
+            Some("Developer ID Application: Example Corp (ABCDE12345)")
+        );
+
+        let ad_hoc = "Executable=/tmp/tool\n\
+            Identifier=tool-55554944\n\
+            Signature=adhoc\n\
+            TeamIdentifier=not set\n";
+        assert_eq!(parse_codesign(ad_hoc), None);
+        assert_eq!(
+            parse_codesign("/tmp/tool: code object is not signed at all\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_verify_failure() {
+        assert_eq!(
+            parse_verify_failure("/tmp/tool: code object is not signed at all\n"),
+            Signature::Unsigned
+        );
+        let tampered = parse_verify_failure(
+            "/tmp/tool: invalid signature (code or signature have been modified)\n\
+             In architecture: arm64\n",
+        );
+        assert_eq!(
+            tampered,
+            Signature::Invalid {
+                reason: "invalid signature (code or signature have been modified)".to_string()
+            }
+        );
+        assert!(!tampered.is_trusted());
+    }
+
+    #[test]
+    fn test_signature() {
+        let authority = "Developer ID Application: Example Corp (ABCDE12345)".to_string();
+        assert!(Signature::Notarized {
+            authority: authority.clone()
+        }
+        .is_trusted());
+        assert!(!Signature::AdHoc.is_trusted());
+        assert_eq!(
+            Signature::Signed { authority }.to_string(),
+            "signed by Developer ID Application: Example Corp (ABCDE12345), not notarized"
+        );
+        assert_eq!(Signature::Unsigned.to_string(), "unsigned");
+    }
+}
@@ -1,18 +1,22 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use crate::validate;
+
 #[derive(Debug, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     #[serde(default)]
     pub default: DefaultConfig,
 
-    #[serde(default)]
+    #[serde(default, deserialize_with = "validate::repo_table")]
     pub repo: HashMap<String, RepoConfig>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct DefaultConfig {
     #[serde(alias = "install-dir")]
     #[serde(default = "default_install_dir")]
@@ -20,6 +24,33 @@ pub struct DefaultConfig {
 
     #[serde(default = "default_timeout")]
     pub timeout: u64,
+
+    /// Choose releases from a list, as with --interactive
+    #[serde(default)]
+    pub interactive: bool,
+
+    /// On Linux, prefer -musl builds, as with --prefer-musl
+    #[serde(alias = "prefer-musl")]
+    #[serde(default)]
+    pub prefer_musl: bool,
+
+    /// On Windows, prefer -gnu builds, as with --prefer-gnu
+    #[serde(alias = "prefer-gnu")]
+    #[serde(default)]
+    pub prefer_gnu: bool,
+
+    /// Install completions and man pages, as with --extras
+    #[serde(default)]
+    pub extras: bool,
+
+    /// Refuse unsigned macOS binaries, as with --require-signed
+    #[serde(alias = "require-signed")]
+    #[serde(default)]
+    pub require_signed: bool,
+
+    /// Keep replaced binaries for `rollback`, as with --backup
+    #[serde(default)]
+    pub backup: bool,
 }
 
 impl Default for DefaultConfig {
@@ -27,17 +58,46 @@ impl Default for DefaultConfig {
         Self {
             install_dir: default_install_dir(),
             timeout: default_timeout(),
+            interactive: false,
+            prefer_musl: false,
+            prefer_gnu: false,
+            extras: false,
+            require_signed: false,
+            backup: false,
         }
     }
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct RepoConfig {
     pub bin: Option<String>,
+    /// Binaries to install by name, for repositories shipping several
+    pub bins: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "validate::optional_targets")]
     pub targets: Option<Vec<String>>,
     #[serde(alias = "verify-signature")]
     #[serde(default)]
     pub verify_signature: bool,
+    /// Key file or URL trusted to sign releases, as with --signing-key
+    #[serde(alias = "signing-key")]
+    pub signing_key: Option<String>,
+    /// Require build provenance from the repository, as with --require-provenance
+    #[serde(alias = "require-provenance")]
+    #[serde(default)]
+    pub require_provenance: bool,
+    /// Workflow file the provenance must name, as with --provenance-workflow
+    #[serde(alias = "provenance-workflow")]
+    pub provenance_workflow: Option<String>,
+    /// Let the latest release be a prerelease
+    #[serde(default)]
+    pub pre: bool,
+    /// Only consider tags starting with this, for monorepos tagging per package
+    #[serde(alias = "tag-prefix")]
+    pub tag_prefix: Option<String>,
+    /// Asset glob, or regex between slashes, overriding target matching
+    #[serde(default, deserialize_with = "validate::optional_asset_pattern")]
+    pub asset: Option<String>,
 }
 
 fn default_install_dir() -> String {
@@ -56,8 +116,8 @@ impl Config {
         }
 
         let content = std::fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
-        Ok(config)
+        validate::parse(&content)
+            .with_context(|| format!("Invalid configuration in {}", path.display()))
     }
 
     /// Get the default configuration file path
@@ -80,16 +140,48 @@ impl Config {
         if args.install_dir == "~/.cargo/bin" && self.default.install_dir != "~/.cargo/bin" {
             args.install_dir = self.default.install_dir.clone();
         }
+        args.interactive |= self.default.interactive;
+        args.prefer_musl |= self.default.prefer_musl;
+        args.prefer_gnu |= self.default.prefer_gnu;
+        args.extras |= self.default.extras;
+        args.require_signed |= self.default.require_signed;
+        args.backup |= self.default.backup;
 
         // Apply repository-specific configuration
         if let Some(repo_config) = self.get_repo_config(owner, repo) {
             if args.bin.is_none() && repo_config.bin.is_some() {
                 args.bin = repo_config.bin.clone();
             }
+            if args.bin.is_none() && !args.bins {
+                if let Some(bins) = &repo_config.bins {
+                    args.bin_names = bins.clone();
+                }
+            }
 
             if !args.verify_signature && repo_config.verify_signature {
                 args.verify_signature = true;
             }
+
+            if args.signing_key.is_none() {
+                args.signing_key = repo_config.signing_key.clone();
+            }
+
+            args.require_provenance |= repo_config.require_provenance;
+            if args.provenance_workflow.is_none() {
+                args.provenance_workflow = repo_config.provenance_workflow.clone();
+            }
+
+            if !args.selection.pre && repo_config.pre {
+                args.selection.pre = true;
+            }
+
+            if args.selection.tag_prefix.is_none() {
+                args.selection.tag_prefix = repo_config.tag_prefix.clone();
+            }
+
+            if args.selection.asset.is_none() {
+                args.selection.asset = repo_config.asset.clone();
+            }
         }
     }
 }
@@ -109,11 +201,25 @@ mod tests {
 [default]
 install-dir = "/usr/local/bin"
 timeout = 60
+prefer-musl = true
+prefer-gnu = true
+extras = true
+require-signed = true
+backup = true
 
 [repo."owner/repo"]
 bin = "mybin"
 targets = ["x86_64-unknown-linux-gnu"]
 verify-signature = true
+signing-key = "~/.config/ghinstall/keys/owner.asc"
+require-provenance = true
+provenance-workflow = "release.yml"
+pre = true
+tag-prefix = "cli-v"
+asset = "/linux-musl\\.tar\\.gz$/"
+
+[repo."owner/tools"]
+bins = ["tool", "tool-helper"]
 "#;
 
         fs::write(&config_path, config_content).unwrap();
@@ -122,10 +228,62 @@ verify-signature = true
 
         assert_eq!(config.default.install_dir, "/usr/local/bin");
         assert_eq!(config.default.timeout, 60);
+        assert!(config.default.prefer_musl);
+        assert!(config.default.prefer_gnu);
+        assert!(config.default.extras);
+        assert!(config.default.require_signed);
+        assert!(config.default.backup);
 
         let repo_config = config.get_repo_config("owner", "repo").unwrap();
         assert_eq!(repo_config.bin, Some("mybin".to_string()));
         assert!(repo_config.verify_signature);
+        assert_eq!(
+            repo_config.signing_key.as_deref(),
+            Some("~/.config/ghinstall/keys/owner.asc")
+        );
+        assert!(repo_config.require_provenance);
+        assert_eq!(
+            repo_config.provenance_workflow.as_deref(),
+            Some("release.yml")
+        );
+        assert!(repo_config.pre);
+        assert_eq!(repo_config.tag_prefix.as_deref(), Some("cli-v"));
+        assert_eq!(
+            repo_config.asset.as_deref(),
+            Some(r"/linux-musl\.tar\.gz$/")
+        );
+
+        let tools = config.get_repo_config("owner", "tools").unwrap();
+        assert_eq!(
+            tools.bins.as_deref(),
+            Some(&["tool".to_string(), "tool-helper".to_string()][..])
+        );
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_keys() {
+        let dir = tempdir().unwrap();
+        let config_path = dir.path().join("test.toml");
+
+        fs::write(&config_path, "[default]\ninstal_dir = \"/opt/bin\"\n").unwrap();
+        let error = format!("{:#}", Config::load(&config_path).unwrap_err());
+        assert!(error.contains("line 2, column 1"), "{error}");
+        assert!(error.contains("did you mean `install_dir`?"), "{error}");
+
+        fs::write(
+            &config_path,
+            "[repo.\"owner/repo\"]\ntargets = [\"x86_64-unknown-linux-gun\"]\n",
+        )
+        .unwrap();
+        let error = format!("{:#}", Config::load(&config_path).unwrap_err());
+        assert!(
+            error.contains("did you mean `x86_64-unknown-linux-gnu`?"),
+            "{error}"
+        );
+
+        fs::write(&config_path, "[repo.ripgrep]\nbin = \"rg\"\n").unwrap();
+        let error = format!("{:#}", Config::load(&config_path).unwrap_err());
+        assert!(error.contains("owner/repo"), "{error}");
     }
 
     #[test]
@@ -35,11 +35,12 @@ pub enum GhInstallError {
     #[error("Installation failed: {message}. Path: {path}")]
     Installation { message: String, path: String },
 
-    #[error("Signature verification failed for {file}. Signature file: {sig_file}")]
-    SignatureVerification { file: String, sig_file: String },
-
-    #[error("Signature verification is not implemented for {file}. Refusing to trust signature file: {sig_file}")]
-    SignatureVerificationUnsupported { file: String, sig_file: String },
+    #[error("Signature verification failed for {file}. Signature file: {sig_file}: {reason}")]
+    SignatureVerification {
+        file: String,
+        sig_file: String,
+        reason: String,
+    },
 
     #[error("Checksum verification failed for {file}: expected {expected}, got {actual}")]
     ChecksumVerification {
@@ -54,7 +55,7 @@ pub enum GhInstallError {
     #[error("Archive extraction failed for {file}: {reason}. Supported formats: .tar.gz, .tgz, .zip, .tar.xz, .tar.bz2")]
     ArchiveExtraction { file: String, reason: String },
 
-    #[error("No checksum file found in release. Expected one of: SHA256SUMS, checksums.txt, sha256sums.txt")]
+    #[error("No checksum file found in release. Expected one of: SHA256SUMS, checksums.txt, sha256sums.txt, SHA512SUMS, B3SUMS, or <asset>.sha256 (.sha512, .b3)")]
     ChecksumFileNotFound,
 
     #[error("Failed to download {asset} from {url}: HTTP {status} - {message}")]
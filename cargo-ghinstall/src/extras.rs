@@ -0,0 +1,239 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A shell completion script or man page shipped next to a binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extra {
+    Bash,
+    Zsh,
+    Fish,
+    /// A man page of this section
+    Man(u8),
+}
+
+/// A companion file found in an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtraFile {
+    pub kind: Extra,
+    pub source: PathBuf,
+    /// The command it is for, matched against the installed binaries
+    pub command: String,
+    /// The file name it is installed as
+    pub file_name: String,
+}
+
+/// The kind, command and installed file name of a companion file, from its
+/// name or, for completions without a telling name, the shell directory it
+/// is in (`completions/bash/tool`)
+fn classify(path: &Path) -> Option<(Extra, String, String)> {
+    let name = path.file_name()?.to_str()?;
+    let shell_dir = path
+        .parent()
+        .filter(|dir| {
+            dir.parent()
+                .and_then(|dir| dir.file_name()?.to_str())
+                .is_some_and(|dir| dir.contains("complet"))
+        })
+        .and_then(|dir| dir.file_name()?.to_str());
+
+    if let Some(command) = name
+        .strip_suffix(".bash")
+        .or_else(|| name.strip_suffix(".bash-completion"))
+    {
+        return Some((Extra::Bash, command.to_string(), command.to_string()));
+    }
+    if let Some(command) = name.strip_suffix(".fish") {
+        return Some((Extra::Fish, command.to_string(), name.to_string()));
+    }
+    if let Some(command) = name.strip_suffix(".zsh") {
+        let command = command.trim_start_matches('_');
+        return Some((Extra::Zsh, command.to_string(), format!("_{command}")));
+    }
+    if let Some(command) = name.strip_prefix('_').filter(|c| !c.contains('.')) {
+        return Some((Extra::Zsh, command.to_string(), name.to_string()));
+    }
+    if let Some(extra) = man_page(name) {
+        return Some(extra);
+    }
+    match shell_dir? {
+        _ if name.contains('.') => None,
+        "bash" => Some((Extra::Bash, name.to_string(), name.to_string())),
+        "zsh" => Some((Extra::Zsh, name.to_string(), format!("_{name}"))),
+        "fish" => Some((Extra::Fish, name.to_string(), format!("{name}.fish"))),
+        _ => None,
+    }
+}
+
+/// A man page named `tool.1` or `tool-sub.1.gz`
+fn man_page(name: &str) -> Option<(Extra, String, String)> {
+    let page = name.strip_suffix(".gz").unwrap_or(name);
+    let (command, section) = page.rsplit_once('.')?;
+    let section = match section.as_bytes() {
+        [digit @ b'1'..=b'9'] => digit - b'0',
+        _ => return None,
+    };
+    (!command.is_empty()).then(|| (Extra::Man(section), command.to_string(), name.to_string()))
+}
+
+/// The companion files in an extracted archive for the installed `binaries`:
+/// completions of the command, and man pages of it and its `tool-sub` pages
+pub fn find(dir: &Path, binaries: &[&str]) -> Vec<ExtraFile> {
+    let mut extras = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let (kind, command, file_name) = classify(entry.path())?;
+            let for_binary = binaries.iter().any(|bin| {
+                command == *bin
+                    || (matches!(kind, Extra::Man(_))
+                        && command
+                            .strip_prefix(bin)
+                            .is_some_and(|rest| rest.starts_with('-')))
+            });
+            for_binary.then(|| ExtraFile {
+                kind,
+                source: entry.into_path(),
+                command,
+                file_name,
+            })
+        })
+        .collect::<Vec<_>>();
+    extras.sort_by(|a, b| a.source.cmp(&b.source));
+    extras
+}
+
+/// Where a companion file of `kind` goes: the XDG user directories bash-completion,
+/// fish and man look in, and zsh's conventional site-functions directory
+fn install_dir(kind: Extra) -> Option<PathBuf> {
+    let home = directories::BaseDirs::new()?.home_dir().to_path_buf();
+    let xdg = |var: &str, default: &str| {
+        std::env::var_os(var)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(default))
+    };
+    let data = xdg("XDG_DATA_HOME", ".local/share");
+    Some(match kind {
+        Extra::Bash => data.join("bash-completion").join("completions"),
+        Extra::Zsh => data.join("zsh").join("site-functions"),
+        Extra::Fish => xdg("XDG_CONFIG_HOME", ".config")
+            .join("fish")
+            .join("completions"),
+        Extra::Man(section) => data.join("man").join(format!("man{section}")),
+    })
+}
+
+/// Install the companion files of the installed `binaries`, returning the
+/// paths written for the receipt
+pub fn install(dir: &Path, binaries: &[&str]) -> Result<Vec<PathBuf>> {
+    let mut installed = Vec::new();
+    for extra in find(dir, binaries) {
+        let Some(dest_dir) = install_dir(extra.kind) else {
+            continue;
+        };
+        fs::create_dir_all(&dest_dir)
+            .with_context(|| format!("Failed to create {}", dest_dir.display()))?;
+        let dest = dest_dir.join(&extra.file_name);
+        fs::copy(&extra.source, &dest)
+            .with_context(|| format!("Failed to install {}", dest.display()))?;
+        tracing::info!("Installed {}", dest.display());
+        if extra.kind == Extra::Zsh && !on_fpath(&dest_dir) {
+            println!(
+                "zsh doesn't search {} by default; add this line before `compinit` in ~/.zshrc:\n\n    {}\n",
+                dest_dir.display(),
+                fpath_line(&dest_dir)
+            );
+        }
+        installed.push(dest);
+    }
+    Ok(installed)
+}
+
+/// Whether `dir` is on zsh's `fpath`, as far as an exported `FPATH` tells
+fn on_fpath(dir: &Path) -> bool {
+    std::env::var_os("FPATH")
+        .is_some_and(|fpath| std::env::split_paths(&fpath).any(|entry| entry == dir))
+}
+
+/// The `~/.zshrc` line that puts `dir` on zsh's `fpath`
+fn fpath_line(dir: &Path) -> String {
+    format!("fpath=(\"{}\" $fpath)", dir.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_classify() {
+        let classify = |path: &str| classify(Path::new(path));
+        assert_eq!(
+            classify("complete/rg.bash"),
+            Some((Extra::Bash, "rg".to_string(), "rg".to_string()))
+        );
+        assert_eq!(
+            classify("complete/_rg"),
+            Some((Extra::Zsh, "rg".to_string(), "_rg".to_string()))
+        );
+        assert_eq!(
+            classify("complete/rg.fish"),
+            Some((Extra::Fish, "rg".to_string(), "rg.fish".to_string()))
+        );
+        assert_eq!(
+            classify("completions/zsh/tool"),
+            Some((Extra::Zsh, "tool".to_string(), "_tool".to_string()))
+        );
+        assert_eq!(
+            classify("doc/rg.1"),
+            Some((Extra::Man(1), "rg".to_string(), "rg.1".to_string()))
+        );
+        assert_eq!(
+            classify("man/tool-sub.5.gz"),
+            Some((
+                Extra::Man(5),
+                "tool-sub".to_string(),
+                "tool-sub.5.gz".to_string()
+            ))
+        );
+        assert_eq!(classify("complete/_rg.ps1"), None);
+        assert_eq!(classify("README.md"), None);
+        assert_eq!(classify("tool-1.2.3/tool"), None);
+        assert_eq!(classify("lib/bash/tool"), None);
+    }
+
+    #[test]
+    fn test_fpath_line() {
+        let dir = Path::new("/home/user/.local/share/zsh/site-functions");
+        assert_eq!(
+            fpath_line(dir),
+            "fpath=(\"/home/user/.local/share/zsh/site-functions\" $fpath)"
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        let dir = tempdir().unwrap();
+        for path in [
+            "tool/complete/tool.bash",
+            "tool/complete/_tool",
+            "tool/complete/other.fish",
+            "tool/doc/tool.1",
+            "tool/doc/tool-sub.1",
+            "tool/doc/toolbox.1",
+            "tool/tool",
+        ] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let found = find(dir.path(), &["tool"])
+            .into_iter()
+            .map(|extra| extra.file_name)
+            .collect::<Vec<_>>();
+        assert_eq!(found, ["_tool", "tool", "tool-sub.1", "tool.1"]);
+        assert!(find(dir.path(), &["missing"]).is_empty());
+    }
+}
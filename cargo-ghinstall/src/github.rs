@@ -1,18 +1,78 @@
+use crate::auth;
 use crate::error::{GhInstallError, Result as GhResult};
+use crate::matcher;
+use crate::progress::Progress;
 use crate::retry::{with_retry, RetryConfig};
+use crate::utils;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use octocrab::{models::repos::Release, Octocrab};
+use regex::Regex;
 use reqwest::Client;
+use semver::{Version, VersionReq};
 
+/// How many of the newest releases a version requirement, or a release date,
+/// is matched against
+const REQUIREMENT_SEARCH_LIMIT: usize = 1000;
+
+/// How many of the newest releases are searched for the latest release when
+/// the latest-release endpoint can't be used
+const LATEST_SEARCH_LIMIT: usize = 100;
+
+/// The tag standing for the newest prerelease
+pub const LATEST_PRERELEASE: &str = "latest-pre";
+
+/// Which releases the newest release is picked from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Stable,
+    /// Stable releases and prereleases
+    Any,
+    Prerelease,
+}
+
+/// Which releases the latest release and version requirements resolve among
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReleaseFilter {
+    /// Only tags starting with this, as in monorepos tagging per package
+    pub tag_prefix: Option<String>,
+    /// Only releases published before this
+    pub published_before: Option<DateTime<Utc>>,
+}
+
+impl ReleaseFilter {
+    /// Whether the release is published, has the tag prefix and is old enough
+    pub fn admits(&self, release: &Release) -> bool {
+        let published = release.published_at.or(release.created_at);
+        !release.draft
+            && strip_tag_prefix(&release.tag_name, self.tag_prefix.as_deref()).is_some()
+            && self
+                .published_before
+                .is_none_or(|before| published.is_some_and(|published| published < before))
+    }
+
+    /// Whether the latest-release endpoint can't answer for this filter
+    fn is_set(&self) -> bool {
+        self.tag_prefix.is_some() || self.published_before.is_some()
+    }
+}
+
+#[derive(Clone)]
 pub struct GitHubClient {
     octocrab: Octocrab,
     http_client: Client,
     retry_config: RetryConfig,
+    /// Show download progress (a bar on a terminal, log lines otherwise)
+    progress: bool,
+    /// Let the latest release be a prerelease
+    prereleases: bool,
+    /// Releases the latest release and version requirements resolve among
+    filter: ReleaseFilter,
 }
 
 impl GitHubClient {
     pub fn new() -> Result<Self> {
-        let octocrab = if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        let octocrab = if let Some((token, _)) = auth::find_token() {
             Octocrab::builder().personal_token(token).build()?
         } else {
             Octocrab::builder().build()?
@@ -27,6 +87,9 @@ impl GitHubClient {
             octocrab,
             http_client,
             retry_config: RetryConfig::default(),
+            progress: true,
+            prereleases: false,
+            filter: ReleaseFilter::default(),
         })
     }
 
@@ -37,13 +100,72 @@ impl GitHubClient {
         Ok(client)
     }
 
+    /// Turn download progress reporting on or off
+    pub fn with_progress(mut self, enabled: bool) -> Self {
+        self.progress = enabled;
+        self
+    }
+
+    /// Let the latest release be a prerelease
+    pub fn with_prereleases(mut self, enabled: bool) -> Self {
+        self.prereleases = enabled;
+        self
+    }
+
+    /// Resolve the latest release and version requirements among the tags
+    /// starting with `prefix` (e.g. `cli-v`)
+    pub fn with_tag_prefix(mut self, prefix: Option<String>) -> Self {
+        self.filter.tag_prefix = prefix;
+        self
+    }
+
+    /// Resolve the latest release and version requirements among the
+    /// releases published before `before`
+    pub fn with_published_before(mut self, before: Option<DateTime<Utc>>) -> Self {
+        self.filter.published_before = before;
+        self
+    }
+
     /// Fetch release by tag or get latest release
+    ///
+    /// A version requirement such as `^1.2` resolves to the highest stable
+    /// release it matches, and `latest-pre` to the newest prerelease. With a
+    /// tag prefix or publication date, only the releases passing that filter
+    /// are considered; exact tags are fetched as they are.
     pub async fn get_release(
         &self,
         owner: &str,
         repo: &str,
         tag: Option<&str>,
     ) -> GhResult<Release> {
+        if let Some((tag, requirement)) = tag.and_then(|t| Some((t, version_requirement(t)?))) {
+            match self
+                .resolve_requirement(owner, repo, tag, &requirement)
+                .await
+            {
+                Ok(release) => return Ok(release),
+                // A repository may tag its releases `1` or `1.4` itself
+                Err(e) if is_partial_version(tag) => {
+                    tracing::debug!("{}; looking for the tag {} instead", e, tag)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if tag == Some(LATEST_PRERELEASE) {
+            return self.resolve_newest(owner, repo, Channel::Prerelease).await;
+        }
+        if tag.is_none() && self.prereleases {
+            return self.resolve_newest(owner, repo, Channel::Any).await;
+        }
+        if tag.is_none() && self.filter.is_set() {
+            return self.resolve_newest(owner, repo, Channel::Stable).await;
+        }
+        if let (Some(tag), Some(before)) = (tag, self.filter.published_before) {
+            tracing::warn!(
+                "Installing {tag} as tagged, whether or not it was published before {before}"
+            );
+        }
+
         let owner_clone = owner.to_string();
         let repo_clone = repo.to_string();
         let tag_clone = tag.map(|t| t.to_string());
@@ -94,40 +216,199 @@ impl GitHubClient {
         })
     }
 
+    /// The highest stable release matching `requirement`, written as `tag`
+    async fn resolve_requirement(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        requirement: &VersionReq,
+    ) -> GhResult<Release> {
+        let not_found = || GhInstallError::ReleaseNotFound {
+            tag: tag.to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        };
+        let releases = self
+            .list_releases(owner, repo, REQUIREMENT_SEARCH_LIMIT)
+            .await
+            .map_err(|e| {
+                tracing::error!("Resolving '{}' for {}/{}: {}", tag, owner, repo, e);
+                not_found()
+            })?;
+        // A release tagged exactly as written wins, e.g. one tagged `1.4`
+        let release = releases
+            .iter()
+            .find(|release| release.tag_name == tag)
+            .or_else(|| select_matching(&releases, requirement, &self.filter))
+            .ok_or_else(not_found)?;
+        tracing::info!("Resolved '{}' to {}", tag, release.tag_name);
+        Ok(release.clone())
+    }
+
+    /// The newest published release of the channel with the tag prefix, for
+    /// what the latest-release endpoint can't answer
+    async fn resolve_newest(&self, owner: &str, repo: &str, channel: Channel) -> GhResult<Release> {
+        let mut tag = match channel {
+            Channel::Prerelease => LATEST_PRERELEASE.to_string(),
+            Channel::Stable | Channel::Any => "latest".to_string(),
+        };
+        if let Some(prefix) = &self.filter.tag_prefix {
+            tag = format!("{tag} {prefix}*");
+        }
+        if let Some(before) = self.filter.published_before {
+            tag = format!("{tag} before {}", before.format("%Y-%m-%d %H:%M:%S UTC"));
+        }
+        // Releases from long ago can be far down the list
+        let limit = match self.filter.published_before {
+            Some(_) => REQUIREMENT_SEARCH_LIMIT,
+            None => LATEST_SEARCH_LIMIT,
+        };
+        let not_found = || GhInstallError::ReleaseNotFound {
+            tag: tag.clone(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        };
+        let releases = self.list_releases(owner, repo, limit).await.map_err(|e| {
+            tracing::error!("Resolving '{}' for {}/{}: {}", tag, owner, repo, e);
+            not_found()
+        })?;
+        let release = select_newest(&releases, channel, &self.filter).ok_or_else(not_found)?;
+        tracing::info!("Resolved '{}' to {}", tag, release.tag_name);
+        Ok(release.clone())
+    }
+
+    /// Up to `limit` of the newest releases the filter admits, prereleases included
+    pub async fn recent_releases(
+        &self,
+        owner: &str,
+        repo: &str,
+        limit: usize,
+    ) -> Result<Vec<Release>> {
+        let mut releases = self.list_releases(owner, repo, limit).await?;
+        releases.retain(|release| self.filter.admits(release));
+        Ok(releases)
+    }
+
+    /// List up to `limit` releases, newest first, fetching as many pages as needed
+    pub async fn list_releases(
+        &self,
+        owner: &str,
+        repo: &str,
+        limit: usize,
+    ) -> Result<Vec<Release>> {
+        // Pages are offset by their size, so it stays the same for every page
+        let per_page = limit.clamp(1, 100) as u8;
+        let operation_name = format!("Listing releases for {owner}/{repo}");
+        let mut releases = Vec::new();
+        let mut page = 1u32;
+        while releases.len() < limit {
+            let items = with_retry(&operation_name, &self.retry_config, || {
+                let octocrab = self.octocrab.clone();
+                let (owner, repo) = (owner.to_string(), repo.to_string());
+                async move {
+                    octocrab
+                        .repos(&owner, &repo)
+                        .releases()
+                        .list()
+                        .per_page(per_page)
+                        .page(page)
+                        .send()
+                        .await
+                        .map(|page| page.items)
+                        .map_err(|e| anyhow::anyhow!("Failed to list releases: {e}"))
+                }
+            })
+            .await?;
+            let last_page = items.len() < per_page as usize;
+            releases.extend(items);
+            if last_page {
+                break;
+            }
+            page += 1;
+        }
+        releases.truncate(limit);
+        Ok(releases)
+    }
+
     /// Find matching asset for the target platform
     pub fn find_asset(
         release: &Release,
         target: &str,
         bin_name: Option<&str>,
     ) -> Option<ReleaseAsset> {
-        let bin_name = bin_name.unwrap_or("");
+        Self::matching_assets(release, target, bin_name)
+            .into_iter()
+            .next()
+    }
 
-        for asset in &release.assets {
-            let name = &asset.name;
+    /// The assets matching the target platform best, in the release's order;
+    /// packages only when no archive or binary matches
+    pub fn matching_assets(
+        release: &Release,
+        target: &str,
+        bin_name: Option<&str>,
+    ) -> Vec<ReleaseAsset> {
+        let scored = release
+            .assets
+            .iter()
+            .filter_map(|asset| {
+                matcher::score(&asset.name, target, bin_name)
+                    .ok()
+                    .map(|score| (asset, score))
+            })
+            .collect::<Vec<_>>();
+        let rank = |(asset, score): &(&octocrab::models::repos::Asset, u32)| {
+            matcher::rank(&asset.name, *score)
+        };
+        let best = scored.iter().map(rank).max();
+        scored
+            .into_iter()
+            .filter(|scored| Some(rank(scored)) == best)
+            .map(|(asset, _)| ReleaseAsset {
+                name: asset.name.clone(),
+                url: asset.browser_download_url.to_string(),
+                size: asset.size as u64,
+            })
+            .collect()
+    }
 
-            // Check if asset matches target platform
-            if !name.contains(target) {
-                continue;
-            }
+    /// The first asset whose name matches `pattern`, whatever its target,
+    /// leaving out the checksums and signatures published next to assets
+    pub fn find_asset_matching(release: &Release, pattern: &AssetPattern) -> Option<ReleaseAsset> {
+        release
+            .assets
+            .iter()
+            .find(|asset| !is_sidecar(&asset.name) && pattern.matches(&asset.name))
+            .map(|asset| ReleaseAsset {
+                name: asset.name.clone(),
+                url: asset.browser_download_url.to_string(),
+                size: asset.size as u64,
+            })
+    }
 
-            // Check if it's a compressed archive
-            if !is_archive(name) {
-                continue;
-            }
+    /// Why the asset `name` doesn't match the target platform and binary,
+    /// or `None` when it does
+    pub fn asset_mismatch(name: &str, target: &str, bin_name: Option<&str>) -> Option<String> {
+        matcher::score(name, target, bin_name).err()
+    }
 
-            // If bin_name is specified, check if it matches
-            if !bin_name.is_empty() && !name.contains(bin_name) {
-                continue;
+    /// The attestations GitHub stores for the artifact with `sha256` in a
+    /// repository, as the API returns them; an empty list when there are none
+    pub async fn attestations(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha256: &str,
+    ) -> Result<serde_json::Value> {
+        let route = format!("/repos/{owner}/{repo}/attestations/sha256:{sha256}");
+        match self.octocrab.get(route, None::<&()>).await {
+            Ok(response) => Ok(response),
+            Err(octocrab::Error::GitHub { source, .. }) if source.status_code.as_u16() == 404 => {
+                Ok(serde_json::json!({ "attestations": [] }))
             }
-
-            return Some(ReleaseAsset {
-                name: name.clone(),
-                url: asset.browser_download_url.to_string(),
-                size: asset.size as u64,
-            });
+            Err(e) => Err(anyhow::anyhow!("Failed to fetch attestations: {e}")),
         }
-
-        None
     }
 
     /// Download asset to a temporary file
@@ -138,6 +419,8 @@ impl GitHubClient {
         let url_clone = asset.url.clone();
         let name_clone = asset.name.clone();
         let http_client = self.http_client.clone();
+        let size = asset.size;
+        let show_progress = self.progress;
 
         // Determine file extension for temp file
         let extension = if asset.name.ends_with(".tar.gz") {
@@ -150,6 +433,18 @@ impl GitHubClient {
             ".tar.xz"
         } else if asset.name.ends_with(".tar.bz2") {
             ".tar.bz2"
+        } else if asset.name.ends_with(".tar.zst") {
+            ".tar.zst"
+        } else if asset.name.ends_with(".deb") {
+            ".deb"
+        } else if asset.name.ends_with(".rpm") {
+            ".rpm"
+        } else if asset.name.ends_with(".gz") {
+            ".gz"
+        } else if asset.name.ends_with(".xz") {
+            ".xz"
+        } else if asset.name.ends_with(".zst") {
+            ".zst"
         } else {
             ""
         };
@@ -157,7 +452,7 @@ impl GitHubClient {
         with_retry(&operation_name, &self.retry_config, || {
             let http_client = http_client.clone();
             let url = url_clone.clone();
-            let _name = name_clone.clone();
+            let name = name_clone.clone();
             let ext = extension;
 
             async move {
@@ -193,6 +488,8 @@ impl GitHubClient {
                     .tempfile()
                     .map_err(|e| anyhow::anyhow!("Failed to create temp file: {e}"))?;
 
+                let total = response.content_length().or(Some(size)).filter(|t| *t > 0);
+                let progress = Progress::new(&name, total, show_progress);
                 let mut stream = response.bytes_stream();
 
                 use futures_util::StreamExt;
@@ -203,7 +500,9 @@ impl GitHubClient {
                     temp_file
                         .write_all(&chunk)
                         .map_err(|e| anyhow::anyhow!("Failed to write to temp file: {e}"))?;
+                    progress.inc(chunk.len() as u64);
                 }
+                progress.finish();
 
                 Ok(temp_file)
             }
@@ -221,27 +520,482 @@ impl GitHubClient {
     }
 }
 
+/// Which asset to install when target matching gets it wrong: a glob over
+/// the whole name, or a regex between slashes (`/musl.*\.tar\.gz$/`)
+#[derive(Debug, Clone)]
+pub enum AssetPattern {
+    Glob(String),
+    Regex(Regex),
+}
+
+impl AssetPattern {
+    pub fn parse(pattern: &str) -> std::result::Result<Self, regex::Error> {
+        match pattern
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            Some(regex) => Ok(Self::Regex(Regex::new(regex)?)),
+            None => Ok(Self::Glob(pattern.to_string())),
+        }
+    }
+
+    /// Whether the asset name matches: all of it for a glob, any part of it
+    /// for a regex unless anchored
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Glob(glob) => utils::glob_match(glob, name),
+            Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+impl std::fmt::Display for AssetPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Glob(glob) => f.write_str(glob),
+            Self::Regex(regex) => write!(f, "/{regex}/"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ReleaseAsset {
     pub name: String,
     pub url: String,
-    #[allow(dead_code)]
     pub size: u64,
 }
 
-/// Check if a filename is a supported archive format
-fn is_archive(name: &str) -> bool {
-    name.ends_with(".tar.gz")
+/// The version requirement `tag` stands for, if it is one rather than a tag
+///
+/// Requirements start with an operator (`^1.2`, `~1.4`, `>=1, <2`, `*`) or
+/// are a partial version (`1`, `1.4`). Full versions such as `1.2.3` are tags,
+/// and a partial version falls back to the tag of that name when no release
+/// matches it as a requirement.
+pub fn version_requirement(tag: &str) -> Option<VersionReq> {
+    let operator = tag.starts_with(['^', '~', '=', '>', '<', '*']);
+    if operator || is_partial_version(tag) {
+        VersionReq::parse(tag).ok()
+    } else {
+        None
+    }
+}
+
+/// Whether `tag` is a version with only a major, or a major and minor part
+fn is_partial_version(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.split('.').count() < 3
+        && tag
+            .split('.')
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// The semantic version a tag names, tolerating a `v` prefix
+pub fn tag_semver(tag: &str) -> Option<Version> {
+    Version::parse(tag.strip_prefix('v').unwrap_or(tag)).ok()
+}
+
+/// `tag` without `prefix`, or `None` when it doesn't start with it
+pub fn strip_tag_prefix<'a>(tag: &'a str, prefix: Option<&str>) -> Option<&'a str> {
+    match prefix {
+        Some(prefix) => tag.strip_prefix(prefix),
+        None => Some(tag),
+    }
+}
+
+/// The release with the highest stable version matching `requirement`,
+/// among those the filter admits
+pub fn select_matching<'a>(
+    releases: &'a [Release],
+    requirement: &VersionReq,
+    filter: &ReleaseFilter,
+) -> Option<&'a Release> {
+    let prefix = filter.tag_prefix.as_deref();
+    releases
+        .iter()
+        .filter(|release| !release.prerelease && filter.admits(release))
+        .filter_map(|release| {
+            let version = tag_semver(strip_tag_prefix(&release.tag_name, prefix)?)?;
+            Some((version, release))
+        })
+        .filter(|(version, _)| version.pre.is_empty() && requirement.matches(version))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, release)| release)
+}
+
+/// The most recently published release of the channel among those the
+/// filter admits
+pub fn select_newest<'a>(
+    releases: &'a [Release],
+    channel: Channel,
+    filter: &ReleaseFilter,
+) -> Option<&'a Release> {
+    releases
+        .iter()
+        .filter(|release| match channel {
+            Channel::Stable => !release.prerelease,
+            Channel::Any => true,
+            Channel::Prerelease => release.prerelease,
+        })
+        .filter(|release| filter.admits(release))
+        .max_by_key(|release| release.published_at.or(release.created_at))
+}
+
+/// The asset name without its archive, compression or `.exe` extension, so
+/// the `.tar.gz`, `.zip` and bare binary of one build compare equal
+pub fn archive_stem(name: &str) -> &str {
+    let name = [
+        ".tar.gz", ".tgz", ".tar.xz", ".tar.bz2", ".tar.zst", ".zip", ".deb", ".rpm",
+    ]
+    .iter()
+    .chain(COMPRESSIONS)
+    .find_map(|ext| name.strip_suffix(ext))
+    .unwrap_or(name);
+    name.strip_suffix(".exe").unwrap_or(name)
+}
+
+/// Extensions of single compressed files
+const COMPRESSIONS: &[&str] = &[".gz", ".xz", ".zst"];
+
+/// Check if a filename is a supported archive format, packages included
+pub(crate) fn is_archive(name: &str) -> bool {
+    is_package(name)
+        || name.ends_with(".tar.gz")
         || name.ends_with(".tgz")
         || name.ends_with(".zip")
         || name.ends_with(".tar.xz")
         || name.ends_with(".tar.bz2")
+        || name.ends_with(".tar.zst")
+}
+
+/// Check if a filename is a .deb or .rpm package, installed only when a
+/// release has no archive or binary for the target
+pub(crate) fn is_package(name: &str) -> bool {
+    name.ends_with(".deb") || name.ends_with(".rpm")
+}
+
+/// Check if a filename is a single compressed executable, such as
+/// `tool-x86_64-linux.gz` or `tool.exe.zst`, rather than a compressed tarball
+pub(crate) fn is_compressed_binary(name: &str) -> bool {
+    !is_archive(name)
+        && COMPRESSIONS
+            .iter()
+            .find_map(|ext| name.strip_suffix(ext))
+            .is_some_and(is_bare_binary)
+}
+
+/// Check if a filename looks like a bare executable: a `.exe`, or a name
+/// without a file extension such as `tool-linux-amd64` or `tool_1.2.3_linux_x64`
+pub(crate) fn is_bare_binary(name: &str) -> bool {
+    match name.rsplit_once('.') {
+        None => true,
+        Some((_, ext)) => {
+            ext.eq_ignore_ascii_case("exe")
+                || ext.chars().all(|c| c.is_ascii_digit())
+                || !ext.chars().all(|c| c.is_ascii_alphanumeric())
+        }
+    }
+}
+
+/// Check if a filename is the checksum or signature of another asset, such
+/// as `tool.tar.gz.sha256` or `tool.tar.gz.sig`
+pub(crate) fn is_sidecar(name: &str) -> bool {
+    name.rsplit_once('.').is_some_and(|(_, ext)| {
+        ["sig", "asc"]
+            .into_iter()
+            .chain(
+                crate::checksums::SIDECAR_EXTENSIONS
+                    .iter()
+                    .map(|(ext, _)| *ext),
+            )
+            .any(|sidecar| ext.eq_ignore_ascii_case(sidecar))
+    })
+}
+
+/// A release as the API returns it, with `assets` given as name and size,
+/// for the tests of every module that reads releases
+#[cfg(test)]
+pub(crate) fn test_release(
+    tag: &str,
+    prerelease: bool,
+    published_at: Option<&str>,
+    assets: &[(&str, i64)],
+) -> Release {
+    let assets = assets
+        .iter()
+        .map(|(name, size)| {
+            serde_json::json!({
+                "url": "https://api.github.com/repos/o/r/releases/assets/1",
+                "browser_download_url": format!("https://github.com/o/r/releases/download/{tag}/{name}"),
+                "id": 1, "node_id": "a", "name": name, "label": null, "state": "uploaded",
+                "content_type": "application/octet-stream", "size": size, "download_count": 7,
+                "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+                "uploader": null
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::from_value(serde_json::json!({
+        "url": "https://api.github.com/repos/o/r/releases/1",
+        "html_url": format!("https://github.com/o/r/releases/tag/{tag}"),
+        "assets_url": "https://api.github.com/repos/o/r/releases/1/assets",
+        "upload_url": "https://uploads.github.com/repos/o/r/releases/1/assets",
+        "id": 1, "node_id": "r", "tag_name": tag, "target_commitish": "main",
+        "name": tag, "body": null, "draft": false, "prerelease": prerelease,
+        "created_at": "2024-01-01T00:00:00Z", "published_at": published_at,
+        "author": null, "assets": assets
+    }))
+    .unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn release(tag: &str, prerelease: bool) -> Release {
+        released(tag, prerelease, "2024-01-01T00:00:00Z")
+    }
+
+    fn released(tag: &str, prerelease: bool, published_at: &str) -> Release {
+        test_release(tag, prerelease, Some(published_at), &[])
+    }
+
+    #[test]
+    fn test_asset_pattern() {
+        let glob = AssetPattern::parse("tool-*-linux-musl.tar.gz").unwrap();
+        assert!(glob.matches("tool-x86_64-unknown-linux-musl.tar.gz"));
+        assert!(!glob.matches("tool-x86_64-unknown-linux-musl.tar.gz.sha256"));
+
+        let regex = AssetPattern::parse(r"/linux-(musl|gnu)\.tar\.gz$/").unwrap();
+        assert!(regex.matches("tool-x86_64-unknown-linux-musl.tar.gz"));
+        assert!(regex.matches("tool-aarch64-unknown-linux-gnu.tar.gz"));
+        assert!(!regex.matches("tool-x86_64-unknown-linux-musl.tar.gz.sha256"));
+        assert_eq!(regex.to_string(), r"/linux-(musl|gnu)\.tar\.gz$/");
+
+        assert!(AssetPattern::parse("/(unclosed/").is_err());
+        // A lone slash is not a regex
+        assert!(matches!(
+            AssetPattern::parse("/"),
+            Ok(AssetPattern::Glob(_))
+        ));
+    }
+
+    #[test]
+    fn test_find_asset_matching() {
+        let release = test_release(
+            "v1.0.0",
+            false,
+            None,
+            &[
+                ("tool-x86_64-unknown-linux-musl.tar.gz.sha256", 64),
+                ("tool-x86_64-unknown-linux-musl.tar.gz.sig", 64),
+                ("tool-x86_64-unknown-linux-musl.tar.gz", 1024),
+            ],
+        );
+        let pattern = AssetPattern::parse("*linux-musl*").unwrap();
+        assert_eq!(
+            GitHubClient::find_asset_matching(&release, &pattern).map(|asset| asset.name),
+            Some("tool-x86_64-unknown-linux-musl.tar.gz".to_string())
+        );
+        let pattern = AssetPattern::parse("*.sha256").unwrap();
+        assert!(GitHubClient::find_asset_matching(&release, &pattern).is_none());
+    }
+
+    #[test]
+    fn test_archive_stem() {
+        assert_eq!(
+            archive_stem("tool-x86_64-unknown-linux-gnu.tar.gz"),
+            "tool-x86_64-unknown-linux-gnu"
+        );
+        assert_eq!(
+            archive_stem("tool-x86_64-pc-windows-msvc.zip"),
+            "tool-x86_64-pc-windows-msvc"
+        );
+        assert_eq!(archive_stem("tool-windows-x64.exe"), "tool-windows-x64");
+        assert_eq!(archive_stem("tool-windows-x64.exe.gz"), "tool-windows-x64");
+        assert_eq!(archive_stem("tool-linux-amd64.tar.zst"), "tool-linux-amd64");
+        assert_eq!(archive_stem("SHA256SUMS"), "SHA256SUMS");
+    }
+
+    #[test]
+    fn test_version_requirement() {
+        assert!(version_requirement("^1.2").is_some());
+        assert!(version_requirement("~1.4").is_some());
+        assert!(version_requirement("1").is_some());
+        assert!(version_requirement("1.4").is_some());
+        assert!(version_requirement(">=1.2, <1.5").is_some());
+        // Exact versions, refs and action-style major tags are tags
+        assert!(version_requirement("1.2.3").is_none());
+        assert!(version_requirement("v1.2.3").is_none());
+        assert!(version_requirement("v1").is_none());
+        assert!(version_requirement("main").is_none());
+        assert!(version_requirement("abcdef0").is_none());
+        assert!(version_requirement("").is_none());
+    }
+
+    #[test]
+    fn test_select_matching() {
+        let releases = vec![
+            release("v2.0.0", false),
+            release("v1.5.0-rc.1", true),
+            release("v1.5.0-beta.2", false),
+            release("1.4.2", false),
+            release("v1.4.10", false),
+            release("v1.2.0", false),
+            release("nightly", false),
+        ];
+        let pick = |req: &str| {
+            select_matching(
+                &releases,
+                &VersionReq::parse(req).unwrap(),
+                &ReleaseFilter::default(),
+            )
+            .map(|release| release.tag_name.as_str())
+        };
+        assert_eq!(pick("^1.2"), Some("v1.4.10"));
+        assert_eq!(pick("1"), Some("v1.4.10"));
+        assert_eq!(pick("~1.4"), Some("v1.4.10"));
+        assert_eq!(pick("~1.2"), Some("v1.2.0"));
+        assert_eq!(pick("*"), Some("v2.0.0"));
+        assert_eq!(pick("^3"), None);
+    }
+
+    #[test]
+    fn test_select_newest() {
+        let releases = vec![
+            released("v1.1.0", false, "2024-03-01T00:00:00Z"),
+            released("v1.2.0-rc.1", true, "2024-02-01T00:00:00Z"),
+            released("v1.0.0", false, "2024-01-01T00:00:00Z"),
+        ];
+        let newest = |releases: &[Release], channel| {
+            select_newest(releases, channel, &ReleaseFilter::default())
+                .map(|release| release.tag_name.clone())
+        };
+        // A stable release published after the prerelease is the latest
+        assert_eq!(newest(&releases, Channel::Any).as_deref(), Some("v1.1.0"));
+        assert_eq!(
+            newest(&releases, Channel::Prerelease).as_deref(),
+            Some("v1.2.0-rc.1")
+        );
+
+        let mut releases = releases;
+        releases.insert(0, released("v1.2.0-rc.2", true, "2024-04-01T00:00:00Z"));
+        assert_eq!(
+            newest(&releases, Channel::Any).as_deref(),
+            Some("v1.2.0-rc.2")
+        );
+        assert_eq!(
+            newest(&releases, Channel::Stable).as_deref(),
+            Some("v1.1.0")
+        );
+        assert_eq!(newest(&releases[3..], Channel::Prerelease), None);
+    }
+
+    #[test]
+    fn test_tag_prefix() {
+        let releases = vec![
+            released("server-v2.0.0", false, "2024-04-01T00:00:00Z"),
+            released("cli-v1.3.0-rc.1", true, "2024-03-01T00:00:00Z"),
+            released("cli-v1.2.1", false, "2024-02-01T00:00:00Z"),
+            released("cli-v1.2.0", false, "2024-01-01T00:00:00Z"),
+            released("v3.0.0", false, "2024-01-01T00:00:00Z"),
+        ];
+        let prefix = Some("cli-v");
+        let filter = ReleaseFilter {
+            tag_prefix: prefix.map(String::from),
+            ..Default::default()
+        };
+        assert_eq!(
+            select_newest(&releases, Channel::Stable, &filter).map(|r| r.tag_name.as_str()),
+            Some("cli-v1.2.1")
+        );
+        assert_eq!(
+            select_newest(&releases, Channel::Any, &filter).map(|r| r.tag_name.as_str()),
+            Some("cli-v1.3.0-rc.1")
+        );
+        let requirement = VersionReq::parse("~1.2").unwrap();
+        assert_eq!(
+            select_matching(&releases, &requirement, &filter).map(|r| r.tag_name.as_str()),
+            Some("cli-v1.2.1")
+        );
+        let requirement = VersionReq::parse("*").unwrap();
+        assert_eq!(
+            select_matching(&releases, &requirement, &ReleaseFilter::default())
+                .map(|r| r.tag_name.as_str()),
+            Some("v3.0.0")
+        );
+        assert_eq!(strip_tag_prefix("cli-v1.2.1", prefix), Some("1.2.1"));
+        assert_eq!(strip_tag_prefix("v1.2.1", prefix), None);
+    }
+
+    #[test]
+    fn test_published_before() {
+        let releases = vec![
+            released("v1.3.0", false, "2024-06-01T12:00:00Z"),
+            released("v1.2.0", false, "2024-05-20T00:00:00Z"),
+            released("v2.0.0-rc.1", true, "2024-05-25T00:00:00Z"),
+            released("v1.1.0", false, "2024-04-01T00:00:00Z"),
+        ];
+        let filter = |before: &str| ReleaseFilter {
+            published_before: Some(before.parse().unwrap()),
+            ..Default::default()
+        };
+        let newest = |before: &str, channel| {
+            select_newest(&releases, channel, &filter(before)).map(|r| r.tag_name.as_str())
+        };
+        assert_eq!(
+            newest("2024-06-01T00:00:00Z", Channel::Stable),
+            Some("v1.2.0")
+        );
+        assert_eq!(
+            newest("2024-06-01T00:00:00Z", Channel::Any),
+            Some("v2.0.0-rc.1")
+        );
+        assert_eq!(
+            newest("2024-06-02T00:00:00Z", Channel::Stable),
+            Some("v1.3.0")
+        );
+        // Exactly at the cutoff is not before it
+        assert_eq!(
+            newest("2024-05-20T00:00:00Z", Channel::Stable),
+            Some("v1.1.0")
+        );
+        assert_eq!(newest("2024-01-01T00:00:00Z", Channel::Stable), None);
+
+        let requirement = VersionReq::parse("^1").unwrap();
+        assert_eq!(
+            select_matching(&releases, &requirement, &filter("2024-05-01T00:00:00Z"))
+                .map(|r| r.tag_name.as_str()),
+            Some("v1.1.0")
+        );
+    }
+
+    #[test]
+    fn test_asset_mismatch() {
+        let target = "x86_64-unknown-linux-gnu";
+        assert_eq!(
+            GitHubClient::asset_mismatch("tool-x86_64-unknown-linux-gnu.tar.gz", target, None),
+            None
+        );
+        assert_eq!(
+            GitHubClient::asset_mismatch("tool-aarch64-apple-darwin.tar.gz", target, None)
+                .as_deref(),
+            Some("built for macos, not linux")
+        );
+        assert_eq!(
+            GitHubClient::asset_mismatch("tool-x86_64-unknown-linux-gnu.tar.gz.sig", target, None)
+                .as_deref(),
+            Some("not an archive or executable")
+        );
+        assert_eq!(
+            GitHubClient::asset_mismatch(
+                "tool-x86_64-unknown-linux-gnu.tar.gz",
+                target,
+                Some("other")
+            )
+            .as_deref(),
+            Some("name doesn't contain other")
+        );
+    }
+
     #[test]
     fn test_is_archive() {
         assert!(is_archive("binary.tar.gz"));
@@ -249,8 +1003,33 @@ mod tests {
         assert!(is_archive("binary.zip"));
         assert!(is_archive("binary.tar.xz"));
         assert!(is_archive("binary.tar.bz2"));
+        assert!(is_archive("binary.tar.zst"));
+        assert!(is_archive("binary_1.0.0_amd64.deb"));
+        assert!(is_archive("binary-1.0.0-1.x86_64.rpm"));
         assert!(!is_archive("binary.exe"));
         assert!(!is_archive("binary"));
         assert!(!is_archive("README.md"));
     }
+
+    #[test]
+    fn test_is_bare_binary() {
+        assert!(is_bare_binary("tool-linux-amd64"));
+        assert!(is_bare_binary("tool.exe"));
+        assert!(is_bare_binary("tool_1.2.3_linux_x86_64"));
+        assert!(is_bare_binary("tool-v1.2"));
+        assert!(!is_bare_binary("tool-linux-amd64.sha256"));
+        assert!(!is_bare_binary("tool-linux-amd64.deb"));
+        assert!(!is_bare_binary("checksums.txt"));
+    }
+
+    #[test]
+    fn test_is_compressed_binary() {
+        assert!(is_compressed_binary("tool-x86_64-linux.gz"));
+        assert!(is_compressed_binary("tool-linux-amd64.xz"));
+        assert!(is_compressed_binary("tool.exe.zst"));
+        assert!(!is_compressed_binary("tool-linux-amd64.tar.gz"));
+        assert!(!is_compressed_binary("tool-linux-amd64.tar.zst"));
+        assert!(!is_compressed_binary("tool-linux-amd64.deb.gz"));
+        assert!(!is_compressed_binary("tool-linux-amd64"));
+    }
 }
@@ -1,13 +1,23 @@
 use anyhow::{Context, Result};
+use indicatif::HumanBytes;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
 
+use crate::checksums::{self, Algorithm};
 use crate::cli::Args;
+use crate::codesign;
 use crate::config::Config;
 use crate::error::{GhInstallError, Result as GhResult};
-use crate::github::{GitHubClient, ReleaseAsset};
+use crate::extras;
+use crate::github::{self, AssetPattern, GitHubClient, ReleaseAsset};
+use crate::matcher;
+use crate::outdated;
+use crate::picker::{self, Picker};
+use crate::provenance;
+use crate::receipts::{InstalledBinary, Receipt, Receipts};
 use crate::retry::RetryConfig;
+use crate::signature;
 use crate::utils;
 use std::time::Duration;
 
@@ -46,7 +56,11 @@ impl Installer {
             }
         };
 
-        let github_client = GitHubClient::with_retry_config(retry_config)?;
+        let github_client = GitHubClient::with_retry_config(retry_config)?
+            .with_progress(!args.no_progress)
+            .with_prereleases(args.selection.pre)
+            .with_tag_prefix(args.selection.tag_prefix.clone())
+            .with_published_before(args.selection.published_before());
 
         Ok(Self {
             args,
@@ -55,8 +69,16 @@ impl Installer {
         })
     }
 
-    pub async fn run(&self) -> Result<()> {
+    /// Install from the release, returning the receipt of the install, or
+    /// `None` when it fell back to `cargo install`
+    pub async fn run(&self) -> Result<Option<Receipt>> {
         let (owner, repo, tag) = self.args.parse_repo()?;
+        let tag = match tag {
+            None if self.args.interactive && self.can_prompt() => {
+                Some(self.pick_release(&owner, &repo).await?)
+            }
+            tag => tag,
+        };
 
         tracing::info!(
             "Installing from {}/{} (tag: {})",
@@ -80,7 +102,8 @@ impl Installer {
                     );
                     return self
                         .fallback_cargo_install(&owner, &repo, tag.as_deref())
-                        .await;
+                        .await
+                        .map(|()| None);
                 }
                 return Err(e.into());
             }
@@ -95,7 +118,7 @@ impl Installer {
 
         // Find matching asset
         let target = self.args.target();
-        let asset = match GitHubClient::find_asset(&release, &target, self.args.bin.as_deref()) {
+        let asset = match self.find_release_asset(&release, &target)? {
             Some(asset) => asset,
             None => {
                 if !self.args.no_fallback {
@@ -105,37 +128,235 @@ impl Installer {
                     );
                     return self
                         .fallback_cargo_install(&owner, &repo, tag.as_deref())
-                        .await;
+                        .await
+                        .map(|()| None);
                 }
 
-                let available_assets = release
-                    .assets
-                    .iter()
-                    .map(|a| a.name.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                let error = GhInstallError::AssetNotFound {
-                    target: target.clone(),
-                    release_tag: release.tag_name.clone(),
-                    available: if available_assets.is_empty() {
-                        "No assets available".to_string()
-                    } else {
-                        available_assets
-                    },
-                };
-                return Err(error.into());
+                return Err(asset_not_found(&release, &target, |name| {
+                    self.asset_mismatch(name, &target)
+                })
+                .into());
             }
         };
 
-        // Download asset
-        let temp_file = self.github_client.download_asset(&asset).await?;
+        // Download and verify asset
+        let (temp_file, asset_sha256) = self.download_verified(&release, &asset).await?;
+
+        // Extract archive, or take the bare binary
+        let default_name = self.default_name(tag.as_deref(), &release, &repo);
+        let extracted_dir = self.unpack(&asset, temp_file.path(), default_name)?;
+
+        let full_name = format!("{owner}/{repo}");
+        let receipts_path = self.args.receipts.receipts_path();
+        let mut receipts = Receipts::load(&receipts_path).unwrap_or_else(|e| {
+            tracing::warn!("Starting a new receipts file: {:#}", e);
+            Receipts::default()
+        });
+
+        // Find and install binaries
+        let installed = self
+            .install_binaries(extracted_dir.path(), default_name, &receipts)
+            .await?;
+        let extras = if self.args.extras {
+            let names = installed
+                .iter()
+                .map(|(binary, _)| binary.name.as_str())
+                .collect::<Vec<_>>();
+            extras::install(extracted_dir.path(), &names)?
+        } else {
+            Vec::new()
+        };
+
+        let unchanged = installed.iter().all(|(_, copied)| !copied)
+            && receipts.get(&full_name).is_some_and(|receipt| {
+                receipt.tag == release.tag_name && receipt.asset == asset.name
+            });
+        // Keep the replaced install while its binaries have backups to roll back to
+        let backed_up = installed.iter().any(|(binary, copied)| {
+            *copied && utils::backup_path(&self.args.receipts.backup_dir(), &binary.path).exists()
+        });
+        let previous = receipts
+            .get(&full_name)
+            .filter(|_| backed_up && self.args.backup)
+            .map(|receipt| {
+                Box::new(Receipt {
+                    previous: None,
+                    ..receipt.clone()
+                })
+            });
+        let receipt = Receipt {
+            tag_prefix: self.args.selection.tag_prefix.clone(),
+            extras,
+            previous,
+            ..Receipt::new(
+                &release.tag_name,
+                &target,
+                &asset.name,
+                asset_sha256,
+                installed.into_iter().map(|(binary, _)| binary).collect(),
+            )
+        };
+        receipts.record(&full_name, receipt.clone());
+        if let Err(e) = receipts.save(&receipts_path) {
+            tracing::warn!("Failed to record the install: {:#}", e);
+        }
+
+        if unchanged {
+            println!("{full_name} {} is already installed", release.tag_name);
+        } else {
+            tracing::info!("Installation completed successfully!");
+        }
+        Ok(Some(receipt))
+    }
+
+    /// Ask which of the recent releases to install, returning its tag
+    async fn pick_release(&self, owner: &str, repo: &str) -> Result<String> {
+        let releases = self
+            .github_client
+            .recent_releases(owner, repo, PICKER_RELEASES)
+            .await?;
+        anyhow::ensure!(
+            !releases.is_empty(),
+            "{owner}/{repo} has no releases to pick from"
+        );
+        let rows = releases
+            .iter()
+            .map(|release| {
+                let date = release
+                    .published_at
+                    .or(release.created_at)
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                let flag = if release.prerelease { "prerelease" } else { "" };
+                vec![release.tag_name.clone(), date, flag.to_string()]
+            })
+            .collect::<Vec<_>>();
+        let choices = release_choices(&rows);
+        match Picker::terminal().pick(&format!("Releases of {owner}/{repo}:"), &choices)? {
+            Some(index) => Ok(releases[index].tag_name.clone()),
+            None => anyhow::bail!("No release picked"),
+        }
+    }
+
+    /// The binary name the install defaults to; releases found by tag prefix
+    /// are per package, so their resolved tag names the package
+    fn default_name<'a>(
+        &self,
+        tag: Option<&'a str>,
+        release: &'a octocrab::models::repos::Release,
+        repo: &'a str,
+    ) -> &'a str {
+        let tag = match self.args.selection.tag_prefix {
+            Some(_) => Some(release.tag_name.as_str()),
+            None => tag,
+        };
+        default_binary_name(tag, repo)
+    }
+
+    /// Whether questions can be asked: on a terminal, without --yes
+    fn can_prompt(&self) -> bool {
+        !self.args.yes && picker::is_interactive()
+    }
+
+    /// The asset to install: the one matching `--asset`, or the target's
+    /// (falling back to -gnu builds when a glibc host prefers -musl ones),
+    /// asking which one when several different builds match
+    fn find_release_asset(
+        &self,
+        release: &octocrab::models::repos::Release,
+        target: &str,
+    ) -> Result<Option<ReleaseAsset>> {
+        if let Some(pattern) = &self.args.selection.asset {
+            let pattern = AssetPattern::parse(pattern)
+                .with_context(|| format!("Invalid asset pattern '{pattern}'"))?;
+            return Ok(GitHubClient::find_asset_matching(release, &pattern));
+        }
+        let mut assets = GitHubClient::matching_assets(release, target, self.args.bin.as_deref());
+        if let Some(fallback) = self.args.fallback_target().filter(|_| assets.is_empty()) {
+            // glibc hosts run -gnu builds too, just less portably
+            tracing::info!(
+                "No asset of {} matches {target}; trying {fallback}",
+                release.tag_name
+            );
+            assets = GitHubClient::matching_assets(release, &fallback, self.args.bin.as_deref());
+            let arch = |target: &str| matcher::Platform::parse(target).map(|p| p.arch);
+            if !assets.is_empty() && arch(&fallback) != arch(target) {
+                tracing::warn!(
+                    "{} has no {target} build; installing the {fallback} one, which runs under Rosetta \
+                     (--no-arch-fallback to refuse)",
+                    release.tag_name
+                );
+            }
+        }
+        let variants = distinct_builds(&assets);
+        if variants.len() < 2 || !self.can_prompt() {
+            return Ok(assets.into_iter().next());
+        }
+
+        let choices = variants
+            .iter()
+            .map(|asset| format!("{} ({})", asset.name, HumanBytes(asset.size)))
+            .collect::<Vec<_>>();
+        let prompt = format!("Several assets of {} match {target}:", release.tag_name);
+        match Picker::terminal().pick(&prompt, &choices)? {
+            Some(index) => Ok(Some(variants[index].clone())),
+            None => anyhow::bail!("No asset picked"),
+        }
+    }
+
+    /// Extract the downloaded asset, or place a bare binary asset under the
+    /// name `--bin` or the default selection looks for
+    fn unpack(
+        &self,
+        asset: &ReleaseAsset,
+        path: &Path,
+        default_name: &str,
+    ) -> Result<tempfile::TempDir> {
+        let bin_name = self.args.bin.as_deref().unwrap_or(default_name);
+        utils::unpack_asset(path, &asset.name, bin_name)
+    }
+
+    /// Why `find_release_asset` passes over the asset `name`
+    fn asset_mismatch(&self, name: &str, target: &str) -> Option<String> {
+        match self
+            .args
+            .selection
+            .asset
+            .as_deref()
+            .map(AssetPattern::parse)
+        {
+            Some(Ok(pattern)) if pattern.matches(name) => None,
+            Some(Ok(pattern)) => Some(format!("doesn't match {pattern}")),
+            Some(Err(_)) => None,
+            None => GitHubClient::asset_mismatch(name, target, self.args.bin.as_deref()),
+        }
+    }
+
+    /// Download the asset and check its checksum (and signature when asked),
+    /// returning the file with its SHA256
+    async fn download_verified(
+        &self,
+        release: &octocrab::models::repos::Release,
+        asset: &ReleaseAsset,
+    ) -> Result<(tempfile::NamedTempFile, String)> {
+        let temp_file = self.github_client.download_asset(asset).await?;
+        let asset_sha256 = utils::calculate_sha256(temp_file.path())?;
+
+        // A locked install takes exactly the artifact that was locked
+        if let Some(expected) = &self.args.expected_sha256 {
+            if !asset_sha256.eq_ignore_ascii_case(expected) {
+                return Err(GhInstallError::ChecksumVerification {
+                    file: asset.name.clone(),
+                    expected: expected.clone(),
+                    actual: asset_sha256,
+                })
+                .context("The release asset changed since it was locked");
+            }
+        }
 
         // Verify checksum unless explicitly skipped
         if !self.args.skip_checksum {
-            if let Err(e) = self
-                .verify_checksum(&release, &asset, temp_file.path())
-                .await
-            {
+            if let Err(e) = self.verify_checksum(release, asset, temp_file.path()).await {
                 tracing::error!("Checksum verification failed: {}", e);
                 return Err(e.into());
             }
@@ -143,10 +364,10 @@ impl Installer {
             tracing::warn!("Skipping checksum verification (--skip-checksum was specified)");
         }
 
-        // Verify signature if requested
-        if self.args.verify_signature {
+        // Verify signature if requested, or a signing key is given
+        if self.args.verify_signature || self.args.signing_key.is_some() {
             if let Err(e) = self
-                .verify_signature(&release, &asset, temp_file.path())
+                .verify_signature(release, asset, temp_file.path())
                 .await
             {
                 tracing::error!("Signature verification failed: {}", e);
@@ -154,17 +375,197 @@ impl Installer {
             }
         }
 
-        // Extract archive
-        let extracted_dir = utils::extract_archive(temp_file.path())?;
+        if self.args.verify_provenance
+            || self.args.require_provenance
+            || self.args.provenance_workflow.is_some()
+        {
+            self.verify_provenance(asset, temp_file.path(), &asset_sha256)
+                .await?;
+        }
+
+        Ok((temp_file, asset_sha256))
+    }
+
+    /// Check the asset's SLSA build provenance names the repository (and the
+    /// --provenance-workflow) as its builder, verifying its Sigstore bundle
+    /// with `gh attestation verify`. Assets without provenance pass with a
+    /// warning unless --require-provenance.
+    async fn verify_provenance(
+        &self,
+        asset: &ReleaseAsset,
+        path: &Path,
+        sha256: &str,
+    ) -> Result<()> {
+        let (owner, repo, _) = self.args.parse_repo()?;
+        let full_name = format!("{owner}/{repo}");
+        let response = self
+            .github_client
+            .attestations(&owner, &repo, sha256)
+            .await?;
+
+        let claimed = provenance::envelopes(&response)
+            .into_iter()
+            .any(|envelope| provenance::claims_provenance(envelope, sha256));
+        if !claimed {
+            anyhow::ensure!(
+                !self.args.require_provenance,
+                "{} has no build provenance attestation on GitHub (--require-provenance)",
+                asset.name
+            );
+            tracing::warn!(
+                "{} has no build provenance attestation on GitHub",
+                asset.name
+            );
+            return Ok(());
+        }
+
+        let found = provenance::verify(path, &full_name)
+            .with_context(|| format!("Build provenance of {} didn't verify", asset.name))?;
+        if let Some(mismatch) = found.mismatch(&full_name, self.args.provenance_workflow.as_deref())
+        {
+            anyhow::bail!(
+                "Build provenance of {} doesn't match {full_name}: {mismatch}",
+                asset.name
+            );
+        }
+        tracing::info!(
+            "{} was built by {} on {} in {}",
+            asset.name,
+            found.workflow,
+            found.git_ref,
+            found.repository
+        );
+        Ok(())
+    }
+
+    /// Make the release's executables available under `cache_root` and return
+    /// the one to run, downloading only when the asset isn't cached yet
+    ///
+    /// Executables are kept in a directory named after the asset's SHA256,
+    /// and `refs/<owner>/<repo>/<tag>/<asset>` records which hash an asset of
+    /// a release had, so a new release with the same asset names isn't
+    /// mistaken for the cached one.
+    pub async fn cache_release(&self, cache_root: &Path) -> Result<std::path::PathBuf> {
+        let (owner, repo, tag) = self.args.parse_repo()?;
+        let release = self
+            .github_client
+            .get_release(&owner, &repo, tag.as_deref())
+            .await?;
+        let target = self.args.target();
+        let asset = self.find_release_asset(&release, &target)?.ok_or_else(|| {
+            asset_not_found(&release, &target, |name| self.asset_mismatch(name, &target))
+        })?;
+        let default_name = self.default_name(tag.as_deref(), &release, &repo);
+
+        let ref_path = cache_root
+            .join("refs")
+            .join(&owner)
+            .join(&repo)
+            .join(&release.tag_name)
+            .join(&asset.name);
+        let cached = fs::read_to_string(&ref_path)
+            .ok()
+            .map(|sha256| cache_root.join(sha256.trim()))
+            .filter(|dir| dir.is_dir());
+        if let Some(dir) = cached {
+            if let Some(binary) = self.select_cached(&dir, default_name)? {
+                tracing::info!("Running cached {}", binary.display());
+                return Ok(binary);
+            }
+        }
+
+        let (temp_file, asset_sha256) = self.download_verified(&release, &asset).await?;
+        let extracted_dir = self.unpack(&asset, temp_file.path(), default_name)?;
+        let dir = cache_root.join(&asset_sha256);
+        fs::create_dir_all(&dir)?;
+        for exe_path in utils::find_executables(extracted_dir.path())? {
+            if let Some(file_name) = exe_path.file_name() {
+                utils::replace_executable(&exe_path, &dir.join(file_name))?;
+            }
+        }
+        if let Some(parent) = ref_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&ref_path, &asset_sha256)?;
 
-        // Find and install binaries
-        self.install_binaries(extracted_dir.path(), &repo).await?;
+        self.select_cached(&dir, default_name)?.ok_or_else(|| {
+            GhInstallError::NoExecutablesFound {
+                archive: asset.name.clone(),
+            }
+            .into()
+        })
+    }
+
+    /// The executable in a cache directory that `--bin`, or else the default
+    /// binary selection, picks
+    fn select_cached(&self, dir: &Path, default_name: &str) -> Result<Option<std::path::PathBuf>> {
+        let mut executables = utils::find_executables(dir)?;
+        executables.sort();
+        let selected = match &self.args.bin {
+            Some(bin_name) => executables.iter().find(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.contains(bin_name.as_str()))
+            }),
+            None => Self::select_default_executable(&executables, default_name).map(|(p, _)| p),
+        };
+        Ok(selected.cloned())
+    }
+
+    /// Replace `exe` with `bin` from the release when it is newer than
+    /// `current_version`, or from the requested tag whatever its version
+    pub async fn self_update(&self, bin: &str, exe: &Path, current_version: &str) -> Result<()> {
+        let (owner, repo, tag) = self.args.parse_repo()?;
+        let release = self
+            .github_client
+            .get_release(&owner, &repo, tag.as_deref())
+            .await?;
+        if tag.is_none() && !outdated::is_upgrade(current_version, &release.tag_name) {
+            println!("{bin} {current_version} is up to date");
+            return Ok(());
+        }
+
+        // Each archive carries all of the workspace's binaries for one target
+        let target = self.args.target();
+        let asset = GitHubClient::find_asset(&release, &target, None).ok_or_else(|| {
+            asset_not_found(&release, &target, |name| {
+                GitHubClient::asset_mismatch(name, &target, None)
+            })
+        })?;
+        let temp_file = self.github_client.download_asset(&asset).await?;
+        self.verify_checksum(&release, &asset, temp_file.path())
+            .await?;
+
+        let extracted_dir = utils::unpack_asset(temp_file.path(), &asset.name, bin)?;
+        let executables = utils::find_executables(extracted_dir.path())?;
+        let source = executables
+            .iter()
+            .find(|path| path.file_stem().is_some_and(|stem| stem == bin))
+            .ok_or_else(|| GhInstallError::BinaryNotFound {
+                name: bin.to_string(),
+                available: executables
+                    .iter()
+                    .filter_map(|p| p.file_name()?.to_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })?;
+        utils::replace_executable(source, exe)?;
 
-        tracing::info!("Installation completed successfully!");
+        println!(
+            "Updated {bin} from {current_version} to {}",
+            release.tag_name
+        );
         Ok(())
     }
 
-    async fn install_binaries(&self, extracted_dir: &Path, default_name: &str) -> Result<()> {
+    /// Install the selected binaries, returning each with whether it was
+    /// copied or already in place
+    async fn install_binaries(
+        &self,
+        extracted_dir: &Path,
+        default_name: &str,
+        receipts: &Receipts,
+    ) -> Result<Vec<(InstalledBinary, bool)>> {
         let executables = utils::find_executables(extracted_dir)?;
 
         if executables.is_empty() {
@@ -178,11 +579,29 @@ impl Installer {
 
         // Create install directory if it doesn't exist
         fs::create_dir_all(&install_dir)?;
+        utils::remove_replaced(&install_dir);
 
+        let mut installed = Vec::new();
         if self.args.bins {
             // Install all binaries
             for exe_path in &executables {
-                self.install_binary(exe_path, &install_dir, None)?;
+                installed.push(self.install_binary(exe_path, &install_dir, None, receipts)?);
+            }
+        } else if !self.args.bin_names.is_empty() {
+            // Install the binaries the repository config names
+            for bin_name in &self.args.bin_names {
+                let exe_path = executables
+                    .iter()
+                    .find(|p| p.file_stem().is_some_and(|stem| stem == bin_name.as_str()))
+                    .ok_or_else(|| GhInstallError::BinaryNotFound {
+                        name: bin_name.to_string(),
+                        available: executables
+                            .iter()
+                            .filter_map(|p| p.file_name()?.to_str())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    })?;
+                installed.push(self.install_binary(exe_path, &install_dir, None, receipts)?);
             }
         } else if let Some(bin_name) = &self.args.bin {
             // Install specific binary
@@ -194,7 +613,12 @@ impl Installer {
             });
 
             if let Some(exe_path) = matching {
-                self.install_binary(exe_path, &install_dir, Some(bin_name))?;
+                installed.push(self.install_binary(
+                    exe_path,
+                    &install_dir,
+                    Some(bin_name),
+                    receipts,
+                )?);
             } else {
                 let available = executables
                     .iter()
@@ -214,7 +638,12 @@ impl Installer {
                 Self::select_default_executable(&executables, default_name)
             {
                 let install_name = rename_to_default.then_some(default_name);
-                self.install_binary(exe_path, &install_dir, install_name)?;
+                installed.push(self.install_binary(
+                    exe_path,
+                    &install_dir,
+                    install_name,
+                    receipts,
+                )?);
             } else {
                 return Err(GhInstallError::NoExecutablesFound {
                     archive: extracted_dir.display().to_string(),
@@ -223,7 +652,7 @@ impl Installer {
             }
         }
 
-        Ok(())
+        Ok(installed)
     }
 
     fn select_default_executable<'a>(
@@ -242,13 +671,21 @@ impl Installer {
             .or_else(|| executables.first().map(|path| (path, false)))
     }
 
-    fn install_binary(&self, source: &Path, install_dir: &Path, name: Option<&str>) -> Result<()> {
-        let binary_name = name
+    fn install_binary(
+        &self,
+        source: &Path,
+        install_dir: &Path,
+        name: Option<&str>,
+        receipts: &Receipts,
+    ) -> Result<(InstalledBinary, bool)> {
+        let original_name = name
             .or_else(|| source.file_stem()?.to_str())
             .ok_or_else(|| GhInstallError::Installation {
                 message: "Invalid binary name".to_string(),
                 path: source.display().to_string(),
             })?;
+        // --as changes the destination name only
+        let binary_name = self.args.rename.as_deref().unwrap_or(original_name);
 
         let dest_path = install_dir.join(binary_name);
 
@@ -260,14 +697,104 @@ impl Installer {
             dest_path
         };
 
+        let sha256 = utils::calculate_sha256(source)?;
+        let installed = InstalledBinary {
+            name: binary_name.to_string(),
+            path: dest_path.clone(),
+            sha256,
+            renamed_from: (binary_name != original_name).then(|| original_name.to_string()),
+        };
+
+        // Reinstalling the same release leaves identical binaries untouched
+        if installed.is_intact() {
+            tracing::info!("{} is already up to date", dest_path.display());
+            return Ok((installed, false));
+        }
+
+        if dest_path.exists() {
+            // Another repository's binary is only replaced with --force
+            let (owner, repo, _) = self.args.parse_repo()?;
+            let full_name = format!("{owner}/{repo}");
+            match receipts.owner_of(&dest_path) {
+                Some(other) if other != full_name && !self.args.force => {
+                    return Err(GhInstallError::Installation {
+                        message: format!(
+                            "The binary there was installed from {other}; pass --force to replace it"
+                        ),
+                        path: dest_path.display().to_string(),
+                    }
+                    .into());
+                }
+                // Installed before receipts were kept, or their file was lost
+                None if !self.args.force => tracing::warn!(
+                    "Replacing {}, which no install receipt records",
+                    dest_path.display()
+                ),
+                _ => {}
+            }
+            if self.args.backup {
+                let backup = utils::backup_path(&self.args.receipts.backup_dir(), &dest_path);
+                tracing::info!("Backing up {} to {}", dest_path.display(), backup.display());
+                if let Some(dir) = backup.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::copy(&dest_path, &backup)?;
+            }
+        }
+
+        if cfg!(target_os = "macos") && self.args.target().contains("apple-darwin") {
+            self.check_signature(source, &dest_path)?;
+        }
+
         tracing::info!("Installing {} to {}", binary_name, dest_path.display());
 
-        // Copy binary to destination
-        fs::copy(source, &dest_path)?;
+        // Rename an executable copy into place, so a running binary keeps working
+        utils::replace_executable(source, &dest_path)?;
 
-        // Make executable on Unix
-        utils::make_executable(&dest_path)?;
+        Ok((installed, true))
+    }
 
+    /// Report how a macOS binary is signed, warning about unsigned and ad-hoc
+    /// signed ones, or refusing them with --require-signed. A signature that
+    /// doesn't match the binary is always refused.
+    fn check_signature(&self, binary: &Path, dest_path: &Path) -> Result<()> {
+        let signature = match codesign::assess(binary) {
+            Ok(signature) => signature,
+            Err(e) if !self.args.require_signed => {
+                tracing::warn!(
+                    "Couldn't check the signature of {}: {:#}",
+                    dest_path.display(),
+                    e
+                );
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+        tracing::info!("{} is {}", dest_path.display(), signature);
+        if signature.is_trusted() {
+            return Ok(());
+        }
+        if let codesign::Signature::Invalid { .. } = signature {
+            return Err(GhInstallError::Installation {
+                message: format!("Binary has an {signature}; it may have been tampered with"),
+                path: dest_path.display().to_string(),
+            }
+            .into());
+        }
+        if self.args.require_signed {
+            return Err(GhInstallError::Installation {
+                message: format!(
+                    "Binary is {signature}; --require-signed accepts only Developer ID signed ones"
+                ),
+                path: dest_path.display().to_string(),
+            }
+            .into());
+        }
+        tracing::warn!(
+            "{} is {}, so nothing vouches for who built it",
+            dest_path.display(),
+            signature
+        );
         Ok(())
     }
 
@@ -278,112 +805,112 @@ impl Installer {
         asset: &ReleaseAsset,
         file_path: &Path,
     ) -> GhResult<()> {
-        // Look for SHA256SUMS file in the release
-        let checksum_asset = release.assets.iter().find(|a| {
-            let name = &a.name;
-            name == "SHA256SUMS" || name == "checksums.txt" || name == "sha256sums.txt"
-        });
-
-        if let Some(checksum_asset) = checksum_asset {
-            tracing::info!("Found checksum file: {}", checksum_asset.name);
-
-            // Download checksum file
-            let checksum_asset = ReleaseAsset {
-                name: checksum_asset.name.clone(),
-                url: checksum_asset.browser_download_url.to_string(),
-                size: checksum_asset.size as u64,
-            };
-
-            let checksum_file = self
-                .github_client
-                .download_asset(&checksum_asset)
-                .await
-                .map_err(|e| {
-                    tracing::error!(
-                        "Failed to download checksum file {}: {}",
-                        checksum_asset.name,
-                        e
-                    );
-                    GhInstallError::DownloadFailed {
-                        asset: checksum_asset.name.clone(),
-                        url: checksum_asset.url.clone(),
-                        status: 0, // Unknown status
-                        message: e.to_string(),
-                    }
-                })?;
-
-            // Read checksums from file
-            let checksum_content = std::fs::read_to_string(checksum_file.path()).map_err(|e| {
-                tracing::error!("Failed to read checksum file: {}", e);
-                GhInstallError::Io(e)
-            })?;
+        // Look for SHA256SUMS (or SHA512SUMS, B3SUMS) in the release, then for
+        // a `<asset>.sha256` (`.sha512`, `.b3`) sidecar
+        let find = |name: &str| release.assets.iter().find(|a| a.name == name);
+        let aggregate = checksums::AGGREGATE_FILES
+            .iter()
+            .find_map(|(name, algorithm)| Some((find(name)?, *algorithm, false)));
+        let sidecar = || {
+            checksums::SIDECAR_EXTENSIONS
+                .iter()
+                .find_map(|(extension, algorithm)| {
+                    Some((
+                        find(&format!("{}.{extension}", asset.name))?,
+                        *algorithm,
+                        true,
+                    ))
+                })
+        };
+        let Some((checksum_asset, algorithm, is_sidecar)) = aggregate.or_else(sidecar) else {
+            // No checksum file found, which is an error unless --skip-checksum is used
+            tracing::warn!(
+                "No SHA256SUMS, SHA512SUMS, B3SUMS or {}.sha256 file found in release",
+                asset.name
+            );
+            return Err(GhInstallError::ChecksumFileNotFound);
+        };
+        tracing::info!("Found checksum file: {}", checksum_asset.name);
 
-            // Parse checksums and find the one for our asset
-            let expected_checksum = self.parse_checksum(&checksum_content, &asset.name)?;
+        // Download checksum file
+        let checksum_asset = ReleaseAsset {
+            name: checksum_asset.name.clone(),
+            url: checksum_asset.browser_download_url.to_string(),
+            size: checksum_asset.size as u64,
+        };
 
-            // Calculate actual checksum
-            let actual_checksum = utils::calculate_sha256(file_path).map_err(|e| {
+        let checksum_file = self
+            .github_client
+            .download_asset(&checksum_asset)
+            .await
+            .map_err(|e| {
                 tracing::error!(
-                    "Failed to calculate SHA256 for {}: {}",
-                    file_path.display(),
+                    "Failed to download checksum file {}: {}",
+                    checksum_asset.name,
                     e
                 );
-                GhInstallError::Io(std::io::Error::other(e))
+                GhInstallError::DownloadFailed {
+                    asset: checksum_asset.name.clone(),
+                    url: checksum_asset.url.clone(),
+                    status: 0, // Unknown status
+                    message: e.to_string(),
+                }
             })?;
 
-            // Compare checksums
-            if actual_checksum.to_lowercase() != expected_checksum.to_lowercase() {
-                tracing::error!(
-                    "Checksum mismatch for {}: expected {}, got {}",
-                    asset.name,
-                    expected_checksum,
-                    actual_checksum
-                );
-                return Err(GhInstallError::ChecksumVerification {
-                    file: asset.name.clone(),
-                    expected: expected_checksum,
-                    actual: actual_checksum,
-                });
-            }
+        // Read checksums from file
+        let checksum_content = std::fs::read_to_string(checksum_file.path()).map_err(|e| {
+            tracing::error!("Failed to read checksum file: {}", e);
+            GhInstallError::Io(e)
+        })?;
 
-            tracing::info!("Checksum verified successfully for {}", asset.name);
-            Ok(())
+        // Parse checksums and find the one for our asset
+        let expected_checksum = if is_sidecar {
+            checksums::sidecar(&checksum_content, algorithm).ok_or_else(|| {
+                GhInstallError::ChecksumVerification {
+                    file: asset.name.clone(),
+                    expected: "Unknown".to_string(),
+                    actual: format!("No {algorithm:?} checksum in {}", checksum_asset.name),
+                }
+            })?
         } else {
-            // No checksum file found, which is an error unless --skip-checksum is used
-            tracing::warn!("No SHA256SUMS file found in release");
-            Err(GhInstallError::ChecksumFileNotFound)
-        }
-    }
+            parse_checksum(&checksum_content, &asset.name, algorithm)?
+        };
 
-    #[allow(clippy::result_large_err)]
-    fn parse_checksum(&self, content: &str, filename: &str) -> GhResult<String> {
-        // SHA256SUMS format: <checksum>  <filename>
-        for line in content.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                let checksum = parts[0];
-                let file = parts[1..].join(" ");
-
-                // Check if this line is for our file
-                if file == filename || file.ends_with(&format!("/{filename}")) {
-                    return Ok(checksum.to_string());
-                }
-            }
+        // Calculate actual checksum
+        let actual_checksum = algorithm.digest(file_path).map_err(|e| {
+            tracing::error!(
+                "Failed to calculate {:?} for {}: {}",
+                algorithm,
+                file_path.display(),
+                e
+            );
+            GhInstallError::Io(std::io::Error::other(e))
+        })?;
+
+        // Compare checksums
+        if actual_checksum.to_lowercase() != expected_checksum.to_lowercase() {
+            tracing::error!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                asset.name,
+                expected_checksum,
+                actual_checksum
+            );
+            return Err(GhInstallError::ChecksumVerification {
+                file: asset.name.clone(),
+                expected: expected_checksum,
+                actual: actual_checksum,
+            });
         }
 
-        tracing::error!("No checksum found for file: {}", filename);
-        Err(GhInstallError::ChecksumVerification {
-            file: filename.to_string(),
-            expected: "Unknown".to_string(),
-            actual: "Not found in checksum file".to_string(),
-        })
+        tracing::info!("Checksum verified successfully for {}", asset.name);
+        Ok(())
     }
 
     async fn verify_signature(
         &self,
         release: &octocrab::models::repos::Release,
         asset: &ReleaseAsset,
-        _file_path: &Path,
+        file_path: &Path,
     ) -> GhResult<()> {
         // Look for .sig or .asc file
         let sig_asset = release.assets.iter().find(|a| {
@@ -391,19 +918,73 @@ impl Installer {
             let asset_name = &asset.name;
             name == &format!("{asset_name}.sig") || name == &format!("{asset_name}.asc")
         });
-
-        if let Some(sig_asset) = sig_asset {
-            tracing::info!("Found signature file: {}", sig_asset.name);
-            Err(GhInstallError::SignatureVerificationUnsupported {
-                file: asset.name.clone(),
-                sig_file: sig_asset.name.clone(),
-            })
-        } else {
-            Err(GhInstallError::SignatureVerification {
+        let Some(sig_asset) = sig_asset else {
+            return Err(GhInstallError::SignatureVerification {
                 file: asset.name.clone(),
                 sig_file: format!("{}.sig or {}.asc", asset.name, asset.name),
-            })
+                reason: "the release has no signature for the asset".to_string(),
+            });
+        };
+        tracing::info!("Found signature file: {}", sig_asset.name);
+
+        let failed = |reason: String| GhInstallError::SignatureVerification {
+            file: asset.name.clone(),
+            sig_file: sig_asset.name.clone(),
+            reason,
+        };
+        let signature = self
+            .download_bytes(&sig_asset.name, sig_asset.browser_download_url.as_str())
+            .await
+            .map_err(|e| failed(format!("{e:#}")))?;
+        let keys = self
+            .trusted_keys()
+            .await
+            .map_err(|e| failed(format!("{e:#}")))?;
+        let fingerprint = signature::verify_detached(file_path, &signature, &keys)
+            .map_err(|e| failed(format!("{e:#}")))?;
+        tracing::info!(
+            "Signature of {} verified with key {}",
+            asset.name,
+            fingerprint
+        );
+        Ok(())
+    }
+
+    /// The keys trusted to sign the release: the --signing-key file or URL,
+    /// or the OpenPGP keys on the owner's GitHub account
+    async fn trusted_keys(&self) -> Result<Vec<u8>> {
+        let source = match &self.args.signing_key {
+            Some(key) => key.clone(),
+            None => {
+                let (owner, _, _) = self.args.parse_repo()?;
+                signature::github_keys_url(&owner)
+            }
+        };
+        // Over plain HTTP, whoever can swap the signature could swap the key too
+        if let Some((scheme, _)) = source.split_once("://").filter(|(s, _)| *s != "https") {
+            anyhow::bail!("Signing keys must come from a file or an https:// URL, not {scheme}://");
+        }
+        if source.starts_with("https://") {
+            tracing::info!("Fetching trusted keys from {}", source);
+            let keys = self.download_bytes("trusted keys", &source).await?;
+            anyhow::ensure!(!keys.is_empty(), "No OpenPGP keys at {source}");
+            return Ok(keys);
         }
+        let path = crate::cli::expand_tilde(&source);
+        fs::read(&path).with_context(|| format!("Failed to read signing key {}", path.display()))
+    }
+
+    /// Download a small file, such as a signature or key, into memory
+    async fn download_bytes(&self, name: &str, url: &str) -> Result<Vec<u8>> {
+        let file = self
+            .github_client
+            .download_asset(&ReleaseAsset {
+                name: name.to_string(),
+                url: url.to_string(),
+                size: 0,
+            })
+            .await?;
+        Ok(fs::read(file.path())?)
     }
 
     async fn fallback_cargo_install(
@@ -412,6 +993,27 @@ impl Installer {
         repo: &str,
         tag: Option<&str>,
     ) -> Result<()> {
+        // A git checkout can't be resolved from a version requirement, `latest-pre`
+        // or a release date
+        if let Some(tag) = tag.filter(|tag| {
+            *tag == github::LATEST_PRERELEASE || github::version_requirement(tag).is_some()
+        }) {
+            return Err(GhInstallError::Installation {
+                message: format!("no release matches '{tag}' to install"),
+                path: format!("https://github.com/{owner}/{repo}.git"),
+            }
+            .into());
+        }
+        if tag.is_none() {
+            if let Some(before) = self.args.selection.published_before() {
+                return Err(GhInstallError::Installation {
+                    message: format!("no release was published before {before} to install"),
+                    path: format!("https://github.com/{owner}/{repo}.git"),
+                }
+                .into());
+            }
+        }
+
         tracing::info!("Falling back to cargo install from git");
 
         let mut cmd = Command::new("cargo");
@@ -421,6 +1023,11 @@ impl Installer {
 
         if let Some(tag) = tag {
             cmd.arg("--rev").arg(tag);
+
+            // Monorepos need the crate name when the git repository has several packages
+            if let Some(package) = utils::package_from_tag(tag) {
+                cmd.arg(package);
+            }
         }
 
         if let Some(bin) = &self.args.bin {
@@ -441,9 +1048,82 @@ impl Installer {
     }
 }
 
+/// The first asset of each build, skipping the same build in another archive
+/// format
+fn distinct_builds(assets: &[ReleaseAsset]) -> Vec<&ReleaseAsset> {
+    let mut builds: Vec<&ReleaseAsset> = Vec::new();
+    for asset in assets {
+        let stem = github::archive_stem(&asset.name);
+        if !builds.iter().any(|b| github::archive_stem(&b.name) == stem) {
+            builds.push(asset);
+        }
+    }
+    builds
+}
+
+/// How many recent releases the release picker offers
+const PICKER_RELEASES: usize = 50;
+
+/// Picker lines for releases, with their tag, date and prerelease columns aligned
+fn release_choices(rows: &[Vec<String>]) -> Vec<String> {
+    utils::render_table(&["Tag", "Published", ""], rows)
+        .lines()
+        .skip(1)
+        .map(String::from)
+        .collect()
+}
+
+/// The binary name an install defaults to: the repository's, or the
+/// package's for per-package tags (cli-v1.2.0)
+fn default_binary_name<'a>(tag: Option<&'a str>, repo: &'a str) -> &'a str {
+    tag.and_then(utils::package_from_tag).unwrap_or(repo)
+}
+
+/// No asset of the release matches `target`; lists the assets it has with
+/// why each one was passed over
+fn asset_not_found(
+    release: &octocrab::models::repos::Release,
+    target: &str,
+    mismatch: impl Fn(&str) -> Option<String>,
+) -> GhInstallError {
+    let available_assets = release
+        .assets
+        .iter()
+        .map(|a| match mismatch(&a.name) {
+            Some(reason) => format!("{} ({reason})", a.name),
+            None => a.name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    GhInstallError::AssetNotFound {
+        target: target.to_string(),
+        release_tag: release.tag_name.clone(),
+        available: if available_assets.is_empty() {
+            "No assets available".to_string()
+        } else {
+            available_assets
+        },
+    }
+}
+
+/// The hash an aggregate checksum file (`SHA256SUMS` and the like) lists for `filename`
+#[allow(clippy::result_large_err)]
+fn parse_checksum(content: &str, filename: &str, algorithm: Algorithm) -> GhResult<String> {
+    checksums::find(content, filename, algorithm).ok_or_else(|| {
+        tracing::error!("No checksum found for file: {}", filename);
+        GhInstallError::ChecksumVerification {
+            file: filename.to_string(),
+            expected: "Unknown".to_string(),
+            actual: "Not found in checksum file".to_string(),
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::{ReceiptsArg, ReleaseSelection};
+    use clap::Parser;
     use std::fs;
     use tempfile::tempdir;
 
@@ -470,6 +1150,100 @@ mod tests {
         assert!(dest_file.exists());
     }
 
+    #[tokio::test]
+    async fn test_install_binary_replaces_only_owned_binaries() {
+        let source_dir = tempdir().unwrap();
+        let install_dir = tempdir().unwrap();
+        let state_dir = tempdir().unwrap();
+        let source = source_dir.path().join("tool");
+        fs::write(&source, b"new").unwrap();
+        let dest = install_dir
+            .path()
+            .join(if cfg!(windows) { "tool.exe" } else { "tool" });
+        fs::write(&dest, b"old").unwrap();
+
+        let receipts_path = state_dir.path().join("installed.toml");
+        let installer = |extra: &[&str]| {
+            let mut args = vec![
+                "cargo-ghinstall",
+                "owner/tool",
+                "--config",
+                "missing.toml",
+                "--receipts",
+                receipts_path.to_str().unwrap(),
+            ];
+            args.extend(extra);
+            Installer::new(Args::parse_from(args)).unwrap()
+        };
+        let backup = utils::backup_path(&state_dir.path().join("backups"), &dest);
+        let owned_by = |repo: &str| {
+            let mut receipts = Receipts::default();
+            receipts.record(
+                repo,
+                Receipt::new(
+                    "v1.0.0",
+                    "x86_64-unknown-linux-gnu",
+                    "tool.tar.gz",
+                    "a".repeat(64),
+                    vec![InstalledBinary {
+                        name: "tool".to_string(),
+                        path: dest.clone(),
+                        sha256: "b".repeat(64),
+                        renamed_from: None,
+                    }],
+                ),
+            );
+            receipts
+        };
+
+        // Another repository's binary is left alone without --force
+        let err = installer(&[])
+            .install_binary(&source, install_dir.path(), None, &owned_by("other/tool"))
+            .unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        assert_eq!(fs::read(&dest).unwrap(), b"old");
+
+        // One no receipt records, as from before receipts, is replaced
+        // without a backup by default
+        let (_, copied) = installer(&[])
+            .install_binary(&source, install_dir.path(), None, &Receipts::default())
+            .unwrap();
+        assert!(copied);
+        assert_eq!(fs::read(&dest).unwrap(), b"new");
+        assert!(!backup.exists());
+
+        // --backup keeps the replaced binary outside the install directory
+        fs::write(&dest, b"old").unwrap();
+        installer(&["--backup"])
+            .install_binary(&source, install_dir.path(), None, &owned_by("owner/tool"))
+            .unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), b"new");
+        assert_eq!(fs::read(&backup).unwrap(), b"old");
+        assert_eq!(fs::read_dir(install_dir.path()).unwrap().count(), 1);
+
+        // --force replaces another repository's binary
+        fs::write(&dest, b"foreign").unwrap();
+        installer(&["--force"])
+            .install_binary(&source, install_dir.path(), None, &owned_by("other/tool"))
+            .unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), b"new");
+    }
+
+    #[tokio::test]
+    async fn test_trusted_keys_require_https() {
+        let installer = Installer::new(Args::parse_from([
+            "cargo-ghinstall",
+            "owner/tool",
+            "--config",
+            "missing.toml",
+            "--signing-key",
+            "http://example.com/owner.gpg",
+        ]))
+        .unwrap();
+        let err = installer.trusted_keys().await.unwrap_err();
+        assert!(err.to_string().contains("https://"));
+    }
+
     #[test]
     fn test_select_default_executable_preserves_fallback_name() {
         let executables = vec![
@@ -498,6 +1272,92 @@ mod tests {
         assert!(rename_to_default);
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_select_cached() {
+        let dir = tempdir().unwrap();
+        for name in ["tool", "tool-helper"] {
+            let path = dir.path().join(name);
+            fs::write(&path, b"#!/bin/sh\n").unwrap();
+            utils::make_executable(&path).unwrap();
+        }
+        fs::write(dir.path().join("README.md"), b"docs").unwrap();
+
+        let installer = Installer::new(Args::parse_from([
+            "cargo-ghinstall",
+            "owner/tool",
+            "--config",
+            "missing.toml",
+        ]))
+        .unwrap();
+        let selected = installer.select_cached(dir.path(), "tool").unwrap();
+        assert_eq!(selected, Some(dir.path().join("tool")));
+
+        let installer = Installer::new(Args::parse_from([
+            "cargo-ghinstall",
+            "owner/tool",
+            "--bin",
+            "helper",
+            "--config",
+            "missing.toml",
+        ]))
+        .unwrap();
+        let selected = installer.select_cached(dir.path(), "tool").unwrap();
+        assert_eq!(selected, Some(dir.path().join("tool-helper")));
+
+        let empty = tempdir().unwrap();
+        assert_eq!(installer.select_cached(empty.path(), "tool").unwrap(), None);
+    }
+
+    #[test]
+    fn test_distinct_builds() {
+        let asset = |name: &str| ReleaseAsset {
+            name: name.to_string(),
+            url: format!("https://example.com/{name}"),
+            size: 1,
+        };
+        let assets = vec![
+            asset("tool-x86_64-unknown-linux-gnu.tar.gz"),
+            asset("tool-x86_64-unknown-linux-gnu.zip"),
+            asset("tool-lite-x86_64-unknown-linux-gnu.tar.xz"),
+        ];
+        let names = distinct_builds(&assets)
+            .iter()
+            .map(|asset| asset.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "tool-x86_64-unknown-linux-gnu.tar.gz",
+                "tool-lite-x86_64-unknown-linux-gnu.tar.xz"
+            ]
+        );
+        assert_eq!(distinct_builds(&assets[..2]).len(), 1);
+    }
+
+    #[test]
+    fn test_release_choices() {
+        let rows = vec![
+            vec![
+                "v2.0.0-rc.1".to_string(),
+                "2024-05-01".to_string(),
+                "prerelease".to_string(),
+            ],
+            vec![
+                "v1.9.0".to_string(),
+                "2024-04-01".to_string(),
+                String::new(),
+            ],
+        ];
+        assert_eq!(
+            release_choices(&rows),
+            vec![
+                "v2.0.0-rc.1  2024-05-01  prerelease",
+                "v1.9.0       2024-04-01",
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_checksum() {
         // Test standard SHA256SUMS format
@@ -532,20 +1392,42 @@ abc123def456  ./dist/test-binary-linux.tar.gz
     fn test_skip_checksum_behavior() {
         // Create test arguments with skip_checksum = false
         let args_verify = Args {
-            repo: "test/repo".to_string(),
+            repo: Some("test/repo".to_string()),
             tag: None,
             bin: None,
             bins: false,
-            target: None,
+            bin_names: Vec::new(),
+            rename: None,
             install_dir: "/tmp".to_string(),
+            force: false,
+            backup: false,
             show_notes: false,
+            extras: false,
             verify_signature: false,
+            signing_key: None,
+            verify_provenance: false,
+            require_provenance: false,
+            provenance_workflow: None,
+            require_signed: false,
             no_fallback: false,
+            no_arch_fallback: false,
             skip_checksum: false, // Should verify checksums
             config: std::path::PathBuf::from("test.toml"),
             verbose: false,
             max_retries: 3,
             no_retry: false,
+            no_progress: false,
+            receipts: ReceiptsArg {
+                receipts: std::path::PathBuf::from("installed.toml"),
+            },
+            from_file: None,
+            locked: false,
+            expected_sha256: None,
+            interactive: false,
+            yes: false,
+            prefer_musl: false,
+            prefer_gnu: false,
+            selection: ReleaseSelection::default(),
         };
 
         // Test that verification is required when skip_checksum is false
@@ -556,20 +1438,42 @@ abc123def456  ./dist/test-binary-linux.tar.gz
 
         // Create test arguments with skip_checksum = true
         let args_skip = Args {
-            repo: "test/repo".to_string(),
+            repo: Some("test/repo".to_string()),
             tag: None,
             bin: None,
             bins: false,
-            target: None,
+            bin_names: Vec::new(),
+            rename: None,
             install_dir: "/tmp".to_string(),
+            force: false,
+            backup: false,
             show_notes: false,
+            extras: false,
             verify_signature: false,
+            signing_key: None,
+            verify_provenance: false,
+            require_provenance: false,
+            provenance_workflow: None,
+            require_signed: false,
             no_fallback: false,
+            no_arch_fallback: false,
             skip_checksum: true, // Should skip checksums
             config: std::path::PathBuf::from("test.toml"),
             verbose: false,
             max_retries: 3,
             no_retry: false,
+            no_progress: false,
+            receipts: ReceiptsArg {
+                receipts: std::path::PathBuf::from("installed.toml"),
+            },
+            from_file: None,
+            locked: false,
+            expected_sha256: None,
+            interactive: false,
+            yes: false,
+            prefer_musl: false,
+            prefer_gnu: false,
+            selection: ReleaseSelection::default(),
         };
 
         // Test that verification is skipped when skip_checksum is true
@@ -606,39 +1510,12 @@ abc123def456  ./dist/test-binary-linux.tar.gz
 
     // Helper function to parse a single checksum line
     fn parse_checksum_for_line(line: &str, filename: &str) -> Option<String> {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            let checksum = parts[0];
-            let file = parts[1..].join(" ");
-
-            if file == filename || file.ends_with(&format!("/{filename}")) {
-                return Some(checksum.to_string());
-            }
-        }
-        None
+        parse_checksum(line, filename, Algorithm::Sha256).ok()
     }
 
     // Helper function for testing parse_checksum logic
     #[allow(clippy::result_large_err)]
     fn parse_checksum_helper(content: &str, filename: &str) -> GhResult<String> {
-        // SHA256SUMS format: <checksum>  <filename>
-        for line in content.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                let checksum = parts[0];
-                let file = parts[1..].join(" ");
-
-                // Check if this line is for our file
-                if file == filename || file.ends_with(&format!("/{filename}")) {
-                    return Ok(checksum.to_string());
-                }
-            }
-        }
-
-        Err(GhInstallError::ChecksumVerification {
-            file: filename.to_string(),
-            expected: "Unknown".to_string(),
-            actual: "Not found in checksum file".to_string(),
-        })
+        parse_checksum(content, filename, Algorithm::Sha256)
     }
 }
@@ -15,8 +15,9 @@
 //! - Architecture detection (x86_64, aarch64)
 //! - Support for specific version tags and latest releases
 //! - Multiple binary selection with `--bin` flag
-//! - Configuration file support for custom settings
+//! - Configuration file support with typo suggestions for unknown keys
 //! - Fallback to `cargo install` when binaries are unavailable
+//! - Tokens from GITHUB_TOKEN, the OS keyring or the gh CLI
 //!
 //! ## Usage
 //!
@@ -36,23 +37,83 @@
 //! Configuration defaults to `~/.config/ghinstall.toml`; pass `--config`
 //! to use a project-local file.
 
+/// Installing the tools declared in a manifest with `--from-file`
+pub mod batch;
+
+/// Parsing release checksum files and hashing assets to check them against
+pub mod checksums;
+
 /// Command-line interface definitions and argument parsing
 pub mod cli;
 
+/// Code signature and Gatekeeper checks of macOS binaries
+pub mod codesign;
+
+/// `assets`: a release's assets and which one an install would pick
+pub mod assets;
+
 /// Configuration file handling and repository-specific settings
 pub mod config;
 
 /// Error types and error handling utilities
 pub mod error;
 
+/// Shell completions and man pages shipped alongside release binaries
+pub mod extras;
+
 /// GitHub API client for interacting with releases and assets
 pub mod github;
 
 /// Core installation logic for downloading and installing binaries
 pub mod installer;
 
+/// Scoring release assets against a target triple
+pub mod matcher;
+
 /// Utility functions for platform detection, archive extraction, and file operations
 pub mod utils;
 
+/// Extracting the binaries of .deb and .rpm packages without dpkg or rpm
+pub mod package;
+
+/// `outdated`: recorded installs compared with their repositories' latest releases
+pub mod outdated;
+
+/// Checking the install directory is on PATH and helping put it there
+pub mod path_setup;
+
+/// Choosing from a list of releases or assets at a terminal prompt
+pub mod picker;
+
+/// Verifying detached OpenPGP signatures of release assets
+pub mod signature;
+
+/// SLSA build provenance attestations of release assets
+pub mod provenance;
+
+/// Receipts of installed releases, binaries and their hashes
+pub mod receipts;
+
+/// `releases`: the releases of a repository
+pub mod releases;
+
 /// Network retry logic with exponential backoff
 pub mod retry;
+
+/// Download and upload progress bars and their log-line fallback, shared with cargo-ghdist
+pub mod progress;
+
+/// GitHub token lookup in the environment, OS keyring and gh CLI, shared with cargo-ghdist
+pub mod auth;
+
+/// `rollback`: restoring the binaries an install replaced from their backups
+pub mod rollback;
+
+/// `run`: executing a release binary from a cache without installing it
+pub mod run;
+
+/// Updating cargo-ghinstall and cargo-ghdist from their own releases
+pub mod self_update;
+
+/// Strict config parsing with "did you mean" hints and target triple checks
+pub mod validate;
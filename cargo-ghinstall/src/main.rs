@@ -1,16 +1,39 @@
+mod assets;
+mod auth;
+mod batch;
+mod checksums;
 mod cli;
+mod codesign;
 mod config;
 mod error;
+mod extras;
 mod github;
 mod installer;
+mod matcher;
+mod outdated;
+mod package;
+mod path_setup;
+mod picker;
+mod progress;
+mod provenance;
+mod receipts;
+mod releases;
 mod retry;
+mod rollback;
+mod run;
+mod self_update;
+mod signature;
 mod utils;
+mod validate;
 
 use anyhow::Result;
 use clap::Parser;
 use tracing_subscriber::EnvFilter;
 
-use crate::cli::{Args, CargoCli};
+use crate::cli::{
+    Args, AssetsArgs, AuthAction, AuthArgs, CargoCli, OutdatedArgs, ReleasesArgs, RollbackArgs,
+    RunArgs, SelfAction, SelfArgs,
+};
 use crate::installer::Installer;
 
 #[tokio::main]
@@ -20,6 +43,45 @@ async fn main() -> Result<()> {
         .with_env_filter(EnvFilter::from_default_env())
         .init();
 
+    if let Some(auth) = AuthArgs::parse_if_requested() {
+        return match auth.action {
+            AuthAction::Login { with_token } => {
+                auth::login(with_token, "no scopes needed for public repositories")
+            }
+            AuthAction::Logout => auth::logout(),
+            AuthAction::Status => auth::status("ghinstall"),
+        };
+    }
+
+    if let Some(self_args) = SelfArgs::parse_if_requested() {
+        return match self_args.action {
+            SelfAction::Update { tag } => {
+                self_update::run("cargo-ghinstall", env!("CARGO_PKG_VERSION"), tag.as_deref()).await
+            }
+        };
+    }
+
+    if let Some(releases) = ReleasesArgs::parse_if_requested() {
+        return releases::run(&releases).await;
+    }
+
+    if let Some(assets) = AssetsArgs::parse_if_requested() {
+        return assets::run(&assets).await;
+    }
+
+    if let Some(run) = RunArgs::parse_if_requested() {
+        let code = run::run(&run).await?;
+        std::process::exit(code);
+    }
+
+    if let Some(outdated) = OutdatedArgs::parse_if_requested() {
+        return outdated::run(&outdated).await;
+    }
+
+    if let Some(rollback) = RollbackArgs::parse_if_requested() {
+        return rollback::run(&rollback);
+    }
+
     // Parse command line arguments - handle both cargo subcommand and direct invocation
     let args = match CargoCli::try_parse() {
         Ok(CargoCli::Ghinstall(args)) => args,
@@ -33,9 +95,22 @@ async fn main() -> Result<()> {
         tracing::info!("Running cargo-ghinstall with verbose output");
     }
 
+    let install_dir = args.install_dir();
+    let prompt = !args.yes && picker::is_interactive();
+    if let Some(manifest) = &args.from_file {
+        batch::run(&args, manifest).await?;
+        return path_setup::check(&install_dir, prompt);
+    }
+    anyhow::ensure!(
+        !args.locked,
+        "--locked installs from a lockfile and needs --from-file"
+    );
+
     // Create installer and run
     let installer = Installer::new(args)?;
-    installer.run().await?;
+    if installer.run().await?.is_some() {
+        path_setup::check(&install_dir, prompt)?;
+    }
 
     Ok(())
 }
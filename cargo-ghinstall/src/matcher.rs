@@ -0,0 +1,408 @@
+use std::fmt;
+
+/// Score of an asset naming the exact target triple, on top of its platform
+/// score, so it beats any spelling of the same platform
+const EXACT_TRIPLE: u32 = 100;
+/// Score of an asset naming the target's operating system
+const OS_MATCH: u32 = 20;
+/// Score of an asset naming the target's architecture
+const ARCH_MATCH: u32 = 20;
+/// Score of a macOS universal build, below a native one
+const ARCH_UNIVERSAL: u32 = 15;
+/// Score of an asset naming no architecture on an x86_64 target, which is
+/// what such assets are built for in practice
+const ARCH_ASSUMED: u32 = 5;
+/// Score of an asset naming the target's libc or ABI
+const LIBC_MATCH: u32 = 10;
+/// Score of an asset naming no libc, below one naming the target's
+const LIBC_UNSTATED: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X86_64,
+    Aarch64,
+    X86,
+    Arm,
+    Riscv64,
+    Powerpc64,
+    S390x,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Os {
+    Linux,
+    Macos,
+    Windows,
+    FreeBsd,
+    NetBsd,
+    Android,
+    Illumos,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Libc {
+    Gnu,
+    Musl,
+    Msvc,
+}
+
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::X86_64 => "x86_64",
+            Self::Aarch64 => "aarch64",
+            Self::X86 => "x86",
+            Self::Arm => "arm",
+            Self::Riscv64 => "riscv64",
+            Self::Powerpc64 => "powerpc64",
+            Self::S390x => "s390x",
+        })
+    }
+}
+
+impl fmt::Display for Os {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Linux => "linux",
+            Self::Macos => "macos",
+            Self::Windows => "windows",
+            Self::FreeBsd => "freebsd",
+            Self::NetBsd => "netbsd",
+            Self::Android => "android",
+            Self::Illumos => "illumos",
+        })
+    }
+}
+
+impl fmt::Display for Libc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Gnu => "glibc",
+            Self::Musl => "musl",
+            Self::Msvc => "msvc",
+        })
+    }
+}
+
+/// What one token of an asset name says about the platform it's built for
+#[derive(Debug, Default, PartialEq)]
+struct Token {
+    arch: Option<Arch>,
+    os: Option<Os>,
+    libc: Option<Libc>,
+    universal: bool,
+}
+
+/// The platform a token names, through the spellings releases use for the
+/// same architecture, OS or libc
+fn classify(token: &str) -> Token {
+    let arch = match token {
+        "x86_64" | "amd64" | "x64" | "64bit" => Some(Arch::X86_64),
+        "aarch64" | "arm64" | "armv8" => Some(Arch::Aarch64),
+        "x86" | "i386" | "i486" | "i586" | "i686" | "386" | "32bit" => Some(Arch::X86),
+        "arm" | "armv6" | "armv7" | "armv7l" | "armhf" | "armel" => Some(Arch::Arm),
+        "riscv64" | "riscv64gc" => Some(Arch::Riscv64),
+        "powerpc64" | "powerpc64le" | "ppc64" | "ppc64le" => Some(Arch::Powerpc64),
+        "s390x" => Some(Arch::S390x),
+        _ => None,
+    };
+    let libc = match token {
+        "gnu" | "glibc" | "gnueabi" | "gnueabihf" => Some(Libc::Gnu),
+        "musl" | "musleabi" | "musleabihf" => Some(Libc::Musl),
+        "msvc" => Some(Libc::Msvc),
+        _ => None,
+    };
+    // Compound names carry both the OS and the architecture
+    let (os, arch) = match token {
+        "win64" => (Some(Os::Windows), Some(Arch::X86_64)),
+        "win32" => (Some(Os::Windows), Some(Arch::X86)),
+        "linux64" => (Some(Os::Linux), Some(Arch::X86_64)),
+        "linux32" => (Some(Os::Linux), Some(Arch::X86)),
+        _ => (os_name(token), arch),
+    };
+    Token {
+        arch,
+        os,
+        libc,
+        universal: matches!(token, "universal" | "universal2"),
+    }
+}
+
+/// The OS a token names, allowing a trailing version as in `macos11` or
+/// `darwin20`
+fn os_name(token: &str) -> Option<Os> {
+    let name = token.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    match name {
+        "linux" => Some(Os::Linux),
+        "darwin" | "macos" | "macosx" | "osx" | "apple" => Some(Os::Macos),
+        "windows" | "win" => Some(Os::Windows),
+        "freebsd" => Some(Os::FreeBsd),
+        "netbsd" => Some(Os::NetBsd),
+        "android" => Some(Os::Android),
+        "illumos" | "solaris" => Some(Os::Illumos),
+        _ => None,
+    }
+}
+
+/// The lowercase words of an asset name, without its archive extension,
+/// keeping `x86_64` and `x86-64` whole
+fn tokenize(name: &str) -> Vec<String> {
+    let name = name.to_lowercase();
+    let stem = crate::github::archive_stem(&name);
+    let mut tokens: Vec<String> = Vec::new();
+    for word in stem
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        match tokens.last_mut() {
+            Some(last) if last == "x86" && word == "64" => last.push_str("_64"),
+            _ => tokens.push(word.to_string()),
+        }
+    }
+    tokens
+}
+
+/// A target triple's architecture, OS and libc
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Platform {
+    pub arch: Arch,
+    pub os: Os,
+    pub libc: Option<Libc>,
+}
+
+impl Platform {
+    /// The platform of a target triple, `None` for triples it doesn't know
+    pub fn parse(target: &str) -> Option<Self> {
+        let parts = target.split('-').collect::<Vec<_>>();
+        let arch = classify(parts.first()?).arch?;
+        // Android triples also name linux
+        let os = if parts.contains(&"android") || parts.contains(&"androideabi") {
+            Os::Android
+        } else {
+            parts.iter().find_map(|part| os_name(part))?
+        };
+        let libc = parts.last().and_then(|part| classify(part).libc);
+        Some(Self { arch, os, libc })
+    }
+}
+
+/// How well the asset `name` fits the target and binary, or why it
+/// doesn't: naming the exact triple scores highest, then naming the
+/// target's OS, architecture and libc through any of their aliases. An
+/// asset naming another OS or architecture, or none at all, doesn't match.
+pub fn score(name: &str, target: &str, bin_name: Option<&str>) -> Result<u32, String> {
+    if !crate::github::is_archive(name)
+        && !crate::github::is_bare_binary(name)
+        && !crate::github::is_compressed_binary(name)
+    {
+        return Err("not an archive or executable".to_string());
+    }
+    if let Some(bin_name) = bin_name.filter(|bin| !bin.is_empty()) {
+        if !name.contains(bin_name) {
+            return Err(format!("name doesn't contain {bin_name}"));
+        }
+    }
+    let lower = name.to_lowercase();
+    if lower.contains(&target.to_lowercase()) {
+        return Ok(EXACT_TRIPLE + OS_MATCH + ARCH_MATCH + LIBC_MATCH);
+    }
+    let Some(platform) = Platform::parse(target) else {
+        return Err(format!("name doesn't contain {target}"));
+    };
+
+    let tokens = tokenize(name)
+        .iter()
+        .map(|t| classify(t))
+        .collect::<Vec<_>>();
+    let mut oses = tokens.iter().filter_map(|t| t.os).collect::<Vec<_>>();
+    if oses.contains(&Os::Android) {
+        oses.retain(|os| *os != Os::Linux);
+    }
+    // A bare .exe names its OS by its extension
+    if oses.is_empty() && lower.contains(".exe") {
+        oses.push(Os::Windows);
+    }
+    // So do packages, which are built against glibc
+    let package = crate::github::is_package(&lower);
+    if oses.is_empty() && package {
+        oses.push(Os::Linux);
+    }
+    let arches = tokens.iter().filter_map(|t| t.arch).collect::<Vec<_>>();
+    let mut libcs = tokens.iter().filter_map(|t| t.libc).collect::<Vec<_>>();
+    if libcs.is_empty() && package {
+        libcs.push(Libc::Gnu);
+    }
+    let universal = tokens.iter().any(|t| t.universal);
+
+    let os_score = match oses.first() {
+        None => return Err("name has no operating system".to_string()),
+        Some(_) if oses.contains(&platform.os) => OS_MATCH,
+        Some(os) => return Err(format!("built for {os}, not {}", platform.os)),
+    };
+    let arch_score = match arches.first() {
+        _ if arches.contains(&platform.arch) => ARCH_MATCH,
+        _ if universal
+            && platform.os == Os::Macos
+            && matches!(platform.arch, Arch::X86_64 | Arch::Aarch64) =>
+        {
+            ARCH_UNIVERSAL
+        }
+        Some(arch) => return Err(format!("built for {arch}, not {}", platform.arch)),
+        None if platform.arch == Arch::X86_64 => ARCH_ASSUMED,
+        None => {
+            return Err(format!(
+                "name has no architecture, taken as x86_64 rather than {}",
+                platform.arch
+            ))
+        }
+    };
+    let libc_score = match (platform.libc, libcs.first()) {
+        (Some(libc), _) if libcs.contains(&libc) => LIBC_MATCH,
+        (Some(_), None) => LIBC_UNSTATED,
+        // glibc builds don't run on musl hosts
+        (Some(Libc::Musl), Some(Libc::Gnu)) => {
+            return Err(format!("built for {}, not musl", Libc::Gnu))
+        }
+        _ => 0,
+    };
+    Ok(os_score + arch_score + libc_score)
+}
+
+/// The order matching assets are ranked in: archives and binaries before
+/// packages, which are only taken when nothing else matches, then by score
+pub fn rank(name: &str, score: u32) -> (bool, u32) {
+    (!crate::github::is_package(name), score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("Tool_1.2.3_Linux_x86_64.tar.gz"),
+            ["tool", "1", "2", "3", "linux", "x86_64"]
+        );
+        assert_eq!(tokenize("tool-x86-64-win.zip"), ["tool", "x86_64", "win"]);
+    }
+
+    #[test]
+    fn test_platform_parse() {
+        assert_eq!(
+            Platform::parse("x86_64-unknown-linux-musl"),
+            Some(Platform {
+                arch: Arch::X86_64,
+                os: Os::Linux,
+                libc: Some(Libc::Musl)
+            })
+        );
+        assert_eq!(
+            Platform::parse("aarch64-apple-darwin"),
+            Some(Platform {
+                arch: Arch::Aarch64,
+                os: Os::Macos,
+                libc: None
+            })
+        );
+        assert_eq!(
+            Platform::parse("armv7-linux-androideabi").map(|p| p.os),
+            Some(Os::Android)
+        );
+        assert_eq!(Platform::parse("wasm32-wasi"), None);
+    }
+
+    #[test]
+    fn test_score_aliases() {
+        let linux = "x86_64-unknown-linux-gnu";
+        assert_eq!(
+            score("tool-x86_64-unknown-linux-gnu.tar.gz", linux, None),
+            Ok(150)
+        );
+        assert_eq!(score("tool_Linux_amd64.tar.gz", linux, None), Ok(45));
+        assert_eq!(score("tool-linux64.tar.gz", linux, None), Ok(45));
+        assert_eq!(score("tool-linux.tar.gz", linux, None), Ok(30));
+        assert_eq!(score("tool-linux-amd64-gnu.tar.gz", linux, None), Ok(50));
+
+        let mac = "aarch64-apple-darwin";
+        assert_eq!(score("tool-macos-arm64.tar.gz", mac, None), Ok(40));
+        assert_eq!(score("tool_Darwin_arm64.tar.gz", mac, None), Ok(40));
+        assert_eq!(score("tool-macos11-universal.zip", mac, None), Ok(35));
+
+        let windows = "x86_64-pc-windows-msvc";
+        assert_eq!(score("tool-win64.zip", windows, None), Ok(45));
+        assert_eq!(score("tool-windows-x64.zip", windows, None), Ok(45));
+        assert_eq!(score("tool-x64.exe", windows, None), Ok(45));
+        assert_eq!(score("tool-linux-amd64", linux, None), Ok(45));
+        assert_eq!(score("tool-linux-amd64.gz", linux, None), Ok(45));
+        assert_eq!(score("tool-x64.exe.zst", windows, None), Ok(45));
+        assert_eq!(score("tool_1.0.0_amd64.deb", linux, None), Ok(50));
+        assert_eq!(score("tool-1.0.0-1.x86_64.rpm", linux, None), Ok(50));
+    }
+
+    #[test]
+    fn test_score_rejects() {
+        let linux = "x86_64-unknown-linux-gnu";
+        assert_eq!(
+            score("tool-aarch64-apple-darwin.tar.gz", linux, None),
+            Err("built for macos, not linux".to_string())
+        );
+        assert_eq!(
+            score("tool-linux-arm64.tar.gz", linux, None),
+            Err("built for aarch64, not x86_64".to_string())
+        );
+        assert_eq!(
+            score("tool-1.0.tar.gz", linux, None),
+            Err("name has no operating system".to_string())
+        );
+        assert_eq!(
+            score("tool-aarch64-linux-android.tar.gz", linux, None),
+            Err("built for android, not linux".to_string())
+        );
+        assert_eq!(
+            score("tool-linux.tar.gz", "aarch64-unknown-linux-gnu", None),
+            Err("name has no architecture, taken as x86_64 rather than aarch64".to_string())
+        );
+        assert_eq!(
+            score(
+                "tool-x86_64-unknown-linux-gnu.tar.gz",
+                "x86_64-unknown-linux-musl",
+                None
+            ),
+            Err("built for glibc, not musl".to_string())
+        );
+        assert_eq!(
+            score("tool_1.0.0_amd64.deb", "x86_64-unknown-linux-musl", None),
+            Err("built for glibc, not musl".to_string())
+        );
+        assert_eq!(
+            score("tool-x86_64-unknown-linux-gnu.tar.gz", "wasm32-wasi", None),
+            Err("name doesn't contain wasm32-wasi".to_string())
+        );
+    }
+
+    #[test]
+    fn test_score_prefers_exact_builds() {
+        let linux = "x86_64-unknown-linux-gnu";
+        let exact = score("tool-x86_64-unknown-linux-gnu.tar.gz", linux, None).unwrap();
+        let gnu = score("tool-linux-amd64-gnu.tar.gz", linux, None).unwrap();
+        let musl = score("tool-x86_64-unknown-linux-musl.tar.gz", linux, None).unwrap();
+        let unstated = score("tool-linux-amd64.tar.gz", linux, None).unwrap();
+        assert!(exact > gnu && gnu > unstated && unstated > musl);
+
+        let mac = "aarch64-apple-darwin";
+        let native = score("tool-darwin-arm64.tar.gz", mac, None).unwrap();
+        let universal = score("tool-darwin-universal.tar.gz", mac, None).unwrap();
+        assert!(native > universal);
+
+        // The other Windows ABI still runs, below builds for the target's
+        let msvc = "x86_64-pc-windows-msvc";
+        let exact = score("tool-x86_64-pc-windows-msvc.zip", msvc, None).unwrap();
+        let gnu = score("tool-x86_64-pc-windows-gnu.zip", msvc, None).unwrap();
+        assert!(exact > gnu);
+        let mingw = "x86_64-pc-windows-gnu";
+        let exact = score("tool-x86_64-pc-windows-gnu.zip", mingw, None).unwrap();
+        let msvc = score("tool-x86_64-pc-windows-msvc.zip", mingw, None).unwrap();
+        assert!(exact > msvc);
+    }
+}
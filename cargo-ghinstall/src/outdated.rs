@@ -0,0 +1,232 @@
+use anyhow::Result;
+use semver::Version;
+use std::path::Path;
+use std::process::Command;
+
+use crate::cli::OutdatedArgs;
+use crate::github::{self, GitHubClient};
+use crate::receipts::{Receipt, Receipts};
+use crate::utils;
+
+/// One recorded install and the newest release of its repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutdatedRow {
+    pub repo: String,
+    pub installed: String,
+    /// `None` when the latest release couldn't be fetched
+    pub latest: Option<String>,
+}
+
+impl OutdatedRow {
+    pub fn has_upgrade(&self) -> bool {
+        self.latest
+            .as_deref()
+            .is_some_and(|latest| is_upgrade(&self.installed, latest))
+    }
+}
+
+/// Print the installs with newer releases, failing when there are any
+pub async fn run(args: &OutdatedArgs) -> Result<()> {
+    let receipts_path = args.receipts.receipts_path();
+    let receipts = Receipts::load(&receipts_path)?;
+    if receipts.installed.is_empty() {
+        println!("No installs recorded in {}", receipts_path.display());
+        return Ok(());
+    }
+
+    let client = GitHubClient::new()?;
+    let mut rows = Vec::new();
+    for (repo, receipt) in &receipts.installed {
+        let client = client.clone().with_tag_prefix(receipt.tag_prefix.clone());
+        let prefix = receipt.tag_prefix.as_deref();
+        let latest = match repo.split_once('/') {
+            Some((owner, name)) => match client.get_release(owner, name, None).await {
+                Ok(release) => Some(unprefixed(&release.tag_name, prefix)),
+                Err(e) => {
+                    tracing::warn!("Failed to fetch the latest release of {}: {}", repo, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        rows.push(OutdatedRow {
+            repo: repo.clone(),
+            installed: unprefixed(&installed_version(receipt), prefix),
+            latest,
+        });
+    }
+
+    print!("{}", render_table(&rows));
+    let upgrades = rows.iter().filter(|row| row.has_upgrade()).count();
+    if upgrades > 0 {
+        anyhow::bail!("{upgrades} installed tool(s) have newer releases");
+    }
+    if rows.iter().all(|row| row.latest.is_some()) {
+        println!("All installed tools are up to date");
+    }
+    Ok(())
+}
+
+/// The recorded tag, or the version the binary reports when it was
+/// replaced since ghinstall installed it
+fn installed_version(receipt: &Receipt) -> String {
+    receipt
+        .binaries
+        .iter()
+        .find(|binary| !binary.is_intact())
+        .and_then(|binary| probe_version(&binary.path))
+        .unwrap_or_else(|| receipt.tag.clone())
+}
+
+/// The tag without the prefix it was resolved with, e.g. `1.2.3` for
+/// `tool2-v1.2.3`, whose prefix would otherwise be read as part of the version
+fn unprefixed(tag: &str, prefix: Option<&str>) -> String {
+    github::strip_tag_prefix(tag, prefix)
+        .unwrap_or(tag)
+        .to_string()
+}
+
+/// The first version number in `<binary> --version`
+fn probe_version(binary: &Path) -> Option<String> {
+    let output = Command::new(binary).arg("--version").output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .split_whitespace()
+        .find(|word| tag_version(word).is_some())
+        .map(String::from)
+}
+
+/// The semantic version in a tag such as `v1.2.3` or `cli-v1.2.3`
+fn tag_version(tag: &str) -> Option<Version> {
+    let start = tag.find(|c: char| c.is_ascii_digit())?;
+    Version::parse(&tag[start..]).ok()
+}
+
+/// Whether `latest` is newer than `installed`, comparing versions when both
+/// tags have one and otherwise treating any other tag as an upgrade
+pub fn is_upgrade(installed: &str, latest: &str) -> bool {
+    match (tag_version(installed), tag_version(latest)) {
+        (Some(installed), Some(latest)) => latest > installed,
+        _ => installed != latest,
+    }
+}
+
+fn render_table(rows: &[OutdatedRow]) -> String {
+    let lines = rows
+        .iter()
+        .map(|row| {
+            let status = if row.latest.is_none() {
+                "unknown"
+            } else if row.has_upgrade() {
+                "upgrade available"
+            } else {
+                "up to date"
+            };
+            vec![
+                row.repo.clone(),
+                row.installed.clone(),
+                row.latest.clone().unwrap_or_else(|| "?".to_string()),
+                status.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    utils::render_table(&["Repository", "Installed", "Latest", ""], &lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipts::InstalledBinary;
+
+    fn row(installed: &str, latest: Option<&str>) -> OutdatedRow {
+        OutdatedRow {
+            repo: "owner/tool".to_string(),
+            installed: installed.to_string(),
+            latest: latest.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_is_upgrade() {
+        assert!(is_upgrade("v1.2.3", "v1.3.0"));
+        assert!(is_upgrade("1.2.3", "v1.2.4"));
+        assert!(is_upgrade("cli-v1.0.0", "cli-v1.0.1"));
+        assert!(!is_upgrade("v1.2.3", "v1.2.3"));
+        // A newer prerelease installed by tag is not downgraded
+        assert!(!is_upgrade("v2.0.0-rc.1", "v1.9.0"));
+        assert!(is_upgrade("nightly-2024-01-01", "nightly-2024-02-01"));
+        assert!(!is_upgrade("main", "main"));
+    }
+
+    #[test]
+    fn test_unprefixed() {
+        let prefix = Some("tool2-v");
+        assert_eq!(unprefixed("tool2-v1.2.3", prefix), "1.2.3");
+        assert!(is_upgrade(
+            &unprefixed("tool2-v1.2.3", prefix),
+            &unprefixed("tool2-v1.10.0", prefix)
+        ));
+        // A probed version has no prefix to strip
+        assert_eq!(unprefixed("1.4.2", prefix), "1.4.2");
+        assert_eq!(unprefixed("v1.4.2", None), "v1.4.2");
+    }
+
+    #[test]
+    fn test_installed_version_uses_receipt_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tool");
+        std::fs::write(&path, b"binary").unwrap();
+        let receipt = Receipt {
+            tag: "v1.2.3".to_string(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            asset: "tool-x86_64-unknown-linux-gnu.tar.gz".to_string(),
+            asset_sha256: "a".repeat(64),
+            tag_prefix: None,
+            installed_at: 0,
+            binaries: vec![InstalledBinary {
+                name: "tool".to_string(),
+                sha256: crate::utils::calculate_sha256(&path).unwrap(),
+                path,
+                renamed_from: None,
+            }],
+            extras: Vec::new(),
+            previous: None,
+        };
+        assert_eq!(installed_version(&receipt), "v1.2.3");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe_version() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tool");
+        std::fs::write(&path, "#!/bin/sh\necho 'tool 1.4.2 (abc1234)'\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(probe_version(&path).as_deref(), Some("1.4.2"));
+        assert_eq!(probe_version(&dir.path().join("missing")), None);
+    }
+
+    #[test]
+    fn test_render_table() {
+        let rows = vec![
+            row("v1.2.3", Some("v1.3.0")),
+            OutdatedRow {
+                repo: "owner/another-tool".to_string(),
+                ..row("v2.0.0", Some("v2.0.0"))
+            },
+            row("v0.1.0", None),
+        ];
+        assert_eq!(
+            render_table(&rows),
+            "\
+Repository          Installed  Latest
+owner/tool          v1.2.3     v1.3.0  upgrade available
+owner/another-tool  v2.0.0     v2.0.0  up to date
+owner/tool          v0.1.0     ?       unknown
+"
+        );
+        assert!(rows[0].has_upgrade());
+        assert!(!rows[2].has_upgrade());
+    }
+}
@@ -0,0 +1,287 @@
+use crate::error::GhInstallError;
+use anyhow::Result;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Directories of a package whose files are installed; the rest of the
+/// package (libraries, docs, system config) is left out
+const BIN_DIRS: &[&str] = &["usr/bin/", "usr/local/bin/", "bin/"];
+
+/// RPM header magic, followed by 4 reserved bytes and the entry counts
+const RPM_HEADER_MAGIC: [u8; 4] = [0x8e, 0xad, 0xe8, 0x01];
+/// Size of the RPM lead before the signature header
+const RPM_LEAD_SIZE: usize = 96;
+
+/// The file name of a package path under one of [`BIN_DIRS`], `None` for
+/// everything else, including subdirectories of them
+fn bin_file_name(path: &str) -> Option<&str> {
+    let path = path.trim_start_matches("./").trim_start_matches('/');
+    BIN_DIRS
+        .iter()
+        .find_map(|dir| path.strip_prefix(dir))
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+}
+
+/// Extract the binaries of a .deb: the `usr/bin` files of its `data.tar.*`
+/// member, without dpkg
+pub fn extract_deb(package_path: &Path, dest_dir: &Path) -> Result<()> {
+    let error = |reason: String| GhInstallError::ArchiveExtraction {
+        file: package_path.display().to_string(),
+        reason,
+    };
+    let file =
+        fs::File::open(package_path).map_err(|e| error(format!("Failed to open package: {e}")))?;
+    let mut archive = ar::Archive::new(file);
+    while let Some(entry) = archive.next_entry() {
+        let entry = entry.map_err(|e| error(format!("Failed to read .deb: {e}")))?;
+        let identifier = String::from_utf8_lossy(entry.header().identifier()).into_owned();
+        let Some(compression) = identifier.strip_prefix("data.tar") else {
+            continue;
+        };
+        let data = decompress(compression, entry)
+            .map_err(|e| error(format!("Failed to read {identifier}: {e}")))?;
+        let mut tar = tar::Archive::new(data);
+        for entry in tar
+            .entries()
+            .map_err(|e| error(format!("Failed to read {identifier}: {e}")))?
+        {
+            let mut entry =
+                entry.map_err(|e| error(format!("Failed to read {identifier}: {e}")))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry.path()?.to_string_lossy().into_owned();
+            if let Some(name) = bin_file_name(&path) {
+                entry.unpack(dest_dir.join(name))?;
+            }
+        }
+        return Ok(());
+    }
+    Err(error("No data.tar member in .deb".to_string()).into())
+}
+
+/// Extract the binaries of an .rpm: the `usr/bin` files of its cpio
+/// payload, without rpm
+pub fn extract_rpm(package_path: &Path, dest_dir: &Path) -> Result<()> {
+    let error = |reason: String| GhInstallError::ArchiveExtraction {
+        file: package_path.display().to_string(),
+        reason,
+    };
+    let bytes =
+        fs::read(package_path).map_err(|e| error(format!("Failed to open package: {e}")))?;
+    if bytes.get(..4) != Some(&[0xed, 0xab, 0xee, 0xdb][..]) {
+        return Err(error("Not an RPM package".to_string()).into());
+    }
+
+    // The lead, then the signature header padded to 8 bytes, then the header
+    let signature_end = rpm_header_end(&bytes, RPM_LEAD_SIZE)
+        .ok_or_else(|| error("Truncated signature header".to_string()))?;
+    let header_start = signature_end.next_multiple_of(8);
+    let payload_start = rpm_header_end(&bytes, header_start)
+        .ok_or_else(|| error("Truncated header".to_string()))?;
+
+    let payload = &bytes[payload_start..];
+    let compression = match payload {
+        [0x1f, 0x8b, ..] => ".gz",
+        [0xfd, b'7', b'z', b'X', b'Z', 0, ..] => ".xz",
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => ".zst",
+        [b'B', b'Z', b'h', ..] => ".bz2",
+        _ => "",
+    };
+    let cpio = decompress(compression, payload)
+        .map_err(|e| error(format!("Failed to decompress payload: {e}")))?;
+    extract_cpio(cpio, dest_dir).map_err(|e| error(format!("Failed to read payload: {e}")).into())
+}
+
+/// The offset just past the RPM header starting at `start`
+fn rpm_header_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let header = bytes.get(start..start + 16)?;
+    if header[..4] != RPM_HEADER_MAGIC {
+        return None;
+    }
+    let entries = u32::from_be_bytes(header[8..12].try_into().ok()?) as usize;
+    let data_size = u32::from_be_bytes(header[12..16].try_into().ok()?) as usize;
+    let end = start + 16 + entries * 16 + data_size;
+    (end <= bytes.len()).then_some(end)
+}
+
+/// A reader decompressing `reader` by its file extension, as is when `""`
+fn decompress<'a>(extension: &str, reader: impl Read + 'a) -> io::Result<Box<dyn Read + 'a>> {
+    Ok(match extension {
+        ".gz" => Box::new(flate2::read::GzDecoder::new(reader)),
+        ".xz" => Box::new(xz2::read::XzDecoder::new(reader)),
+        ".zst" => Box::new(zstd::Decoder::new(reader)?),
+        ".bz2" => Box::new(bzip2::read::BzDecoder::new(reader)),
+        "" => Box::new(reader),
+        other => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported compression {other}"),
+            ))
+        }
+    })
+}
+
+/// Extract the regular files under [`BIN_DIRS`] from a "newc" cpio archive,
+/// the format of RPM payloads
+fn extract_cpio(mut reader: impl Read, dest_dir: &Path) -> io::Result<()> {
+    let invalid = |reason: &str| io::Error::new(io::ErrorKind::InvalidData, reason.to_string());
+    loop {
+        let mut header = [0u8; 110];
+        reader.read_exact(&mut header)?;
+        if &header[..6] != b"070701" && &header[..6] != b"070702" {
+            return Err(invalid("not a newc cpio archive"));
+        }
+        let field = |index: usize| {
+            let hex = std::str::from_utf8(&header[6 + index * 8..14 + index * 8])
+                .map_err(|_| invalid("malformed cpio header"))?;
+            u32::from_str_radix(hex, 16).map_err(|_| invalid("malformed cpio header"))
+        };
+        let mode = field(1)?;
+        let file_size = field(6)? as usize;
+        let name_size = field(11)? as usize;
+
+        // The name is NUL-terminated and padded so the data starts on 4 bytes
+        let mut name = vec![0u8; (110 + name_size).next_multiple_of(4) - 110];
+        reader.read_exact(&mut name)?;
+        let name = String::from_utf8_lossy(&name[..name_size.saturating_sub(1)]).into_owned();
+        if name == "TRAILER!!!" {
+            return Ok(());
+        }
+
+        let mut data = vec![0u8; file_size.next_multiple_of(4)];
+        reader.read_exact(&mut data)?;
+        data.truncate(file_size);
+        // Regular files only; symlinks and directories are skipped
+        if mode & 0o170000 != 0o100000 {
+            continue;
+        }
+        if let Some(file_name) = bin_file_name(&name) {
+            let path = dest_dir.join(file_name);
+            fs::write(&path, &data)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&path, fs::Permissions::from_mode(mode & 0o7777))?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    /// A gzipped tar of `(path, contents, mode)` entries
+    fn data_tar(files: &[(&str, &[u8], u32)]) -> Vec<u8> {
+        let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            Default::default(),
+        ));
+        for (path, contents, mode) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(*mode);
+            header.set_cksum();
+            tar.append_data(&mut header, path, *contents).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap()
+    }
+
+    /// A newc cpio archive of `(path, contents, mode)` entries
+    fn cpio(files: &[(&str, &[u8], u32)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let trailer = ("TRAILER!!!", &b""[..], 0);
+        for (name, contents, mode) in files.iter().chain([&trailer]) {
+            let fields = [0, *mode, 0, 0, 1, 0, contents.len() as u32, 0, 0, 0, 0];
+            out.extend(b"070701");
+            for value in fields.iter().chain([&(name.len() as u32 + 1), &0]) {
+                out.extend(format!("{value:08x}").as_bytes());
+            }
+            out.extend(name.as_bytes());
+            out.push(0);
+            out.resize(out.len().next_multiple_of(4), 0);
+            out.extend(*contents);
+            out.resize(out.len().next_multiple_of(4), 0);
+        }
+        out
+    }
+
+    /// An RPM header with no entries and `data_size` bytes of data
+    fn rpm_header(data_size: u32) -> Vec<u8> {
+        let mut header = RPM_HEADER_MAGIC.to_vec();
+        header.extend([0; 4]);
+        header.extend(0u32.to_be_bytes());
+        header.extend(data_size.to_be_bytes());
+        header.resize(header.len() + data_size as usize, 0);
+        header
+    }
+
+    #[test]
+    fn test_bin_file_name() {
+        assert_eq!(bin_file_name("./usr/bin/tool"), Some("tool"));
+        assert_eq!(bin_file_name("/usr/local/bin/tool"), Some("tool"));
+        assert_eq!(bin_file_name("./usr/lib/libtool.so"), None);
+        assert_eq!(bin_file_name("./usr/bin/"), None);
+        assert_eq!(bin_file_name("./usr/bin/nested/tool"), None);
+    }
+
+    #[test]
+    fn test_extract_deb() {
+        let dir = tempdir().unwrap();
+        let deb = dir.path().join("tool_1.0.0_amd64.deb");
+        let data = data_tar(&[
+            ("./usr/bin/tool", b"\x7fELF", 0o755),
+            ("./usr/lib/libtool.so", b"\x7fELF", 0o755),
+            ("./usr/share/doc/tool/copyright", b"MIT", 0o644),
+        ]);
+        let mut builder = ar::Builder::new(fs::File::create(&deb).unwrap());
+        for (name, contents) in [
+            ("debian-binary", &b"2.0\n"[..]),
+            ("control.tar.gz", &data_tar(&[])[..]),
+            ("data.tar.gz", &data[..]),
+        ] {
+            let header = ar::Header::new(name.as_bytes().to_vec(), contents.len() as u64);
+            builder.append(&header, contents).unwrap();
+        }
+        drop(builder);
+
+        let dest = tempdir().unwrap();
+        extract_deb(&deb, dest.path()).unwrap();
+        assert_eq!(fs::read(dest.path().join("tool")).unwrap(), b"\x7fELF");
+        assert_eq!(fs::read_dir(dest.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_extract_rpm() {
+        let dir = tempdir().unwrap();
+        let rpm = dir.path().join("tool-1.0.0-1.x86_64.rpm");
+        let mut bytes = vec![0xed, 0xab, 0xee, 0xdb];
+        bytes.resize(RPM_LEAD_SIZE, 0);
+        // A signature header ending off an 8-byte boundary, then padding
+        bytes.extend(rpm_header(3));
+        bytes.resize(bytes.len().next_multiple_of(8), 0);
+        bytes.extend(rpm_header(24));
+        let mut payload = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+        payload
+            .write_all(&cpio(&[
+                ("./usr", b"", 0o040755),
+                ("./usr/bin/tool", b"\x7fELF", 0o100755),
+                ("./usr/share/man/man1/tool.1", b".TH", 0o100644),
+            ]))
+            .unwrap();
+        bytes.extend(payload.finish().unwrap());
+        fs::write(&rpm, bytes).unwrap();
+
+        let dest = tempdir().unwrap();
+        extract_rpm(&rpm, dest.path()).unwrap();
+        assert_eq!(fs::read(dest.path().join("tool")).unwrap(), b"\x7fELF");
+        assert_eq!(fs::read_dir(dest.path()).unwrap().count(), 1);
+
+        fs::write(&rpm, b"not an rpm").unwrap();
+        assert!(extract_rpm(&rpm, dest.path()).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use anyhow::{Context, Result};
+use std::ffi::OsStr;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::picker::Picker;
+use crate::utils;
+
+/// The shell whose startup file puts the install directory on PATH
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    /// Windows outside MSYS2 and Cygwin: PowerShell, with PATH in the registry
+    Windows,
+    /// Any other POSIX shell
+    Sh,
+}
+
+impl Shell {
+    /// The user's shell, from `$SHELL` (PowerShell on Windows outside MSYS2)
+    pub fn detect() -> Self {
+        if cfg!(windows) && !utils::in_msys_shell() {
+            return Self::Windows;
+        }
+        Self::from_path(&std::env::var("SHELL").unwrap_or_default())
+    }
+
+    /// The shell at a path such as `/usr/bin/zsh`
+    pub fn from_path(shell: &str) -> Self {
+        match Path::new(shell).file_name().and_then(|name| name.to_str()) {
+            Some("bash") => Self::Bash,
+            Some("zsh") => Self::Zsh,
+            Some("fish") => Self::Fish,
+            _ => Self::Sh,
+        }
+    }
+
+    /// The line that puts `dir` first on PATH in this shell
+    pub fn path_line(self, dir: &Path) -> String {
+        let dir = dir.display();
+        match self {
+            Self::Fish => format!("fish_add_path \"{dir}\""),
+            Self::Windows => format!("$env:Path = \"{dir};\" + $env:Path"),
+            Self::Bash | Self::Zsh | Self::Sh => format!("export PATH=\"{dir}:$PATH\""),
+        }
+    }
+
+    /// The startup file [`Shell::path_line`] goes in, under `home`
+    pub fn rc_file(self, home: &Path) -> Option<PathBuf> {
+        match self {
+            // Terminals on macOS start login shells, which don't read .bashrc
+            Self::Bash if cfg!(target_os = "macos") => Some(home.join(".bash_profile")),
+            Self::Bash => Some(home.join(".bashrc")),
+            Self::Zsh => Some(
+                std::env::var_os("ZDOTDIR")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| home.to_path_buf())
+                    .join(".zshrc"),
+            ),
+            Self::Fish => Some(home.join(".config").join("fish").join("config.fish")),
+            Self::Sh => Some(home.join(".profile")),
+            Self::Windows => None,
+        }
+    }
+}
+
+/// Whether `dir` is one of the directories of the PATH value `path`
+pub fn on_path(dir: &Path, path: &OsStr) -> bool {
+    let canonical = dir.canonicalize().ok();
+    std::env::split_paths(path).any(|entry| {
+        entry == dir || (canonical.is_some() && entry.canonicalize().ok() == canonical)
+    })
+}
+
+/// Tell how to put `dir` on PATH when it isn't, offering to do it when
+/// `prompt` is set: appending to the shell's startup file, or on Windows
+/// adding it to the user's Path in the registry
+pub fn check(dir: &Path, prompt: bool) -> Result<()> {
+    if on_path(dir, &std::env::var_os("PATH").unwrap_or_default()) {
+        return Ok(());
+    }
+    let shell = Shell::detect();
+    let line = shell.path_line(dir);
+    let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+    let rc_file = home.as_deref().and_then(|home| shell.rc_file(home));
+
+    println!("\n{} is not on your PATH.", dir.display());
+    let offer = match (shell, &rc_file) {
+        (Shell::Windows, _) => "Add it to your user Path?".to_string(),
+        (_, Some(rc_file)) => format!("Add `{line}` to {}?", rc_file.display()),
+        (_, None) => String::new(),
+    };
+    if prompt && !offer.is_empty() && Picker::terminal().confirm(&offer)? {
+        match &rc_file {
+            Some(rc_file) if shell != Shell::Windows => append_line(rc_file, &line)?,
+            _ => add_to_user_path(dir)?,
+        }
+        println!("Done; open a new terminal to pick it up.");
+        return Ok(());
+    }
+
+    match (shell, &rc_file) {
+        (Shell::Windows, _) => {
+            println!("Add it to your user Path in System Properties > Environment Variables,");
+            println!("or for this session run:\n\n    {line}\n");
+        }
+        (_, Some(rc_file)) => println!(
+            "Add this line to {} and open a new terminal:\n\n    {line}\n",
+            rc_file.display()
+        ),
+        (_, None) => println!("Add it to PATH in your shell's startup file:\n\n    {line}\n"),
+    }
+    Ok(())
+}
+
+/// Append `line` to a startup file, creating it (and its directory) if needed
+fn append_line(rc_file: &Path, line: &str) -> Result<()> {
+    if let Some(dir) = rc_file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let existing = fs::read_to_string(rc_file).unwrap_or_default();
+    if existing.lines().any(|existing| existing.trim() == line) {
+        return Ok(());
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(rc_file)
+        .with_context(|| format!("Failed to open {}", rc_file.display()))?;
+    let separator = if existing.is_empty() || existing.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    writeln!(file, "{separator}\n# Added by cargo-ghinstall\n{line}")
+        .with_context(|| format!("Failed to write {}", rc_file.display()))
+}
+
+/// Prepend `dir` to the user's Path in the registry unless it is already
+/// there, through PowerShell so the value isn't truncated as with `setx`
+///
+/// The raw value is read and written back as REG_EXPAND_SZ, so entries such
+/// as `%USERPROFILE%\bin` stay unexpanded. Setting and clearing a throwaway
+/// variable afterwards tells Explorer to pick up the new Path.
+fn add_to_user_path(dir: &Path) -> Result<()> {
+    let dir = dir.display().to_string().replace('\'', "''");
+    let script = format!(
+        "$dir = '{dir}'; \
+         $path = (Get-Item -Path 'HKCU:\\Environment').GetValue('Path', '', 'DoNotExpandEnvironmentNames'); \
+         $entries = @($path -split ';' | Where-Object {{ $_ }}); \
+         if ($entries | Where-Object {{ $_.TrimEnd('\\') -ieq $dir.TrimEnd('\\') }}) {{ exit 0 }}; \
+         New-ItemProperty -Path 'HKCU:\\Environment' -Name 'Path' -PropertyType ExpandString \
+             -Value ((@($dir) + $entries) -join ';') -Force | Out-Null; \
+         [Environment]::SetEnvironmentVariable('CARGO_GHINSTALL_PATH', '1', 'User'); \
+         [Environment]::SetEnvironmentVariable('CARGO_GHINSTALL_PATH', $null, 'User')"
+    );
+    let status = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .status()
+        .context("Failed to run powershell")?;
+    anyhow::ensure!(status.success(), "Failed to update the user Path");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_on_path() {
+        let dir = tempdir().unwrap();
+        let other = tempdir().unwrap();
+        let path = std::env::join_paths([other.path(), dir.path()]).unwrap();
+        assert!(on_path(dir.path(), &path));
+        // The same directory spelled differently
+        assert!(on_path(&dir.path().join("."), &path));
+
+        let path = std::env::join_paths([other.path()]).unwrap();
+        assert!(!on_path(dir.path(), &path));
+    }
+
+    #[test]
+    fn test_shell() {
+        assert_eq!(Shell::from_path("/usr/bin/zsh"), Shell::Zsh);
+        assert_eq!(Shell::from_path("/opt/homebrew/bin/fish"), Shell::Fish);
+        assert_eq!(Shell::from_path("bash"), Shell::Bash);
+        assert_eq!(Shell::from_path(""), Shell::Sh);
+
+        let dir = Path::new("/home/user/.cargo/bin");
+        assert_eq!(
+            Shell::Bash.path_line(dir),
+            "export PATH=\"/home/user/.cargo/bin:$PATH\""
+        );
+        assert_eq!(
+            Shell::Fish.path_line(dir),
+            "fish_add_path \"/home/user/.cargo/bin\""
+        );
+        let home = Path::new("/home/user");
+        assert_eq!(
+            Shell::Fish.rc_file(home),
+            Some(home.join(".config/fish/config.fish"))
+        );
+        assert_eq!(Shell::Windows.rc_file(home), None);
+        let bash = if cfg!(target_os = "macos") {
+            ".bash_profile"
+        } else {
+            ".bashrc"
+        };
+        assert_eq!(Shell::Bash.rc_file(home), Some(home.join(bash)));
+    }
+
+    #[test]
+    fn test_append_line() {
+        let dir = tempdir().unwrap();
+        let rc_file = dir.path().join("fish").join("config.fish");
+        let line = "fish_add_path \"/opt/bin\"";
+        append_line(&rc_file, line).unwrap();
+        // Appending again doesn't repeat it
+        append_line(&rc_file, line).unwrap();
+        let content = fs::read_to_string(&rc_file).unwrap();
+        assert_eq!(content.matches(line).count(), 1);
+        assert!(content.ends_with(&format!("{line}\n")));
+    }
+}
@@ -0,0 +1,177 @@
+use anyhow::Result;
+use std::io::{BufRead, IsTerminal, Write};
+
+/// How many choices are listed at once; typing narrows the list down
+const SHOWN: usize = 15;
+
+/// Whether prompts can be answered: stdin and stderr are both terminals
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Line-based choice from a list, generic over the streams so it can be
+/// scripted in tests
+pub struct Picker<R, W> {
+    input: R,
+    output: W,
+}
+
+impl Picker<std::io::StdinLock<'static>, std::io::Stderr> {
+    /// A picker reading stdin and prompting on stderr, keeping stdout for results
+    pub fn terminal() -> Self {
+        Self::new(std::io::stdin().lock(), std::io::stderr())
+    }
+}
+
+impl<R: BufRead, W: Write> Picker<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    /// Ask a yes/no question, defaulting to no
+    pub fn confirm(&mut self, prompt: &str) -> Result<bool> {
+        write!(self.output, "{prompt} [y/N] ")?;
+        self.output.flush()?;
+        let mut line = String::new();
+        self.input.read_line(&mut line)?;
+        Ok(matches!(line.trim(), "y" | "Y" | "yes" | "Yes"))
+    }
+
+    /// Pick one of `choices` by number, typing text to narrow them down;
+    /// Enter takes the first one listed. `None` when input ends or is `q`.
+    pub fn pick(&mut self, prompt: &str, choices: &[String]) -> Result<Option<usize>> {
+        let mut query = String::new();
+        loop {
+            let mut shown = choices
+                .iter()
+                .enumerate()
+                .filter(|(_, choice)| fuzzy_match(&query, choice))
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>();
+            if shown.is_empty() {
+                writeln!(self.output, "Nothing matches '{query}'")?;
+                query.clear();
+                continue;
+            }
+            let more = shown.len().saturating_sub(SHOWN);
+            shown.truncate(SHOWN);
+
+            writeln!(self.output, "{prompt}")?;
+            for (number, &index) in shown.iter().enumerate() {
+                writeln!(self.output, "  {:>2}. {}", number + 1, choices[index])?;
+            }
+            if more > 0 {
+                writeln!(self.output, "  ... {more} more, type to narrow down")?;
+            }
+            write!(
+                self.output,
+                "Number, text to search, or q to cancel (Enter picks 1): "
+            )?;
+            self.output.flush()?;
+
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let answer = line.trim();
+            match answer.parse::<usize>() {
+                _ if answer.is_empty() => return Ok(Some(shown[0])),
+                _ if answer.eq_ignore_ascii_case("q") => return Ok(None),
+                Ok(number) if (1..=shown.len()).contains(&number) => {
+                    return Ok(Some(shown[number - 1]))
+                }
+                Ok(_) => writeln!(self.output, "Pick a number from 1 to {}", shown.len())?,
+                Err(_) => query = answer.to_string(),
+            }
+        }
+    }
+}
+
+/// Whether the characters of `query` appear in `candidate` in order,
+/// ignoring case, so `rg1413` finds `ripgrep 14.1.3`
+pub fn fuzzy_match(query: &str, candidate: &str) -> bool {
+    let mut candidate = candidate.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|q| candidate.any(|c| c == q))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pick(answers: &str, choices: &[&str]) -> (Option<usize>, String) {
+        let choices = choices.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let mut output = Vec::new();
+        let picked = Picker::new(answers.as_bytes(), &mut output)
+            .pick("Pick one:", &choices)
+            .unwrap();
+        (picked, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert!(fuzzy_match("", "v1.2.3"));
+        assert!(fuzzy_match("v12", "v1.2.3"));
+        assert!(fuzzy_match("RC", "v2.0.0-rc.1  2024-05-01  prerelease"));
+        assert!(fuzzy_match(
+            "x86 musl",
+            "tool-x86_64-unknown-linux-musl.tar.gz"
+        ));
+        assert!(!fuzzy_match("v21", "v1.2.3"));
+        assert!(!fuzzy_match("musl", "tool-x86_64-unknown-linux-gnu.tar.gz"));
+    }
+
+    #[test]
+    fn test_confirm() {
+        let confirm = |answer: &str| {
+            Picker::new(answer.as_bytes(), Vec::new())
+                .confirm("Add it?")
+                .unwrap()
+        };
+        assert!(confirm("y\n"));
+        assert!(confirm("yes\n"));
+        assert!(!confirm("\n"));
+        assert!(!confirm("n\n"));
+        assert!(!confirm(""));
+    }
+
+    #[test]
+    fn test_pick() {
+        let choices = ["v1.3.0", "v1.2.0", "v1.1.0"];
+        assert_eq!(pick("\n", &choices).0, Some(0));
+        assert_eq!(pick("2\n", &choices).0, Some(1));
+        assert_eq!(pick("q\n", &choices).0, None);
+        assert_eq!(pick("", &choices).0, None);
+
+        // Out of range numbers are asked again
+        let (picked, output) = pick("7\n3\n", &choices);
+        assert_eq!(picked, Some(2));
+        assert!(output.contains("Pick a number from 1 to 3"), "{output}");
+    }
+
+    #[test]
+    fn test_pick_searches() {
+        let choices = ["v1.3.0", "v1.2.0", "v1.1.0"];
+        // Numbers refer to the narrowed list
+        let (picked, output) = pick("1.1\n1\n", &choices);
+        assert_eq!(picked, Some(2));
+        assert!(output.contains("   1. v1.1.0"), "{output}");
+
+        let (picked, output) = pick("v9\n\n", &choices);
+        assert_eq!(picked, Some(0));
+        assert!(output.contains("Nothing matches 'v9'"), "{output}");
+    }
+
+    #[test]
+    fn test_pick_lists_a_page() {
+        let choices = (0..20).map(|n| format!("v1.{n}.0")).collect::<Vec<_>>();
+        let choices = choices.iter().map(String::as_str).collect::<Vec<_>>();
+        let (picked, output) = pick("\n", &choices);
+        assert_eq!(picked, Some(0));
+        assert!(output.contains("... 5 more"), "{output}");
+        assert!(!output.contains("v1.15.0"), "{output}");
+    }
+}
@@ -0,0 +1,104 @@
+use indicatif::{HumanBytes, ProgressBar, ProgressDrawTarget, ProgressFinish, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How often a transfer is logged when stderr is not a terminal, e.g. in CI
+const LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Progress of one download or upload: a bar on a terminal, periodic log
+/// lines otherwise. Clones report to the same bar, e.g. from a body stream.
+#[derive(Clone)]
+pub struct Progress {
+    bar: ProgressBar,
+    label: String,
+    /// When progress was last logged; `None` while drawing a bar or when disabled
+    last_log: Option<Arc<Mutex<Instant>>>,
+}
+
+impl Progress {
+    /// `total` is `None` when the server doesn't send a content length
+    pub fn new(label: &str, total: Option<u64>, enabled: bool) -> Self {
+        let interactive = enabled && std::io::stderr().is_terminal();
+        let bar = if interactive {
+            let template = if total.is_some() {
+                "{msg} [{bar:30}] {bytes}/{total_bytes} {binary_bytes_per_sec} ETA {eta}"
+            } else {
+                "{spinner} {msg} {bytes} {binary_bytes_per_sec}"
+            };
+            // Clear the bar even when a failed attempt drops it halfway
+            let bar = total
+                .map_or_else(ProgressBar::new_spinner, ProgressBar::new)
+                .with_finish(ProgressFinish::AndClear);
+            bar.set_style(
+                ProgressStyle::with_template(template)
+                    .unwrap_or_else(|_| ProgressStyle::default_bar())
+                    .progress_chars("=> "),
+            );
+            bar.set_message(label.to_string());
+            bar
+        } else {
+            ProgressBar::with_draw_target(total, ProgressDrawTarget::hidden())
+        };
+
+        Self {
+            bar,
+            label: label.to_string(),
+            last_log: (enabled && !interactive).then(|| Arc::new(Mutex::new(Instant::now()))),
+        }
+    }
+
+    pub fn inc(&self, bytes: u64) {
+        self.bar.inc(bytes);
+        if let Some(last_log) = &self.last_log {
+            let mut last_log = last_log.lock().unwrap_or_else(|e| e.into_inner());
+            if last_log.elapsed() >= LOG_INTERVAL {
+                *last_log = Instant::now();
+                tracing::info!(
+                    "{}",
+                    log_line(
+                        &self.label,
+                        self.bar.position(),
+                        self.bar.length(),
+                        self.bar.per_sec()
+                    )
+                );
+            }
+        }
+    }
+
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+/// Progress line for logs, e.g. "tool.tar.gz: 42% (12.00 MiB/28.57 MiB, 3.00 MiB/s)"
+pub fn log_line(label: &str, position: u64, total: Option<u64>, per_sec: f64) -> String {
+    let rate = HumanBytes(per_sec as u64);
+    match total.filter(|total| *total > 0) {
+        Some(total) => format!(
+            "{label}: {}% ({}/{}, {rate}/s)",
+            position.saturating_mul(100) / total,
+            HumanBytes(position),
+            HumanBytes(total)
+        ),
+        None => format!("{label}: {} ({rate}/s)", HumanBytes(position)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_line() {
+        assert_eq!(
+            log_line("tool.tar.gz", 512 * 1024, Some(1024 * 1024), 1024.0),
+            "tool.tar.gz: 50% (512.00 KiB/1.00 MiB, 1.00 KiB/s)"
+        );
+        assert_eq!(
+            log_line("tool.tar.gz", 1024, Some(0), 0.0),
+            "tool.tar.gz: 1.00 KiB (0 B/s)"
+        );
+    }
+}
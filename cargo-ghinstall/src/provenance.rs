@@ -0,0 +1,232 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// The predicate type of SLSA build provenance, as GitHub's
+/// `actions/attest-build-provenance` records it
+pub const SLSA_PROVENANCE_V1: &str = "https://slsa.dev/provenance/v1";
+
+/// The in-toto statement payload type of a DSSE envelope
+const IN_TOTO_PAYLOAD: &str = "application/vnd.in-toto+json";
+
+/// Where and how an artifact was built, from the Fulcio certificate of its
+/// verified provenance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// The repository whose workflow built it, `https://github.com/owner/repo`
+    pub repository: String,
+    /// The workflow that signed it, `owner/repo/.github/workflows/release.yml`
+    pub workflow: String,
+    /// The ref the workflow ran on, `refs/tags/v1.2.3`
+    pub git_ref: String,
+}
+
+impl Provenance {
+    /// Why the build didn't come from the repository `repo` (`owner/repo`)
+    /// and, when given, its `workflow` (a path or file name), or `None` when
+    /// it did
+    pub fn mismatch(&self, repo: &str, workflow: Option<&str>) -> Option<String> {
+        let expected = format!("https://github.com/{repo}");
+        if !self.repository.eq_ignore_ascii_case(&expected) {
+            return Some(format!(
+                "built in {} instead of {expected}",
+                self.repository
+            ));
+        }
+        let workflow = workflow?;
+        let matches = self.workflow == workflow || self.workflow.ends_with(&format!("/{workflow}"));
+        (!matches).then(|| format!("built by workflow {} instead of {workflow}", self.workflow))
+    }
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    payload: String,
+    #[serde(rename = "payloadType")]
+    payload_type: String,
+}
+
+#[derive(Deserialize)]
+struct Statement {
+    #[serde(default)]
+    subject: Vec<Subject>,
+    #[serde(rename = "predicateType")]
+    predicate_type: String,
+}
+
+#[derive(Deserialize)]
+struct Subject {
+    #[serde(default)]
+    digest: HashMap<String, String>,
+}
+
+/// The DSSE envelopes of a response of GitHub's attestations API
+pub fn envelopes(response: &Value) -> Vec<&Value> {
+    response["attestations"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|attestation| attestation["bundle"].get("dsseEnvelope"))
+        .collect()
+}
+
+/// Whether a DSSE envelope claims to be build provenance of the artifact
+/// with `sha256`. Nothing in it is verified; it only tells whether there is
+/// provenance for [`verify`] to check.
+pub fn claims_provenance(envelope: &Value, sha256: &str) -> bool {
+    let Ok(envelope) = Envelope::deserialize(envelope) else {
+        return false;
+    };
+    let statement = base64::engine::general_purpose::STANDARD
+        .decode(&envelope.payload)
+        .ok()
+        .and_then(|payload| serde_json::from_slice::<Statement>(&payload).ok());
+    envelope.payload_type == IN_TOTO_PAYLOAD
+        && statement.is_some_and(|statement| {
+            statement.predicate_type == SLSA_PROVENANCE_V1
+                && statement.subject.iter().any(|subject| {
+                    subject
+                        .digest
+                        .get("sha256")
+                        .is_some_and(|digest| digest.eq_ignore_ascii_case(sha256))
+                })
+        })
+}
+
+/// Verify the build provenance of the file at `path` with `gh attestation
+/// verify`, which checks the Sigstore bundle (the Fulcio certificate chain,
+/// the transparency log entry and the signature) and that the certificate
+/// was issued to a workflow of `repo` (`owner/repo`)
+pub fn verify(path: &Path, repo: &str) -> Result<Provenance> {
+    let output = Command::new("gh")
+        .args(["attestation", "verify"])
+        .arg(path)
+        .args(["--repo", repo, "--predicate-type", SLSA_PROVENANCE_V1])
+        .args(["--format", "json"])
+        .output()
+        .context("Failed to run gh, which verifies build provenance; install the GitHub CLI")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh attestation verify failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let results: Value =
+        serde_json::from_slice(&output.stdout).context("Malformed gh attestation verify output")?;
+    parse_verified(&results)
+}
+
+/// The provenance of the first result of `gh attestation verify --format
+/// json`, taken from the verified certificate's OIDC claims
+fn parse_verified(results: &Value) -> Result<Provenance> {
+    let certificate = &results[0]["verificationResult"]["signature"]["certificate"];
+    let field = |name: &str| {
+        certificate[name]
+            .as_str()
+            .map(str::to_string)
+            .with_context(|| format!("the provenance certificate has no {name}"))
+    };
+    let signer = field("buildSignerURI")?;
+    let workflow = signer
+        .strip_prefix("https://github.com/")
+        .unwrap_or(&signer)
+        .split('@')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    Ok(Provenance {
+        repository: field("sourceRepositoryURI")?,
+        workflow,
+        git_ref: field("sourceRepositoryRef")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SHA256: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn envelope(predicate_type: &str, sha256: &str) -> Value {
+        let statement = json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [{"name": "tool.tar.gz", "digest": {"sha256": sha256}}],
+            "predicateType": predicate_type,
+            "predicate": {}
+        });
+        json!({
+            "payload": base64::engine::general_purpose::STANDARD.encode(statement.to_string()),
+            "payloadType": IN_TOTO_PAYLOAD,
+            "signatures": [{"sig": "MEUCIQ..."}]
+        })
+    }
+
+    #[test]
+    fn test_claims_provenance() {
+        let response = json!({"attestations": [
+            {"bundle": {"dsseEnvelope": envelope("https://spdx.dev/Document/v2.3", SHA256)}},
+            {"bundle": {"dsseEnvelope": envelope(SLSA_PROVENANCE_V1, SHA256)}},
+        ]});
+        let found = envelopes(&response);
+        assert_eq!(found.len(), 2);
+
+        // An SBOM isn't provenance
+        assert!(!claims_provenance(found[0], SHA256));
+        assert!(claims_provenance(found[1], SHA256));
+
+        let other = envelope(SLSA_PROVENANCE_V1, &"0".repeat(64));
+        assert!(!claims_provenance(&other, SHA256));
+        assert!(!claims_provenance(&json!({"payload": "!"}), SHA256));
+        assert!(envelopes(&json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_parse_verified() {
+        let results = json!([{
+            "verificationResult": {
+                "signature": {
+                    "certificate": {
+                        "buildSignerURI":
+                            "https://github.com/owner/tool/.github/workflows/release.yml@refs/tags/v1.2.3",
+                        "sourceRepositoryURI": "https://github.com/owner/tool",
+                        "sourceRepositoryRef": "refs/tags/v1.2.3"
+                    }
+                }
+            }
+        }]);
+        assert_eq!(
+            parse_verified(&results).unwrap(),
+            Provenance {
+                repository: "https://github.com/owner/tool".to_string(),
+                workflow: "owner/tool/.github/workflows/release.yml".to_string(),
+                git_ref: "refs/tags/v1.2.3".to_string(),
+            }
+        );
+        assert!(parse_verified(&json!([])).is_err());
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let found = Provenance {
+            repository: "https://github.com/owner/tool".to_string(),
+            workflow: "owner/tool/.github/workflows/release.yml".to_string(),
+            git_ref: "refs/tags/v1.2.3".to_string(),
+        };
+        assert_eq!(found.mismatch("Owner/Tool", None), None);
+        assert_eq!(found.mismatch("owner/tool", Some("release.yml")), None);
+        assert_eq!(
+            found.mismatch("owner/tool", Some(".github/workflows/release.yml")),
+            None
+        );
+        assert_eq!(
+            found.mismatch("fork/tool", None).as_deref(),
+            Some("built in https://github.com/owner/tool instead of https://github.com/fork/tool")
+        );
+        assert!(found.mismatch("owner/tool", Some("ci.yml")).is_some());
+    }
+}
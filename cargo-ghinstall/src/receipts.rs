@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::utils;
+
+/// Every install made from a release asset, keyed by `owner/repo`
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Receipts {
+    #[serde(default)]
+    pub installed: BTreeMap<String, Receipt>,
+}
+
+/// What one install put on disk and where it came from
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Receipt {
+    pub tag: String,
+    pub target: String,
+    pub asset: String,
+    pub asset_sha256: String,
+    /// The prefix the tag was resolved with, which isn't part of its version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_prefix: Option<String>,
+    /// Seconds since the Unix epoch
+    pub installed_at: u64,
+    #[serde(default)]
+    pub binaries: Vec<InstalledBinary>,
+    /// Completions and man pages installed with --extras
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extras: Vec<PathBuf>,
+    /// The install this one replaced, kept while its binaries have backups
+    /// for `rollback`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<Box<Receipt>>,
+}
+
+/// An installed binary with the hash it had when it was installed
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct InstalledBinary {
+    pub name: String,
+    pub path: PathBuf,
+    pub sha256: String,
+    /// The binary's name in the release when --as installed it as `name`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renamed_from: Option<String>,
+}
+
+impl Receipts {
+    /// Load the receipts, or none when the file doesn't exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid receipts in {}", path.display()))
+    }
+
+    /// Write the receipts, replacing the file in one step so a failed write
+    /// can't leave it truncated
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        fs::create_dir_all(dir)?;
+        let mut file = tempfile::NamedTempFile::new_in(dir)?;
+        std::io::Write::write_all(&mut file, toml::to_string_pretty(self)?.as_bytes())?;
+        file.persist(path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// The latest install from `repo` (`owner/repo`)
+    pub fn get(&self, repo: &str) -> Option<&Receipt> {
+        self.installed.get(repo)
+    }
+
+    /// Record an install, replacing the previous receipt for the repository
+    pub fn record(&mut self, repo: &str, receipt: Receipt) {
+        self.installed.insert(repo.to_string(), receipt);
+    }
+
+    /// The repository whose recorded install put a binary at `path`
+    pub fn owner_of(&self, path: &Path) -> Option<&str> {
+        self.installed
+            .iter()
+            .find(|(_, receipt)| receipt.binaries.iter().any(|binary| binary.path == path))
+            .map(|(repo, _)| repo.as_str())
+    }
+}
+
+impl Receipt {
+    /// A receipt stamped with the current time
+    pub fn new(
+        tag: &str,
+        target: &str,
+        asset: &str,
+        asset_sha256: String,
+        binaries: Vec<InstalledBinary>,
+    ) -> Self {
+        let installed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            tag: tag.to_string(),
+            target: target.to_string(),
+            asset: asset.to_string(),
+            asset_sha256,
+            tag_prefix: None,
+            installed_at,
+            binaries,
+            extras: Vec::new(),
+            previous: None,
+        }
+    }
+}
+
+impl InstalledBinary {
+    /// Whether the file at `path` still has the recorded hash
+    pub fn is_intact(&self) -> bool {
+        utils::calculate_sha256(&self.path).is_ok_and(|sha256| sha256 == self.sha256)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn receipt(tag: &str, binaries: Vec<InstalledBinary>) -> Receipt {
+        Receipt {
+            tag: tag.to_string(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            asset: format!("tool-{tag}-x86_64-unknown-linux-gnu.tar.gz"),
+            asset_sha256: "a".repeat(64),
+            tag_prefix: None,
+            installed_at: 1_700_000_000,
+            binaries,
+            extras: Vec::new(),
+            previous: None,
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ghinstall").join("installed.toml");
+        assert_eq!(Receipts::load(&path).unwrap(), Receipts::default());
+
+        let mut receipts = Receipts::default();
+        receipts.record(
+            "owner/tool",
+            receipt(
+                "v1.0.0",
+                vec![InstalledBinary {
+                    name: "tool".to_string(),
+                    path: PathBuf::from("/home/user/.cargo/bin/tool"),
+                    sha256: "b".repeat(64),
+                    renamed_from: Some("tool-x86_64".to_string()),
+                }],
+            ),
+        );
+        receipts.save(&path).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("[installed.\"owner/tool\"]"), "{content}");
+        assert_eq!(Receipts::load(&path).unwrap(), receipts);
+
+        receipts.record("owner/tool", receipt("v1.1.0", Vec::new()));
+        assert_eq!(receipts.get("owner/tool").unwrap().tag, "v1.1.0");
+        assert_eq!(receipts.installed.len(), 1);
+        assert!(receipts.get("owner/other").is_none());
+    }
+
+    #[test]
+    fn test_owner_of() {
+        let mut receipts = Receipts::default();
+        let path = PathBuf::from("/home/user/.cargo/bin/tool");
+        receipts.record(
+            "owner/tool",
+            receipt(
+                "v1.0.0",
+                vec![InstalledBinary {
+                    name: "tool".to_string(),
+                    path: path.clone(),
+                    sha256: "b".repeat(64),
+                    renamed_from: None,
+                }],
+            ),
+        );
+        assert_eq!(receipts.owner_of(&path), Some("owner/tool"));
+        assert_eq!(receipts.owner_of(Path::new("/usr/local/bin/tool")), None);
+    }
+
+    #[test]
+    fn test_is_intact() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("tool");
+        fs::write(&path, b"binary").unwrap();
+        let binary = InstalledBinary {
+            name: "tool".to_string(),
+            path: path.clone(),
+            sha256: utils::calculate_sha256(&path).unwrap(),
+            renamed_from: None,
+        };
+        assert!(binary.is_intact());
+
+        fs::write(&path, b"modified").unwrap();
+        assert!(!binary.is_intact());
+        fs::remove_file(&path).unwrap();
+        assert!(!binary.is_intact());
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("installed.toml");
+        fs::write(&path, "installed = 1\n").unwrap();
+        assert!(Receipts::load(&path).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use anyhow::Result;
+use octocrab::models::repos::Release;
+
+use crate::cli::ReleasesArgs;
+use crate::github::GitHubClient;
+use crate::utils;
+
+/// Print the newest releases of the repository
+pub async fn run(args: &ReleasesArgs) -> Result<()> {
+    let (owner, repo) = args.parse_repo()?;
+    let client = GitHubClient::new()?;
+    let releases = client.list_releases(&owner, &repo, args.limit).await?;
+    if releases.is_empty() {
+        println!("{owner}/{repo} has no releases");
+        return Ok(());
+    }
+    print!("{}", render_table(&releases));
+    Ok(())
+}
+
+fn render_table(releases: &[Release]) -> String {
+    let rows = releases
+        .iter()
+        .map(|release| {
+            let date = release
+                .published_at
+                .or(release.created_at)
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            vec![
+                release.tag_name.clone(),
+                date,
+                flags(release),
+                release.assets.len().to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    utils::render_table(&["Tag", "Published", "Flags", "Assets"], &rows)
+}
+
+fn flags(release: &Release) -> String {
+    let flags = [(release.draft, "draft"), (release.prerelease, "prerelease")]
+        .into_iter()
+        .filter_map(|(set, flag)| set.then_some(flag))
+        .collect::<Vec<_>>();
+    if flags.is_empty() {
+        "-".to_string()
+    } else {
+        flags.join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::test_release;
+
+    fn release(tag: &str, published_at: Option<&str>, prerelease: bool, assets: usize) -> Release {
+        test_release(
+            tag,
+            prerelease,
+            published_at,
+            &vec![("a.tar.gz", 1); assets],
+        )
+    }
+
+    #[test]
+    fn test_render_table() {
+        let releases = vec![
+            release("v1.1.0-rc.1", Some("2024-06-01T12:00:00Z"), true, 4),
+            release("v1.0.0", Some("2024-05-20T08:30:00Z"), false, 12),
+            release("v0.9.0", None, false, 0),
+        ];
+        assert_eq!(
+            render_table(&releases),
+            "\
+Tag          Published   Flags       Assets
+v1.1.0-rc.1  2024-06-01  prerelease  4
+v1.0.0       2024-05-20  -           12
+v0.9.0       2024-01-01  -           0
+"
+        );
+    }
+}
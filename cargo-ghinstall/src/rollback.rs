@@ -0,0 +1,142 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::RollbackArgs;
+use crate::receipts::Receipts;
+use crate::utils;
+
+/// Restore the binaries the last install of `args.bin` replaced
+pub fn run(args: &RollbackArgs) -> Result<()> {
+    let receipts_path = args.receipts.receipts_path();
+    let mut receipts = Receipts::load(&receipts_path)?;
+    let restored = rollback(
+        &mut receipts,
+        &args.bin,
+        &args.install_dir(),
+        &args.receipts.backup_dir(),
+    )?;
+    receipts.save(&receipts_path)?;
+    for path in restored {
+        println!("Restored {}", path.display());
+    }
+    Ok(())
+}
+
+/// Move the backups in `backup_dir` of the install that put `bin` in place
+/// back over its binaries, returning the restored paths. The install's
+/// receipt goes back to the one it replaced, when it was kept; a binary no
+/// receipt records is looked for in `install_dir`.
+pub fn rollback(
+    receipts: &mut Receipts,
+    bin: &str,
+    install_dir: &Path,
+    backup_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let owner = receipts
+        .installed
+        .iter()
+        .find(|(_, receipt)| receipt.binaries.iter().any(|binary| binary.name == bin))
+        .map(|(repo, _)| repo.clone());
+    let Some(repo) = owner else {
+        let path = install_dir.join(format!("{bin}{}", std::env::consts::EXE_SUFFIX));
+        return Ok(vec![restore(&path, backup_dir)?]);
+    };
+
+    let receipt = receipts.installed.get_mut(&repo).expect("found above");
+    let mut restored = Vec::new();
+    for binary in &mut receipt.binaries {
+        if utils::backup_path(backup_dir, &binary.path).exists() {
+            restored.push(restore(&binary.path, backup_dir)?);
+            binary.sha256 = utils::calculate_sha256(&binary.path)?;
+        }
+    }
+    anyhow::ensure!(
+        !restored.is_empty(),
+        "No backup of {bin} to roll back to; the last install of {repo} didn't replace it"
+    );
+    if let Some(previous) = receipt.previous.take() {
+        receipts.record(&repo, *previous);
+    }
+    Ok(restored)
+}
+
+/// Put the backup of `path` back in its place, then delete the backup
+fn restore(path: &Path, backup_dir: &Path) -> Result<PathBuf> {
+    let backup = utils::backup_path(backup_dir, path);
+    anyhow::ensure!(backup.exists(), "No backup at {}", backup.display());
+    utils::replace_executable(&backup, path).with_context(|| {
+        format!(
+            "Failed to restore {} from {}",
+            path.display(),
+            backup.display()
+        )
+    })?;
+    fs::remove_file(&backup)?;
+    Ok(path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipts::{InstalledBinary, Receipt};
+    use tempfile::tempdir;
+
+    fn receipt(tag: &str, path: &Path) -> Receipt {
+        Receipt::new(
+            tag,
+            "x86_64-unknown-linux-gnu",
+            "tool.tar.gz",
+            "a".repeat(64),
+            vec![InstalledBinary {
+                name: "tool".to_string(),
+                path: path.to_path_buf(),
+                sha256: "b".repeat(64),
+                renamed_from: None,
+            }],
+        )
+    }
+
+    #[test]
+    fn test_rollback() {
+        let dir = tempdir().unwrap();
+        let backups = tempdir().unwrap();
+        let path = dir.path().join("tool");
+        fs::write(&path, b"v2").unwrap();
+        fs::write(utils::backup_path(backups.path(), &path), b"v1").unwrap();
+
+        let mut receipts = Receipts::default();
+        receipts.record(
+            "owner/tool",
+            Receipt {
+                previous: Some(Box::new(receipt("v1.0.0", &path))),
+                ..receipt("v2.0.0", &path)
+            },
+        );
+
+        let restored = rollback(&mut receipts, "tool", dir.path(), backups.path()).unwrap();
+        assert_eq!(restored, vec![path.clone()]);
+        assert_eq!(fs::read(&path).unwrap(), b"v1");
+        assert!(!utils::backup_path(backups.path(), &path).exists());
+        assert_eq!(receipts.get("owner/tool").unwrap().tag, "v1.0.0");
+
+        // The backup is used up
+        assert!(rollback(&mut receipts, "tool", dir.path(), backups.path()).is_err());
+    }
+
+    #[test]
+    fn test_rollback_unrecorded() {
+        let dir = tempdir().unwrap();
+        let backups = tempdir().unwrap();
+        let path = dir
+            .path()
+            .join(format!("other{}", std::env::consts::EXE_SUFFIX));
+        fs::write(&path, b"v2").unwrap();
+        fs::write(utils::backup_path(backups.path(), &path), b"v1").unwrap();
+
+        let mut receipts = Receipts::default();
+        rollback(&mut receipts, "other", dir.path(), backups.path()).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"v1");
+        assert!(rollback(&mut receipts, "missing", dir.path(), backups.path()).is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::{Context, Result};
+use std::process::Command;
+
+use crate::cli::RunArgs;
+use crate::installer::Installer;
+
+/// Run the release binary from the cache, downloading it first if needed,
+/// and return its exit code
+pub async fn run(args: &RunArgs) -> Result<i32> {
+    let installer = Installer::new(args.install_args())?;
+    let binary = installer.cache_release(&args.cache_path()).await?;
+    let status = Command::new(&binary)
+        .args(args.binary_args())
+        .status()
+        .with_context(|| format!("Failed to run {}", binary.display()))?;
+    Ok(exit_code(status))
+}
+
+/// The exit code to pass on, following the shell convention of 128 plus
+/// the signal for a binary killed by one
+fn exit_code(status: std::process::ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code() {
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(exit_code(std::process::ExitStatus::from_raw(0)), 0);
+        assert_eq!(exit_code(std::process::ExitStatus::from_raw(3 << 8)), 3);
+        // Killed by SIGKILL
+        assert_eq!(exit_code(std::process::ExitStatus::from_raw(9)), 137);
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+
+use crate::cli::Args;
+use crate::installer::Installer;
+
+/// Repository whose releases carry cargo-ghinstall and cargo-ghdist
+pub const REPOSITORY: &str = "mkusaka/cargo-gh";
+
+/// Replace the running `bin` with the one from the latest release of
+/// mkusaka/cargo-gh when that is newer than `current_version`, or from `tag`
+pub async fn run(bin: &str, current_version: &str, tag: Option<&str>) -> Result<()> {
+    // Update the file behind a symlink rather than replacing the link
+    let exe = std::env::current_exe()
+        .and_then(std::fs::canonicalize)
+        .context("Failed to locate the running executable")?;
+
+    let args = Args::parse_from(update_args(tag));
+    Installer::new(args)?
+        .self_update(bin, &exe, current_version)
+        .await
+}
+
+fn update_args(tag: Option<&str>) -> Vec<String> {
+    let repo = match tag {
+        Some(tag) => format!("{REPOSITORY}@{tag}"),
+        None => REPOSITORY.to_string(),
+    };
+    ["cargo-ghinstall", &repo, "--no-fallback"]
+        .map(String::from)
+        .to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_args() {
+        let args = Args::parse_from(update_args(Some("v0.2.0")));
+        let (owner, repo, tag) = args.parse_repo().unwrap();
+        assert_eq!(
+            (owner.as_str(), repo.as_str(), tag.as_deref()),
+            ("mkusaka", "cargo-gh", Some("v0.2.0"))
+        );
+        assert!(args.no_fallback);
+        assert!(!args.skip_checksum);
+
+        let args = Args::parse_from(update_args(None));
+        assert_eq!(args.parse_repo().unwrap().2, None);
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use sequoia_openpgp as openpgp;
+use std::path::Path;
+
+use openpgp::cert::{Cert, CertParser};
+use openpgp::parse::stream::{
+    DetachedVerifierBuilder, MessageLayer, MessageStructure, VerificationHelper,
+};
+use openpgp::parse::Parse;
+use openpgp::policy::StandardPolicy;
+use openpgp::KeyHandle;
+
+/// Where the keys trusted to sign an owner's releases are fetched when none
+/// is configured: the OpenPGP keys of their GitHub account
+pub fn github_keys_url(owner: &str) -> String {
+    format!("https://github.com/{owner}.gpg")
+}
+
+/// Check the detached `signature` (armored or binary) of the file at `data`
+/// against the trusted `keys`, returning the fingerprint of the key that made
+/// it. Fails unless a trusted key made a valid signature.
+pub fn verify_detached(data: &Path, signature: &[u8], keys: &[u8]) -> Result<String> {
+    let certs = CertParser::from_bytes(keys)
+        .context("Failed to read the trusted keys")?
+        .collect::<openpgp::Result<Vec<Cert>>>()
+        .context("Failed to read the trusted keys")?;
+    anyhow::ensure!(!certs.is_empty(), "No trusted keys to verify against");
+
+    let policy = StandardPolicy::new();
+    let helper = Helper {
+        certs,
+        signer: None,
+    };
+    let mut verifier = DetachedVerifierBuilder::from_bytes(signature)
+        .context("Failed to read the signature")?
+        .with_policy(&policy, None, helper)?;
+    verifier.verify_file(data)?;
+    let helper = verifier.into_helper();
+    Ok(helper.signer.expect("checked by Helper::check"))
+}
+
+/// Hands the trusted keys to the verifier and accepts the data when one of
+/// them made a good signature
+struct Helper {
+    certs: Vec<Cert>,
+    /// Fingerprint of the key of the good signature
+    signer: Option<String>,
+}
+
+impl VerificationHelper for Helper {
+    fn get_certs(&mut self, _ids: &[KeyHandle]) -> openpgp::Result<Vec<Cert>> {
+        Ok(self.certs.clone())
+    }
+
+    fn check(&mut self, structure: MessageStructure) -> openpgp::Result<()> {
+        let mut errors = Vec::new();
+        for layer in structure {
+            let MessageLayer::SignatureGroup { results } = layer else {
+                continue;
+            };
+            for result in results {
+                match result {
+                    Ok(good) => {
+                        self.signer = Some(good.ka.key().fingerprint().to_hex());
+                        return Ok(());
+                    }
+                    Err(e) => errors.push(e.to_string()),
+                }
+            }
+        }
+        match errors.is_empty() {
+            true => Err(anyhow::anyhow!("No signature found")),
+            false => Err(anyhow::anyhow!("{}", errors.join("; "))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openpgp::cert::CertBuilder;
+    use openpgp::serialize::stream::{Armorer, Message, Signer};
+    use openpgp::serialize::SerializeInto;
+    use std::io::Write;
+
+    fn key() -> Cert {
+        CertBuilder::new()
+            .add_userid("Example <release@example.com>")
+            .add_signing_subkey()
+            .generate()
+            .unwrap()
+            .0
+    }
+
+    /// An armored detached signature of `data` by `cert`'s signing key
+    fn sign(cert: &Cert, data: &[u8]) -> Vec<u8> {
+        let policy = StandardPolicy::new();
+        let keypair = cert
+            .keys()
+            .with_policy(&policy, None)
+            .secret()
+            .for_signing()
+            .next()
+            .unwrap()
+            .key()
+            .clone()
+            .into_keypair()
+            .unwrap();
+        let mut signature = Vec::new();
+        let message = Message::new(&mut signature);
+        let message = Armorer::new(message)
+            .kind(openpgp::armor::Kind::Signature)
+            .build()
+            .unwrap();
+        let mut signer = Signer::new(message, keypair)
+            .unwrap()
+            .detached()
+            .build()
+            .unwrap();
+        signer.write_all(data).unwrap();
+        signer.finalize().unwrap();
+        signature
+    }
+
+    #[test]
+    fn test_verify_detached() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("tool.tar.gz");
+        std::fs::write(&data, b"release archive").unwrap();
+
+        let cert = key();
+        let other = key();
+        let signature = sign(&cert, b"release archive");
+        // A keyring of several armored keys, as GitHub serves them
+        let mut keys = other.armored().to_vec().unwrap();
+        keys.extend(cert.armored().to_vec().unwrap());
+
+        let signer = verify_detached(&data, &signature, &keys).unwrap();
+        assert!(cert
+            .keys()
+            .any(|key| key.key().fingerprint().to_hex() == signer));
+
+        // Another key's signature, tampered data, and no keys all fail
+        let wrong = other.armored().to_vec().unwrap();
+        assert!(verify_detached(&data, &signature, &wrong).is_err());
+        std::fs::write(&data, b"tampered archive").unwrap();
+        assert!(verify_detached(&data, &signature, &keys).is_err());
+        assert!(verify_detached(&data, &signature, b"").is_err());
+    }
+
+    #[test]
+    fn test_github_keys_url() {
+        assert_eq!(github_keys_url("sharkdp"), "https://github.com/sharkdp.gpg");
+    }
+}
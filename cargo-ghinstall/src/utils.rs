@@ -21,8 +21,14 @@ pub fn extract_archive(archive_path: &Path) -> Result<tempfile::TempDir> {
         extract_tar_xz(archive_path, temp_dir.path())?;
     } else if archive_name.ends_with(".tar.bz2") {
         extract_tar_bz2(archive_path, temp_dir.path())?;
+    } else if archive_name.ends_with(".tar.zst") {
+        extract_tar_zst(archive_path, temp_dir.path())?;
     } else if archive_name.ends_with(".zip") {
         extract_zip(archive_path, temp_dir.path())?;
+    } else if archive_name.ends_with(".deb") {
+        crate::package::extract_deb(archive_path, temp_dir.path())?;
+    } else if archive_name.ends_with(".rpm") {
+        crate::package::extract_rpm(archive_path, temp_dir.path())?;
     } else {
         return Err(GhInstallError::ArchiveExtraction {
             file: archive_name.to_string(),
@@ -34,6 +40,40 @@ pub fn extract_archive(archive_path: &Path) -> Result<tempfile::TempDir> {
     Ok(temp_dir)
 }
 
+/// Unpack a downloaded release asset into a temporary directory: extract an
+/// archive, or place a bare or single compressed binary there as `bin_name`
+pub fn unpack_asset(path: &Path, asset_name: &str, bin_name: &str) -> Result<tempfile::TempDir> {
+    if crate::github::is_archive(asset_name) {
+        return extract_archive(path);
+    }
+    let temp_dir = tempfile::tempdir()?;
+    // `tool.exe` and `tool.exe.gz` install as `.exe`
+    let lower = asset_name.to_lowercase();
+    let suffix = if lower.ends_with(".exe") || lower.contains(".exe.") {
+        ".exe"
+    } else {
+        ""
+    };
+    let dest = temp_dir.path().join(format!("{bin_name}{suffix}"));
+    let file = fs::File::open(path)?;
+    let mut reader: Box<dyn Read> = if asset_name.ends_with(".gz") {
+        Box::new(flate2::read::GzDecoder::new(file))
+    } else if asset_name.ends_with(".xz") {
+        Box::new(xz2::read::XzDecoder::new(file))
+    } else if asset_name.ends_with(".zst") {
+        Box::new(zstd::Decoder::new(file)?)
+    } else {
+        Box::new(file)
+    };
+    let mut out = fs::File::create(&dest)?;
+    io::copy(&mut reader, &mut out).map_err(|e| GhInstallError::ArchiveExtraction {
+        file: asset_name.to_string(),
+        reason: format!("Failed to write binary: {e}"),
+    })?;
+    make_executable(&dest)?;
+    Ok(temp_dir)
+}
+
 /// Extract tar.gz archive
 fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<()> {
     let file = fs::File::open(archive_path).map_err(|e| GhInstallError::ArchiveExtraction {
@@ -85,6 +125,23 @@ fn extract_tar_bz2(archive_path: &Path, dest_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Extract tar.zst archive
+fn extract_tar_zst(archive_path: &Path, dest_dir: &Path) -> Result<()> {
+    let file = fs::File::open(archive_path).map_err(|e| GhInstallError::ArchiveExtraction {
+        file: archive_path.display().to_string(),
+        reason: format!("Failed to open archive: {e}"),
+    })?;
+    let zst_decoder = zstd::Decoder::new(file)?;
+    let mut archive = tar::Archive::new(zst_decoder);
+    archive
+        .unpack(dest_dir)
+        .map_err(|e| GhInstallError::ArchiveExtraction {
+            file: archive_path.display().to_string(),
+            reason: format!("Failed to extract tar.zst: {e}"),
+        })?;
+    Ok(())
+}
+
 /// Extract zip archive
 fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<()> {
     let file = fs::File::open(archive_path).map_err(|e| GhInstallError::ArchiveExtraction {
@@ -175,8 +232,137 @@ pub fn make_executable(_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Where the binary at `path` is backed up in `backup_dir` before an install
+/// replaces it: `<name>-<hash of its directory>.bak`, so binaries of the same
+/// name in different install directories don't share a backup, and the backup
+/// is never on PATH
+pub fn backup_path(backup_dir: &Path, path: &Path) -> PathBuf {
+    use sha2::{Digest, Sha256};
+
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let hash = hex::encode(Sha256::digest(dir.to_string_lossy().as_bytes()));
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!("-{}.bak", &hash[..8]));
+    backup_dir.join(name)
+}
+
+/// Replace `dest` with an executable copy of `source`
+///
+/// The copy is written next to `dest` and renamed over it, so `dest` is never
+/// half-written and a running executable (such as ghinstall itself) can be
+/// replaced. Windows can't replace a running executable but can rename it, so
+/// when `dest` is in use it is moved aside to `<name>.old` first, for
+/// [`remove_replaced`] to delete once it has exited.
+pub fn replace_executable(source: &Path, dest: &Path) -> Result<()> {
+    let dir = dest
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let temp = tempfile::Builder::new()
+        .prefix(".ghinstall-")
+        .tempfile_in(dir)?;
+    fs::copy(source, temp.path())?;
+    make_executable(temp.path())?;
+
+    let temp = match temp.persist(dest) {
+        Ok(_) => return Ok(()),
+        Err(e) if cfg!(windows) && e.error.kind() == io::ErrorKind::PermissionDenied => e.file,
+        Err(e) => return Err(e.error.into()),
+    };
+    let aside = aside_path(dest);
+    // Left behind by the previous update, whose process has exited since
+    let _ = fs::remove_file(&aside);
+    fs::rename(dest, &aside)?;
+    temp.persist(dest).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Where [`replace_executable`] moves a running executable it replaces
+fn aside_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".old");
+    path.with_file_name(name)
+}
+
+/// Delete the executables [`replace_executable`] moved aside in `dir` whose
+/// processes have exited: `<name>.exe.old` files next to `<name>.exe`. Ones
+/// still running can't be deleted yet and are left for the next install.
+pub fn remove_replaced(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let replaced = path
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_suffix(".old"))
+            .filter(|name| name.ends_with(".exe"))
+            .is_some_and(|name| dir.join(name).is_file());
+        if replaced && fs::remove_file(&path).is_ok() {
+            tracing::debug!("Removed {}", path.display());
+        }
+    }
+}
+
+/// Match a name against a pattern where `*` is any run of characters and `?`
+/// any one character
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (pattern.as_bytes(), name.as_bytes());
+    let (mut p, mut n) = (0, 0);
+    // Where the last `*` was and the name position it is retried from
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Left-aligned columns separated by two spaces, one line per row
+pub fn render_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let widths = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(|cell| cell.chars().count())
+                .chain([header[i].chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    let header = header.iter().map(|h| h.to_string()).collect::<Vec<_>>();
+
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
 /// Calculate SHA256 hash of a file
-#[allow(dead_code)]
 pub fn calculate_sha256(path: &Path) -> Result<String> {
     use sha2::{Digest, Sha256};
 
@@ -195,6 +381,113 @@ pub fn calculate_sha256(path: &Path) -> Result<String> {
     Ok(hex::encode(hasher.finalize()))
 }
 
+/// Package name of a per-package release tag of the `<package>-v<semver>`
+/// shape ghdist gives them, such as `cli-v1.2.0` or `my-tool-v1.0.0-rc.1`
+///
+/// Returns `None` for repository-wide tags like `v1.2.0` or `release-1.2.0`,
+/// branch names or commit SHAs.
+pub fn package_from_tag(tag: &str) -> Option<&str> {
+    tag.match_indices("-v").find_map(|(index, _)| {
+        let (package, version) = (&tag[..index], &tag[index + 2..]);
+        (!package.is_empty() && semver::Version::parse(version).is_ok()).then_some(package)
+    })
+}
+
+/// The C library of a Linux host
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostLibc {
+    Musl,
+    /// glibc, with its version when it could be read
+    Glibc(Option<(u32, u32)>),
+}
+
+/// Oldest glibc that `-gnu` release builds are taken to run on: they are
+/// usually built on current CI images, which link newer symbol versions
+pub const MIN_GLIBC: (u32, u32) = (2, 31);
+
+impl HostLibc {
+    /// Whether `-musl` builds should be preferred: on musl hosts, and on
+    /// glibc older than [`MIN_GLIBC`]
+    pub fn prefers_musl(self) -> bool {
+        match self {
+            Self::Musl => true,
+            Self::Glibc(Some(version)) => version < MIN_GLIBC,
+            Self::Glibc(None) => false,
+        }
+    }
+}
+
+/// The host's C library, `None` off Linux
+pub fn host_libc() -> Option<HostLibc> {
+    static LIBC: std::sync::OnceLock<Option<HostLibc>> = std::sync::OnceLock::new();
+    *LIBC.get_or_init(|| cfg!(target_os = "linux").then(detect_libc))
+}
+
+/// Look for musl's dynamic loader, then ask `getconf` or `ldd` for the
+/// glibc version
+fn detect_libc() -> HostLibc {
+    let musl_loader = fs::read_dir("/lib").ok().is_some_and(|entries| {
+        entries.flatten().any(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with("ld-musl-"))
+        })
+    });
+    if musl_loader {
+        return HostLibc::Musl;
+    }
+
+    let output = |program: &str, arg: &str| {
+        std::process::Command::new(program)
+            .arg(arg)
+            .output()
+            .ok()
+            .map(|output| {
+                // musl's ldd prints its version to stderr
+                let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+                text.push_str(&String::from_utf8_lossy(&output.stderr));
+                text
+            })
+    };
+    if let Some(version) = output("getconf", "GNU_LIBC_VERSION")
+        .as_deref()
+        .and_then(parse_glibc_version)
+    {
+        return HostLibc::Glibc(Some(version));
+    }
+    match output("ldd", "--version") {
+        Some(text) if text.to_lowercase().contains("musl") => HostLibc::Musl,
+        Some(text) => HostLibc::Glibc(parse_glibc_version(&text)),
+        None => HostLibc::Glibc(None),
+    }
+}
+
+/// Whether this runs from an MSYS2 or Cygwin shell on Windows, where the
+/// toolchain and DLLs around are the MinGW (-gnu) ones even though the OS
+/// reads as plain `windows`
+pub fn in_msys_shell() -> bool {
+    cfg!(windows)
+        && (std::env::var_os("MSYSTEM").is_some()
+            || std::env::var("OSTYPE")
+                .is_ok_and(|ostype| ostype.starts_with("msys") || ostype.starts_with("cygwin")))
+}
+
+/// Whether Rosetta 2 is installed to run x86_64 binaries on Apple Silicon
+pub fn has_rosetta() -> bool {
+    cfg!(all(target_os = "macos", target_arch = "aarch64"))
+        && Path::new("/Library/Apple/usr/libexec/oah/libRosettaRuntime").exists()
+}
+
+/// The glibc version from `getconf GNU_LIBC_VERSION` (`glibc 2.35`) or the
+/// first line of `ldd --version` (`ldd (Ubuntu GLIBC 2.35-0ubuntu3) 2.35`)
+fn parse_glibc_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.lines().next()?.split_whitespace().last()?;
+    let (major, minor) = version.split_once('.')?;
+    let minor = minor.split(|c: char| !c.is_ascii_digit()).next()?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,6 +522,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unpack_bare_binary() {
+        let dir = tempdir().unwrap();
+        let asset = dir.path().join("download");
+        fs::write(&asset, b"\x7fELF").unwrap();
+
+        let unpacked = unpack_asset(&asset, "tool-linux-amd64", "tool").unwrap();
+        let binary = unpacked.path().join("tool");
+        assert_eq!(fs::read(&binary).unwrap(), b"\x7fELF");
+        #[cfg(unix)]
+        assert_eq!(find_executables(unpacked.path()).unwrap(), [binary]);
+
+        let unpacked = unpack_asset(&asset, "tool-windows-x64.exe", "tool").unwrap();
+        assert!(unpacked.path().join("tool.exe").exists());
+    }
+
+    #[test]
+    fn test_unpack_compressed_binary() {
+        use std::io::Write;
+
+        let dir = tempdir().unwrap();
+        let gz = dir.path().join("download.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(fs::File::create(&gz).unwrap(), Default::default());
+        encoder.write_all(b"\x7fELF").unwrap();
+        encoder.finish().unwrap();
+        let unpacked = unpack_asset(&gz, "tool-x86_64-linux.gz", "tool").unwrap();
+        assert_eq!(fs::read(unpacked.path().join("tool")).unwrap(), b"\x7fELF");
+
+        let zst = dir.path().join("download.zst");
+        fs::write(&zst, zstd::encode_all(&b"MZ"[..], 0).unwrap()).unwrap();
+        let unpacked = unpack_asset(&zst, "tool-windows-x64.exe.zst", "tool").unwrap();
+        assert_eq!(fs::read(unpacked.path().join("tool.exe")).unwrap(), b"MZ");
+
+        let xz = dir.path().join("download.xz");
+        let mut encoder = xz2::write::XzEncoder::new(fs::File::create(&xz).unwrap(), 6);
+        encoder.write_all(b"\x7fELF").unwrap();
+        encoder.finish().unwrap();
+        let unpacked = unpack_asset(&xz, "tool-linux-amd64.xz", "tool").unwrap();
+        assert_eq!(fs::read(unpacked.path().join("tool")).unwrap(), b"\x7fELF");
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(
+            "*-musl.tar.gz",
+            "tool-x86_64-unknown-linux-musl.tar.gz"
+        ));
+        assert!(glob_match("tool-v?.?.?-*", "tool-v1.2.3-linux.zip"));
+        assert!(!glob_match(
+            "*-musl.tar.gz",
+            "tool-x86_64-unknown-linux-gnu.tar.gz"
+        ));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("*-helper", "helper"));
+        assert!(glob_match("*a*b", "xaxxab"));
+        assert!(!glob_match("?", ""));
+    }
+
+    #[test]
+    fn test_replace_executable() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("new");
+        let dest = dir.path().join("tool");
+        fs::write(&source, b"new binary").unwrap();
+        fs::write(&dest, b"old binary").unwrap();
+
+        replace_executable(&source, &dest).unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), b"new binary");
+        assert!(is_executable(&dest).unwrap() || cfg!(windows));
+        // Only the source and the replaced file remain, no temporary copies
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_remove_replaced() {
+        let dir = tempdir().unwrap();
+        for name in ["tool.exe", "tool.exe.old", "gone.exe.old", "notes.old"] {
+            fs::write(dir.path().join(name), b"").unwrap();
+        }
+
+        remove_replaced(dir.path());
+        let mut left = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        left.sort();
+        assert_eq!(left, ["gone.exe.old", "notes.old", "tool.exe"]);
+    }
+
     #[test]
     fn test_calculate_sha256() {
         let dir = tempdir().unwrap();
@@ -241,4 +624,37 @@ mod tests {
             "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f"
         );
     }
+
+    #[test]
+    fn test_package_from_tag() {
+        assert_eq!(package_from_tag("cli-v1.2.0"), Some("cli"));
+        assert_eq!(package_from_tag("my-tool-v1.2.0-rc.1"), Some("my-tool"));
+        assert_eq!(package_from_tag("core-0.3.0"), None);
+        assert_eq!(package_from_tag("release-1.2.0"), None);
+        assert_eq!(package_from_tag("nightly-v2024.01"), None);
+        assert_eq!(package_from_tag("v1.2.0"), None);
+        assert_eq!(package_from_tag("1.2.0-rc.1"), None);
+        assert_eq!(package_from_tag("0.1.0-abcdef12"), None);
+        assert_eq!(package_from_tag("main"), None);
+        assert_eq!(package_from_tag("feature-x"), None);
+    }
+
+    #[test]
+    fn test_parse_glibc_version() {
+        assert_eq!(parse_glibc_version("glibc 2.35\n"), Some((2, 35)));
+        assert_eq!(
+            parse_glibc_version("ldd (Ubuntu GLIBC 2.35-0ubuntu3.8) 2.35\nCopyright (C) 2022\n"),
+            Some((2, 35))
+        );
+        assert_eq!(parse_glibc_version("ldd (GNU libc) 2.17"), Some((2, 17)));
+        assert_eq!(parse_glibc_version("musl libc (x86_64)"), None);
+    }
+
+    #[test]
+    fn test_prefers_musl() {
+        assert!(HostLibc::Musl.prefers_musl());
+        assert!(HostLibc::Glibc(Some((2, 17))).prefers_musl());
+        assert!(!HostLibc::Glibc(Some((2, 35))).prefers_musl());
+        assert!(!HostLibc::Glibc(None).prefers_musl());
+    }
 }
@@ -0,0 +1,208 @@
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Targets accepted without asking rustc, and the suggestions for a
+/// misspelled triple when rustc isn't available
+const COMMON_TARGETS: &[&str] = &[
+    "x86_64-unknown-linux-gnu",
+    "x86_64-unknown-linux-musl",
+    "aarch64-unknown-linux-gnu",
+    "aarch64-unknown-linux-musl",
+    "armv7-unknown-linux-gnueabihf",
+    "armv7-unknown-linux-musleabihf",
+    "arm-unknown-linux-gnueabihf",
+    "i686-unknown-linux-gnu",
+    "i686-unknown-linux-musl",
+    "riscv64gc-unknown-linux-gnu",
+    "powerpc64le-unknown-linux-gnu",
+    "s390x-unknown-linux-gnu",
+    "loongarch64-unknown-linux-gnu",
+    "x86_64-apple-darwin",
+    "aarch64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+    "x86_64-pc-windows-gnu",
+    "aarch64-pc-windows-msvc",
+    "i686-pc-windows-msvc",
+    "x86_64-unknown-freebsd",
+    "x86_64-unknown-netbsd",
+    "x86_64-unknown-illumos",
+    "aarch64-linux-android",
+    "wasm32-wasip1",
+    "wasm32-unknown-unknown",
+];
+
+/// Parse a config file, adding a "did you mean" hint to unknown-key errors
+///
+/// toml's own message already points at the offending line and column.
+pub fn parse<T: DeserializeOwned>(content: &str) -> anyhow::Result<T> {
+    toml::from_str(content).map_err(explain)
+}
+
+/// Append a suggestion to an unknown field or variant error
+pub fn explain(error: toml::de::Error) -> anyhow::Error {
+    match unknown_key_suggestion(error.message()) {
+        Some(hint) => anyhow::anyhow!("{}\nhelp: {hint}", error.to_string().trim_end()),
+        None => error.into(),
+    }
+}
+
+/// "did you mean `format`?" for serde's "unknown field `fromat`, expected one of ..." messages
+fn unknown_key_suggestion(message: &str) -> Option<String> {
+    if !message.starts_with("unknown field") && !message.starts_with("unknown variant") {
+        return None;
+    }
+    let mut quoted = message.split('`').skip(1).step_by(2);
+    let unknown = quoted.next()?;
+    suggest(unknown, quoted).map(|candidate| format!("did you mean `{candidate}`?"))
+}
+
+/// The candidate closest to `value`, if it is close enough to be a likely typo
+pub fn suggest<'a>(value: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = (value.chars().count() / 3).max(2);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(value, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The targets `rustc --print target-list` lists, or `None` without a
+/// working rustc. Queried once per process.
+fn rustc_targets() -> Option<&'static [String]> {
+    static TARGETS: OnceLock<Option<Vec<String>>> = OnceLock::new();
+    TARGETS
+        .get_or_init(|| {
+            let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+            let output = Command::new(rustc)
+                .args(["--print", "target-list"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())?;
+            let list = String::from_utf8_lossy(&output.stdout);
+            Some(list.lines().map(str::to_string).collect())
+        })
+        .as_deref()
+}
+
+/// Check that `triple` is a target rustc knows, or a path to a custom target
+/// spec. Without rustc, a near miss of a common target only gets a warning.
+pub fn check_target(triple: &str) -> Result<(), String> {
+    if triple.ends_with(".json") || COMMON_TARGETS.contains(&triple) {
+        return Ok(());
+    }
+
+    let Some(known) = rustc_targets() else {
+        if let Some(candidate) = suggest(triple, COMMON_TARGETS.iter().copied()) {
+            tracing::warn!("`{triple}` is not a common target triple, did you mean `{candidate}`?");
+        }
+        return Ok(());
+    };
+    if known.iter().any(|target| target == triple) {
+        return Ok(());
+    }
+
+    let mut message = format!("`{triple}` is not a known target triple");
+    match suggest(triple, known.iter().map(String::as_str)) {
+        Some(candidate) => message.push_str(&format!(", did you mean `{candidate}`?")),
+        None => message.push_str(" (see `rustc --print target-list`)"),
+    }
+    Err(message)
+}
+
+/// Deserialize optional per-repository targets, rejecting unknown triples
+pub fn optional_targets<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<String>>, D::Error> {
+    let targets = Option::<Vec<String>>::deserialize(deserializer)?;
+    for target in targets.iter().flatten() {
+        check_target(target).map_err(D::Error::custom)?;
+    }
+    Ok(targets)
+}
+
+/// Deserialize an optional asset pattern, rejecting invalid regexes
+pub fn optional_asset_pattern<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    let pattern = Option::<String>::deserialize(deserializer)?;
+    if let Some(pattern) = &pattern {
+        crate::github::AssetPattern::parse(pattern)
+            .map_err(|e| D::Error::custom(format!("invalid asset pattern: {e}")))?;
+    }
+    Ok(pattern)
+}
+
+/// Deserialize the `[repo."owner/repo"]` tables, rejecting keys that aren't owner/repo
+pub fn repo_table<'de, D: Deserializer<'de>, T: Deserialize<'de>>(
+    deserializer: D,
+) -> Result<HashMap<String, T>, D::Error> {
+    let table = HashMap::<String, T>::deserialize(deserializer)?;
+    for key in table.keys() {
+        let valid = key.split_once('/').is_some_and(|(owner, repo)| {
+            !owner.is_empty() && !repo.is_empty() && !repo.contains('/')
+        });
+        if !valid {
+            return Err(D::Error::custom(format!(
+                "repository `{key}` should be written as owner/repo"
+            )));
+        }
+    }
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(suggest("fromat", ["format", "targets"]), Some("format"));
+        assert_eq!(
+            suggest("skip-publish", ["skip_publish"]),
+            Some("skip_publish")
+        );
+        assert_eq!(suggest("colour", ["format", "targets"]), None);
+    }
+
+    #[test]
+    fn test_check_target() {
+        assert!(check_target("x86_64-unknown-linux-gnu").is_ok());
+        assert!(check_target("aarch64-unknown-linux-ohos").is_ok());
+        assert!(check_target("thumbv7em-none-eabihf").is_ok());
+        assert!(check_target("wasm32-wasip2").is_ok());
+        assert!(check_target("targets/custom.json").is_ok());
+        for target in [
+            "xtensa-esp32-none-elf",
+            "avr-none",
+            "armv6k-nintendo-3ds",
+            "aarch64-nintendo-switch-freestanding",
+            "armv7-sony-vita-newlibeabihf",
+            "aarch64-unknown-linux-gnu_ilp32",
+        ] {
+            assert!(check_target(target).is_ok(), "{target}");
+        }
+
+        let error = check_target("x86_64-unknown-linux-gun").unwrap_err();
+        assert!(error.contains("did you mean `x86_64-unknown-linux-gnu`?"));
+        assert!(check_target("x86_64-linux").is_err());
+        assert!(check_target("linux").is_err());
+    }
+}
@@ -1,22 +1,44 @@
-use cargo_ghinstall::cli::Args;
+use cargo_ghinstall::cli::{Args, ReceiptsArg, ReleaseSelection};
 
 #[test]
 fn test_parse_repo_with_tag() {
     let args = Args {
-        repo: "owner/repo@v1.2.3".to_string(),
+        repo: Some("owner/repo@v1.2.3".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     let (owner, repo, tag) = args.parse_repo().unwrap();
@@ -28,20 +50,42 @@ fn test_parse_repo_with_tag() {
 #[test]
 fn test_parse_repo_without_tag() {
     let args = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: Some("v2.0.0".to_string()),
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     let (owner, repo, tag) = args.parse_repo().unwrap();
@@ -53,20 +97,42 @@ fn test_parse_repo_without_tag() {
 #[test]
 fn test_parse_repo_with_hash_tag() {
     let args = Args {
-        repo: "owner/repo@vabcdef0".to_string(),
+        repo: Some("owner/repo@vabcdef0".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     let (owner, repo, tag) = args.parse_repo().unwrap();
@@ -78,20 +144,42 @@ fn test_parse_repo_with_hash_tag() {
 #[test]
 fn test_parse_repo_with_plain_hash() {
     let args = Args {
-        repo: "owner/repo@abcdef0".to_string(),
+        repo: Some("owner/repo@abcdef0".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     let (owner, repo, tag) = args.parse_repo().unwrap();
@@ -103,20 +191,42 @@ fn test_parse_repo_with_plain_hash() {
 #[test]
 fn test_parse_repo_with_branch_name() {
     let args = Args {
-        repo: "owner/repo@main".to_string(),
+        repo: Some("owner/repo@main".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     let (owner, repo, tag) = args.parse_repo().unwrap();
@@ -125,23 +235,205 @@ fn test_parse_repo_with_branch_name() {
     assert_eq!(tag, Some("main".to_string()));
 }
 
+#[test]
+fn test_parse_repo_with_version_requirement() {
+    use cargo_ghinstall::github::version_requirement;
+    use clap::Parser;
+
+    let args = Args::try_parse_from(["cargo-ghinstall", "owner/repo@^1.2"]).unwrap();
+    let (_, _, tag) = args.parse_repo().unwrap();
+    assert_eq!(tag.as_deref(), Some("^1.2"));
+    assert!(version_requirement(tag.as_deref().unwrap()).is_some());
+
+    let args = Args::try_parse_from(["cargo-ghinstall", "owner/repo", "--tag", "~1.4"]).unwrap();
+    let (_, _, tag) = args.parse_repo().unwrap();
+    assert!(version_requirement(tag.as_deref().unwrap()).is_some());
+}
+
+#[test]
+fn test_pre_flag() {
+    use clap::Parser;
+
+    assert!(
+        !Args::try_parse_from(["cargo-ghinstall", "owner/repo"])
+            .unwrap()
+            .selection
+            .pre
+    );
+    let args = Args::try_parse_from(["cargo-ghinstall", "owner/repo", "--pre"]).unwrap();
+    assert!(args.selection.pre);
+
+    let args = Args::try_parse_from(["cargo-ghinstall", "owner/repo@latest-pre"]).unwrap();
+    let (_, _, tag) = args.parse_repo().unwrap();
+    assert_eq!(
+        tag.as_deref(),
+        Some(cargo_ghinstall::github::LATEST_PRERELEASE)
+    );
+}
+
+#[test]
+fn test_tag_prefix_flag() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from(["cargo-ghinstall", "owner/monorepo", "--tag-prefix", "cli-v"])
+        .unwrap();
+    assert_eq!(args.selection.tag_prefix.as_deref(), Some("cli-v"));
+    assert!(Args::try_parse_from(["cargo-ghinstall", "owner/repo"])
+        .unwrap()
+        .selection
+        .tag_prefix
+        .is_none());
+}
+
+#[test]
+fn test_release_date_flags() {
+    use cargo_ghinstall::cli::ReleaseDate;
+    use clap::Parser;
+
+    let time = |s: &str| s.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+
+    let args =
+        Args::try_parse_from(["cargo-ghinstall", "owner/repo", "--before", "2024-06-01"]).unwrap();
+    assert_eq!(
+        args.selection.published_before(),
+        Some(time("2024-06-01T00:00:00Z"))
+    );
+
+    // --at includes the whole day, or the second it names
+    let args =
+        Args::try_parse_from(["cargo-ghinstall", "owner/repo", "--at", "2024-06-01"]).unwrap();
+    assert_eq!(
+        args.selection.published_before(),
+        Some(time("2024-06-02T00:00:00Z"))
+    );
+    let args = Args::try_parse_from([
+        "cargo-ghinstall",
+        "owner/repo",
+        "--at",
+        "2024-06-01T12:30:00+02:00",
+    ])
+    .unwrap();
+    assert_eq!(
+        args.selection.published_before(),
+        Some(time("2024-06-01T10:30:01Z"))
+    );
+
+    assert_eq!(
+        "2024-06-01T12:00:00Z"
+            .parse::<ReleaseDate>()
+            .unwrap()
+            .start(),
+        time("2024-06-01T12:00:00Z")
+    );
+    assert!("June 1st".parse::<ReleaseDate>().is_err());
+    assert!(Args::try_parse_from([
+        "cargo-ghinstall",
+        "owner/repo",
+        "--before",
+        "2024-06-01",
+        "--at",
+        "2024-06-01"
+    ])
+    .is_err());
+    assert!(Args::try_parse_from(["cargo-ghinstall", "owner/repo"])
+        .unwrap()
+        .selection
+        .published_before()
+        .is_none());
+}
+
+#[test]
+fn test_yes_flag() {
+    use cargo_ghinstall::cli::RunArgs;
+    use clap::Parser;
+
+    assert!(
+        Args::try_parse_from(["cargo-ghinstall", "owner/repo", "-y"])
+            .unwrap()
+            .yes
+    );
+    assert!(
+        !Args::try_parse_from(["cargo-ghinstall", "owner/repo"])
+            .unwrap()
+            .yes
+    );
+    let run = RunArgs::parse_from_if_requested(
+        ["cargo-ghinstall", "run", "--yes", "owner/tool", "--yes"].map(String::from),
+    )
+    .unwrap();
+    assert!(run.install_args().yes);
+    // After the repository, --yes belongs to the binary
+    assert_eq!(run.binary_args(), ["--yes"]);
+}
+
+#[test]
+fn test_asset_flag() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from([
+        "cargo-ghinstall",
+        "owner/repo",
+        "--asset",
+        "tool-*-linux-musl.tar.gz",
+    ])
+    .unwrap();
+    assert_eq!(
+        args.selection.asset.as_deref(),
+        Some("tool-*-linux-musl.tar.gz")
+    );
+
+    let args = Args::try_parse_from([
+        "cargo-ghinstall",
+        "owner/repo",
+        "--asset",
+        r"/musl\.tar\.gz$/",
+    ])
+    .unwrap();
+    assert_eq!(args.selection.asset.as_deref(), Some(r"/musl\.tar\.gz$/"));
+
+    // Invalid regexes are rejected before anything is downloaded
+    assert!(Args::try_parse_from(["cargo-ghinstall", "owner/repo", "--asset", "/(musl/"]).is_err());
+}
+
 #[test]
 fn test_retry_configuration_defaults() {
     let args = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     assert_eq!(args.max_retries, 3);
@@ -151,20 +443,42 @@ fn test_retry_configuration_defaults() {
 #[test]
 fn test_retry_configuration_custom() {
     let args = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 5,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     assert_eq!(args.max_retries, 5);
@@ -174,20 +488,42 @@ fn test_retry_configuration_custom() {
 #[test]
 fn test_retry_disabled() {
     let args = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: true,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     assert!(args.no_retry);
@@ -196,20 +532,42 @@ fn test_retry_disabled() {
 #[test]
 fn test_parse_repo_invalid_format() {
     let args = Args {
-        repo: "invalid-format".to_string(),
+        repo: Some("invalid-format".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     assert!(args.parse_repo().is_err());
@@ -218,20 +576,42 @@ fn test_parse_repo_invalid_format() {
 #[test]
 fn test_target_detection() {
     let args = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     let target = args.target();
@@ -243,20 +623,45 @@ fn test_target_detection() {
 #[test]
 fn test_target_override() {
     let args = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: Some("x86_64-pc-windows-msvc".to_string()),
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection {
+            target: Some("x86_64-pc-windows-msvc".to_string()),
+            ..Default::default()
+        },
     };
 
     let target = args.target();
@@ -266,20 +671,42 @@ fn test_target_override() {
 #[test]
 fn test_install_dir_expansion() {
     let args = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/custom/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     let install_dir = args.install_dir();
@@ -290,20 +717,42 @@ fn test_install_dir_expansion() {
 #[test]
 fn test_config_path_expansion() {
     let args = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from("~/.config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     let config_path = args.config_path();
@@ -313,22 +762,333 @@ fn test_config_path_expansion() {
 #[test]
 fn test_install_dir_absolute_path() {
     let args = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "/usr/local/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     let install_dir = args.install_dir();
     assert_eq!(install_dir.to_string_lossy(), "/usr/local/bin");
 }
+
+#[test]
+fn test_from_file_replaces_repository() {
+    use clap::Parser;
+    let args = Args::try_parse_from(["cargo-ghinstall", "--from-file", "tools.toml"]).unwrap();
+    assert_eq!(args.from_file, Some(std::path::PathBuf::from("tools.toml")));
+
+    assert!(Args::try_parse_from(["cargo-ghinstall"]).is_err());
+    assert!(
+        Args::try_parse_from(["cargo-ghinstall", "owner/repo", "--from-file", "tools.toml"])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_auth_args() {
+    use cargo_ghinstall::cli::{AuthAction, AuthArgs};
+    let argv = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+    let auth = AuthArgs::parse_from_if_requested(argv(&[
+        "cargo",
+        "ghinstall",
+        "auth",
+        "login",
+        "--with-token",
+    ]))
+    .unwrap();
+    assert!(matches!(
+        auth.action,
+        AuthAction::Login { with_token: true }
+    ));
+
+    let auth =
+        AuthArgs::parse_from_if_requested(argv(&["cargo-ghinstall", "auth", "status"])).unwrap();
+    assert!(matches!(auth.action, AuthAction::Status));
+
+    assert!(
+        AuthArgs::parse_from_if_requested(argv(&["cargo", "ghinstall", "owner/repo"])).is_none()
+    );
+}
+
+#[test]
+fn test_self_args() {
+    use cargo_ghinstall::cli::{SelfAction, SelfArgs};
+    let argv = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+    let self_args =
+        SelfArgs::parse_from_if_requested(argv(&["cargo", "ghinstall", "self", "update"])).unwrap();
+    assert!(matches!(self_args.action, SelfAction::Update { tag: None }));
+    assert!(
+        SelfArgs::parse_from_if_requested(argv(&["cargo", "ghinstall", "owner/self"])).is_none()
+    );
+}
+
+#[test]
+fn test_releases_args() {
+    use cargo_ghinstall::cli::ReleasesArgs;
+    let argv = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+    let releases = ReleasesArgs::parse_from_if_requested(argv(&[
+        "cargo",
+        "ghinstall",
+        "releases",
+        "sharkdp/fd",
+        "--limit",
+        "5",
+    ]))
+    .unwrap();
+    assert_eq!(releases.limit, 5);
+    assert_eq!(
+        releases.parse_repo().unwrap(),
+        ("sharkdp".to_string(), "fd".to_string())
+    );
+
+    let releases =
+        ReleasesArgs::parse_from_if_requested(argv(&["cargo-ghinstall", "releases", "fd"]))
+            .unwrap();
+    assert_eq!(releases.limit, 30);
+    assert!(releases.parse_repo().is_err());
+}
+
+#[test]
+fn test_assets_args() {
+    use cargo_ghinstall::cli::AssetsArgs;
+    let argv = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+    let assets = AssetsArgs::parse_from_if_requested(argv(&[
+        "cargo",
+        "ghinstall",
+        "assets",
+        "sharkdp/fd@v10.2.0",
+        "--target",
+        "aarch64-apple-darwin",
+    ]))
+    .unwrap();
+    assert_eq!(
+        assets.parse_repo().unwrap(),
+        (
+            "sharkdp".to_string(),
+            "fd".to_string(),
+            Some("v10.2.0".to_string())
+        )
+    );
+    assert_eq!(assets.target(), "aarch64-apple-darwin");
+}
+
+#[test]
+fn test_run_args() {
+    use cargo_ghinstall::cli::RunArgs;
+    use clap::Parser;
+    let argv = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+    let run = RunArgs::parse_from_if_requested(argv(&[
+        "cargo-ghinstall",
+        "run",
+        "--bin",
+        "fd",
+        "--cache-dir",
+        "/tmp/ghinstall-cache",
+        "sharkdp/fd@v10.2.0",
+        "--",
+        "--hidden",
+        "pattern",
+    ]))
+    .unwrap();
+    assert_eq!(run.binary_args(), ["--hidden", "pattern"]);
+    assert_eq!(
+        run.cache_path(),
+        std::path::PathBuf::from("/tmp/ghinstall-cache")
+    );
+
+    let args = run.install_args();
+    assert_eq!(
+        args.parse_repo().unwrap(),
+        (
+            "sharkdp".to_string(),
+            "fd".to_string(),
+            Some("v10.2.0".to_string())
+        )
+    );
+    assert_eq!(args.bin.as_deref(), Some("fd"));
+    assert!(args.no_fallback);
+    assert!(!args.skip_checksum);
+
+    // Arguments after the repository go to the binary even without `--`,
+    // including ones run takes itself
+    let run = RunArgs::parse_from_if_requested(argv(&[
+        "cargo",
+        "ghinstall",
+        "run",
+        "owner/tool",
+        "--version",
+        "--bin",
+        "x",
+    ]))
+    .unwrap();
+    assert_eq!(run.repo(), "owner/tool");
+    assert_eq!(run.binary_args(), ["--version", "--bin", "x"]);
+    assert!(run.install_args().bin.is_none());
+    assert!(RunArgs::try_parse_from(["run"]).is_err());
+    assert!(RunArgs::parse_from_if_requested(argv(&["cargo-ghinstall", "owner/run"])).is_none());
+}
+
+#[test]
+fn test_outdated_args() {
+    use cargo_ghinstall::cli::OutdatedArgs;
+    let argv = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+    let outdated = OutdatedArgs::parse_from_if_requested(argv(&[
+        "cargo",
+        "ghinstall",
+        "outdated",
+        "--receipts",
+        "/tmp/installed.toml",
+    ]))
+    .unwrap();
+    assert_eq!(
+        outdated.receipts.receipts_path(),
+        std::path::PathBuf::from("/tmp/installed.toml")
+    );
+
+    assert!(
+        OutdatedArgs::parse_from_if_requested(argv(&["cargo-ghinstall", "owner/outdated"]))
+            .is_none()
+    );
+}
+
+#[test]
+fn test_prefer_musl_flag() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from(["cargo-ghinstall", "owner/repo", "--prefer-musl"]).unwrap();
+    assert!(args.prefer_musl);
+    if cfg!(target_os = "linux") {
+        assert!(args.target().ends_with("-linux-musl"));
+    }
+
+    // An explicit target is taken as given, without a fallback
+    let args = Args::try_parse_from([
+        "cargo-ghinstall",
+        "owner/repo",
+        "--prefer-musl",
+        "--target",
+        "x86_64-unknown-linux-gnu",
+    ])
+    .unwrap();
+    assert_eq!(args.target(), "x86_64-unknown-linux-gnu");
+    assert_eq!(args.fallback_target(), None);
+}
+
+#[test]
+fn test_prefer_gnu_flag() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from(["cargo-ghinstall", "owner/repo", "--prefer-gnu"]).unwrap();
+    assert!(args.prefer_gnu);
+    if cfg!(windows) {
+        assert!(args.target().ends_with("-pc-windows-gnu"));
+    } else {
+        assert!(!args.target().contains("windows"));
+    }
+}
+
+#[test]
+fn test_no_arch_fallback_flag() {
+    use clap::Parser;
+
+    let args =
+        Args::try_parse_from(["cargo-ghinstall", "owner/repo", "--no-arch-fallback"]).unwrap();
+    assert!(args.no_arch_fallback);
+    if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        assert_eq!(args.fallback_target(), None);
+    }
+}
+
+#[test]
+fn test_as_flag() {
+    use clap::Parser;
+
+    let args = Args::try_parse_from([
+        "cargo-ghinstall",
+        "sharkdp/fd",
+        "--bin",
+        "fd",
+        "--as",
+        "fdfind",
+    ])
+    .unwrap();
+    assert_eq!(args.bin.as_deref(), Some("fd"));
+    assert_eq!(args.rename.as_deref(), Some("fdfind"));
+    let args =
+        Args::try_parse_from(["cargo-ghinstall", "owner/tool", "--rename", "tool-1.2"]).unwrap();
+    assert_eq!(args.rename.as_deref(), Some("tool-1.2"));
+
+    // One name can't cover several binaries, and must not be a path
+    assert!(
+        Args::try_parse_from(["cargo-ghinstall", "owner/tool", "--bins", "--as", "t"]).is_err()
+    );
+    assert!(
+        Args::try_parse_from(["cargo-ghinstall", "owner/tool", "--as", "../bin/tool"]).is_err()
+    );
+}
+
+#[test]
+fn test_force_and_rollback_args() {
+    use cargo_ghinstall::cli::RollbackArgs;
+    use clap::Parser;
+    let argv = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+    let args = Args::try_parse_from(["cargo-ghinstall", "owner/tool", "-f", "--backup"]).unwrap();
+    assert!(args.force);
+    assert!(args.backup);
+
+    let rollback = RollbackArgs::parse_from_if_requested(argv(&[
+        "cargo",
+        "ghinstall",
+        "rollback",
+        "rg",
+        "--install-dir",
+        "/opt/bin",
+    ]))
+    .unwrap();
+    assert_eq!(rollback.bin, "rg");
+    assert_eq!(rollback.install_dir(), std::path::PathBuf::from("/opt/bin"));
+    assert!(
+        RollbackArgs::parse_from_if_requested(argv(&["cargo", "ghinstall", "owner/rollback"]))
+            .is_none()
+    );
+}
@@ -2,7 +2,7 @@
 //!
 //! These tests verify the complete workflow of installing binaries from GitHub releases.
 
-use cargo_ghinstall::cli::Args;
+use cargo_ghinstall::cli::{Args, ReceiptsArg, ReleaseSelection};
 use std::env;
 use std::fs;
 use std::path::PathBuf;
@@ -23,40 +23,84 @@ fn setup_test_env(install_dir: &str) {
 fn test_skip_checksum_flag() {
     // Test that skip_checksum flag defaults to false
     let args = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: false,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     assert!(!args.skip_checksum, "skip_checksum should default to false");
 
     // Test with skip_checksum set to true
     let args_skip = Args {
-        repo: "owner/repo".to_string(),
+        repo: Some("owner/repo".to_string()),
         tag: None,
         bin: None,
         bins: false,
-        target: None,
+        bin_names: Vec::new(),
+        rename: None,
         install_dir: "~/.cargo/bin".to_string(),
+        force: false,
+        backup: false,
         show_notes: false,
+        extras: false,
         verify_signature: false,
+        signing_key: None,
+        verify_provenance: false,
+        require_provenance: false,
+        provenance_workflow: None,
+        require_signed: false,
         no_fallback: false,
+        no_arch_fallback: false,
         skip_checksum: true,
         config: std::path::PathBuf::from(".config/ghinstall.toml"),
         verbose: false,
         max_retries: 3,
         no_retry: false,
+        no_progress: false,
+        receipts: ReceiptsArg {
+            receipts: std::path::PathBuf::from("installed.toml"),
+        },
+        from_file: None,
+        locked: false,
+        expected_sha256: None,
+        interactive: false,
+        yes: false,
+        prefer_musl: false,
+        prefer_gnu: false,
+        selection: ReleaseSelection::default(),
     };
 
     assert!(